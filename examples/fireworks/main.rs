@@ -45,7 +45,7 @@ fn main() {
         ParticlesProgram::new(&context, &include_str!("../assets/shaders/particles.frag")).unwrap();
     let mut particles =
         Particles::new(&context, &CPUMesh::square(1.2), &vec3(0.0, -9.82, 0.0)).unwrap();
-    particles.cull = CullType::Back;
+    particles.cull = Some(CullType::Back);
 
     // main loop
     let mut time = explosion_time + 100.0;
@@ -120,7 +120,7 @@ fn main() {
                     }),
                     write_mask: WriteMask::COLOR,
                     depth_test: DepthTestType::Always,
-                    ..Default::default()
+                    ..RenderStates::new(frame_input.viewport)
                 };
                 let f = time / explosion_time.max(0.0);
                 let fade = 1.0 - f * f * f * f;
@@ -129,13 +129,7 @@ fn main() {
                     "color",
                     &vec4(color.x * fade, color.y * fade, color.z * fade, 1.0),
                 )?;
-                particles.render(
-                    &particles_program,
-                    render_states,
-                    frame_input.viewport,
-                    &camera,
-                    time,
-                )?;
+                particles.render(&particles_program, render_states, &camera, time)?;
                 Ok(())
             })
             .unwrap();
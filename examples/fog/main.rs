@@ -45,7 +45,7 @@ fn main() {
                 &PhongMaterial::new(&context, &materials[0]).unwrap(),
             )
             .unwrap();
-            monkey.cull = CullType::Back;
+            monkey.cull = Some(CullType::Back);
 
             let ambient_light = AmbientLight {
                 intensity: 0.2,
@@ -138,8 +138,7 @@ fn main() {
                             .unwrap()
                             .write(Some(1.0), &|| {
                                 monkey.render_depth(
-                                    RenderStates::default(),
-                                    frame_input.viewport,
+                                    RenderStates::new(frame_input.viewport),
                                     &camera,
                                 )?;
                                 Ok(())
@@ -151,9 +150,8 @@ fn main() {
                         monkey.render_with_lighting(
                             RenderStates {
                                 depth_test: DepthTestType::LessOrEqual,
-                                ..Default::default()
+                                ..RenderStates::new(frame_input.viewport)
                             },
-                            frame_input.viewport,
                             &camera,
                             Some(&ambient_light),
                             &[&directional_light],
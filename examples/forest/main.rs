@@ -51,11 +51,7 @@ fn main() {
                 .unwrap();
             let tree_material = PhongMaterial::new(&context, &tree_cpu_material).unwrap();
             let mut tree_mesh = PhongMesh::new(&context, tree_cpu_mesh, &tree_material).unwrap();
-            tree_mesh.cull = CullType::Back;
-            let tree_mesh_render_states = RenderStates {
-                depth_test: DepthTestType::LessOrEqual,
-                ..Default::default()
-            };
+            tree_mesh.cull = Some(CullType::Back);
 
             let leaves_cpu_mesh = meshes.iter().find(|m| m.name == "leaves.001").unwrap();
             let leaves_cpu_material = materials
@@ -68,10 +64,6 @@ fn main() {
                 &PhongMaterial::new(&context, &leaves_cpu_material).unwrap(),
             )
             .unwrap();
-            let leaves_mesh_render_states = RenderStates {
-                depth_test: DepthTestType::LessOrEqual,
-                ..Default::default()
-            };
 
             // Lights
             let ambient_light = AmbientLight {
@@ -90,8 +82,10 @@ fn main() {
                 .update_texture(
                     |viewport: Viewport, camera: &Camera| {
                         tree_mesh.render_with_lighting(
-                            tree_mesh_render_states,
-                            viewport,
+                            RenderStates {
+                                depth_test: DepthTestType::LessOrEqual,
+                                ..RenderStates::new(viewport)
+                            },
                             camera,
                             Some(&ambient_light),
                             &[&directional_light],
@@ -99,8 +93,10 @@ fn main() {
                             &[],
                         )?;
                         leaves_mesh.render_with_lighting(
-                            leaves_mesh_render_states,
-                            viewport,
+                            RenderStates {
+                                depth_test: DepthTestType::LessOrEqual,
+                                ..RenderStates::new(viewport)
+                            },
                             camera,
                             Some(&ambient_light),
                             &[&directional_light],
@@ -147,7 +143,7 @@ fn main() {
                 },
             )
             .unwrap();
-            plane.cull = CullType::Back;
+            plane.cull = Some(CullType::Back);
 
             // Shadows
             directional_light
@@ -204,9 +200,8 @@ fn main() {
                                 plane.render_with_lighting(
                                     RenderStates {
                                         depth_test: DepthTestType::LessOrEqual,
-                                        ..Default::default()
+                                        ..RenderStates::new(frame_input.viewport)
                                     },
-                                    frame_input.viewport,
                                     &camera,
                                     Some(&ambient_light),
                                     &[&directional_light],
@@ -214,8 +209,10 @@ fn main() {
                                     &[],
                                 )?;
                                 tree_mesh.render_with_lighting(
-                                    tree_mesh_render_states,
-                                    frame_input.viewport,
+                                    RenderStates {
+                                        depth_test: DepthTestType::LessOrEqual,
+                                        ..RenderStates::new(frame_input.viewport)
+                                    },
                                     &camera,
                                     Some(&ambient_light),
                                     &[&directional_light],
@@ -223,8 +220,10 @@ fn main() {
                                     &[],
                                 )?;
                                 leaves_mesh.render_with_lighting(
-                                    leaves_mesh_render_states,
-                                    frame_input.viewport,
+                                    RenderStates {
+                                        depth_test: DepthTestType::LessOrEqual,
+                                        ..RenderStates::new(frame_input.viewport)
+                                    },
                                     &camera,
                                     Some(&ambient_light),
                                     &[&directional_light],
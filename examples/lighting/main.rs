@@ -43,7 +43,7 @@ fn main() {
                 &PhongMaterial::new(&context, &monkey_cpu_materials[0]).unwrap(),
             )
             .unwrap();
-            monkey.cull = CullType::Back;
+            monkey.cull = Some(CullType::Back);
 
             let mut plane = PhongMesh::new(
                 &context,
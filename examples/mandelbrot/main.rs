@@ -38,7 +38,7 @@ fn main() {
         },
     )
     .unwrap();
-    mesh.cull = CullType::Back;
+    mesh.cull = Some(CullType::Back);
     mesh.transformation = Mat4::from_scale(10.0);
     let program =
         MeshProgram::new(&context, include_str!("../assets/shaders/mandelbrot.frag")).unwrap();
@@ -78,16 +78,7 @@ fn main() {
                         if pick.is_none() {
                             let p = camera
                                 .pick(
-                                    (
-                                        ((position.0 * frame_input.device_pixel_ratio
-                                            - frame_input.viewport.x as f64)
-                                            / frame_input.viewport.width as f64)
-                                            as f32,
-                                        ((position.1 * frame_input.device_pixel_ratio
-                                            - frame_input.viewport.y as f64)
-                                            / frame_input.viewport.height as f64)
-                                            as f32,
-                                    ),
+                                    frame_input.screen_coordinates(*position, frame_input.viewport),
                                     10.0,
                                     &[&mesh],
                                 )
@@ -113,9 +104,8 @@ fn main() {
                         RenderStates {
                             write_mask: WriteMask::COLOR,
                             depth_test: DepthTestType::Always,
-                            ..Default::default()
+                            ..RenderStates::new(frame_input.viewport)
                         },
-                        frame_input.viewport,
                         &camera,
                     )
                     .unwrap();
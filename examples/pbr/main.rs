@@ -37,7 +37,7 @@ fn main() {
             &PhongMaterial::new(&context, &cpu_materials[0]).unwrap(),
         )
         .unwrap();
-        model.cull = CullType::Back;
+        model.cull = Some(CullType::Back);
 
         let plane = PhongMesh::new(
             &context,
@@ -156,8 +156,7 @@ fn main() {
                     .unwrap();
                 Screen::write(&context, ClearState::default(), || {
                     plane.render_with_lighting(
-                        RenderStates::default(),
-                        frame_input.viewport,
+                        RenderStates::new(frame_input.viewport),
                         &camera,
                         Some(&ambient_light),
                         &[&directional_light0, &directional_light1],
@@ -166,8 +165,7 @@ fn main() {
                     )?;
 
                     model.render_with_lighting(
-                        RenderStates::default(),
-                        frame_input.viewport,
+                        RenderStates::new(frame_input.viewport),
                         &camera,
                         Some(&ambient_light),
                         &[&directional_light0, &directional_light1],
@@ -51,7 +51,7 @@ fn main() {
                 &PhongMaterial::new(&context, &materials[0]).unwrap(),
             )
             .unwrap();
-            monkey.cull = CullType::Back;
+            monkey.cull = Some(CullType::Back);
 
             let ambient_light = AmbientLight {
                 intensity: 0.2,
@@ -80,15 +80,9 @@ fn main() {
                                 if *button == MouseButton::Left && *state == State::Pressed {
                                     if let Some(pick) = camera
                                         .pick(
-                                            (
-                                                ((position.0 * frame_input.device_pixel_ratio
-                                                    - frame_input.viewport.x as f64)
-                                                    / frame_input.viewport.width as f64)
-                                                    as f32,
-                                                ((position.1 * frame_input.device_pixel_ratio
-                                                    - frame_input.viewport.y as f64)
-                                                    / frame_input.viewport.height as f64)
-                                                    as f32,
+                                            frame_input.screen_coordinates(
+                                                *position,
+                                                frame_input.viewport,
                                             ),
                                             100.0,
                                             &[&monkey],
@@ -128,9 +122,8 @@ fn main() {
                             monkey.render_with_lighting(
                                 RenderStates {
                                     depth_test: DepthTestType::LessOrEqual,
-                                    ..Default::default()
+                                    ..RenderStates::new(frame_input.viewport)
                                 },
-                                frame_input.viewport,
                                 &camera,
                                 Some(&ambient_light),
                                 &[&directional_light],
@@ -138,8 +131,7 @@ fn main() {
                                 &[],
                             )?;
                             pick_mesh.render_with_lighting(
-                                RenderStates::default(),
-                                frame_input.viewport,
+                                RenderStates::new(frame_input.viewport),
                                 &camera,
                                 Some(&ambient_light),
                                 &[&directional_light],
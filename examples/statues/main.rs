@@ -54,7 +54,7 @@ fn main() {
             let statue_material = PhongMaterial::new(&context, &statue_cpu_materials[0]).unwrap();
             let mut statue =
                 PhongMesh::new(&context, &statue_cpu_meshes[0], &statue_material).unwrap();
-            statue.cull = CullType::Back;
+            statue.cull = Some(CullType::Back);
 
             let mut statues = Vec::new();
             let scale = Mat4::from_scale(10.0);
@@ -77,7 +77,7 @@ fn main() {
                 PhongMaterial::new(&context, &fountain_cpu_materials[0]).unwrap();
             let mut fountain =
                 PhongMesh::new(&context, &fountain_cpu_meshes[0], &fountain_material).unwrap();
-            fountain.cull = CullType::Back;
+            fountain.cull = Some(CullType::Back);
             fountain.transformation = Mat4::from_angle_x(degrees(-90.0));
 
             let ambient_light = AmbientLight {
@@ -154,8 +154,7 @@ fn main() {
                                         .unwrap_or(true)
                                     {
                                         statue.render_with_lighting(
-                                            RenderStates::default(),
-                                            frame_input.viewport,
+                                            RenderStates::new(frame_input.viewport),
                                             if is_primary_camera {
                                                 &primary_camera
                                             } else {
@@ -170,8 +169,7 @@ fn main() {
                                 }
 
                                 fountain.render_with_lighting(
-                                    RenderStates::default(),
-                                    frame_input.viewport,
+                                    RenderStates::new(frame_input.viewport),
                                     if is_primary_camera {
                                         &primary_camera
                                     } else {
@@ -57,7 +57,7 @@ fn main() {
                 ..Default::default()
             };
             let mut box_mesh = PhongMesh::new(&context, &box_cpu_mesh, &box_material).unwrap();
-            box_mesh.cull = CullType::Back;
+            box_mesh.cull = Some(CullType::Back);
 
             let skybox = Skybox::new(
                 &context,
@@ -80,11 +80,11 @@ fn main() {
                 PhongMaterial::new(&context, &penguin_cpu_materials[0]).unwrap();
             let mut penguin_deferred =
                 PhongMesh::new(&context, &penguin_cpu_meshes[0], &penguin_cpu_material).unwrap();
-            penguin_deferred.cull = CullType::Back;
+            penguin_deferred.cull = Some(CullType::Back);
             penguin_deferred.transformation = Mat4::from_translation(vec3(-0.5, 1.0, 0.0));
             let mut penguin_forward =
                 PhongMesh::new(&context, &penguin_cpu_meshes[0], &penguin_cpu_material).unwrap();
-            penguin_forward.cull = CullType::Back;
+            penguin_forward.cull = Some(CullType::Back);
             penguin_forward.transformation = Mat4::from_translation(vec3(0.5, 1.0, 0.0));
 
             let ambient_light = AmbientLight {
@@ -152,8 +152,7 @@ fn main() {
                                 &[],
                             )?;
                             penguin_forward.render_with_lighting(
-                                RenderStates::default(),
-                                frame_input.viewport,
+                                RenderStates::new(frame_input.viewport),
                                 &camera,
                                 Some(&ambient_light),
                                 &[&directional_light],
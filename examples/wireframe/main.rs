@@ -48,7 +48,7 @@ fn main() {
             )
             .unwrap();
             model.transformation = Mat4::from_translation(vec3(0.0, 2.0, 0.0));
-            model.cull = CullType::Back;
+            model.cull = Some(CullType::Back);
 
             let wireframe_material = PhongMaterial {
                 name: "wireframe".to_string(),
@@ -56,6 +56,7 @@ fn main() {
                 specular_intensity: 0.2,
                 specular_power: 5.0,
                 color_source: ColorSource::Color(vec4(0.9, 0.2, 0.2, 1.0)),
+                ..Default::default()
             };
             let mut edges = PhongInstancedMesh::new(
                 &gl,
@@ -65,7 +66,7 @@ fn main() {
             )
             .unwrap();
             edges.transformation = Mat4::from_translation(vec3(0.0, 2.0, 0.0));
-            edges.cull = CullType::Back;
+            edges.cull = Some(CullType::Back);
 
             let mut vertices = PhongInstancedMesh::new(
                 &gl,
@@ -75,7 +76,7 @@ fn main() {
             )
             .unwrap();
             vertices.transformation = Mat4::from_translation(vec3(0.0, 2.0, 0.0));
-            vertices.cull = CullType::Back;
+            vertices.cull = Some(CullType::Back);
 
             let mut plane = PhongMesh::new(
                 &gl,
@@ -95,7 +96,7 @@ fn main() {
                 },
             )
             .unwrap();
-            plane.cull = CullType::Back;
+            plane.cull = Some(CullType::Back);
 
             let mut spot_light0 = SpotLight::new(
                 &gl,
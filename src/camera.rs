@@ -6,6 +6,22 @@ mod camera;
 #[doc(inline)]
 pub use camera::*;
 
+mod camera_uniform_buffer;
+#[doc(inline)]
+pub use camera_uniform_buffer::*;
+
 mod camera_control;
 #[doc(inline)]
 pub use camera_control::*;
+
+mod stereo_camera;
+#[doc(inline)]
+pub use stereo_camera::*;
+
+mod camera_modifier;
+#[doc(inline)]
+pub use camera_modifier::*;
+
+mod large_world_camera;
+#[doc(inline)]
+pub use large_world_camera::*;
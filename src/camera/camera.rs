@@ -1,5 +1,8 @@
+use crate::camera::*;
 use crate::core::*;
+#[cfg(feature = "renderer")]
 use crate::definition::*;
+#[cfg(feature = "renderer")]
 use crate::function::*;
 use crate::math::*;
 
@@ -28,9 +31,13 @@ pub struct Camera {
     up: Vec3,
     view: Mat4,
     projection: Mat4,
+    projection_window: Mat4,
+    clip_plane: Option<Vec4>,
+    reverse_z: bool,
     screen2ray: Mat4,
-    uniform_buffer: UniformBuffer,
+    uniform_buffer: CameraUniformBuffer,
     frustrum: [Vec4; 6],
+    layer_mask: u32,
 }
 
 impl Camera {
@@ -75,6 +82,8 @@ impl Camera {
 
     ///
     /// Specify the camera to use perspective projection with the given field of view in the y-direction, aspect and near and far plane.
+    /// The far plane can be infinitely far away, ie. `z_far` can be [f32::INFINITY], in which case the near plane must be in front of the camera,
+    /// which is most useful together with [reversed depth](Self::set_reverse_z).
     ///
     pub fn set_perspective_projection(
         &mut self,
@@ -93,11 +102,7 @@ impl Camera {
             z_near,
             z_far,
         };
-        self.projection = perspective(field_of_view_y, aspect, z_near, z_far);
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.update_projection()
     }
 
     ///
@@ -115,18 +120,31 @@ impl Camera {
             height,
             depth,
         };
-        self.projection = ortho(
-            -0.5 * width,
-            0.5 * width,
-            -0.5 * height,
-            0.5 * height,
-            0.0,
-            depth,
-        );
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.update_projection()
+    }
+
+    ///
+    /// Restricts the projection to the rectangle between the given minimum and maximum screen/image plane coordinates,
+    /// such that only that part of the view is rendered but stretched to fill the entire viewport.
+    /// The coordinates must be between 0 and 1, where (0, 0) indicate the top left corner of the screen
+    /// and (1, 1) indicate the bottom right corner. This is for example used when rendering an image in several tiles.
+    ///
+    pub fn set_projection_window(&mut self, min: Vec2, max: Vec2) -> Result<(), Error> {
+        let width = 2.0 * (max.x - min.x);
+        let height = 2.0 * (max.y - min.y);
+        let center_x = min.x + max.x - 1.0;
+        let center_y = 1.0 - min.y - max.y;
+        self.projection_window = Mat4::from_nonuniform_scale(2.0 / width, 2.0 / height, 1.0)
+            * Mat4::from_translation(vec3(-center_x, -center_y, 0.0));
+        self.update_projection()
+    }
+
+    ///
+    /// Removes the restriction set by [set_projection_window](Self::set_projection_window), ie. the entire view is rendered.
+    ///
+    pub fn clear_projection_window(&mut self) -> Result<(), Error> {
+        self.projection_window = Mat4::identity();
+        self.update_projection()
     }
 
     ///
@@ -173,10 +191,7 @@ impl Camera {
             Point::from_vec(self.target),
             self.up,
         );
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.update_view()
     }
 
     ///
@@ -186,10 +201,140 @@ impl Camera {
         self.view[1][0] = -self.view[1][0];
         self.view[1][1] = -self.view[1][1];
         self.view[1][2] = -self.view[1][2];
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.update_view()
+    }
+
+    ///
+    /// Change the camera view such that it is mirrored in the plane through the given point with the given normal,
+    /// for example to render the reflection in a mirror or a water surface.
+    /// Note that a mirrored view reverses the winding order of the triangles, so front faces are seen as back faces.
+    ///
+    pub fn mirror_in_plane(&mut self, point: Vec3, normal: Vec3) -> Result<(), Error> {
+        let normal = normal.normalize();
+        let reflect_point = |p: Vec3| p - normal * (2.0 * (p - point).dot(normal));
+        let reflect_direction = |d: Vec3| d - normal * (2.0 * d.dot(normal));
+        let reflection = Mat4::from_translation(point)
+            * Mat4::from(Mat3::from_cols(
+                reflect_direction(vec3(1.0, 0.0, 0.0)),
+                reflect_direction(vec3(0.0, 1.0, 0.0)),
+                reflect_direction(vec3(0.0, 0.0, 1.0)),
+            ))
+            * Mat4::from_translation(-point);
+        self.position = reflect_point(self.position);
+        self.target = reflect_point(self.target);
+        self.up = reflect_direction(self.up);
+        self.view = self.view * reflection;
+        self.update_view()
+    }
+
+    ///
+    /// Replaces the near plane of the projection with the given plane `(a, b, c, d)` in world space, such that everything on the negative side
+    /// of the plane, ie. where `a * x + b * y + c * z + d < 0`, is clipped (oblique near-plane clipping).
+    /// The camera must be on the negative side of the plane. This is for example used for clipping the objects behind a mirror
+    /// when rendering the reflection, see [mirror_in_plane](Self::mirror_in_plane), without adding clipping to the shaders.
+    /// Use `None` to use the near plane of the projection again.
+    ///
+    pub fn set_clip_plane(&mut self, plane: Option<Vec4>) -> Result<(), Error> {
+        self.clip_plane = plane;
+        self.update_projection()
+    }
+
+    ///
+    /// Returns the clip plane set by [set_clip_plane](Self::set_clip_plane), if any.
+    ///
+    pub fn clip_plane(&self) -> Option<Vec4> {
+        self.clip_plane
+    }
+
+    ///
+    /// Reverses the depth, ie. the near plane is mapped to the depth 1 and the far plane to the depth 0, which together with a float depth buffer
+    /// spreads the precision of the depth buffer almost evenly over the distance instead of using most of it close to the near plane.
+    /// This removes z-fighting between distant objects in large scenes, for example terrains or space scenes,
+    /// especially with an [infinitely far](Self::set_perspective_projection) far plane.
+    ///
+    /// To render with this camera, write to the render target or the [Screen](crate::Screen) with the [clear state](Self::clear_state) of this camera,
+    /// which clears the depth to the [far depth](Self::far_depth), ie. 0 instead of 1, and mirrors the [depth test](crate::DepthTestType)
+    /// of the render calls in that pass, so [DepthTestType::Less](crate::DepthTestType::Less) still keeps the closest fragment.
+    /// The matrices in the [uniform buffer](Self::uniform_buffer) are adjusted so the built-in shaders can reconstruct positions from the depth.
+    /// The precision is only improved when rendering into a [DepthTargetTexture2D](crate::DepthTargetTexture2D) with the [DepthFormat::Depth32F](crate::DepthFormat::Depth32F) format,
+    /// as done by the geometry pass of the deferred pipeline and [render_to_texture](crate::PhongForwardPipeline::render_to_texture) of the forward pipeline,
+    /// since the depth buffer of the [Screen](crate::Screen) is usually stored as fixed point.
+    /// Also, the depth range in normalized device coordinates is changed from [-1, 1] to [0, 1] for the full precision,
+    /// which is only possible when supported by the graphics driver and never on the web, where reversed depth only helps a little.
+    ///
+    pub fn set_reverse_z(&mut self, reverse_z: bool) -> Result<(), Error> {
+        self.reverse_z = reverse_z;
+        self.update_projection()
+    }
+
+    ///
+    /// Returns whether the depth is reversed, see [set_reverse_z](Self::set_reverse_z).
+    ///
+    pub fn reverse_z(&self) -> bool {
+        self.reverse_z
+    }
+
+    ///
+    /// Returns the depth of the far plane, ie. the value the depth should be cleared to before rendering with this camera,
+    /// which is 1 unless the depth is [reversed](Self::set_reverse_z).
+    ///
+    pub fn far_depth(&self) -> f32 {
+        if self.reverse_z {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    ///
+    /// Returns the clear state which clears the color to the given values and the depth to the [far depth](Self::far_depth) of this camera,
+    /// and which sets up the depth convention of this camera, ie. whether the [depth is reversed](Self::set_reverse_z),
+    /// for the render calls until the next render target is written to.
+    /// Use this when writing to a render target or the [Screen] to render with this camera.
+    ///
+    pub fn clear_state(&self, red: f32, green: f32, blue: f32, alpha: f32) -> ClearState {
+        ClearState {
+            reverse_depth: self.reverse_z,
+            ..ClearState::color_and_depth(red, green, blue, alpha, self.far_depth())
+        }
+    }
+
+    ///
+    /// Copies the view, projection, projection window, clip plane, depth direction and layer mask of the given camera to this camera,
+    /// for example before modifying this camera to render the scene from a related viewpoint.
+    ///
+    pub fn copy_from(&mut self, camera: &Camera) -> Result<(), Error> {
+        self.projection_type = match camera.projection_type {
+            ProjectionType::Orthographic {
+                width,
+                height,
+                depth,
+            } => ProjectionType::Orthographic {
+                width,
+                height,
+                depth,
+            },
+            ProjectionType::Perspective {
+                field_of_view_y,
+                aspect,
+                z_near,
+                z_far,
+            } => ProjectionType::Perspective {
+                field_of_view_y,
+                aspect,
+                z_near,
+                z_far,
+            },
+        };
+        self.position = camera.position;
+        self.target = camera.target;
+        self.up = camera.up;
+        self.view = camera.view;
+        self.projection_window = camera.projection_window;
+        self.clip_plane = camera.clip_plane;
+        self.reverse_z = camera.reverse_z;
+        self.layer_mask = camera.layer_mask;
+        self.update_projection()
     }
 
     ///
@@ -237,6 +382,7 @@ impl Camera {
     /// Finds the closest intersection between a ray from this camera in the direction of the given screen coordinates and the given geometries.
     /// Returns ```None``` if no geometry was hit before the given maximum depth.
     ///
+    #[cfg(feature = "renderer")]
     pub fn pick(
         &self,
         screen_coordinates: (f32, f32),
@@ -276,10 +422,11 @@ impl Camera {
         match self.projection_type() {
             ProjectionType::Orthographic { .. } => self.view_direction(),
             ProjectionType::Perspective { .. } => {
+                // Halfway between the near and far plane in normalized device coordinates, which is always a point in front of the camera
                 let screen_pos = vec4(
                     2. * screen_coordinates.0 as f32 - 1.,
                     1. - 2. * screen_coordinates.1 as f32,
-                    0.,
+                    0.5 * (self.near_ndc() + self.far_ndc()),
                     1.,
                 );
                 (self.screen2ray * screen_pos).truncate().normalize()
@@ -305,6 +452,22 @@ impl Camera {
         &self.projection
     }
 
+    ///
+    /// Sets the render layers this camera renders as a bit mask, for example to render a first-person weapon with a separate camera
+    /// or to only show some objects on a minimap. Only geometries with a [layer mask](crate::Geometry::layer_mask) which has at least one layer
+    /// in common with this mask are rendered by the pipelines. All layers are rendered by default.
+    ///
+    pub fn set_layer_mask(&mut self, layer_mask: u32) {
+        self.layer_mask = layer_mask;
+    }
+
+    ///
+    /// Returns the render layers this camera renders, see [set_layer_mask](Self::set_layer_mask).
+    ///
+    pub fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
+
     ///
     /// Returns the position of this camera.
     ///
@@ -342,8 +505,12 @@ impl Camera {
 
     ///
     /// Returns an uniform buffer containing camera information which makes it easy to transfer all necessary camera information to a shader.
+    /// The buffer is only transferred to the GPU when it is used after the camera has changed, see [CameraUniformBuffer].
+    /// The `viewport` member is the viewport given by [set_viewport](Self::set_viewport),
+    /// use [uniform_buffer_for_viewport](Self::uniform_buffer_for_viewport) to get it with the viewport of the draw call instead.
     ///
-    /// Use this buffer in your [Program](crate::Program) like this `program.use_uniform_block(camera.uniform_buffer(), "Camera");` and add the following to your shader code:
+    /// Use this buffer in your [Program](crate::Program) like this `program.use_uniform_block(camera.uniform_buffer(), "Camera")?;`
+    /// and add `#include "camera"` to your shader code if it is processed by a [ShaderPreprocessor](crate::ShaderPreprocessor), otherwise add the following:
     ///
     /// ```ignore
     /// layout (std140) uniform Camera
@@ -353,11 +520,33 @@ impl Camera {
     ///     mat4 projection;
     ///     vec3 position;
     ///     float padding;
+    ///     mat4 viewProjectionInverse;
+    ///     mat4 viewInverse;
+    ///     mat4 projectionInverse;
+    ///     vec4 viewport;
     /// } camera;
     /// ```
     ///
     pub fn uniform_buffer(&self) -> &UniformBuffer {
-        &self.uniform_buffer
+        self.uniform_buffer.buffer()
+    }
+
+    ///
+    /// Returns the same [uniform buffer](Self::uniform_buffer), but with the `viewport` member set to the given viewport,
+    /// which should be the viewport that is drawn to. This is used by all of the built-in objects and effects,
+    /// so the viewport in the shader always matches the render target. The buffer is only transferred to the GPU again if the viewport has changed.
+    ///
+    pub fn uniform_buffer_for_viewport(&self, viewport: Viewport) -> Result<&UniformBuffer, Error> {
+        self.uniform_buffer.buffer_for_viewport(viewport)
+    }
+
+    ///
+    /// Sets the viewport which is available as `camera.viewport` (x, y, width, height) in the [uniform buffer](Self::uniform_buffer).
+    /// The built-in objects and effects instead use the viewport they are drawn in, see [uniform_buffer_for_viewport](Self::uniform_buffer_for_viewport).
+    /// It does not affect the projection, use [set_aspect](Self::set_aspect) for that.
+    ///
+    pub fn set_viewport(&mut self, viewport: Viewport) -> Result<(), Error> {
+        self.uniform_buffer.set_viewport(viewport)
     }
 
     fn new(context: &Context) -> Camera {
@@ -368,17 +557,126 @@ impl Camera {
                 height: 1.0,
                 depth: 1.0,
             },
-            uniform_buffer: UniformBuffer::new(context, &[16, 16, 16, 3, 1]).unwrap(),
+            uniform_buffer: CameraUniformBuffer::new(context).unwrap(),
+            layer_mask: crate::ALL_LAYERS,
             frustrum: [vec4(0.0, 0.0, 0.0, 0.0); 6],
             position: vec3(0.0, 0.0, 5.0),
             target: vec3(0.0, 0.0, 0.0),
             up: vec3(0.0, 1.0, 0.0),
             view: Mat4::identity(),
             projection: Mat4::identity(),
+            projection_window: Mat4::identity(),
+            clip_plane: None,
+            reverse_z: false,
             screen2ray: Mat4::identity(),
         }
     }
 
+    fn update_projection(&mut self) -> Result<(), Error> {
+        let projection = match self.projection_type {
+            ProjectionType::Orthographic {
+                width,
+                height,
+                depth,
+            } => ortho(
+                -0.5 * width,
+                0.5 * width,
+                -0.5 * height,
+                0.5 * height,
+                0.0,
+                depth,
+            ),
+            ProjectionType::Perspective {
+                field_of_view_y,
+                aspect,
+                z_near,
+                z_far,
+            } => {
+                if z_far.is_infinite() {
+                    infinite_perspective(field_of_view_y, aspect, z_near)
+                } else {
+                    perspective(field_of_view_y, aspect, z_near, z_far)
+                }
+            }
+        };
+        self.projection = self.projection_window * projection;
+        if let Some(plane) = self.clip_plane {
+            if let Some(view_inverse) = self.view.invert() {
+                self.projection =
+                    oblique_projection(self.projection, view_inverse.transpose() * plane);
+            }
+        }
+        if self.reverse_z {
+            let reversal = if self.zero_to_one_depth() {
+                // z' = (w - z) / 2 maps the range [-1, 1] to [1, 0]
+                Mat4::new(
+                    1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -0.5, 0.0, 0.0, 0.0, 0.5, 1.0,
+                )
+            } else {
+                Mat4::from_nonuniform_scale(1.0, 1.0, -1.0)
+            };
+            self.projection = reversal * self.projection;
+        }
+        self.update_screen2ray();
+        self.update_uniform_buffer()?;
+        self.update_frustrum();
+        Ok(())
+    }
+
+    fn update_view(&mut self) -> Result<(), Error> {
+        if self.clip_plane.is_some() {
+            // The clip plane is given in world space, so the projection depends on the view
+            self.update_projection()
+        } else {
+            self.update_screen2ray();
+            self.update_uniform_buffer()?;
+            self.update_frustrum();
+            Ok(())
+        }
+    }
+
+    ///
+    /// Returns whether the depth range in normalized device coordinates is [0, 1] instead of [-1, 1], see [set_reverse_z](Self::set_reverse_z).
+    ///
+    fn zero_to_one_depth(&self) -> bool {
+        self.reverse_z && self.context.supports_clip_control()
+    }
+
+    ///
+    /// Returns the distance to the near and far plane, where the far plane can be infinitely far away.
+    ///
+    #[cfg(feature = "renderer")]
+    pub(crate) fn z_near_far(&self) -> (f32, f32) {
+        match self.projection_type {
+            ProjectionType::Orthographic { depth, .. } => (0.0, depth),
+            ProjectionType::Perspective { z_near, z_far, .. } => (z_near, z_far),
+        }
+    }
+
+    ///
+    /// Returns the depth of the near plane in normalized device coordinates.
+    ///
+    pub(crate) fn near_ndc(&self) -> f32 {
+        if self.reverse_z {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+
+    ///
+    /// Returns the depth of the far plane in normalized device coordinates.
+    ///
+    pub(crate) fn far_ndc(&self) -> f32 {
+        if self.zero_to_one_depth() {
+            0.0
+        } else if self.reverse_z {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
     fn update_screen2ray(&mut self) {
         let mut v = self.view;
         v[3] = vec4(0.0, 0.0, 0.0, 1.0);
@@ -386,23 +684,71 @@ impl Camera {
     }
 
     fn update_uniform_buffer(&mut self) -> Result<(), Error> {
-        self.uniform_buffer
-            .update(0, &(self.projection * self.view).to_slice())?;
-        self.uniform_buffer.update(1, &self.view.to_slice())?;
-        self.uniform_buffer.update(2, &self.projection.to_slice())?;
-        self.uniform_buffer.update(3, &self.position.to_slice())?;
-        Ok(())
+        self.uniform_buffer.update(
+            &self.view,
+            &self.projection,
+            &self.position,
+            self.zero_to_one_depth(),
+        )
     }
 
     fn update_frustrum(&mut self) {
         let m = self.projection * self.view;
+        // The far plane is z = 0 instead of z = -w when the depth range is [0, 1]
+        let near_or_far = if self.zero_to_one_depth() {
+            vec4(m.x.z, m.y.z, m.z.z, m.w.z)
+        } else {
+            vec4(m.x.w + m.x.z, m.y.w + m.y.z, m.z.w + m.z.z, m.w.w + m.w.z)
+        };
         self.frustrum = [
             vec4(m.x.w + m.x.x, m.y.w + m.y.x, m.z.w + m.z.x, m.w.w + m.w.x),
             vec4(m.x.w - m.x.x, m.y.w - m.y.x, m.z.w - m.z.x, m.w.w - m.w.x),
             vec4(m.x.w + m.x.y, m.y.w + m.y.y, m.z.w + m.z.y, m.w.w + m.w.y),
             vec4(m.x.w - m.x.y, m.y.w - m.y.y, m.z.w - m.z.y, m.w.w - m.w.y),
-            vec4(m.x.w + m.x.z, m.y.w + m.y.z, m.z.w + m.z.z, m.w.w + m.w.z),
+            near_or_far,
             vec4(m.x.w - m.x.z, m.y.w - m.y.z, m.z.w - m.z.z, m.w.w - m.w.z),
         ];
     }
 }
+
+///
+/// Returns a perspective projection where the far plane is infinitely far away, ie. the limit of [perspective] when the far plane goes to infinity.
+///
+fn infinite_perspective(field_of_view_y: Radians, aspect: f32, z_near: f32) -> Mat4 {
+    let f = 1.0 / (0.5 * field_of_view_y.0).tan();
+    Mat4::new(
+        f / aspect,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        f,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -1.0,
+        -1.0,
+        0.0,
+        0.0,
+        -2.0 * z_near,
+        0.0,
+    )
+}
+
+///
+/// Returns the given projection where the near plane is replaced by the given plane in view space,
+/// see "Oblique View Frustum Depth Projection and Clipping" by Eric Lengyel.
+///
+fn oblique_projection(projection: Mat4, plane: Vec4) -> Mat4 {
+    let corner = match projection.invert() {
+        Some(inverse) => inverse * vec4(plane.x.signum(), plane.y.signum(), 1.0, 1.0),
+        None => return projection,
+    };
+    let scaled_plane = plane * (2.0 / plane.dot(corner));
+    let mut projection = projection;
+    for column in 0..4 {
+        projection[column][2] = scaled_plane[column] - projection[column][3];
+    }
+    projection
+}
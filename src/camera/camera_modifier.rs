@@ -0,0 +1,243 @@
+use crate::camera::*;
+use crate::core::Error;
+use crate::math::*;
+use crate::procedural::*;
+
+///
+/// The position, target and up direction of a camera, which is modified by [camera modifiers](CameraModifier) before it is applied to a [Camera].
+/// Keep a base pose, for example controlled by the user, and apply the modifiers on top of it each frame:
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let mut camera: Camera = unimplemented!();
+/// # let frame_input: FrameInput = unimplemented!();
+/// let base = CameraPose::new(vec3(0.0, 2.0, 5.0), vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0));
+/// let mut shake = CameraShake::new(0.2, degrees(3.0));
+/// shake.add_trauma(0.5); // For example when something explodes
+/// base.with(&mut shake, frame_input.elapsed_time)
+///     .apply_to(&mut camera)
+///     .unwrap();
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraPose {
+    pub position: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+}
+
+impl CameraPose {
+    pub fn new(position: Vec3, target: Vec3, up: Vec3) -> Self {
+        Self {
+            position,
+            target,
+            up,
+        }
+    }
+
+    ///
+    /// Returns the current pose of the given camera.
+    ///
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self::new(*camera.position(), *camera.target(), *camera.up())
+    }
+
+    ///
+    /// Returns this pose modified by the given modifier, where the elapsed time is the time in milliseconds since the last frame,
+    /// see [FrameInput::elapsed_time](crate::FrameInput::elapsed_time). Calls can be chained to apply several modifiers.
+    ///
+    pub fn with(self, modifier: &mut dyn CameraModifier, elapsed_time: f64) -> Self {
+        modifier.modify(self, elapsed_time)
+    }
+
+    ///
+    /// Sets the view of the given camera to this pose.
+    ///
+    pub fn apply_to(&self, camera: &mut Camera) -> Result<(), Error> {
+        camera.set_view(self.position, self.target, self.up)
+    }
+
+    fn view_direction(&self) -> Vec3 {
+        (self.target - self.position).normalize()
+    }
+}
+
+///
+/// A modifier of a [CameraPose] which is applied each frame, for example [shake](CameraShake), [smooth follow](SmoothFollow) or a [look-at constraint](LookAt).
+///
+pub trait CameraModifier {
+    ///
+    /// Returns the modified pose given the pose and the time in milliseconds since the last frame.
+    ///
+    fn modify(&mut self, pose: CameraPose, elapsed_time: f64) -> CameraPose;
+}
+
+///
+/// Shakes the camera with an amount given by the trauma, a value between 0 and 1 which decays over time.
+/// Add trauma when something happens, for example an explosion or a hit, and the camera shakes smoothly and settles down again.
+///
+pub struct CameraShake {
+    trauma: f32,
+    time: f32,
+    noise: Noise,
+    /// The amount of trauma removed each second.
+    pub decay: f32,
+    /// The maximum distance the camera is moved at full trauma.
+    pub max_offset: f32,
+    /// The maximum angle the camera is rotated at full trauma.
+    pub max_angle: Radians,
+    /// The number of shakes each second.
+    pub frequency: f32,
+}
+
+impl CameraShake {
+    ///
+    /// Creates a new camera shake without trauma, which moves the camera at most the given offset and rotates it at most the given angle at full trauma.
+    ///
+    pub fn new(max_offset: f32, max_angle: impl Into<Radians>) -> Self {
+        Self {
+            trauma: 0.0,
+            time: 0.0,
+            noise: Noise::new(NoiseType::Perlin, 1),
+            decay: 1.0,
+            max_offset,
+            max_angle: max_angle.into(),
+            frequency: 15.0,
+        }
+    }
+
+    ///
+    /// Adds the given amount of trauma. The trauma is clamped to the range [0..1].
+    ///
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).max(0.0).min(1.0);
+    }
+
+    ///
+    /// Returns the current trauma.
+    ///
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    fn sample(&self, channel: f32) -> f32 {
+        // Sample away from the integer lattice where gradient noise is always zero
+        2.0 * self
+            .noise
+            .sample_2d(self.time * self.frequency, channel * 7.31 + 0.5)
+            - 1.0
+    }
+}
+
+impl CameraModifier for CameraShake {
+    fn modify(&mut self, pose: CameraPose, elapsed_time: f64) -> CameraPose {
+        let seconds = elapsed_time as f32 * 0.001;
+        self.time += seconds;
+        // The shake is proportional to the trauma squared, which gives a smoother falloff
+        let shake = self.trauma * self.trauma;
+        self.trauma = (self.trauma - self.decay * seconds).max(0.0);
+        if shake <= 0.0 {
+            return pose;
+        }
+        let direction = pose.view_direction();
+        let right = direction.cross(pose.up).normalize();
+        let up = right.cross(direction);
+        let offset = (right * self.sample(0.0) + up * self.sample(1.0)) * self.max_offset * shake;
+        let angle = self.max_angle * shake;
+        let rotation = Mat3::from_axis_angle(up, angle * self.sample(2.0))
+            * Mat3::from_axis_angle(right, angle * self.sample(3.0));
+        let distance = (pose.target - pose.position).magnitude();
+        let position = pose.position + offset;
+        CameraPose {
+            position,
+            target: position + rotation * direction * distance,
+            up: Mat3::from_axis_angle(direction, angle * self.sample(4.0)) * pose.up,
+        }
+    }
+}
+
+///
+/// Smoothly follows a target, for example a character or a vehicle, with a lag.
+/// The camera is placed at an offset given in the local space of the target and looks at the position of the target.
+///
+pub struct SmoothFollow {
+    transformation: Mat4,
+    current: Option<(Vec3, Vec3)>,
+    /// The position of the camera in the local space of the target.
+    pub offset: Vec3,
+    /// The time in seconds it takes for the camera to move most of the way (about 63%) to a new position of the target, zero means no lag.
+    pub lag: f32,
+}
+
+impl SmoothFollow {
+    pub fn new(offset: Vec3, lag: f32) -> Self {
+        Self {
+            transformation: Mat4::identity(),
+            current: None,
+            offset,
+            lag,
+        }
+    }
+
+    ///
+    /// Sets the transformation of the target which is followed, usually each frame.
+    ///
+    pub fn set_target(&mut self, transformation: &Mat4) {
+        self.transformation = *transformation;
+    }
+
+    ///
+    /// Moves the camera directly to the target, for example after the target has been teleported.
+    ///
+    pub fn snap(&mut self) {
+        self.current = None;
+    }
+}
+
+impl CameraModifier for SmoothFollow {
+    fn modify(&mut self, pose: CameraPose, elapsed_time: f64) -> CameraPose {
+        let desired_position = (self.transformation * self.offset.extend(1.0)).truncate();
+        let desired_target = self.transformation.w.truncate();
+        let (position, target) = if let Some((position, target)) = self.current {
+            let factor = if self.lag > 0.0 {
+                1.0 - (-(elapsed_time as f32 * 0.001) / self.lag).exp()
+            } else {
+                1.0
+            };
+            (
+                position.lerp(desired_position, factor),
+                target.lerp(desired_target, factor),
+            )
+        } else {
+            (desired_position, desired_target)
+        };
+        self.current = Some((position, target));
+        CameraPose {
+            position,
+            target,
+            up: pose.up,
+        }
+    }
+}
+
+///
+/// Constrains the camera to look at the given point while keeping its position.
+///
+pub struct LookAt {
+    pub point: Vec3,
+}
+
+impl LookAt {
+    pub fn new(point: Vec3) -> Self {
+        Self { point }
+    }
+}
+
+impl CameraModifier for LookAt {
+    fn modify(&mut self, pose: CameraPose, _elapsed_time: f64) -> CameraPose {
+        CameraPose {
+            target: self.point,
+            ..pose
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::core::*;
+use crate::math::*;
+use std::cell::Cell;
+
+///
+/// A uniform buffer containing all of the camera information needed in a shader, that is the view, projection and view-projection matrices,
+/// their inverses, the camera position and the viewport.
+/// It is owned by a [Camera] and updated whenever the camera changes, but only transferred to the GPU the first time it is used afterwards,
+/// ie. at most once per frame, after which it is shared by all draw calls as the uniform block `Camera`.
+/// The viewport is only transferred together with the rest of the data, so it can be set to the viewport of each draw call,
+/// see [buffer_for_viewport](Self::buffer_for_viewport), without an extra transfer as long as the viewport does not change.
+/// All of the built-in shaders use this block instead of separate uniforms, see [Camera::uniform_buffer] for how to use it in custom shaders.
+///
+pub struct CameraUniformBuffer {
+    buffer: UniformBuffer,
+    viewport: Viewport,
+    needs_send: Cell<bool>,
+    sent_viewport: Cell<Option<Viewport>>,
+}
+
+impl CameraUniformBuffer {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            buffer: UniformBuffer::new(context, &[16, 16, 16, 3, 1, 16, 16, 16, 4])?,
+            viewport: Viewport::new_at_origo(0, 0),
+            needs_send: Cell::new(true),
+            sent_viewport: Cell::new(None),
+        })
+    }
+
+    ///
+    /// Updates the camera matrices and position. The data is transferred to the GPU the next time [buffer](Self::buffer) is called.
+    /// If the depth range in normalized device coordinates is [0, 1] instead of [-1, 1], see [Camera::set_reverse_z],
+    /// the inverse matrices expect the depth in the range [-1, 1] anyway, so that shaders can always compute the position from the depth `d`
+    /// using the normalized device coordinates `(x, y, 2d - 1)`.
+    ///
+    pub fn update(
+        &mut self,
+        view: &Mat4,
+        projection: &Mat4,
+        position: &Vec3,
+        zero_to_one_depth: bool,
+    ) -> Result<(), Error> {
+        let view_projection = projection * view;
+        // Maps the depth z in the range [-1, 1] to (z + 1) / 2 in the range [0, 1]
+        let depth_range = if zero_to_one_depth {
+            Mat4::new(
+                1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.5, 1.0,
+            )
+        } else {
+            Mat4::identity()
+        };
+        self.buffer.set(0, &view_projection.to_slice())?;
+        self.buffer.set(1, &view.to_slice())?;
+        self.buffer.set(2, &projection.to_slice())?;
+        self.buffer.set(3, &position.to_slice())?;
+        self.buffer.set(
+            5,
+            &(view_projection.invert().unwrap_or(Mat4::identity()) * depth_range).to_slice(),
+        )?;
+        self.buffer
+            .set(6, &view.invert().unwrap_or(Mat4::identity()).to_slice())?;
+        self.buffer.set(
+            7,
+            &(projection.invert().unwrap_or(Mat4::identity()) * depth_range).to_slice(),
+        )?;
+        self.needs_send.set(true);
+        Ok(())
+    }
+
+    ///
+    /// Updates the viewport which is used by [buffer](Self::buffer). The data is transferred to the GPU the next time [buffer](Self::buffer) is called.
+    ///
+    pub fn set_viewport(&mut self, viewport: Viewport) -> Result<(), Error> {
+        self.buffer.set(8, &Self::viewport_data(viewport))?;
+        self.viewport = viewport;
+        self.needs_send.set(true);
+        Ok(())
+    }
+
+    ///
+    /// Returns the uniform buffer with the viewport given by [set_viewport](Self::set_viewport)
+    /// after transferring the data to the GPU if it has changed since the last call.
+    ///
+    pub fn buffer(&self) -> &UniformBuffer {
+        if self.needs_send.get() || self.sent_viewport.get() != Some(self.viewport) {
+            self.buffer.send();
+            self.needs_send.set(false);
+            self.sent_viewport.set(Some(self.viewport));
+        }
+        &self.buffer
+    }
+
+    ///
+    /// Returns the uniform buffer with the given viewport after transferring the data to the GPU
+    /// if it or the viewport has changed since the last call.
+    ///
+    pub fn buffer_for_viewport(&self, viewport: Viewport) -> Result<&UniformBuffer, Error> {
+        if self.needs_send.get() || self.sent_viewport.get() != Some(viewport) {
+            self.buffer.send_with(8, &Self::viewport_data(viewport))?;
+            self.needs_send.set(false);
+            self.sent_viewport.set(Some(viewport));
+        }
+        Ok(&self.buffer)
+    }
+
+    fn viewport_data(viewport: Viewport) -> [f32; 4] {
+        [
+            viewport.x as f32,
+            viewport.y as f32,
+            viewport.width as f32,
+            viewport.height as f32,
+        ]
+    }
+}
@@ -0,0 +1,169 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+
+///
+/// A camera for worlds which are too large for single precision coordinates, for example geospatial or space visualizations,
+/// where objects far away from the origin jitter because their positions can not be represented accurately by [f32].
+///
+/// The positions in the world are stored in double precision and rendered relative to an origin close to the camera (origin rebasing).
+/// The [camera](Self::camera) used for rendering is placed relative to this origin, and each frame the positions of the objects
+/// are converted to single precision positions relative to the origin using [relative_position](Self::relative_position)
+/// or [relative_transformation](Self::relative_transformation), which are accurate close to the camera where it matters.
+/// Call [rebase](Self::rebase) after moving the camera, for example with [Camera::set_view], to move the origin to the camera again.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let (mut mesh, frame_input): (Mesh, FrameInput) = unimplemented!();
+/// let mut camera = LargeWorldCamera::new_perspective(&context, dvec3(6.4e6, 0.0, 100.0), dvec3(6.4e6, 0.0, 0.0),
+///     vec3(0.0, 1.0, 0.0), degrees(45.0), 1.0, 0.1, 1000.0).unwrap();
+/// let mesh_position = dvec3(6.4e6, 0.0, 0.0);
+/// // Each frame:
+/// camera.rebase().unwrap();
+/// mesh.transformation = Mat4::from_translation(camera.relative_position(mesh_position));
+/// mesh.render_with_color(&vec4(1.0, 0.0, 0.0, 1.0), RenderStates::new(frame_input.viewport), camera.camera()).unwrap();
+/// ```
+///
+pub struct LargeWorldCamera {
+    camera: Camera,
+    origin: DVec3,
+}
+
+impl LargeWorldCamera {
+    ///
+    /// New camera which projects the world with a perspective projection, placed at the given position in the world looking at the given target.
+    /// See [Camera::new_perspective] for the other parameters.
+    ///
+    pub fn new_perspective(
+        context: &Context,
+        position: DVec3,
+        target: DVec3,
+        up: Vec3,
+        field_of_view_y: impl Into<Radians>,
+        aspect: f32,
+        z_near: f32,
+        z_far: f32,
+    ) -> Result<Self, Error> {
+        let mut camera = Self {
+            camera: Camera::new_perspective(
+                context,
+                vec3(0.0, 0.0, 5.0),
+                vec3(0.0, 0.0, 0.0),
+                up,
+                field_of_view_y,
+                aspect,
+                z_near,
+                z_far,
+            )?,
+            origin: position,
+        };
+        camera.set_view(position, target, up)?;
+        Ok(camera)
+    }
+
+    ///
+    /// Change the view of the camera to the given position in the world, looking at the given target and with the given up direction.
+    /// The origin is moved to the camera position.
+    ///
+    pub fn set_view(&mut self, position: DVec3, target: DVec3, up: Vec3) -> Result<(), Error> {
+        self.origin = position;
+        self.camera.set_view(
+            vec3(0.0, 0.0, 0.0),
+            to_single_precision(target - position),
+            up,
+        )
+    }
+
+    ///
+    /// Moves the origin to the current position of the [camera](Self::camera) without changing the view,
+    /// such that the objects close to the camera are rendered accurately.
+    /// Call this each frame after the camera has been moved, and before the relative positions of the objects are computed.
+    /// Note that all relative positions change, so relative positions computed before must be computed again.
+    ///
+    pub fn rebase(&mut self) -> Result<(), Error> {
+        let position = *self.camera.position();
+        if position == vec3(0.0, 0.0, 0.0) {
+            return Ok(());
+        }
+        let target = *self.camera.target();
+        let up = *self.camera.up();
+        self.origin += to_double_precision(position);
+        self.camera
+            .set_view(vec3(0.0, 0.0, 0.0), target - position, up)
+    }
+
+    ///
+    /// Returns the origin, ie. the position in the world which is the origin of the [camera](Self::camera) and of the relative positions.
+    ///
+    pub fn origin(&self) -> DVec3 {
+        self.origin
+    }
+
+    ///
+    /// Returns the position of the camera in the world.
+    ///
+    pub fn position(&self) -> DVec3 {
+        self.world_position(*self.camera.position())
+    }
+
+    ///
+    /// Returns the target of the camera in the world, ie. the point that the camera looks towards.
+    ///
+    pub fn target(&self) -> DVec3 {
+        self.world_position(*self.camera.target())
+    }
+
+    ///
+    /// Returns the camera used for rendering, which is placed relative to the [origin](Self::origin).
+    ///
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    ///
+    /// Returns the camera used for rendering, for example to change the projection or to move the camera.
+    /// Positions given to this camera are relative to the [origin](Self::origin), see [relative_position](Self::relative_position).
+    ///
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    ///
+    /// Returns the given position in the world relative to the [origin](Self::origin) in single precision,
+    /// for example the position of an object or a light.
+    ///
+    pub fn relative_position(&self, position: DVec3) -> Vec3 {
+        to_single_precision(position - self.origin)
+    }
+
+    ///
+    /// Returns the given transformation in the world relative to the [origin](Self::origin) in single precision,
+    /// for example to use as the transformation of a [Mesh](crate::Mesh).
+    ///
+    pub fn relative_transformation(&self, transformation: &DMat4) -> Mat4 {
+        let relative = DMat4::from_translation(-self.origin) * transformation;
+        Mat4::from_cols(
+            to_single_precision(relative.x.truncate()).extend(relative.x.w as f32),
+            to_single_precision(relative.y.truncate()).extend(relative.y.w as f32),
+            to_single_precision(relative.z.truncate()).extend(relative.z.w as f32),
+            to_single_precision(relative.w.truncate()).extend(relative.w.w as f32),
+        )
+    }
+
+    ///
+    /// Returns the position in the world of the given position relative to the [origin](Self::origin),
+    /// for example of a point found by [Camera::pick].
+    ///
+    pub fn world_position(&self, relative_position: Vec3) -> DVec3 {
+        self.origin + to_double_precision(relative_position)
+    }
+}
+
+fn to_single_precision(v: DVec3) -> Vec3 {
+    vec3(v.x as f32, v.y as f32, v.z as f32)
+}
+
+fn to_double_precision(v: Vec3) -> DVec3 {
+    dvec3(v.x as f64, v.y as f64, v.z as f64)
+}
@@ -0,0 +1,12 @@
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+    mat4 viewProjectionInverse;
+    mat4 viewInverse;
+    mat4 projectionInverse;
+    vec4 viewport;
+} camera;
@@ -0,0 +1,152 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+
+///
+/// A pair of cameras, one for each eye, used for stereo rendering, for example side-by-side output for VR headsets.
+/// The eye cameras are derived from a center camera by offsetting them half the interpupillary distance to each side and
+/// shifting their frustums such that objects at the convergence distance appear at the same place in both images.
+///
+pub struct StereoCamera {
+    left: Camera,
+    right: Camera,
+    /// The distance between the two eyes.
+    pub interpupillary_distance: f32,
+    /// The distance from the eyes to the plane where there is zero parallax between the two images.
+    pub convergence_distance: f32,
+}
+
+impl StereoCamera {
+    pub fn new(
+        context: &Context,
+        interpupillary_distance: f32,
+        convergence_distance: f32,
+    ) -> Result<Self, Error> {
+        let new_eye = || {
+            Camera::new_perspective(
+                context,
+                vec3(0.0, 0.0, 5.0),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                degrees(45.0),
+                1.0,
+                0.1,
+                100.0,
+            )
+        };
+        Ok(Self {
+            left: new_eye()?,
+            right: new_eye()?,
+            interpupillary_distance,
+            convergence_distance,
+        })
+    }
+
+    ///
+    /// Updates the eye cameras to follow the given center camera.
+    /// The eye cameras get the given aspect, for example the aspect of a texture that each eye is rendered into.
+    ///
+    pub fn update(&mut self, camera: &Camera, aspect: f32) -> Result<(), Error> {
+        let half_distance = 0.5 * self.interpupillary_distance;
+        update_eye(
+            &mut self.left,
+            camera,
+            aspect,
+            -half_distance,
+            self.convergence_distance,
+        )?;
+        update_eye(
+            &mut self.right,
+            camera,
+            aspect,
+            half_distance,
+            self.convergence_distance,
+        )
+    }
+
+    ///
+    /// Returns the camera for the left eye.
+    ///
+    pub fn left(&self) -> &Camera {
+        &self.left
+    }
+
+    ///
+    /// Returns the camera for the right eye.
+    ///
+    pub fn right(&self) -> &Camera {
+        &self.right
+    }
+
+    ///
+    /// Returns the left and right halves of the given viewport.
+    ///
+    pub fn side_by_side_viewports(viewport: Viewport) -> (Viewport, Viewport) {
+        let half_width = viewport.width / 2;
+        (
+            Viewport {
+                width: half_width,
+                ..viewport
+            },
+            Viewport {
+                x: viewport.x + half_width as i32,
+                width: viewport.width - half_width,
+                ..viewport
+            },
+        )
+    }
+
+    ///
+    /// Renders the scene side-by-side, the left eye in the left half of the given viewport and the right eye in the right half.
+    /// The `render` closure is called once for each eye and must render the scene with the given viewport and camera.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_side_by_side<F: FnMut(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        camera: &Camera,
+        viewport: Viewport,
+        mut render: F,
+    ) -> Result<(), Error> {
+        let (left_viewport, right_viewport) = Self::side_by_side_viewports(viewport);
+        self.update(camera, left_viewport.aspect())?;
+        render(left_viewport, &self.left)?;
+        render(right_viewport, &self.right)?;
+        Ok(())
+    }
+}
+
+fn update_eye(
+    eye: &mut Camera,
+    camera: &Camera,
+    aspect: f32,
+    offset: f32,
+    convergence_distance: f32,
+) -> Result<(), Error> {
+    let translation = camera.right_direction().normalize() * offset;
+    eye.set_view(
+        camera.position() + translation,
+        camera.target() + translation,
+        *camera.up(),
+    )?;
+    match *camera.projection_type() {
+        ProjectionType::Perspective {
+            field_of_view_y,
+            z_near,
+            z_far,
+            ..
+        } => eye.set_perspective_projection(field_of_view_y, aspect, z_near, z_far)?,
+        ProjectionType::Orthographic { height, depth, .. } => {
+            eye.set_orthographic_projection(height * aspect, height, depth)?
+        }
+    }
+    eye.clear_projection_window()?;
+    // Shift the frustum such that a point at the convergence distance in front of the center camera is projected to the center of the image
+    let shift = match *camera.projection_type() {
+        ProjectionType::Perspective { .. } => {
+            eye.projection()[0][0] * offset / convergence_distance
+        }
+        ProjectionType::Orthographic { .. } => 0.0,
+    };
+    eye.set_projection_window(vec2(-0.5 * shift, 0.0), vec2(1.0 - 0.5 * shift, 1.0))
+}
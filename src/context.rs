@@ -4,8 +4,22 @@
 //! Thin and low-level graphics abstraction layer which maps one-to-one with the OpenGL graphics API on desktop
 //! and WebGL2 bindings provided by the [web-sys](https://rustwasm.github.io/wasm-bindgen/api/web_sys/) crate on web.
 //! Can be used in combination with more high-level features or be ignored entirely.
+//! The graphics API in use is returned by [Context::backend] and the limits of the device by [Context::capabilities].
+//! A backend on wgpu, which provides the same [resources](crate::GraphicsBackend), is available in the `wgpu_backend` module when the `wgpu-backend` feature is enabled.
 //!
 
+mod state_cache;
+use state_cache::StateCache;
+
+mod memory;
+#[doc(inline)]
+pub use memory::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod program_binary_cache;
+#[cfg(not(target_arch = "wasm32"))]
+use program_binary_cache::ProgramBinaryCache;
+
 // GL
 #[cfg(not(target_arch = "wasm32"))]
 mod ogl;
@@ -21,3 +35,93 @@ mod wgl2;
 #[doc(inline)]
 #[cfg(target_arch = "wasm32")]
 pub use wgl2::*;
+
+///
+/// The graphics API which a [Context] or another [graphics backend](crate::GraphicsBackend) maps to,
+/// which can be used to work around the restrictions of a specific API.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// OpenGL 3.3 or newer on desktop.
+    OpenGL,
+    /// WebGL 2 on web.
+    WebGL2,
+    /// wgpu using the given graphics API, for example Vulkan, Metal, DX12 or WebGPU (only available when the `wgpu-backend` feature is enabled).
+    #[cfg(feature = "wgpu-backend")]
+    Wgpu(wgpu::Backend),
+}
+
+///
+/// The limits of the device a [Context] renders on, which are queried once and then cached.
+/// Resources exceeding these limits are rejected with a descriptive error when created,
+/// see for example [CPUTexture::resize_to_max_size](crate::CPUTexture::resize_to_max_size) for downscaling textures to fit.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The maximum width and height of a 2D texture or a texture array.
+    pub max_texture_size: u32,
+    /// The maximum width and height of a side of a cube map.
+    pub max_cube_map_texture_size: u32,
+    /// The maximum number of layers in a texture array.
+    pub max_array_texture_layers: u32,
+    /// The maximum number of vertex attributes in a shader program.
+    pub max_vertex_attribs: u32,
+    /// The maximum size of a uniform block in bytes.
+    pub max_uniform_block_size: u32,
+    /// The maximum number of color textures which can be rendered into at the same time.
+    pub max_color_attachments: u32,
+    /// The maximum number of samples used for multisampling.
+    pub max_samples: u32,
+    /// Whether textures with a width or height which is not a power of two can be mip mapped and repeated,
+    /// which is always the case for OpenGL 3.3 and WebGL 2, but not for example for WebGL 1. See [NonPowerOfTwo](crate::NonPowerOfTwo).
+    pub non_power_of_two_textures: bool,
+}
+
+impl Capabilities {
+    fn query(context: &Context) -> Self {
+        Self {
+            max_texture_size: context.get_integer_parameter(consts::MAX_TEXTURE_SIZE),
+            max_cube_map_texture_size: context
+                .get_integer_parameter(consts::MAX_CUBE_MAP_TEXTURE_SIZE),
+            max_array_texture_layers: context
+                .get_integer_parameter(consts::MAX_ARRAY_TEXTURE_LAYERS),
+            max_vertex_attribs: context.get_integer_parameter(consts::MAX_VERTEX_ATTRIBS),
+            max_uniform_block_size: context.get_integer_parameter(consts::MAX_UNIFORM_BLOCK_SIZE),
+            // Rendering into a color attachment also requires a draw buffer
+            max_color_attachments: context
+                .get_integer_parameter(consts::MAX_COLOR_ATTACHMENTS)
+                .min(context.get_integer_parameter(consts::MAX_DRAW_BUFFERS)),
+            max_samples: context.get_integer_parameter(consts::MAX_SAMPLES),
+            // Required by both OpenGL 3.3 and WebGL 2
+            non_power_of_two_textures: true,
+        }
+    }
+}
+
+impl Context {
+    ///
+    /// Returns the limits of the device, see [Capabilities].
+    ///
+    pub fn capabilities(&self) -> Capabilities {
+        if let Some(capabilities) = self.cached_capabilities() {
+            return capabilities;
+        }
+        let capabilities = Capabilities::query(self);
+        self.cache_capabilities(capabilities);
+        capabilities
+    }
+
+    ///
+    /// Returns the graphics API which this context maps to.
+    ///
+    pub fn backend(&self) -> Backend {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Backend::OpenGL
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            Backend::WebGL2
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use super::Context;
+use std::collections::HashMap;
+
+///
+/// The types of GPU resources whose memory is tracked by a [Context], see [Context::memory_report].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ResourceType {
+    /// A [VertexBuffer](crate::VertexBuffer).
+    VertexBuffer,
+    /// An [ElementBuffer](crate::ElementBuffer).
+    ElementBuffer,
+    /// A [UniformBuffer](crate::UniformBuffer).
+    UniformBuffer,
+    /// A texture which can only be sampled, for example a [Texture2D](crate::Texture2D), a [Texture2DArray](crate::Texture2DArray)
+    /// or a [TextureCubeMap](crate::TextureCubeMap).
+    Texture,
+    /// A texture which can be rendered into, for example a [ColorTargetTexture2D](crate::ColorTargetTexture2D) or a [DepthTargetTexture2D](crate::DepthTargetTexture2D).
+    RenderTargetTexture,
+    /// A [Program](crate::Program). The memory of a program is unknown, so only the number of programs is tracked.
+    Program,
+}
+
+impl ResourceType {
+    const ALL: [ResourceType; 6] = [
+        ResourceType::VertexBuffer,
+        ResourceType::ElementBuffer,
+        ResourceType::UniformBuffer,
+        ResourceType::Texture,
+        ResourceType::RenderTargetTexture,
+        ResourceType::Program,
+    ];
+}
+
+///
+/// The number of resources of one [ResourceType] which are currently allocated and an estimate of the GPU memory they use.
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// The number of allocated resources.
+    pub count: usize,
+    /// The estimated number of bytes used by the resources, computed from the size and format of the data,
+    /// so the actual memory use depends on the driver, for example because of padding and alignment.
+    pub bytes: u64,
+}
+
+///
+/// The GPU resources allocated through a [Context] at the time the report was made, see [Context::memory_report].
+/// Implements [Display](std::fmt::Display) which writes one resource type on each line, for example for logging.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryReport {
+    /// The usage of each resource type.
+    pub resources: Vec<(ResourceType, ResourceUsage)>,
+    /// The memory budget in bytes, see [Context::set_memory_budget].
+    pub budget: Option<u64>,
+}
+
+impl MemoryReport {
+    ///
+    /// Returns the usage of the given resource type.
+    ///
+    pub fn usage(&self, resource_type: ResourceType) -> ResourceUsage {
+        self.resources
+            .iter()
+            .find(|(t, _)| *t == resource_type)
+            .map(|(_, usage)| *usage)
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Returns the total number of allocated resources.
+    ///
+    pub fn total_count(&self) -> usize {
+        self.resources.iter().map(|(_, usage)| usage.count).sum()
+    }
+
+    ///
+    /// Returns the estimated total number of bytes used by the allocated resources.
+    ///
+    pub fn total_bytes(&self) -> u64 {
+        self.resources.iter().map(|(_, usage)| usage.bytes).sum()
+    }
+
+    ///
+    /// Returns whether the total number of bytes exceeds the memory budget.
+    ///
+    pub fn is_over_budget(&self) -> bool {
+        self.budget
+            .map(|budget| self.total_bytes() > budget)
+            .unwrap_or(false)
+    }
+}
+
+impl std::fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (resource_type, usage) in self.resources.iter() {
+            writeln!(
+                f,
+                "{:?}: {} ({:.2} MB)",
+                resource_type,
+                usage.count,
+                usage.bytes as f64 / (1024.0 * 1024.0)
+            )?;
+        }
+        write!(
+            f,
+            "Total: {} ({:.2} MB",
+            self.total_count(),
+            self.total_bytes() as f64 / (1024.0 * 1024.0)
+        )?;
+        if let Some(budget) = self.budget {
+            write!(
+                f,
+                " of a budget of {:.2} MB",
+                budget as f64 / (1024.0 * 1024.0)
+            )?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MemoryTracker {
+    usage: HashMap<ResourceType, ResourceUsage>,
+    budget: Option<u64>,
+    over_budget: bool,
+}
+
+impl MemoryTracker {
+    fn allocate(&mut self, resource_type: ResourceType, bytes: u64) {
+        let usage = self.usage.entry(resource_type).or_default();
+        usage.count += 1;
+        usage.bytes += bytes;
+        self.check_budget();
+    }
+
+    fn deallocate(&mut self, resource_type: ResourceType, bytes: u64) {
+        let usage = self.usage.entry(resource_type).or_default();
+        usage.count = usage.count.saturating_sub(1);
+        usage.bytes = usage.bytes.saturating_sub(bytes);
+        self.check_budget();
+    }
+
+    fn resize(&mut self, resource_type: ResourceType, old_bytes: u64, new_bytes: u64) {
+        let usage = self.usage.entry(resource_type).or_default();
+        usage.bytes = usage.bytes.saturating_sub(old_bytes) + new_bytes;
+        self.check_budget();
+    }
+
+    fn report(&self) -> MemoryReport {
+        MemoryReport {
+            resources: ResourceType::ALL
+                .iter()
+                .map(|t| (*t, self.usage.get(t).cloned().unwrap_or_default()))
+                .collect(),
+            budget: self.budget,
+        }
+    }
+
+    fn check_budget(&mut self) {
+        let total: u64 = self.usage.values().map(|usage| usage.bytes).sum();
+        let over_budget = self.budget.map(|budget| total > budget).unwrap_or(false);
+        // Only warn when the budget is first exceeded and not again for each following allocation
+        #[cfg(debug_assertions)]
+        if over_budget && !self.over_budget {
+            log::warn!(
+                "The estimated GPU memory use of {:.2} MB exceeds the budget of {:.2} MB",
+                total as f64 / (1024.0 * 1024.0),
+                self.budget.unwrap() as f64 / (1024.0 * 1024.0)
+            );
+        }
+        self.over_budget = over_budget;
+    }
+}
+
+impl Context {
+    ///
+    /// Returns the number of resources of each [ResourceType] which are currently allocated through this context and an estimate of their GPU memory use.
+    /// A number that keeps growing from frame to frame usually means that resources are created each frame but kept alive somewhere.
+    ///
+    pub fn memory_report(&self) -> MemoryReport {
+        self.memory_tracker().borrow().report()
+    }
+
+    ///
+    /// Sets the budget in bytes for the estimated GPU memory use, see [memory_report](Self::memory_report).
+    /// In debug builds, a warning is logged when the total exceeds the budget.
+    ///
+    pub fn set_memory_budget(&self, budget: Option<u64>) {
+        let mut tracker = self.memory_tracker().borrow_mut();
+        tracker.budget = budget;
+        tracker.check_budget();
+    }
+
+    ///
+    /// In debug builds, logs a warning with the [memory report](Self::memory_report) if any resources are still allocated,
+    /// and returns whether that is the case.
+    /// Call this when all objects using this context are expected to be dropped, for example when the render loop has ended,
+    /// to detect resources which are never dropped, for example because of a reference cycle.
+    ///
+    pub fn check_for_leaks(&self) -> bool {
+        let report = self.memory_report();
+        let leaks = report.total_count() > 0;
+        #[cfg(debug_assertions)]
+        if leaks {
+            log::warn!(
+                "{} GPU resources were not dropped before the end:\n{}",
+                report.total_count(),
+                report
+            );
+        }
+        leaks
+    }
+}
+
+///
+/// Registers an allocated resource in the [MemoryTracker] of the context while alive.
+///
+pub(crate) struct Allocation {
+    context: Context,
+    resource_type: ResourceType,
+    bytes: u64,
+}
+
+impl Allocation {
+    pub fn new(context: &Context, resource_type: ResourceType, bytes: u64) -> Self {
+        context
+            .memory_tracker()
+            .borrow_mut()
+            .allocate(resource_type, bytes);
+        Self {
+            context: context.clone(),
+            resource_type,
+            bytes,
+        }
+    }
+
+    pub fn resize(&mut self, bytes: u64) {
+        self.context
+            .memory_tracker()
+            .borrow_mut()
+            .resize(self.resource_type, self.bytes, bytes);
+        self.bytes = bytes;
+    }
+}
+
+impl Drop for Allocation {
+    fn drop(&mut self) {
+        self.context
+            .memory_tracker()
+            .borrow_mut()
+            .deallocate(self.resource_type, self.bytes);
+    }
+}
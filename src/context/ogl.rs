@@ -15,6 +15,7 @@ pub type Framebuffer = u32;
 pub type Texture = u32;
 pub type VertexArrayObject = u32;
 pub type Sync = consts::types::GLsync;
+pub type Query = u32;
 pub struct ActiveInfo {
     size: u32,
     type_: u32,
@@ -44,20 +45,81 @@ impl ActiveInfo {
 #[derive(Clone)]
 pub struct Context {
     inner: Rc<InnerGl>,
+    state: Rc<std::cell::RefCell<super::StateCache>>,
+    memory: Rc<std::cell::RefCell<super::MemoryTracker>>,
+    program_binary_cache: Rc<std::cell::RefCell<Option<super::ProgramBinaryCache>>>,
 }
 
 impl Context {
+    ///
+    /// Forgets the cached graphics state, so that the next state changes are applied regardless of the current state.
+    /// Must be called if the state is changed without using this context, for example by another library using the same OpenGL context.
+    ///
+    pub fn invalidate_state_cache(&self) {
+        self.state.borrow_mut().invalidate();
+    }
+
+    ///
+    /// Returns the number of state changes that have been skipped because they would not change the current state.
+    ///
+    pub fn skipped_state_changes(&self) -> usize {
+        self.state.borrow().skipped_calls()
+    }
+
     pub fn load_with<F>(loadfn: F) -> Context
     where
         for<'r> F: FnMut(&'r str) -> *const consts::types::GLvoid,
     {
         let gl = Context {
             inner: Rc::new(InnerGl::load_with(loadfn)),
+            state: Rc::new(std::cell::RefCell::new(super::StateCache::default())),
+            memory: Rc::new(std::cell::RefCell::new(super::MemoryTracker::default())),
+            program_binary_cache: Rc::new(std::cell::RefCell::new(None)),
         };
-        gl.bind_vertex_array(&gl.create_vertex_array().unwrap());
+        gl.initialize();
+        gl
+    }
+
+    ///
+    /// Loads a context for an OpenGL context which shares its resources with the OpenGL context of the given context,
+    /// and which has the given id. Both contexts use the same state cache, since the objects created with one of them
+    /// can be rendered while the OpenGL context of the other one is current, so [make_current](Self::make_current)
+    /// must be called whenever the OpenGL context is made current.
+    ///
+    #[cfg(feature = "glutin-window")]
+    pub(crate) fn load_shared_with<F>(loadfn: F, shared: &Context, id: usize) -> Context
+    where
+        for<'r> F: FnMut(&'r str) -> *const consts::types::GLvoid,
+    {
+        let gl = Context {
+            inner: Rc::new(InnerGl::load_with(loadfn)),
+            state: shared.state.clone(),
+            memory: Rc::new(std::cell::RefCell::new(super::MemoryTracker::default())),
+            program_binary_cache: Rc::new(std::cell::RefCell::new(None)),
+        };
+        gl.make_current(id);
+        gl.initialize();
         gl
     }
 
+    ///
+    /// Switches the state cache to the OpenGL context with the given id, see [load_shared_with](Self::load_shared_with).
+    /// Must be called after the OpenGL context has been made current.
+    ///
+    #[cfg(feature = "glutin-window")]
+    pub(crate) fn make_current(&self, id: usize) {
+        self.state.borrow_mut().switch_context(id);
+    }
+
+    fn initialize(&self) {
+        let gl = self;
+        gl.bind_vertex_array(&gl.create_vertex_array().unwrap());
+        // Always enabled in WebGL2, so enabled here to get the same behaviour on desktop
+        gl.enable(consts::PRIMITIVE_RESTART_FIXED_INDEX);
+        // Always seamless in WebGL2, so enabled here to filter across the cube map faces on desktop too
+        gl.enable(consts::TEXTURE_CUBE_MAP_SEAMLESS);
+    }
+
     pub fn finish(&self) {
         unsafe {
             self.inner.Finish();
@@ -70,7 +132,11 @@ impl Context {
     }
 
     pub fn compile_shader(&self, source: &str, shader: &Shader) {
-        let header = "#version 330 core\n";
+        let header = if source.trim_start().starts_with("#version") {
+            ""
+        } else {
+            "#version 330 core\n"
+        };
         let s: &str = &[header, source].concat();
 
         use std::ffi::{CStr, CString};
@@ -232,6 +298,85 @@ impl Context {
         }
     }
 
+    pub fn get_active_uniform_block_parameter(
+        &self,
+        program: &Program,
+        location: u32,
+        pname: u32,
+    ) -> u32 {
+        let mut out = 0;
+        unsafe {
+            self.inner
+                .GetActiveUniformBlockiv(*program, location, pname, &mut out);
+        }
+        out as u32
+    }
+
+    pub fn get_max_vertex_uniform_vectors(&self) -> u32 {
+        let mut out = 0;
+        unsafe {
+            self.inner
+                .GetIntegerv(consts::MAX_VERTEX_UNIFORM_COMPONENTS, &mut out);
+        }
+        out as u32 / 4
+    }
+
+    pub fn get_integer_parameter(&self, pname: u32) -> u32 {
+        let mut out = 0;
+        unsafe {
+            self.inner.GetIntegerv(pname, &mut out);
+        }
+        out as u32
+    }
+
+    pub(super) fn memory_tracker(&self) -> &std::cell::RefCell<super::MemoryTracker> {
+        &self.memory
+    }
+
+    pub(super) fn program_binary_cache(
+        &self,
+    ) -> &std::cell::RefCell<Option<super::ProgramBinaryCache>> {
+        &self.program_binary_cache
+    }
+
+    pub(super) fn cached_capabilities(&self) -> Option<super::Capabilities> {
+        self.state.borrow().capabilities()
+    }
+
+    pub(super) fn cache_capabilities(&self, capabilities: super::Capabilities) {
+        self.state.borrow_mut().set_capabilities(capabilities);
+    }
+
+    pub fn get_max_uniform_buffer_bindings(&self) -> u32 {
+        let mut out = 0;
+        unsafe {
+            self.inner
+                .GetIntegerv(consts::MAX_UNIFORM_BUFFER_BINDINGS, &mut out);
+        }
+        out as u32
+    }
+
+    ///
+    /// Returns the uniform buffer binding point assigned to uniform blocks with the given name, which is shared by all programs using this context.
+    /// The binding point is allocated the first time it is acquired and freed again when it has been [released](Self::release_uniform_block_binding)
+    /// as many times as it has been acquired. Returns `None` if all of the binding points are in use.
+    ///
+    pub fn acquire_uniform_block_binding(&self, block_name: &str) -> Option<u32> {
+        let max_bindings = self.get_max_uniform_buffer_bindings();
+        self.state
+            .borrow_mut()
+            .acquire_uniform_block_binding(block_name, max_bindings)
+    }
+
+    ///
+    /// Releases the uniform buffer binding point of uniform blocks with the given name, see [acquire_uniform_block_binding](Self::acquire_uniform_block_binding).
+    ///
+    pub fn release_uniform_block_binding(&self, block_name: &str) {
+        self.state
+            .borrow_mut()
+            .release_uniform_block_binding(block_name)
+    }
+
     pub fn buffer_data(&self, target: u32, size_in_bytes: u32, usage: u32) {
         unsafe {
             self.inner.BufferData(
@@ -287,6 +432,17 @@ impl Context {
         }
     }
 
+    pub fn buffer_sub_data_f32(&self, target: u32, offset: u32, data: &[f32]) {
+        unsafe {
+            self.inner.BufferSubData(
+                target,
+                byte_size_for_type(consts::FLOAT, offset) as consts::types::GLintptr, // offset in bytes
+                (data.len() * std::mem::size_of::<f32>()) as consts::types::GLsizeiptr, // size of data in bytes
+                data.as_ptr() as *const consts::types::GLvoid, // pointer to data
+            );
+        }
+    }
+
     pub fn create_vertex_array(&self) -> Option<VertexArrayObject> {
         let mut id: u32 = 0;
         unsafe {
@@ -305,6 +461,73 @@ impl Context {
         unsafe { self.inner.CreateProgram() }
     }
 
+    pub fn get_string(&self, name: u32) -> String {
+        unsafe {
+            let string = self.inner.GetString(name);
+            if string.is_null() {
+                String::new()
+            } else {
+                std::ffi::CStr::from_ptr(string as *const std::os::raw::c_char)
+                    .to_string_lossy()
+                    .into_owned()
+            }
+        }
+    }
+
+    pub fn supports_program_binaries(&self) -> bool {
+        self.get_integer_parameter(consts::NUM_PROGRAM_BINARY_FORMATS) > 0
+    }
+
+    pub fn program_binary_retrievable_hint(&self, program: &Program) {
+        unsafe {
+            self.inner.ProgramParameteri(
+                *program,
+                consts::PROGRAM_BINARY_RETRIEVABLE_HINT,
+                consts::TRUE as i32,
+            );
+        }
+    }
+
+    pub fn get_program_binary(&self, program: &Program) -> Option<(u32, Vec<u8>)> {
+        let length = self.get_program_parameter(program, consts::PROGRAM_BINARY_LENGTH);
+        if length == 0 {
+            return None;
+        }
+        let mut binary = vec![0u8; length as usize];
+        let mut written: consts::types::GLsizei = 0;
+        let mut format: u32 = 0;
+        unsafe {
+            self.inner.GetProgramBinary(
+                *program,
+                length as i32,
+                &mut written,
+                &mut format,
+                binary.as_mut_ptr() as *mut consts::types::GLvoid,
+            );
+        }
+        binary.truncate(written as usize);
+        if binary.is_empty() {
+            None
+        } else {
+            Some((format, binary))
+        }
+    }
+
+    pub fn program_binary(&self, program: &Program, format: u32, binary: &[u8]) -> bool {
+        let mut success: consts::types::GLint = 0;
+        unsafe {
+            self.inner.ProgramBinary(
+                *program,
+                format,
+                binary.as_ptr() as *const consts::types::GLvoid,
+                binary.len() as i32,
+            );
+            self.inner
+                .GetProgramiv(*program, consts::LINK_STATUS, &mut success);
+        }
+        success == 1
+    }
+
     pub fn link_program(&self, program: &Program) -> bool {
         unsafe {
             self.inner.LinkProgram(*program);
@@ -467,8 +690,12 @@ impl Context {
 
     pub fn uniform_matrix4fv(&self, location: &UniformLocation, data: &[f32]) {
         unsafe {
-            self.inner
-                .UniformMatrix4fv(*location as i32, 1, consts::FALSE, data.as_ptr());
+            self.inner.UniformMatrix4fv(
+                *location as i32,
+                (data.len() / 16) as i32,
+                consts::FALSE,
+                data.as_ptr(),
+            );
         }
     }
 
@@ -566,11 +793,23 @@ impl Context {
     }
 
     pub fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        if !self.state.borrow_mut().set_viewport([x, y, width, height]) {
+            return;
+        }
         unsafe {
             self.inner.Viewport(x, y, width, height);
         }
     }
 
+    pub fn scissor(&self, x: i32, y: i32, width: i32, height: i32) {
+        if !self.state.borrow_mut().set_scissor([x, y, width, height]) {
+            return;
+        }
+        unsafe {
+            self.inner.Scissor(x, y, width, height);
+        }
+    }
+
     pub fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
         unsafe {
             self.inner.ClearColor(red, green, blue, alpha);
@@ -583,6 +822,12 @@ impl Context {
         }
     }
 
+    pub fn clear_stencil(&self, stencil: i32) {
+        unsafe {
+            self.inner.ClearStencil(stencil);
+        }
+    }
+
     pub fn clear(&self, mask: u32) {
         unsafe {
             self.inner.Clear(mask);
@@ -590,24 +835,44 @@ impl Context {
     }
 
     pub fn enable(&self, cap: u32) {
+        if !self.state.borrow_mut().set_capability(cap, true) {
+            return;
+        }
         unsafe {
             self.inner.Enable(cap);
         }
     }
 
     pub fn disable(&self, cap: u32) {
+        if !self.state.borrow_mut().set_capability(cap, false) {
+            return;
+        }
         unsafe {
             self.inner.Disable(cap);
         }
     }
 
     pub fn blend_func(&self, sfactor: u32, dfactor: u32) {
+        if !self
+            .state
+            .borrow_mut()
+            .set_blend_func([sfactor, dfactor, sfactor, dfactor])
+        {
+            return;
+        }
         unsafe {
             self.inner.BlendFunc(sfactor, dfactor);
         }
     }
 
     pub fn blend_func_separate(&self, src_rgb: u32, dst_rgb: u32, src_alpha: u32, dst_alpha: u32) {
+        if !self
+            .state
+            .borrow_mut()
+            .set_blend_func([src_rgb, dst_rgb, src_alpha, dst_alpha])
+        {
+            return;
+        }
         unsafe {
             self.inner
                 .BlendFuncSeparate(src_rgb, dst_rgb, src_alpha, dst_alpha);
@@ -615,30 +880,140 @@ impl Context {
     }
 
     pub fn blend_equation(&self, mode: u32) {
+        if !self.state.borrow_mut().set_blend_equation([mode, mode]) {
+            return;
+        }
         unsafe {
             self.inner.BlendEquation(mode);
         }
     }
 
     pub fn blend_equation_separate(&self, mode_rgb: u32, mode_alpha: u32) {
+        if !self
+            .state
+            .borrow_mut()
+            .set_blend_equation([mode_rgb, mode_alpha])
+        {
+            return;
+        }
         unsafe {
             self.inner.BlendEquationSeparate(mode_rgb, mode_alpha);
         }
     }
 
     pub fn cull_face(&self, mode: u32) {
+        if !self.state.borrow_mut().set_cull_face(mode) {
+            return;
+        }
         unsafe {
             self.inner.CullFace(mode);
         }
     }
 
+    pub fn front_face(&self, mode: u32) {
+        unsafe {
+            self.inner.FrontFace(mode);
+        }
+    }
+
     pub fn depth_func(&self, func: u32) {
+        if !self.state.borrow_mut().set_depth_func(func) {
+            return;
+        }
         unsafe {
             self.inner.DepthFunc(func);
         }
     }
 
+    pub fn polygon_offset(&self, factor: f32, units: f32) {
+        if !self.state.borrow_mut().set_polygon_offset([factor, units]) {
+            return;
+        }
+        unsafe {
+            self.inner.PolygonOffset(factor, units);
+        }
+    }
+
+    pub fn line_width(&self, width: f32) {
+        if !self.state.borrow_mut().set_line_width(width) {
+            return;
+        }
+        unsafe {
+            self.inner.LineWidth(width);
+        }
+    }
+
+    pub fn stencil_func(&self, func: u32, reference: i32, mask: u32) {
+        if !self
+            .state
+            .borrow_mut()
+            .set_stencil_func((func, reference, mask))
+        {
+            return;
+        }
+        unsafe {
+            self.inner.StencilFunc(func, reference, mask);
+        }
+    }
+
+    pub fn stencil_op(&self, fail: u32, depth_fail: u32, pass: u32) {
+        if !self
+            .state
+            .borrow_mut()
+            .set_stencil_op([fail, depth_fail, pass])
+        {
+            return;
+        }
+        unsafe {
+            self.inner.StencilOp(fail, depth_fail, pass);
+        }
+    }
+
+    pub fn stencil_mask(&self, mask: u32) {
+        if !self.state.borrow_mut().set_stencil_mask(mask) {
+            return;
+        }
+        unsafe {
+            self.inner.StencilMask(mask);
+        }
+    }
+
+    pub fn supports_clip_control(&self) -> bool {
+        self.inner.ClipControl.is_loaded()
+    }
+
+    pub fn set_reverse_depth(&self, reverse: bool) {
+        if !self.state.borrow_mut().set_reverse_depth(reverse) {
+            return;
+        }
+        // With the depth range [0, 1] in normalized device coordinates, the precision of a float depth buffer is spread evenly
+        // over the distance when the depth is reversed, which is not the case for the default range [-1, 1]
+        if self.supports_clip_control() {
+            unsafe {
+                self.inner.ClipControl(
+                    consts::LOWER_LEFT,
+                    if reverse {
+                        consts::ZERO_TO_ONE
+                    } else {
+                        consts::NEGATIVE_ONE_TO_ONE
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn reverse_depth(&self) -> bool {
+        self.state.borrow().reverse_depth()
+    }
+
     pub fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool) {
+        if !self
+            .state
+            .borrow_mut()
+            .set_color_mask([red, green, blue, alpha])
+        {
+            return;
+        }
         unsafe {
             self.inner.ColorMask(
                 if red { consts::TRUE } else { consts::FALSE },
@@ -650,6 +1025,9 @@ impl Context {
     }
 
     pub fn depth_mask(&self, flag: bool) {
+        if !self.state.borrow_mut().set_depth_mask(flag) {
+            return;
+        }
         unsafe {
             if flag {
                 self.inner.DepthMask(consts::TRUE);
@@ -886,6 +1264,99 @@ impl Context {
         }
     }
 
+    pub fn tex_sub_image_3d_with_u8_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[u8],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
+    pub fn tex_sub_image_3d_with_f32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[f32],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
+    pub fn tex_sub_image_3d_with_u32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[u32],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
     pub fn tex_image_3d(
         &self,
         target: u32,
@@ -1148,6 +1619,59 @@ impl Context {
             self.inner.DeleteSync(*sync);
         }
     }
+
+    pub fn supports_timer_queries(&self) -> bool {
+        true
+    }
+
+    pub fn create_query(&self) -> Option<Query> {
+        let mut id: u32 = 0;
+        unsafe {
+            self.inner.GenQueries(1, &mut id);
+        }
+        Some(id)
+    }
+
+    pub fn delete_query(&self, query: &Query) {
+        unsafe {
+            self.inner.DeleteQueries(1, query);
+        }
+    }
+
+    pub fn begin_query(&self, target: u32, query: &Query) {
+        unsafe {
+            self.inner.BeginQuery(target, *query);
+        }
+    }
+
+    pub fn end_query(&self, target: u32) {
+        unsafe {
+            self.inner.EndQuery(target);
+        }
+    }
+
+    pub fn get_query_result_available(&self, query: &Query) -> bool {
+        let mut available: u32 = 0;
+        unsafe {
+            self.inner
+                .GetQueryObjectuiv(*query, consts::QUERY_RESULT_AVAILABLE, &mut available);
+        }
+        available != 0
+    }
+
+    pub fn get_gpu_disjoint(&self) -> bool {
+        // Only the EXT_disjoint_timer_query extensions of OpenGL ES and WebGL report disjoint operations
+        false
+    }
+
+    pub fn get_query_result(&self, query: &Query) -> u64 {
+        let mut result: u64 = 0;
+        unsafe {
+            self.inner
+                .GetQueryObjectui64v(*query, consts::QUERY_RESULT, &mut result);
+        }
+        result
+    }
 }
 
 fn create_whitespace_cstring_with_len(len: usize) -> std::ffi::CString {
@@ -0,0 +1,147 @@
+use super::{consts, Context, Program};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+///
+/// Stores the binaries of linked programs in a directory, one file for each program named by the hash of the shader sources,
+/// in a subdirectory named by the hash of the driver, since a binary can only be loaded by the driver that created it.
+///
+pub(crate) struct ProgramBinaryCache {
+    directory: PathBuf,
+    binaries: HashMap<u64, (u32, Vec<u8>)>,
+}
+
+impl ProgramBinaryCache {
+    fn load(directory: &Path, driver: &str) -> std::io::Result<Self> {
+        let directory = directory.join(format!("{:016x}", hash(&[driver.as_bytes()])));
+        std::fs::create_dir_all(&directory)?;
+        let mut binaries = HashMap::new();
+        for entry in std::fs::read_dir(&directory)? {
+            let path = entry?.path();
+            let key = match path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| u64::from_str_radix(stem, 16).ok())
+            {
+                Some(key) => key,
+                None => continue,
+            };
+            let bytes = std::fs::read(&path)?;
+            // The binary format is stored in the first four bytes of the file
+            if bytes.len() > 4 {
+                let format = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                binaries.insert(key, (format, bytes[4..].to_vec()));
+            }
+        }
+        Ok(Self {
+            directory,
+            binaries,
+        })
+    }
+
+    fn store(&mut self, key: u64, format: u32, binary: Vec<u8>) {
+        let mut bytes = format.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&binary);
+        let path = self.directory.join(format!("{:016x}.bin", key));
+        if let Err(error) = std::fs::write(&path, bytes) {
+            log::warn!(
+                "Failed to write the program binary {}: {}",
+                path.display(),
+                error
+            );
+        }
+        self.binaries.insert(key, (format, binary));
+    }
+}
+
+impl Context {
+    ///
+    /// Enables caching of the binaries of linked [programs](crate::Program) in the given directory, or disables it if `None`,
+    /// which reduces the time spent compiling shaders when the application is started again, especially for applications with many materials.
+    /// The cached binaries are loaded from the directory when this is called and are only used with the same driver and shader sources,
+    /// otherwise the program is compiled and the binary is added to the cache.
+    /// Only available on desktop, since WebGL does not support program binaries.
+    ///
+    pub fn set_program_binary_cache(&self, directory: Option<&Path>) -> std::io::Result<()> {
+        let cache = match directory {
+            Some(directory) if self.supports_program_binaries() => {
+                let driver = format!(
+                    "{}\n{}\n{}",
+                    self.get_string(consts::VENDOR),
+                    self.get_string(consts::RENDERER),
+                    self.get_string(consts::VERSION)
+                );
+                Some(ProgramBinaryCache::load(directory, &driver)?)
+            }
+            _ => None,
+        };
+        *self.program_binary_cache().borrow_mut() = cache;
+        Ok(())
+    }
+
+    ///
+    /// Returns whether the binaries of linked programs are cached, see [set_program_binary_cache](Self::set_program_binary_cache).
+    ///
+    pub fn has_program_binary_cache(&self) -> bool {
+        self.program_binary_cache().borrow().is_some()
+    }
+
+    ///
+    /// Loads the cached binary of the program with the given shader sources into the given program, if any,
+    /// and returns whether the program is linked successfully.
+    ///
+    pub(crate) fn load_program_binary(
+        &self,
+        program: &Program,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> bool {
+        let key = hash(&[
+            vertex_shader_source.as_bytes(),
+            fragment_shader_source.as_bytes(),
+        ]);
+        match self.program_binary_cache().borrow().as_ref() {
+            Some(cache) => match cache.binaries.get(&key) {
+                Some((format, binary)) => self.program_binary(program, *format, binary),
+                None => false,
+            },
+            None => false,
+        }
+    }
+
+    ///
+    /// Adds the binary of the given linked program with the given shader sources to the cache.
+    ///
+    pub(crate) fn store_program_binary(
+        &self,
+        program: &Program,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) {
+        let mut cache = self.program_binary_cache().borrow_mut();
+        if let Some(cache) = cache.as_mut() {
+            if let Some((format, binary)) = self.get_program_binary(program) {
+                let key = hash(&[
+                    vertex_shader_source.as_bytes(),
+                    fragment_shader_source.as_bytes(),
+                ]);
+                cache.store(key, format, binary);
+            }
+        }
+    }
+}
+
+// FNV-1a, which unlike the hasher in the standard library gives the same hash across compiler versions
+fn hash(data: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for bytes in data {
+        for byte in bytes.iter() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        // Separates the parts, so that moving bytes from one part to the next changes the hash
+        hash ^= 0xff;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+///
+/// Keeps track of the graphics state set through a [Context](crate::context::Context), for example which capabilities are enabled,
+/// so that calls that would not change the state can be skipped.
+/// The cache can be shared by several contexts which use different OpenGL contexts, for example the windows of a
+/// [MultiWindow](crate::MultiWindow), in which case the state of each OpenGL context is cached separately, see [switch_context](Self::switch_context).
+///
+#[derive(Debug, Default)]
+pub(crate) struct StateCache {
+    capabilities: HashMap<u32, bool>,
+    viewport: Option<[i32; 4]>,
+    scissor: Option<[i32; 4]>,
+    cull_face: Option<u32>,
+    blend_func: Option<[u32; 4]>,
+    blend_equation: Option<[u32; 2]>,
+    color_mask: Option<[bool; 4]>,
+    depth_mask: Option<bool>,
+    depth_func: Option<u32>,
+    polygon_offset: Option<[f32; 2]>,
+    line_width: Option<f32>,
+    stencil_func: Option<(u32, i32, u32)>,
+    stencil_op: Option<[u32; 3]>,
+    stencil_mask: Option<u32>,
+    reverse_depth: Option<bool>,
+    uniform_block_bindings: HashMap<String, (u32, usize)>,
+    capabilities_cache: Option<super::Capabilities>,
+    skipped_calls: usize,
+    current_context: usize,
+    other_contexts: HashMap<usize, StateCache>,
+}
+
+impl StateCache {
+    pub fn set_capability(&mut self, cap: u32, enabled: bool) -> bool {
+        let changed = self.capabilities.get(&cap) != Some(&enabled);
+        self.capabilities.insert(cap, enabled);
+        self.count(changed)
+    }
+
+    pub fn set_viewport(&mut self, viewport: [i32; 4]) -> bool {
+        let changed = update(&mut self.viewport, viewport);
+        self.count(changed)
+    }
+
+    pub fn set_scissor(&mut self, scissor: [i32; 4]) -> bool {
+        let changed = update(&mut self.scissor, scissor);
+        self.count(changed)
+    }
+
+    pub fn set_cull_face(&mut self, mode: u32) -> bool {
+        let changed = update(&mut self.cull_face, mode);
+        self.count(changed)
+    }
+
+    pub fn set_blend_func(&mut self, func: [u32; 4]) -> bool {
+        let changed = update(&mut self.blend_func, func);
+        self.count(changed)
+    }
+
+    pub fn set_blend_equation(&mut self, equation: [u32; 2]) -> bool {
+        let changed = update(&mut self.blend_equation, equation);
+        self.count(changed)
+    }
+
+    pub fn set_color_mask(&mut self, mask: [bool; 4]) -> bool {
+        let changed = update(&mut self.color_mask, mask);
+        self.count(changed)
+    }
+
+    pub fn set_depth_mask(&mut self, mask: bool) -> bool {
+        let changed = update(&mut self.depth_mask, mask);
+        self.count(changed)
+    }
+
+    pub fn set_depth_func(&mut self, func: u32) -> bool {
+        let changed = update(&mut self.depth_func, func);
+        self.count(changed)
+    }
+
+    pub fn set_polygon_offset(&mut self, offset: [f32; 2]) -> bool {
+        let changed = update(&mut self.polygon_offset, offset);
+        self.count(changed)
+    }
+
+    pub fn set_line_width(&mut self, width: f32) -> bool {
+        let changed = update(&mut self.line_width, width);
+        self.count(changed)
+    }
+
+    pub fn set_stencil_func(&mut self, func: (u32, i32, u32)) -> bool {
+        let changed = update(&mut self.stencil_func, func);
+        self.count(changed)
+    }
+
+    pub fn set_stencil_op(&mut self, op: [u32; 3]) -> bool {
+        let changed = update(&mut self.stencil_op, op);
+        self.count(changed)
+    }
+
+    pub fn set_stencil_mask(&mut self, mask: u32) -> bool {
+        let changed = update(&mut self.stencil_mask, mask);
+        self.count(changed)
+    }
+
+    pub fn set_reverse_depth(&mut self, reverse: bool) -> bool {
+        let changed = update(&mut self.reverse_depth, reverse);
+        self.count(changed)
+    }
+
+    pub fn reverse_depth(&self) -> bool {
+        self.reverse_depth.unwrap_or(false)
+    }
+
+    pub fn acquire_uniform_block_binding(
+        &mut self,
+        block_name: &str,
+        max_bindings: u32,
+    ) -> Option<u32> {
+        if let Some((binding, users)) = self.uniform_block_bindings.get_mut(block_name) {
+            *users += 1;
+            return Some(*binding);
+        }
+        let binding = (0..max_bindings).find(|binding| {
+            !self
+                .uniform_block_bindings
+                .values()
+                .any(|(used, _)| used == binding)
+        })?;
+        self.uniform_block_bindings
+            .insert(block_name.to_string(), (binding, 1));
+        Some(binding)
+    }
+
+    pub fn release_uniform_block_binding(&mut self, block_name: &str) {
+        if let Some((_, users)) = self.uniform_block_bindings.get_mut(block_name) {
+            *users -= 1;
+            if *users == 0 {
+                self.uniform_block_bindings.remove(block_name);
+            }
+        }
+    }
+
+    pub fn skipped_calls(&self) -> usize {
+        self.skipped_calls
+    }
+
+    pub fn capabilities(&self) -> Option<super::Capabilities> {
+        self.capabilities_cache
+    }
+
+    pub fn set_capabilities(&mut self, capabilities: super::Capabilities) {
+        self.capabilities_cache = Some(capabilities);
+    }
+
+    pub fn invalidate(&mut self) {
+        // The binding points are assigned by this library and not queried from the graphics state, so they are kept,
+        // and the limits of the device do not change
+        *self = Self {
+            uniform_block_bindings: std::mem::take(&mut self.uniform_block_bindings),
+            capabilities_cache: self.capabilities_cache,
+            skipped_calls: self.skipped_calls,
+            current_context: self.current_context,
+            other_contexts: std::mem::take(&mut self.other_contexts),
+            ..Default::default()
+        };
+    }
+
+    ///
+    /// Switches to the cached state of the OpenGL context with the given id, which must be called whenever another OpenGL context
+    /// sharing this cache is made current. The state of an OpenGL context which has not been current before is unknown.
+    ///
+    #[cfg(feature = "glutin-window")]
+    pub fn switch_context(&mut self, id: usize) {
+        if id == self.current_context {
+            return;
+        }
+        let cached = self.other_contexts.remove(&id).unwrap_or_default();
+        // The binding points are shared, since the programs they are assigned in are shared between the OpenGL contexts
+        let next = Self {
+            uniform_block_bindings: std::mem::take(&mut self.uniform_block_bindings),
+            capabilities_cache: self.capabilities_cache,
+            skipped_calls: self.skipped_calls,
+            current_context: id,
+            other_contexts: std::mem::take(&mut self.other_contexts),
+            ..cached
+        };
+        let current = std::mem::replace(self, next);
+        self.other_contexts.insert(current.current_context, current);
+    }
+
+    fn count(&mut self, changed: bool) -> bool {
+        if !changed {
+            self.skipped_calls += 1;
+        }
+        changed
+    }
+}
+
+fn update<T: PartialEq>(current: &mut Option<T>, value: T) -> bool {
+    if current.as_ref() == Some(&value) {
+        false
+    } else {
+        *current = Some(value);
+        true
+    }
+}
@@ -8,6 +8,7 @@ pub use web_sys::WebGlActiveInfo as ActiveInfo;
 pub use web_sys::WebGlBuffer as Buffer;
 pub use web_sys::WebGlFramebuffer as Framebuffer;
 pub use web_sys::WebGlProgram as Program;
+pub use web_sys::WebGlQuery as Query;
 pub use web_sys::WebGlShader as Shader;
 pub use web_sys::WebGlSync as Sync;
 pub use web_sys::WebGlTexture as Texture;
@@ -17,12 +18,171 @@ pub use web_sys::WebGlVertexArrayObject as VertexArrayObject;
 #[derive(Clone)]
 pub struct Context {
     inner: std::rc::Rc<InnerGl>,
+    state: std::rc::Rc<std::cell::RefCell<super::StateCache>>,
+    memory: std::rc::Rc<std::cell::RefCell<super::MemoryTracker>>,
 }
 
 impl Context {
     pub fn new(webgl_context: InnerGl) -> Self {
         Self {
             inner: std::rc::Rc::new(webgl_context),
+            state: std::rc::Rc::new(std::cell::RefCell::new(super::StateCache::default())),
+            memory: std::rc::Rc::new(std::cell::RefCell::new(super::MemoryTracker::default())),
+        }
+    }
+
+    ///
+    /// Forgets the cached graphics state, so that the next state changes are applied regardless of the current state.
+    /// Must be called if the state is changed without using this context, for example by another library using the same WebGL context.
+    ///
+    pub fn invalidate_state_cache(&self) {
+        self.state.borrow_mut().invalidate();
+    }
+
+    ///
+    /// Returns the number of state changes that have been skipped because they would not change the current state.
+    ///
+    pub fn skipped_state_changes(&self) -> usize {
+        self.state.borrow().skipped_calls()
+    }
+
+    pub fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        if self.state.borrow_mut().set_viewport([x, y, width, height]) {
+            self.inner.viewport(x, y, width, height);
+        }
+    }
+
+    pub fn scissor(&self, x: i32, y: i32, width: i32, height: i32) {
+        if self.state.borrow_mut().set_scissor([x, y, width, height]) {
+            self.inner.scissor(x, y, width, height);
+        }
+    }
+
+    pub fn enable(&self, cap: u32) {
+        if self.state.borrow_mut().set_capability(cap, true) {
+            self.inner.enable(cap);
+        }
+    }
+
+    pub fn disable(&self, cap: u32) {
+        if self.state.borrow_mut().set_capability(cap, false) {
+            self.inner.disable(cap);
+        }
+    }
+
+    pub fn blend_func(&self, sfactor: u32, dfactor: u32) {
+        if self
+            .state
+            .borrow_mut()
+            .set_blend_func([sfactor, dfactor, sfactor, dfactor])
+        {
+            self.inner.blend_func(sfactor, dfactor);
+        }
+    }
+
+    pub fn blend_func_separate(&self, src_rgb: u32, dst_rgb: u32, src_alpha: u32, dst_alpha: u32) {
+        if self
+            .state
+            .borrow_mut()
+            .set_blend_func([src_rgb, dst_rgb, src_alpha, dst_alpha])
+        {
+            self.inner
+                .blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha);
+        }
+    }
+
+    pub fn blend_equation(&self, mode: u32) {
+        if self.state.borrow_mut().set_blend_equation([mode, mode]) {
+            self.inner.blend_equation(mode);
+        }
+    }
+
+    pub fn blend_equation_separate(&self, mode_rgb: u32, mode_alpha: u32) {
+        if self
+            .state
+            .borrow_mut()
+            .set_blend_equation([mode_rgb, mode_alpha])
+        {
+            self.inner.blend_equation_separate(mode_rgb, mode_alpha);
+        }
+    }
+
+    pub fn cull_face(&self, mode: u32) {
+        if self.state.borrow_mut().set_cull_face(mode) {
+            self.inner.cull_face(mode);
+        }
+    }
+
+    pub fn depth_func(&self, func: u32) {
+        if self.state.borrow_mut().set_depth_func(func) {
+            self.inner.depth_func(func);
+        }
+    }
+
+    pub fn polygon_offset(&self, factor: f32, units: f32) {
+        if self.state.borrow_mut().set_polygon_offset([factor, units]) {
+            self.inner.polygon_offset(factor, units);
+        }
+    }
+
+    pub fn line_width(&self, width: f32) {
+        if self.state.borrow_mut().set_line_width(width) {
+            self.inner.line_width(width);
+        }
+    }
+
+    pub fn stencil_func(&self, func: u32, reference: i32, mask: u32) {
+        if self
+            .state
+            .borrow_mut()
+            .set_stencil_func((func, reference, mask))
+        {
+            self.inner.stencil_func(func, reference, mask);
+        }
+    }
+
+    pub fn stencil_op(&self, fail: u32, depth_fail: u32, pass: u32) {
+        if self
+            .state
+            .borrow_mut()
+            .set_stencil_op([fail, depth_fail, pass])
+        {
+            self.inner.stencil_op(fail, depth_fail, pass);
+        }
+    }
+
+    pub fn stencil_mask(&self, mask: u32) {
+        if self.state.borrow_mut().set_stencil_mask(mask) {
+            self.inner.stencil_mask(mask);
+        }
+    }
+
+    pub fn supports_clip_control(&self) -> bool {
+        false
+    }
+
+    pub fn set_reverse_depth(&self, reverse: bool) {
+        // WebGL does not support changing the depth range in normalized device coordinates, so only the convention is stored
+        self.state.borrow_mut().set_reverse_depth(reverse);
+    }
+
+    pub fn reverse_depth(&self) -> bool {
+        self.state.borrow().reverse_depth()
+    }
+
+    pub fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool) {
+        if self
+            .state
+            .borrow_mut()
+            .set_color_mask([red, green, blue, alpha])
+        {
+            self.inner.color_mask(red, green, blue, alpha);
+        }
+    }
+
+    pub fn depth_mask(&self, flag: bool) {
+        if self.state.borrow_mut().set_depth_mask(flag) {
+            self.inner.depth_mask(flag);
         }
     }
 
@@ -34,6 +194,84 @@ impl Context {
         self.inner.bind_buffer_base(target, index, Some(buffer));
     }
 
+    pub fn get_uniform_block_index(&self, program: &Program, name: &str) -> u32 {
+        self.inner.get_uniform_block_index(program, name)
+    }
+
+    pub fn uniform_block_binding(&self, program: &Program, location: u32, index: u32) {
+        self.inner.uniform_block_binding(program, location, index);
+    }
+
+    pub fn get_active_uniform_block_parameter(
+        &self,
+        program: &Program,
+        location: u32,
+        pname: u32,
+    ) -> u32 {
+        self.inner
+            .get_active_uniform_block_parameter(program, location, pname)
+            .ok()
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0) as u32
+    }
+
+    pub fn get_max_vertex_uniform_vectors(&self) -> u32 {
+        self.inner
+            .get_parameter(consts::MAX_VERTEX_UNIFORM_VECTORS)
+            .ok()
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0) as u32
+    }
+
+    pub fn get_integer_parameter(&self, pname: u32) -> u32 {
+        self.inner
+            .get_parameter(pname)
+            .ok()
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0) as u32
+    }
+
+    pub(super) fn memory_tracker(&self) -> &std::cell::RefCell<super::MemoryTracker> {
+        &self.memory
+    }
+
+    pub(super) fn cached_capabilities(&self) -> Option<super::Capabilities> {
+        self.state.borrow().capabilities()
+    }
+
+    pub(super) fn cache_capabilities(&self, capabilities: super::Capabilities) {
+        self.state.borrow_mut().set_capabilities(capabilities);
+    }
+
+    pub fn get_max_uniform_buffer_bindings(&self) -> u32 {
+        self.inner
+            .get_parameter(consts::MAX_UNIFORM_BUFFER_BINDINGS)
+            .ok()
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0) as u32
+    }
+
+    ///
+    /// Returns the uniform buffer binding point assigned to uniform blocks with the given name, which is shared by all programs using this context.
+    /// The binding point is allocated the first time it is acquired and freed again when it has been [released](Self::release_uniform_block_binding)
+    /// as many times as it has been acquired. Returns `None` if all of the binding points are in use.
+    ///
+    pub fn acquire_uniform_block_binding(&self, block_name: &str) -> Option<u32> {
+        let max_bindings = self.get_max_uniform_buffer_bindings();
+        self.state
+            .borrow_mut()
+            .acquire_uniform_block_binding(block_name, max_bindings)
+    }
+
+    ///
+    /// Releases the uniform buffer binding point of uniform blocks with the given name, see [acquire_uniform_block_binding](Self::acquire_uniform_block_binding).
+    ///
+    pub fn release_uniform_block_binding(&self, block_name: &str) {
+        self.state
+            .borrow_mut()
+            .release_uniform_block_binding(block_name)
+    }
+
     pub fn bind_buffer(&self, target: u32, buffer: &Buffer) {
         self.inner.bind_buffer(target, Some(buffer));
     }
@@ -97,8 +335,29 @@ impl Context {
             .buffer_data_with_array_buffer_view(target, &array, usage);
     }
 
+    pub fn buffer_sub_data_f32(&self, target: u32, offset: u32, data: &[f32]) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = data.as_ptr() as u32 / 4;
+        let array = js_sys::Float32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + data.len() as u32);
+
+        self.inner.buffer_sub_data_with_i32_and_array_buffer_view(
+            target,
+            byte_size_for_type(consts::FLOAT, offset) as i32,
+            &array,
+        );
+    }
+
     pub fn compile_shader(&self, source: &str, shader: &Shader) {
-        let header = "#version 300 es\nprecision highp float;\nprecision highp int;\nprecision highp sampler2DArray;\n";
+        let header = if source.trim_start().starts_with("#version") {
+            ""
+        } else {
+            "#version 300 es\nprecision highp float;\nprecision highp int;\nprecision highp sampler2DArray;\n"
+        };
         let s: &str = &[header, source].concat();
 
         self.inner.shader_source(shader, s);
@@ -191,6 +450,117 @@ impl Context {
             .unwrap();
     }
 
+    pub fn tex_sub_image_3d_with_u8_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[u8],
+    ) {
+        self.inner
+            .tex_sub_image_3d_with_opt_u8_array(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                Some(pixels),
+            )
+            .unwrap();
+    }
+
+    pub fn tex_sub_image_3d_with_f32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[f32],
+    ) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = pixels.as_ptr() as u32 / 4;
+        let array = js_sys::Float32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + pixels.len() as u32);
+
+        self.inner
+            .tex_sub_image_3d_with_opt_array_buffer_view(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                Some(&array),
+            )
+            .unwrap();
+    }
+
+    pub fn tex_sub_image_3d_with_u32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[u32],
+    ) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = pixels.as_ptr() as u32 / 4;
+        let array = js_sys::Uint32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + pixels.len() as u32);
+
+        self.inner
+            .tex_sub_image_3d_with_opt_array_buffer_view(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                Some(&array),
+            )
+            .unwrap();
+    }
+
     pub fn tex_sub_image_2d_with_u8_data(
         &self,
         target: u32,
@@ -704,6 +1074,54 @@ impl Context {
     pub fn delete_sync(&self, sync: &Sync) {
         self.inner.delete_sync(Some(sync));
     }
+
+    pub fn supports_timer_queries(&self) -> bool {
+        // Timer queries are only available with the EXT_disjoint_timer_query_webgl2 extension, which has to be enabled before use
+        self.inner
+            .get_extension("EXT_disjoint_timer_query_webgl2")
+            .ok()
+            .flatten()
+            .is_some()
+    }
+
+    pub fn create_query(&self) -> Option<Query> {
+        self.inner.create_query()
+    }
+
+    pub fn delete_query(&self, query: &Query) {
+        self.inner.delete_query(Some(query));
+    }
+
+    pub fn begin_query(&self, target: u32, query: &Query) {
+        self.inner.begin_query(target, query);
+    }
+
+    pub fn end_query(&self, target: u32) {
+        self.inner.end_query(target);
+    }
+
+    pub fn get_query_result_available(&self, query: &Query) -> bool {
+        self.inner
+            .get_query_parameter(query, consts::QUERY_RESULT_AVAILABLE)
+            .as_bool()
+            .unwrap_or(false)
+    }
+
+    pub fn get_gpu_disjoint(&self) -> bool {
+        // GPU_DISJOINT_EXT from the EXT_disjoint_timer_query_webgl2 extension, which is reset when queried
+        self.inner
+            .get_parameter(0x8FBB)
+            .ok()
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    }
+
+    pub fn get_query_result(&self, query: &Query) -> u64 {
+        self.inner
+            .get_query_parameter(query, consts::QUERY_RESULT)
+            .as_f64()
+            .unwrap_or(0.0) as u64
+    }
 }
 
 impl std::ops::Deref for Context {
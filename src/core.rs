@@ -5,6 +5,7 @@
 //!
 
 pub use crate::context::Context;
+pub use crate::error::Error;
 
 mod render_states;
 #[doc(inline)]
@@ -14,6 +15,14 @@ mod texture2d;
 #[doc(inline)]
 pub use texture2d::*;
 
+mod texture_cache;
+#[doc(inline)]
+pub use texture_cache::*;
+
+mod texture2d_array;
+#[doc(inline)]
+pub use texture2d_array::*;
+
 mod texture_cube_map;
 #[doc(inline)]
 pub use texture_cube_map::*;
@@ -46,6 +55,10 @@ mod uniform_buffer;
 #[doc(inline)]
 pub use uniform_buffer::*;
 
+mod frame_globals;
+#[doc(inline)]
+pub use frame_globals::*;
+
 mod render_target;
 #[doc(inline)]
 pub use render_target::*;
@@ -54,42 +67,37 @@ mod program;
 #[doc(inline)]
 pub use program::*;
 
-///
-/// Error in some part of the render engine.
-///
-#[derive(Debug)]
-pub enum Error {
-    /// An error in a shader program.
-    ProgramError {
-        /// Error message
-        message: String,
-    },
-    /// An error when using a render target.
-    RenderTargetError {
-        /// Error message
-        message: String,
-    },
-    /// An error when using a texture.
-    TextureError {
-        /// Error message
-        message: String,
-    },
-    /// An error when using a buffer.
-    BufferError {
-        /// Error message
-        message: String,
-    },
-    /// An error when using a mesh.
-    MeshError {
-        /// Error message
-        message: String,
-    },
-    /// An error when using a camera.
-    CameraError {
-        /// Error message
-        message: String,
-    },
-}
+mod graphics_backend;
+#[doc(inline)]
+pub use graphics_backend::*;
+
+mod shader_preprocessor;
+#[doc(inline)]
+pub use shader_preprocessor::*;
+
+mod screen_quad;
+#[doc(inline)]
+pub use screen_quad::*;
+
+mod render_target_pool;
+#[doc(inline)]
+pub use render_target_pool::*;
+
+mod resource_registry;
+#[doc(inline)]
+pub use resource_registry::*;
+
+mod command_list;
+#[doc(inline)]
+pub use command_list::*;
+
+mod render_graph;
+#[doc(inline)]
+pub use render_graph::*;
+
+mod profiler;
+#[doc(inline)]
+pub use profiler::*;
 
 pub trait VertexBufferDataType:
     Default + std::fmt::Debug + Clone + internal::BufferDataTypeExtension
@@ -176,6 +184,15 @@ pub(crate) mod internal {
             format: Format,
             data: &[Self],
         );
+        fn fill_layer(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            layer: u32,
+            format: Format,
+            data: &[Self],
+        );
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]);
     }
 
@@ -212,6 +229,30 @@ pub(crate) mod internal {
             );
         }
 
+        fn fill_layer(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            layer: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_u8_data(
+                target,
+                0,
+                0,
+                0,
+                layer,
+                width,
+                height,
+                1,
+                format_from(format),
+                consts::UNSIGNED_BYTE,
+                data,
+            );
+        }
+
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
             context.read_pixels_with_u8_data(
                 viewport.x as u32,
@@ -262,6 +303,30 @@ pub(crate) mod internal {
             );
         }
 
+        fn fill_layer(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            layer: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_f32_data(
+                target,
+                0,
+                0,
+                0,
+                layer,
+                width,
+                height,
+                1,
+                format_from(format),
+                consts::FLOAT,
+                data,
+            );
+        }
+
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
             context.read_pixels_with_f32_data(
                 viewport.x as u32,
@@ -275,6 +340,70 @@ pub(crate) mod internal {
         }
     }
 
+    impl TextureDataTypeExtension for u16 {
+        fn internal_format(format: Format) -> Result<u32, crate::Error> {
+            // 16 bit normalized formats are not available in OpenGL ES/WebGL, so the data is stored as half floats in the [0..1] range.
+            Ok(match format {
+                Format::R => crate::context::consts::R16F,
+                Format::RG => crate::context::consts::RG16F,
+                Format::RGB => crate::context::consts::RGB16F,
+                Format::RGBA => crate::context::consts::RGBA16F,
+                _ => {
+                    return Err(crate::Error::TextureError {
+                        message:
+                            "Cannot only use the sRGB(A) format together with a u8 texture value type."
+                                .to_string(),
+                    });
+                }
+            })
+        }
+
+        fn fill(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            f32::fill(context, target, width, height, format, &normalize(data));
+        }
+
+        fn fill_layer(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            layer: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            f32::fill_layer(
+                context,
+                target,
+                width,
+                height,
+                layer,
+                format,
+                &normalize(data),
+            );
+        }
+
+        fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
+            let mut values = vec![0.0; pixels.len()];
+            f32::read(context, viewport, format, &mut values);
+            for (pixel, value) in pixels.iter_mut().zip(values) {
+                *pixel = (value.max(0.0).min(1.0) * std::u16::MAX as f32).round() as u16;
+            }
+        }
+    }
+
+    fn normalize(data: &[u16]) -> Vec<f32> {
+        data.iter()
+            .map(|v| *v as f32 / std::u16::MAX as f32)
+            .collect()
+    }
+
     impl TextureDataTypeExtension for u32 {
         fn internal_format(format: Format) -> Result<u32, crate::Error> {
             Ok(match format {
@@ -312,6 +441,30 @@ pub(crate) mod internal {
                 data,
             );
         }
+
+        fn fill_layer(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            layer: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_u32_data(
+                target,
+                0,
+                0,
+                0,
+                layer,
+                width,
+                height,
+                1,
+                format_from(format),
+                consts::UNSIGNED_INT,
+                data,
+            );
+        }
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
             context.read_pixels_with_u32_data(
                 viewport.x as u32,
@@ -338,6 +491,7 @@ pub(crate) mod internal {
 }
 
 use crate::context::consts;
+use crate::definition::{CPUTexture, NonPowerOfTwo, ResizeFilter, TextureDataType};
 
 pub use crate::{Format, Interpolation, Wrapping};
 
@@ -452,6 +606,29 @@ fn set_parameters(
     }
 }
 
+// Estimates the memory used by a texture with the given size, number of layers and mip levels
+fn texture_memory(
+    width: u32,
+    height: u32,
+    depth: u32,
+    number_of_mip_maps: u32,
+    bytes_per_texel: u64,
+) -> u64 {
+    let texels: u64 = (0..number_of_mip_maps)
+        .map(|level| (width >> level).max(1) as u64 * (height >> level).max(1) as u64)
+        .sum();
+    texels * depth as u64 * bytes_per_texel
+}
+
+fn depth_texel_size(format: DepthFormat) -> u64 {
+    match format {
+        DepthFormat::Depth16 => 2,
+        // 24 bit depth is usually stored in 32 bits
+        DepthFormat::Depth24 => 4,
+        DepthFormat::Depth32F => 4,
+    }
+}
+
 fn calculate_number_of_mip_maps(
     mip_map_filter: Option<Interpolation>,
     width: u32,
@@ -468,7 +645,7 @@ fn calculate_number_of_mip_maps(
     }
 }
 
-fn check_data_length(
+pub(crate) fn check_data_length(
     width: u32,
     height: u32,
     depth: u32,
@@ -489,6 +666,100 @@ fn check_data_length(
     Ok(())
 }
 
+fn is_non_power_of_two_restricted(context: &Context, width: u32, height: u32) -> bool {
+    !context.capabilities().non_power_of_two_textures
+        && !(width.is_power_of_two() && height.is_power_of_two())
+}
+
+// Returns the mip map filter and wrapping (s, t and r) to use for the given texture, which are restricted if needed, see NonPowerOfTwo.
+// Textures which should be resized but are not, for example texture arrays, are restricted instead.
+fn sampling_parameters<T: TextureDataType>(
+    context: &Context,
+    cpu_texture: &CPUTexture<T>,
+) -> (Option<Interpolation>, Wrapping, Wrapping, Wrapping) {
+    if cpu_texture.non_power_of_two != NonPowerOfTwo::Allow
+        && is_non_power_of_two_restricted(context, cpu_texture.width, cpu_texture.height)
+    {
+        (
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+    } else {
+        (
+            cpu_texture.mip_map_filter,
+            cpu_texture.wrap_s,
+            cpu_texture.wrap_t,
+            cpu_texture.wrap_r,
+        )
+    }
+}
+
+// Returns a copy of the given texture resized to the nearest power of two size if needed, see NonPowerOfTwo
+fn resize_to_power_of_two<T: TextureDataType>(
+    context: &Context,
+    cpu_texture: &CPUTexture<T>,
+) -> Option<CPUTexture<T>> {
+    if cpu_texture.non_power_of_two == NonPowerOfTwo::Resize
+        && is_non_power_of_two_restricted(context, cpu_texture.width, cpu_texture.height)
+    {
+        let nearest = |size: u32| {
+            let above = size.next_power_of_two();
+            let below = above / 2;
+            if below > 0 && size - below < above - size {
+                below
+            } else {
+                above
+            }
+        };
+        let (width, height) = (nearest(cpu_texture.width), nearest(cpu_texture.height));
+        log::warn!(
+            "Resizing a texture from {}x{} to {}x{} since textures with a size which is not a power of two are restricted on this platform",
+            cpu_texture.width,
+            cpu_texture.height,
+            width,
+            height
+        );
+        Some(cpu_texture.resize(width, height, ResizeFilter::Bilinear))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn check_texture_size(width: u32, height: u32, max_size: u32) -> Result<(), Error> {
+    if width == 0 || height == 0 {
+        Err(Error::TextureError {
+            message: format!(
+                "The texture size {}x{} is empty, both the width and height must be at least one",
+                width, height
+            ),
+        })?;
+    }
+    // A limit of zero means that it could not be queried, in which case creation is left to the graphics API
+    if max_size > 0 && (width > max_size || height > max_size) {
+        Err(Error::TextureError {
+            message: format!(
+                "The texture size {}x{} exceeds the maximum size {}x{} supported by this device, downscale it first, for example using CPUTexture::resize_to_max_size",
+                width, height, max_size, max_size
+            ),
+        })?;
+    }
+    Ok(())
+}
+
+fn check_texture_layers(depth: u32, max_layers: u32) -> Result<(), Error> {
+    if max_layers > 0 && depth > max_layers {
+        Err(Error::TextureError {
+            message: format!(
+                "The texture array has {} layers which exceeds the maximum of {} layers supported by this device",
+                depth, max_layers
+            ),
+        })?;
+    }
+    Ok(())
+}
+
 fn internal_format_from_depth(format: DepthFormat) -> u32 {
     match format {
         DepthFormat::Depth16 => consts::DEPTH_COMPONENT16,
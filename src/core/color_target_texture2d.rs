@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::*;
 use crate::definition::*;
 use crate::math::*;
@@ -12,6 +12,7 @@ use crate::math::*;
 pub struct ColorTargetTexture2D<T: TextureDataType> {
     context: Context,
     id: crate::context::Texture,
+    _allocation: Allocation,
     width: u32,
     height: u32,
     number_of_mip_maps: u32,
@@ -34,6 +35,7 @@ impl<T: TextureDataType> ColorTargetTexture2D<T> {
         wrap_t: Wrapping,
         format: Format,
     ) -> Result<Self, Error> {
+        check_texture_size(width, height, context.capabilities().max_texture_size)?;
         let id = generate(context)?;
         let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height, 1);
         set_parameters(
@@ -61,6 +63,17 @@ impl<T: TextureDataType> ColorTargetTexture2D<T> {
         Ok(Self {
             context: context.clone(),
             id,
+            _allocation: Allocation::new(
+                context,
+                ResourceType::RenderTargetTexture,
+                texture_memory(
+                    width,
+                    height,
+                    1,
+                    number_of_mip_maps,
+                    format.color_channel_count() as u64 * std::mem::size_of::<T>() as u64,
+                ),
+            ),
             width,
             height,
             number_of_mip_maps,
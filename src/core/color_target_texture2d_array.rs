@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::*;
 use crate::definition::*;
 use crate::math::*;
@@ -12,6 +12,7 @@ use crate::math::*;
 pub struct ColorTargetTexture2DArray<T: TextureDataType> {
     context: Context,
     id: crate::context::Texture,
+    _allocation: Allocation,
     width: u32,
     height: u32,
     depth: u32,
@@ -32,6 +33,9 @@ impl<T: TextureDataType> ColorTargetTexture2DArray<T> {
         wrap_t: Wrapping,
         format: Format,
     ) -> Result<Self, Error> {
+        let capabilities = context.capabilities();
+        check_texture_size(width, height, capabilities.max_texture_size)?;
+        check_texture_layers(depth, capabilities.max_array_texture_layers)?;
         let id = generate(context)?;
         let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height, depth);
         set_parameters(
@@ -61,6 +65,17 @@ impl<T: TextureDataType> ColorTargetTexture2DArray<T> {
         Ok(Self {
             context: context.clone(),
             id,
+            _allocation: Allocation::new(
+                context,
+                ResourceType::RenderTargetTexture,
+                texture_memory(
+                    width,
+                    height,
+                    depth,
+                    number_of_mip_maps,
+                    format.color_channel_count() as u64 * std::mem::size_of::<T>() as u64,
+                ),
+            ),
             width,
             height,
             depth,
@@ -0,0 +1,251 @@
+use crate::core::*;
+use crate::math::*;
+
+///
+/// A value of a uniform variable recorded in a [CommandList].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UniformValue {
+    Int(i32),
+    Float(f32),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Mat3(Mat3),
+    Mat4(Mat4),
+}
+
+///
+/// A command recorded in a [CommandList]. The resources are referred to by their [handles](Handle) in a [ResourceRegistry].
+///
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Uses the given program for the following commands.
+    UseProgram(ProgramHandle),
+    /// Sets the uniform variable with the given name in the current program.
+    Uniform { name: String, value: UniformValue },
+    /// Uses the given texture for the sampler with the given name in the current program.
+    Texture {
+        name: String,
+        texture: TextureHandle,
+    },
+    /// Uses the given vertex buffer for the attribute with the given name in the current program.
+    /// The size is the number of values per vertex, ie. 1, 2, 3 or 4.
+    Attribute {
+        name: String,
+        buffer: VertexBufferHandle,
+        size: u32,
+    },
+    /// Draws the given number of vertices using the current program.
+    DrawArrays {
+        render_states: RenderStates,
+        primitive_type: PrimitiveType,
+        count: u32,
+    },
+    /// Draws the triangles given by the indices in the given element buffer using the current program.
+    DrawElements {
+        render_states: RenderStates,
+        primitive_type: PrimitiveType,
+        elements: ElementBufferHandle,
+    },
+}
+
+///
+/// A list of recorded draw commands which can be recorded on any thread, since it only refers to resources by their [handles](Handle),
+/// and then sent to and [executed](CommandList::execute) on the thread owning the graphics context.
+/// This separates the scene traversal from the submission of the graphics API calls.
+///
+#[derive(Debug, Clone, Default)]
+pub struct CommandList {
+    commands: Vec<Command>,
+}
+
+impl CommandList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Records the given command.
+    ///
+    pub fn push(&mut self, command: Command) {
+        self.commands.push(command);
+    }
+
+    ///
+    /// Records that the given program is used for the following commands.
+    ///
+    pub fn use_program(&mut self, program: ProgramHandle) {
+        self.push(Command::UseProgram(program));
+    }
+
+    ///
+    /// Records setting the uniform variable with the given name in the current program.
+    ///
+    pub fn uniform(&mut self, name: &str, value: UniformValue) {
+        self.push(Command::Uniform {
+            name: name.to_string(),
+            value,
+        });
+    }
+
+    ///
+    /// Records using the given texture for the sampler with the given name in the current program.
+    ///
+    pub fn texture(&mut self, name: &str, texture: TextureHandle) {
+        self.push(Command::Texture {
+            name: name.to_string(),
+            texture,
+        });
+    }
+
+    ///
+    /// Records using the given vertex buffer, with the given number of values per vertex, for the attribute with the given name in the current program.
+    ///
+    pub fn attribute(&mut self, name: &str, buffer: VertexBufferHandle, size: u32) {
+        self.push(Command::Attribute {
+            name: name.to_string(),
+            buffer,
+            size,
+        });
+    }
+
+    ///
+    /// Records drawing the given number of vertices as triangles using the current program.
+    ///
+    pub fn draw_arrays(&mut self, render_states: RenderStates, count: u32) {
+        self.push(Command::DrawArrays {
+            render_states,
+            primitive_type: PrimitiveType::Triangles,
+            count,
+        });
+    }
+
+    ///
+    /// Records drawing the triangles given by the indices in the given element buffer using the current program.
+    ///
+    pub fn draw_elements(&mut self, render_states: RenderStates, elements: ElementBufferHandle) {
+        self.push(Command::DrawElements {
+            render_states,
+            primitive_type: PrimitiveType::Triangles,
+            elements,
+        });
+    }
+
+    ///
+    /// Appends all of the commands in the other list to this list, for example when the commands are recorded on several threads.
+    ///
+    pub fn append(&mut self, other: &mut CommandList) {
+        self.commands.append(&mut other.commands);
+    }
+
+    ///
+    /// Returns the recorded commands.
+    ///
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    ///
+    /// Returns whether no commands are recorded.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    ///
+    /// Removes all recorded commands.
+    ///
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    ///
+    /// Executes the recorded commands in order using the resources in the given registry.
+    /// Must be called on the thread owning the graphics context, in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if a resource has been destroyed, if a command requires a program and no program is used
+    /// or if a uniform or attribute does not exist in the program.
+    ///
+    pub fn execute(&self, registry: &ResourceRegistry) -> Result<(), Error> {
+        let missing = |resource: &str| Error::ProgramError {
+            message: format!(
+                "The {} used in the command list has been destroyed",
+                resource
+            ),
+        };
+        let mut program: Option<&Program> = None;
+        for command in self.commands.iter() {
+            if let Command::UseProgram(handle) = command {
+                program = Some(
+                    registry
+                        .program(*handle)
+                        .ok_or_else(|| missing("program"))?,
+                );
+                continue;
+            }
+            let program = program.ok_or_else(|| Error::ProgramError {
+                message: format!("No program is used before the command {:?}", command),
+            })?;
+            match command {
+                Command::UseProgram(_) => unreachable!(),
+                Command::Uniform { name, value } => match value {
+                    UniformValue::Int(v) => program.use_uniform_int(name, v)?,
+                    UniformValue::Float(v) => program.use_uniform_float(name, v)?,
+                    UniformValue::Vec2(v) => program.use_uniform_vec2(name, v)?,
+                    UniformValue::Vec3(v) => program.use_uniform_vec3(name, v)?,
+                    UniformValue::Vec4(v) => program.use_uniform_vec4(name, v)?,
+                    UniformValue::Mat3(v) => program.use_uniform_mat3(name, v)?,
+                    UniformValue::Mat4(v) => program.use_uniform_mat4(name, v)?,
+                },
+                Command::Texture { name, texture } => {
+                    let texture = registry
+                        .texture(*texture)
+                        .ok_or_else(|| missing("texture"))?;
+                    program.use_texture(texture, name)?;
+                }
+                Command::Attribute { name, buffer, size } => {
+                    let buffer = registry
+                        .vertex_buffer(*buffer)
+                        .ok_or_else(|| missing("vertex buffer"))?;
+                    match size {
+                        1 => program.use_attribute(buffer, name)?,
+                        2 => program.use_attribute_vec2(buffer, name)?,
+                        3 => program.use_attribute_vec3(buffer, name)?,
+                        4 => program.use_attribute_vec4(buffer, name)?,
+                        _ => Err(Error::ProgramError {
+                            message: format!(
+                                "The attribute {} has {} values per vertex, but only 1 to 4 is supported",
+                                name, size
+                            ),
+                        })?,
+                    }
+                }
+                Command::DrawArrays {
+                    render_states,
+                    primitive_type,
+                    count,
+                } => {
+                    program.draw_arrays_with_primitive_type(*render_states, *primitive_type, *count)
+                }
+                Command::DrawElements {
+                    render_states,
+                    primitive_type,
+                    elements,
+                } => {
+                    let elements = registry
+                        .element_buffer(*elements)
+                        .ok_or_else(|| missing("element buffer"))?;
+                    program.draw_elements_with_primitive_type(
+                        *render_states,
+                        *primitive_type,
+                        elements,
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::*;
 use crate::definition::*;
 use crate::math::*;
@@ -20,6 +20,7 @@ pub enum DepthFormat {
 pub struct DepthTargetTexture2D {
     context: Context,
     id: crate::context::Texture,
+    _allocation: Allocation,
     width: u32,
     height: u32,
 }
@@ -36,6 +37,7 @@ impl DepthTargetTexture2D {
         wrap_t: Wrapping,
         format: DepthFormat,
     ) -> Result<Self, Error> {
+        check_texture_size(width, height, context.capabilities().max_texture_size)?;
         let id = generate(context)?;
         set_parameters(
             context,
@@ -58,6 +60,11 @@ impl DepthTargetTexture2D {
         Ok(Self {
             context: context.clone(),
             id,
+            _allocation: Allocation::new(
+                context,
+                ResourceType::RenderTargetTexture,
+                texture_memory(width, height, 1, 1, depth_texel_size(format)),
+            ),
             width,
             height,
         })
@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::*;
 use crate::definition::*;
 use crate::math::*;
@@ -9,6 +9,7 @@ use crate::math::*;
 pub struct DepthTargetTexture2DArray {
     context: Context,
     id: crate::context::Texture,
+    _allocation: Allocation,
     width: u32,
     height: u32,
     depth: u32,
@@ -24,6 +25,9 @@ impl DepthTargetTexture2DArray {
         wrap_t: Wrapping,
         format: DepthFormat,
     ) -> Result<Self, Error> {
+        let capabilities = context.capabilities();
+        check_texture_size(width, height, capabilities.max_texture_size)?;
+        check_texture_layers(depth, capabilities.max_array_texture_layers)?;
         let id = generate(context)?;
         set_parameters(
             context,
@@ -48,6 +52,11 @@ impl DepthTargetTexture2DArray {
         Ok(Self {
             context: context.clone(),
             id,
+            _allocation: Allocation::new(
+                context,
+                ResourceType::RenderTargetTexture,
+                texture_memory(width, height, depth, 1, depth_texel_size(format)),
+            ),
             width,
             height,
             depth,
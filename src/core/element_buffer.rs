@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::{ElementBufferDataType, Error};
 
 ///
@@ -8,6 +8,7 @@ use crate::core::{ElementBufferDataType, Error};
 pub struct ElementBuffer {
     context: Context,
     id: crate::context::Buffer,
+    allocation: Allocation,
     count: usize,
     data_type: u32,
 }
@@ -24,6 +25,7 @@ impl ElementBuffer {
         let mut buffer = ElementBuffer {
             context: context.clone(),
             id,
+            allocation: Allocation::new(context, ResourceType::ElementBuffer, 0),
             count: 0,
             data_type: T::data_type(),
         };
@@ -47,6 +49,8 @@ impl ElementBuffer {
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
         self.count = data.len();
+        self.allocation
+            .resize((data.len() * std::mem::size_of::<T>()) as u64);
     }
 
     ///
@@ -0,0 +1,75 @@
+use crate::context::consts;
+use crate::core::*;
+use crate::frame::FrameInput;
+
+///
+/// A uniform buffer with per-frame global values which is automatically available to all [programs](Program) and [effects](crate::ImageEffect),
+/// so procedural and animated shaders do not need to set the time or resolution as uniforms.
+/// Declare the following in the shader code to use it:
+///
+/// ```ignore
+/// layout (std140) uniform Globals
+/// {
+///     float time; // Seconds since start
+///     float deltaTime; // Seconds since last frame
+///     float frameIndex;
+///     float padding;
+///     vec2 resolution; // Size of the window in physical pixels
+/// } globals;
+/// ```
+///
+/// The windows in this crate update the globals before each call to the render loop callback.
+/// When using a [RawWindow](crate::RawWindow) or a headless context, create the globals once and call [update](Self::update) each frame.
+///
+pub struct FrameGlobals {
+    context: Context,
+    buffer: UniformBuffer,
+    binding: u32,
+}
+
+impl FrameGlobals {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        let buffer = UniformBuffer::new(context, &[1, 1, 1, 1, 2, 2])?;
+        let binding = context
+            .acquire_uniform_block_binding("Globals")
+            .ok_or_else(|| Error::BufferError {
+                message: format!(
+                    "Cannot bind the frame globals since all of the {} uniform buffer binding points are in use",
+                    context.get_max_uniform_buffer_bindings()
+                ),
+            })?;
+        Ok(Self {
+            context: context.clone(),
+            buffer,
+            binding,
+        })
+    }
+
+    ///
+    /// Updates the globals from the given frame input and binds them so they are available to all programs using the `Globals` uniform block.
+    ///
+    pub fn update(&mut self, frame_input: &FrameInput) -> Result<(), Error> {
+        self.buffer
+            .set(0, &[(0.001 * frame_input.accumulated_time) as f32])?;
+        self.buffer
+            .set(1, &[(0.001 * frame_input.elapsed_time) as f32])?;
+        self.buffer.set(2, &[frame_input.frame_index as f32])?;
+        self.buffer.set(
+            4,
+            &[
+                frame_input.viewport.width as f32,
+                frame_input.viewport.height as f32,
+            ],
+        )?;
+        self.buffer.send();
+        self.buffer.bind(self.binding);
+        self.context.unbind_buffer(consts::UNIFORM_BUFFER);
+        Ok(())
+    }
+}
+
+impl Drop for FrameGlobals {
+    fn drop(&mut self) {
+        self.context.release_uniform_block_binding("Globals");
+    }
+}
@@ -0,0 +1,93 @@
+use crate::context::Backend;
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// The resources which are provided by each graphics backend, ie. by the OpenGL/WebGL [Context] and,
+/// if the `wgpu-backend` feature is enabled, by the [WgpuContext](crate::WgpuContext).
+/// Code which only creates these resources can be written once for both backends,
+/// while using the resources is done with the methods of the concrete types, which are the same for both backends except for the draw calls.
+///
+pub trait GraphicsBackend {
+    /// A buffer containing per vertex data, see [VertexBuffer].
+    type VertexBuffer;
+    /// A buffer containing indices, see [ElementBuffer].
+    type ElementBuffer;
+    /// A buffer containing a set of uniform variables, see [UniformBuffer].
+    type UniformBuffer;
+    /// A 2D texture, see [Texture2D].
+    type Texture2D;
+    /// A shader program, see [Program].
+    type Program;
+
+    ///
+    /// Returns the graphics API which this backend maps to.
+    ///
+    fn backend(&self) -> Backend;
+
+    ///
+    /// Creates a new vertex buffer filled with the given data.
+    ///
+    fn new_vertex_buffer(&self, data: &[f32]) -> Result<Self::VertexBuffer, Error>;
+
+    ///
+    /// Creates a new element buffer filled with the given indices.
+    ///
+    fn new_element_buffer(&self, data: &[u32]) -> Result<Self::ElementBuffer, Error>;
+
+    ///
+    /// Creates a new uniform buffer with the given sizes, see [UniformBuffer::new].
+    ///
+    fn new_uniform_buffer(&self, sizes: &[u32]) -> Result<Self::UniformBuffer, Error>;
+
+    ///
+    /// Creates a new texture with the given data.
+    ///
+    fn new_texture(&self, cpu_texture: &CPUTexture<u8>) -> Result<Self::Texture2D, Error>;
+
+    ///
+    /// Creates a new shader program from the given vertex and fragment glsl shader source.
+    /// The source is the same for all backends, see [Program::from_source].
+    ///
+    fn new_program(
+        &self,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> Result<Self::Program, Error>;
+}
+
+impl GraphicsBackend for Context {
+    type VertexBuffer = VertexBuffer;
+    type ElementBuffer = ElementBuffer;
+    type UniformBuffer = UniformBuffer;
+    type Texture2D = Texture2D;
+    type Program = Program;
+
+    fn backend(&self) -> Backend {
+        Context::backend(self)
+    }
+
+    fn new_vertex_buffer(&self, data: &[f32]) -> Result<VertexBuffer, Error> {
+        VertexBuffer::new_with_static(self, data)
+    }
+
+    fn new_element_buffer(&self, data: &[u32]) -> Result<ElementBuffer, Error> {
+        ElementBuffer::new(self, data)
+    }
+
+    fn new_uniform_buffer(&self, sizes: &[u32]) -> Result<UniformBuffer, Error> {
+        UniformBuffer::new(self, sizes)
+    }
+
+    fn new_texture(&self, cpu_texture: &CPUTexture<u8>) -> Result<Texture2D, Error> {
+        Texture2D::new(self, cpu_texture)
+    }
+
+    fn new_program(
+        &self,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> Result<Program, Error> {
+        Program::from_source(self, vertex_shader_source, fragment_shader_source)
+    }
+}
@@ -0,0 +1,590 @@
+use crate::context::Query;
+use crate::core::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+
+// The same value as TIME_ELAPSED_EXT in the EXT_disjoint_timer_query_webgl2 extension
+const TIME_ELAPSED: u32 = 0x88BF;
+
+// The maximum number of frames waiting for the results of the timer queries before the oldest frame is discarded
+const MAX_PENDING_FRAMES: usize = 8;
+
+thread_local! {
+    static ACTIVE_PROFILER: RefCell<Weak<RefCell<ProfilerState>>> = RefCell::new(Weak::new());
+}
+
+///
+/// Starts a named profiling scope which ends at the end of the enclosing block,
+/// for example `profile_scope!("shadow pass");` at the start of a function.
+/// Scopes can be nested and are recorded by the [Profiler] which is currently recording a frame on this thread, if any,
+/// otherwise the scope does nothing.
+///
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _profile_scope = $crate::ProfileScopeGuard::new($name);
+    };
+}
+
+///
+/// The time spent in a profiling scope in one frame, see [Profiler].
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileScope {
+    /// The name of the scope.
+    pub name: String,
+    /// The CPU time in nanoseconds from the start of the frame to the start of the scope.
+    pub start: u64,
+    /// The CPU time in nanoseconds spent in the scope, including the nested scopes.
+    pub cpu_time: u64,
+    /// The GPU time in nanoseconds spent executing the commands issued in the scope, including the nested scopes,
+    /// or `None` if timer queries are not supported or the timing is undefined because of a disjoint operation on the GPU, for example a change of the GPU clock.
+    pub gpu_time: Option<u64>,
+    /// The scopes nested in this scope in the order they started.
+    pub children: Vec<ProfileScope>,
+}
+
+impl ProfileScope {
+    fn fmt_indented(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        write!(
+            f,
+            "{:indent$}{}: cpu {:.3} ms",
+            "",
+            self.name,
+            self.cpu_time as f64 * 1e-6,
+            indent = indent
+        )?;
+        if let Some(gpu_time) = self.gpu_time {
+            write!(f, ", gpu {:.3} ms", gpu_time as f64 * 1e-6)?;
+        }
+        writeln!(f)?;
+        for child in self.children.iter() {
+            child.fmt_indented(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// The profiling scopes recorded in one frame as a tree, see [Profiler].
+/// Implements [Display](std::fmt::Display) which writes the tree with one scope on each line, for example for showing it in a GUI.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileFrame {
+    /// The number of the frame counted from the first frame recorded by the profiler.
+    pub frame_index: u64,
+    /// The CPU time in nanoseconds from the creation of the profiler to the start of the frame.
+    pub start: u64,
+    /// The CPU time in nanoseconds from the start to the end of the frame.
+    pub cpu_time: u64,
+    /// The GPU time in nanoseconds spent executing the commands issued during the frame, or `None` if timer queries are not supported
+    /// or the timing is undefined because of a disjoint operation on the GPU, which is only reported on web.
+    pub gpu_time: Option<u64>,
+    /// The outermost scopes in the order they started.
+    pub scopes: Vec<ProfileScope>,
+}
+
+impl ProfileFrame {
+    ///
+    /// Returns the first scope with the given path of names from an outermost scope, for example `&["render", "shadow pass"]`.
+    ///
+    pub fn find(&self, path: &[&str]) -> Option<&ProfileScope> {
+        let (first, rest) = path.split_first()?;
+        let mut scope = self.scopes.iter().find(|s| s.name == *first)?;
+        for name in rest {
+            scope = scope.children.iter().find(|s| s.name == *name)?;
+        }
+        Some(scope)
+    }
+}
+
+impl std::fmt::Display for ProfileFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "frame {}: cpu {:.3} ms",
+            self.frame_index,
+            self.cpu_time as f64 * 1e-6
+        )?;
+        if let Some(gpu_time) = self.gpu_time {
+            write!(f, ", gpu {:.3} ms", gpu_time as f64 * 1e-6)?;
+        }
+        writeln!(f)?;
+        for scope in self.scopes.iter() {
+            scope.fmt_indented(f, 2)?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Records the CPU and GPU time spent in nested, named scopes each frame, see [profile_scope](crate::profile_scope).
+/// The GPU time is measured using timer queries, which are available on desktop and
+/// on web when the `EXT_disjoint_timer_query_webgl2` extension is supported.
+/// Since the GPU runs behind the CPU, a frame is available a few frames after it is recorded.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// let profiler = Profiler::new(&context);
+/// // Each frame:
+/// profiler.begin_frame();
+/// {
+///     profile_scope!("shadow pass");
+///     // Render the shadow maps ...
+/// }
+/// profiler.end_frame();
+/// if let Some(frame) = profiler.last_frame() {
+///     println!("{}", frame);
+/// }
+/// // When done profiling:
+/// std::fs::write("trace.json", profiler.to_chrome_tracing()).unwrap();
+/// ```
+///
+#[derive(Clone)]
+pub struct Profiler {
+    state: Rc<RefCell<ProfilerState>>,
+}
+
+impl Profiler {
+    ///
+    /// Creates a new profiler which keeps the last 300 recorded frames.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self::new_with_history(context, 300)
+    }
+
+    ///
+    /// Creates a new profiler which keeps the given number of recorded frames.
+    ///
+    pub fn new_with_history(context: &Context, max_frame_count: usize) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(ProfilerState {
+                context: context.clone(),
+                gpu_timing: context.supports_timer_queries(),
+                #[cfg(not(target_arch = "wasm32"))]
+                start_time: std::time::Instant::now(),
+                #[cfg(target_arch = "wasm32")]
+                start_time: now(),
+                frame_index: 0,
+                recording: None,
+                stack: Vec::new(),
+                query_active: false,
+                pending: VecDeque::new(),
+                frames: VecDeque::new(),
+                max_frame_count: max_frame_count.max(1),
+                free_queries: Vec::new(),
+            })),
+        }
+    }
+
+    ///
+    /// Starts recording a frame and makes this the profiler which the [profile_scope](crate::profile_scope) macro records to on this thread.
+    /// Also collects the results of earlier frames which have become available.
+    ///
+    pub fn begin_frame(&self) {
+        let mut state = self.state.borrow_mut();
+        if state.recording.is_some() {
+            state.end_frame();
+        }
+        state.collect();
+        state.begin_frame();
+        ACTIVE_PROFILER.with(|active| *active.borrow_mut() = Rc::downgrade(&self.state));
+    }
+
+    ///
+    /// Ends recording the current frame, including the scopes which have not ended yet.
+    ///
+    pub fn end_frame(&self) {
+        self.state.borrow_mut().end_frame();
+        ACTIVE_PROFILER.with(|active| *active.borrow_mut() = Weak::new());
+    }
+
+    ///
+    /// Starts a named scope nested in the current scope. Must be followed by a call to [end_scope](Self::end_scope),
+    /// alternatively use [scope](Self::scope) or the [profile_scope](crate::profile_scope) macro which ends the scope automatically.
+    ///
+    pub fn begin_scope(&self, name: &str) {
+        self.state.borrow_mut().begin_scope(name);
+    }
+
+    ///
+    /// Ends the current scope.
+    ///
+    pub fn end_scope(&self) {
+        self.state.borrow_mut().end_scope();
+    }
+
+    ///
+    /// Starts a named scope nested in the current scope which ends when the returned guard is dropped.
+    ///
+    pub fn scope(&self, name: &str) -> ProfileScopeGuard {
+        self.begin_scope(name);
+        ProfileScopeGuard {
+            state: Some(self.state.clone()),
+        }
+    }
+
+    ///
+    /// Returns whether the GPU time is measured, ie. whether timer queries are supported.
+    ///
+    pub fn gpu_timing(&self) -> bool {
+        self.state.borrow().gpu_timing
+    }
+
+    ///
+    /// Returns the last frame for which all results are available, if any.
+    ///
+    pub fn last_frame(&self) -> Option<ProfileFrame> {
+        self.state.borrow().frames.back().cloned()
+    }
+
+    ///
+    /// Returns the recorded frames for which all results are available, from the oldest to the newest.
+    ///
+    pub fn frames(&self) -> Vec<ProfileFrame> {
+        self.state.borrow().frames.iter().cloned().collect()
+    }
+
+    ///
+    /// Forgets all recorded frames.
+    ///
+    pub fn clear(&self) {
+        self.state.borrow_mut().frames.clear();
+    }
+
+    ///
+    /// Returns the recorded frames in the [Trace Event Format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU)
+    /// which can be opened in `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+    /// Each scope is a complete event on the CPU timeline with the GPU time in milliseconds as an argument.
+    ///
+    pub fn to_chrome_tracing(&self) -> String {
+        let mut events = Vec::new();
+        for frame in self.state.borrow().frames.iter() {
+            events.push(trace_event(
+                &format!("frame {}", frame.frame_index),
+                frame.start,
+                frame.cpu_time,
+                frame.gpu_time,
+            ));
+            add_trace_events(&mut events, frame.start, &frame.scopes);
+        }
+        format!("{{\"traceEvents\":[\n{}\n]}}\n", events.join(",\n"))
+    }
+}
+
+impl Drop for ProfilerState {
+    fn drop(&mut self) {
+        if self.query_active {
+            self.context.end_query(TIME_ELAPSED);
+        }
+        let queries = self
+            .recording
+            .iter()
+            .chain(self.pending.iter())
+            .flat_map(|record| record.nodes.iter())
+            .flat_map(|node| node.queries.iter())
+            .chain(self.free_queries.iter());
+        for query in queries {
+            self.context.delete_query(query);
+        }
+    }
+}
+
+///
+/// Ends a profiling scope when dropped, see [profile_scope](crate::profile_scope) and [Profiler::scope].
+///
+pub struct ProfileScopeGuard {
+    state: Option<Rc<RefCell<ProfilerState>>>,
+}
+
+impl ProfileScopeGuard {
+    ///
+    /// Starts a named scope in the [Profiler] which is currently recording a frame on this thread, if any.
+    ///
+    pub fn new(name: &str) -> Self {
+        let state = ACTIVE_PROFILER.with(|active| active.borrow().upgrade());
+        if let Some(ref state) = state {
+            state.borrow_mut().begin_scope(name);
+        }
+        Self { state }
+    }
+}
+
+impl Drop for ProfileScopeGuard {
+    fn drop(&mut self) {
+        if let Some(ref state) = self.state {
+            state.borrow_mut().end_scope();
+        }
+    }
+}
+
+struct Node {
+    name: String,
+    start: u64,
+    end: u64,
+    queries: Vec<Query>,
+    children: Vec<usize>,
+}
+
+struct FrameRecord {
+    frame_index: u64,
+    // Whether the GPU timings are undefined because of a disjoint operation, for example a power saving change of the GPU clock
+    disjoint: bool,
+    // The first node is the frame itself
+    nodes: Vec<Node>,
+}
+
+struct ProfilerState {
+    context: Context,
+    gpu_timing: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    start_time: std::time::Instant,
+    #[cfg(target_arch = "wasm32")]
+    start_time: f64,
+    frame_index: u64,
+    recording: Option<FrameRecord>,
+    stack: Vec<usize>,
+    query_active: bool,
+    pending: VecDeque<FrameRecord>,
+    frames: VecDeque<ProfileFrame>,
+    max_frame_count: usize,
+    free_queries: Vec<Query>,
+}
+
+impl ProfilerState {
+    fn begin_frame(&mut self) {
+        let start = self.now();
+        self.recording = Some(FrameRecord {
+            frame_index: self.frame_index,
+            disjoint: false,
+            nodes: vec![Node {
+                name: String::new(),
+                start,
+                end: start,
+                queries: Vec::new(),
+                children: Vec::new(),
+            }],
+        });
+        self.frame_index += 1;
+        self.stack.clear();
+        self.stack.push(0);
+        self.begin_query(0);
+    }
+
+    fn end_frame(&mut self) {
+        while self.stack.len() > 1 {
+            self.end_scope();
+        }
+        self.stack.clear();
+        if self.query_active {
+            self.context.end_query(TIME_ELAPSED);
+            self.query_active = false;
+        }
+        let end = self.now();
+        // A disjoint operation invalidates the results of all of the timer queries which have not been read yet
+        if self.gpu_timing && self.context.get_gpu_disjoint() {
+            for record in self.recording.iter_mut().chain(self.pending.iter_mut()) {
+                record.disjoint = true;
+            }
+        }
+        if let Some(mut record) = self.recording.take() {
+            record.nodes[0].end = end;
+            self.pending.push_back(record);
+            if self.pending.len() > MAX_PENDING_FRAMES {
+                let record = self.pending.pop_front().unwrap();
+                self.release(record);
+            }
+        }
+        self.collect();
+    }
+
+    fn begin_scope(&mut self, name: &str) {
+        let parent = match self.stack.last() {
+            Some(parent) => *parent,
+            None => return,
+        };
+        let start = self.now();
+        let record = self.recording.as_mut().unwrap();
+        let index = record.nodes.len();
+        record.nodes.push(Node {
+            name: name.to_string(),
+            start,
+            end: start,
+            queries: Vec::new(),
+            children: Vec::new(),
+        });
+        record.nodes[parent].children.push(index);
+        self.stack.push(index);
+        self.begin_query(index);
+    }
+
+    fn end_scope(&mut self) {
+        // The frame itself is ended by end_frame
+        if self.stack.len() < 2 {
+            return;
+        }
+        let index = self.stack.pop().unwrap();
+        let end = self.now();
+        self.recording.as_mut().unwrap().nodes[index].end = end;
+        let parent = *self.stack.last().unwrap();
+        self.begin_query(parent);
+    }
+
+    // Timer queries cannot be nested, so the GPU time of a scope is measured in segments between the starts and ends of the nested scopes
+    fn begin_query(&mut self, node: usize) {
+        if !self.gpu_timing {
+            return;
+        }
+        if self.query_active {
+            self.context.end_query(TIME_ELAPSED);
+            self.query_active = false;
+        }
+        let query = match self
+            .free_queries
+            .pop()
+            .or_else(|| self.context.create_query())
+        {
+            Some(query) => query,
+            None => return,
+        };
+        self.context.begin_query(TIME_ELAPSED, &query);
+        self.query_active = true;
+        self.recording.as_mut().unwrap().nodes[node]
+            .queries
+            .push(query);
+    }
+
+    fn collect(&mut self) {
+        while let Some(record) = self.pending.front() {
+            let available = record
+                .nodes
+                .iter()
+                .flat_map(|node| node.queries.iter())
+                .all(|query| self.context.get_query_result_available(query));
+            if !available {
+                break;
+            }
+            let record = self.pending.pop_front().unwrap();
+            let frame = self.resolve(&record);
+            self.release(record);
+            if self.frames.len() == self.max_frame_count {
+                self.frames.pop_front();
+            }
+            self.frames.push_back(frame);
+        }
+    }
+
+    fn resolve(&self, record: &FrameRecord) -> ProfileFrame {
+        let root = &record.nodes[0];
+        let scopes: Vec<ProfileScope> = root
+            .children
+            .iter()
+            .map(|child| self.resolve_scope(record, *child, root.start))
+            .collect();
+        let gpu_time = self.gpu_time(record, &root.queries, &scopes);
+        ProfileFrame {
+            frame_index: record.frame_index,
+            start: root.start,
+            cpu_time: root.end - root.start,
+            gpu_time,
+            scopes,
+        }
+    }
+
+    fn resolve_scope(&self, record: &FrameRecord, index: usize, frame_start: u64) -> ProfileScope {
+        let node = &record.nodes[index];
+        let children: Vec<ProfileScope> = node
+            .children
+            .iter()
+            .map(|child| self.resolve_scope(record, *child, frame_start))
+            .collect();
+        ProfileScope {
+            name: node.name.clone(),
+            start: node.start - frame_start,
+            cpu_time: node.end - node.start,
+            gpu_time: self.gpu_time(record, &node.queries, &children),
+            children,
+        }
+    }
+
+    fn gpu_time(
+        &self,
+        record: &FrameRecord,
+        queries: &[Query],
+        children: &[ProfileScope],
+    ) -> Option<u64> {
+        if !self.gpu_timing || record.disjoint {
+            return None;
+        }
+        let own: u64 = queries
+            .iter()
+            .map(|query| self.context.get_query_result(query))
+            .sum();
+        Some(
+            own + children
+                .iter()
+                .filter_map(|child| child.gpu_time)
+                .sum::<u64>(),
+        )
+    }
+
+    fn release(&mut self, record: FrameRecord) {
+        for node in record.nodes {
+            self.free_queries.extend(node.queries);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn now(&self) -> u64 {
+        self.start_time.elapsed().as_nanos() as u64
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn now(&self) -> u64 {
+        ((now() - self.start_time) * 1e6) as u64
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+fn add_trace_events(events: &mut Vec<String>, frame_start: u64, scopes: &[ProfileScope]) {
+    for scope in scopes {
+        events.push(trace_event(
+            &scope.name,
+            frame_start + scope.start,
+            scope.cpu_time,
+            scope.gpu_time,
+        ));
+        add_trace_events(events, frame_start, &scope.children);
+    }
+}
+
+fn trace_event(name: &str, start: u64, duration: u64, gpu_time: Option<u64>) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    let args = gpu_time
+        .map(|gpu_time| format!(",\"args\":{{\"gpu_ms\":{}}}", gpu_time as f64 * 1e-6))
+        .unwrap_or_default();
+    format!(
+        "{{\"name\":\"{}\",\"ph\":\"X\",\"pid\":1,\"tid\":1,\"ts\":{},\"dur\":{}{}}}",
+        escaped,
+        start as f64 * 1e-3,
+        duration as f64 * 1e-3,
+        args
+    )
+}
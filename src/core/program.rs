@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::{Error::ProgramError, *};
 use crate::math::*;
 use std::cell::RefCell;
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 pub struct Program {
     context: Context,
     id: crate::context::Program,
+    _allocation: Allocation,
     vertex_attributes: HashMap<String, u32>,
     textures: RefCell<HashMap<String, u32>>,
     uniforms: HashMap<String, crate::context::UniformLocation>,
@@ -20,6 +21,23 @@ pub struct Program {
 }
 
 impl Program {
+    ///
+    /// Creates a new shader program from the given vertex and fragment glsl shader source after processing both with the given [ShaderPreprocessor],
+    /// ie. after resolving includes, adding defines and the version header.
+    ///
+    pub fn from_source_with_preprocessor(
+        context: &Context,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+        preprocessor: &ShaderPreprocessor,
+    ) -> Result<Program, Error> {
+        Self::from_source(
+            context,
+            &preprocessor.process(vertex_shader_source)?,
+            &preprocessor.process(fragment_shader_source)?,
+        )
+    }
+
     ///
     /// Creates a new shader program from the given vertex and fragment glsl shader source.
     ///
@@ -28,43 +46,18 @@ impl Program {
         vertex_shader_source: &str,
         fragment_shader_source: &str,
     ) -> Result<Program, Error> {
-        let vert_shader = context
-            .create_shader(consts::VERTEX_SHADER)
-            .ok_or(ProgramError {
-                message: "Unable to create Vertex shader object".to_string(),
-            })?;
-        let frag_shader = context
-            .create_shader(consts::FRAGMENT_SHADER)
-            .ok_or(ProgramError {
-                message: "Unable to create Fragment shader object".to_string(),
-            })?;
-        context.compile_shader(vertex_shader_source, &vert_shader);
-        context.compile_shader(fragment_shader_source, &frag_shader);
-
         let id = context.create_program();
-        context.attach_shader(&id, &vert_shader);
-        context.attach_shader(&id, &frag_shader);
-        let success = context.link_program(&id);
-
-        if !success {
-            let mut message = "Failed to compile shader program:\n".to_string();
-            if let Some(log) = context.get_program_info_log(&id) {
-                message = format!("{}\nLink error: {}", message, log);
-            }
-            if let Some(log) = context.get_shader_info_log(&vert_shader) {
-                message = format!("{}\nVertex shader error: {}", message, log);
-            }
-            if let Some(log) = context.get_shader_info_log(&frag_shader) {
-                message = format!("{}\nFragment shader error: {}", message, log);
-            }
-            return Err(Error::ProgramError { message });
+        // Loading a cached binary is much faster than compiling, see Context::set_program_binary_cache
+        #[cfg(not(target_arch = "wasm32"))]
+        let linked = context.load_program_binary(&id, vertex_shader_source, fragment_shader_source);
+        #[cfg(target_arch = "wasm32")]
+        let linked = false;
+        if !linked {
+            compile_and_link(context, &id, vertex_shader_source, fragment_shader_source)?;
+            #[cfg(not(target_arch = "wasm32"))]
+            context.store_program_binary(&id, vertex_shader_source, fragment_shader_source);
         }
 
-        context.detach_shader(&id, &vert_shader);
-        context.detach_shader(&id, &frag_shader);
-        context.delete_shader(Some(&vert_shader));
-        context.delete_shader(Some(&frag_shader));
-
         // Init vertex attributes
         let num_attribs = context.get_program_parameter(&id, consts::ACTIVE_ATTRIBUTES);
         let mut vertex_attributes = HashMap::new();
@@ -87,12 +80,34 @@ impl Program {
             }
         }
 
+        // Bind the per-frame globals block, if used, to the binding point shared by all programs (see FrameGlobals)
+        let mut uniform_blocks = HashMap::new();
+        let globals_location = context.get_uniform_block_index(&id, "Globals");
+        if globals_location != consts::INVALID_INDEX {
+            let binding = context
+                .acquire_uniform_block_binding("Globals")
+                .ok_or_else(|| Error::ProgramError {
+                    message: format!(
+                        "Cannot bind the uniform block Globals since all of the {} uniform buffer binding points are in use",
+                        context.get_max_uniform_buffer_bindings()
+                    ),
+                })?;
+            context.uniform_block_binding(&id, globals_location, binding);
+            let size = context.get_active_uniform_block_parameter(
+                &id,
+                globals_location,
+                consts::UNIFORM_BLOCK_DATA_SIZE,
+            );
+            uniform_blocks.insert("Globals".to_owned(), (binding, size));
+        }
+
         Ok(Program {
             context: context.clone(),
             id,
+            _allocation: Allocation::new(context, ResourceType::Program, 0),
             vertex_attributes,
             uniforms,
-            uniform_blocks: RefCell::new(HashMap::new()),
+            uniform_blocks: RefCell::new(uniform_blocks),
             textures: RefCell::new(HashMap::new()),
         })
     }
@@ -190,6 +205,20 @@ impl Program {
         Ok(())
     }
 
+    ///
+    /// Send the given array of [Mat4](crate::Mat4) values to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform mat4 name[N]` where N is at least the number of given values.
+    ///
+    pub fn use_uniform_mat4_array(&self, name: &str, data: &[Mat4]) -> Result<(), Error> {
+        let location = self
+            .get_uniform_location(&format!("{}[0]", name))
+            .or_else(|_| self.get_uniform_location(name))?;
+        let data: Vec<f32> = data.iter().flat_map(|m| m.to_slice()).collect();
+        self.context.uniform_matrix4fv(location, &data);
+        self.context.unuse_program();
+        Ok(())
+    }
+
     fn get_uniform_location(&self, name: &str) -> Result<&crate::context::UniformLocation, Error> {
         self.set_used();
         let loc = self.uniforms.get(name).ok_or_else(|| ProgramError {
@@ -205,7 +234,11 @@ impl Program {
     /// Use the given [Texture2D](crate::Texture2D) in this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform sampler2D` and can only be accessed in the fragment shader.
     ///
-    pub fn use_texture(&self, texture: &impl Texture, texture_name: &str) -> Result<(), Error> {
+    pub fn use_texture(
+        &self,
+        texture: &(impl Texture + ?Sized),
+        texture_name: &str,
+    ) -> Result<(), Error> {
         let index = self.get_texture_index(texture_name);
         texture.bind(index);
         self.use_uniform_int(texture_name, &(index as i32))?;
@@ -218,7 +251,7 @@ impl Program {
     ///
     pub fn use_texture_array(
         &self,
-        texture: &impl TextureArray,
+        texture: &(impl TextureArray + ?Sized),
         texture_name: &str,
     ) -> Result<(), Error> {
         let index = self.get_texture_index(texture_name);
@@ -251,23 +284,61 @@ impl Program {
         self.textures.borrow().get(texture_name).unwrap().clone()
     }
 
-    pub fn use_uniform_block(&self, buffer: &UniformBuffer, block_name: &str) {
+    ///
+    /// Use the given [UniformBuffer] in this shader program and associate it with the uniform block with the given name.
+    /// The uniform block is bound to a binding point which is allocated the first time a block with the given name is used
+    /// and then shared by all programs, so the same buffer, for example a camera or light buffer, can safely be used in many programs.
+    ///
+    /// # Errors
+    /// Will return an error if the uniform block is not used in the shader code (it might be optimized away)
+    /// or if the buffer is smaller than the uniform block layout (std140) requires.
+    ///
+    pub fn use_uniform_block(&self, buffer: &UniformBuffer, block_name: &str) -> Result<(), Error> {
         if !self.uniform_blocks.borrow().contains_key(block_name) {
-            let mut map = self.uniform_blocks.borrow_mut();
             let location = self.context.get_uniform_block_index(&self.id, block_name);
-            let index = map.len() as u32;
-            map.insert(block_name.to_owned(), (location, index));
+            if location == consts::INVALID_INDEX {
+                Err(Error::ProgramError {
+                    message: format!(
+                        "The uniform block {} is not used in the shader program (it might be optimized away)",
+                        block_name
+                    ),
+                })?;
+            }
+            let size = self.context.get_active_uniform_block_parameter(
+                &self.id,
+                location,
+                consts::UNIFORM_BLOCK_DATA_SIZE,
+            );
+            let binding = self
+                .context
+                .acquire_uniform_block_binding(block_name)
+                .ok_or_else(|| Error::ProgramError {
+                    message: format!(
+                        "Cannot bind the uniform block {} since all of the {} uniform buffer binding points are in use",
+                        block_name,
+                        self.context.get_max_uniform_buffer_bindings()
+                    ),
+                })?;
+            self.context
+                .uniform_block_binding(&self.id, location, binding);
+            self.uniform_blocks
+                .borrow_mut()
+                .insert(block_name.to_owned(), (binding, size));
         };
-        let (location, index) = self
-            .uniform_blocks
-            .borrow()
-            .get(block_name)
-            .unwrap()
-            .clone();
-        self.context
-            .uniform_block_binding(&self.id, location, index);
-        buffer.bind(index);
+        let (binding, size) = *self.uniform_blocks.borrow().get(block_name).unwrap();
+        if (buffer.size_in_bytes() as u32) < size {
+            Err(Error::ProgramError {
+                message: format!(
+                    "The uniform buffer used for the uniform block {} has size {} bytes, but the block requires {} bytes",
+                    block_name,
+                    buffer.size_in_bytes(),
+                    size
+                ),
+            })?;
+        }
+        buffer.bind(binding);
         self.context.unbind_buffer(consts::UNIFORM_BUFFER);
+        Ok(())
     }
 
     pub fn use_attribute(&self, buffer: &VertexBuffer, attribute_name: &str) -> Result<(), Error> {
@@ -378,18 +449,70 @@ impl Program {
         Ok(())
     }
 
-    pub fn draw_arrays(
+    ///
+    /// Uses the attribute with the given name from the given interleaved vertex buffer, which is laid out as described by the given [vertex layout](crate::VertexLayout).
+    ///
+    /// # Errors
+    /// Will return an error if the attribute is not part of the vertex layout or if it is not used in the shader code.
+    ///
+    pub fn use_attribute_interleaved(
+        &self,
+        buffer: &VertexBuffer,
+        layout: &VertexLayout,
+        attribute_name: &str,
+    ) -> Result<(), Error> {
+        let attribute = layout
+            .attribute(attribute_name)
+            .ok_or(Error::ProgramError {
+                message: format!(
+                    "The attribute {} is not part of the vertex layout.",
+                    attribute_name
+                ),
+            })?;
+        if buffer.count() > 0 {
+            buffer.bind();
+            let loc = self.location(&attribute_name)?;
+            self.context.enable_vertex_attrib_array(loc);
+            self.context.vertex_attrib_pointer(
+                loc,
+                attribute.size,
+                buffer.data_type(),
+                false,
+                layout.stride,
+                attribute.offset,
+            );
+            self.context.vertex_attrib_divisor(loc, 0);
+            self.context.unbind_buffer(consts::ARRAY_BUFFER);
+            self.context.unuse_program();
+        }
+        Ok(())
+    }
+
+    pub fn draw_arrays(&self, render_states: RenderStates, count: u32) {
+        self.draw_arrays_with_primitive_type(render_states, PrimitiveType::Triangles, count);
+    }
+
+    ///
+    /// Draws the vertices given by the vertex attributes as a list of line segments, ie. each pair of vertices defines a line segment.
+    ///
+    pub fn draw_lines(&self, render_states: RenderStates, count: u32) {
+        self.draw_arrays_with_primitive_type(render_states, PrimitiveType::Lines, count);
+    }
+
+    ///
+    /// Draws the given number of vertices given by the vertex attributes as the given type of primitives,
+    /// for example as a triangle strip.
+    ///
+    pub fn draw_arrays_with_primitive_type(
         &self,
         render_states: RenderStates,
-        cull: CullType,
-        viewport: Viewport,
+        primitive_type: PrimitiveType,
         count: u32,
     ) {
-        Self::set_viewport(&self.context, viewport);
-        Self::set_cull(&self.context, cull);
-        Self::set_states(&self.context, render_states);
+        render_states.apply(&self.context);
         self.set_used();
-        self.context.draw_arrays(consts::TRIANGLES, 0, count);
+        self.context
+            .draw_arrays(primitive_type.to_const(), 0, count);
         for location in self.vertex_attributes.values() {
             self.context.disable_vertex_attrib_array(*location);
         }
@@ -399,14 +522,10 @@ impl Program {
     pub fn draw_arrays_instanced(
         &self,
         render_states: RenderStates,
-        cull: CullType,
-        viewport: Viewport,
         count: u32,
         instance_count: u32,
     ) {
-        Self::set_viewport(&self.context, viewport);
-        Self::set_cull(&self.context, cull);
-        Self::set_states(&self.context, render_states);
+        render_states.apply(&self.context);
         self.set_used();
         self.context
             .draw_arrays_instanced(consts::TRIANGLES, 0, count, instance_count);
@@ -417,39 +536,72 @@ impl Program {
         self.context.unuse_program();
     }
 
-    pub fn draw_elements(
+    pub fn draw_elements(&self, render_states: RenderStates, element_buffer: &ElementBuffer) {
+        self.draw_subset_of_elements(
+            render_states,
+            element_buffer,
+            0,
+            element_buffer.count() as u32,
+        );
+    }
+
+    pub fn draw_subset_of_elements(
         &self,
         render_states: RenderStates,
-        cull: CullType,
-        viewport: Viewport,
         element_buffer: &ElementBuffer,
+        first: u32,
+        count: u32,
     ) {
-        self.draw_subset_of_elements(
+        self.draw_subset_of_elements_with_primitive_type(
+            render_states,
+            PrimitiveType::Triangles,
+            element_buffer,
+            first,
+            count,
+        );
+    }
+
+    ///
+    /// Draws the vertices indexed by the given element buffer as the given type of primitives, for example as a triangle strip.
+    /// For strips and fans, the maximum value of the index type (for example 65535 for `u16` indices) restarts the primitive,
+    /// which makes it possible to draw several strips with one element buffer.
+    ///
+    pub fn draw_elements_with_primitive_type(
+        &self,
+        render_states: RenderStates,
+        primitive_type: PrimitiveType,
+        element_buffer: &ElementBuffer,
+    ) {
+        self.draw_subset_of_elements_with_primitive_type(
             render_states,
-            cull,
-            viewport,
+            primitive_type,
             element_buffer,
             0,
             element_buffer.count() as u32,
         );
     }
 
-    pub fn draw_subset_of_elements(
+    ///
+    /// Draws a subset of the vertices indexed by the given element buffer as the given type of primitives,
+    /// see [draw_elements_with_primitive_type](Program::draw_elements_with_primitive_type).
+    ///
+    pub fn draw_subset_of_elements_with_primitive_type(
         &self,
         render_states: RenderStates,
-        cull: CullType,
-        viewport: Viewport,
+        primitive_type: PrimitiveType,
         element_buffer: &ElementBuffer,
         first: u32,
         count: u32,
     ) {
-        Self::set_viewport(&self.context, viewport);
-        Self::set_cull(&self.context, cull);
-        Self::set_states(&self.context, render_states);
+        render_states.apply(&self.context);
         self.set_used();
         element_buffer.bind();
-        self.context
-            .draw_elements(consts::TRIANGLES, count, element_buffer.data_type(), first);
+        self.context.draw_elements(
+            primitive_type.to_const(),
+            count,
+            element_buffer.data_type(),
+            first,
+        );
         self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
 
         for location in self.vertex_attributes.values() {
@@ -461,14 +613,10 @@ impl Program {
     pub fn draw_elements_instanced(
         &self,
         render_states: RenderStates,
-        cull: CullType,
-        viewport: Viewport,
         element_buffer: &ElementBuffer,
         count: u32,
     ) {
-        Self::set_viewport(&self.context, viewport);
-        Self::set_cull(&self.context, cull);
-        Self::set_states(&self.context, render_states);
+        render_states.apply(&self.context);
         self.set_used();
         element_buffer.bind();
         self.context.draw_elements_instanced(
@@ -502,192 +650,102 @@ impl Program {
     fn set_used(&self) {
         self.context.use_program(&self.id);
     }
+}
 
-    fn set_states(context: &Context, render_states: RenderStates) {
-        Self::set_write_mask(context, render_states.write_mask);
-        Self::set_depth(
-            context,
-            Some(render_states.depth_test),
-            render_states.write_mask.depth,
-        );
-        Self::set_blend(context, render_states.blend);
-    }
-
-    fn set_viewport(context: &Context, viewport: Viewport) {
-        unsafe {
-            static mut CURRENT_VIEWPORT: Viewport = Viewport {
-                x: 0,
-                y: 0,
-                width: 0,
-                height: 0,
-            };
-            if viewport != CURRENT_VIEWPORT {
-                context.viewport(
-                    viewport.x,
-                    viewport.y,
-                    viewport.width as i32,
-                    viewport.height as i32,
-                );
-                CURRENT_VIEWPORT = viewport;
-            }
+impl Drop for Program {
+    fn drop(&mut self) {
+        for block_name in self.uniform_blocks.borrow().keys() {
+            self.context.release_uniform_block_binding(block_name);
         }
+        self.context.delete_program(&self.id);
     }
+}
 
-    fn set_cull(context: &Context, cull: CullType) {
-        unsafe {
-            static mut CURRENT_CULL: CullType = CullType::None;
-            if cull != CURRENT_CULL {
-                match cull {
-                    CullType::None => {
-                        context.disable(consts::CULL_FACE);
-                    }
-                    CullType::Back => {
-                        context.enable(consts::CULL_FACE);
-                        context.cull_face(consts::BACK);
-                    }
-                    CullType::Front => {
-                        context.enable(consts::CULL_FACE);
-                        context.cull_face(consts::FRONT);
-                    }
-                    CullType::FrontAndBack => {
-                        context.enable(consts::CULL_FACE);
-                        context.cull_face(consts::FRONT_AND_BACK);
-                    }
-                }
-                CURRENT_CULL = cull;
-            }
-        }
-    }
+///
+/// The type of primitives that the vertices are assembled into when drawing.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrimitiveType {
+    /// Each three consecutive vertices define a triangle.
+    Triangles,
+    /// Each vertex after the first two defines a triangle together with the two previous vertices.
+    TriangleStrip,
+    /// Each vertex after the first two defines a triangle together with the previous vertex and the first vertex.
+    TriangleFan,
+    /// Each pair of consecutive vertices define a line segment.
+    Lines,
+    /// Each vertex after the first defines a line segment together with the previous vertex.
+    LineStrip,
+    /// Like [LineStrip](PrimitiveType::LineStrip), but the last vertex is also connected to the first vertex.
+    LineLoop,
+    /// Each vertex is drawn as a point.
+    Points,
+}
 
-    fn set_blend(context: &Context, blend: Option<BlendParameters>) {
-        unsafe {
-            static mut CURRENT: Option<BlendParameters> = None;
-            if blend != CURRENT {
-                if let Some(blend_parameters) = blend {
-                    context.enable(consts::BLEND);
-                    context.blend_func_separate(
-                        Self::blend_const_from_multiplier(blend_parameters.source_rgb_multiplier),
-                        Self::blend_const_from_multiplier(
-                            blend_parameters.destination_rgb_multiplier,
-                        ),
-                        Self::blend_const_from_multiplier(blend_parameters.source_alpha_multiplier),
-                        Self::blend_const_from_multiplier(
-                            blend_parameters.destination_alpha_multiplier,
-                        ),
-                    );
-                    context.blend_equation_separate(
-                        Self::blend_const_from_equation(blend_parameters.rgb_equation),
-                        Self::blend_const_from_equation(blend_parameters.alpha_equation),
-                    );
-                } else {
-                    context.disable(consts::BLEND);
-                }
-                CURRENT = blend;
-            }
-        }
+impl Default for PrimitiveType {
+    fn default() -> Self {
+        Self::Triangles
     }
+}
 
-    fn blend_const_from_multiplier(multiplier: BlendMultiplierType) -> u32 {
-        match multiplier {
-            BlendMultiplierType::Zero => consts::ZERO,
-            BlendMultiplierType::One => consts::ONE,
-            BlendMultiplierType::SrcColor => consts::SRC_COLOR,
-            BlendMultiplierType::OneMinusSrcColor => consts::ONE_MINUS_SRC_COLOR,
-            BlendMultiplierType::DstColor => consts::DST_COLOR,
-            BlendMultiplierType::OneMinusDstColor => consts::ONE_MINUS_DST_COLOR,
-            BlendMultiplierType::SrcAlpha => consts::SRC_ALPHA,
-            BlendMultiplierType::OneMinusSrcAlpha => consts::ONE_MINUS_SRC_ALPHA,
-            BlendMultiplierType::DstAlpha => consts::DST_ALPHA,
-            BlendMultiplierType::OneMinusDstAlpha => consts::ONE_MINUS_DST_ALPHA,
-            BlendMultiplierType::SrcAlphaSaturate => consts::SRC_ALPHA_SATURATE,
+impl PrimitiveType {
+    fn to_const(&self) -> u32 {
+        match self {
+            PrimitiveType::Triangles => consts::TRIANGLES,
+            PrimitiveType::TriangleStrip => consts::TRIANGLE_STRIP,
+            PrimitiveType::TriangleFan => consts::TRIANGLE_FAN,
+            PrimitiveType::Lines => consts::LINES,
+            PrimitiveType::LineStrip => consts::LINE_STRIP,
+            PrimitiveType::LineLoop => consts::LINE_LOOP,
+            PrimitiveType::Points => consts::POINTS,
         }
     }
+}
 
-    fn blend_const_from_equation(equation: BlendEquationType) -> u32 {
-        match equation {
-            BlendEquationType::Add => consts::FUNC_ADD,
-            BlendEquationType::Subtract => consts::FUNC_SUBTRACT,
-            BlendEquationType::ReverseSubtract => consts::FUNC_REVERSE_SUBTRACT,
-            BlendEquationType::Min => consts::MIN,
-            BlendEquationType::Max => consts::MAX,
-        }
-    }
+fn compile_and_link(
+    context: &Context,
+    id: &crate::context::Program,
+    vertex_shader_source: &str,
+    fragment_shader_source: &str,
+) -> Result<(), Error> {
+    let vert_shader = context
+        .create_shader(consts::VERTEX_SHADER)
+        .ok_or(ProgramError {
+            message: "Unable to create Vertex shader object".to_string(),
+        })?;
+    let frag_shader = context
+        .create_shader(consts::FRAGMENT_SHADER)
+        .ok_or(ProgramError {
+            message: "Unable to create Fragment shader object".to_string(),
+        })?;
+    context.compile_shader(vertex_shader_source, &vert_shader);
+    context.compile_shader(fragment_shader_source, &frag_shader);
 
-    pub(crate) fn set_write_mask(context: &Context, write_mask: WriteMask) {
-        unsafe {
-            static mut CURRENT_COLOR_MASK: WriteMask = WriteMask::COLOR_AND_DEPTH;
-            if write_mask != CURRENT_COLOR_MASK {
-                context.color_mask(
-                    write_mask.red,
-                    write_mask.green,
-                    write_mask.blue,
-                    write_mask.alpha,
-                );
-                Self::set_depth(context, None, write_mask.depth);
-                CURRENT_COLOR_MASK = write_mask;
-            }
-        }
+    context.attach_shader(id, &vert_shader);
+    context.attach_shader(id, &frag_shader);
+    #[cfg(not(target_arch = "wasm32"))]
+    if context.has_program_binary_cache() {
+        context.program_binary_retrievable_hint(id);
     }
+    let success = context.link_program(id);
 
-    fn set_depth(context: &Context, depth_test: Option<DepthTestType>, depth_mask: bool) {
-        unsafe {
-            static mut CURRENT_DEPTH_ENABLE: bool = false;
-            static mut CURRENT_DEPTH_MASK: bool = true;
-            static mut CURRENT_DEPTH_TEST: DepthTestType = DepthTestType::Less;
-
-            if depth_mask == false && depth_test == Some(DepthTestType::Always) {
-                if CURRENT_DEPTH_ENABLE {
-                    context.disable(consts::DEPTH_TEST);
-                    CURRENT_DEPTH_ENABLE = false;
-                    return;
-                }
-            } else {
-                if !CURRENT_DEPTH_ENABLE {
-                    context.enable(consts::DEPTH_TEST);
-                    CURRENT_DEPTH_ENABLE = true;
-                }
-            }
-
-            if depth_mask != CURRENT_DEPTH_MASK {
-                context.depth_mask(depth_mask);
-                CURRENT_DEPTH_MASK = depth_mask;
-            }
-
-            if depth_test.is_some() && depth_test.unwrap() != CURRENT_DEPTH_TEST {
-                match depth_test.unwrap() {
-                    DepthTestType::Never => {
-                        context.depth_func(consts::NEVER);
-                    }
-                    DepthTestType::Less => {
-                        context.depth_func(consts::LESS);
-                    }
-                    DepthTestType::Equal => {
-                        context.depth_func(consts::EQUAL);
-                    }
-                    DepthTestType::LessOrEqual => {
-                        context.depth_func(consts::LEQUAL);
-                    }
-                    DepthTestType::Greater => {
-                        context.depth_func(consts::GREATER);
-                    }
-                    DepthTestType::NotEqual => {
-                        context.depth_func(consts::NOTEQUAL);
-                    }
-                    DepthTestType::GreaterOrEqual => {
-                        context.depth_func(consts::GEQUAL);
-                    }
-                    DepthTestType::Always => {
-                        context.depth_func(consts::ALWAYS);
-                    }
-                }
-                CURRENT_DEPTH_TEST = depth_test.unwrap();
-            }
+    if !success {
+        let mut message = "Failed to compile shader program:\n".to_string();
+        if let Some(log) = context.get_program_info_log(id) {
+            message = format!("{}\nLink error: {}", message, log);
+        }
+        if let Some(log) = context.get_shader_info_log(&vert_shader) {
+            message = format!("{}\nVertex shader error: {}", message, log);
         }
+        if let Some(log) = context.get_shader_info_log(&frag_shader) {
+            message = format!("{}\nFragment shader error: {}", message, log);
+        }
+        return Err(Error::ProgramError { message });
     }
-}
 
-impl Drop for Program {
-    fn drop(&mut self) {
-        self.context.delete_program(&self.id);
-    }
+    context.detach_shader(id, &vert_shader);
+    context.detach_shader(id, &frag_shader);
+    context.delete_shader(Some(&vert_shader));
+    context.delete_shader(Some(&frag_shader));
+    Ok(())
 }
@@ -0,0 +1,335 @@
+use crate::core::*;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+///
+/// Description of a transient texture which is written by one [render pass](RenderPass) and read by other render passes in a [render graph](RenderGraph).
+///
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RenderGraphTexture {
+    /// A color texture with 8 bits per channel.
+    Color {
+        width: u32,
+        height: u32,
+        format: Format,
+    },
+    /// A color texture with a 32 bit float per channel, for example for high dynamic range colors.
+    FloatColor {
+        width: u32,
+        height: u32,
+        format: Format,
+    },
+    /// A depth texture.
+    Depth {
+        width: u32,
+        height: u32,
+        format: DepthFormat,
+    },
+}
+
+enum TransientTexture {
+    Color(Rc<ColorTargetTexture2D<u8>>),
+    FloatColor(Rc<ColorTargetTexture2D<f32>>),
+    Depth(Rc<DepthTargetTexture2D>),
+}
+
+impl TransientTexture {
+    fn new(pool: &mut RenderTargetPool, description: RenderGraphTexture) -> Result<Self, Error> {
+        Ok(match description {
+            RenderGraphTexture::Color {
+                width,
+                height,
+                format,
+            } => Self::Color(pool.color_texture(width, height, format)?),
+            RenderGraphTexture::FloatColor {
+                width,
+                height,
+                format,
+            } => Self::FloatColor(pool.float_color_texture(width, height, format)?),
+            RenderGraphTexture::Depth {
+                width,
+                height,
+                format,
+            } => Self::Depth(pool.depth_texture(width, height, format)?),
+        })
+    }
+
+    fn texture(&self) -> &dyn Texture {
+        match self {
+            Self::Color(texture) => texture.as_ref(),
+            Self::FloatColor(texture) => texture.as_ref(),
+            Self::Depth(texture) => texture.as_ref(),
+        }
+    }
+
+    fn is_depth(&self) -> bool {
+        match self {
+            Self::Depth(_) => true,
+            _ => false,
+        }
+    }
+}
+
+///
+/// A render pass in a [render graph](RenderGraph) which reads a set of input textures and writes to at most one color and one depth output texture.
+/// The outputs are cleared using the [clear state](RenderPass::with_clear_state) before the render function is called.
+/// A render pass without outputs can for example be used for writing to the [Screen](crate::Screen) in the render function.
+///
+pub struct RenderPass<'a> {
+    name: String,
+    inputs: Vec<String>,
+    outputs: Vec<(String, RenderGraphTexture)>,
+    clear_state: ClearState,
+    render: Box<dyn FnOnce(&RenderPassInputs) -> Result<(), Error> + 'a>,
+}
+
+impl<'a> RenderPass<'a> {
+    ///
+    /// Constructs a new render pass with the given name which calls the `render` closure when the pass is executed.
+    /// The closure is given access to the input textures of the pass.
+    ///
+    pub fn new<F: FnOnce(&RenderPassInputs) -> Result<(), Error> + 'a>(
+        name: &str,
+        render: F,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            clear_state: ClearState::default(),
+            render: Box::new(render),
+        }
+    }
+
+    ///
+    /// Adds the texture with the given name, which must be an output of another pass, as an input to this pass.
+    ///
+    pub fn with_input(mut self, name: &str) -> Self {
+        self.inputs.push(name.to_string());
+        self
+    }
+
+    ///
+    /// Adds an output texture with the given name and description to this pass.
+    ///
+    pub fn with_output(mut self, name: &str, texture: RenderGraphTexture) -> Self {
+        self.outputs.push((name.to_string(), texture));
+        self
+    }
+
+    ///
+    /// Sets the clear state used for clearing the outputs before rendering, the default is to clear all channels.
+    ///
+    pub fn with_clear_state(mut self, clear_state: ClearState) -> Self {
+        self.clear_state = clear_state;
+        self
+    }
+}
+
+///
+/// The input textures of a [render pass](RenderPass) available in the render function of the pass.
+///
+pub struct RenderPassInputs<'a> {
+    textures: HashMap<&'a str, &'a TransientTexture>,
+}
+
+impl<'a> RenderPassInputs<'a> {
+    ///
+    /// Returns the input texture with the given name, which can be used in for example [Program::use_texture](crate::Program::use_texture).
+    ///
+    /// # Errors
+    /// Will return an error if the texture is not declared as an input of the render pass.
+    ///
+    pub fn texture(&self, name: &str) -> Result<&dyn Texture, Error> {
+        self.textures
+            .get(name)
+            .map(|texture| texture.texture())
+            .ok_or(Error::RenderGraphError {
+                message: format!("The texture {} is not an input of the render pass.", name),
+            })
+    }
+}
+
+///
+/// A render graph which schedules a set of [render passes](RenderPass) based on the textures they read and write.
+/// A pass is executed after all of the passes that write to its inputs.
+/// The textures are transient, ie. they are taken from a [render target pool](RenderTargetPool) just before they are written and returned to the pool
+/// when they are no longer needed, so they can be reused for other textures with the same description, also across frames.
+///
+pub struct RenderGraph {
+    context: Context,
+    pool: RenderTargetPool,
+}
+
+impl RenderGraph {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            context: context.clone(),
+            pool: RenderTargetPool::new(context)?,
+        })
+    }
+
+    ///
+    /// Returns the pool which the transient textures are taken from.
+    ///
+    pub fn pool(&mut self) -> &mut RenderTargetPool {
+        &mut self.pool
+    }
+
+    ///
+    /// Validates the dependencies between the given passes, orders the passes such that each pass is executed after the passes it depends on
+    /// and executes them.
+    /// The passes without dependencies between them are executed in the given order.
+    /// Should be called once each frame, since it also marks the end of the frame for the [pool](RenderGraph::pool).
+    ///
+    /// # Errors
+    /// Will return an error if a texture is written by more than one pass, if a pass has more than one color or depth output,
+    /// if an input is not written by any pass or if there is a cyclic dependency between the passes.
+    ///
+    pub fn execute(&mut self, passes: Vec<RenderPass>) -> Result<(), Error> {
+        let order = Self::schedule(&passes)?;
+
+        // Find the index in the execution order where each texture is used for the last time
+        let mut last_use = HashMap::new();
+        for (index, pass_index) in order.iter().enumerate() {
+            let pass = &passes[*pass_index];
+            for name in pass
+                .outputs
+                .iter()
+                .map(|(name, _)| name)
+                .chain(pass.inputs.iter())
+            {
+                last_use.insert(name.clone(), index);
+            }
+        }
+
+        let mut passes: Vec<Option<RenderPass>> = passes.into_iter().map(Some).collect();
+        let mut textures: HashMap<String, TransientTexture> = HashMap::new();
+        for (index, pass_index) in order.iter().enumerate() {
+            let pass = passes[*pass_index].take().unwrap();
+            let mut outputs = Vec::new();
+            for (name, description) in pass.outputs.iter() {
+                outputs.push((
+                    name.clone(),
+                    TransientTexture::new(&mut self.pool, *description)?,
+                ));
+            }
+            let inputs = RenderPassInputs {
+                textures: pass
+                    .inputs
+                    .iter()
+                    .map(|name| (name.as_str(), textures.get(name).unwrap()))
+                    .collect(),
+            };
+            let render = pass.render;
+            let color = outputs
+                .iter()
+                .map(|(_, texture)| texture)
+                .find(|texture| !texture.is_depth());
+            let depth = outputs
+                .iter()
+                .map(|(_, texture)| texture)
+                .find(|texture| texture.is_depth());
+            match (color, depth) {
+                (Some(TransientTexture::Color(color)), Some(TransientTexture::Depth(depth))) => {
+                    RenderTarget::new(&self.context, color, depth)?
+                        .write(pass.clear_state, || render(&inputs))?
+                }
+                (
+                    Some(TransientTexture::FloatColor(color)),
+                    Some(TransientTexture::Depth(depth)),
+                ) => RenderTarget::new(&self.context, color, depth)?
+                    .write(pass.clear_state, || render(&inputs))?,
+                (Some(TransientTexture::Color(color)), None) => {
+                    color.write(pass.clear_state, || render(&inputs))?
+                }
+                (Some(TransientTexture::FloatColor(color)), None) => {
+                    color.write(pass.clear_state, || render(&inputs))?
+                }
+                (None, Some(TransientTexture::Depth(depth))) => {
+                    depth.write(pass.clear_state.depth, || render(&inputs))?
+                }
+                _ => render(&inputs)?,
+            }
+
+            for (name, texture) in outputs {
+                textures.insert(name, texture);
+            }
+            // Release the textures that are not used by any of the remaining passes
+            let released: Vec<String> = textures
+                .keys()
+                .filter(|name| last_use[*name] <= index)
+                .cloned()
+                .collect();
+            for name in released {
+                textures.remove(&name);
+            }
+        }
+        self.pool.end_frame();
+        Ok(())
+    }
+
+    fn schedule(passes: &[RenderPass]) -> Result<Vec<usize>, Error> {
+        let mut writers = HashMap::new();
+        for (index, pass) in passes.iter().enumerate() {
+            let depth_outputs = pass
+                .outputs
+                .iter()
+                .filter(|(_, texture)| match texture {
+                    RenderGraphTexture::Depth { .. } => true,
+                    _ => false,
+                })
+                .count();
+            if depth_outputs > 1 || pass.outputs.len() - depth_outputs > 1 {
+                return Err(Error::RenderGraphError {
+                    message: format!(
+                        "The render pass {} has more than one color or depth output.",
+                        pass.name
+                    ),
+                });
+            }
+            for (name, _) in pass.outputs.iter() {
+                if writers.insert(name.clone(), index).is_some() {
+                    return Err(Error::RenderGraphError {
+                        message: format!(
+                            "The texture {} is written by more than one render pass.",
+                            name
+                        ),
+                    });
+                }
+            }
+        }
+
+        let mut dependencies = Vec::new();
+        for pass in passes.iter() {
+            let mut pass_dependencies = Vec::new();
+            for name in pass.inputs.iter() {
+                pass_dependencies.push(*writers.get(name).ok_or(Error::RenderGraphError {
+                    message: format!(
+                        "The input {} of the render pass {} is not written by any render pass.",
+                        name, pass.name
+                    ),
+                })?);
+            }
+            dependencies.push(pass_dependencies);
+        }
+
+        // Repeatedly execute the first pass where all dependencies are executed
+        let mut order = Vec::new();
+        let mut is_scheduled = vec![false; passes.len()];
+        while order.len() < passes.len() {
+            let next = (0..passes.len()).find(|index| {
+                !is_scheduled[*index] && dependencies[*index].iter().all(|d| is_scheduled[*d])
+            });
+            if let Some(index) = next {
+                is_scheduled[index] = true;
+                order.push(index);
+            } else {
+                return Err(Error::RenderGraphError {
+                    message: "There is a cyclic dependency between the render passes.".to_string(),
+                });
+            }
+        }
+        Ok(order)
+    }
+}
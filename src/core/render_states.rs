@@ -1,8 +1,19 @@
+use crate::context::{consts, Context};
+use crate::math::Viewport;
+
 ///
 /// A set of render specific states that has to be specified at each render call.
+/// Use [RenderStates::new] to construct the render states for a viewport with the default value of all other states,
+/// for example `RenderStates { cull: CullType::Back, ..RenderStates::new(viewport) }`.
 ///
 #[derive(Debug, Copy, Clone)]
 pub struct RenderStates {
+    ///
+    /// Defines the viewport of a render call, ie. the part of the render target which is rendered to.
+    /// The rendered image is scaled to fit the viewport, so the viewport usually has the same aspect ratio as the projection of the camera.
+    ///
+    pub viewport: Viewport,
+
     ///
     /// Defines which channels (red, green, blue, alpha and depth) to write to in a render call.
     ///
@@ -20,20 +31,198 @@ pub struct RenderStates {
     /// Blending allows combining each color channel of a render call with the color already in the
     /// color channels of the render target.
     /// This is usually used to simulate transparency.
+    /// Use one of the presets, for example [BlendParameters::TRANSPARENCY] or [BlendParameters::ADD], or specify the blend factors
+    /// and equations for the color and alpha channels separately.
+    /// Since the render states are given in each render call, each object can be rendered with its own type of blending.
     ///
     pub blend: Option<BlendParameters>,
+
+    ///
+    /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped in a render call.
+    /// Note that the [cull](crate::Mesh::cull) setting of an object or the cull setting of its material overrides this, if it is set.
+    ///
+    pub cull: CullType,
+
+    ///
+    /// Defines the scissor box of a render call, if specified, only the pixels inside the box are written to.
+    /// As opposed to the [viewport](RenderStates::viewport), the scissor box does not affect the projection, so it can be used for
+    /// rendering only a part of the image, for example to update a dirty region or for clipping user interface elements.
+    ///
+    pub scissor: Option<Viewport>,
+
+    ///
+    /// Defines an offset which is added to the depth of each fragment before the depth test, if specified.
+    /// This is used to avoid z-fighting when rendering coplanar geometry, for example decals or a wireframe on top of a mesh,
+    /// and as a bias when rendering shadow maps.
+    ///
+    pub polygon_offset: Option<PolygonOffset>,
+
+    ///
+    /// The width of lines in pixels, for example when using [Program::draw_lines](crate::Program::draw_lines).
+    /// Note that only a width of 1 is guaranteed to be supported, many platforms, including WebGL and OpenGL core profiles on macOS,
+    /// ignore any other width.
+    ///
+    pub line_width: f32,
+
+    ///
+    /// Defines the stencil test in a render call, if specified.
+    /// The stencil test compares a reference value with the value in the stencil buffer to determine whether or not a fragment should be discarded,
+    /// and updates the stencil buffer depending on the outcome of the stencil and depth test.
+    /// This is for example used to mask a region of the render target or to fill the cut surface of a [SectionPlane](crate::SectionPlane).
+    ///
+    /// **Note:** The stencil test only has an effect when rendering to the [Screen](crate::Screen), since the render targets in this library do not have a stencil buffer.
+    ///
+    pub stencil: Option<StencilTest>,
 }
 
-impl Default for RenderStates {
-    fn default() -> Self {
+impl RenderStates {
+    ///
+    /// Returns render states for rendering to the given viewport with the default value of all other states,
+    /// which writes to all channels, keeps the closest fragments, does not blend and does not cull any triangles.
+    ///
+    pub fn new(viewport: Viewport) -> Self {
         Self {
+            viewport,
             write_mask: WriteMask::default(),
             depth_test: DepthTestType::Less,
             blend: None,
+            cull: CullType::None,
+            scissor: None,
+            polygon_offset: None,
+            line_width: 1.0,
+            stencil: None,
+        }
+    }
+
+    ///
+    /// Applies these render states to the given context.
+    /// This is done automatically in each render call, so it is only needed when rendering directly using the [context](crate::context) API.
+    /// Only the states that differ from the current states are changed, since the context keeps track of the current states.
+    ///
+    pub fn apply(&self, context: &Context) {
+        context.viewport(
+            self.viewport.x,
+            self.viewport.y,
+            self.viewport.width as i32,
+            self.viewport.height as i32,
+        );
+        if let Some(scissor) = self.scissor {
+            context.enable(consts::SCISSOR_TEST);
+            context.scissor(
+                scissor.x,
+                scissor.y,
+                scissor.width as i32,
+                scissor.height as i32,
+            );
+        } else {
+            context.disable(consts::SCISSOR_TEST);
+        }
+        match self.cull {
+            CullType::None => {
+                context.disable(consts::CULL_FACE);
+            }
+            CullType::Back => {
+                context.enable(consts::CULL_FACE);
+                context.cull_face(consts::BACK);
+            }
+            CullType::Front => {
+                context.enable(consts::CULL_FACE);
+                context.cull_face(consts::FRONT);
+            }
+            CullType::FrontAndBack => {
+                context.enable(consts::CULL_FACE);
+                context.cull_face(consts::FRONT_AND_BACK);
+            }
+        }
+        if let Some(polygon_offset) = self.polygon_offset {
+            context.enable(consts::POLYGON_OFFSET_FILL);
+            context.polygon_offset(polygon_offset.factor, polygon_offset.units);
+        } else {
+            context.disable(consts::POLYGON_OFFSET_FILL);
+        }
+        context.line_width(self.line_width);
+        self.write_mask.apply(context);
+        if !self.write_mask.depth && self.depth_test == DepthTestType::Always {
+            context.disable(consts::DEPTH_TEST);
+        } else {
+            context.enable(consts::DEPTH_TEST);
+            // The depth test is mirrored in a pass with reversed depth, see ClearState::reverse_depth,
+            // so that closer still means less
+            let reverse = context.reverse_depth();
+            context.depth_func(match self.depth_test {
+                DepthTestType::Never => consts::NEVER,
+                DepthTestType::Less if reverse => consts::GREATER,
+                DepthTestType::Less => consts::LESS,
+                DepthTestType::Equal => consts::EQUAL,
+                DepthTestType::LessOrEqual if reverse => consts::GEQUAL,
+                DepthTestType::LessOrEqual => consts::LEQUAL,
+                DepthTestType::Greater if reverse => consts::LESS,
+                DepthTestType::Greater => consts::GREATER,
+                DepthTestType::NotEqual => consts::NOTEQUAL,
+                DepthTestType::GreaterOrEqual if reverse => consts::LEQUAL,
+                DepthTestType::GreaterOrEqual => consts::GEQUAL,
+                DepthTestType::Always => consts::ALWAYS,
+            });
+        }
+        if let Some(blend) = self.blend {
+            context.enable(consts::BLEND);
+            blend.apply(context);
+        } else {
+            context.disable(consts::BLEND);
+        }
+        if let Some(stencil) = self.stencil {
+            context.enable(consts::STENCIL_TEST);
+            stencil.apply(context);
+        } else {
+            context.disable(consts::STENCIL_TEST);
+        }
+    }
+}
+
+impl RenderStates {
+    ///
+    /// Returns these render states with the given cull type, if any, otherwise the cull type of these render states is kept.
+    ///
+    #[cfg(feature = "renderer")]
+    pub(crate) fn override_cull(self, cull: Option<CullType>) -> Self {
+        match cull {
+            Some(cull) => Self { cull, ..self },
+            None => self,
         }
     }
 }
 
+///
+/// An offset added to the depth of each fragment of the triangles in a render call, see [RenderStates::polygon_offset].
+/// The offset is `factor * slope + units * r` where `slope` is the depth slope of the triangle and `r` is the smallest
+/// resolvable difference in depth, so positive values move the triangles away from the camera and negative values move them closer.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PolygonOffset {
+    /// Scales the depth slope of the triangle.
+    pub factor: f32,
+    /// Scales the smallest resolvable difference in depth.
+    pub units: f32,
+}
+
+impl PolygonOffset {
+    ///
+    /// Moves the triangles slightly closer to the camera, for example used for rendering decals or overlays on top of coplanar geometry.
+    ///
+    pub const TOWARDS_CAMERA: Self = Self {
+        factor: -1.0,
+        units: -1.0,
+    };
+
+    ///
+    /// Moves the triangles slightly away from the camera, for example used as a bias when rendering shadow maps.
+    ///
+    pub const AWAY_FROM_CAMERA: Self = Self {
+        factor: 1.0,
+        units: 1.0,
+    };
+}
+
 ///
 /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped in a render call.
 ///
@@ -54,6 +243,9 @@ pub enum CullType {
 /// but do work when writing to the [Screen](crate::Screen), a [RenderTarget](crate::RenderTarget), [RenderTargetArray](crate::RenderTargetArray),
 /// [DepthTargetTexture2D](crate::DepthTargetTexture2D) or [DepthTargetTexture2DArray](crate::DepthTargetTexture2DArray).
 ///
+/// **Note:** When writing to a render target with [reversed depth](crate::ClearState::reverse_depth), the comparison is mirrored,
+/// so for example [DepthTestType::Less] still means that the closest fragment is kept.
+///
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum DepthTestType {
     Never,
@@ -66,15 +258,136 @@ pub enum DepthTestType {
     Always,
 }
 
+///
+/// Defines the stencil test and how the stencil buffer is updated in a render call, see [RenderStates::stencil].
+/// A fragment passes the stencil test if `reference & read_mask` compares to `stencil & read_mask` according to the test type,
+/// where `stencil` is the current value in the stencil buffer.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StencilTest {
+    /// The comparison between the reference value and the value in the stencil buffer.
+    pub test: StencilTestType,
+    /// The reference value which is compared with the value in the stencil buffer and written by [StencilOperation::Replace].
+    pub reference: i32,
+    /// The bits of the reference value and the value in the stencil buffer which are compared.
+    pub read_mask: u32,
+    /// The bits of the stencil buffer which are written to.
+    pub write_mask: u32,
+    /// How the stencil buffer is updated when the fragment fails the stencil test.
+    pub fail: StencilOperation,
+    /// How the stencil buffer is updated when the fragment passes the stencil test but fails the depth test.
+    pub depth_fail: StencilOperation,
+    /// How the stencil buffer is updated when the fragment passes both the stencil and the depth test.
+    pub pass: StencilOperation,
+}
+
+impl StencilTest {
+    ///
+    /// Only the fragments where the stencil buffer is not zero pass the test and the stencil buffer is not changed.
+    ///
+    pub const NOT_ZERO: Self = Self {
+        test: StencilTestType::NotEqual,
+        reference: 0,
+        read_mask: 0xFF,
+        write_mask: 0,
+        fail: StencilOperation::Keep,
+        depth_fail: StencilOperation::Keep,
+        pass: StencilOperation::Keep,
+    };
+
+    fn apply(&self, context: &Context) {
+        context.stencil_func(
+            match self.test {
+                StencilTestType::Never => consts::NEVER,
+                StencilTestType::Less => consts::LESS,
+                StencilTestType::Equal => consts::EQUAL,
+                StencilTestType::LessOrEqual => consts::LEQUAL,
+                StencilTestType::Greater => consts::GREATER,
+                StencilTestType::NotEqual => consts::NOTEQUAL,
+                StencilTestType::GreaterOrEqual => consts::GEQUAL,
+                StencilTestType::Always => consts::ALWAYS,
+            },
+            self.reference,
+            self.read_mask,
+        );
+        context.stencil_op(
+            self.fail.to_const(),
+            self.depth_fail.to_const(),
+            self.pass.to_const(),
+        );
+        context.stencil_mask(self.write_mask);
+    }
+}
+
+///
+/// The comparison between the reference value and the value in the stencil buffer in a [stencil test](crate::StencilTest).
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilTestType {
+    Never,
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+///
+/// How the value in the stencil buffer is updated in a [stencil test](crate::StencilTest).
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilOperation {
+    /// Keeps the current value.
+    Keep,
+    /// Sets the value to zero.
+    Zero,
+    /// Sets the value to the reference value.
+    Replace,
+    /// Increments the value, clamped to the maximum value.
+    Increment,
+    /// Increments the value and wraps to zero when exceeding the maximum value.
+    IncrementWrap,
+    /// Decrements the value, clamped to zero.
+    Decrement,
+    /// Decrements the value and wraps to the maximum value when decrementing zero.
+    DecrementWrap,
+    /// Inverts the bits of the value.
+    Invert,
+}
+
+impl StencilOperation {
+    fn to_const(&self) -> u32 {
+        match self {
+            StencilOperation::Keep => consts::KEEP,
+            StencilOperation::Zero => consts::ZERO,
+            StencilOperation::Replace => consts::REPLACE,
+            StencilOperation::Increment => consts::INCR,
+            StencilOperation::IncrementWrap => consts::INCR_WRAP,
+            StencilOperation::Decrement => consts::DECR,
+            StencilOperation::DecrementWrap => consts::DECR_WRAP,
+            StencilOperation::Invert => consts::INVERT,
+        }
+    }
+}
+
 ///
 /// Defines which channels (red, green, blue, alpha and depth) to write to in a render call.
+/// The channels that are not written to keep their current value, which for example makes it possible to
+/// write different data into each channel of a render target in separate render calls.
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct WriteMask {
+    /// Whether or not to write to the red channel.
     pub red: bool,
+    /// Whether or not to write to the green channel.
     pub green: bool,
+    /// Whether or not to write to the blue channel.
     pub blue: bool,
+    /// Whether or not to write to the alpha channel.
     pub alpha: bool,
+    /// Whether or not to write to the depth channel.
     pub depth: bool,
 }
 
@@ -122,6 +435,79 @@ impl WriteMask {
         alpha: false,
         depth: false,
     };
+
+    ///
+    /// Writes to the red channel only.
+    ///
+    pub const RED: Self = Self {
+        red: true,
+        ..Self::NONE
+    };
+
+    ///
+    /// Writes to the green channel only.
+    ///
+    pub const GREEN: Self = Self {
+        green: true,
+        ..Self::NONE
+    };
+
+    ///
+    /// Writes to the blue channel only.
+    ///
+    pub const BLUE: Self = Self {
+        blue: true,
+        ..Self::NONE
+    };
+
+    ///
+    /// Writes to the alpha channel only.
+    ///
+    pub const ALPHA: Self = Self {
+        alpha: true,
+        ..Self::NONE
+    };
+
+    ///
+    /// Writes to the red, green and blue channels, but not to the alpha and depth channels.
+    ///
+    pub const RGB: Self = Self {
+        red: true,
+        green: true,
+        blue: true,
+        ..Self::NONE
+    };
+
+    ///
+    /// Writes to the given color channels and not to the depth channel.
+    ///
+    pub const fn color(red: bool, green: bool, blue: bool, alpha: bool) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+            depth: false,
+        }
+    }
+
+    ///
+    /// Returns this write mask where writing to the depth channel is enabled,
+    /// for example `WriteMask::RED.with_depth()` writes to the red and depth channels.
+    ///
+    pub const fn with_depth(self) -> Self {
+        Self {
+            depth: true,
+            ..self
+        }
+    }
+}
+
+impl WriteMask {
+    pub(crate) fn apply(&self, context: &Context) {
+        context.color_mask(self.red, self.green, self.blue, self.alpha);
+        context.depth_mask(self.depth);
+    }
 }
 
 impl Default for WriteMask {
@@ -170,6 +556,47 @@ impl BlendParameters {
         rgb_equation: BlendEquationType::Add,
         alpha_equation: BlendEquationType::Add,
     };
+
+    ///
+    /// Transparency blending where the output color of the render call is already multiplied with its alpha value,
+    /// for example used for user interfaces and for textures with premultiplied alpha.
+    /// As opposed to [TRANSPARENCY](BlendParameters::TRANSPARENCY), this gives correct results when blending
+    /// into a transparent render target which is later blended into another render target.
+    ///
+    pub const PREMULTIPLIED_ALPHA: Self = Self {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::One,
+        destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+        destination_alpha_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Multiplies the color of the render target with the output color of the render call, for example used for
+    /// darkening the render target with decals or light maps. The alpha value of the render target is kept.
+    ///
+    pub const MULTIPLY: Self = Self {
+        source_rgb_multiplier: BlendMultiplierType::DstColor,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::Zero,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+}
+
+impl BlendParameters {
+    fn apply(&self, context: &Context) {
+        context.blend_func_separate(
+            self.source_rgb_multiplier.to_const(),
+            self.destination_rgb_multiplier.to_const(),
+            self.source_alpha_multiplier.to_const(),
+            self.destination_alpha_multiplier.to_const(),
+        );
+        context
+            .blend_equation_separate(self.rgb_equation.to_const(), self.alpha_equation.to_const());
+    }
 }
 
 impl Default for BlendParameters {
@@ -207,3 +634,33 @@ pub enum BlendEquationType {
     Max,
     Min,
 }
+
+impl BlendMultiplierType {
+    fn to_const(&self) -> u32 {
+        match self {
+            BlendMultiplierType::Zero => consts::ZERO,
+            BlendMultiplierType::One => consts::ONE,
+            BlendMultiplierType::SrcColor => consts::SRC_COLOR,
+            BlendMultiplierType::OneMinusSrcColor => consts::ONE_MINUS_SRC_COLOR,
+            BlendMultiplierType::DstColor => consts::DST_COLOR,
+            BlendMultiplierType::OneMinusDstColor => consts::ONE_MINUS_DST_COLOR,
+            BlendMultiplierType::SrcAlpha => consts::SRC_ALPHA,
+            BlendMultiplierType::OneMinusSrcAlpha => consts::ONE_MINUS_SRC_ALPHA,
+            BlendMultiplierType::DstAlpha => consts::DST_ALPHA,
+            BlendMultiplierType::OneMinusDstAlpha => consts::ONE_MINUS_DST_ALPHA,
+            BlendMultiplierType::SrcAlphaSaturate => consts::SRC_ALPHA_SATURATE,
+        }
+    }
+}
+
+impl BlendEquationType {
+    fn to_const(&self) -> u32 {
+        match self {
+            BlendEquationType::Add => consts::FUNC_ADD,
+            BlendEquationType::Subtract => consts::FUNC_SUBTRACT,
+            BlendEquationType::ReverseSubtract => consts::FUNC_REVERSE_SUBTRACT,
+            BlendEquationType::Min => consts::MIN,
+            BlendEquationType::Max => consts::MAX,
+        }
+    }
+}
@@ -19,8 +19,13 @@ pub struct ClearState {
     pub blue: Option<f32>,
     /// Defines the clear value for the alpha channel.
     pub alpha: Option<f32>,
-    /// Defines the clear value for the depth channel. A value of 1 means a depth value equal to the far plane and 0 means a depth value equal to the near plane.
+    /// Defines the clear value for the depth channel. A value of 1 means a depth value equal to the far plane and 0 means a depth value equal to the near plane,
+    /// unless the depth is reversed.
     pub depth: Option<f32>,
+    /// Defines whether the depth is reversed in the render calls until the next render target is written to, ie. the depth of the near plane is 1
+    /// and the depth of the far plane is 0, which is the case when rendering with a camera with [reversed depth](crate::Camera::set_reverse_z),
+    /// see [Camera::clear_state](crate::Camera::clear_state). Then the [depth test](crate::DepthTestType) of all render calls is mirrored.
+    pub reverse_depth: bool,
 }
 
 impl ClearState {
@@ -34,6 +39,7 @@ impl ClearState {
             blue: None,
             alpha: None,
             depth: None,
+            reverse_depth: false,
         }
     }
 
@@ -47,6 +53,7 @@ impl ClearState {
             blue: None,
             alpha: None,
             depth: Some(depth),
+            reverse_depth: false,
         }
     }
 
@@ -60,6 +67,7 @@ impl ClearState {
             blue: Some(blue),
             alpha: Some(alpha),
             depth: None,
+            reverse_depth: false,
         }
     }
 
@@ -73,6 +81,7 @@ impl ClearState {
             blue: Some(blue),
             alpha: Some(alpha),
             depth: Some(depth),
+            reverse_depth: false,
         }
     }
 }
@@ -206,6 +215,7 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
                 blue: self.color_texture.and(clear_state.blue),
                 alpha: self.color_texture.and(clear_state.alpha),
                 depth: self.depth_texture.and(clear_state.depth),
+                reverse_depth: clear_state.reverse_depth,
             },
         );
         render()?;
@@ -233,14 +243,11 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
             if let Some(tex) = self.depth_texture {
                 effect.use_texture(tex, "depthMap")?;
             }
-            effect.apply(
-                RenderStates {
-                    depth_test: DepthTestType::Always,
-                    write_mask,
-                    ..Default::default()
-                },
-                viewport,
-            )?;
+            effect.apply(RenderStates {
+                depth_test: DepthTestType::Always,
+                write_mask,
+                ..RenderStates::new(viewport)
+            })?;
             Ok(())
         };
         match destination {
@@ -392,6 +399,7 @@ impl<'a, 'b, T: TextureDataType> RenderTargetArray<'a, 'b, T> {
                 blue: self.color_texture.and(clear_state.blue),
                 alpha: self.color_texture.and(clear_state.alpha),
                 depth: self.depth_texture.and(clear_state.depth),
+                reverse_depth: clear_state.reverse_depth,
             },
         );
         render()?;
@@ -423,14 +431,11 @@ impl<'a, 'b, T: TextureDataType> RenderTargetArray<'a, 'b, T> {
                 effect.use_texture_array(tex, "depthMap")?;
                 effect.use_uniform_int("depthLayer", &(depth_layer as i32))?;
             }
-            effect.apply(
-                RenderStates {
-                    depth_test: DepthTestType::Always,
-                    write_mask,
-                    ..Default::default()
-                },
-                viewport,
-            )?;
+            effect.apply(RenderStates {
+                depth_test: DepthTestType::Always,
+                write_mask,
+                ..RenderStates::new(viewport)
+            })?;
             Ok(())
         };
         match destination {
@@ -465,6 +470,16 @@ impl<'a, 'b, T: TextureDataType> RenderTargetArray<'a, 'b, T> {
             .bind_framebuffer(consts::DRAW_FRAMEBUFFER, Some(&self.id));
         if let Some(color_texture) = self.color_texture {
             if let Some(color_layers) = color_layers {
+                let max_color_attachments = self.context.capabilities().max_color_attachments;
+                if max_color_attachments > 0 && color_layers.len() as u32 > max_color_attachments {
+                    Err(Error::RenderTargetError {
+                        message: format!(
+                            "Cannot render into {} color layers at the same time, this device supports at most {}",
+                            color_layers.len(),
+                            max_color_attachments
+                        ),
+                    })?;
+                }
                 self.context.draw_buffers(
                     &(0..color_layers.len())
                         .map(|i| consts::COLOR_ATTACHMENT0 + i as u32)
@@ -492,7 +507,7 @@ impl<T: TextureDataType> Drop for RenderTargetArray<'_, '_, T> {
     }
 }
 
-fn new_framebuffer(context: &Context) -> Result<crate::context::Framebuffer, Error> {
+pub(super) fn new_framebuffer(context: &Context) -> Result<crate::context::Framebuffer, Error> {
     Ok(context
         .create_framebuffer()
         .ok_or_else(|| Error::RenderTargetError {
@@ -509,17 +524,19 @@ fn check(context: &Context) -> Result<(), Error> {
     })
 }
 
-fn clear(context: &Context, clear_state: &ClearState) {
-    Program::set_write_mask(
-        context,
-        WriteMask {
-            red: clear_state.red.is_some(),
-            green: clear_state.green.is_some(),
-            blue: clear_state.blue.is_some(),
-            alpha: clear_state.alpha.is_some(),
-            depth: clear_state.depth.is_some(),
-        },
-    );
+pub(super) fn clear(context: &Context, clear_state: &ClearState) {
+    // Each render target write starts a new pass with its own depth convention, so it is never inherited from a previous pass
+    context.set_reverse_depth(clear_state.reverse_depth);
+    // The scissor test also applies to clearing, so make sure the whole render target is cleared
+    context.disable(consts::SCISSOR_TEST);
+    WriteMask {
+        red: clear_state.red.is_some(),
+        green: clear_state.green.is_some(),
+        blue: clear_state.blue.is_some(),
+        alpha: clear_state.alpha.is_some(),
+        depth: clear_state.depth.is_some(),
+    }
+    .apply(context);
     let clear_color = clear_state.red.is_some()
         || clear_state.green.is_some()
         || clear_state.blue.is_some()
@@ -546,6 +563,19 @@ fn clear(context: &Context, clear_state: &ClearState) {
     });
 }
 
+///
+/// Clears the stencil buffer of the current render target to the given value,
+/// which is not part of the [ClearState] since only the [Screen] has a stencil buffer.
+///
+#[cfg(feature = "renderer")]
+pub(crate) fn clear_stencil(context: &Context, value: i32) {
+    // The scissor test and the stencil write mask also apply to clearing
+    context.disable(consts::SCISSOR_TEST);
+    context.stencil_mask(0xFF);
+    context.clear_stencil(value);
+    context.clear(consts::STENCIL_BUFFER_BIT);
+}
+
 fn get_copy_effect(context: &Context) -> Result<&ImageEffect, Error> {
     unsafe {
         static mut COPY_EFFECT: Option<ImageEffect> = None;
@@ -0,0 +1,184 @@
+use crate::core::*;
+use crate::definition::*;
+use std::rc::Rc;
+
+struct PooledTexture<T, F> {
+    texture: Rc<T>,
+    width: u32,
+    height: u32,
+    format: F,
+    unused_frames: u32,
+}
+
+///
+/// A pool of temporary color and depth textures, for example used as intermediate render targets in post-processing effects.
+/// A texture is handed out if it has the requested size and format and is not in use, ie. all previously handed out references to it are dropped,
+/// otherwise a new texture is allocated. This avoids allocating new textures each frame.
+/// Call [end_frame](RenderTargetPool::end_frame) at the end of each frame to release the textures that have not been used for a while.
+///
+pub struct RenderTargetPool {
+    context: Context,
+    color_textures: Vec<PooledTexture<ColorTargetTexture2D<u8>, Format>>,
+    float_color_textures: Vec<PooledTexture<ColorTargetTexture2D<f32>, Format>>,
+    depth_textures: Vec<PooledTexture<DepthTargetTexture2D, DepthFormat>>,
+    /// The number of frames a texture can stay unused before it is released.
+    pub max_unused_frames: u32,
+}
+
+impl RenderTargetPool {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            context: context.clone(),
+            color_textures: Vec::new(),
+            float_color_textures: Vec::new(),
+            depth_textures: Vec::new(),
+            max_unused_frames: 2,
+        })
+    }
+
+    ///
+    /// Returns a color texture with 8 bits per channel and the given size and format which is not used elsewhere.
+    /// The texture is returned to the pool when the returned reference is dropped.
+    ///
+    pub fn color_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Format,
+    ) -> Result<Rc<ColorTargetTexture2D<u8>>, Error> {
+        let context = &self.context;
+        take(&mut self.color_textures, width, height, format, || {
+            new_color_texture(context, width, height, format)
+        })
+    }
+
+    ///
+    /// Returns a color texture with a 32 bit float per channel and the given size and format which is not used elsewhere.
+    /// The texture is returned to the pool when the returned reference is dropped.
+    ///
+    pub fn float_color_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Format,
+    ) -> Result<Rc<ColorTargetTexture2D<f32>>, Error> {
+        let context = &self.context;
+        take(
+            &mut self.float_color_textures,
+            width,
+            height,
+            format,
+            || new_color_texture(context, width, height, format),
+        )
+    }
+
+    ///
+    /// Returns a depth texture with the given size and format which is not used elsewhere.
+    /// The texture is returned to the pool when the returned reference is dropped.
+    ///
+    pub fn depth_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: DepthFormat,
+    ) -> Result<Rc<DepthTargetTexture2D>, Error> {
+        let context = &self.context;
+        take(&mut self.depth_textures, width, height, format, || {
+            DepthTargetTexture2D::new(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                format,
+            )
+        })
+    }
+
+    ///
+    /// Releases the textures which have not been used for more than [max_unused_frames](RenderTargetPool::max_unused_frames) frames.
+    /// Should be called once at the end of each frame.
+    ///
+    pub fn end_frame(&mut self) {
+        let max_unused_frames = self.max_unused_frames;
+        release_unused(&mut self.color_textures, max_unused_frames);
+        release_unused(&mut self.float_color_textures, max_unused_frames);
+        release_unused(&mut self.depth_textures, max_unused_frames);
+    }
+
+    ///
+    /// Releases all textures which are not currently in use.
+    ///
+    pub fn clear(&mut self) {
+        self.color_textures
+            .retain(|t| Rc::strong_count(&t.texture) > 1);
+        self.float_color_textures
+            .retain(|t| Rc::strong_count(&t.texture) > 1);
+        self.depth_textures
+            .retain(|t| Rc::strong_count(&t.texture) > 1);
+    }
+
+    ///
+    /// Returns the number of textures allocated by the pool, both in use and unused.
+    ///
+    pub fn texture_count(&self) -> usize {
+        self.color_textures.len() + self.float_color_textures.len() + self.depth_textures.len()
+    }
+}
+
+fn new_color_texture<T: TextureDataType>(
+    context: &Context,
+    width: u32,
+    height: u32,
+    format: Format,
+) -> Result<ColorTargetTexture2D<T>, Error> {
+    ColorTargetTexture2D::new(
+        context,
+        width,
+        height,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        format,
+    )
+}
+
+fn take<T, F: PartialEq>(
+    textures: &mut Vec<PooledTexture<T, F>>,
+    width: u32,
+    height: u32,
+    format: F,
+    create: impl FnOnce() -> Result<T, Error>,
+) -> Result<Rc<T>, Error> {
+    if let Some(pooled) = textures.iter_mut().find(|t| {
+        t.width == width
+            && t.height == height
+            && t.format == format
+            && Rc::strong_count(&t.texture) == 1
+    }) {
+        pooled.unused_frames = 0;
+        return Ok(pooled.texture.clone());
+    }
+    let texture = Rc::new(create()?);
+    textures.push(PooledTexture {
+        texture: texture.clone(),
+        width,
+        height,
+        format,
+        unused_frames: 0,
+    });
+    Ok(texture)
+}
+
+fn release_unused<T, F>(textures: &mut Vec<PooledTexture<T, F>>, max_unused_frames: u32) {
+    for pooled in textures.iter_mut() {
+        if Rc::strong_count(&pooled.texture) > 1 {
+            pooled.unused_frames = 0;
+        } else {
+            pooled.unused_frames += 1;
+        }
+    }
+    textures.retain(|t| t.unused_frames <= max_unused_frames);
+}
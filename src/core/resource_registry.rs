@@ -0,0 +1,307 @@
+use crate::core::*;
+use crate::definition::*;
+use std::marker::PhantomData;
+
+///
+/// A handle to a resource in a [ResourceRegistry].
+/// Handles are small, copyable and can be sent between threads, however, the resource can only be accessed on the thread owning the registry.
+/// A handle to a destroyed resource never refers to another resource, even if the slot is reused.
+///
+pub struct Handle<T> {
+    index: u32,
+    generation: u32,
+    resource: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> std::hash::Hash for Handle<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handle")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+///
+/// A handle to a [Texture2D] in a [ResourceRegistry].
+///
+pub type TextureHandle = Handle<Texture2D>;
+
+///
+/// A handle to a [Program] in a [ResourceRegistry].
+///
+pub type ProgramHandle = Handle<Program>;
+
+///
+/// A handle to a [VertexBuffer] in a [ResourceRegistry].
+///
+pub type VertexBufferHandle = Handle<VertexBuffer>;
+
+///
+/// A handle to an [ElementBuffer] in a [ResourceRegistry].
+///
+pub type ElementBufferHandle = Handle<ElementBuffer>;
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> Arena<T> {
+    fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, value: T) -> Handle<T> {
+        let index = if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(value);
+            index
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                value: Some(value),
+            });
+            self.slots.len() as u32 - 1
+        };
+        Handle {
+            index,
+            generation: self.slots[index as usize].generation,
+            resource: PhantomData,
+        }
+    }
+
+    fn get(&self, handle: Handle<T>) -> Option<&T> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation || slot.value.is_none() {
+            return None;
+        }
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        slot.value.take()
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+}
+
+///
+/// A registry which owns GPU resources and hands out [handles](Handle) to them, as an alternative to sharing the resources using reference counting.
+/// The resources are deleted exactly when they are [destroyed](ResourceRegistry::destroy_texture) or when the registry is dropped,
+/// which gives deterministic lifetimes. Since the handles can be sent between threads,
+/// scenes can be prepared on other threads referring to resources by handle and then rendered on the thread owning the registry.
+///
+pub struct ResourceRegistry {
+    context: Context,
+    textures: Arena<Texture2D>,
+    programs: Arena<Program>,
+    vertex_buffers: Arena<VertexBuffer>,
+    element_buffers: Arena<ElementBuffer>,
+}
+
+impl ResourceRegistry {
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            textures: Arena::new(),
+            programs: Arena::new(),
+            vertex_buffers: Arena::new(),
+            element_buffers: Arena::new(),
+        }
+    }
+
+    ///
+    /// Creates a new [Texture2D] from the given CPU texture and returns a handle to it.
+    ///
+    pub fn create_texture<T: TextureDataType>(
+        &mut self,
+        cpu_texture: &CPUTexture<T>,
+    ) -> Result<TextureHandle, Error> {
+        Ok(self
+            .textures
+            .insert(Texture2D::new(&self.context, cpu_texture)?))
+    }
+
+    ///
+    /// Moves the given texture into the registry and returns a handle to it.
+    ///
+    pub fn insert_texture(&mut self, texture: Texture2D) -> TextureHandle {
+        self.textures.insert(texture)
+    }
+
+    ///
+    /// Returns the texture with the given handle or `None` if it has been destroyed.
+    ///
+    pub fn texture(&self, handle: TextureHandle) -> Option<&Texture2D> {
+        self.textures.get(handle)
+    }
+
+    ///
+    /// Returns the texture with the given handle or `None` if it has been destroyed.
+    ///
+    pub fn texture_mut(&mut self, handle: TextureHandle) -> Option<&mut Texture2D> {
+        self.textures.get_mut(handle)
+    }
+
+    ///
+    /// Deletes the texture with the given handle. Returns false if it has already been destroyed.
+    ///
+    pub fn destroy_texture(&mut self, handle: TextureHandle) -> bool {
+        self.textures.remove(handle).is_some()
+    }
+
+    ///
+    /// Creates a new [Program] from the given vertex and fragment shader source and returns a handle to it.
+    ///
+    pub fn create_program(
+        &mut self,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> Result<ProgramHandle, Error> {
+        Ok(self.programs.insert(Program::from_source(
+            &self.context,
+            vertex_shader_source,
+            fragment_shader_source,
+        )?))
+    }
+
+    ///
+    /// Moves the given program into the registry and returns a handle to it.
+    ///
+    pub fn insert_program(&mut self, program: Program) -> ProgramHandle {
+        self.programs.insert(program)
+    }
+
+    ///
+    /// Returns the program with the given handle or `None` if it has been destroyed.
+    ///
+    pub fn program(&self, handle: ProgramHandle) -> Option<&Program> {
+        self.programs.get(handle)
+    }
+
+    ///
+    /// Deletes the program with the given handle. Returns false if it has already been destroyed.
+    ///
+    pub fn destroy_program(&mut self, handle: ProgramHandle) -> bool {
+        self.programs.remove(handle).is_some()
+    }
+
+    ///
+    /// Creates a new [VertexBuffer] with the given static data and returns a handle to it.
+    ///
+    pub fn create_vertex_buffer<T: VertexBufferDataType>(
+        &mut self,
+        data: &[T],
+    ) -> Result<VertexBufferHandle, Error> {
+        Ok(self
+            .vertex_buffers
+            .insert(VertexBuffer::new_with_static(&self.context, data)?))
+    }
+
+    ///
+    /// Returns the vertex buffer with the given handle or `None` if it has been destroyed.
+    ///
+    pub fn vertex_buffer(&self, handle: VertexBufferHandle) -> Option<&VertexBuffer> {
+        self.vertex_buffers.get(handle)
+    }
+
+    ///
+    /// Returns the vertex buffer with the given handle or `None` if it has been destroyed.
+    ///
+    pub fn vertex_buffer_mut(&mut self, handle: VertexBufferHandle) -> Option<&mut VertexBuffer> {
+        self.vertex_buffers.get_mut(handle)
+    }
+
+    ///
+    /// Deletes the vertex buffer with the given handle. Returns false if it has already been destroyed.
+    ///
+    pub fn destroy_vertex_buffer(&mut self, handle: VertexBufferHandle) -> bool {
+        self.vertex_buffers.remove(handle).is_some()
+    }
+
+    ///
+    /// Creates a new [ElementBuffer] with the given indices and returns a handle to it.
+    ///
+    pub fn create_element_buffer<T: ElementBufferDataType>(
+        &mut self,
+        data: &[T],
+    ) -> Result<ElementBufferHandle, Error> {
+        Ok(self
+            .element_buffers
+            .insert(ElementBuffer::new(&self.context, data)?))
+    }
+
+    ///
+    /// Returns the element buffer with the given handle or `None` if it has been destroyed.
+    ///
+    pub fn element_buffer(&self, handle: ElementBufferHandle) -> Option<&ElementBuffer> {
+        self.element_buffers.get(handle)
+    }
+
+    ///
+    /// Deletes the element buffer with the given handle. Returns false if it has already been destroyed.
+    ///
+    pub fn destroy_element_buffer(&mut self, handle: ElementBufferHandle) -> bool {
+        self.element_buffers.remove(handle).is_some()
+    }
+
+    ///
+    /// Returns the number of textures in the registry.
+    ///
+    pub fn texture_count(&self) -> usize {
+        self.textures.len()
+    }
+
+    ///
+    /// Returns the number of programs in the registry.
+    ///
+    pub fn program_count(&self) -> usize {
+        self.programs.len()
+    }
+}
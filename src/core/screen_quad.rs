@@ -0,0 +1,81 @@
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// A screen-space pass, ie. a [Program] with a standard vertex shader which draws a single triangle covering the entire viewport.
+/// The triangle is generated in the vertex shader from the vertex index, so no vertex buffers are needed,
+/// and only a fragment shader has to be written for a custom post-processing pass.
+/// The fragment shader receives the texture coordinates of the screen (from (0, 0) in the bottom left corner to (1, 1) in the top right corner) as
+///
+/// ```ignore
+/// in vec2 uv;
+/// ```
+///
+/// The fragment shader is processed with a default [ShaderPreprocessor], so it can include the built-in shader chunks.
+///
+pub struct ScreenQuad {
+    program: Program,
+}
+
+impl ScreenQuad {
+    ///
+    /// The vertex shader used for all screen quads.
+    ///
+    pub const VERTEX_SHADER: &'static str = "
+        out vec2 uv;
+        void main()
+        {
+            uv = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+            gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+        }";
+
+    pub fn new(context: &Context, fragment_shader: &str) -> Result<Self, Error> {
+        Ok(Self {
+            program: Program::from_source_with_preprocessor(
+                context,
+                Self::VERTEX_SHADER,
+                fragment_shader,
+                &ShaderPreprocessor::new(),
+            )?,
+        })
+    }
+
+    ///
+    /// Draws the screen quad in the viewport of the render states in the current render target using the uniforms and textures set on this program.
+    ///
+    pub fn apply(&self, render_states: RenderStates) -> Result<(), Error> {
+        self.program.draw_arrays(
+            RenderStates {
+                cull: CullType::Back,
+                ..render_states
+            },
+            3,
+        );
+        Ok(())
+    }
+
+    ///
+    /// Uses the given input textures, each associated with the sampler with the given name,
+    /// and draws the screen quad into the viewport of the render states in the target texture, usually the entire target texture.
+    /// Other uniforms must be set before calling this function.
+    ///
+    pub fn apply_to<T: TextureDataType>(
+        &self,
+        inputs: &[(&str, &dyn Texture)],
+        target: &ColorTargetTexture2D<T>,
+        render_states: RenderStates,
+    ) -> Result<(), Error> {
+        for (name, texture) in inputs.iter() {
+            self.program.use_texture(*texture, name)?;
+        }
+        target.write(ClearState::none(), || self.apply(render_states))
+    }
+}
+
+impl std::ops::Deref for ScreenQuad {
+    type Target = Program;
+
+    fn deref(&self) -> &Self::Target {
+        &self.program
+    }
+}
@@ -0,0 +1,168 @@
+use crate::core::*;
+use std::collections::{HashMap, HashSet};
+
+///
+/// A small GLSL preprocessor which resolves `#include "name"` directives of named shader chunks,
+/// injects `#define`s given from Rust, for example the maximum number of lights or feature toggles,
+/// and prepends the version header matching the target, ie. `#version 300 es` on web and `#version 330 core` on desktop.
+/// It does not translate between GLSL dialects, so the shader source must be valid both as GLSL 3.30 and GLSL ES 3.00.
+/// All of the built-in shaders are processed with a default preprocessor.
+///
+/// The following shader chunks used by the built-in shaders are always available:
+/// - `camera` contains the `Camera` uniform block with the data of the [camera uniform buffer](crate::Camera::uniform_buffer).
+/// - `shared` contains the functions shared by the built-in shaders, for example conversion between sRGB and linear color space.
+/// - `lights` contains the light structs and the functions for calculating the lighting and shadows (only with the `phong-renderer` feature).
+/// - `deferred_objects` contains the output of the geometry pass of the deferred phong renderer (only with the `phong-renderer` feature).
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// let mut preprocessor = ShaderPreprocessor::new();
+/// preprocessor.add_chunk("lighting", "vec3 light(vec3 n) { return vec3(max(dot(n, vec3(0.0, 1.0, 0.0)), 0.0)); }");
+/// preprocessor.define("MAX_LIGHTS", 4);
+/// preprocessor.enable("USE_FOG");
+/// let program = Program::from_source_with_preprocessor(
+///     &context,
+///     "in vec3 position; void main() { gl_Position = vec4(position, 1.0); }",
+///     "#include \"lighting\"\n layout (location = 0) out vec4 color; void main() { color = vec4(light(vec3(0.0, 1.0, 0.0)), 1.0); }",
+///     &preprocessor,
+/// ).unwrap();
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct ShaderPreprocessor {
+    chunks: HashMap<String, String>,
+    defines: Vec<(String, String)>,
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> Self {
+        let mut chunks = HashMap::new();
+        chunks.insert(
+            "camera".to_string(),
+            include_str!("../camera/shaders/camera.frag").to_string(),
+        );
+        chunks.insert(
+            "shared".to_string(),
+            include_str!("shared.frag").to_string(),
+        );
+        #[cfg(feature = "phong-renderer")]
+        {
+            chunks.insert(
+                "lights".to_string(),
+                include_str!("../phong/shaders/light_shared.frag").to_string(),
+            );
+            chunks.insert(
+                "deferred_objects".to_string(),
+                include_str!("../phong/shaders/deferred_objects_shared.frag").to_string(),
+            );
+        }
+        Self {
+            chunks,
+            defines: Vec::new(),
+        }
+    }
+
+    ///
+    /// Adds a shader chunk which can be included in shader source using `#include "name"`.
+    /// Replaces any existing chunk with the same name.
+    ///
+    pub fn add_chunk(&mut self, name: &str, source: &str) {
+        self.chunks.insert(name.to_string(), source.to_string());
+    }
+
+    ///
+    /// Adds a `#define name value` to the processed shader source. Replaces any existing define with the same name.
+    ///
+    pub fn define<T: std::fmt::Display>(&mut self, name: &str, value: T) {
+        let value = value.to_string();
+        if let Some(define) = self.defines.iter_mut().find(|(n, _)| n == name) {
+            define.1 = value;
+        } else {
+            self.defines.push((name.to_string(), value));
+        }
+    }
+
+    ///
+    /// Enables the feature toggle with the given name, ie. adds `#define name 1` so it can be tested with `#ifdef name`.
+    ///
+    pub fn enable(&mut self, name: &str) {
+        self.define(name, 1);
+    }
+
+    ///
+    /// Removes the define or feature toggle with the given name.
+    ///
+    pub fn undefine(&mut self, name: &str) {
+        self.defines.retain(|(n, _)| n != name);
+    }
+
+    ///
+    /// Returns the version header used for the current target, including the default precision qualifiers on web.
+    ///
+    pub fn version_header() -> &'static str {
+        if cfg!(target_arch = "wasm32") {
+            "#version 300 es\nprecision highp float;\nprecision highp int;\nprecision highp sampler2DArray;\n"
+        } else {
+            "#version 330 core\n"
+        }
+    }
+
+    ///
+    /// Processes the given shader source, that is, resolves the includes, adds the defines and prepends the version header.
+    /// Each chunk is only included once, even if it is included several times.
+    /// A `#version` line in the given source is replaced by the version header for the current target.
+    ///
+    /// # Errors
+    /// Will return an error if an included chunk does not exist or an include directive is malformed.
+    ///
+    pub fn process(&self, source: &str) -> Result<String, Error> {
+        let mut output = Self::version_header().to_string();
+        for (name, value) in self.defines.iter() {
+            output.push_str(&format!("#define {} {}\n", name, value));
+        }
+        let mut included = HashSet::new();
+        self.resolve(source, &mut included, &mut output)?;
+        Ok(output)
+    }
+
+    fn resolve(
+        &self,
+        source: &str,
+        included: &mut HashSet<String>,
+        output: &mut String,
+    ) -> Result<(), Error> {
+        for line in source.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("#version") {
+                continue;
+            }
+            if let Some(rest) = trimmed.strip_prefix("#include") {
+                let rest = rest.trim();
+                let name = rest
+                    .strip_prefix('"')
+                    .and_then(|r| r.strip_suffix('"'))
+                    .or_else(|| rest.strip_prefix('<').and_then(|r| r.strip_suffix('>')))
+                    .ok_or_else(|| Error::ProgramError {
+                        message: format!("Malformed include directive: {}", trimmed),
+                    })?;
+                if included.insert(name.to_string()) {
+                    let chunk = self.chunks.get(name).ok_or_else(|| Error::ProgramError {
+                        message: format!("The included shader chunk {} does not exist", name),
+                    })?;
+                    self.resolve(chunk, included, output)?;
+                }
+                continue;
+            }
+            output.push_str(line);
+            output.push('\n');
+        }
+        Ok(())
+    }
+}
+
+impl Default for ShaderPreprocessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::*;
 use crate::definition::*;
 
@@ -9,6 +9,7 @@ use crate::definition::*;
 pub struct Texture2D {
     context: Context,
     id: crate::context::Texture,
+    _allocation: Allocation,
     width: u32,
     height: u32,
     format: Format,
@@ -23,13 +24,18 @@ impl Texture2D {
         context: &Context,
         cpu_texture: &CPUTexture<T>,
     ) -> Result<Texture2D, Error> {
-        let id = generate(context)?;
-        let number_of_mip_maps = calculate_number_of_mip_maps(
-            cpu_texture.mip_map_filter,
+        if let Some(cpu_texture) = resize_to_power_of_two(context, cpu_texture) {
+            return Self::new(context, &cpu_texture);
+        }
+        check_texture_size(
             cpu_texture.width,
             cpu_texture.height,
-            1,
-        );
+            context.capabilities().max_texture_size,
+        )?;
+        let (mip_map_filter, wrap_s, wrap_t, _) = sampling_parameters(context, cpu_texture);
+        let id = generate(context)?;
+        let number_of_mip_maps =
+            calculate_number_of_mip_maps(mip_map_filter, cpu_texture.width, cpu_texture.height, 1);
         set_parameters(
             context,
             &id,
@@ -39,31 +45,60 @@ impl Texture2D {
             if number_of_mip_maps == 1 {
                 None
             } else {
-                cpu_texture.mip_map_filter
+                mip_map_filter
             },
-            cpu_texture.wrap_s,
-            cpu_texture.wrap_t,
+            wrap_s,
+            wrap_t,
             None,
         );
         context.tex_storage_2d(
             consts::TEXTURE_2D,
             number_of_mip_maps,
-            T::internal_format(cpu_texture.format)?,
+            T::internal_format(cpu_texture.gpu_format())?,
             cpu_texture.width as u32,
             cpu_texture.height as u32,
         );
         let mut tex = Self {
             context: context.clone(),
             id,
+            _allocation: Allocation::new(
+                context,
+                ResourceType::Texture,
+                texture_memory(
+                    cpu_texture.width,
+                    cpu_texture.height,
+                    1,
+                    number_of_mip_maps,
+                    cpu_texture.gpu_format().color_channel_count() as u64
+                        * std::mem::size_of::<T>() as u64,
+                ),
+            ),
             width: cpu_texture.width,
             height: cpu_texture.height,
-            format: cpu_texture.format,
+            format: cpu_texture.gpu_format(),
             number_of_mip_maps,
         };
         tex.fill(&cpu_texture.data)?;
         Ok(tex)
     }
 
+    ///
+    /// Construcs a new texture with the given data like [new](Self::new),
+    /// but first downscales the data using the given filter if it exceeds the [maximum texture size](crate::context::Capabilities::max_texture_size) of this device.
+    ///
+    pub fn new_downscaled<T: TextureDataType>(
+        context: &Context,
+        cpu_texture: &CPUTexture<T>,
+        filter: ResizeFilter,
+    ) -> Result<Texture2D, Error> {
+        let max_size = context.capabilities().max_texture_size;
+        if max_size > 0 && cpu_texture.width.max(cpu_texture.height) > max_size {
+            Self::new(context, &cpu_texture.resize_to_max_size(max_size, filter))
+        } else {
+            Self::new(context, cpu_texture)
+        }
+    }
+
     ///
     /// Fills this texture with the given data.
     ///
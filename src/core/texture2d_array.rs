@@ -0,0 +1,175 @@
+use crate::context::{consts, Allocation, Context, ResourceType};
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// An array of 2D textures, ie. several equally sized images that are transferred to the GPU as one texture
+/// and sampled in a fragment shader as a `sampler2DArray` (see [use_texture_array](crate::Program::use_texture_array)).
+/// Useful for example for terrain splatting, decals and batched materials since only one texture unit is used for all of the images.
+/// For a texture array that can be rendered into, see [ColorTargetTexture2DArray](crate::ColorTargetTexture2DArray).
+///
+pub struct Texture2DArray {
+    context: Context,
+    id: crate::context::Texture,
+    _allocation: Allocation,
+    width: u32,
+    height: u32,
+    depth: u32,
+    format: Format,
+    number_of_mip_maps: u32,
+}
+
+impl Texture2DArray {
+    ///
+    /// Constructs a new texture array with one layer for each of the given textures.
+    /// The filtering and wrapping parameters are taken from the first texture.
+    ///
+    /// # Errors
+    /// Returns an error if no textures are given or if the textures do not have the same size and format.
+    ///
+    pub fn new<T: TextureDataType>(
+        context: &Context,
+        cpu_textures: &[&CPUTexture<T>],
+    ) -> Result<Texture2DArray, Error> {
+        let first = cpu_textures.first().ok_or(Error::TextureError {
+            message: "Cannot create a texture array without any textures.".to_string(),
+        })?;
+        if cpu_textures.iter().any(|t| {
+            t.width != first.width
+                || t.height != first.height
+                || t.gpu_format() != first.gpu_format()
+        }) {
+            return Err(Error::TextureError {
+                message: "All textures in a texture array must have the same size and format."
+                    .to_string(),
+            });
+        }
+        let depth = cpu_textures.len() as u32;
+        let capabilities = context.capabilities();
+        check_texture_size(first.width, first.height, capabilities.max_texture_size)?;
+        check_texture_layers(depth, capabilities.max_array_texture_layers)?;
+        let (mip_map_filter, wrap_s, wrap_t, _) = sampling_parameters(context, first);
+        let id = generate(context)?;
+        let number_of_mip_maps =
+            calculate_number_of_mip_maps(mip_map_filter, first.width, first.height, 1);
+        set_parameters(
+            context,
+            &id,
+            consts::TEXTURE_2D_ARRAY,
+            first.min_filter,
+            first.mag_filter,
+            if number_of_mip_maps == 1 {
+                None
+            } else {
+                mip_map_filter
+            },
+            wrap_s,
+            wrap_t,
+            None,
+        );
+        context.bind_texture(consts::TEXTURE_2D_ARRAY, &id);
+        context.tex_storage_3d(
+            consts::TEXTURE_2D_ARRAY,
+            number_of_mip_maps,
+            T::internal_format(first.gpu_format())?,
+            first.width,
+            first.height,
+            depth,
+        );
+        let mut texture = Self {
+            context: context.clone(),
+            id,
+            _allocation: Allocation::new(
+                context,
+                ResourceType::Texture,
+                texture_memory(
+                    first.width,
+                    first.height,
+                    depth,
+                    number_of_mip_maps,
+                    first.gpu_format().color_channel_count() as u64
+                        * std::mem::size_of::<T>() as u64,
+                ),
+            ),
+            width: first.width,
+            height: first.height,
+            depth,
+            format: first.gpu_format(),
+            number_of_mip_maps,
+        };
+        for (layer, cpu_texture) in cpu_textures.iter().enumerate() {
+            texture.fill_layer_without_mip_maps(layer as u32, &cpu_texture.data)?;
+        }
+        texture.generate_mip_maps();
+        Ok(texture)
+    }
+
+    ///
+    /// Fills the layer with the given index with the given data.
+    ///
+    /// # Errors
+    /// Return an error if the layer is out of range or if the length of the data array is smaller or bigger than the necessary number of bytes to fill the entire layer.
+    ///
+    pub fn fill_layer<T: TextureDataType>(&mut self, layer: u32, data: &[T]) -> Result<(), Error> {
+        self.fill_layer_without_mip_maps(layer, data)?;
+        self.generate_mip_maps();
+        Ok(())
+    }
+
+    fn fill_layer_without_mip_maps<T: TextureDataType>(
+        &mut self,
+        layer: u32,
+        data: &[T],
+    ) -> Result<(), Error> {
+        if layer >= self.depth {
+            return Err(Error::TextureError {
+                message: format!(
+                    "Cannot fill layer {} of a texture array with {} layers.",
+                    layer, self.depth
+                ),
+            });
+        }
+        check_data_length(self.width, self.height, 1, self.format, data.len())?;
+        self.context
+            .bind_texture(consts::TEXTURE_2D_ARRAY, &self.id);
+        T::fill_layer(
+            &self.context,
+            consts::TEXTURE_2D_ARRAY,
+            self.width,
+            self.height,
+            layer,
+            self.format,
+            data,
+        );
+        Ok(())
+    }
+
+    pub(crate) fn generate_mip_maps(&self) {
+        if self.number_of_mip_maps > 1 {
+            self.context
+                .bind_texture(consts::TEXTURE_2D_ARRAY, &self.id);
+            self.context.generate_mipmap(consts::TEXTURE_2D_ARRAY);
+        }
+    }
+}
+
+impl TextureArray for Texture2DArray {
+    fn bind(&self, location: u32) {
+        bind_at(&self.context, &self.id, consts::TEXTURE_2D_ARRAY, location);
+    }
+    fn width(&self) -> u32 {
+        self.width
+    }
+    fn height(&self) -> u32 {
+        self.height
+    }
+    fn depth(&self) -> u32 {
+        self.depth
+    }
+}
+
+impl Drop for Texture2DArray {
+    fn drop(&mut self) {
+        self.context.delete_texture(&self.id);
+    }
+}
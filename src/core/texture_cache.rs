@@ -0,0 +1,78 @@
+use crate::core::*;
+use crate::definition::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+///
+/// A content-addressed cache of 2D textures, so that identical [CPU textures](crate::CPUTexture), for example the same image referenced by several materials,
+/// are only uploaded to the GPU once and then shared.
+/// The textures are identified by a hash of the data and all of the other properties of the CPU texture.
+///
+pub struct TextureCache {
+    context: Context,
+    textures: HashMap<u64, Rc<Texture2D>>,
+}
+
+impl TextureCache {
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            textures: HashMap::new(),
+        }
+    }
+
+    ///
+    /// Returns the texture created from an identical CPU texture if it exists in the cache,
+    /// otherwise creates a new texture from the given CPU texture and adds it to the cache.
+    ///
+    pub fn texture(&mut self, cpu_texture: &CPUTexture<u8>) -> Result<Rc<Texture2D>, Error> {
+        let key = content_hash(cpu_texture);
+        if let Some(texture) = self.textures.get(&key) {
+            return Ok(texture.clone());
+        }
+        let texture = Rc::new(Texture2D::new(&self.context, cpu_texture)?);
+        self.textures.insert(key, texture.clone());
+        Ok(texture)
+    }
+
+    ///
+    /// Returns the number of textures in the cache.
+    ///
+    pub fn len(&self) -> usize {
+        self.textures.len()
+    }
+
+    ///
+    /// Removes the textures which are not used outside of the cache.
+    ///
+    pub fn remove_unused(&mut self) {
+        self.textures
+            .retain(|_, texture| Rc::strong_count(texture) > 1);
+    }
+
+    ///
+    /// Removes all textures from the cache. Textures still used elsewhere are not deleted.
+    ///
+    pub fn clear(&mut self) {
+        self.textures.clear();
+    }
+}
+
+fn content_hash(cpu_texture: &CPUTexture<u8>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    cpu_texture.data.hash(&mut hasher);
+    cpu_texture.width.hash(&mut hasher);
+    cpu_texture.height.hash(&mut hasher);
+    cpu_texture.depth.hash(&mut hasher);
+    cpu_texture.format.hash(&mut hasher);
+    cpu_texture.is_srgb.hash(&mut hasher);
+    cpu_texture.min_filter.hash(&mut hasher);
+    cpu_texture.mag_filter.hash(&mut hasher);
+    cpu_texture.mip_map_filter.hash(&mut hasher);
+    cpu_texture.wrap_s.hash(&mut hasher);
+    cpu_texture.wrap_t.hash(&mut hasher);
+    cpu_texture.wrap_r.hash(&mut hasher);
+    hasher.finish()
+}
@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::*;
 use crate::definition::*;
 
@@ -8,6 +8,7 @@ use crate::definition::*;
 pub struct TextureCubeMap {
     context: Context,
     id: crate::context::Texture,
+    _allocation: Allocation,
     width: u32,
     height: u32,
     format: Format,
@@ -19,13 +20,18 @@ impl TextureCubeMap {
         context: &Context,
         cpu_texture: &CPUTexture<T>,
     ) -> Result<TextureCubeMap, Error> {
-        let id = generate(context)?;
-        let number_of_mip_maps = calculate_number_of_mip_maps(
-            cpu_texture.mip_map_filter,
+        if let Some(cpu_texture) = resize_to_power_of_two(context, cpu_texture) {
+            return Self::new(context, &cpu_texture);
+        }
+        check_texture_size(
             cpu_texture.width,
             cpu_texture.height,
-            1,
-        );
+            context.capabilities().max_cube_map_texture_size,
+        )?;
+        let (mip_map_filter, wrap_s, wrap_t, wrap_r) = sampling_parameters(context, cpu_texture);
+        let id = generate(context)?;
+        let number_of_mip_maps =
+            calculate_number_of_mip_maps(mip_map_filter, cpu_texture.width, cpu_texture.height, 1);
         set_parameters(
             context,
             &id,
@@ -35,32 +41,128 @@ impl TextureCubeMap {
             if number_of_mip_maps == 1 {
                 None
             } else {
-                cpu_texture.mip_map_filter
+                mip_map_filter
             },
-            cpu_texture.wrap_s,
-            cpu_texture.wrap_t,
-            Some(cpu_texture.wrap_r),
+            wrap_s,
+            wrap_t,
+            Some(wrap_r),
         );
         context.bind_texture(consts::TEXTURE_CUBE_MAP, &id);
         context.tex_storage_2d(
             consts::TEXTURE_CUBE_MAP,
             number_of_mip_maps,
-            T::internal_format(cpu_texture.format)?,
+            T::internal_format(cpu_texture.gpu_format())?,
             cpu_texture.width,
             cpu_texture.height,
         );
         let mut texture = Self {
             context: context.clone(),
             id,
+            _allocation: Allocation::new(
+                context,
+                ResourceType::Texture,
+                texture_memory(
+                    cpu_texture.width,
+                    cpu_texture.height,
+                    6,
+                    number_of_mip_maps,
+                    cpu_texture.gpu_format().color_channel_count() as u64
+                        * std::mem::size_of::<T>() as u64,
+                ),
+            ),
             width: cpu_texture.width,
             height: cpu_texture.height,
-            format: cpu_texture.format,
+            format: cpu_texture.gpu_format(),
             number_of_mip_maps,
         };
         texture.fill(&cpu_texture.data)?;
         Ok(texture)
     }
 
+    ///
+    /// Creates a new cube map with the given data like [new](Self::new),
+    /// but first downscales the data using the given filter if it exceeds the [maximum cube map size](crate::context::Capabilities::max_cube_map_texture_size) of this device.
+    ///
+    pub fn new_downscaled<T: TextureDataType>(
+        context: &Context,
+        cpu_texture: &CPUTexture<T>,
+        filter: ResizeFilter,
+    ) -> Result<TextureCubeMap, Error> {
+        let max_size = context.capabilities().max_cube_map_texture_size;
+        if max_size > 0 && cpu_texture.width.max(cpu_texture.height) > max_size {
+            Self::new(context, &cpu_texture.resize_to_max_size(max_size, filter))
+        } else {
+            Self::new(context, cpu_texture)
+        }
+    }
+
+    ///
+    /// Creates a new cube map without any content, which can be rendered into using [write](Self::write),
+    /// for example when prefiltering an environment map for image based lighting where each mip level is rendered separately.
+    ///
+    pub fn new_empty<T: TextureDataType>(
+        context: &Context,
+        width: u32,
+        height: u32,
+        min_filter: Interpolation,
+        mag_filter: Interpolation,
+        mip_map_filter: Option<Interpolation>,
+        wrap_s: Wrapping,
+        wrap_t: Wrapping,
+        wrap_r: Wrapping,
+        format: Format,
+    ) -> Result<TextureCubeMap, Error> {
+        check_texture_size(
+            width,
+            height,
+            context.capabilities().max_cube_map_texture_size,
+        )?;
+        let id = generate(context)?;
+        let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height, 1);
+        set_parameters(
+            context,
+            &id,
+            consts::TEXTURE_CUBE_MAP,
+            min_filter,
+            mag_filter,
+            if number_of_mip_maps == 1 {
+                None
+            } else {
+                mip_map_filter
+            },
+            wrap_s,
+            wrap_t,
+            Some(wrap_r),
+        );
+        context.bind_texture(consts::TEXTURE_CUBE_MAP, &id);
+        context.tex_storage_2d(
+            consts::TEXTURE_CUBE_MAP,
+            number_of_mip_maps,
+            T::internal_format(format)?,
+            width,
+            height,
+        );
+        Ok(Self {
+            context: context.clone(),
+            id,
+            _allocation: Allocation::new(
+                context,
+                ResourceType::Texture,
+                texture_memory(
+                    width,
+                    height,
+                    6,
+                    number_of_mip_maps,
+                    format.color_channel_count() as u64 * std::mem::size_of::<T>() as u64,
+                ),
+            ),
+            width,
+            height,
+            format,
+            number_of_mip_maps,
+        })
+    }
+
     // data contains 6 images in the following order; right, left, top, bottom, front, back
     pub fn fill<T: TextureDataType>(&mut self, data: &[T]) -> Result<(), Error> {
         let offset = data.len() / 6;
@@ -81,7 +183,81 @@ impl TextureCubeMap {
         Ok(())
     }
 
-    pub(crate) fn generate_mip_maps(&self) {
+    ///
+    /// Renders whatever rendered in the `render` closure into the given side of the cube map at the given mip level,
+    /// where the sides are in the order right, left, top, bottom, front and back.
+    /// Before writing, the side is cleared based on the given clear state.
+    /// The size of the mip level is given by [mip_level_size](Self::mip_level_size) and the viewport used when rendering should have that size.
+    /// As opposed to writing to other textures, the mip maps are not generated afterwards, so that each mip level can be rendered separately,
+    /// call [generate_mip_maps](Self::generate_mip_maps) to generate them from the first mip level.
+    ///
+    /// # Errors
+    /// Will return an error if the side is not in the range [0..5] or the mip level does not exist.
+    ///
+    pub fn write<F: FnOnce() -> Result<(), Error>>(
+        &self,
+        side: u32,
+        mip_level: u32,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        if side >= 6 {
+            Err(Error::TextureError {
+                message: format!("The cube map side {} is not in the range [0..5]", side),
+            })?;
+        }
+        if mip_level >= self.number_of_mip_maps {
+            Err(Error::TextureError {
+                message: format!(
+                    "The mip level {} does not exist, the cube map has {} mip levels",
+                    mip_level, self.number_of_mip_maps
+                ),
+            })?;
+        }
+        let id = new_framebuffer(&self.context)?;
+        self.context
+            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, Some(&id));
+        self.context.draw_buffers(&[consts::COLOR_ATTACHMENT0]);
+        self.context.framebuffer_texture_2d(
+            consts::FRAMEBUFFER,
+            consts::COLOR_ATTACHMENT0,
+            consts::TEXTURE_CUBE_MAP_POSITIVE_X + side,
+            &self.id,
+            mip_level,
+        );
+        clear(
+            &self.context,
+            &ClearState {
+                depth: None,
+                ..clear_state
+            },
+        );
+        let result = render();
+        self.context.delete_framebuffer(Some(&id));
+        result
+    }
+
+    ///
+    /// Returns the number of mip levels of the cube map, which is 1 if the cube map has no mip maps.
+    ///
+    pub fn number_of_mip_maps(&self) -> u32 {
+        self.number_of_mip_maps
+    }
+
+    ///
+    /// Returns the width and height of the given mip level.
+    ///
+    pub fn mip_level_size(&self, mip_level: u32) -> (u32, u32) {
+        (
+            (self.width >> mip_level).max(1),
+            (self.height >> mip_level).max(1),
+        )
+    }
+
+    ///
+    /// Generates the mip maps from the first mip level, for example after [writing](Self::write) to the first mip level.
+    ///
+    pub fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.context
                 .bind_texture(consts::TEXTURE_CUBE_MAP, &self.id);
@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::Error;
 
 ///
@@ -8,23 +8,34 @@ use crate::core::Error;
 pub struct UniformBuffer {
     context: Context,
     id: crate::context::Buffer,
+    _allocation: Allocation,
     offsets: Vec<usize>,
     data: Vec<f32>,
 }
 
 impl UniformBuffer {
     pub fn new(context: &Context, sizes: &[u32]) -> Result<UniformBuffer, Error> {
-        let id = context.create_buffer().unwrap();
-
         let mut offsets = Vec::new();
         let mut length = 0;
         for size in sizes {
             offsets.push(length);
             length += *size as usize;
         }
+        let max_size = context.capabilities().max_uniform_block_size as usize;
+        if max_size > 0 && length * 4 > max_size {
+            Err(Error::BufferError {
+                message: format!(
+                    "The uniform buffer has a size of {} bytes which exceeds the maximum uniform block size of {} bytes supported by this device",
+                    length * 4,
+                    max_size
+                ),
+            })?;
+        }
+        let id = context.create_buffer().unwrap();
         Ok(UniformBuffer {
             context: context.clone(),
             id,
+            _allocation: Allocation::new(context, ResourceType::UniformBuffer, length as u64 * 4),
             offsets,
             data: vec![0.0; length as usize],
         })
@@ -36,6 +47,16 @@ impl UniformBuffer {
     }
 
     pub fn update(&mut self, index: u32, data: &[f32]) -> Result<(), Error> {
+        self.set(index, data)?;
+        self.send();
+        Ok(())
+    }
+
+    ///
+    /// Sets the data for the given index without transferring it to the GPU, which is done by [send](Self::send),
+    /// so that several updates only result in one transfer.
+    ///
+    pub(crate) fn set(&mut self, index: u32, data: &[f32]) -> Result<(), Error> {
         let (offset, length) = self.offset_length(index as usize)?;
         if data.len() != length {
             return Err(Error::BufferError {
@@ -49,11 +70,16 @@ impl UniformBuffer {
         }
         self.data
             .splice(offset..offset + length, data.iter().cloned());
-        self.send();
-        //TODO: Send to GPU (contextBufferSubData)
         Ok(())
     }
 
+    ///
+    /// Returns the size of the buffer in bytes.
+    ///
+    pub fn size_in_bytes(&self) -> usize {
+        self.data.len() * std::mem::size_of::<f32>()
+    }
+
     pub fn get(&self, index: u32) -> Result<&[f32], Error> {
         let (offset, length) = self.offset_length(index as usize)?;
         Ok(&self.data[offset..offset + length])
@@ -78,10 +104,36 @@ impl UniformBuffer {
         Ok((offset, length))
     }
 
-    fn send(&self) {
+    pub(crate) fn send(&self) {
+        self.send_data(&self.data);
+    }
+
+    ///
+    /// Transfers the data to the GPU like [send](Self::send), but with the data for the given index replaced by the given data,
+    /// which is not stored in this buffer.
+    ///
+    pub(crate) fn send_with(&self, index: u32, data: &[f32]) -> Result<(), Error> {
+        let (offset, length) = self.offset_length(index as usize)?;
+        if data.len() != length {
+            return Err(Error::BufferError {
+                message: format!(
+                    "The uniform buffer data for index {} has length {} but it must be {}.",
+                    index,
+                    data.len(),
+                    length
+                ),
+            });
+        }
+        let mut all_data = self.data.clone();
+        all_data.splice(offset..offset + length, data.iter().cloned());
+        self.send_data(&all_data);
+        Ok(())
+    }
+
+    fn send_data(&self, data: &[f32]) {
         self.context.bind_buffer(consts::UNIFORM_BUFFER, &self.id);
         self.context
-            .buffer_data_f32(consts::UNIFORM_BUFFER, &self.data, consts::STATIC_DRAW);
+            .buffer_data_f32(consts::UNIFORM_BUFFER, data, consts::STATIC_DRAW);
         self.context.unbind_buffer(consts::UNIFORM_BUFFER);
     }
 }
@@ -1,4 +1,4 @@
-use crate::context::{consts, Context};
+use crate::context::{consts, Allocation, Context, ResourceType};
 use crate::core::{Error, VertexBufferDataType};
 
 ///
@@ -8,6 +8,7 @@ use crate::core::{Error, VertexBufferDataType};
 pub struct VertexBuffer {
     context: Context,
     id: crate::context::Buffer,
+    allocation: Allocation,
     count: usize,
     data_type: u32,
 }
@@ -20,6 +21,7 @@ impl VertexBuffer {
         Ok(VertexBuffer {
             context: context.clone(),
             id: context.create_buffer().unwrap(),
+            allocation: Allocation::new(context, ResourceType::VertexBuffer, 0),
             count: 0,
             data_type: consts::FLOAT,
         })
@@ -57,6 +59,8 @@ impl VertexBuffer {
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ARRAY_BUFFER);
         self.count = data.len();
+        self.allocation
+            .resize((data.len() * std::mem::size_of::<T>()) as u64);
     }
 
     ///
@@ -91,6 +95,38 @@ impl VertexBuffer {
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ARRAY_BUFFER);
         self.count = data.len();
+        self.allocation
+            .resize((data.len() * std::mem::size_of::<T>()) as u64);
+    }
+
+    ///
+    /// Overwrites the part of the buffer starting at the given offset (measured in number of elements) with the given data
+    /// without reallocating the buffer, which is faster than filling the buffer when only the content changes.
+    ///
+    /// # Errors
+    /// Will return an error if the buffer does not contain `f32` data or if the data does not fit inside the buffer.
+    ///
+    pub fn update_with(&mut self, offset: usize, data: &[f32]) -> Result<(), Error> {
+        if self.data_type != consts::FLOAT {
+            return Err(Error::BufferError {
+                message: "Only vertex buffers containing f32 data can be updated.".to_string(),
+            });
+        }
+        if offset + data.len() > self.count {
+            return Err(Error::BufferError {
+                message: format!(
+                    "The data of length {} at offset {} does not fit in the vertex buffer of length {}.",
+                    data.len(),
+                    offset,
+                    self.count
+                ),
+            });
+        }
+        self.bind();
+        self.context
+            .buffer_sub_data_f32(consts::ARRAY_BUFFER, offset as u32, data);
+        self.context.unbind_buffer(consts::ARRAY_BUFFER);
+        Ok(())
     }
 
     ///
@@ -114,3 +150,57 @@ impl Drop for VertexBuffer {
         self.context.delete_buffer(&self.id);
     }
 }
+
+///
+/// Describes one attribute, for example the position or the normal, in an interleaved [vertex buffer](crate::VertexBuffer).
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexAttribute {
+    /// The name of the attribute in the shader program.
+    pub name: String,
+    /// The number of components of the attribute, for example 3 for a `vec3`.
+    pub size: u32,
+    /// The offset of the attribute from the start of each vertex, measured in number of components.
+    pub offset: u32,
+}
+
+///
+/// Describes how several attributes are interleaved in one [vertex buffer](crate::VertexBuffer),
+/// ie. the data for each vertex is stored consecutively, for example position, normal, position, normal, etc.
+/// Use it with [Program::use_attribute_interleaved](crate::Program::use_attribute_interleaved).
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VertexLayout {
+    /// The attributes for each vertex.
+    pub attributes: Vec<VertexAttribute>,
+    /// The distance between the start of two consecutive vertices, measured in number of components.
+    pub stride: u32,
+}
+
+impl VertexLayout {
+    ///
+    /// Creates a new vertex layout without any attributes.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Adds an attribute with the given name and number of components after the existing attributes.
+    ///
+    pub fn push(&mut self, name: &str, size: u32) {
+        self.attributes.push(VertexAttribute {
+            name: name.to_string(),
+            size,
+            offset: self.stride,
+        });
+        self.stride += size;
+    }
+
+    ///
+    /// Returns the attribute with the given name if it is part of this layout.
+    ///
+    pub fn attribute(&self, name: &str) -> Option<&VertexAttribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+}
@@ -3,11 +3,40 @@
 //! before transferring it to the GPU.
 //! Can be constructed manually or loaded via [io](crate::io).
 //!
+//! Except for the `Geometry` trait, the `cpu_scene_channel` and the font and vector graphics definitions, the definitions do not depend on a graphics context
+//! and only use `core` and `alloc`, so they can be used for asset preprocessing without a window or GPU by disabling the default `std` feature.
+//! Building for a target without the standard library is not yet possible since the [math](crate::math) module depends on [cgmath](https://crates.io/crates/cgmath)
+//! which requires the standard library.
+//!
 
 mod cpu_mesh;
 #[doc(inline)]
 pub use cpu_mesh::*;
 
+mod collision_shape;
+#[doc(inline)]
+pub use collision_shape::*;
+
+mod mesh_cleanup;
+#[doc(inline)]
+pub use mesh_cleanup::*;
+
+mod csg;
+
+mod sweep;
+
+mod bounding_volume_hierarchy;
+#[doc(inline)]
+pub use bounding_volume_hierarchy::*;
+
+mod ambient_occlusion;
+#[doc(inline)]
+pub use ambient_occlusion::*;
+
+mod uv_unwrap;
+#[doc(inline)]
+pub use uv_unwrap::*;
+
 mod cpu_material;
 #[doc(inline)]
 pub use cpu_material::*;
@@ -16,6 +45,36 @@ mod cpu_texture;
 #[doc(inline)]
 pub use cpu_texture::*;
 
+mod cpu_scene;
+#[doc(inline)]
+pub use cpu_scene::*;
+
+#[cfg(feature = "std")]
 mod geometry;
 #[doc(inline)]
+#[cfg(feature = "std")]
 pub use geometry::*;
+
+mod skeleton;
+#[doc(inline)]
+pub use skeleton::*;
+
+mod animation;
+#[doc(inline)]
+pub use animation::*;
+
+mod texture_atlas;
+#[doc(inline)]
+pub use texture_atlas::*;
+
+#[cfg(feature = "sdf-text")]
+mod sdf_font;
+#[doc(inline)]
+#[cfg(feature = "sdf-text")]
+pub use sdf_font::*;
+
+#[cfg(feature = "vector-graphics")]
+mod vector_graphics;
+#[doc(inline)]
+#[cfg(feature = "vector-graphics")]
+pub use vector_graphics::*;
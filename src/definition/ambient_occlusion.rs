@@ -0,0 +1,223 @@
+use crate::definition::*;
+use crate::math::*;
+use crate::Error;
+use alloc::{string::ToString, vec};
+
+///
+/// Options for baking ambient occlusion, see [CPUMesh::bake_ambient_occlusion] and [CPUMesh::bake_ambient_occlusion_texture].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AmbientOcclusionOptions {
+    /// The number of rays traced in the hemisphere around the normal at each vertex or texel.
+    pub sample_count: u32,
+    /// The maximum distance at which other surfaces occlude.
+    pub max_distance: f32,
+    /// The distance the rays start from the surface along the normal to avoid hitting the surface itself.
+    pub bias: f32,
+}
+
+impl Default for AmbientOcclusionOptions {
+    fn default() -> Self {
+        Self {
+            sample_count: 64,
+            max_distance: 1.0,
+            bias: 0.001,
+        }
+    }
+}
+
+impl CPUMesh {
+    ///
+    /// Bakes ambient occlusion into the vertex colors of this mesh by tracing rays in the hemisphere around the normal at each vertex
+    /// and counting how many of them hit the triangles in the given bounding volume hierarchy, which should contain this mesh
+    /// and the rest of the static scene.
+    /// The existing vertex colors are multiplied by the ambient occlusion, which is 1 if nothing occludes the vertex.
+    /// If the mesh has no normals, the normals are computed.
+    ///
+    pub fn bake_ambient_occlusion(
+        &mut self,
+        scene: &BoundingVolumeHierarchy,
+        options: &AmbientOcclusionOptions,
+    ) {
+        if self.normals.is_none() {
+            self.compute_normals();
+        }
+        let vertex_count = self.positions.len() / 3;
+        let mut colors = self
+            .colors
+            .take()
+            .unwrap_or_else(|| vec![255; 4 * vertex_count]);
+        let normals = self.normals.as_ref().unwrap();
+        for i in 0..vertex_count {
+            let occlusion = ambient_occlusion(
+                scene,
+                vec3(
+                    self.positions[3 * i],
+                    self.positions[3 * i + 1],
+                    self.positions[3 * i + 2],
+                ),
+                vec3(normals[3 * i], normals[3 * i + 1], normals[3 * i + 2]),
+                i,
+                options,
+            );
+            for c in colors[4 * i..4 * i + 3].iter_mut() {
+                *c = (*c as f32 * occlusion).round() as u8;
+            }
+        }
+        self.colors = Some(colors);
+    }
+
+    ///
+    /// Bakes ambient occlusion into a single channel texture with the given width and height, laid out according to the uv coordinates of this mesh,
+    /// by tracing rays in the hemisphere around the normal at each texel and counting how many of them hit the triangles in the given
+    /// bounding volume hierarchy, which should contain this mesh and the rest of the static scene.
+    /// The ambient occlusion is 1 (255) if nothing occludes the texel.
+    /// The baked texels are extended `padding` texels outside the uv charts to avoid seams, and the remaining texels are 1 (255).
+    ///
+    /// # Errors
+    /// Will return an error if the mesh has no uv coordinates.
+    ///
+    pub fn bake_ambient_occlusion_texture(
+        &self,
+        scene: &BoundingVolumeHierarchy,
+        width: u32,
+        height: u32,
+        padding: u32,
+        options: &AmbientOcclusionOptions,
+    ) -> Result<CPUTexture<u8>, Error> {
+        let uvs = self.uvs.as_ref().ok_or(Error::MeshError {
+            message:
+                "Cannot bake ambient occlusion into a texture for a mesh without uv coordinates."
+                    .to_string(),
+        })?;
+        let computed_normals;
+        let normals = match self.normals {
+            Some(ref normals) => normals,
+            None => {
+                let mut mesh = CPUMesh {
+                    positions: self.positions.clone(),
+                    indices: self
+                        .indices
+                        .as_ref()
+                        .map(|indices| Indices::U32(indices.into_u32())),
+                    ..Default::default()
+                };
+                mesh.compute_normals();
+                computed_normals = mesh.normals.unwrap();
+                &computed_normals
+            }
+        };
+        let indices = self
+            .indices
+            .as_ref()
+            .map(|indices| indices.into_u32())
+            .unwrap_or_else(|| (0..self.positions.len() as u32 / 3).collect());
+        let position = |i: usize| {
+            vec3(
+                self.positions[3 * i],
+                self.positions[3 * i + 1],
+                self.positions[3 * i + 2],
+            )
+        };
+        let normal = |i: usize| vec3(normals[3 * i], normals[3 * i + 1], normals[3 * i + 2]);
+        // The texel coordinates of a vertex, where the first row of the texture is at v = 1
+        let texel = |i: usize| {
+            vec2(
+                uvs[2 * i] * width as f32,
+                (1.0 - uvs[2 * i + 1]) * height as f32,
+            )
+        };
+
+        let mut data = vec![0u8; 4 * width as usize * height as usize];
+        for triangle in indices.chunks_exact(3) {
+            let (a, b, c) = (
+                triangle[0] as usize,
+                triangle[1] as usize,
+                triangle[2] as usize,
+            );
+            let (ta, tb, tc) = (texel(a), texel(b), texel(c));
+            let area = (tb - ta).perp_dot(tc - ta);
+            if area.abs() < core::f32::EPSILON {
+                continue;
+            }
+            let x0 = ta.x.min(tb.x).min(tc.x).floor().max(0.0) as u32;
+            let x1 = (ta.x.max(tb.x).max(tc.x).ceil() as u32).min(width);
+            let y0 = ta.y.min(tb.y).min(tc.y).floor().max(0.0) as u32;
+            let y1 = (ta.y.max(tb.y).max(tc.y).ceil() as u32).min(height);
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let p = vec2(x as f32 + 0.5, y as f32 + 0.5);
+                    let wb = (p - ta).perp_dot(tc - ta) / area;
+                    let wc = (tb - ta).perp_dot(p - ta) / area;
+                    let wa = 1.0 - wb - wc;
+                    if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                        continue;
+                    }
+                    let mut n = wa * normal(a) + wb * normal(b) + wc * normal(c);
+                    if n.magnitude2() < core::f32::EPSILON {
+                        n = (position(b) - position(a)).cross(position(c) - position(a));
+                    }
+                    let i = (y * width + x) as usize;
+                    let occlusion = ambient_occlusion(
+                        scene,
+                        wa * position(a) + wb * position(b) + wc * position(c),
+                        n.normalize(),
+                        i,
+                        options,
+                    );
+                    let value = (occlusion * 255.0).round() as u8;
+                    data[4 * i..4 * i + 4].copy_from_slice(&[value, value, value, 255]);
+                }
+            }
+        }
+        dilate(&mut data, width as usize, height as usize, padding);
+        Ok(CPUTexture {
+            data: data
+                .chunks_exact(4)
+                .map(|texel| if texel[3] > 0 { texel[0] } else { 255 })
+                .collect(),
+            width,
+            height,
+            format: Format::R,
+            ..Default::default()
+        })
+    }
+}
+
+// Returns the fraction of cosine weighted rays in the hemisphere around the normal which are not occluded
+fn ambient_occlusion(
+    scene: &BoundingVolumeHierarchy,
+    position: Vec3,
+    normal: Vec3,
+    index: usize,
+    options: &AmbientOcclusionOptions,
+) -> f32 {
+    let normal = if normal.magnitude2() > 0.0 {
+        normal.normalize()
+    } else {
+        return 1.0;
+    };
+    let tangent = if normal.x.abs() > 0.9 {
+        normal.cross(vec3(0.0, 1.0, 0.0)).normalize()
+    } else {
+        normal.cross(vec3(1.0, 0.0, 0.0)).normalize()
+    };
+    let bitangent = normal.cross(tangent);
+    let origin = position + options.bias * normal;
+    let sample_count = options.sample_count.max(1);
+    // A stratified sequence which is rotated differently for each vertex or texel to turn banding into noise
+    let rotation = (index as f32 * 0.754_877_7).fract();
+    let mut visible = 0;
+    for i in 0..sample_count {
+        let u1 = (i as f32 + 0.5) / sample_count as f32;
+        let u2 = (i as f32 * 0.618_034 + rotation).fract();
+        let r = u1.sqrt();
+        let phi = 2.0 * core::f32::consts::PI * u2;
+        let direction =
+            r * phi.cos() * tangent + r * phi.sin() * bitangent + (1.0 - u1).sqrt() * normal;
+        if !scene.is_occluded(origin, direction, options.max_distance) {
+            visible += 1;
+        }
+    }
+    visible as f32 / sample_count as f32
+}
@@ -0,0 +1,458 @@
+use crate::definition::*;
+use crate::math::*;
+use crate::Error;
+use alloc::collections::BTreeMap;
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+///
+/// The keyframes of one joint in an [AnimationClip]. The keyframes are given as pairs of a time in seconds and the transform of the joint relative to its parent,
+/// sorted by time. The transform is interpolated linearly between the keyframes.
+///
+#[derive(Debug, Clone)]
+pub struct JointTrack {
+    /// The index of the animated joint in the [Skeleton].
+    pub joint: usize,
+    /// The keyframes sorted by time.
+    pub keyframes: Vec<(f32, JointTransform)>,
+}
+
+impl JointTrack {
+    ///
+    /// Returns the transform of the joint at the given time in seconds.
+    ///
+    pub fn sample(&self, time: f32) -> JointTransform {
+        let index = self.keyframes.iter().position(|(t, _)| *t > time);
+        match index {
+            Some(0) => self.keyframes[0].1,
+            Some(i) => {
+                let (t0, a) = &self.keyframes[i - 1];
+                let (t1, b) = &self.keyframes[i];
+                a.interpolate(b, (time - t0) / (t1 - t0))
+            }
+            None => self
+                .keyframes
+                .last()
+                .map(|(_, transform)| *transform)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+///
+/// An animation of a [Skeleton], for example walking or jumping, given by keyframes for each animated joint.
+///
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    /// The name of the clip.
+    pub name: String,
+    /// The keyframes of the animated joints.
+    pub tracks: Vec<JointTrack>,
+    /// The duration of the clip in seconds.
+    pub duration: f32,
+}
+
+impl AnimationClip {
+    ///
+    /// Creates a new clip with the given tracks, the duration is the time of the last keyframe.
+    ///
+    pub fn new(name: &str, tracks: Vec<JointTrack>) -> Self {
+        let duration = tracks
+            .iter()
+            .filter_map(|track| track.keyframes.last().map(|(t, _)| *t))
+            .fold(0.0, f32::max);
+        Self {
+            name: name.to_string(),
+            tracks,
+            duration,
+        }
+    }
+
+    ///
+    /// Sets the transforms of the animated joints in the given pose to their transforms at the given time in seconds.
+    /// The transforms of the joints which are not animated by this clip are not changed.
+    ///
+    pub fn sample(&self, time: f32, pose: &mut [JointTransform]) {
+        for track in self.tracks.iter() {
+            if let Some(transform) = pose.get_mut(track.joint) {
+                *transform = track.sample(time);
+            }
+        }
+    }
+
+    fn clip_time(&self, time: f32, looping: bool) -> f32 {
+        if self.duration <= 0.0 {
+            0.0
+        } else if looping {
+            time.rem_euclid(self.duration)
+        } else {
+            time.min(self.duration)
+        }
+    }
+}
+
+///
+/// Blends the two poses, where a factor of 0 gives the first pose and a factor of 1 gives the second pose, and writes the result to the first pose.
+///
+pub fn blend_poses(pose: &mut [JointTransform], other: &[JointTransform], factor: f32) {
+    for (transform, other) in pose.iter_mut().zip(other.iter()) {
+        *transform = transform.interpolate(other, factor);
+    }
+}
+
+///
+/// Adds the difference between the additive pose and the reference pose, weighted by the given weight, to the pose.
+/// Used for additive animations, for example breathing or leaning, which are layered on top of another animation.
+///
+pub fn add_pose(
+    pose: &mut [JointTransform],
+    additive: &[JointTransform],
+    reference: &[JointTransform],
+    weight: f32,
+) {
+    for ((transform, additive), reference) in
+        pose.iter_mut().zip(additive.iter()).zip(reference.iter())
+    {
+        let delta = JointTransform {
+            translation: additive.translation - reference.translation,
+            rotation: reference.rotation.invert() * additive.rotation,
+            scale: vec3(
+                additive.scale.x / reference.scale.x,
+                additive.scale.y / reference.scale.y,
+                additive.scale.z / reference.scale.z,
+            ),
+        };
+        let delta = JointTransform::identity().interpolate(&delta, weight);
+        transform.translation += delta.translation;
+        transform.rotation = transform.rotation * delta.rotation;
+        transform.scale = vec3(
+            transform.scale.x * delta.scale.x,
+            transform.scale.y * delta.scale.y,
+            transform.scale.z * delta.scale.z,
+        );
+    }
+}
+
+///
+/// The parameters of an [AnimationStateMachine] which are used by the conditions of the transitions between states.
+///
+#[derive(Debug, Clone, Default)]
+pub struct AnimationParameters {
+    values: BTreeMap<String, f32>,
+    /// The time in seconds the current state has been playing.
+    pub state_time: f32,
+    /// The number of times the clip of the current state has been played, for example 0.5 when half of the clip has been played.
+    pub state_normalized_time: f32,
+}
+
+impl AnimationParameters {
+    ///
+    /// Returns the value of the parameter with the given name, or zero if it has not been set.
+    ///
+    pub fn value(&self, name: &str) -> f32 {
+        self.values.get(name).cloned().unwrap_or(0.0)
+    }
+
+    ///
+    /// Returns whether the parameter with the given name is set to a value different from zero, for example used for boolean parameters.
+    ///
+    pub fn is_set(&self, name: &str) -> bool {
+        self.value(name) != 0.0
+    }
+}
+
+struct AnimationState {
+    name: String,
+    clip: usize,
+    looping: bool,
+    speed: f32,
+}
+
+struct AnimationTransition {
+    from: Option<usize>,
+    to: usize,
+    duration: f32,
+    condition: Box<dyn Fn(&AnimationParameters) -> bool>,
+}
+
+struct AdditiveLayer {
+    clip: usize,
+    weight: f32,
+    time: f32,
+}
+
+///
+/// A minimal animation state machine which drives a [Skeleton], for example for character locomotion.
+/// Each state plays an [AnimationClip] and the state machine crossfades to another state when the condition of a transition is fulfilled.
+/// Additive layers can be played on top of the states.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let clips: Vec<AnimationClip> = unimplemented!();
+/// # let mut skeleton: Skeleton = unimplemented!();
+/// # let frame_input: FrameInput = unimplemented!();
+/// let mut state_machine = AnimationStateMachine::new(clips);
+/// state_machine.add_state("idle", "idle", true).unwrap();
+/// state_machine.add_state("walk", "walk", true).unwrap();
+/// state_machine.add_transition(Some("idle"), "walk", 0.3, |p| p.value("speed") > 0.1).unwrap();
+/// state_machine.add_transition(Some("walk"), "idle", 0.3, |p| p.value("speed") <= 0.1).unwrap();
+/// // Each frame:
+/// state_machine.set_parameter("speed", 1.0);
+/// state_machine.update(frame_input.elapsed_time, &mut skeleton);
+/// ```
+///
+pub struct AnimationStateMachine {
+    clips: Vec<AnimationClip>,
+    states: Vec<AnimationState>,
+    transitions: Vec<AnimationTransition>,
+    layers: Vec<AdditiveLayer>,
+    parameters: AnimationParameters,
+    current: Option<(usize, f32)>,
+    previous: Option<(usize, f32)>,
+    fade: Option<(f32, f32)>,
+}
+
+impl AnimationStateMachine {
+    ///
+    /// Creates a new state machine without any states using the given clips.
+    ///
+    pub fn new(clips: Vec<AnimationClip>) -> Self {
+        Self {
+            clips,
+            states: Vec::new(),
+            transitions: Vec::new(),
+            layers: Vec::new(),
+            parameters: AnimationParameters::default(),
+            current: None,
+            previous: None,
+            fade: None,
+        }
+    }
+
+    fn clip_index(&self, name: &str) -> Result<usize, Error> {
+        self.clips
+            .iter()
+            .position(|clip| clip.name == name)
+            .ok_or_else(|| Error::AnimationError {
+                message: format!("There is no animation clip named {}", name),
+            })
+    }
+
+    fn state_index(&self, name: &str) -> Result<usize, Error> {
+        self.states
+            .iter()
+            .position(|state| state.name == name)
+            .ok_or_else(|| Error::AnimationError {
+                message: format!("There is no animation state named {}", name),
+            })
+    }
+
+    ///
+    /// Adds a state with the given name which plays the clip with the given name. The first state added is the initial state.
+    ///
+    /// # Errors
+    /// Will return an error if there is no clip with the given name.
+    ///
+    pub fn add_state(&mut self, name: &str, clip_name: &str, looping: bool) -> Result<(), Error> {
+        let clip = self.clip_index(clip_name)?;
+        self.states.push(AnimationState {
+            name: name.to_string(),
+            clip,
+            looping,
+            speed: 1.0,
+        });
+        if self.current.is_none() {
+            self.current = Some((self.states.len() - 1, 0.0));
+        }
+        Ok(())
+    }
+
+    ///
+    /// Sets the playback speed of the state with the given name, where 1 is the original speed of the clip.
+    ///
+    /// # Errors
+    /// Will return an error if there is no state with the given name.
+    ///
+    pub fn set_state_speed(&mut self, name: &str, speed: f32) -> Result<(), Error> {
+        let index = self.state_index(name)?;
+        self.states[index].speed = speed;
+        Ok(())
+    }
+
+    ///
+    /// Adds a transition from the state with the given name, or from any state if `None` is given, to the state with the given name.
+    /// The transition crossfades between the states over the given duration in seconds when the condition is fulfilled.
+    /// The transitions are checked in the order they are added.
+    ///
+    /// # Errors
+    /// Will return an error if there is no state with one of the given names.
+    ///
+    pub fn add_transition(
+        &mut self,
+        from: Option<&str>,
+        to: &str,
+        duration: f32,
+        condition: impl Fn(&AnimationParameters) -> bool + 'static,
+    ) -> Result<(), Error> {
+        let from = from.map(|name| self.state_index(name)).transpose()?;
+        let to = self.state_index(to)?;
+        self.transitions.push(AnimationTransition {
+            from,
+            to,
+            duration,
+            condition: Box::new(condition),
+        });
+        Ok(())
+    }
+
+    ///
+    /// Adds an additive layer playing the clip with the given name on top of the states with the given weight and returns the index of the layer.
+    /// The first keyframe of the clip is used as the reference pose, ie. the difference to the first keyframe is added.
+    ///
+    /// # Errors
+    /// Will return an error if there is no clip with the given name.
+    ///
+    pub fn add_additive_layer(&mut self, clip_name: &str, weight: f32) -> Result<usize, Error> {
+        let clip = self.clip_index(clip_name)?;
+        self.layers.push(AdditiveLayer {
+            clip,
+            weight,
+            time: 0.0,
+        });
+        Ok(self.layers.len() - 1)
+    }
+
+    ///
+    /// Sets the weight of the additive layer with the given index, where 0 disables the layer.
+    ///
+    pub fn set_layer_weight(&mut self, layer: usize, weight: f32) {
+        self.layers[layer].weight = weight;
+    }
+
+    ///
+    /// Sets the value of the parameter with the given name, which is used by the conditions of the transitions.
+    ///
+    pub fn set_parameter(&mut self, name: &str, value: f32) {
+        self.parameters.values.insert(name.to_string(), value);
+    }
+
+    ///
+    /// Returns the parameters used by the conditions of the transitions.
+    ///
+    pub fn parameters(&self) -> &AnimationParameters {
+        &self.parameters
+    }
+
+    ///
+    /// Returns the name of the current state.
+    ///
+    pub fn current_state(&self) -> Option<&str> {
+        self.current
+            .map(|(index, _)| self.states[index].name.as_str())
+    }
+
+    ///
+    /// Crossfades to the state with the given name over the given duration in seconds, regardless of the transitions.
+    ///
+    /// # Errors
+    /// Will return an error if there is no state with the given name.
+    ///
+    pub fn play(&mut self, name: &str, duration: f32) -> Result<(), Error> {
+        let index = self.state_index(name)?;
+        self.start_transition(index, duration);
+        Ok(())
+    }
+
+    fn start_transition(&mut self, to: usize, duration: f32) {
+        self.previous = self.current;
+        self.current = Some((to, 0.0));
+        self.fade = if duration > 0.0 && self.previous.is_some() {
+            Some((0.0, duration))
+        } else {
+            None
+        };
+    }
+
+    fn sample_state(&self, state: usize, time: f32, pose: &mut [JointTransform]) {
+        let state = &self.states[state];
+        let clip = &self.clips[state.clip];
+        clip.sample(clip.clip_time(time, state.looping), pose);
+    }
+
+    ///
+    /// Advances the state machine by the given time in milliseconds, see [FrameInput::elapsed_time](crate::FrameInput::elapsed_time),
+    /// performs the transitions whose conditions are fulfilled and poses and updates the given skeleton.
+    ///
+    pub fn update(&mut self, elapsed_time: f64, skeleton: &mut Skeleton) {
+        let seconds = elapsed_time as f32 * 0.001;
+        let (current, time) = match self.current {
+            Some(current) => current,
+            None => return,
+        };
+        let time = time + seconds * self.states[current].speed;
+        self.current = Some((current, time));
+        if let Some((previous, previous_time)) = self.previous {
+            self.previous = Some((
+                previous,
+                previous_time + seconds * self.states[previous].speed,
+            ));
+        }
+        for layer in self.layers.iter_mut() {
+            layer.time += seconds;
+        }
+
+        let duration = self.clips[self.states[current].clip].duration;
+        self.parameters.state_time = time;
+        self.parameters.state_normalized_time = if duration > 0.0 { time / duration } else { 0.0 };
+        let transition = self
+            .transitions
+            .iter()
+            .find(|t| {
+                t.from.map_or(true, |from| from == current)
+                    && t.to != current
+                    && (t.condition)(&self.parameters)
+            })
+            .map(|t| (t.to, t.duration));
+        if let Some((to, duration)) = transition {
+            self.start_transition(to, duration);
+        }
+
+        let rest_pose: Vec<JointTransform> = skeleton
+            .joints()
+            .iter()
+            .map(|joint| joint.rest_transform)
+            .collect();
+        let mut pose = rest_pose.clone();
+        let (current, time) = self.current.unwrap();
+        self.sample_state(current, time, &mut pose);
+        if let (Some((previous, previous_time)), Some((fade_time, fade_duration))) =
+            (self.previous, self.fade)
+        {
+            let fade_time = fade_time + seconds;
+            if fade_time < fade_duration {
+                let mut previous_pose = rest_pose.clone();
+                self.sample_state(previous, previous_time, &mut previous_pose);
+                blend_poses(&mut previous_pose, &pose, fade_time / fade_duration);
+                pose = previous_pose;
+                self.fade = Some((fade_time, fade_duration));
+            } else {
+                self.previous = None;
+                self.fade = None;
+            }
+        }
+        for layer in self.layers.iter().filter(|layer| layer.weight > 0.0) {
+            let clip = &self.clips[layer.clip];
+            let mut reference = rest_pose.clone();
+            clip.sample(0.0, &mut reference);
+            let mut additive = reference.clone();
+            clip.sample(clip.clip_time(layer.time, true), &mut additive);
+            add_pose(&mut pose, &additive, &reference, layer.weight);
+        }
+        skeleton.set_pose(&pose);
+        skeleton.update();
+    }
+}
@@ -0,0 +1,322 @@
+use crate::definition::*;
+use crate::math::*;
+use alloc::{vec, vec::Vec};
+
+///
+/// The closest intersection between a ray and the triangles in a [BoundingVolumeHierarchy].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RayHit {
+    /// The distance from the origin of the ray to the intersection point, measured in the length of the ray direction.
+    pub distance: f32,
+    /// The index of the mesh which the intersected triangle belongs to.
+    pub mesh_index: usize,
+    /// The index of the intersected triangle in the mesh.
+    pub triangle_index: usize,
+    /// The barycentric coordinates of the intersection point with respect to the second and third vertex of the triangle.
+    pub barycentric: Vec2,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    min: Vec3,
+    max: Vec3,
+    // The index of the first child node if this is an interior node or the first triangle if this is a leaf
+    start: usize,
+    // The number of triangles if this is a leaf or zero if this is an interior node
+    count: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Triangle {
+    vertices: [Vec3; 3],
+    mesh_index: usize,
+    triangle_index: usize,
+}
+
+///
+/// A bounding volume hierarchy of the triangles in a set of [CPUMesh]es,
+/// which enables fast ray intersection queries on the CPU, for example for picking, baking ambient occlusion or ray tracing.
+///
+#[derive(Debug, Clone)]
+pub struct BoundingVolumeHierarchy {
+    nodes: Vec<Node>,
+    triangles: Vec<Triangle>,
+}
+
+const MAX_TRIANGLES_IN_LEAF: usize = 4;
+
+impl BoundingVolumeHierarchy {
+    ///
+    /// Constructs a bounding volume hierarchy of the triangles in the given meshes.
+    /// The triangles are split recursively at the middle of the longest axis of their centers.
+    ///
+    pub fn new(cpu_meshes: &[CPUMesh]) -> Self {
+        let mut triangles = Vec::new();
+        for (mesh_index, mesh) in cpu_meshes.iter().enumerate() {
+            let position = |i: u32| {
+                let i = i as usize;
+                vec3(
+                    mesh.positions[3 * i],
+                    mesh.positions[3 * i + 1],
+                    mesh.positions[3 * i + 2],
+                )
+            };
+            let indices = mesh
+                .indices
+                .as_ref()
+                .map(|indices| indices.into_u32())
+                .unwrap_or_else(|| (0..mesh.positions.len() as u32 / 3).collect());
+            for (triangle_index, t) in indices.chunks_exact(3).enumerate() {
+                triangles.push(Triangle {
+                    vertices: [position(t[0]), position(t[1]), position(t[2])],
+                    mesh_index,
+                    triangle_index,
+                });
+            }
+        }
+        let mut bvh = Self {
+            nodes: Vec::new(),
+            triangles,
+        };
+        if !bvh.triangles.is_empty() {
+            bvh.nodes.push(Node {
+                min: vec3(0.0, 0.0, 0.0),
+                max: vec3(0.0, 0.0, 0.0),
+                start: 0,
+                count: bvh.triangles.len(),
+            });
+            bvh.subdivide(0);
+        }
+        bvh
+    }
+
+    fn subdivide(&mut self, node_index: usize) {
+        let start = self.nodes[node_index].start;
+        let count = self.nodes[node_index].count;
+        let triangles = &mut self.triangles[start..start + count];
+        let (min, max) = bounds(triangles.iter().flat_map(|t| t.vertices.iter().cloned()));
+        self.nodes[node_index].min = min;
+        self.nodes[node_index].max = max;
+        if count <= MAX_TRIANGLES_IN_LEAF {
+            return;
+        }
+
+        let (center_min, center_max) = bounds(triangles.iter().map(center));
+        let extent = center_max - center_min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+        if extent[axis] <= 0.0 {
+            return;
+        }
+        let split = center_min[axis] + 0.5 * extent[axis];
+        let mut left = 0;
+        for i in 0..count {
+            if center(&triangles[i])[axis] < split {
+                triangles.swap(i, left);
+                left += 1;
+            }
+        }
+        if left == 0 || left == count {
+            return;
+        }
+
+        let first_child = self.nodes.len();
+        for (child_start, child_count) in [(start, left), (start + left, count - left)].iter() {
+            self.nodes.push(Node {
+                min,
+                max,
+                start: *child_start,
+                count: *child_count,
+            });
+        }
+        self.nodes[node_index].start = first_child;
+        self.nodes[node_index].count = 0;
+        self.subdivide(first_child);
+        self.subdivide(first_child + 1);
+    }
+
+    ///
+    /// Returns the closest intersection between the triangles and the ray with the given origin and direction
+    /// within the given maximum distance, or `None` if the ray does not hit any triangles.
+    /// Both sides of the triangles are hit.
+    ///
+    pub fn intersect(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RayHit> {
+        let mut closest: Option<RayHit> = None;
+        self.traverse(
+            origin,
+            direction,
+            max_distance,
+            |triangle, distance, barycentric| {
+                closest = Some(RayHit {
+                    distance,
+                    mesh_index: triangle.mesh_index,
+                    triangle_index: triangle.triangle_index,
+                    barycentric,
+                });
+                false
+            },
+        );
+        closest
+    }
+
+    ///
+    /// Returns whether the ray with the given origin and direction hits any triangle within the given maximum distance.
+    /// This is faster than [intersect](Self::intersect) since the traversal stops at the first hit.
+    ///
+    pub fn is_occluded(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> bool {
+        let mut occluded = false;
+        self.traverse(origin, direction, max_distance, |_, _, _| {
+            occluded = true;
+            true
+        });
+        occluded
+    }
+
+    // Calls the callback for each hit which is closer than the previous hits until the callback returns true
+    fn traverse(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        mut callback: impl FnMut(&Triangle, f32, Vec2) -> bool,
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let inverse_direction = vec3(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+        let mut max_distance = max_distance;
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if intersect_box(origin, inverse_direction, node.min, node.max, max_distance).is_none()
+            {
+                continue;
+            }
+            if node.count > 0 {
+                for triangle in self.triangles[node.start..node.start + node.count].iter() {
+                    if let Some((distance, barycentric)) =
+                        intersect_triangle(origin, direction, &triangle.vertices)
+                    {
+                        if distance < max_distance {
+                            max_distance = distance;
+                            if callback(triangle, distance, barycentric) {
+                                return;
+                            }
+                        }
+                    }
+                }
+            } else {
+                // Visit the closest child first
+                let first = node.start;
+                let second = node.start + 1;
+                let first_distance = intersect_box(
+                    origin,
+                    inverse_direction,
+                    self.nodes[first].min,
+                    self.nodes[first].max,
+                    max_distance,
+                );
+                let second_distance = intersect_box(
+                    origin,
+                    inverse_direction,
+                    self.nodes[second].min,
+                    self.nodes[second].max,
+                    max_distance,
+                );
+                match (first_distance, second_distance) {
+                    (Some(a), Some(b)) if a <= b => {
+                        stack.push(second);
+                        stack.push(first);
+                    }
+                    (Some(_), Some(_)) => {
+                        stack.push(first);
+                        stack.push(second);
+                    }
+                    (Some(_), None) => stack.push(first),
+                    (None, Some(_)) => stack.push(second),
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+}
+
+fn center(triangle: &Triangle) -> Vec3 {
+    (triangle.vertices[0] + triangle.vertices[1] + triangle.vertices[2]) / 3.0
+}
+
+fn bounds(points: impl Iterator<Item = Vec3>) -> (Vec3, Vec3) {
+    points.fold(
+        (
+            vec3(core::f32::MAX, core::f32::MAX, core::f32::MAX),
+            vec3(core::f32::MIN, core::f32::MIN, core::f32::MIN),
+        ),
+        |(min, max), p| {
+            (
+                vec3(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                vec3(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+            )
+        },
+    )
+}
+
+// Returns the distance to the box if the ray hits the box within the given maximum distance
+fn intersect_box(
+    origin: Vec3,
+    inverse_direction: Vec3,
+    min: Vec3,
+    max: Vec3,
+    max_distance: f32,
+) -> Option<f32> {
+    let mut t_min = 0.0f32;
+    let mut t_max = max_distance;
+    for axis in 0..3 {
+        let t0 = (min[axis] - origin[axis]) * inverse_direction[axis];
+        let t1 = (max[axis] - origin[axis]) * inverse_direction[axis];
+        // NaN occurs when the ray is parallel to and exactly on a slab boundary, in which case the slab is ignored
+        if t0.is_nan() || t1.is_nan() {
+            continue;
+        }
+        t_min = t_min.max(t0.min(t1));
+        t_max = t_max.min(t0.max(t1));
+    }
+    if t_min <= t_max {
+        Some(t_min)
+    } else {
+        None
+    }
+}
+
+// The Möller-Trumbore ray-triangle intersection
+fn intersect_triangle(origin: Vec3, direction: Vec3, vertices: &[Vec3; 3]) -> Option<(f32, Vec2)> {
+    let edge1 = vertices[1] - vertices[0];
+    let edge2 = vertices[2] - vertices[0];
+    let p = direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < 1.0e-12 {
+        return None;
+    }
+    let inverse_determinant = 1.0 / determinant;
+    let s = origin - vertices[0];
+    let u = s.dot(p) * inverse_determinant;
+    if u < 0.0 || u > 1.0 {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = direction.dot(q) * inverse_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = edge2.dot(q) * inverse_determinant;
+    if distance > 0.0 {
+        Some((distance, vec2(u, v)))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,342 @@
+use crate::definition::*;
+use crate::math::*;
+use alloc::collections::BTreeSet;
+use alloc::{string::ToString, vec, vec::Vec};
+
+///
+/// A triangle mesh given by a list of vertex positions and a list of triangles, each given by the indices of its three vertices.
+/// This is the form used by most physics engines for triangle mesh and convex polyhedron colliders.
+///
+#[derive(Debug, Clone, Default)]
+pub struct TriangleSoup {
+    /// The positions of the vertices.
+    pub vertices: Vec<[f32; 3]>,
+    /// The indices of the vertices of each triangle, with a counter clockwise winding when seen from the outside.
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl TriangleSoup {
+    ///
+    /// Converts the triangle soup into a [CPUMesh] with computed normals, for example for visualizing a collision shape.
+    ///
+    pub fn to_cpu_mesh(&self) -> CPUMesh {
+        let mut mesh = CPUMesh {
+            name: "collision shape".to_string(),
+            positions: self
+                .vertices
+                .iter()
+                .flat_map(|v| v.iter().cloned())
+                .collect(),
+            indices: Some(Indices::U32(
+                self.triangles
+                    .iter()
+                    .flat_map(|t| t.iter().cloned())
+                    .collect(),
+            )),
+            ..Default::default()
+        };
+        mesh.compute_normals();
+        mesh
+    }
+}
+
+///
+/// A sphere enclosing a set of positions.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+///
+/// A box enclosing a set of positions which, as opposed to an [AxisAlignedBoundingBox], can be rotated.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrientedBoundingBox {
+    /// The center of the box.
+    pub center: Vec3,
+    /// The three orthonormal axes of the box.
+    pub axes: [Vec3; 3],
+    /// Half the size of the box along each of the axes.
+    pub half_extents: Vec3,
+}
+
+impl OrientedBoundingBox {
+    ///
+    /// Returns the transformation from a box with corners at -1 and 1 in all directions to this box.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        Mat4::from_translation(self.center)
+            * Mat4::from(Mat3::from_cols(self.axes[0], self.axes[1], self.axes[2]))
+            * Mat4::from_nonuniform_scale(
+                self.half_extents.x,
+                self.half_extents.y,
+                self.half_extents.z,
+            )
+    }
+}
+
+impl CPUMesh {
+    ///
+    /// Returns the triangles of the mesh as a [TriangleSoup], for example for creating a triangle mesh collider in a physics engine.
+    ///
+    pub fn triangle_soup(&self) -> TriangleSoup {
+        let vertices = self.vertices();
+        let indices = self
+            .indices
+            .as_ref()
+            .map(|indices| indices.into_u32())
+            .unwrap_or_else(|| (0..vertices.len() as u32).collect());
+        TriangleSoup {
+            vertices: vertices.iter().map(|v| [v.x, v.y, v.z]).collect(),
+            triangles: indices
+                .chunks_exact(3)
+                .map(|t| [t[0], t[1], t[2]])
+                .collect(),
+        }
+    }
+
+    ///
+    /// Computes a bounding sphere of the mesh using Ritter's algorithm.
+    /// The sphere is not necessarily the smallest possible, but usually within a few percent of it.
+    ///
+    pub fn compute_bounding_sphere(&self) -> BoundingSphere {
+        let vertices = self.vertices();
+        if vertices.is_empty() {
+            return BoundingSphere {
+                center: vec3(0.0, 0.0, 0.0),
+                radius: 0.0,
+            };
+        }
+        let farthest_from = |point: Vec3| {
+            *vertices
+                .iter()
+                .max_by(|a, b| {
+                    a.distance2(point)
+                        .partial_cmp(&b.distance2(point))
+                        .unwrap_or(core::cmp::Ordering::Equal)
+                })
+                .unwrap()
+        };
+        let a = farthest_from(vertices[0]);
+        let b = farthest_from(a);
+        let mut center = 0.5 * (a + b);
+        let mut radius = 0.5 * a.distance(b);
+        for v in vertices.iter() {
+            let distance = v.distance(center);
+            if distance > radius {
+                let new_radius = 0.5 * (radius + distance);
+                center += (distance - new_radius) / distance * (v - center);
+                radius = new_radius;
+            }
+        }
+        BoundingSphere { center, radius }
+    }
+
+    ///
+    /// Computes an oriented bounding box of the mesh with axes along the principal components of the positions,
+    /// which fits elongated and rotated meshes much better than an axis aligned bounding box.
+    ///
+    pub fn compute_oriented_bounding_box(&self) -> OrientedBoundingBox {
+        let vertices = self.vertices();
+        let count = vertices.len().max(1) as f32;
+        let mean = vertices.iter().fold(vec3(0.0, 0.0, 0.0), |sum, v| sum + v) / count;
+        let mut covariance = [[0.0f32; 3]; 3];
+        for v in vertices.iter() {
+            let d = v - mean;
+            for i in 0..3 {
+                for j in 0..3 {
+                    covariance[i][j] += d[i] * d[j] / count;
+                }
+            }
+        }
+        let eigenvectors = symmetric_eigenvectors(covariance);
+        let x = eigenvectors[0];
+        let y = eigenvectors[1];
+        let axes = [x, y, x.cross(y)];
+
+        let mut min = vec3(core::f32::MAX, core::f32::MAX, core::f32::MAX);
+        let mut max = vec3(core::f32::MIN, core::f32::MIN, core::f32::MIN);
+        for v in vertices.iter() {
+            for i in 0..3 {
+                let p = axes[i].dot(*v);
+                min[i] = min[i].min(p);
+                max[i] = max[i].max(p);
+            }
+        }
+        if vertices.is_empty() {
+            min = vec3(0.0, 0.0, 0.0);
+            max = vec3(0.0, 0.0, 0.0);
+        }
+        let c = 0.5 * (min + max);
+        OrientedBoundingBox {
+            center: c.x * axes[0] + c.y * axes[1] + c.z * axes[2],
+            axes,
+            half_extents: 0.5 * (max - min),
+        }
+    }
+
+    ///
+    /// Computes the convex hull of the positions of the mesh, ie. the smallest convex triangle mesh containing all of the positions,
+    /// for example for creating a convex collider in a physics engine.
+    /// Returns `None` if the positions do not span a volume, for example if the mesh is flat.
+    ///
+    pub fn compute_convex_hull(&self) -> Option<TriangleSoup> {
+        convex_hull(&self.vertices())
+    }
+
+    fn vertices(&self) -> Vec<Vec3> {
+        self.positions
+            .chunks_exact(3)
+            .map(|p| vec3(p[0], p[1], p[2]))
+            .collect()
+    }
+}
+
+// Incremental convex hull, each point is added by replacing the faces that can see the point with a cone from the point to the horizon
+fn convex_hull(points: &[Vec3]) -> Option<TriangleSoup> {
+    let aabb_size = points.iter().fold(0.0f32, |max, p| {
+        max.max(p.x.abs()).max(p.y.abs()).max(p.z.abs())
+    });
+    let epsilon = 0.00001 * aabb_size.max(core::f32::EPSILON);
+
+    // Find a non-degenerate tetrahedron
+    let i0 = 0;
+    let i1 = (0..points.len()).max_by(|a, b| {
+        points[*a]
+            .distance2(points[i0])
+            .partial_cmp(&points[*b].distance2(points[i0]))
+            .unwrap()
+    })?;
+    let line = points[i1] - points[i0];
+    if line.magnitude() < epsilon {
+        return None;
+    }
+    let i2 = (0..points.len()).max_by(|a, b| {
+        line.cross(points[*a] - points[i0])
+            .magnitude2()
+            .partial_cmp(&line.cross(points[*b] - points[i0]).magnitude2())
+            .unwrap()
+    })?;
+    let normal = line.cross(points[i2] - points[i0]);
+    if normal.magnitude() < epsilon * line.magnitude() {
+        return None;
+    }
+    let i3 = (0..points.len()).max_by(|a, b| {
+        normal
+            .dot(points[*a] - points[i0])
+            .abs()
+            .partial_cmp(&normal.dot(points[*b] - points[i0]).abs())
+            .unwrap()
+    })?;
+    if normal.normalize().dot(points[i3] - points[i0]).abs() < epsilon {
+        return None;
+    }
+
+    let i = |v: usize| v as u32;
+    let mut faces: Vec<[u32; 3]> = if normal.dot(points[i3] - points[i0]) > 0.0 {
+        vec![
+            [i(i0), i(i2), i(i1)],
+            [i(i0), i(i1), i(i3)],
+            [i(i1), i(i2), i(i3)],
+            [i(i2), i(i0), i(i3)],
+        ]
+    } else {
+        vec![
+            [i(i0), i(i1), i(i2)],
+            [i(i0), i(i3), i(i1)],
+            [i(i1), i(i3), i(i2)],
+            [i(i2), i(i3), i(i0)],
+        ]
+    };
+
+    let is_visible = |face: &[u32; 3], point: Vec3| {
+        let a = points[face[0] as usize];
+        let n = (points[face[1] as usize] - a).cross(points[face[2] as usize] - a);
+        n.dot(point - a) > epsilon * n.magnitude()
+    };
+    for (index, point) in points.iter().enumerate() {
+        if index == i0 || index == i1 || index == i2 || index == i3 {
+            continue;
+        }
+        let (visible, hidden): (Vec<[u32; 3]>, Vec<[u32; 3]>) =
+            faces.into_iter().partition(|face| is_visible(face, *point));
+        faces = hidden;
+        if visible.is_empty() {
+            continue;
+        }
+        let edges: BTreeSet<(u32, u32)> = visible
+            .iter()
+            .flat_map(|f| vec![(f[0], f[1]), (f[1], f[2]), (f[2], f[0])])
+            .collect();
+        for (a, b) in edges.iter() {
+            if !edges.contains(&(*b, *a)) {
+                faces.push([*a, *b, index as u32]);
+            }
+        }
+    }
+
+    // Only keep the vertices that are part of the hull
+    let mut new_index = vec![None; points.len()];
+    let mut vertices = Vec::new();
+    let triangles = faces
+        .iter()
+        .map(|face| {
+            let mut triangle = [0; 3];
+            for k in 0..3 {
+                let v = face[k] as usize;
+                triangle[k] = *new_index[v].get_or_insert_with(|| {
+                    vertices.push([points[v].x, points[v].y, points[v].z]);
+                    vertices.len() as u32 - 1
+                });
+            }
+            triangle
+        })
+        .collect();
+    Some(TriangleSoup {
+        vertices,
+        triangles,
+    })
+}
+
+// Returns the eigenvectors of a symmetric 3x3 matrix sorted by decreasing eigenvalue, using the Jacobi eigenvalue algorithm
+fn symmetric_eigenvectors(matrix: [[f32; 3]; 3]) -> [Vec3; 3] {
+    let mut a = matrix;
+    let mut v = [[1.0f32, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..50 {
+        let (mut p, mut q) = (0, 1);
+        for (i, j) in [(0, 2), (1, 2)].iter() {
+            if a[*i][*j].abs() > a[p][q].abs() {
+                p = *i;
+                q = *j;
+            }
+        }
+        if a[p][q].abs() < 1.0e-12 {
+            break;
+        }
+        let theta = 0.5 * (a[q][q] - a[p][p]) / a[p][q];
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+        for k in 0..3 {
+            let (akp, akq) = (a[k][p], a[k][q]);
+            a[k][p] = c * akp - s * akq;
+            a[k][q] = s * akp + c * akq;
+        }
+        for k in 0..3 {
+            let (apk, aqk) = (a[p][k], a[q][k]);
+            a[p][k] = c * apk - s * aqk;
+            a[q][k] = s * apk + c * aqk;
+        }
+        for k in 0..3 {
+            let (vkp, vkq) = (v[k][p], v[k][q]);
+            v[k][p] = c * vkp - s * vkq;
+            v[k][q] = s * vkp + c * vkq;
+        }
+    }
+    let mut order = [0, 1, 2];
+    order.sort_by(|i, j| a[*j][*j].partial_cmp(&a[*i][*i]).unwrap());
+    let column = |i: usize| vec3(v[0][i], v[1][i], v[2][i]).normalize();
+    [column(order[0]), column(order[1]), column(order[2])]
+}
@@ -1,4 +1,5 @@
 use crate::definition::*;
+use alloc::string::{String, ToString};
 
 ///
 /// A CPU-side version of a material (for example [phong material](crate::PhongMaterial)).
@@ -14,6 +15,11 @@ pub struct CPUMaterial {
     pub metallic_factor: Option<f32>,
     pub roughness_factor: Option<f32>,
     pub metallic_roughness_texture: Option<CPUTexture<u8>>,
+    /// Whether or not both sides of the triangles should be visible.
+    /// If false, the back sides of the triangles are culled, if not specified, the culling is decided when rendering.
+    pub double_sided: Option<bool>,
+    /// Defines how the alpha value of the color is used, if not specified, the alpha value is ignored.
+    pub alpha_mode: Option<AlphaMode>,
 }
 
 impl Default for CPUMaterial {
@@ -28,6 +34,31 @@ impl Default for CPUMaterial {
             diffuse_intensity: None,
             specular_intensity: None,
             specular_power: None,
+            double_sided: None,
+            alpha_mode: None,
         }
     }
 }
+
+///
+/// Defines how the alpha value of the color of a material is used.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AlphaMode {
+    /// The alpha value is ignored and the object is rendered fully opaque.
+    Opaque,
+    /// The object is fully opaque where the alpha value is larger than or equal to the cutoff and fully transparent elsewhere,
+    /// for example used for vegetation and fences. This also applies when the object is rendered into shadow maps.
+    Mask {
+        /// The smallest alpha value that is rendered.
+        cutoff: f32,
+    },
+    /// The alpha value is used for blending the object with the objects behind it.
+    Blend,
+}
+
+impl Default for AlphaMode {
+    fn default() -> Self {
+        Self::Opaque
+    }
+}
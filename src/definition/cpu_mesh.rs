@@ -1,4 +1,9 @@
 use crate::math::*;
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 
 #[derive(Debug)]
 pub enum Indices {
@@ -58,7 +63,7 @@ impl CPUMesh {
         let mut indices = Vec::new();
         let mut normals = Vec::new();
         for j in 0..angle_subdivisions {
-            let angle = 2.0 * std::f32::consts::PI * j as f32 / angle_subdivisions as f32;
+            let angle = 2.0 * core::f32::consts::PI * j as f32 / angle_subdivisions as f32;
 
             positions.push(radius * angle.cos());
             positions.push(radius * angle.sin());
@@ -112,7 +117,7 @@ impl CPUMesh {
         for i in 0..length_subdivisions + 1 {
             let x = i as f32 / length_subdivisions as f32;
             for j in 0..angle_subdivisions {
-                let angle = 2.0 * std::f32::consts::PI * j as f32 / angle_subdivisions as f32;
+                let angle = 2.0 * core::f32::consts::PI * j as f32 / angle_subdivisions as f32;
 
                 positions.push(length * x);
                 positions.push(radius * angle.cos());
@@ -147,7 +152,7 @@ impl CPUMesh {
         for i in 0..length_subdivisions + 1 {
             let x = i as f32 / length_subdivisions as f32;
             for j in 0..angle_subdivisions {
-                let angle = 2.0 * std::f32::consts::PI * j as f32 / angle_subdivisions as f32;
+                let angle = 2.0 * core::f32::consts::PI * j as f32 / angle_subdivisions as f32;
 
                 positions.push(length * x);
                 positions.push(radius * angle.cos() * (1.0 - x));
@@ -217,6 +222,146 @@ impl CPUMesh {
     pub fn compute_aabb(&self) -> AxisAlignedBoundingBox {
         AxisAlignedBoundingBox::new_with_positions(&self.positions)
     }
+
+    ///
+    /// Merges the given meshes into one mesh, so that many small static objects can be rendered with one draw call.
+    /// If some of the meshes have normals, uv coordinates or colors, the merged mesh also has them and
+    /// they are computed (normals), set to zero (uv coordinates) or set to white (colors) for the meshes that do not have them.
+    /// The material name of the merged mesh is only set if all the meshes have the same material,
+    /// otherwise use [material_indices](CPUMesh::material_indices) to keep track of the materials
+    /// or pack the textures into a [texture atlas](crate::TextureAtlas) and [remap the uv coordinates](crate::TextureAtlas::remap_uvs) before merging.
+    ///
+    pub fn merge(meshes: &[CPUMesh]) -> Self {
+        Self::merge_internal(meshes, None)
+    }
+
+    ///
+    /// Merges the given meshes into one mesh like [merge](CPUMesh::merge),
+    /// but first transforms the positions and normals of each mesh by the transformation with the same index.
+    ///
+    pub fn merge_with_transformations(meshes: &[CPUMesh], transformations: &[Mat4]) -> Self {
+        Self::merge_internal(meshes, Some(transformations))
+    }
+
+    ///
+    /// Returns the unique material names of the given meshes and for each vertex in the mesh that results from
+    /// [merging](CPUMesh::merge) the meshes, the index into the list of material names.
+    /// The indices can be used as an extra vertex attribute for selecting the material in a custom shader.
+    ///
+    pub fn material_indices(meshes: &[CPUMesh]) -> (Vec<Option<String>>, Vec<f32>) {
+        let mut material_names: Vec<Option<String>> = Vec::new();
+        let mut indices = Vec::new();
+        for mesh in meshes {
+            let index = material_names
+                .iter()
+                .position(|name| *name == mesh.material_name)
+                .unwrap_or_else(|| {
+                    material_names.push(mesh.material_name.clone());
+                    material_names.len() - 1
+                });
+            indices.extend(core::iter::repeat(index as f32).take(mesh.positions.len() / 3));
+        }
+        (material_names, indices)
+    }
+
+    fn merge_internal(meshes: &[CPUMesh], transformations: Option<&[Mat4]>) -> Self {
+        let has_normals = meshes.iter().any(|m| m.normals.is_some());
+        let has_uvs = meshes.iter().any(|m| m.uvs.is_some());
+        let has_colors = meshes.iter().any(|m| m.colors.is_some());
+        let has_indices = meshes.iter().any(|m| m.indices.is_some());
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut colors = Vec::new();
+        let mut indices = Vec::new();
+        for (i, mesh) in meshes.iter().enumerate() {
+            let offset = (positions.len() / 3) as u32;
+            let vertex_count = mesh.positions.len() / 3;
+            let transformation = transformations.map(|t| t[i]).unwrap_or(Mat4::identity());
+            let normal_transformation = transformation.invert().unwrap().transpose();
+            for v in 0..vertex_count {
+                let p = transformation
+                    * vec4(
+                        mesh.positions[v * 3],
+                        mesh.positions[v * 3 + 1],
+                        mesh.positions[v * 3 + 2],
+                        1.0,
+                    );
+                positions.extend(&[p.x, p.y, p.z]);
+            }
+            if has_normals {
+                let mesh_normals = mesh.normals.clone().unwrap_or_else(|| {
+                    mesh.indices
+                        .as_ref()
+                        .map(|ind| compute_normals_with_indices(&ind.into_u32(), &mesh.positions))
+                        .unwrap_or(compute_normals(&mesh.positions))
+                });
+                for v in 0..vertex_count {
+                    let n = (normal_transformation
+                        * vec4(
+                            mesh_normals[v * 3],
+                            mesh_normals[v * 3 + 1],
+                            mesh_normals[v * 3 + 2],
+                            0.0,
+                        ))
+                    .truncate()
+                    .normalize();
+                    normals.extend(&[n.x, n.y, n.z]);
+                }
+            }
+            if has_uvs {
+                if let Some(ref mesh_uvs) = mesh.uvs {
+                    uvs.extend(mesh_uvs);
+                } else {
+                    uvs.extend(core::iter::repeat(0.0).take(vertex_count * 2));
+                }
+            }
+            if has_colors {
+                if let Some(ref mesh_colors) = mesh.colors {
+                    colors.extend(mesh_colors);
+                } else {
+                    colors.extend(core::iter::repeat(255).take(vertex_count * 4));
+                }
+            }
+            if has_indices {
+                if let Some(ref mesh_indices) = mesh.indices {
+                    indices.extend(mesh_indices.into_u32().iter().map(|i| i + offset));
+                } else {
+                    indices.extend(offset..offset + vertex_count as u32);
+                }
+            }
+        }
+
+        let material_name = meshes.first().and_then(|first| {
+            if meshes
+                .iter()
+                .all(|m| m.material_name == first.material_name)
+            {
+                first.material_name.clone()
+            } else {
+                None
+            }
+        });
+        let vertex_count = positions.len() / 3;
+        CPUMesh {
+            name: "merged".to_string(),
+            material_name,
+            positions,
+            indices: if has_indices {
+                Some(if vertex_count <= u16::MAX as usize {
+                    Indices::U16(indices.iter().map(|i| *i as u16).collect())
+                } else {
+                    Indices::U32(indices)
+                })
+            } else {
+                None
+            },
+            normals: if has_normals { Some(normals) } else { None },
+            uvs: if has_uvs { Some(uvs) } else { None },
+            colors: if has_colors { Some(colors) } else { None },
+        }
+    }
 }
 
 fn compute_normals_with_indices(indices: &[u32], positions: &[f32]) -> Vec<f32> {
@@ -0,0 +1,111 @@
+use crate::definition::*;
+use crate::math::*;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+
+///
+/// A CPU-side scene consisting of meshes and materials, for example loaded from a file.
+///
+/// All of the CPU-side types, ie. [CPUMesh], [CPUMaterial], [CPUTexture] and the math types, are [Send] and [Sync],
+/// so a scene can be prepared (loaded, parsed, processed) on a worker thread and then handed over to the thread owning the graphics context for upload,
+/// for example using a [cpu_scene_channel]:
+///
+/// ```no_run
+/// # use three_d::*;
+/// let (sender, receiver) = cpu_scene_channel();
+/// std::thread::spawn(move || {
+///     let mut scene = CPUScene::default();
+///     scene.meshes.push(CPUMesh::sphere(1.0));
+///     sender.send(scene);
+/// });
+/// // Each frame on the thread owning the graphics context:
+/// # let context: Context = unimplemented!();
+/// if let Some(scene) = receiver.try_receive() {
+///     for cpu_mesh in scene.meshes.iter() {
+///         let mesh = Mesh::new(&context, cpu_mesh).unwrap();
+///     }
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct CPUScene {
+    pub meshes: Vec<CPUMesh>,
+    pub materials: Vec<CPUMaterial>,
+}
+
+///
+/// Creates a channel for sending [CPU scenes](CPUScene) from worker threads to the thread owning the graphics context.
+/// The sender can be cloned to send from several threads.
+///
+#[cfg(feature = "std")]
+pub fn cpu_scene_channel() -> (CPUSceneSender, CPUSceneReceiver) {
+    let (sender, receiver) = channel();
+    (CPUSceneSender { sender }, CPUSceneReceiver { receiver })
+}
+
+///
+/// The sending half of a [cpu_scene_channel], which can be moved to a worker thread.
+///
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct CPUSceneSender {
+    sender: Sender<CPUScene>,
+}
+
+#[cfg(feature = "std")]
+impl CPUSceneSender {
+    ///
+    /// Sends the scene to the receiver. Returns false if the receiver has been dropped.
+    ///
+    pub fn send(&self, scene: CPUScene) -> bool {
+        self.sender.send(scene).is_ok()
+    }
+}
+
+///
+/// The receiving half of a [cpu_scene_channel], which is used on the thread owning the graphics context.
+///
+#[cfg(feature = "std")]
+pub struct CPUSceneReceiver {
+    receiver: Receiver<CPUScene>,
+}
+
+#[cfg(feature = "std")]
+impl CPUSceneReceiver {
+    ///
+    /// Returns the next scene if one has been sent, otherwise `None`. Never blocks, so it can be called each frame.
+    ///
+    pub fn try_receive(&self) -> Option<CPUScene> {
+        match self.receiver.try_recv() {
+            Ok(scene) => Some(scene),
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+        }
+    }
+
+    ///
+    /// Blocks until the next scene is sent. Returns `None` if all senders have been dropped.
+    ///
+    pub fn receive(&self) -> Option<CPUScene> {
+        self.receiver.recv().ok()
+    }
+}
+
+// Compile time check that the CPU-side types can be shared between threads
+#[allow(dead_code)]
+fn assert_send_and_sync() {
+    fn is_send_and_sync<T: Send + Sync>() {}
+    is_send_and_sync::<CPUMesh>();
+    is_send_and_sync::<CPUMaterial>();
+    is_send_and_sync::<CPUTexture<u8>>();
+    is_send_and_sync::<CPUTexture<u16>>();
+    is_send_and_sync::<CPUTexture<f32>>();
+    is_send_and_sync::<CPUScene>();
+    is_send_and_sync::<Vec2>();
+    is_send_and_sync::<Vec3>();
+    is_send_and_sync::<Vec4>();
+    is_send_and_sync::<Mat3>();
+    is_send_and_sync::<Mat4>();
+    is_send_and_sync::<cgmath::Quaternion<f32>>();
+    is_send_and_sync::<AxisAlignedBoundingBox>();
+}
@@ -1,3 +1,5 @@
+use alloc::{vec, vec::Vec};
+
 ///
 /// Possible modes of interpolation which determines the texture output between texture pixels.
 ///
@@ -18,13 +20,96 @@ pub enum Wrapping {
     ClampToEdge,
 }
 
+///
+/// How a texture with a width or height which is not a power of two is handled on platforms which restrict such textures,
+/// ie. render them black when they are mip mapped or repeated, see [Capabilities::non_power_of_two_textures](crate::context::Capabilities::non_power_of_two_textures).
+/// Both OpenGL 3.3 and WebGL 2 fully support such textures, so they are always used as they are on the currently supported platforms.
+///
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum NonPowerOfTwo {
+    /// Uses the texture as it is.
+    Allow,
+    /// Disables mip mapping and uses [clamp to edge](Wrapping::ClampToEdge) wrapping, which keeps the size but changes how the texture is sampled.
+    Restrict,
+    /// Resizes the texture to the nearest power of two size on the CPU before it is transferred to the GPU and logs a warning,
+    /// which keeps the mip mapping and wrapping but costs time when creating the texture.
+    Resize,
+}
+
+///
+/// Possible filters used when [resizing](CPUTexture::resize) a texture on the CPU.
+///
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ResizeFilter {
+    /// Uses the value of the nearest pixel, fast but gives blocky results.
+    Nearest,
+    /// Interpolates linearly between the pixels and averages all covered pixels when downsampling.
+    Bilinear,
+    /// Uses a Lanczos filter with a support of three pixels, slower but gives sharper results.
+    Lanczos3,
+}
+
+///
+/// The type of the values in a [CPUTexture], which can be converted to and from `f32` when processing the texture.
+/// When the `std` feature is enabled, the type can also be used for textures on the GPU.
+///
+#[cfg(feature = "std")]
 pub trait TextureDataType:
-    Default + std::fmt::Debug + Clone + crate::core::internal::TextureDataTypeExtension
+    Default + core::fmt::Debug + Clone + crate::core::internal::TextureDataTypeExtension
 {
+    fn to_f32(&self) -> f32;
+    fn from_f32(value: f32) -> Self;
+}
+
+///
+/// The type of the values in a [CPUTexture], which can be converted to and from `f32` when processing the texture.
+/// When the `std` feature is enabled, the type can also be used for textures on the GPU.
+///
+#[cfg(not(feature = "std"))]
+pub trait TextureDataType: Default + core::fmt::Debug + Clone {
+    fn to_f32(&self) -> f32;
+    fn from_f32(value: f32) -> Self;
+}
+
+impl TextureDataType for u8 {
+    fn to_f32(&self) -> f32 {
+        *self as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value.round().max(0.0).min(core::u8::MAX as f32) as u8
+    }
+}
+
+impl TextureDataType for u16 {
+    fn to_f32(&self) -> f32 {
+        *self as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value.round().max(0.0).min(core::u16::MAX as f32) as u16
+    }
+}
+
+impl TextureDataType for f32 {
+    fn to_f32(&self) -> f32 {
+        *self
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+impl TextureDataType for u32 {
+    fn to_f32(&self) -> f32 {
+        *self as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value.round().max(0.0).min(core::u32::MAX as f32) as u32
+    }
 }
-impl TextureDataType for u8 {}
-impl TextureDataType for f32 {}
-impl TextureDataType for u32 {}
 
 ///
 /// Possible formats for pixels in a texture.
@@ -40,6 +125,17 @@ pub enum Format {
 }
 
 impl Format {
+    ///
+    /// Returns the sRGB version of this format if it exists, otherwise the format itself.
+    ///
+    pub fn to_srgb(&self) -> Format {
+        match self {
+            Format::RGB => Format::SRGB,
+            Format::RGBA => Format::SRGBA,
+            _ => *self,
+        }
+    }
+
     pub fn color_channel_count(&self) -> u32 {
         match self {
             Format::R => 1,
@@ -56,21 +152,40 @@ impl Format {
 /// A CPU-side version of a texture, for example [2D texture](crate::Texture2D).
 /// Can be constructed manually or loaded via [io](crate::io).
 ///
+#[derive(Clone)]
 pub struct CPUTexture<T: TextureDataType> {
     pub data: Vec<T>,
     pub width: u32,
     pub height: u32,
     pub depth: u32,
     pub format: Format,
+    /// Whether or not the data is in the sRGB color space, which is the case for color images like the base color of a material.
+    /// The data is then converted to linear space when sampled, so that lighting is computed in linear space.
+    /// Data textures, for example normal maps and metallic/roughness maps, must be in linear space.
+    pub is_srgb: bool,
     pub min_filter: Interpolation,
     pub mag_filter: Interpolation,
     pub mip_map_filter: Option<Interpolation>,
     pub wrap_s: Wrapping,
     pub wrap_t: Wrapping,
     pub wrap_r: Wrapping,
+    /// How the texture is handled if the width or height is not a power of two on platforms which restrict such textures.
+    pub non_power_of_two: NonPowerOfTwo,
 }
 
 impl<T: TextureDataType> CPUTexture<T> {
+    ///
+    /// Returns the format used when this texture is transferred to the GPU,
+    /// which is the [sRGB version](Format::to_srgb) of the format if the data is [in sRGB color space](CPUTexture::is_srgb).
+    ///
+    pub fn gpu_format(&self) -> Format {
+        if self.is_srgb {
+            self.format.to_srgb()
+        } else {
+            self.format
+        }
+    }
+
     pub fn add_padding(&mut self, x0: u32, x1: u32, y0: u32, y1: u32) {
         let channels = self.format.color_channel_count();
         let width = x0 + self.width + x1;
@@ -92,6 +207,97 @@ impl<T: TextureDataType> CPUTexture<T> {
         self.width = width;
         self.height = height;
     }
+
+    ///
+    /// Returns a copy of this texture resized to the given width and height using the given filter.
+    /// If the texture is [in sRGB color space](CPUTexture::is_srgb), the filtering is done in linear space.
+    /// If the data contains more than one image, for example the six sides of a cube map, each image is resized.
+    ///
+    pub fn resize(&self, width: u32, height: u32, filter: ResizeFilter) -> CPUTexture<T> {
+        let channels = self.format.color_channel_count() as usize;
+        let is_srgb = matches!(self.gpu_format(), Format::SRGB | Format::SRGBA);
+        let image_size = self.width as usize * self.height as usize * channels;
+        let mut data = Vec::with_capacity(
+            self.data.len() / image_size.max(1) * width as usize * height as usize * channels,
+        );
+        for image in self.data.chunks(image_size.max(1)) {
+            let mut values: Vec<f32> = image.iter().map(|v| v.to_f32()).collect();
+            if is_srgb {
+                for (i, value) in values.iter_mut().enumerate() {
+                    if i % channels < 3 {
+                        *value = 255.0 * rgb_from_srgb(*value / 255.0);
+                    }
+                }
+            }
+            let values = resample(
+                &values,
+                (self.width as usize, self.height as usize),
+                channels,
+                (width as usize, height as usize),
+                filter,
+            );
+            data.extend(values.iter().enumerate().map(|(i, value)| {
+                if is_srgb && i % channels < 3 {
+                    T::from_f32(255.0 * srgb_from_rgb(*value / 255.0))
+                } else {
+                    T::from_f32(*value)
+                }
+            }));
+        }
+        CPUTexture {
+            data,
+            width,
+            height,
+            depth: self.depth,
+            format: self.format,
+            is_srgb: self.is_srgb,
+            min_filter: self.min_filter,
+            mag_filter: self.mag_filter,
+            mip_map_filter: self.mip_map_filter,
+            wrap_s: self.wrap_s,
+            wrap_t: self.wrap_t,
+            wrap_r: self.wrap_r,
+            non_power_of_two: self.non_power_of_two,
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture which is downsampled, keeping the aspect ratio, such that neither the width nor the height exceeds the given maximum size,
+    /// for example the maximum texture size supported by the GPU.
+    /// Returns an unchanged copy if the texture is already small enough.
+    ///
+    pub fn resize_to_max_size(&self, max_size: u32, filter: ResizeFilter) -> CPUTexture<T> {
+        let max_size = max_size.max(1);
+        let scale = (max_size as f32 / self.width.max(self.height) as f32).min(1.0);
+        self.resize(
+            ((self.width as f32 * scale).round() as u32)
+                .max(1)
+                .min(max_size),
+            ((self.height as f32 * scale).round() as u32)
+                .max(1)
+                .min(max_size),
+            filter,
+        )
+    }
+
+    ///
+    /// Generates the chain of mip maps for this texture, ie. versions of the texture where the size is halved for each level until the size is one pixel.
+    /// The first element is the first mip map level which is half the size of this texture.
+    ///
+    pub fn mip_maps(&self, filter: ResizeFilter) -> Vec<CPUTexture<T>> {
+        let mut mip_maps: Vec<CPUTexture<T>> = Vec::new();
+        let (mut width, mut height) = (self.width, self.height);
+        while width > 1 || height > 1 {
+            width = (width / 2).max(1);
+            height = (height / 2).max(1);
+            let mip_map = mip_maps
+                .last()
+                .unwrap_or(self)
+                .resize(width, height, filter);
+            mip_maps.push(mip_map);
+        }
+        mip_maps
+    }
 }
 
 impl<T: TextureDataType> Default for CPUTexture<T> {
@@ -102,20 +308,23 @@ impl<T: TextureDataType> Default for CPUTexture<T> {
             height: 1,
             depth: 1,
             format: Format::RGBA,
+            is_srgb: false,
             min_filter: Interpolation::Linear,
             mag_filter: Interpolation::Linear,
             mip_map_filter: Some(Interpolation::Linear),
             wrap_s: Wrapping::Repeat,
             wrap_t: Wrapping::Repeat,
             wrap_r: Wrapping::Repeat,
+            non_power_of_two: NonPowerOfTwo::Allow,
         }
     }
 }
 
-impl<T: TextureDataType> std::fmt::Debug for CPUTexture<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T: TextureDataType> core::fmt::Debug for CPUTexture<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("CPUTexture")
             .field("format", &self.format)
+            .field("is_srgb", &self.is_srgb)
             .field("data length", &self.data.len())
             .field("width", &self.width)
             .field("height", &self.height)
@@ -126,6 +335,150 @@ impl<T: TextureDataType> std::fmt::Debug for CPUTexture<T> {
             .field("wrap_s", &self.wrap_s)
             .field("wrap_t", &self.wrap_t)
             .field("wrap_r", &self.wrap_r)
+            .field("non_power_of_two", &self.non_power_of_two)
             .finish()
     }
 }
+
+// Fills the uncovered texels (with an alpha value of 0) of an RGBA texture next to the covered texels with the average of the covered neighbours, once for each texel of padding
+pub(crate) fn dilate(data: &mut [u8], width: usize, height: usize, padding: u32) {
+    for _ in 0..padding {
+        let source = data.to_vec();
+        let mut changed = false;
+        for y in 0..height {
+            for x in 0..width {
+                let i = 4 * (y * width + x);
+                if source[i + 3] > 0 {
+                    continue;
+                }
+                let mut sum = [0u32; 4];
+                let mut count = 0;
+                for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                        let j = 4 * (ny * width + nx);
+                        if source[j + 3] > 0 {
+                            for c in 0..4 {
+                                sum[c] += source[j + c] as u32;
+                            }
+                            count += 1;
+                        }
+                    }
+                }
+                if count > 0 {
+                    for c in 0..4 {
+                        data[i + c] = (sum[c] / count) as u8;
+                    }
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+fn resample(
+    data: &[f32],
+    (width, height): (usize, usize),
+    channels: usize,
+    (new_width, new_height): (usize, usize),
+    filter: ResizeFilter,
+) -> Vec<f32> {
+    // Resample the rows, then transpose and resample the columns as rows before transposing back
+    let data = resample_rows(data, width, height, channels, new_width, filter);
+    let data = transpose(&data, new_width, height, channels);
+    let data = resample_rows(&data, height, new_width, channels, new_height, filter);
+    transpose(&data, new_height, new_width, channels)
+}
+
+fn resample_rows(
+    data: &[f32],
+    width: usize,
+    height: usize,
+    channels: usize,
+    new_width: usize,
+    filter: ResizeFilter,
+) -> Vec<f32> {
+    let mut result = vec![0.0; new_width * height * channels];
+    let scale = width as f32 / new_width as f32;
+    let filter_scale = scale.max(1.0);
+    let support = match filter {
+        ResizeFilter::Nearest => 0.0,
+        ResizeFilter::Bilinear => 1.0,
+        ResizeFilter::Lanczos3 => 3.0,
+    } * filter_scale;
+    for x in 0..new_width {
+        let weights: Vec<(usize, f32)> = if filter == ResizeFilter::Nearest {
+            vec![((((x as f32 + 0.5) * scale) as usize).min(width - 1), 1.0)]
+        } else {
+            let center = (x as f32 + 0.5) * scale - 0.5;
+            let start = (center - support).ceil() as i64;
+            let end = (center + support).floor() as i64;
+            let mut weights: Vec<(usize, f32)> = (start..=end)
+                .map(|i| {
+                    let t = (i as f32 - center) / filter_scale;
+                    let weight = match filter {
+                        ResizeFilter::Lanczos3 => lanczos(t, 3.0),
+                        _ => (1.0 - t.abs()).max(0.0),
+                    };
+                    (i.max(0).min(width as i64 - 1) as usize, weight)
+                })
+                .collect();
+            let sum: f32 = weights.iter().map(|(_, w)| w).sum();
+            if sum.abs() > 0.0 {
+                weights.iter_mut().for_each(|(_, w)| *w /= sum);
+            }
+            weights
+        };
+        for y in 0..height {
+            for c in 0..channels {
+                result[(y * new_width + x) * channels + c] = weights
+                    .iter()
+                    .map(|(i, w)| w * data[(y * width + i) * channels + c])
+                    .sum();
+            }
+        }
+    }
+    result
+}
+
+fn transpose(data: &[f32], width: usize, height: usize, channels: usize) -> Vec<f32> {
+    let mut result = vec![0.0; data.len()];
+    for y in 0..height {
+        for x in 0..width {
+            for c in 0..channels {
+                result[(x * height + y) * channels + c] = data[(y * width + x) * channels + c];
+            }
+        }
+    }
+    result
+}
+
+fn lanczos(x: f32, a: f32) -> f32 {
+    if x.abs() < 0.00001 {
+        1.0
+    } else if x.abs() < a {
+        let pi_x = core::f32::consts::PI * x;
+        a * pi_x.sin() * (pi_x / a).sin() / (pi_x * pi_x)
+    } else {
+        0.0
+    }
+}
+
+fn rgb_from_srgb(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn srgb_from_rgb(value: f32) -> f32 {
+    let value = value.max(0.0).min(1.0);
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
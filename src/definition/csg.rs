@@ -0,0 +1,448 @@
+use crate::definition::*;
+use crate::math::*;
+use alloc::{string::ToString, vec, vec::Vec};
+
+impl CPUMesh {
+    ///
+    /// Returns a new mesh containing the volume which is inside this mesh, the other mesh or both.
+    /// See [CPUMesh::difference] for requirements and limitations.
+    ///
+    pub fn union(&self, other: &CPUMesh) -> CPUMesh {
+        let mut a = Tree::new(polygons(self));
+        let mut b = Tree::new(polygons(other));
+        a.clip_to(&b);
+        b.clip_to(&a);
+        b.invert();
+        b.clip_to(&a);
+        b.invert();
+        a.build(b.all_polygons());
+        to_cpu_mesh(a.all_polygons(), &self.name)
+    }
+
+    ///
+    /// Returns a new mesh containing the volume which is inside both this mesh and the other mesh.
+    /// See [CPUMesh::difference] for requirements and limitations.
+    ///
+    pub fn intersection(&self, other: &CPUMesh) -> CPUMesh {
+        let mut a = Tree::new(polygons(self));
+        let mut b = Tree::new(polygons(other));
+        a.invert();
+        b.clip_to(&a);
+        b.invert();
+        a.clip_to(&b);
+        b.clip_to(&a);
+        a.build(b.all_polygons());
+        a.invert();
+        to_cpu_mesh(a.all_polygons(), &self.name)
+    }
+
+    ///
+    /// Returns a new mesh containing the volume which is inside this mesh but not inside the other mesh,
+    /// for example for cutting a hole in this mesh or for a cut-away visualization.
+    /// Both meshes must be closed, ie. watertight, and have the triangles facing outwards, in which case the resulting mesh is also closed,
+    /// since the edges of the triangles along the cut are split at the vertices of the neighbouring triangles.
+    /// The resulting mesh is indexed with the vertices shared between neighbouring triangles and has [computed normals](CPUMesh::compute_normals),
+    /// other vertex attributes are not kept.
+    ///
+    pub fn difference(&self, other: &CPUMesh) -> CPUMesh {
+        let mut a = Tree::new(polygons(self));
+        let mut b = Tree::new(polygons(other));
+        a.invert();
+        a.clip_to(&b);
+        b.clip_to(&a);
+        b.invert();
+        b.clip_to(&a);
+        b.invert();
+        a.build(b.all_polygons());
+        a.invert();
+        to_cpu_mesh(a.all_polygons(), &self.name)
+    }
+}
+
+// The distance within which a point is considered to be on a plane
+const EPSILON: f32 = 1.0e-5;
+
+#[derive(Clone, Copy)]
+struct Plane {
+    normal: Vec3,
+    w: f32,
+}
+
+impl Plane {
+    fn from_points(a: Vec3, b: Vec3, c: Vec3) -> Option<Self> {
+        let normal = (b - a).cross(c - a);
+        if normal.magnitude2() <= core::f32::MIN_POSITIVE {
+            return None;
+        }
+        let normal = normal.normalize();
+        Some(Self {
+            normal,
+            w: normal.dot(a),
+        })
+    }
+
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+}
+
+#[derive(Clone)]
+struct Polygon {
+    vertices: Vec<Vec3>,
+    plane: Plane,
+}
+
+impl Polygon {
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        self.plane.flip();
+    }
+}
+
+// Splits the polygon by the plane and puts the parts into the list corresponding to which side of the plane they are on
+fn split_polygon(
+    plane: &Plane,
+    polygon: Polygon,
+    coplanar_front: &mut Vec<Polygon>,
+    coplanar_back: &mut Vec<Polygon>,
+    front: &mut Vec<Polygon>,
+    back: &mut Vec<Polygon>,
+) {
+    const COPLANAR: u8 = 0;
+    const FRONT: u8 = 1;
+    const BACK: u8 = 2;
+    const SPANNING: u8 = 3;
+
+    let mut polygon_type = COPLANAR;
+    let types: Vec<u8> = polygon
+        .vertices
+        .iter()
+        .map(|v| {
+            let t = plane.normal.dot(*v) - plane.w;
+            let vertex_type = if t < -EPSILON {
+                BACK
+            } else if t > EPSILON {
+                FRONT
+            } else {
+                COPLANAR
+            };
+            polygon_type |= vertex_type;
+            vertex_type
+        })
+        .collect();
+
+    match polygon_type {
+        COPLANAR => {
+            if plane.normal.dot(polygon.plane.normal) > 0.0 {
+                coplanar_front.push(polygon)
+            } else {
+                coplanar_back.push(polygon)
+            }
+        }
+        FRONT => front.push(polygon),
+        BACK => back.push(polygon),
+        _ => {
+            let mut f = Vec::new();
+            let mut b = Vec::new();
+            let count = polygon.vertices.len();
+            for i in 0..count {
+                let j = (i + 1) % count;
+                let (ti, tj) = (types[i], types[j]);
+                let (vi, vj) = (polygon.vertices[i], polygon.vertices[j]);
+                if ti != BACK {
+                    f.push(vi);
+                }
+                if ti != FRONT {
+                    b.push(vi);
+                }
+                if (ti | tj) == SPANNING {
+                    let t = (plane.w - plane.normal.dot(vi)) / plane.normal.dot(vj - vi);
+                    let v = vi + (vj - vi) * t;
+                    f.push(v);
+                    b.push(v);
+                }
+            }
+            if f.len() >= 3 {
+                front.push(Polygon {
+                    vertices: f,
+                    plane: polygon.plane,
+                });
+            }
+            if b.len() >= 3 {
+                back.push(Polygon {
+                    vertices: b,
+                    plane: polygon.plane,
+                });
+            }
+        }
+    }
+}
+
+// A binary space partitioning tree, where each node splits the space by the plane of the polygons in the node.
+// The nodes are stored in a list and the tree is traversed without recursion, so deep trees cannot overflow the stack.
+struct Tree {
+    nodes: Vec<Node>,
+}
+
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<usize>,
+    back: Option<usize>,
+    polygons: Vec<Polygon>,
+}
+
+impl Tree {
+    fn new(polygons: Vec<Polygon>) -> Self {
+        let mut tree = Self {
+            nodes: vec![Node::default()],
+        };
+        tree.build(polygons);
+        tree
+    }
+
+    // Converts the solid space to empty space and the empty space to solid space
+    fn invert(&mut self) {
+        for node in self.nodes.iter_mut() {
+            for polygon in node.polygons.iter_mut() {
+                polygon.flip();
+            }
+            if let Some(ref mut plane) = node.plane {
+                plane.flip();
+            }
+            core::mem::swap(&mut node.front, &mut node.back);
+        }
+    }
+
+    // Removes the parts of the polygons that are inside the solid space of this tree
+    fn clip_polygons(&self, polygons: Vec<Polygon>) -> Vec<Polygon> {
+        let mut result = Vec::new();
+        let mut stack = vec![(0, polygons)];
+        while let Some((index, polygons)) = stack.pop() {
+            let node = &self.nodes[index];
+            let plane = match node.plane {
+                Some(plane) => plane,
+                None => {
+                    result.extend(polygons);
+                    continue;
+                }
+            };
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+            for polygon in polygons {
+                let mut coplanar_front = Vec::new();
+                let mut coplanar_back = Vec::new();
+                split_polygon(
+                    &plane,
+                    polygon,
+                    &mut coplanar_front,
+                    &mut coplanar_back,
+                    &mut front,
+                    &mut back,
+                );
+                front.extend(coplanar_front);
+                back.extend(coplanar_back);
+            }
+            match node.front {
+                Some(child) => stack.push((child, front)),
+                None => result.extend(front),
+            }
+            if let Some(child) = node.back {
+                stack.push((child, back));
+            }
+        }
+        result
+    }
+
+    // Removes the parts of the polygons in this tree that are inside the solid space of the other tree
+    fn clip_to(&mut self, other: &Tree) {
+        for node in self.nodes.iter_mut() {
+            node.polygons = other.clip_polygons(core::mem::take(&mut node.polygons));
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<Polygon> {
+        self.nodes
+            .iter()
+            .flat_map(|node| node.polygons.iter().cloned())
+            .collect()
+    }
+
+    fn build(&mut self, polygons: Vec<Polygon>) {
+        let mut stack = vec![(0, polygons)];
+        while let Some((index, polygons)) = stack.pop() {
+            if polygons.is_empty() {
+                continue;
+            }
+            let plane = *self.nodes[index].plane.get_or_insert(polygons[0].plane);
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+            for polygon in polygons {
+                let mut coplanar_front = Vec::new();
+                let mut coplanar_back = Vec::new();
+                split_polygon(
+                    &plane,
+                    polygon,
+                    &mut coplanar_front,
+                    &mut coplanar_back,
+                    &mut front,
+                    &mut back,
+                );
+                self.nodes[index].polygons.extend(coplanar_front);
+                self.nodes[index].polygons.extend(coplanar_back);
+            }
+            if !front.is_empty() {
+                let child = match self.nodes[index].front {
+                    Some(child) => child,
+                    None => {
+                        self.nodes.push(Node::default());
+                        self.nodes[index].front = Some(self.nodes.len() - 1);
+                        self.nodes.len() - 1
+                    }
+                };
+                stack.push((child, front));
+            }
+            if !back.is_empty() {
+                let child = match self.nodes[index].back {
+                    Some(child) => child,
+                    None => {
+                        self.nodes.push(Node::default());
+                        self.nodes[index].back = Some(self.nodes.len() - 1);
+                        self.nodes.len() - 1
+                    }
+                };
+                stack.push((child, back));
+            }
+        }
+    }
+}
+
+fn polygons(mesh: &CPUMesh) -> Vec<Polygon> {
+    let position = |i: u32| {
+        let i = i as usize * 3;
+        vec3(
+            mesh.positions[i],
+            mesh.positions[i + 1],
+            mesh.positions[i + 2],
+        )
+    };
+    mesh.triangle_indices()
+        .chunks_exact(3)
+        .filter_map(|t| {
+            let vertices = vec![position(t[0]), position(t[1]), position(t[2])];
+            Plane::from_points(vertices[0], vertices[1], vertices[2])
+                .map(|plane| Polygon { vertices, plane })
+        })
+        .collect()
+}
+
+// Inserts the vertices which lie in the middle of an edge of a polygon into that edge, which happens when a neighbouring polygon is split,
+// so that the triangles on both sides of the edge share the same vertices and the mesh stays closed
+fn resolve_t_junctions(polygons: &mut [Polygon]) {
+    let mut vertices: Vec<Vec3> = polygons
+        .iter()
+        .flat_map(|polygon| polygon.vertices.iter().cloned())
+        .collect();
+    vertices.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap_or(core::cmp::Ordering::Equal));
+    for polygon in polygons.iter_mut() {
+        let count = polygon.vertices.len();
+        let mut result = Vec::with_capacity(count);
+        for i in 0..count {
+            let (a, b) = (polygon.vertices[i], polygon.vertices[(i + 1) % count]);
+            result.push(a);
+            let direction = b - a;
+            let length2 = direction.magnitude2();
+            if length2 <= EPSILON * EPSILON {
+                continue;
+            }
+            let start = vertices.partition_point(|v| v.x < a.x.min(b.x) - EPSILON);
+            let mut on_edge: Vec<(f32, Vec3)> = vertices[start..]
+                .iter()
+                .take_while(|v| v.x <= a.x.max(b.x) + EPSILON)
+                .filter_map(|v| {
+                    let t = (*v - a).dot(direction) / length2;
+                    let on_edge = t > 0.0
+                        && t < 1.0
+                        && (a + direction * t - *v).magnitude() <= EPSILON
+                        && (*v - a).magnitude() > EPSILON
+                        && (*v - b).magnitude() > EPSILON;
+                    if on_edge {
+                        Some((t, *v))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            on_edge.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(core::cmp::Ordering::Equal));
+            on_edge.dedup_by(|a, b| (a.1 - b.1).magnitude() <= EPSILON);
+            result.extend(on_edge.into_iter().map(|(_, v)| v));
+        }
+        polygon.vertices = result;
+    }
+}
+
+// Triangulates the convex polygon without creating triangles with zero area, which is not possible with a simple fan
+// when the polygon has several vertices along the same edge
+fn triangulate(polygon: &Polygon, positions: &mut Vec<f32>) {
+    let is_corner = |vertices: &[Vec3], i: usize| {
+        let count = vertices.len();
+        let (a, b, c) = (
+            vertices[(i + count - 1) % count],
+            vertices[i],
+            vertices[(i + 1) % count],
+        );
+        (b - a).cross(c - a).dot(polygon.plane.normal) > EPSILON * (c - a).magnitude()
+    };
+    let mut push = |vertices: &[Vec3], i: usize| {
+        let count = vertices.len();
+        for v in [
+            vertices[(i + count - 1) % count],
+            vertices[i],
+            vertices[(i + 1) % count],
+        ]
+        .iter()
+        {
+            positions.extend_from_slice(&[v.x, v.y, v.z]);
+        }
+    };
+    let mut vertices = polygon.vertices.clone();
+    while vertices.len() > 3 {
+        // Cut off a corner, but not if the rest of the polygon is then a line
+        let ear = (0..vertices.len()).find(|i| {
+            if !is_corner(&vertices, *i) {
+                return false;
+            }
+            let mut rest = vertices.clone();
+            rest.remove(*i);
+            (0..rest.len()).any(|j| is_corner(&rest, j))
+        });
+        match ear {
+            Some(i) => {
+                push(&vertices, i);
+                vertices.remove(i);
+            }
+            None => return,
+        }
+    }
+    if vertices.len() == 3 && is_corner(&vertices, 1) {
+        push(&vertices, 1);
+    }
+}
+
+fn to_cpu_mesh(mut polygons: Vec<Polygon>, name: &str) -> CPUMesh {
+    resolve_t_junctions(&mut polygons);
+    let mut positions = Vec::new();
+    for polygon in polygons.iter() {
+        triangulate(polygon, &mut positions);
+    }
+    let mut mesh = CPUMesh {
+        name: name.to_string(),
+        positions,
+        ..Default::default()
+    };
+    mesh.weld(EPSILON);
+    mesh.remove_degenerates();
+    mesh.compute_normals();
+    mesh
+}
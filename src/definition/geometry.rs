@@ -2,18 +2,18 @@ use crate::camera::*;
 use crate::core::*;
 use crate::math::*;
 
+///
+/// The layer mask which includes all layers, which is the default layer mask of geometries, cameras and lights.
+///
+pub const ALL_LAYERS: u32 = u32::MAX;
+
 pub trait Geometry {
     ///
     /// Render only the depth into the current depth render target which is useful for shadow maps or depth pre-pass.
     /// Must be called in a render target render function,
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     ///
-    fn render_depth(
-        &self,
-        render_states: RenderStates,
-        viewport: Viewport,
-        camera: &Camera,
-    ) -> Result<(), Error>;
+    fn render_depth(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error>;
 
     ///
     /// Render the depth (scaled such that a value of 1 corresponds to max_depth) into the red channel of the current color render target which for example is used for picking.
@@ -23,10 +23,17 @@ pub trait Geometry {
     fn render_depth_to_red(
         &self,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
         max_depth: f32,
     ) -> Result<(), Error>;
 
     fn aabb(&self) -> Option<AxisAlignedBoundingBox>;
+
+    ///
+    /// Returns the render layers the geometry belongs to as a bit mask. A [camera](crate::Camera::set_layer_mask) only renders the geometry
+    /// and a light only affects the geometry if their layer masks have at least one layer in common with this mask.
+    ///
+    fn layer_mask(&self) -> u32 {
+        ALL_LAYERS
+    }
 }
@@ -0,0 +1,329 @@
+use crate::definition::*;
+use crate::math::*;
+use alloc::collections::BTreeMap;
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+///
+/// A problem with a [CPUMesh] found by [CPUMesh::validate].
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshIssue {
+    /// The number of positions is not a multiple of three.
+    InvalidPositionCount,
+    /// The number of values of the given attribute does not match the number of vertices.
+    AttributeCountMismatch { attribute: String },
+    /// The number of indices is not a multiple of three.
+    InvalidIndexCount,
+    /// An index refers to a vertex that does not exist.
+    IndexOutOfRange { index: u32, vertex_count: usize },
+    /// A position is not a finite number.
+    NonFinitePosition { vertex: usize },
+    /// A number of triangles have no area, see [CPUMesh::remove_degenerates].
+    DegenerateTriangles { count: usize },
+    /// A number of edges are shared by more than two triangles.
+    NonManifoldEdges { count: usize },
+    /// A number of edges are shared by two triangles with opposite winding, ie. one of the triangles is facing the wrong way.
+    InconsistentWinding { count: usize },
+}
+
+impl core::fmt::Display for MeshIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MeshIssue::InvalidPositionCount => {
+                write!(f, "The number of positions is not a multiple of three")
+            }
+            MeshIssue::AttributeCountMismatch { attribute } => write!(
+                f,
+                "The number of {} does not match the number of vertices",
+                attribute
+            ),
+            MeshIssue::InvalidIndexCount => {
+                write!(f, "The number of indices is not a multiple of three")
+            }
+            MeshIssue::IndexOutOfRange {
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "The index {} is out of range for a mesh with {} vertices",
+                index, vertex_count
+            ),
+            MeshIssue::NonFinitePosition { vertex } => {
+                write!(f, "The position of vertex {} is not finite", vertex)
+            }
+            MeshIssue::DegenerateTriangles { count } => {
+                write!(f, "{} triangles are degenerate", count)
+            }
+            MeshIssue::NonManifoldEdges { count } => {
+                write!(f, "{} edges are shared by more than two triangles", count)
+            }
+            MeshIssue::InconsistentWinding { count } => write!(
+                f,
+                "{} edges are shared by triangles with inconsistent winding",
+                count
+            ),
+        }
+    }
+}
+
+impl CPUMesh {
+    ///
+    /// Merges the vertices with positions closer than the given epsilon into one vertex, if they also have the same normal,
+    /// uv coordinates and color, so that for example seams in the uv coordinates are kept.
+    /// This is typically needed for imported meshes where each triangle has its own vertices, since those do not get smooth normals
+    /// when [computing the normals](CPUMesh::compute_normals).
+    /// The mesh is indexed afterwards.
+    ///
+    pub fn weld(&mut self, epsilon: f32) {
+        let epsilon = epsilon.max(core::f32::MIN_POSITIVE);
+        let vertex_count = self.positions.len() / 3;
+        let cell = |v: usize| {
+            (
+                (self.positions[v * 3] / epsilon).floor() as i64,
+                (self.positions[v * 3 + 1] / epsilon).floor() as i64,
+                (self.positions[v * 3 + 2] / epsilon).floor() as i64,
+            )
+        };
+        let close = |values: &[f32], size: usize, a: usize, b: usize| {
+            (0..size).all(|i| (values[a * size + i] - values[b * size + i]).abs() <= epsilon)
+        };
+        let is_same = |a: usize, b: usize| {
+            close(&self.positions, 3, a, b)
+                && self.normals.as_ref().map_or(true, |v| close(v, 3, a, b))
+                && self.uvs.as_ref().map_or(true, |v| close(v, 2, a, b))
+                && self.colors.as_ref().map_or(true, |colors| {
+                    colors[a * 4..a * 4 + 4] == colors[b * 4..b * 4 + 4]
+                })
+        };
+
+        let mut grid: BTreeMap<(i64, i64, i64), Vec<usize>> = BTreeMap::new();
+        let mut remap = Vec::with_capacity(vertex_count);
+        for v in 0..vertex_count {
+            let (x, y, z) = cell(v);
+            let mut found = None;
+            'search: for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        if let Some(candidates) = grid.get(&(x + dx, y + dy, z + dz)) {
+                            if let Some(c) = candidates.iter().find(|c| is_same(**c, v)) {
+                                found = Some(*c);
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+            remap.push(found.unwrap_or_else(|| {
+                grid.entry((x, y, z)).or_insert_with(Vec::new).push(v);
+                v
+            }) as u32);
+        }
+        let indices = self
+            .triangle_indices()
+            .iter()
+            .map(|i| remap[*i as usize])
+            .collect();
+        self.rebuild(indices);
+    }
+
+    ///
+    /// Removes the triangles which have no area, for example because two of its vertices are the same,
+    /// and the vertices that are no longer used by any triangle. Returns the number of removed triangles.
+    /// The mesh is indexed afterwards.
+    ///
+    pub fn remove_degenerates(&mut self) -> usize {
+        let indices = self.triangle_indices();
+        let triangle_count = indices.len() / 3;
+        let kept: Vec<u32> = indices
+            .chunks_exact(3)
+            .filter(|t| !self.is_degenerate(t[0], t[1], t[2]))
+            .flatten()
+            .cloned()
+            .collect();
+        let removed = triangle_count - kept.len() / 3;
+        self.rebuild(kept);
+        removed
+    }
+
+    ///
+    /// Flips the winding order of all triangles, ie. turns them inside out, and negates the normals.
+    ///
+    pub fn flip_winding(&mut self) {
+        match self.indices {
+            Some(Indices::U8(ref mut indices)) => flip(indices),
+            Some(Indices::U16(ref mut indices)) => flip(indices),
+            Some(Indices::U32(ref mut indices)) => flip(indices),
+            None => {
+                let vertex_count = self.positions.len() / 3;
+                let mut order: Vec<u32> = (0..vertex_count as u32).collect();
+                flip(&mut order);
+                self.reorder_vertices(&order);
+            }
+        }
+        if let Some(ref mut normals) = self.normals {
+            normals.iter_mut().for_each(|n| *n = -*n);
+        }
+    }
+
+    ///
+    /// Checks the mesh for problems, for example indices out of range, degenerate triangles or inconsistent winding,
+    /// and returns the problems found. The mesh is valid if the returned list is empty.
+    ///
+    pub fn validate(&self) -> Vec<MeshIssue> {
+        let mut issues = Vec::new();
+        if self.positions.len() % 3 != 0 {
+            issues.push(MeshIssue::InvalidPositionCount);
+        }
+        let vertex_count = self.positions.len() / 3;
+        for (attribute, count, size) in [
+            ("normals", self.normals.as_ref().map(|n| n.len()), 3),
+            ("uv coordinates", self.uvs.as_ref().map(|n| n.len()), 2),
+            ("colors", self.colors.as_ref().map(|n| n.len()), 4),
+        ]
+        .iter()
+        {
+            if let Some(count) = count {
+                if *count != vertex_count * size {
+                    issues.push(MeshIssue::AttributeCountMismatch {
+                        attribute: attribute.to_string(),
+                    });
+                }
+            }
+        }
+        if let Some(vertex) = self
+            .positions
+            .chunks_exact(3)
+            .position(|p| p.iter().any(|v| !v.is_finite()))
+        {
+            issues.push(MeshIssue::NonFinitePosition { vertex });
+        }
+
+        let indices = self.triangle_indices();
+        if indices.len() % 3 != 0 {
+            issues.push(MeshIssue::InvalidIndexCount);
+        }
+        if let Some(index) = indices.iter().find(|i| **i as usize >= vertex_count) {
+            issues.push(MeshIssue::IndexOutOfRange {
+                index: *index,
+                vertex_count,
+            });
+            return issues;
+        }
+
+        let degenerates = indices
+            .chunks_exact(3)
+            .filter(|t| self.is_degenerate(t[0], t[1], t[2]))
+            .count();
+        if degenerates > 0 {
+            issues.push(MeshIssue::DegenerateTriangles { count: degenerates });
+        }
+
+        // Count the number of times each edge is used in each direction
+        let mut edges: BTreeMap<(u32, u32), (usize, usize)> = BTreeMap::new();
+        for t in indices.chunks_exact(3) {
+            if t[0] != t[1] && t[1] != t[2] && t[2] != t[0] {
+                for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])].iter() {
+                    let count = edges.entry((*a.min(b), *a.max(b))).or_insert((0, 0));
+                    if a < b {
+                        count.0 += 1;
+                    } else {
+                        count.1 += 1;
+                    }
+                }
+            }
+        }
+        let non_manifold = edges
+            .values()
+            .filter(|(forward, backward)| forward + backward > 2)
+            .count();
+        let inconsistent = edges
+            .values()
+            .filter(|(forward, backward)| {
+                forward + backward == 2 && (*forward == 2 || *backward == 2)
+            })
+            .count();
+        if non_manifold > 0 {
+            issues.push(MeshIssue::NonManifoldEdges {
+                count: non_manifold,
+            });
+        }
+        if inconsistent > 0 {
+            issues.push(MeshIssue::InconsistentWinding {
+                count: inconsistent,
+            });
+        }
+        issues
+    }
+
+    pub(super) fn triangle_indices(&self) -> Vec<u32> {
+        self.indices
+            .as_ref()
+            .map(|indices| indices.into_u32())
+            .unwrap_or_else(|| (0..self.positions.len() as u32 / 3).collect())
+    }
+
+    fn is_degenerate(&self, a: u32, b: u32, c: u32) -> bool {
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            vec3(
+                self.positions[i],
+                self.positions[i + 1],
+                self.positions[i + 2],
+            )
+        };
+        let (pa, pb, pc) = (p(a), p(b), p(c));
+        let longest = (pb - pa)
+            .magnitude2()
+            .max((pc - pa).magnitude2())
+            .max((pc - pb).magnitude2());
+        a == b
+            || b == c
+            || c == a
+            || (pb - pa).cross(pc - pa).magnitude2() <= 1.0e-12 * longest * longest
+    }
+
+    // Sets the given indices and removes the vertices that are not referenced by any of the indices
+    fn rebuild(&mut self, indices: Vec<u32>) {
+        let mut new_index = vec![None; self.positions.len() / 3];
+        let mut order = Vec::new();
+        let indices = indices
+            .iter()
+            .map(|i| {
+                *new_index[*i as usize].get_or_insert_with(|| {
+                    order.push(*i);
+                    order.len() as u32 - 1
+                })
+            })
+            .collect();
+        self.reorder_vertices(&order);
+        self.indices = Some(Indices::U32(indices));
+    }
+
+    // Replaces the vertices with the vertices at the given indices
+    pub(super) fn reorder_vertices(&mut self, order: &[u32]) {
+        fn reorder<T: Copy>(values: &[T], size: usize, order: &[u32]) -> Vec<T> {
+            order
+                .iter()
+                .flat_map(|i| {
+                    values[*i as usize * size..(*i as usize + 1) * size]
+                        .iter()
+                        .cloned()
+                })
+                .collect()
+        }
+        self.positions = reorder(&self.positions, 3, order);
+        self.normals = self.normals.as_ref().map(|v| reorder(v, 3, order));
+        self.uvs = self.uvs.as_ref().map(|v| reorder(v, 2, order));
+        self.colors = self.colors.as_ref().map(|v| reorder(v, 4, order));
+    }
+}
+
+fn flip<T>(indices: &mut [T]) {
+    for triangle in indices.chunks_exact_mut(3) {
+        triangle.swap(1, 2);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::definition::*;
+use crate::math::*;
+use crate::procedural::signed_distance_field;
+use crate::Error;
+use ab_glyph::{Font, FontRef, ScaleFont};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+struct SdfGlyph {
+    // The area of the atlas containing the glyph, None if the glyph is empty, for example a space
+    rect: Option<UVRect>,
+    // The position of the top left corner of the glyph relative to the pen position on the baseline, in pixels with y up
+    offset: Vec2,
+    // The size of the glyph including the spread, in pixels
+    size: Vec2,
+    advance: f32,
+}
+
+///
+/// A font atlas containing [signed distance fields](crate::signed_distance_field) of the glyphs of a font,
+/// which can be used for rendering crisp text at any scale with [Mesh::render_with_sdf](crate::Mesh::render_with_sdf)
+/// as an alternative to a plain bitmap font atlas.
+///
+#[derive(Debug)]
+pub struct SdfFont {
+    /// The texture containing the signed distance fields of all of the glyphs.
+    pub texture: CPUTexture<u8>,
+    glyphs: HashMap<char, SdfGlyph>,
+    kerning: HashMap<(char, char), f32>,
+    pixel_size: f32,
+    line_height: f32,
+}
+
+impl SdfFont {
+    ///
+    /// Generates a signed distance field font atlas of the given characters from the given TrueType or OpenType font data.
+    /// The glyphs are rasterized at the given size in pixels, where a few tens of pixels is usually enough for sharp text at any scale,
+    /// and the spread is the distance in pixels from the edge of the glyphs where the distance field ends, which limits the width of the outlines.
+    ///
+    /// # Errors
+    /// Will return an error if the font data cannot be parsed.
+    ///
+    pub fn new(
+        font_data: &[u8],
+        characters: &str,
+        pixel_size: f32,
+        spread: u32,
+    ) -> Result<Self, Error> {
+        let font = FontRef::try_from_slice(font_data).map_err(|e| Error::FontError {
+            message: format!("Could not parse the font: {}", e),
+        })?;
+        let scaled_font = font.as_scaled(pixel_size);
+
+        let mut characters: Vec<char> = characters.chars().collect();
+        characters.sort();
+        characters.dedup();
+        let mut glyphs = HashMap::new();
+        let mut fields = Vec::new();
+        for c in characters.iter() {
+            let id = font.glyph_id(*c);
+            let advance = scaled_font.h_advance(id);
+            let glyph = match font.outline_glyph(id.with_scale(pixel_size)) {
+                Some(outline) => {
+                    let bounds = outline.px_bounds();
+                    let width = bounds.width() as u32 + 2 * spread;
+                    let height = bounds.height() as u32 + 2 * spread;
+                    let mut mask = CPUTexture {
+                        data: vec![0u8; width as usize * height as usize],
+                        width,
+                        height,
+                        format: Format::R,
+                        ..Default::default()
+                    };
+                    outline.draw(|x, y, coverage| {
+                        let index = ((y + spread) * width + x + spread) as usize;
+                        if index < mask.data.len() {
+                            mask.data[index] = (coverage * 255.0).round() as u8;
+                        }
+                    });
+                    fields.push((*c, signed_distance_field(&mask, spread)));
+                    SdfGlyph {
+                        rect: None,
+                        offset: vec2(bounds.min.x - spread as f32, -bounds.min.y + spread as f32),
+                        size: vec2(width as f32, height as f32),
+                        advance,
+                    }
+                }
+                None => SdfGlyph {
+                    rect: None,
+                    offset: vec2(0.0, 0.0),
+                    size: vec2(0.0, 0.0),
+                    advance,
+                },
+            };
+            glyphs.insert(*c, glyph);
+        }
+
+        let texture = if fields.is_empty() {
+            CPUTexture {
+                data: vec![0u8],
+                format: Format::R,
+                ..Default::default()
+            }
+        } else {
+            let atlas = TextureAtlas::new(
+                &fields.iter().map(|(_, field)| field).collect::<Vec<_>>(),
+                1,
+            )?;
+            for ((c, _), rect) in fields.iter().zip(atlas.rects.iter()) {
+                glyphs.get_mut(c).unwrap().rect = Some(*rect);
+            }
+            CPUTexture {
+                mip_map_filter: None,
+                ..atlas.texture
+            }
+        };
+
+        let mut kerning = HashMap::new();
+        for a in characters.iter() {
+            for b in characters.iter() {
+                let kern = scaled_font.kern(font.glyph_id(*a), font.glyph_id(*b));
+                if kern != 0.0 {
+                    kerning.insert((*a, *b), kern);
+                }
+            }
+        }
+
+        Ok(Self {
+            texture,
+            glyphs,
+            kerning,
+            pixel_size,
+            line_height: scaled_font.height() + scaled_font.line_gap(),
+        })
+    }
+
+    ///
+    /// Returns the width of the given single line of text in the same units as the [text mesh](Self::text_mesh), ie. where one unit is the size of the font,
+    /// including the advance of the last character, for example used for placing a text cursor after the text.
+    /// Characters which are not in the atlas are skipped.
+    ///
+    pub fn text_width(&self, text: &str) -> f32 {
+        let mut width = 0.0;
+        let mut previous = None;
+        for c in text.chars() {
+            if let Some(glyph) = self.glyphs.get(&c) {
+                if let Some(previous) = previous {
+                    width += self.kerning.get(&(previous, c)).cloned().unwrap_or(0.0);
+                }
+                width += glyph.advance;
+                previous = Some(c);
+            }
+        }
+        width / self.pixel_size
+    }
+
+    ///
+    /// Returns a mesh with a quad for each character in the given text with uv coordinates into the [texture](SdfFont::texture).
+    /// The text is laid out in the xy-plane starting at the origin with the first baseline along the x-axis, where one unit is the size of the font,
+    /// and each new line in the text starts a new line below the previous.
+    /// Characters which are not in the atlas are skipped.
+    ///
+    pub fn text_mesh(&self, text: &str) -> CPUMesh {
+        let scale = 1.0 / self.pixel_size;
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        let mut pen = vec2(0.0, 0.0);
+        let mut previous = None;
+        for c in text.chars() {
+            if c == '\n' {
+                pen = vec2(0.0, pen.y - self.line_height);
+                previous = None;
+                continue;
+            }
+            let glyph = match self.glyphs.get(&c) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            if let Some(previous) = previous {
+                pen.x += self.kerning.get(&(previous, c)).cloned().unwrap_or(0.0);
+            }
+            if let Some(rect) = glyph.rect {
+                let top_left = pen + glyph.offset;
+                let bottom_right = top_left + vec2(glyph.size.x, -glyph.size.y);
+                let offset = positions.len() as u32 / 3;
+                for (x, y, u, v) in [
+                    (top_left.x, bottom_right.y, rect.u_min, 1.0 - rect.v_max),
+                    (bottom_right.x, bottom_right.y, rect.u_max, 1.0 - rect.v_max),
+                    (bottom_right.x, top_left.y, rect.u_max, 1.0 - rect.v_min),
+                    (top_left.x, top_left.y, rect.u_min, 1.0 - rect.v_min),
+                ]
+                .iter()
+                {
+                    positions.extend_from_slice(&[x * scale, y * scale, 0.0]);
+                    uvs.extend_from_slice(&[*u, *v]);
+                }
+                indices.extend([0, 1, 2, 2, 3, 0].iter().map(|i| offset + i));
+            }
+            pen.x += glyph.advance;
+            previous = Some(c);
+        }
+        CPUMesh {
+            name: text.to_string(),
+            positions,
+            uvs: Some(uvs),
+            indices: Some(Indices::U32(indices)),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use crate::math::*;
+use crate::Error;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+///
+/// The transformation of a [Joint] relative to its parent joint, given as a translation, a rotation and a scale,
+/// which can be interpolated, for example when blending animations.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct JointTransform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl JointTransform {
+    ///
+    /// Returns the transform which does not change anything.
+    ///
+    pub fn identity() -> Self {
+        Self {
+            translation: vec3(0.0, 0.0, 0.0),
+            rotation: Quat::one(),
+            scale: vec3(1.0, 1.0, 1.0),
+        }
+    }
+
+    ///
+    /// Returns the transform as a transformation matrix, ie. scale, then rotation and then translation.
+    ///
+    pub fn to_matrix(&self) -> Mat4 {
+        Mat4::from_translation(self.translation)
+            * Mat4::from(self.rotation)
+            * Mat4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+
+    ///
+    /// Returns the interpolation between this and the other transform,
+    /// where a factor of 0 returns this transform and a factor of 1 returns the other transform.
+    ///
+    pub fn interpolate(&self, other: &Self, factor: f32) -> Self {
+        // Interpolate along the shortest path between the rotations
+        let other_rotation = if self.rotation.dot(other.rotation) < 0.0 {
+            -other.rotation
+        } else {
+            other.rotation
+        };
+        Self {
+            translation: self.translation.lerp(other.translation, factor),
+            rotation: self.rotation.nlerp(other_rotation, factor),
+            scale: self.scale.lerp(other.scale, factor),
+        }
+    }
+}
+
+impl Default for JointTransform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+///
+/// A joint (or bone) in a [Skeleton].
+///
+#[derive(Debug, Clone)]
+pub struct Joint {
+    /// The name of the joint, for example used for looking up the joint when attaching objects to it.
+    pub name: String,
+    /// The index of the parent joint in the skeleton or `None` if it is a root joint.
+    pub parent: Option<usize>,
+    /// The transform relative to the parent joint when no animation is applied.
+    pub rest_transform: JointTransform,
+    /// The transformation from model space to the local space of the joint in the pose the mesh was modelled in (the bind pose).
+    pub inverse_bind_matrix: Mat4,
+}
+
+///
+/// A named point attached to a joint of a [Skeleton] with an offset,
+/// for example the hand holding a weapon or the point above the head where a name label is shown.
+///
+#[derive(Debug, Clone)]
+pub struct Socket {
+    /// The name of the socket.
+    pub name: String,
+    /// The index of the joint the socket is attached to.
+    pub joint: usize,
+    /// The transformation from the local space of the socket to the local space of the joint.
+    pub offset: Mat4,
+}
+
+///
+/// A hierarchy of joints which are posed, for example by an animation, to deform a skinned mesh.
+/// Objects can be attached to [sockets](Socket) on the joints and follow the posed joints:
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let mut skeleton: Skeleton = unimplemented!();
+/// # let mut sword: Mesh = unimplemented!();
+/// # let character_transformation = Mat4::identity();
+/// skeleton.add_socket("weapon", "hand_r", Mat4::from_angle_x(degrees(90.0))).unwrap();
+/// // Each frame after the skeleton is posed:
+/// skeleton.update();
+/// sword.transformation = character_transformation * skeleton.socket_transformation("weapon").unwrap();
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Skeleton {
+    joints: Vec<Joint>,
+    sockets: Vec<Socket>,
+    pose: Vec<JointTransform>,
+    joint_transformations: Vec<Mat4>,
+}
+
+impl Skeleton {
+    ///
+    /// Creates a new skeleton with the given joints in the rest pose.
+    ///
+    /// # Errors
+    /// Will return an error if the parent of a joint does not come before the joint itself.
+    ///
+    pub fn new(joints: Vec<Joint>) -> Result<Self, Error> {
+        for (index, joint) in joints.iter().enumerate() {
+            if joint.parent.map_or(false, |parent| parent >= index) {
+                Err(Error::SkeletonError {
+                    message: format!(
+                        "The parent of the joint {} must come before the joint itself",
+                        joint.name
+                    ),
+                })?;
+            }
+        }
+        let pose = joints.iter().map(|joint| joint.rest_transform).collect();
+        let mut skeleton = Self {
+            joint_transformations: vec![Mat4::identity(); joints.len()],
+            joints,
+            sockets: Vec::new(),
+            pose,
+        };
+        skeleton.update();
+        Ok(skeleton)
+    }
+
+    ///
+    /// Returns the joints of the skeleton, the parents always come before their children.
+    ///
+    pub fn joints(&self) -> &[Joint] {
+        &self.joints
+    }
+
+    ///
+    /// Returns the index of the joint with the given name.
+    ///
+    pub fn joint_index(&self, name: &str) -> Option<usize> {
+        self.joints.iter().position(|joint| joint.name == name)
+    }
+
+    ///
+    /// Returns the current transform of the joint with the given index relative to its parent.
+    ///
+    pub fn local_transform(&self, joint: usize) -> &JointTransform {
+        &self.pose[joint]
+    }
+
+    ///
+    /// Sets the transform of the joint with the given index relative to its parent.
+    /// Call [update](Self::update) when the whole skeleton is posed to update the joint transformations.
+    ///
+    pub fn set_local_transform(&mut self, joint: usize, transform: JointTransform) {
+        self.pose[joint] = transform;
+    }
+
+    ///
+    /// Returns the current transforms of all joints relative to their parents.
+    ///
+    pub fn pose(&self) -> &[JointTransform] {
+        &self.pose
+    }
+
+    ///
+    /// Sets the transforms of all joints relative to their parents, for example sampled from an animation.
+    /// Call [update](Self::update) afterwards to update the joint transformations.
+    ///
+    pub fn set_pose(&mut self, pose: &[JointTransform]) {
+        let count = self.pose.len();
+        self.pose.copy_from_slice(&pose[..count]);
+    }
+
+    ///
+    /// Sets all joints to their rest transform.
+    ///
+    pub fn reset_pose(&mut self) {
+        for (transform, joint) in self.pose.iter_mut().zip(self.joints.iter()) {
+            *transform = joint.rest_transform;
+        }
+    }
+
+    ///
+    /// Updates the transformations of the joints from model space to the local space of the joints given the current pose.
+    ///
+    pub fn update(&mut self) {
+        for index in 0..self.joints.len() {
+            let local = self.pose[index].to_matrix();
+            self.joint_transformations[index] = match self.joints[index].parent {
+                Some(parent) => self.joint_transformations[parent] * local,
+                None => local,
+            };
+        }
+    }
+
+    ///
+    /// Returns the transformation of the joint with the given index in model space as computed by the last call to [update](Self::update).
+    ///
+    pub fn joint_transformation(&self, joint: usize) -> Mat4 {
+        self.joint_transformations[joint]
+    }
+
+    ///
+    /// Returns the skinning matrices, ie. the transformation of each vertex from the bind pose to the current pose for each joint,
+    /// as computed by the last call to [update](Self::update).
+    ///
+    pub fn skinning_matrices(&self) -> Vec<Mat4> {
+        self.joint_transformations
+            .iter()
+            .zip(self.joints.iter())
+            .map(|(transformation, joint)| transformation * joint.inverse_bind_matrix)
+            .collect()
+    }
+
+    ///
+    /// Adds a socket with the given name to the joint with the given name. The offset is the transformation from the local space of the socket to the local space of the joint.
+    /// Replaces any existing socket with the same name.
+    ///
+    /// # Errors
+    /// Will return an error if the skeleton has no joint with the given name.
+    ///
+    pub fn add_socket(&mut self, name: &str, joint_name: &str, offset: Mat4) -> Result<(), Error> {
+        let joint = self
+            .joint_index(joint_name)
+            .ok_or_else(|| Error::SkeletonError {
+                message: format!("The skeleton has no joint named {}", joint_name),
+            })?;
+        self.remove_socket(name);
+        self.sockets.push(Socket {
+            name: name.to_string(),
+            joint,
+            offset,
+        });
+        Ok(())
+    }
+
+    ///
+    /// Removes the socket with the given name. Returns false if there is no such socket.
+    ///
+    pub fn remove_socket(&mut self, name: &str) -> bool {
+        let count = self.sockets.len();
+        self.sockets.retain(|socket| socket.name != name);
+        self.sockets.len() != count
+    }
+
+    ///
+    /// Returns the sockets of the skeleton.
+    ///
+    pub fn sockets(&self) -> &[Socket] {
+        &self.sockets
+    }
+
+    ///
+    /// Returns the transformation from the local space of the socket with the given name to model space in the current pose,
+    /// which should be multiplied with the transformation of the skinned mesh to place an attached object in world space.
+    /// Returns `None` if there is no socket with the given name.
+    ///
+    pub fn socket_transformation(&self, name: &str) -> Option<Mat4> {
+        self.sockets
+            .iter()
+            .find(|socket| socket.name == name)
+            .map(|socket| self.joint_transformations[socket.joint] * socket.offset)
+    }
+}
+
+///
+/// The joints influencing each vertex of a mesh deformed by a [Skeleton] and how much they influence it.
+/// Each vertex is influenced by up to four joints.
+///
+#[derive(Debug, Clone, Default)]
+pub struct CPUSkin {
+    /// The indices of the four joints influencing each vertex.
+    pub joints: Vec<u32>,
+    /// The weights of the four joints influencing each vertex, the weights of each vertex should sum to one.
+    pub weights: Vec<f32>,
+}
@@ -0,0 +1,263 @@
+use crate::definition::*;
+use crate::math::*;
+use alloc::{string::ToString, vec, vec::Vec};
+
+impl CPUMesh {
+    ///
+    /// Constructs a mesh by extruding the given 2D profile in the xy-plane along the given direction, for example a wall from a floor plan.
+    /// If the profile is closed, ie. the last point is connected to the first point, the ends are capped and the profile should be counter clockwise
+    /// for the triangles to face outwards.
+    /// Corners sharper than 30 degrees in the profile have hard edges, other corners are smooth.
+    ///
+    pub fn extrude(profile: &[Vec2], closed: bool, direction: Vec3) -> Self {
+        let mut profile: Vec<Vec3> = profile.iter().map(|p| vec3(p.x, p.y, 0.0)).collect();
+        if direction.z < 0.0 {
+            profile.reverse();
+        }
+        let end = profile.iter().map(|p| p + direction).collect();
+        sweep("extrusion", vec![profile, end], closed, false)
+    }
+
+    ///
+    /// Constructs a mesh by extruding the given 2D profile along the given path, for example a tube or a rail.
+    /// The xy-plane of the profile is kept perpendicular to the path and is rotated as little as possible along the path.
+    /// If the profile is closed, ie. the last point is connected to the first point, the ends are capped and the profile should be counter clockwise
+    /// for the triangles to face outwards.
+    /// Corners sharper than 30 degrees in the profile have hard edges, other corners are smooth.
+    ///
+    pub fn extrude_along_path(profile: &[Vec2], closed: bool, path: &[Vec3]) -> Self {
+        if path.len() < 2 {
+            return sweep("extrusion", Vec::new(), closed, false);
+        }
+        let direction = |i: usize| {
+            let d = path[(i + 1).min(path.len() - 1)] - path[i.max(1) - 1];
+            if d.magnitude2() > 0.0 {
+                d.normalize()
+            } else {
+                vec3(0.0, 0.0, 1.0)
+            }
+        };
+        let mut rings = Vec::with_capacity(path.len());
+        let mut x = perpendicular(direction(0));
+        for (i, point) in path.iter().enumerate() {
+            // Parallel transport the frame along the path
+            let tangent = direction(i);
+            let projected = x - tangent * x.dot(tangent);
+            if projected.magnitude2() > 0.0 {
+                x = projected.normalize();
+            }
+            let y = tangent.cross(x);
+            rings.push(profile.iter().map(|p| point + x * p.x + y * p.y).collect());
+        }
+        sweep("extrusion", rings, closed, false)
+    }
+
+    ///
+    /// Constructs a surface of revolution by rotating the given 2D profile, where x is the distance to the axis and y is the height, around the y-axis,
+    /// for example a vase or a bottle.
+    /// The profile is rotated the given angle, where a full turn gives a closed surface, in the given number of steps.
+    /// The triangles face outwards if the profile goes upwards, ie. if a closed profile is counter clockwise.
+    /// If the profile is closed and the angle is less than a full turn, the ends are capped.
+    ///
+    pub fn revolve(
+        profile: &[Vec2],
+        closed: bool,
+        angle: impl Into<Radians>,
+        angle_subdivisions: u32,
+    ) -> Self {
+        let angle = angle.into().0;
+        let full_turn = angle.abs() >= 2.0 * core::f32::consts::PI - 0.0001;
+        let steps = angle_subdivisions.max(1);
+        let ring_count = if full_turn { steps } else { steps + 1 };
+        let rings = (0..ring_count)
+            .map(|i| {
+                let a = angle * i as f32 / steps as f32;
+                profile
+                    .iter()
+                    .map(|p| vec3(p.x * a.cos(), p.y, p.x * a.sin()))
+                    .collect()
+            })
+            .collect();
+        sweep("revolution", rings, closed, full_turn)
+    }
+
+    ///
+    /// Constructs a mesh by connecting the given profiles, which must all have the same number of points, for example a boat hull from its cross sections.
+    /// If the profiles are closed, ie. the last point is connected to the first point, the ends are capped and the profiles should be counter clockwise
+    /// when seen from the last profile for the triangles to face outwards.
+    /// Corners sharper than 30 degrees in the first profile have hard edges, other corners are smooth.
+    ///
+    pub fn loft(profiles: &[Vec<Vec3>], closed: bool) -> Self {
+        sweep("loft", profiles.to_vec(), closed, false)
+    }
+}
+
+// Connects each point in each ring to the corresponding point in the next ring and caps the ends if the rings are closed and the sweep is not
+fn sweep(name: &str, rings: Vec<Vec<Vec3>>, closed_profile: bool, closed_sweep: bool) -> CPUMesh {
+    let point_count = rings.iter().map(|r| r.len()).min().unwrap_or(0);
+    if rings.len() < 2 || point_count < 2 {
+        return CPUMesh {
+            name: name.to_string(),
+            ..Default::default()
+        };
+    }
+    let ring_count = rings.len();
+    let edge_count = if closed_profile {
+        point_count
+    } else {
+        point_count - 1
+    };
+
+    // Split the vertices at sharp corners such that the normals are not smoothed across the corners
+    let profile = &rings[0];
+    let max_cos = 30.0f32.to_radians().cos();
+    let mut columns = Vec::new();
+    let mut first_column = vec![0; point_count];
+    let mut last_column = vec![0; point_count];
+    for j in 0..point_count {
+        let has_previous = closed_profile || j > 0;
+        let has_next = closed_profile || j + 1 < point_count;
+        let is_sharp = has_previous && has_next && {
+            let previous = profile[(j + point_count - 1) % point_count];
+            let next = profile[(j + 1) % point_count];
+            (profile[j] - previous)
+                .normalize()
+                .dot((next - profile[j]).normalize())
+                < max_cos
+        };
+        first_column[j] = columns.len();
+        columns.push(j);
+        if is_sharp {
+            columns.push(j);
+        }
+        last_column[j] = columns.len() - 1;
+    }
+    let column_count = columns.len();
+
+    let mut positions = Vec::with_capacity(ring_count * column_count * 3);
+    for ring in rings.iter() {
+        for j in columns.iter() {
+            positions.extend_from_slice(&[ring[*j].x, ring[*j].y, ring[*j].z]);
+        }
+    }
+    let mut indices = Vec::new();
+    let sweep_count = if closed_sweep {
+        ring_count
+    } else {
+        ring_count - 1
+    };
+    for i in 0..sweep_count {
+        let r0 = (i * column_count) as u32;
+        let r1 = (((i + 1) % ring_count) * column_count) as u32;
+        for j in 0..edge_count {
+            let c0 = last_column[j] as u32;
+            let c1 = first_column[(j + 1) % point_count] as u32;
+            indices.extend_from_slice(&[r0 + c0, r0 + c1, r1 + c1, r0 + c0, r1 + c1, r1 + c0]);
+        }
+    }
+    let mut mesh = CPUMesh {
+        name: name.to_string(),
+        positions,
+        indices: Some(Indices::U32(indices)),
+        ..Default::default()
+    };
+    mesh.compute_normals();
+
+    if closed_profile && !closed_sweep && point_count >= 3 {
+        add_cap(&mut mesh, &rings[0][..point_count], true);
+        add_cap(&mut mesh, &rings[ring_count - 1][..point_count], false);
+    }
+    mesh
+}
+
+fn add_cap(mesh: &mut CPUMesh, polygon: &[Vec3], flip: bool) {
+    // Newell's method for the normal of a polygon
+    let mut normal = vec3(0.0, 0.0, 0.0);
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        normal += vec3(
+            (a.y - b.y) * (a.z + b.z),
+            (a.z - b.z) * (a.x + b.x),
+            (a.x - b.x) * (a.y + b.y),
+        );
+    }
+    if normal.magnitude2() <= 0.0 {
+        return;
+    }
+    let normal = normal.normalize();
+    let u = perpendicular(normal);
+    let v = normal.cross(u);
+    let points: Vec<Vec2> = polygon.iter().map(|p| vec2(p.dot(u), p.dot(v))).collect();
+
+    let offset = mesh.positions.len() as u32 / 3;
+    let normal = if flip { -normal } else { normal };
+    for p in polygon.iter() {
+        mesh.positions.extend_from_slice(&[p.x, p.y, p.z]);
+        if let Some(ref mut normals) = mesh.normals {
+            normals.extend_from_slice(&[normal.x, normal.y, normal.z]);
+        }
+    }
+    if let Some(Indices::U32(ref mut indices)) = mesh.indices {
+        for triangle in triangulate(&points).chunks_exact(3) {
+            if flip {
+                indices.extend_from_slice(&[
+                    offset + triangle[0],
+                    offset + triangle[2],
+                    offset + triangle[1],
+                ]);
+            } else {
+                indices.extend(triangle.iter().map(|i| offset + i));
+            }
+        }
+    }
+}
+
+///
+/// Triangulates the given simple counter clockwise polygon using ear clipping and returns the indices of the triangles.
+///
+pub(crate) fn triangulate(polygon: &[Vec2]) -> Vec<u32> {
+    let cross = |a: Vec2, b: Vec2, c: Vec2| (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+    let mut remaining: Vec<u32> = (0..polygon.len() as u32).collect();
+    let mut indices = Vec::with_capacity(polygon.len().saturating_sub(2) * 3);
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let is_ear = |i: usize| {
+            let (a, b, c) = (
+                polygon[remaining[(i + n - 1) % n] as usize],
+                polygon[remaining[i] as usize],
+                polygon[remaining[(i + 1) % n] as usize],
+            );
+            cross(a, b, c) > 0.0
+                && remaining.iter().all(|r| {
+                    let p = polygon[*r as usize];
+                    p == a
+                        || p == b
+                        || p == c
+                        || cross(a, b, p) < 0.0
+                        || cross(b, c, p) < 0.0
+                        || cross(c, a, p) < 0.0
+                })
+        };
+        // If there are no ears, the polygon is not simple, so just clip any vertex to guarantee progress
+        let ear = (0..n).find(|i| is_ear(*i)).unwrap_or(0);
+        indices.extend_from_slice(&[
+            remaining[(ear + n - 1) % n],
+            remaining[ear],
+            remaining[(ear + 1) % n],
+        ]);
+        remaining.remove(ear);
+    }
+    if remaining.len() == 3 {
+        indices.extend_from_slice(&remaining);
+    }
+    indices
+}
+
+fn perpendicular(direction: Vec3) -> Vec3 {
+    let helper = if direction.x.abs() < 0.9 {
+        vec3(1.0, 0.0, 0.0)
+    } else {
+        vec3(0.0, 1.0, 0.0)
+    };
+    helper.cross(direction).normalize()
+}
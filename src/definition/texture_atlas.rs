@@ -0,0 +1,137 @@
+use crate::definition::*;
+use crate::Error;
+use alloc::{string::ToString, vec, vec::Vec};
+
+///
+/// A rectangle in uv coordinates, for example the area of a [texture atlas](TextureAtlas) that contains one of the packed images.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UVRect {
+    pub u_min: f32,
+    pub v_min: f32,
+    pub u_max: f32,
+    pub v_max: f32,
+}
+
+impl UVRect {
+    ///
+    /// Maps the given uv coordinates, which must be between 0 and 1, into this rectangle.
+    ///
+    pub fn map(&self, uv: (f32, f32)) -> (f32, f32) {
+        (
+            self.u_min + uv.0 * (self.u_max - self.u_min),
+            self.v_min + uv.1 * (self.v_max - self.v_min),
+        )
+    }
+}
+
+///
+/// Many images packed into one large texture, for example used for batching objects with different textures into one draw call,
+/// for sprite sheets or for font atlases.
+///
+pub struct TextureAtlas<T: TextureDataType> {
+    /// The texture containing all of the packed images.
+    pub texture: CPUTexture<T>,
+    /// The area of the texture containing each of the packed images, in the same order as the images were given.
+    pub rects: Vec<UVRect>,
+}
+
+impl<T: TextureDataType> TextureAtlas<T> {
+    ///
+    /// Packs the given images into one texture, with the given number of pixels of padding around each image to avoid bleeding between the images when filtering.
+    /// The images are packed in rows sorted by height and the width of the atlas is the smallest power of two that makes the atlas roughly square.
+    ///
+    /// # Errors
+    /// Will return an error if no images are given or if the images does not have the same format and color space.
+    ///
+    pub fn new(textures: &[&CPUTexture<T>], padding: u32) -> Result<Self, Error> {
+        let first = textures.first().ok_or(Error::TextureError {
+            message: "Cannot create a texture atlas without any images.".to_string(),
+        })?;
+        let format = first.format;
+        let is_srgb = first.is_srgb;
+        if textures
+            .iter()
+            .any(|t| t.format != format || t.is_srgb != is_srgb)
+        {
+            return Err(Error::TextureError {
+                message: "All images in a texture atlas must have the same format.".to_string(),
+            });
+        }
+
+        let sizes: Vec<(u32, u32)> = textures
+            .iter()
+            .map(|t| (t.width + 2 * padding, t.height + 2 * padding))
+            .collect();
+        let area: u64 = sizes.iter().map(|(w, h)| *w as u64 * *h as u64).sum();
+        let max_width = sizes.iter().map(|(w, _)| *w).max().unwrap();
+        let width = ((area as f64).sqrt().ceil() as u32)
+            .max(max_width)
+            .next_power_of_two();
+
+        // Shelf packing: place the images in rows, tallest first
+        let mut order: Vec<usize> = (0..textures.len()).collect();
+        order.sort_by(|a, b| sizes[*b].1.cmp(&sizes[*a].1));
+        let mut positions = vec![(0, 0); textures.len()];
+        let (mut x, mut y, mut row_height) = (0, 0, 0);
+        for i in order {
+            let (w, h) = sizes[i];
+            if x + w > width {
+                x = 0;
+                y += row_height;
+                row_height = 0;
+            }
+            positions[i] = (x + padding, y + padding);
+            x += w;
+            row_height = row_height.max(h);
+        }
+        let height = y + row_height;
+
+        let channels = format.color_channel_count() as usize;
+        let mut data = vec![T::default(); width as usize * height as usize * channels];
+        let mut rects = Vec::with_capacity(textures.len());
+        for (texture, (x0, y0)) in textures.iter().zip(positions.iter()) {
+            for y in 0..texture.height as usize {
+                let source = y * texture.width as usize * channels;
+                let dest = ((*y0 as usize + y) * width as usize + *x0 as usize) * channels;
+                let length = texture.width as usize * channels;
+                data[dest..dest + length].clone_from_slice(&texture.data[source..source + length]);
+            }
+            rects.push(UVRect {
+                u_min: *x0 as f32 / width as f32,
+                v_min: *y0 as f32 / height as f32,
+                u_max: (*x0 + texture.width) as f32 / width as f32,
+                v_max: (*y0 + texture.height) as f32 / height as f32,
+            });
+        }
+
+        Ok(Self {
+            texture: CPUTexture {
+                data,
+                width,
+                height,
+                format,
+                is_srgb,
+                wrap_s: Wrapping::ClampToEdge,
+                wrap_t: Wrapping::ClampToEdge,
+                ..Default::default()
+            },
+            rects,
+        })
+    }
+
+    ///
+    /// Rewrites the uv coordinates of the given mesh so that they map into the area of the atlas which contains the image with the given index.
+    /// This only works if the uv coordinates of the mesh are between 0 and 1, ie. repeating textures cannot be packed into an atlas.
+    ///
+    pub fn remap_uvs(&self, mesh: &mut CPUMesh, index: usize) {
+        if let Some(ref mut uvs) = mesh.uvs {
+            let rect = self.rects[index];
+            for uv in uvs.chunks_mut(2) {
+                let (u, v) = rect.map((uv[0], uv[1]));
+                uv[0] = u;
+                uv[1] = v;
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::definition::*;
+use crate::math::*;
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+
+///
+/// Options for [CPUMesh::unwrap_uvs].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UVUnwrapOptions {
+    /// The maximum angle between the normal of a triangle and the average normal of the chart it is added to.
+    /// A smaller angle gives less distortion but more charts and seams.
+    pub max_chart_angle: Radians,
+    /// The width and height in texels of the texture which the uv coordinates are generated for, for example a lightmap.
+    pub resolution: u32,
+    /// The number of texels between the charts to avoid bleeding between the charts when filtering.
+    pub padding: u32,
+    /// The number of texels per unit in the space of the positions.
+    /// If `None`, or if the charts do not fit into the texture with the given density,
+    /// the largest density where all charts fit into the texture is used.
+    pub texels_per_unit: Option<f32>,
+}
+
+impl Default for UVUnwrapOptions {
+    fn default() -> Self {
+        Self {
+            max_chart_angle: degrees(60.0).into(),
+            resolution: 1024,
+            padding: 2,
+            texels_per_unit: None,
+        }
+    }
+}
+
+///
+/// The result of [CPUMesh::unwrap_uvs].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UVUnwrapResult {
+    /// The number of charts, ie. connected areas of the mesh which are mapped into the texture without seams.
+    pub chart_count: usize,
+    /// The number of texels per unit in the space of the positions which was used.
+    pub texels_per_unit: f32,
+}
+
+struct Chart {
+    triangles: Vec<usize>,
+    // The chart local 2D coordinates of the triangle corners
+    coordinates: Vec<[Vec2; 3]>,
+    min: Vec2,
+    size: Vec2,
+}
+
+impl CPUMesh {
+    ///
+    /// Generates new uv coordinates that maps each triangle of the mesh to its own area of a texture,
+    /// for example so that procedurally generated or scanned meshes can be given a lightmap or be textured.
+    /// The triangles are grouped into charts of neighbouring triangles facing roughly the same direction, each chart is projected onto a plane
+    /// and the charts are packed into the texture with the given padding between them.
+    /// Vertices are split along the borders of the charts, and the mesh is indexed afterwards.
+    /// Any existing uv coordinates are replaced.
+    ///
+    pub fn unwrap_uvs(&mut self, options: UVUnwrapOptions) -> UVUnwrapResult {
+        let indices = self.triangle_indices();
+        let position = |i: u32| {
+            let i = i as usize * 3;
+            vec3(
+                self.positions[i],
+                self.positions[i + 1],
+                self.positions[i + 2],
+            )
+        };
+        let triangle_count = indices.len() / 3;
+        let corners: Vec<[Vec3; 3]> = indices
+            .chunks_exact(3)
+            .map(|t| [position(t[0]), position(t[1]), position(t[2])])
+            .collect();
+        let area_normals: Vec<Vec3> = corners
+            .iter()
+            .map(|c| (c[1] - c[0]).cross(c[2] - c[0]))
+            .collect();
+
+        // Find the neighbours of each triangle by comparing positions, such that unindexed meshes are also connected
+        let key = |p: Vec3| (p.x.to_bits(), p.y.to_bits(), p.z.to_bits());
+        let mut edges = BTreeMap::new();
+        for (t, c) in corners.iter().enumerate() {
+            for (a, b) in [(c[0], c[1]), (c[1], c[2]), (c[2], c[0])].iter() {
+                let (a, b) = (key(*a), key(*b));
+                edges
+                    .entry((a.min(b), a.max(b)))
+                    .or_insert_with(Vec::new)
+                    .push(t);
+            }
+        }
+
+        // Grow charts from seed triangles while the triangles face roughly the same direction as the chart
+        let min_cos = options.max_chart_angle.0.cos();
+        let mut chart_of = vec![None; triangle_count];
+        let mut charts = Vec::new();
+        for seed in 0..triangle_count {
+            if chart_of[seed].is_some() {
+                continue;
+            }
+            let chart = charts.len();
+            chart_of[seed] = Some(chart);
+            let mut triangles = vec![seed];
+            let mut sum = area_normals[seed];
+            let mut next = 0;
+            while next < triangles.len() {
+                let t = triangles[next];
+                next += 1;
+                let c = corners[t];
+                for (a, b) in [(c[0], c[1]), (c[1], c[2]), (c[2], c[0])].iter() {
+                    let (a, b) = (key(*a), key(*b));
+                    for neighbour in edges[&(a.min(b), a.max(b))].iter() {
+                        if chart_of[*neighbour].is_none()
+                            && area_normals[*neighbour].normalize().dot(sum.normalize()) >= min_cos
+                        {
+                            chart_of[*neighbour] = Some(chart);
+                            sum += area_normals[*neighbour];
+                            triangles.push(*neighbour);
+                        }
+                    }
+                }
+            }
+            charts.push(Self::project_chart(triangles, sum, &corners));
+        }
+
+        // Pack the charts into the texture, decreasing the density until all charts fit
+        let total_area: f32 = charts.iter().map(|c| c.size.x * c.size.y).sum();
+        let resolution = options.resolution.max(1) as f32;
+        let mut texels_per_unit = options.texels_per_unit.unwrap_or_else(|| {
+            if total_area > 0.0 {
+                resolution / total_area.sqrt()
+            } else {
+                1.0
+            }
+        });
+        let mut offsets = pack(&charts, texels_per_unit, options);
+        // Give up when the density is negligible, ie. when the padding alone does not fit
+        while offsets.is_none() && texels_per_unit > 1.0e-6 {
+            texels_per_unit *= 0.95;
+            offsets = pack(&charts, texels_per_unit, options);
+        }
+        let offsets = offsets.unwrap_or_else(|| vec![vec2(0.0, 0.0); charts.len()]);
+
+        // Split the vertices along the chart borders and compute the uv coordinates
+        let mut new_index = BTreeMap::new();
+        let mut order = Vec::new();
+        let mut uvs = Vec::new();
+        let mut new_indices = vec![0; triangle_count * 3];
+        for (index, (chart, offset)) in charts.iter().zip(offsets.iter()).enumerate() {
+            for (t, coordinates) in chart.triangles.iter().zip(chart.coordinates.iter()) {
+                for k in 0..3 {
+                    let vertex = indices[t * 3 + k];
+                    new_indices[t * 3 + k] =
+                        *new_index.entry((vertex, index)).or_insert_with(|| {
+                            let uv = (*offset + (coordinates[k] - chart.min) * texels_per_unit)
+                                / resolution;
+                            uvs.push(uv.x);
+                            uvs.push(uv.y);
+                            order.push(vertex);
+                            order.len() as u32 - 1
+                        });
+                }
+            }
+        }
+        self.uvs = None;
+        self.reorder_vertices(&order);
+        self.uvs = Some(uvs);
+        self.indices = Some(Indices::U32(new_indices));
+        UVUnwrapResult {
+            chart_count: charts.len(),
+            texels_per_unit,
+        }
+    }
+
+    fn project_chart(triangles: Vec<usize>, normal_sum: Vec3, corners: &[[Vec3; 3]]) -> Chart {
+        let normal = if normal_sum.magnitude2() > 0.0 {
+            normal_sum.normalize()
+        } else {
+            vec3(0.0, 0.0, 1.0)
+        };
+        let helper = if normal.x.abs() < 0.9 {
+            vec3(1.0, 0.0, 0.0)
+        } else {
+            vec3(0.0, 1.0, 0.0)
+        };
+        let mut tangent = helper.cross(normal).normalize();
+        let mut bitangent = normal.cross(tangent);
+        let project =
+            |p: Vec3, tangent: Vec3, bitangent: Vec3| vec2(p.dot(tangent), p.dot(bitangent));
+
+        let bounds = |tangent: Vec3, bitangent: Vec3| {
+            let mut min = vec2(core::f32::MAX, core::f32::MAX);
+            let mut max = vec2(core::f32::MIN, core::f32::MIN);
+            for t in triangles.iter() {
+                for p in corners[*t].iter() {
+                    let c = project(*p, tangent, bitangent);
+                    min = vec2(min.x.min(c.x), min.y.min(c.y));
+                    max = vec2(max.x.max(c.x), max.y.max(c.y));
+                }
+            }
+            (min, max - min)
+        };
+        let (mut min, mut size) = bounds(tangent, bitangent);
+        // Make the charts wider than they are tall, which packs better into rows
+        if size.y > size.x {
+            let t = tangent;
+            tangent = bitangent;
+            bitangent = -t;
+            let (new_min, new_size) = bounds(tangent, bitangent);
+            min = new_min;
+            size = new_size;
+        }
+        let coordinates = triangles
+            .iter()
+            .map(|t| {
+                let c = corners[*t];
+                [
+                    project(c[0], tangent, bitangent),
+                    project(c[1], tangent, bitangent),
+                    project(c[2], tangent, bitangent),
+                ]
+            })
+            .collect();
+        Chart {
+            triangles,
+            coordinates,
+            min,
+            size,
+        }
+    }
+}
+
+// Shelf packing of the charts, tallest first, returns the position in texels of each chart or None if the charts do not fit
+fn pack(charts: &[Chart], texels_per_unit: f32, options: UVUnwrapOptions) -> Option<Vec<Vec2>> {
+    let (padding, resolution) = (options.padding, options.resolution);
+    let sizes: Vec<(u32, u32)> = charts
+        .iter()
+        .map(|c| {
+            (
+                (c.size.x * texels_per_unit).ceil() as u32 + 2 * padding,
+                (c.size.y * texels_per_unit).ceil() as u32 + 2 * padding,
+            )
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..charts.len()).collect();
+    order.sort_by(|a, b| sizes[*b].1.cmp(&sizes[*a].1));
+    let mut offsets = vec![vec2(0.0, 0.0); charts.len()];
+    let (mut x, mut y, mut row_height) = (0, 0, 0);
+    for i in order {
+        let (w, h) = sizes[i];
+        if x + w > resolution {
+            x = 0;
+            y += row_height;
+            row_height = 0;
+        }
+        if x + w > resolution || y + h > resolution {
+            return None;
+        }
+        offsets[i] = vec2((x + padding) as f32, (y + padding) as f32);
+        x += w;
+        row_height = row_height.max(h);
+    }
+    Some(offsets)
+}
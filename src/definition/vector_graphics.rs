@@ -0,0 +1,362 @@
+use crate::definition::*;
+use crate::math::*;
+use crate::Error;
+use lyon::math::point;
+use lyon::path::{FillRule, LineCap, LineJoin, Path};
+use lyon::tessellation::{
+    BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator,
+    StrokeVertex, VertexBuffers,
+};
+
+///
+/// A command in a [VectorPath].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PathCommand {
+    /// Starts a new sub path at the given point.
+    MoveTo(Vec2),
+    /// Adds a straight line to the given point.
+    LineTo(Vec2),
+    /// Adds a quadratic bezier curve with the given control point to the given point.
+    QuadraticTo(Vec2, Vec2),
+    /// Adds a cubic bezier curve with the given two control points to the given point.
+    CubicTo(Vec2, Vec2, Vec2),
+    /// Closes the current sub path with a straight line to the start of the sub path.
+    Close,
+}
+
+///
+/// A 2D vector path consisting of lines and bezier curves in the xy-plane, which can be tessellated into a triangle mesh
+/// by [filling](VectorPath::fill) or [stroking](VectorPath::stroke) it, for example for plots, maps and UI artwork.
+///
+/// # Feature
+/// Only available when the `vector-graphics` feature is enabled.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VectorPath {
+    /// The commands of the path.
+    pub commands: Vec<PathCommand>,
+}
+
+impl VectorPath {
+    ///
+    /// Constructs a new empty path.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Constructs a closed path through the given points.
+    ///
+    pub fn polygon(points: &[Vec2]) -> Self {
+        let mut path = Self::polyline(points);
+        path.commands.push(PathCommand::Close);
+        path
+    }
+
+    ///
+    /// Constructs an open path through the given points.
+    ///
+    pub fn polyline(points: &[Vec2]) -> Self {
+        let mut path = Self::new();
+        for (i, p) in points.iter().enumerate() {
+            path.commands.push(if i == 0 {
+                PathCommand::MoveTo(*p)
+            } else {
+                PathCommand::LineTo(*p)
+            });
+        }
+        path
+    }
+
+    ///
+    /// Constructs a closed path approximating a circle with the given center and radius using four cubic bezier curves.
+    ///
+    pub fn circle(center: Vec2, radius: f32) -> Self {
+        Self::ellipse(center, vec2(radius, radius))
+    }
+
+    ///
+    /// Constructs a closed path approximating an axis aligned ellipse with the given center and radii using four cubic bezier curves.
+    ///
+    pub fn ellipse(center: Vec2, radius: Vec2) -> Self {
+        const K: f32 = 0.552_284_8;
+        let (rx, ry) = (radius.x, radius.y);
+        let c = center;
+        Self::new()
+            .move_to(c + vec2(rx, 0.0))
+            .cubic_to(
+                c + vec2(rx, K * ry),
+                c + vec2(K * rx, ry),
+                c + vec2(0.0, ry),
+            )
+            .cubic_to(
+                c + vec2(-K * rx, ry),
+                c + vec2(-rx, K * ry),
+                c + vec2(-rx, 0.0),
+            )
+            .cubic_to(
+                c + vec2(-rx, -K * ry),
+                c + vec2(-K * rx, -ry),
+                c + vec2(0.0, -ry),
+            )
+            .cubic_to(
+                c + vec2(K * rx, -ry),
+                c + vec2(rx, -K * ry),
+                c + vec2(rx, 0.0),
+            )
+            .close()
+    }
+
+    ///
+    /// Starts a new sub path at the given point.
+    ///
+    pub fn move_to(mut self, to: Vec2) -> Self {
+        self.commands.push(PathCommand::MoveTo(to));
+        self
+    }
+
+    ///
+    /// Adds a straight line from the current point to the given point.
+    ///
+    pub fn line_to(mut self, to: Vec2) -> Self {
+        self.commands.push(PathCommand::LineTo(to));
+        self
+    }
+
+    ///
+    /// Adds a quadratic bezier curve from the current point to the given point.
+    ///
+    pub fn quadratic_to(mut self, control: Vec2, to: Vec2) -> Self {
+        self.commands.push(PathCommand::QuadraticTo(control, to));
+        self
+    }
+
+    ///
+    /// Adds a cubic bezier curve from the current point to the given point.
+    ///
+    pub fn cubic_to(mut self, control1: Vec2, control2: Vec2, to: Vec2) -> Self {
+        self.commands
+            .push(PathCommand::CubicTo(control1, control2, to));
+        self
+    }
+
+    ///
+    /// Closes the current sub path with a straight line to the start of the sub path.
+    ///
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    ///
+    /// Tessellates the inside of the path, using the non-zero fill rule, into a triangle mesh in the xy-plane.
+    /// The tolerance is the maximum distance between the curves and the line segments approximating them.
+    ///
+    /// # Errors
+    /// Will return an error if the path cannot be tessellated.
+    ///
+    pub fn fill(&self, tolerance: f32) -> Result<CPUMesh, Error> {
+        let mut geometry: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(
+                &self.to_lyon(),
+                &FillOptions::tolerance(tolerance).with_fill_rule(FillRule::NonZero),
+                &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
+                    vertex.position().to_array()
+                }),
+            )
+            .map_err(|e| Error::MeshError {
+                message: format!("Could not fill the path: {:?}", e),
+            })?;
+        Ok(to_cpu_mesh(geometry, "fill"))
+    }
+
+    ///
+    /// Tessellates a line with the given width along the path into a triangle mesh in the xy-plane.
+    /// The tolerance is the maximum distance between the curves and the line segments approximating them.
+    ///
+    /// # Errors
+    /// Will return an error if the path cannot be tessellated.
+    ///
+    pub fn stroke(&self, width: f32, tolerance: f32) -> Result<CPUMesh, Error> {
+        let mut geometry: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &self.to_lyon(),
+                &StrokeOptions::tolerance(tolerance)
+                    .with_line_width(width)
+                    .with_line_join(LineJoin::Round)
+                    .with_line_cap(LineCap::Round),
+                &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| {
+                    vertex.position().to_array()
+                }),
+            )
+            .map_err(|e| Error::MeshError {
+                message: format!("Could not stroke the path: {:?}", e),
+            })?;
+        Ok(to_cpu_mesh(geometry, "stroke"))
+    }
+
+    fn to_lyon(&self) -> Path {
+        let mut builder = Path::builder();
+        let mut is_open = false;
+        let mut current = vec2(0.0, 0.0);
+        let mut start = current;
+        for command in self.commands.iter() {
+            let to = match command {
+                PathCommand::MoveTo(to) => *to,
+                PathCommand::LineTo(to) => *to,
+                PathCommand::QuadraticTo(_, to) => *to,
+                PathCommand::CubicTo(_, _, to) => *to,
+                PathCommand::Close => start,
+            };
+            match command {
+                PathCommand::MoveTo(_) => {
+                    if is_open {
+                        builder.end(false);
+                    }
+                    builder.begin(point(to.x, to.y));
+                    is_open = true;
+                    start = to;
+                }
+                PathCommand::Close => {
+                    if is_open {
+                        builder.end(true);
+                        is_open = false;
+                    }
+                }
+                _ => {
+                    if !is_open {
+                        builder.begin(point(current.x, current.y));
+                        is_open = true;
+                        start = current;
+                    }
+                    match command {
+                        PathCommand::QuadraticTo(c, _) => {
+                            builder.quadratic_bezier_to(point(c.x, c.y), point(to.x, to.y));
+                        }
+                        PathCommand::CubicTo(c1, c2, _) => {
+                            builder.cubic_bezier_to(
+                                point(c1.x, c1.y),
+                                point(c2.x, c2.y),
+                                point(to.x, to.y),
+                            );
+                        }
+                        _ => {
+                            builder.line_to(point(to.x, to.y));
+                        }
+                    }
+                }
+            }
+            current = to;
+        }
+        if is_open {
+            builder.end(false);
+        }
+        builder.build()
+    }
+}
+
+///
+/// A shape in a [VectorGraphic], ie. a path which is filled and/or stroked with a color.
+///
+/// # Feature
+/// Only available when the `vector-graphics` feature is enabled.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorShape {
+    /// The path of the shape.
+    pub path: VectorPath,
+    /// The color of the inside of the shape, or `None` if the shape is not filled.
+    pub fill: Option<Vec4>,
+    /// The color and width of the stroke along the path, or `None` if the shape is not stroked.
+    pub stroke: Option<(Vec4, f32)>,
+}
+
+///
+/// A 2D vector graphic consisting of a list of [shapes](VectorShape), where later shapes are drawn on top of earlier shapes,
+/// for example loaded from an SVG file.
+///
+/// # Feature
+/// Only available when the `vector-graphics` feature is enabled.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VectorGraphic {
+    /// The shapes of the graphic.
+    pub shapes: Vec<VectorShape>,
+}
+
+impl VectorGraphic {
+    ///
+    /// Tessellates all of the shapes into one triangle mesh in the xy-plane with the colors of the shapes as per vertex colors,
+    /// which can for example be rendered with [Mesh::render_color](crate::Mesh::render_color).
+    /// Each shape is moved slightly along the z-axis in front of the previous shapes such that the shapes are drawn in order when depth testing.
+    /// The tolerance is the maximum distance between the curves and the line segments approximating them.
+    ///
+    /// # Errors
+    /// Will return an error if one of the shapes cannot be tessellated.
+    ///
+    pub fn to_cpu_mesh(&self, tolerance: f32) -> Result<CPUMesh, Error> {
+        let mut meshes = Vec::new();
+        for shape in self.shapes.iter() {
+            if let Some(color) = shape.fill {
+                meshes.push((shape.path.fill(tolerance)?, color));
+            }
+            if let Some((color, width)) = shape.stroke {
+                meshes.push((shape.path.stroke(width, tolerance)?, color));
+            }
+        }
+        let size = self
+            .shapes
+            .iter()
+            .flat_map(|s| s.path.commands.iter())
+            .fold(0.0f32, |size, command| match command {
+                PathCommand::MoveTo(p)
+                | PathCommand::LineTo(p)
+                | PathCommand::QuadraticTo(_, p)
+                | PathCommand::CubicTo(_, _, p) => size.max(p.x.abs()).max(p.y.abs()),
+                PathCommand::Close => size,
+            });
+        let layer_offset = 0.0001 * size.max(1.0);
+
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+        let mut indices = Vec::new();
+        for (layer, (mesh, color)) in meshes.iter().enumerate() {
+            let offset = positions.len() as u32 / 3;
+            if let Some(ref mesh_indices) = mesh.indices {
+                indices.extend(mesh_indices.into_u32().iter().map(|i| offset + i));
+            }
+            for p in mesh.positions.chunks_exact(3) {
+                positions.extend_from_slice(&[p[0], p[1], layer as f32 * layer_offset]);
+                colors.extend(
+                    [color.x, color.y, color.z, color.w]
+                        .iter()
+                        .map(|c| (c.max(0.0).min(1.0) * 255.0).round() as u8),
+                );
+            }
+        }
+        Ok(CPUMesh {
+            name: "vector graphic".to_string(),
+            positions,
+            colors: Some(colors),
+            indices: Some(Indices::U32(indices)),
+            ..Default::default()
+        })
+    }
+}
+
+fn to_cpu_mesh(geometry: VertexBuffers<[f32; 2], u32>, name: &str) -> CPUMesh {
+    CPUMesh {
+        name: name.to_string(),
+        positions: geometry
+            .vertices
+            .iter()
+            .flat_map(|v| vec![v[0], v[1], 0.0])
+            .collect(),
+        indices: Some(Indices::U32(geometry.indices)),
+        ..Default::default()
+    }
+}
@@ -6,10 +6,38 @@ mod image_effect;
 #[doc(inline)]
 pub use image_effect::*;
 
+#[cfg(feature = "renderer")]
 mod fog;
 #[doc(inline)]
+#[cfg(feature = "renderer")]
 pub use fog::*;
 
+#[cfg(feature = "renderer")]
 mod fxaa;
 #[doc(inline)]
+#[cfg(feature = "renderer")]
 pub use fxaa::*;
+
+#[cfg(feature = "renderer")]
+mod depth_visualization;
+#[doc(inline)]
+#[cfg(feature = "renderer")]
+pub use depth_visualization::*;
+
+#[cfg(feature = "renderer")]
+mod texture_baker;
+#[doc(inline)]
+#[cfg(feature = "renderer")]
+pub use texture_baker::*;
+
+#[cfg(feature = "renderer")]
+mod image_processing;
+#[doc(inline)]
+#[cfg(feature = "renderer")]
+pub use image_processing::*;
+
+#[cfg(feature = "renderer")]
+mod compositor;
+#[doc(inline)]
+#[cfg(feature = "renderer")]
+pub use compositor::*;
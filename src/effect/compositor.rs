@@ -0,0 +1,135 @@
+use crate::core::*;
+use crate::effect::*;
+use crate::math::*;
+
+///
+/// How a [CompositorLayer] is combined with the layers below it.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CompositorBlendMode {
+    /// The layer is drawn on top of the layers below it according to its alpha values.
+    Normal,
+    /// The color of the layer is added to the layers below it, for example for glow or debug visualizations.
+    Additive,
+    /// The layers below are multiplied with the color of the layer, which darkens them.
+    Multiply,
+    /// The inverted colors are multiplied, which brightens the layers below.
+    Screen,
+}
+
+impl CompositorBlendMode {
+    fn blend_parameters(&self) -> BlendParameters {
+        // The source color is always premultiplied with its alpha value
+        let (source_rgb_multiplier, destination_rgb_multiplier) = match self {
+            Self::Normal => (
+                BlendMultiplierType::One,
+                BlendMultiplierType::OneMinusSrcAlpha,
+            ),
+            Self::Additive => (BlendMultiplierType::One, BlendMultiplierType::One),
+            Self::Multiply => (
+                BlendMultiplierType::DstColor,
+                BlendMultiplierType::OneMinusSrcAlpha,
+            ),
+            Self::Screen => (
+                BlendMultiplierType::One,
+                BlendMultiplierType::OneMinusSrcColor,
+            ),
+        };
+        BlendParameters {
+            source_rgb_multiplier,
+            source_alpha_multiplier: BlendMultiplierType::One,
+            destination_rgb_multiplier,
+            destination_alpha_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+            rgb_equation: BlendEquationType::Add,
+            alpha_equation: BlendEquationType::Add,
+        }
+    }
+}
+
+///
+/// A rendered texture which is composited onto the render target by a [Compositor].
+///
+pub struct CompositorLayer<'a> {
+    /// The texture with the rendered result, for example a [ColorTargetTexture2D](crate::ColorTargetTexture2D) which the 3D scene was rendered into.
+    pub texture: &'a dyn Texture,
+    /// How the layer is combined with the layers below it.
+    pub blend_mode: CompositorBlendMode,
+    /// The opacity of the whole layer, between 0 (invisible) and 1 (opaque).
+    pub opacity: f32,
+    /// Whether the colors of the texture are already multiplied with the alpha values, which is the case
+    /// when the layer was rendered with [BlendParameters::PREMULTIPLIED_ALPHA] into a transparent render target.
+    pub premultiplied: bool,
+}
+
+impl<'a> CompositorLayer<'a> {
+    ///
+    /// Creates a new opaque layer with the given texture which is drawn on top of the layers below it according to its alpha values.
+    ///
+    pub fn new(texture: &'a dyn Texture) -> Self {
+        Self {
+            texture,
+            blend_mode: CompositorBlendMode::Normal,
+            opacity: 1.0,
+            premultiplied: false,
+        }
+    }
+}
+
+///
+/// Composites several rendered textures onto the current render target, for example the 3D scene, a debug layer and a user interface layer,
+/// where each layer has its own [blend mode](CompositorBlendMode) and opacity. The layers are blended with premultiplied alpha,
+/// so transparent layers are composited correctly.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let (scene, debug, ui): (ColorTargetTexture2D<u8>, ColorTargetTexture2D<u8>, ColorTargetTexture2D<u8>) = unimplemented!();
+/// # let viewport = Viewport::new_at_origo(1, 1);
+/// let compositor = Compositor::new(&context).unwrap();
+/// Screen::write(&context, ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0), || {
+///     compositor.compose(viewport, &[
+///         CompositorLayer::new(&scene),
+///         CompositorLayer { blend_mode: CompositorBlendMode::Additive, opacity: 0.5, ..CompositorLayer::new(&debug) },
+///         CompositorLayer { premultiplied: true, ..CompositorLayer::new(&ui) },
+///     ])
+/// }).unwrap();
+/// ```
+///
+pub struct Compositor {
+    image_effect: ImageEffect,
+}
+
+impl Compositor {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            image_effect: ImageEffect::new(context, include_str!("shaders/compositor.frag"))?,
+        })
+    }
+
+    ///
+    /// Composites the given layers onto the current render target in the given order, ie. the first layer is at the bottom.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn compose(&self, viewport: Viewport, layers: &[CompositorLayer]) -> Result<(), Error> {
+        for layer in layers {
+            if layer.opacity <= 0.0 {
+                continue;
+            }
+            let render_states = RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTestType::Always,
+                blend: Some(layer.blend_mode.blend_parameters()),
+                ..RenderStates::new(viewport)
+            };
+            self.image_effect
+                .use_texture(layer.texture, "layerTexture")?;
+            self.image_effect
+                .use_uniform_float("opacity", &layer.opacity.min(1.0))?;
+            self.image_effect
+                .use_uniform_int("premultiplied", &(layer.premultiplied as i32))?;
+            self.image_effect.apply(render_states)?;
+        }
+        Ok(())
+    }
+}
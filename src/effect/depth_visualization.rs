@@ -0,0 +1,65 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::effect::*;
+use crate::math::*;
+
+///
+/// A debug effect which shows the content of a depth texture, for example the depth of the rendered scene or a shadow map
+/// (see [DirectionalLight::shadow_map](crate::DirectionalLight::shadow_map) and [SpotLight::shadow_map](crate::SpotLight::shadow_map)).
+/// The depth is linearized using the projection of the camera which produced the depth texture, such that 0 corresponds to the near plane
+/// and 1 corresponds to the far plane, and optionally colorized with a heat map. If the far plane is infinitely far away, the depth `z` is instead mapped to `1 - near / z`,
+/// which also is 0 at the near plane and approaches 1 far away. The background, ie. where the depth is the [far depth](crate::Camera::far_depth), is shown in black,
+/// also when the depth is [reversed](crate::Camera::set_reverse_z).
+/// Use a smaller viewport to show the depth texture in a corner of the screen.
+///
+pub struct DepthVisualizationEffect {
+    /// Whether to show the depth with a heat map (blue is close and red is far away) or in grayscale.
+    pub colorize: bool,
+    image_effect: ImageEffect,
+}
+
+impl DepthVisualizationEffect {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            colorize: true,
+            image_effect: ImageEffect::new(
+                context,
+                include_str!("shaders/depth_visualization.frag"),
+            )?,
+        })
+    }
+
+    ///
+    /// Shows the given depth texture in the given viewport. The camera must be the camera that was used to render the depth,
+    /// for example [DirectionalLight::shadow_camera](crate::DirectionalLight::shadow_camera) for a shadow map.
+    ///
+    pub fn apply(
+        &self,
+        viewport: Viewport,
+        depth_texture: &impl Texture,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTestType::Always,
+            ..RenderStates::new(viewport)
+        };
+
+        let (z_near, z_far) = camera.z_near_far();
+        self.image_effect.use_texture(depth_texture, "depthMap")?;
+        self.image_effect
+            .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
+        self.image_effect.use_uniform_float("zNear", &z_near)?;
+        self.image_effect
+            .use_uniform_float("zFar", &(if z_far.is_infinite() { 0.0 } else { z_far }))?;
+        self.image_effect
+            .use_uniform_int("infiniteFar", &(if z_far.is_infinite() { 1 } else { 0 }))?;
+        self.image_effect
+            .use_uniform_float("farDepth", &camera.far_depth())?;
+        self.image_effect
+            .use_uniform_int("colorize", &(if self.colorize { 1 } else { 0 }))?;
+
+        self.image_effect.apply(render_states)?;
+        Ok(())
+    }
+}
@@ -37,14 +37,12 @@ impl FogEffect {
                 destination_alpha_multiplier: BlendMultiplierType::One,
                 ..Default::default()
             }),
-            ..Default::default()
+            ..RenderStates::new(viewport)
         };
 
         self.image_effect.use_texture(depth_texture, "depthMap")?;
-        self.image_effect.use_uniform_mat4(
-            "viewProjectionInverse",
-            &(camera.projection() * camera.view()).invert().unwrap(),
-        )?;
+        self.image_effect
+            .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
         self.image_effect
             .use_uniform_vec3("fogColor", &self.color)?;
         self.image_effect
@@ -53,10 +51,8 @@ impl FogEffect {
             .use_uniform_float("animation", &self.animation)?;
         self.image_effect
             .use_uniform_float("time", &(0.001 * time))?;
-        self.image_effect
-            .use_uniform_vec3("eyePosition", camera.position())?;
 
-        self.image_effect.apply(render_states, viewport)?;
+        self.image_effect.apply(render_states)?;
         Ok(())
     }
 }
@@ -27,7 +27,7 @@ impl FXAAEffect {
         let render_states = RenderStates {
             write_mask: WriteMask::COLOR,
             depth_test: DepthTestType::Always,
-            ..Default::default()
+            ..RenderStates::new(viewport)
         };
 
         self.image_effect.use_texture(color_texture, "colorMap")?;
@@ -36,7 +36,7 @@ impl FXAAEffect {
             &vec2(color_texture.width() as f32, color_texture.height() as f32),
         )?;
 
-        self.image_effect.apply(render_states, viewport)?;
+        self.image_effect.apply(render_states)?;
         Ok(())
     }
 }
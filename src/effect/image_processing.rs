@@ -0,0 +1,134 @@
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+
+fn render_states<T: TextureDataType>(target: &ColorTargetTexture2D<T>) -> RenderStates {
+    RenderStates {
+        write_mask: WriteMask::COLOR,
+        depth_test: DepthTestType::Always,
+        ..RenderStates::new(Viewport::new_at_origo(target.width(), target.height()))
+    }
+}
+
+///
+/// A separable Gaussian blur which blurs a texture into another texture, for example as part of a bloom effect.
+/// The blur is applied in two passes, first horizontally into an intermediate texture and then vertically into the target.
+///
+pub struct GaussianBlurEffect {
+    /// The standard deviation of the Gaussian in pixels. Larger values give a stronger blur.
+    pub sigma: f32,
+    screen_quad: ScreenQuad,
+}
+
+impl GaussianBlurEffect {
+    pub fn new(context: &Context, sigma: f32) -> Result<Self, Error> {
+        Ok(Self {
+            sigma,
+            screen_quad: ScreenQuad::new(context, include_str!("shaders/gaussian_blur.frag"))?,
+        })
+    }
+
+    ///
+    /// Blurs the source texture into the target texture. The intermediate texture is taken from the given pool.
+    ///
+    pub fn apply<T: TextureDataType>(
+        &self,
+        pool: &mut RenderTargetPool,
+        source: &impl Texture,
+        target: &ColorTargetTexture2D<T>,
+    ) -> Result<(), Error> {
+        let sigma = self.sigma.max(0.01);
+        self.screen_quad.use_uniform_float("sigma", &sigma)?;
+        self.screen_quad
+            .use_uniform_int("radius", &((3.0 * sigma).ceil() as i32))?;
+
+        let intermediate =
+            pool.float_color_texture(target.width(), target.height(), Format::RGBA)?;
+        self.screen_quad
+            .use_uniform_vec2("direction", &vec2(1.0 / source.width() as f32, 0.0))?;
+        self.screen_quad.apply_to(
+            &[("image", source)],
+            &*intermediate,
+            render_states(&*intermediate),
+        )?;
+        self.screen_quad
+            .use_uniform_vec2("direction", &vec2(0.0, 1.0 / intermediate.height() as f32))?;
+        self.screen_quad
+            .apply_to(&[("image", &*intermediate)], target, render_states(target))
+    }
+}
+
+///
+/// A Sobel edge detection which writes the magnitude of the luminance gradient of a texture into another texture,
+/// for example to draw outlines.
+///
+pub struct SobelEffect {
+    /// A factor multiplied onto the edge magnitude before it is clamped to the range 0 to 1.
+    pub strength: f32,
+    screen_quad: ScreenQuad,
+}
+
+impl SobelEffect {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            strength: 1.0,
+            screen_quad: ScreenQuad::new(context, include_str!("shaders/sobel.frag"))?,
+        })
+    }
+
+    ///
+    /// Writes the edges of the source texture into the target texture.
+    ///
+    pub fn apply<T: TextureDataType>(
+        &self,
+        source: &impl Texture,
+        target: &ColorTargetTexture2D<T>,
+    ) -> Result<(), Error> {
+        self.screen_quad.use_uniform_vec2(
+            "texelSize",
+            &vec2(1.0 / source.width() as f32, 1.0 / source.height() as f32),
+        )?;
+        self.screen_quad
+            .use_uniform_float("strength", &self.strength)?;
+        self.screen_quad
+            .apply_to(&[("image", source)], target, render_states(target))
+    }
+}
+
+///
+/// A luminance threshold which keeps the colors of a texture with a luminance above the threshold and sets the rest to black,
+/// for example to extract the bright parts of an image for a bloom effect.
+///
+pub struct ThresholdEffect {
+    /// The luminance threshold.
+    pub threshold: f32,
+    /// The width of the luminance range around the threshold in which the colors are faded out instead of cut off.
+    pub smoothness: f32,
+    screen_quad: ScreenQuad,
+}
+
+impl ThresholdEffect {
+    pub fn new(context: &Context, threshold: f32) -> Result<Self, Error> {
+        Ok(Self {
+            threshold,
+            smoothness: 0.0,
+            screen_quad: ScreenQuad::new(context, include_str!("shaders/threshold.frag"))?,
+        })
+    }
+
+    ///
+    /// Writes the colors of the source texture above the threshold into the target texture.
+    ///
+    pub fn apply<T: TextureDataType>(
+        &self,
+        source: &impl Texture,
+        target: &ColorTargetTexture2D<T>,
+    ) -> Result<(), Error> {
+        self.screen_quad
+            .use_uniform_float("threshold", &self.threshold)?;
+        self.screen_quad
+            .use_uniform_float("smoothness", &self.smoothness)?;
+        self.screen_quad
+            .apply_to(&[("image", source)], target, render_states(target))
+    }
+}
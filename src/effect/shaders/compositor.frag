@@ -0,0 +1,19 @@
+
+uniform sampler2D layerTexture;
+uniform float opacity;
+uniform int premultiplied;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    vec4 c = texture(layerTexture, uv);
+    if(premultiplied == 0)
+    {
+        c.rgb *= c.a;
+    }
+    // The output is always premultiplied, so the opacity scales all channels
+    color = c * opacity;
+}
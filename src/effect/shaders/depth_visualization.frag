@@ -0,0 +1,51 @@
+
+uniform sampler2D depthMap;
+#include "camera"
+uniform float zNear;
+uniform float zFar;
+uniform int infiniteFar;
+uniform float farDepth;
+uniform int colorize;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+// A polynomial approximation of the turbo colormap
+vec3 heatmap(float x)
+{
+    const vec4 kRedVec4 = vec4(0.13572138, 4.61539260, -42.66032258, 132.13108234);
+    const vec4 kGreenVec4 = vec4(0.09140261, 2.19418839, 4.84296658, -14.18503333);
+    const vec4 kBlueVec4 = vec4(0.10667330, 12.64194608, -60.58204836, 110.36276771);
+    const vec2 kRedVec2 = vec2(-152.94239396, 59.28637943);
+    const vec2 kGreenVec2 = vec2(4.27729857, 2.82956604);
+    const vec2 kBlueVec2 = vec2(-89.90310912, 27.34824973);
+
+    x = clamp(x, 0.0, 1.0);
+    vec4 v4 = vec4(1.0, x, x * x, x * x * x);
+    vec2 v2 = v4.zw * v4.z;
+    return vec3(
+        dot(v4, kRedVec4) + dot(v2, kRedVec2),
+        dot(v4, kGreenVec4) + dot(v2, kGreenVec2),
+        dot(v4, kBlueVec4) + dot(v2, kBlueVec2)
+    );
+}
+
+void main()
+{
+    float depth = texture(depthMap, uv).x;
+    vec4 position = camera.projectionInverse * vec4(uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
+    float linearDepth = -position.z / position.w;
+    float value = infiniteFar == 1 ? 1.0 - zNear / max(linearDepth, zNear) : clamp((linearDepth - zNear) / (zFar - zNear), 0.0, 1.0);
+    // The depth is cleared to 0 instead of 1 when it is reversed
+    bool background = farDepth > 0.5 ? depth >= farDepth : depth <= farDepth;
+    if(background) {
+        color = vec4(0.0, 0.0, 0.0, 1.0);
+    }
+    else if(colorize == 1) {
+        color = vec4(heatmap(value), 1.0);
+    }
+    else {
+        color = vec4(value, value, value, 1.0);
+    }
+}
@@ -0,0 +1,17 @@
+#include "shared"
+
+uniform samplerCube cubeMap;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The center of the image is in the negative z direction and the top is in the positive y direction
+    float longitude = (uv.x - 0.5) * 6.2831853;
+    float latitude = (uv.y - 0.5) * 3.1415927;
+    vec3 direction = vec3(sin(longitude) * cos(latitude), sin(latitude), -cos(longitude) * cos(latitude));
+    vec4 color = texture(cubeMap, direction);
+    outColor = vec4(srgb_from_rgb(color.rgb), color.a);
+}
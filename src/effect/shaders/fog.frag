@@ -1,13 +1,12 @@
 
 uniform sampler2D depthMap;
 
-uniform mat4 viewProjectionInverse;
+#include "camera"
 
 uniform float time;
 uniform float fogDensity;
 uniform vec3 fogColor;
 uniform float animation;
-uniform vec3 eyePosition;
 
 in vec2 uv;
 
@@ -128,7 +127,7 @@ float snoise(vec3 v)
 
 vec3 WorldPosFromDepth(float depth, vec2 uv) {
     vec4 clipSpacePosition = vec4(uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
-    vec4 position = viewProjectionInverse * clipSpacePosition;
+    vec4 position = camera.viewProjectionInverse * clipSpacePosition;
     return position.xyz / position.w;
 }
 
@@ -139,7 +138,7 @@ void main()
     vec3 pos = WorldPosFromDepth(depth, uv);
 
     // Distance
-    float dist = depth < 0.999f ? distance(pos, eyePosition) : 100.f;
+    float dist = depth < 0.999f ? distance(pos, camera.position) : 100.f;
 
     float x = dist * fogDensity;
     float factor = 1. - 1. / exp(x * x);
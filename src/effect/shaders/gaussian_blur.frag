@@ -0,0 +1,23 @@
+
+uniform sampler2D image;
+uniform vec2 direction;
+uniform float sigma;
+uniform int radius;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    float weight = 1.0;
+    vec4 sum = texture(image, uv);
+    for (int i = 1; i <= radius; i++)
+    {
+        float w = exp(-float(i * i) / (2.0 * sigma * sigma));
+        vec2 offset = float(i) * direction;
+        sum += w * (texture(image, uv + offset) + texture(image, uv - offset));
+        weight += 2.0 * w;
+    }
+    color = sum / weight;
+}
@@ -0,0 +1,30 @@
+
+uniform sampler2D image;
+uniform vec2 texelSize;
+uniform float strength;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+float luminance(vec2 offset)
+{
+    return dot(texture(image, uv + offset * texelSize).rgb, vec3(0.2126, 0.7152, 0.0722));
+}
+
+void main()
+{
+    float tl = luminance(vec2(-1.0, 1.0));
+    float t = luminance(vec2(0.0, 1.0));
+    float tr = luminance(vec2(1.0, 1.0));
+    float l = luminance(vec2(-1.0, 0.0));
+    float r = luminance(vec2(1.0, 0.0));
+    float bl = luminance(vec2(-1.0, -1.0));
+    float b = luminance(vec2(0.0, -1.0));
+    float br = luminance(vec2(1.0, -1.0));
+
+    float gx = tr + 2.0 * r + br - tl - 2.0 * l - bl;
+    float gy = tl + 2.0 * t + tr - bl - 2.0 * b - br;
+    float edge = clamp(strength * sqrt(gx * gx + gy * gy), 0.0, 1.0);
+    color = vec4(edge, edge, edge, 1.0);
+}
@@ -0,0 +1,32 @@
+#include "shared"
+
+uniform int source;
+uniform vec3 aabbMin;
+uniform vec3 aabbMax;
+uniform vec3 ambientColor;
+uniform vec3 lightDirection;
+uniform vec3 lightColor;
+
+in vec3 pos;
+in vec3 nor;
+in vec4 col;
+in float curvature;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec3 normal = normalize(nor);
+    if(source == 0) {
+        outColor = vec4(0.5 * normal + 0.5, 1.0);
+    } else if(source == 1) {
+        outColor = vec4((pos - aabbMin) / max(aabbMax - aabbMin, vec3(0.000001)), 1.0);
+    } else if(source == 2) {
+        outColor = vec4(srgb_from_rgb(col.rgb/255.0), 1.0);
+    } else if(source == 3) {
+        outColor = vec4(vec3(curvature), 1.0);
+    } else {
+        vec3 light = ambientColor + lightColor * max(dot(normal, -normalize(lightDirection)), 0.0);
+        outColor = vec4(srgb_from_rgb(light), 1.0);
+    }
+}
@@ -0,0 +1,19 @@
+
+in vec2 uv_coordinates;
+out vec2 uvs;
+
+{} // Positions in/out
+{} // Normals in/out
+{} // Colors in/out
+{} // Curvature in/out
+
+void main()
+{{
+    uvs = uv_coordinates;
+    // The mesh is rasterized in its uv layout with the first row of the texture at v = 1
+    gl_Position = vec4(2.0 * uv_coordinates.x - 1.0, 1.0 - 2.0 * uv_coordinates.y, 0.0, 1.0);
+    {} // Position
+    {} // Normal
+    {} // Colors
+    {} // Curvature
+}}
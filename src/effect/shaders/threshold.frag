@@ -0,0 +1,16 @@
+
+uniform sampler2D image;
+uniform float threshold;
+uniform float smoothness;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    vec4 c = texture(image, uv);
+    float l = dot(c.rgb, vec3(0.2126, 0.7152, 0.0722));
+    float factor = smoothstep(threshold - 0.5 * smoothness, threshold + 0.5 * smoothness + 0.00001, l);
+    color = vec4(c.rgb * factor, c.a);
+}
@@ -0,0 +1,314 @@
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+
+///
+/// The property of a mesh which is baked into a texture by a [TextureBaker].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BakeSource {
+    /// The normals, mapped from the range [-1..1] to [0..1].
+    Normals,
+    /// The positions, mapped from the axis aligned bounding box of the mesh to [0..1].
+    Positions,
+    /// The vertex colors, for example ambient occlusion baked into the vertex colors or colors transferred from another mesh.
+    Colors,
+    /// The mean curvature, where 0.5 is flat, higher values are convex and lower values are concave.
+    /// The curvature is scaled such that the most curved part of the mesh reaches 0 or 1.
+    Curvature,
+    /// Diffuse lighting from an ambient light and a directional light.
+    Lighting {
+        ambient_color: Vec3,
+        light_direction: Vec3,
+        light_color: Vec3,
+    },
+}
+
+///
+/// Bakes properties of a mesh, for example lighting, normals or curvature, into a texture by rasterizing the mesh in its uv layout,
+/// which enables for example lightmap baking and texture transfer.
+/// The mesh must have uv coordinates without overlaps, for example generated by [CPUMesh::unwrap_uvs].
+///
+pub struct TextureBaker {
+    context: Context,
+    program: Program,
+    source: Option<BakeSource>,
+    use_positions: bool,
+    use_normals: bool,
+    use_colors: bool,
+    use_curvature: bool,
+}
+
+impl TextureBaker {
+    ///
+    /// Constructs a new texture baker which bakes the given property.
+    ///
+    pub fn new(context: &Context, source: BakeSource) -> Result<Self, Error> {
+        let mut baker = Self::new_with_shader(context, include_str!("shaders/texture_baker.frag"))?;
+        baker.source = Some(source);
+        Ok(baker)
+    }
+
+    ///
+    /// Constructs a new texture baker with a custom fragment shader, which writes the baked color to `outColor`.
+    /// The fragment shader can use the inputs `in vec3 pos;`, `in vec3 nor;`, `in vec2 uvs;`, `in vec4 col;` (in the range [0..255])
+    /// and `in float curvature;`, which is the same as [BakeSource::Curvature].
+    /// The alpha value of the output is used to find the texels covered by the mesh and should therefore be 1.
+    ///
+    pub fn new_with_shader(context: &Context, fragment_shader_source: &str) -> Result<Self, Error> {
+        let use_positions = fragment_shader_source.find("in vec3 pos;").is_some();
+        let use_normals = fragment_shader_source.find("in vec3 nor;").is_some();
+        let use_colors = fragment_shader_source.find("in vec4 col;").is_some();
+        let use_curvature = fragment_shader_source.find("in float curvature;").is_some();
+        let vertex_shader_source = format!(
+            include_str!("shaders/texture_baker.vert"),
+            if use_positions {
+                "in vec3 position;
+                out vec3 pos;"
+            } else {
+                ""
+            },
+            if use_normals {
+                "in vec3 normal;
+                out vec3 nor;"
+            } else {
+                ""
+            },
+            if use_colors {
+                "in vec4 color;
+                out vec4 col;"
+            } else {
+                ""
+            },
+            if use_curvature {
+                "in float vertex_curvature;
+                out float curvature;"
+            } else {
+                ""
+            },
+            if use_positions { "pos = position;" } else { "" },
+            if use_normals { "nor = normal;" } else { "" },
+            if use_colors { "col = color;" } else { "" },
+            if use_curvature {
+                "curvature = vertex_curvature;"
+            } else {
+                ""
+            },
+        );
+        Ok(Self {
+            context: context.clone(),
+            program: Program::from_source_with_preprocessor(
+                context,
+                &vertex_shader_source,
+                fragment_shader_source,
+                &ShaderPreprocessor::new(),
+            )?,
+            source: None,
+            use_positions,
+            use_normals,
+            use_colors,
+            use_curvature,
+        })
+    }
+
+    ///
+    /// Bakes the mesh into an RGBA texture with the given width and height.
+    /// The baked texels are extended `padding` texels outside the uv charts to avoid seams when sampling with linear interpolation and mip maps.
+    /// Texels which are not covered have an alpha value of 0.
+    ///
+    /// # Errors
+    /// Will return an error if the mesh has no uv coordinates, or if the vertex colors are used but the mesh has no vertex colors.
+    ///
+    pub fn bake(
+        &self,
+        cpu_mesh: &CPUMesh,
+        width: u32,
+        height: u32,
+        padding: u32,
+    ) -> Result<CPUTexture<u8>, Error> {
+        let uvs = cpu_mesh.uvs.as_ref().ok_or(Error::MeshError {
+            message: "Cannot bake a texture from a mesh without uv coordinates.".to_string(),
+        })?;
+        let mut normals = cpu_mesh.normals.clone();
+        if normals.is_none() && (self.use_normals || self.use_curvature) {
+            let mut mesh = CPUMesh {
+                positions: cpu_mesh.positions.clone(),
+                indices: cpu_mesh
+                    .indices
+                    .as_ref()
+                    .map(|indices| Indices::U32(indices.into_u32())),
+                ..Default::default()
+            };
+            mesh.compute_normals();
+            normals = mesh.normals;
+        }
+
+        // The buffers must be alive until the mesh is drawn
+        let mut buffers = Vec::new();
+        let buffer = VertexBuffer::new_with_static(&self.context, uvs)?;
+        self.program.use_attribute_vec2(&buffer, "uv_coordinates")?;
+        buffers.push(buffer);
+        if self.use_positions {
+            let buffer = VertexBuffer::new_with_static(&self.context, &cpu_mesh.positions)?;
+            self.program.use_attribute_vec3(&buffer, "position")?;
+            buffers.push(buffer);
+        }
+        if self.use_normals {
+            let buffer = VertexBuffer::new_with_static(&self.context, normals.as_ref().unwrap())?;
+            self.program.use_attribute_vec3(&buffer, "normal")?;
+            buffers.push(buffer);
+        }
+        let vertex_count = cpu_mesh.positions.len() / 3;
+        if self.use_colors {
+            let colors = match cpu_mesh.colors {
+                Some(ref colors) => colors.clone(),
+                // The shader of the built-in sources uses the colors regardless of the source
+                None if self.source.is_some() && self.source != Some(BakeSource::Colors) => {
+                    vec![0u8; 4 * vertex_count]
+                }
+                None => Err(Error::MeshError {
+                    message: "Cannot bake the vertex colors of a mesh without vertex colors."
+                        .to_string(),
+                })?,
+            };
+            let buffer = VertexBuffer::new_with_static(&self.context, &colors)?;
+            self.program.use_attribute_vec4(&buffer, "color")?;
+            buffers.push(buffer);
+        }
+        if self.use_curvature {
+            let curvature = match self.source {
+                None | Some(BakeSource::Curvature) => {
+                    vertex_curvature(cpu_mesh, normals.as_ref().unwrap())
+                }
+                _ => vec![0.5; vertex_count],
+            };
+            let buffer = VertexBuffer::new_with_static(&self.context, &curvature)?;
+            self.program.use_attribute(&buffer, "vertex_curvature")?;
+            buffers.push(buffer);
+        }
+
+        if let Some(source) = self.source {
+            let aabb = cpu_mesh.compute_aabb();
+            self.program.use_uniform_vec3("aabbMin", &aabb.min())?;
+            self.program.use_uniform_vec3("aabbMax", &aabb.max())?;
+            self.program.use_uniform_int(
+                "source",
+                &match source {
+                    BakeSource::Normals => 0,
+                    BakeSource::Positions => 1,
+                    BakeSource::Colors => 2,
+                    BakeSource::Curvature => 3,
+                    BakeSource::Lighting { .. } => 4,
+                },
+            )?;
+            if let BakeSource::Lighting {
+                ambient_color,
+                light_direction,
+                light_color,
+            } = source
+            {
+                self.program
+                    .use_uniform_vec3("ambientColor", &ambient_color)?;
+                self.program
+                    .use_uniform_vec3("lightDirection", &light_direction)?;
+                self.program.use_uniform_vec3("lightColor", &light_color)?;
+            }
+        }
+
+        let texture = ColorTargetTexture2D::<u8>::new(
+            &self.context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        let viewport = Viewport::new_at_origo(width, height);
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTestType::Always,
+            ..RenderStates::new(viewport)
+        };
+        texture.write(ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+            if let Some(ref indices) = cpu_mesh.indices {
+                let index_buffer = ElementBuffer::new(&self.context, &indices.into_u32())?;
+                self.program.draw_elements(render_states, &index_buffer);
+            } else {
+                self.program
+                    .draw_arrays(render_states, cpu_mesh.positions.len() as u32 / 3);
+            }
+            Ok(())
+        })?;
+
+        let mut data = texture.read(viewport)?;
+        dilate(&mut data, width as usize, height as usize, padding);
+        Ok(CPUTexture {
+            data,
+            width,
+            height,
+            format: Format::RGBA,
+            is_srgb: match self.source {
+                Some(BakeSource::Colors) | Some(BakeSource::Lighting { .. }) => true,
+                _ => false,
+            },
+            ..Default::default()
+        })
+    }
+}
+
+impl std::ops::Deref for TextureBaker {
+    type Target = Program;
+
+    fn deref(&self) -> &Self::Target {
+        &self.program
+    }
+}
+
+// Computes the mean curvature at each vertex from the change of the normal along the edges to the neighbouring vertices
+fn vertex_curvature(cpu_mesh: &CPUMesh, normals: &[f32]) -> Vec<f32> {
+    let vertex_count = cpu_mesh.positions.len() / 3;
+    let indices = cpu_mesh
+        .indices
+        .as_ref()
+        .map(|indices| indices.into_u32())
+        .unwrap_or_else(|| (0..vertex_count as u32).collect());
+    let position = |i: usize| {
+        vec3(
+            cpu_mesh.positions[3 * i],
+            cpu_mesh.positions[3 * i + 1],
+            cpu_mesh.positions[3 * i + 2],
+        )
+    };
+    let normal = |i: usize| vec3(normals[3 * i], normals[3 * i + 1], normals[3 * i + 2]);
+
+    let mut sum = vec![0.0; vertex_count];
+    let mut count = vec![0u32; vertex_count];
+    for triangle in indices.chunks_exact(3) {
+        for k in 0..3 {
+            let a = triangle[k] as usize;
+            let b = triangle[(k + 1) % 3] as usize;
+            let edge = position(b) - position(a);
+            let length2 = edge.magnitude2();
+            if length2 > 0.0 {
+                let curvature = (normal(b) - normal(a)).dot(edge) / length2;
+                sum[a] += curvature;
+                sum[b] += curvature;
+                count[a] += 1;
+                count[b] += 1;
+            }
+        }
+    }
+    let curvature: Vec<f32> = sum
+        .iter()
+        .zip(count.iter())
+        .map(|(s, c)| if *c > 0 { s / *c as f32 } else { 0.0 })
+        .collect();
+    let max = curvature.iter().fold(0.0f32, |max, c| max.max(c.abs()));
+    curvature
+        .iter()
+        .map(|c| if max > 0.0 { 0.5 + 0.5 * c / max } else { 0.5 })
+        .collect()
+}
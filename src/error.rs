@@ -0,0 +1,64 @@
+use alloc::string::String;
+
+///
+/// Error in some part of the render engine.
+///
+#[derive(Debug)]
+pub enum Error {
+    /// An error in a shader program.
+    ProgramError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using a render target.
+    RenderTargetError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using a texture.
+    TextureError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using a buffer.
+    BufferError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using a mesh.
+    MeshError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using a camera.
+    CameraError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using a render graph.
+    RenderGraphError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using a font.
+    FontError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using a skeleton.
+    SkeletonError {
+        /// Error message
+        message: String,
+    },
+    /// An error when using an animation.
+    AnimationError {
+        /// Error message
+        message: String,
+    },
+    /// An error in the wgpu backend.
+    #[cfg(feature = "wgpu-backend")]
+    WgpuError {
+        /// Error message
+        message: String,
+    },
+}
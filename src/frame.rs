@@ -6,6 +6,24 @@ mod input;
 #[doc(inline)]
 pub use input::*;
 
+mod input_generator;
+#[doc(inline)]
+pub use input_generator::*;
+
 mod output;
 #[doc(inline)]
 pub use output::*;
+
+mod payload;
+#[doc(inline)]
+pub use payload::*;
+
+mod frame_stats;
+#[doc(inline)]
+pub use frame_stats::*;
+
+#[cfg(feature = "input-recording")]
+mod input_recording;
+#[doc(inline)]
+#[cfg(feature = "input-recording")]
+pub use input_recording::*;
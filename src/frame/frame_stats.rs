@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+
+///
+/// Statistics of the time spent on the last frames, available in the [frame input](crate::FrameInput::stats) each frame,
+/// for example to show the frame rate in a GUI. The statistics can also be logged each second using the [log](https://crates.io/crates/log) crate,
+/// see [FrameInputGenerator::set_log_stats](crate::FrameInputGenerator::set_log_stats).
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FrameStats {
+    /// The number of frames each second computed from the average frame time.
+    pub fps: f64,
+    /// The average time in milliseconds between the last frames.
+    pub average_frame_time: f64,
+    /// The minimum time in milliseconds between the last frames.
+    pub min_frame_time: f64,
+    /// The maximum time in milliseconds between the last frames, which shows if there are occasional slow frames (stutter).
+    pub max_frame_time: f64,
+    /// The number of frames the statistics are computed from.
+    pub frame_count: usize,
+}
+
+impl std::fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.1} fps ({:.2} ms, min {:.2} ms, max {:.2} ms)",
+            self.fps, self.average_frame_time, self.min_frame_time, self.max_frame_time
+        )
+    }
+}
+
+///
+/// Keeps the frame times of the last frames and computes the [FrameStats] from them.
+///
+pub(crate) struct FrameStatsCollector {
+    frame_times: VecDeque<f64>,
+    max_frame_count: usize,
+}
+
+impl FrameStatsCollector {
+    pub fn new(max_frame_count: usize) -> Self {
+        Self {
+            frame_times: VecDeque::with_capacity(max_frame_count),
+            max_frame_count,
+        }
+    }
+
+    pub fn add_frame_time(&mut self, frame_time: f64) {
+        if self.frame_times.len() == self.max_frame_count {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        if self.frame_times.is_empty() {
+            return FrameStats::default();
+        }
+        let sum: f64 = self.frame_times.iter().sum();
+        let average_frame_time = sum / self.frame_times.len() as f64;
+        FrameStats {
+            fps: if average_frame_time > 0.0 {
+                1000.0 / average_frame_time
+            } else {
+                0.0
+            },
+            average_frame_time,
+            min_frame_time: self.frame_times.iter().cloned().fold(f64::MAX, f64::min),
+            max_frame_time: self.frame_times.iter().cloned().fold(0.0, f64::max),
+            frame_count: self.frame_times.len(),
+        }
+    }
+}
@@ -26,10 +26,75 @@ pub struct FrameInput {
 
     /// Whether or not this is the first frame.
     pub first_frame: bool,
+
+    /// The index of this frame, ie. zero for the first frame and then increasing by one each frame.
+    pub frame_index: u64,
+
+    /// The number of fixed time step updates that should be simulated this frame.
+    /// Always zero if no [fixed time step](crate::FrameInputGenerator::set_fixed_time_step) is specified.
+    pub fixed_time_steps: u32,
+
+    /// The fraction of a fixed time step that has elapsed since the last fixed time step update, a value between 0 and 1.
+    /// Use this to interpolate between the previous and the current simulated state when rendering.
+    /// Always zero if no [fixed time step](crate::FrameInputGenerator::set_fixed_time_step) is specified.
+    pub interpolation_factor: f64,
+
+    /// The latest external data sent to the render loop using a [PayloadSender](crate::PayloadSender),
+    /// for example audio analysis or simulation state.
+    pub payload: crate::FramePayload,
+
+    /// Statistics of the time spent on the last frames, for example the frame rate.
+    pub stats: crate::FrameStats,
+}
+
+impl FrameInput {
+    ///
+    /// Converts the given position in logical pixels, for example the position of a mouse [event](crate::Event), to physical pixels.
+    ///
+    pub fn to_physical(&self, logical_position: (f64, f64)) -> (f64, f64) {
+        (
+            logical_position.0 * self.device_pixel_ratio,
+            logical_position.1 * self.device_pixel_ratio,
+        )
+    }
+
+    ///
+    /// Converts the given position in physical pixels to logical pixels.
+    ///
+    pub fn to_logical(&self, physical_position: (f64, f64)) -> (f64, f64) {
+        (
+            physical_position.0 / self.device_pixel_ratio,
+            physical_position.1 / self.device_pixel_ratio,
+        )
+    }
+
+    ///
+    /// Converts the given position in logical pixels, for example the position of a mouse [event](crate::Event),
+    /// to screen coordinates relative to the given viewport, which is given in physical pixels.
+    /// The screen coordinates are between 0 and 1 inside the viewport, where (0, 0) indicate the top left corner of the viewport
+    /// and (1, 1) indicate the bottom right corner, as expected by for example [Camera::pick](crate::Camera::pick).
+    ///
+    pub fn screen_coordinates(
+        &self,
+        logical_position: (f64, f64),
+        viewport: crate::Viewport,
+    ) -> (f32, f32) {
+        let (x, y) = self.to_physical(logical_position);
+        // The viewport is defined from the bottom left corner while the position is defined from the top left corner
+        let top = self.viewport.height as f64 - (viewport.y as f64 + viewport.height as f64);
+        (
+            ((x - viewport.x as f64) / viewport.width as f64) as f32,
+            ((y - top) / viewport.height as f64) as f32,
+        )
+    }
 }
 
 /// State of a key or button click.
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(
+    feature = "input-recording",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum State {
     Pressed,
     Released,
@@ -43,6 +108,10 @@ impl Default for State {
 
 /// Type of mouse button.
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(
+    feature = "input-recording",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum MouseButton {
     Left,
     Right,
@@ -50,7 +119,13 @@ pub enum MouseButton {
 }
 
 /// An input event (from mouse, keyboard or similar).
+/// All positions are given in logical pixels with the origin in the top left corner of the window,
+/// use [FrameInput::to_physical] or [FrameInput::screen_coordinates] to convert them.
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "input-recording",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Event {
     MouseClick {
         state: State,
@@ -83,10 +158,65 @@ pub enum Event {
         modifiers: Modifiers,
     },
     Text(String),
+    /// A file is being dragged over the window.
+    /// The path of the file is only available on desktop, on web it is always None.
+    FileHovered {
+        path: Option<std::path::PathBuf>,
+    },
+    /// The file that was dragged over the window has left the window without being dropped.
+    FileHoverCancelled,
+    /// A file with the given path has been dropped onto the window. Only on desktop.
+    DroppedFile(std::path::PathBuf),
+    /// A file with the given name and contents has been dropped onto the canvas. Only on web.
+    DroppedFileData {
+        name: String,
+        bytes: Vec<u8>,
+    },
+    /// The number of physical pixels for each logical pixel has changed,
+    /// for example when the window is moved to a monitor with a different resolution.
+    DevicePixelRatioChange {
+        device_pixel_ratio: f64,
+    },
+    /// The user wants to copy, for example by pressing Ctrl+C.
+    /// Write the selected text to the [clipboard](crate::Clipboard) if relevant.
+    Copy,
+    /// The user wants to cut, for example by pressing Ctrl+X.
+    /// Write the selected text to the [clipboard](crate::Clipboard) and remove it if relevant.
+    Cut,
+    /// The user has pasted the given text from the clipboard, for example by pressing Ctrl+V.
+    Paste(String),
+    /// An input method editor (IME) has started composing text. Only on web.
+    CompositionStart,
+    /// The text currently being composed by an input method editor (IME) has changed to the given text. Only on web.
+    CompositionUpdate(String),
+    /// An input method editor (IME) has finished composing the given text. Only on web.
+    /// On desktop, the composed text is received as [Text](Event::Text) events.
+    CompositionEnd(String),
+    /// The window has entered (true) or left (false) fullscreen, see [FrameOutput::fullscreen](crate::FrameOutput::fullscreen).
+    FullscreenChange(bool),
+    /// The mouse cursor has been grabbed (true) or released (false), see [FrameOutput::cursor_grab](crate::FrameOutput::cursor_grab).
+    /// On web, the browser also releases the cursor when the user presses escape.
+    CursorGrabChange(bool),
+    /// The window has gained keyboard focus.
+    Focused,
+    /// The window has lost keyboard focus.
+    Unfocused,
+    /// The application has been suspended, for example when it is sent to the background on mobile,
+    /// or on web when the page is hidden, for example by switching to another browser tab.
+    /// On desktop, it is sent when the window is minimized on platforms where minimizing resizes the window to zero size, for example Windows,
+    /// but not when the window is covered by other windows, since that is not reported by the windowing library.
+    /// See [WindowSettings::pause_when_hidden](crate::window::WindowSettings::pause_when_hidden) to stop rendering until it is resumed.
+    Suspended,
+    /// The application has been resumed after being [suspended](Event::Suspended).
+    Resumed,
 }
 
 /// Keyboard key input.
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(
+    feature = "input-recording",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Key {
     ArrowDown,
     ArrowLeft,
@@ -157,6 +287,10 @@ pub enum Key {
 
 /// State of modifiers (alt, ctrl, shift and command).
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "input-recording",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct Modifiers {
     /// Either of the alt keys are down (option ⌥ on Mac).
     pub alt: State,
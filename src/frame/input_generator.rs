@@ -0,0 +1,153 @@
+use crate::frame::*;
+use crate::math::*;
+
+const MAX_FIXED_TIME_STEPS: u32 = 10;
+const STATS_FRAME_COUNT: usize = 60;
+const STATS_LOG_INTERVAL: f64 = 1000.0;
+
+///
+/// Generates the [FrameInput] each frame when rendering without the render loop of the default [window](crate::window),
+/// for example when rendering into a window or canvas owned by another toolkit.
+/// The events from the other toolkit should be translated into [events](crate::Event) and given to
+/// [generate](FrameInputGenerator::generate) together with the size of the render surface.
+///
+pub struct FrameInputGenerator {
+    last_time: f64,
+    accumulated_time: f64,
+    first_frame: bool,
+    frame_index: u64,
+    device_pixel_ratio: f64,
+    fixed_time_step: Option<f64>,
+    fixed_time_accumulator: f64,
+    payload_sender: PayloadSender,
+    stats_collector: FrameStatsCollector,
+    log_stats: bool,
+    last_stats_log_time: f64,
+    #[cfg(not(target_arch = "wasm32"))]
+    start_time: std::time::Instant,
+}
+
+impl FrameInputGenerator {
+    pub fn new() -> Self {
+        let mut generator = Self {
+            last_time: 0.0,
+            accumulated_time: 0.0,
+            first_frame: true,
+            frame_index: 0,
+            device_pixel_ratio: 1.0,
+            fixed_time_step: None,
+            fixed_time_accumulator: 0.0,
+            payload_sender: PayloadSender::default(),
+            stats_collector: FrameStatsCollector::new(STATS_FRAME_COUNT),
+            log_stats: false,
+            last_stats_log_time: 0.0,
+            #[cfg(not(target_arch = "wasm32"))]
+            start_time: std::time::Instant::now(),
+        };
+        generator.last_time = generator.now();
+        generator
+    }
+
+    ///
+    /// Sets the time step in milliseconds used for computing the number of [fixed time steps](FrameInput::fixed_time_steps)
+    /// and the [interpolation factor](FrameInput::interpolation_factor) each frame. If None is specified, no fixed time steps are computed.
+    /// At most 10 fixed time steps are returned in one frame, the remaining time is skipped to avoid spending more and more time catching up.
+    ///
+    pub fn set_fixed_time_step(&mut self, time_step: Option<f64>) {
+        self.fixed_time_step = time_step.filter(|t| *t > 0.0);
+        self.fixed_time_accumulator = 0.0;
+    }
+
+    ///
+    /// Sets whether to log the [frame statistics](FrameInput::stats) each second at the info level using the [log](https://crates.io/crates/log) crate.
+    ///
+    pub fn set_log_stats(&mut self, log_stats: bool) {
+        self.log_stats = log_stats;
+    }
+
+    ///
+    /// Returns a sender which can be used to send external data to the [payload](FrameInput::payload) of the generated frame inputs.
+    ///
+    pub fn payload_sender(&self) -> PayloadSender {
+        self.payload_sender.clone()
+    }
+
+    ///
+    /// Generates the frame input for a new frame with the given events, a render surface with the given size in physical pixels
+    /// and the given number of physical pixels for each logical pixel.
+    /// A [DevicePixelRatioChange](Event::DevicePixelRatioChange) event is added if the number of physical pixels for each logical pixel has changed since the last frame.
+    ///
+    pub fn generate(
+        &mut self,
+        mut events: Vec<Event>,
+        physical_width: u32,
+        physical_height: u32,
+        device_pixel_ratio: f64,
+    ) -> FrameInput {
+        let now = self.now();
+        let elapsed_time = now - self.last_time;
+        self.last_time = now;
+        self.accumulated_time += elapsed_time;
+        if !self.first_frame {
+            self.stats_collector.add_frame_time(elapsed_time);
+        }
+        let stats = self.stats_collector.stats();
+        if self.log_stats && self.accumulated_time - self.last_stats_log_time >= STATS_LOG_INTERVAL
+        {
+            self.last_stats_log_time = self.accumulated_time;
+            log::info!("{}", stats);
+        }
+        if !self.first_frame && (device_pixel_ratio - self.device_pixel_ratio).abs() > 0.0001 {
+            events.push(Event::DevicePixelRatioChange { device_pixel_ratio });
+        }
+        self.device_pixel_ratio = device_pixel_ratio;
+        let first_frame = self.first_frame;
+        self.first_frame = false;
+        let frame_index = self.frame_index;
+        self.frame_index += 1;
+        let (fixed_time_steps, interpolation_factor) = if let Some(time_step) = self.fixed_time_step
+        {
+            self.fixed_time_accumulator += elapsed_time;
+            let mut steps = (self.fixed_time_accumulator / time_step).floor();
+            self.fixed_time_accumulator -= steps * time_step;
+            if steps > MAX_FIXED_TIME_STEPS as f64 {
+                steps = MAX_FIXED_TIME_STEPS as f64;
+            }
+            (steps as u32, self.fixed_time_accumulator / time_step)
+        } else {
+            (0, 0.0)
+        };
+        FrameInput {
+            events,
+            elapsed_time,
+            accumulated_time: self.accumulated_time,
+            viewport: Viewport::new_at_origo(physical_width, physical_height),
+            window_width: (physical_width as f64 / device_pixel_ratio) as u32,
+            window_height: (physical_height as f64 / device_pixel_ratio) as u32,
+            device_pixel_ratio,
+            first_frame,
+            frame_index,
+            fixed_time_steps,
+            interpolation_factor,
+            payload: self.payload_sender.payload(),
+            stats,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn now(&self) -> f64 {
+        let duration = self.start_time.elapsed();
+        duration.as_secs() as f64 * 1000.0 + duration.subsec_nanos() as f64 * 1e-6
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn now(&self) -> f64 {
+        js_sys::Date::now()
+    }
+}
+
+impl Default for FrameInputGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
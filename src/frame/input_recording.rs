@@ -0,0 +1,208 @@
+use crate::frame::*;
+use crate::io::IOError;
+
+const VERSION: u32 = 1;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct RecordedFrame {
+    elapsed_time: f64,
+    fixed_time_steps: u32,
+    interpolation_factor: f64,
+    events: Vec<Event>,
+}
+
+///
+/// The input of a number of frames recorded by an [InputRecorder], ie. the events and the elapsed time of each frame,
+/// which can be replayed by an [InputReplayer], for example for automated demo playback or for reproducing a bug.
+///
+/// # Feature
+/// Only available when the `input-recording` feature is enabled.
+///
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct InputRecording {
+    version: u32,
+    frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    ///
+    /// Returns the number of recorded frames.
+    ///
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    ///
+    /// Returns the total recorded time in milliseconds.
+    ///
+    pub fn duration(&self) -> f64 {
+        self.frames.iter().map(|frame| frame.elapsed_time).sum()
+    }
+
+    ///
+    /// Serializes the recording into bytes, which can for example be saved to a file using [Saver::save_file](crate::Saver::save_file).
+    ///
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IOError> {
+        bincode::serialize(&InputRecording {
+            version: VERSION,
+            frames: self.frames.clone(),
+        })
+        .map_err(|e| IOError::FailedToSave {
+            message: format!("Could not serialize the input recording: {}", e),
+        })
+    }
+
+    ///
+    /// Deserializes a recording from bytes serialized by [to_bytes](Self::to_bytes),
+    /// for example loaded from a file using [Loader::load](crate::Loader::load).
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IOError> {
+        let recording =
+            bincode::deserialize::<InputRecording>(bytes).map_err(|e| IOError::FailedToLoad {
+                message: format!("Could not deserialize the input recording: {}", e),
+            })?;
+        if recording.version != VERSION {
+            Err(IOError::FailedToLoad {
+                message: format!(
+                    "The input recording has version {} but only version {} is supported",
+                    recording.version, VERSION
+                ),
+            })?;
+        }
+        Ok(recording)
+    }
+}
+
+///
+/// Records the input of each frame, see [InputRecording].
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let frame_input: FrameInput = unimplemented!();
+/// let mut recorder = InputRecorder::new();
+/// // Each frame before the events are handled:
+/// recorder.record(&frame_input);
+/// // When done:
+/// Saver::save_file("input.rec", &recorder.recording().to_bytes().unwrap()).unwrap();
+/// ```
+///
+/// # Feature
+/// Only available when the `input-recording` feature is enabled.
+///
+pub struct InputRecorder {
+    recording: InputRecording,
+}
+
+impl InputRecorder {
+    pub fn new() -> Self {
+        Self {
+            recording: InputRecording {
+                version: VERSION,
+                frames: Vec::new(),
+            },
+        }
+    }
+
+    ///
+    /// Records the events and the elapsed time of the given frame.
+    /// Should be called before any of the events are marked as handled, since whether an event is handled is also recorded.
+    ///
+    pub fn record(&mut self, frame_input: &FrameInput) {
+        self.recording.frames.push(RecordedFrame {
+            elapsed_time: frame_input.elapsed_time,
+            fixed_time_steps: frame_input.fixed_time_steps,
+            interpolation_factor: frame_input.interpolation_factor,
+            events: frame_input.events.clone(),
+        });
+    }
+
+    ///
+    /// Returns the input recorded so far.
+    ///
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    ///
+    /// Stops recording and returns the recorded input.
+    ///
+    pub fn finish(self) -> InputRecording {
+        self.recording
+    }
+}
+
+impl Default for InputRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///
+/// Replays an [InputRecording] by replacing the input of each frame with the recorded input,
+/// so that the render loop receives the same events and time steps as when the input was recorded.
+/// The replay is deterministic if the application only depends on the input, for example not on random numbers seeded by the time.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let mut frame_input: FrameInput = unimplemented!();
+/// # let bytes: &[u8] = unimplemented!();
+/// let mut replayer = InputReplayer::new(InputRecording::from_bytes(bytes).unwrap());
+/// // Each frame before the events are handled:
+/// if !replayer.replay(&mut frame_input) {
+///     // The replay has finished
+/// }
+/// ```
+///
+/// # Feature
+/// Only available when the `input-recording` feature is enabled.
+///
+pub struct InputReplayer {
+    recording: InputRecording,
+    next_frame: usize,
+    accumulated_time: f64,
+}
+
+impl InputReplayer {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            next_frame: 0,
+            accumulated_time: 0.0,
+        }
+    }
+
+    ///
+    /// Replaces the events, the elapsed and accumulated time and the fixed time steps of the given frame input with the next recorded frame.
+    /// The live events are discarded, so that the replay is not disturbed by the user.
+    /// Returns false and leaves the frame input unchanged if all recorded frames have been replayed.
+    ///
+    pub fn replay(&mut self, frame_input: &mut FrameInput) -> bool {
+        if let Some(frame) = self.recording.frames.get(self.next_frame) {
+            self.next_frame += 1;
+            self.accumulated_time += frame.elapsed_time;
+            frame_input.events = frame.events.clone();
+            frame_input.elapsed_time = frame.elapsed_time;
+            frame_input.accumulated_time = self.accumulated_time;
+            frame_input.fixed_time_steps = frame.fixed_time_steps;
+            frame_input.interpolation_factor = frame.interpolation_factor;
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// Returns whether all recorded frames have been replayed.
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.recording.frames.len()
+    }
+
+    ///
+    /// Starts the replay from the first recorded frame again.
+    ///
+    pub fn restart(&mut self) {
+        self.next_frame = 0;
+        self.accumulated_time = 0.0;
+    }
+}
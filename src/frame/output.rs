@@ -1,3 +1,5 @@
+use crate::definition::*;
+
 ///
 /// Output from the rendering to the window each frame.
 ///
@@ -20,14 +22,46 @@ pub struct FrameOutput {
 
     ///
     /// Takes a screenshot if this is set to some path and saves it at the given location.
+    /// Requires the `image-io` feature.
     /// Only works on desktop, will be ignored on web.
     ///
     pub screenshot: Option<std::path::PathBuf>,
 
     ///
     /// Whether to stop the render loop until next event.
+    /// If this is true, the render loop blocks until an [event](crate::Event) arrives or the window is resized,
+    /// so no time is spent rendering when nothing changes, which is ideal for viewer-style applications.
+    /// Set it to false to explicitly request a redraw, for example while an animation is running,
+    /// and the next frame is rendered as soon as possible.
     ///
     pub wait_next_event: bool,
+
+    ///
+    /// The position in logical pixels of the text cursor, if any text is being edited.
+    /// Used for placing the candidate window of an input method editor (IME) next to the edited text.
+    /// Only works on desktop, will be ignored on web.
+    ///
+    pub ime_position: Option<(f64, f64)>,
+
+    ///
+    /// Changes the mouse cursor if this is set to some cursor. The cursor stays the same until it is changed again.
+    ///
+    pub cursor: Option<Cursor>,
+
+    ///
+    /// Enters fullscreen if this is set to true and leaves fullscreen if it is set to false.
+    /// A [FullscreenChange](crate::Event::FullscreenChange) event is sent when the window enters or leaves fullscreen.
+    /// On web, the browser only allows entering fullscreen shortly after user input, for example a mouse click.
+    ///
+    pub fullscreen: Option<bool>,
+
+    ///
+    /// Grabs and hides the mouse cursor if this is set to true and releases it if it is set to false, for example for first-person camera controls.
+    /// While the cursor is grabbed, [MouseMotion](crate::Event::MouseMotion) events only contain the movement of the mouse in the delta and the position is not changed.
+    /// A [CursorGrabChange](crate::Event::CursorGrabChange) event is sent when the cursor is grabbed or released.
+    /// On web, this uses the pointer lock API and the browser only allows locking the pointer shortly after user input, for example a mouse click.
+    ///
+    pub cursor_grab: Option<bool>,
 }
 
 impl Default for FrameOutput {
@@ -37,6 +71,79 @@ impl Default for FrameOutput {
             swap_buffers: true,
             screenshot: None,
             wait_next_event: false,
+            ime_position: None,
+            cursor: None,
+            fullscreen: None,
+            cursor_grab: None,
+        }
+    }
+}
+
+///
+/// The mouse cursor shown when the mouse is over the window, see [FrameOutput::cursor].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cursor {
+    /// The default cursor of the platform, usually an arrow.
+    Default,
+    /// A pointing hand, usually used for links and buttons.
+    Pointer,
+    Crosshair,
+    /// A text cursor.
+    Text,
+    Move,
+    Wait,
+    NotAllowed,
+    Grab,
+    Grabbing,
+    /// A horizontal (east-west) resize cursor.
+    ResizeHorizontal,
+    /// A vertical (north-south) resize cursor.
+    ResizeVertical,
+    /// A diagonal (northwest-southeast) resize cursor.
+    ResizeDiagonal,
+    /// A diagonal (northeast-southwest) resize cursor.
+    ResizeAntiDiagonal,
+    /// No cursor is shown.
+    Hidden,
+    /// A custom cursor image given as RGBA bytes with the top row first and the hotspot, ie. the clicked point, in pixels from the top left corner.
+    /// Only supported on web, on desktop the default cursor is shown instead.
+    Custom {
+        rgba: Vec<u8>,
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    },
+}
+
+impl Cursor {
+    ///
+    /// Creates a [custom](Cursor::Custom) cursor from the given texture with the top row first, for example loaded from an image,
+    /// with the hotspot given in pixels from the top left corner.
+    ///
+    pub fn from_texture(texture: &CPUTexture<u8>, hotspot: (u32, u32)) -> Self {
+        Cursor::Custom {
+            rgba: rgba_from_texture(texture),
+            width: texture.width,
+            height: texture.height,
+            hotspot,
+        }
+    }
+}
+
+///
+/// Returns the pixels of the given texture as RGBA bytes. One channel is treated as gray and two channels as gray and alpha.
+///
+pub(crate) fn rgba_from_texture(texture: &CPUTexture<u8>) -> Vec<u8> {
+    let channels = texture.format.color_channel_count() as usize;
+    let mut rgba = Vec::with_capacity((texture.width * texture.height * 4) as usize);
+    for pixel in texture.data.chunks(channels) {
+        match channels {
+            1 => rgba.extend_from_slice(&[pixel[0], pixel[0], pixel[0], 255]),
+            2 => rgba.extend_from_slice(&[pixel[0], pixel[0], pixel[0], pixel[1]]),
+            3 => rgba.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]),
+            _ => rgba.extend_from_slice(&pixel[..4]),
         }
     }
+    rgba
 }
@@ -0,0 +1,78 @@
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+type Data = Arc<dyn Any + Send + Sync>;
+
+///
+/// External data given to the render loop, for example audio analysis (FFT bins) or the state of a simulation running on another thread,
+/// which is available in the [FrameInput](crate::FrameInput) each frame without the need for global mutable state.
+/// The data is sent using a [PayloadSender] and the latest data sent is available until new data is sent.
+///
+#[derive(Clone, Default)]
+pub struct FramePayload {
+    data: Option<Data>,
+}
+
+impl FramePayload {
+    ///
+    /// Returns the latest data sent if it is of the given type, otherwise `None`.
+    ///
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.data.as_ref().and_then(|data| data.downcast_ref::<T>())
+    }
+
+    ///
+    /// Returns whether no data has been sent yet.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.data.is_none()
+    }
+}
+
+impl std::fmt::Debug for FramePayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FramePayload")
+            .field("is_empty", &self.is_empty())
+            .finish()
+    }
+}
+
+///
+/// Sends data to the render loop which is then available as the [payload](crate::FrameInput::payload) of the following frames.
+/// The sender can be cloned and moved to other threads, for example an audio thread, and only the latest data sent is kept.
+/// Sending data does not wake up a render loop which waits for the next event, see [FrameOutput::wait_next_event](crate::FrameOutput::wait_next_event).
+///
+/// ```no_run
+/// # use three_d::*;
+/// let mut frame_input_generator = FrameInputGenerator::new(); // Or use the payload sender of the window
+/// let sender = frame_input_generator.payload_sender();
+/// std::thread::spawn(move || loop {
+///     let spectrum: Vec<f32> = vec![0.0; 256]; // Computed from audio input
+///     sender.send(spectrum);
+/// });
+/// // Each frame:
+/// let frame_input = frame_input_generator.generate(Vec::new(), 1024, 768, 1.0);
+/// if let Some(spectrum) = frame_input.payload.get::<Vec<f32>>() {
+///     // Visualize the spectrum
+/// }
+/// ```
+///
+#[derive(Clone, Default)]
+pub struct PayloadSender {
+    latest: Arc<Mutex<Option<Data>>>,
+}
+
+impl PayloadSender {
+    ///
+    /// Sends the given data to the render loop, replacing any data sent earlier.
+    ///
+    pub fn send<T: Any + Send + Sync>(&self, data: T) {
+        *self.latest.lock().unwrap() = Some(Arc::new(data));
+    }
+
+    pub(crate) fn payload(&self) -> FramePayload {
+        FramePayload {
+            data: self.latest.lock().unwrap().clone(),
+        }
+    }
+}
@@ -5,6 +5,7 @@
 use crate::camera::*;
 use crate::core::*;
 use crate::definition::*;
+use crate::effect::*;
 use crate::math::*;
 
 pub fn ray_intersect(
@@ -57,7 +58,7 @@ pub fn ray_intersect(
             ..WriteMask::NONE
         },
         depth_test: DepthTestType::Less,
-        ..Default::default()
+        ..RenderStates::new(viewport)
     };
     render_target.write(
         ClearState {
@@ -72,7 +73,7 @@ pub fn ray_intersect(
                     .map(|aabb| camera.in_frustum(&aabb))
                     .unwrap_or(true)
                 {
-                    geometry.render_depth_to_red(render_states, viewport, &camera, max_depth)?;
+                    geometry.render_depth_to_red(render_states, &camera, max_depth)?;
                 }
             }
             Ok(())
@@ -85,3 +86,255 @@ pub fn ray_intersect(
         None
     })
 }
+
+///
+/// Renders an image with the given width and height, which can be far larger than the maximum size of the screen or a render target,
+/// by splitting the image into tiles of at most `tile_size` x `tile_size` pixels and rendering each tile with a
+/// [projection window](crate::Camera::set_projection_window) which only covers that tile.
+/// The `render` closure is called once for each tile and must render the scene with the given viewport and camera.
+/// The aspect and projection window of the camera are changed while rendering the tiles and restored afterwards.
+/// A projection window set on the camera is ignored, ie. the entire view is rendered.
+///
+/// Returns the RGBA color values of the stitched image as a list of bytes with the bottom row first,
+/// in the same way as [Screen::read_color](crate::Screen::read_color), so it can be saved using
+/// [Saver::save_pixels](crate::Saver::save_pixels).
+/// Returns an error if the tile size, width or height is zero.
+///
+pub fn render_tiled<F: FnMut(Viewport, &Camera) -> Result<(), Error>>(
+    context: &Context,
+    camera: &mut Camera,
+    width: u32,
+    height: u32,
+    tile_size: u32,
+    clear_state: ClearState,
+    render: F,
+) -> Result<Vec<u8>, Error> {
+    if tile_size == 0 {
+        return Err(Error::RenderTargetError {
+            message: "The tile size must be at least one pixel".to_string(),
+        });
+    }
+    if width == 0 || height == 0 {
+        return Err(Error::RenderTargetError {
+            message: "The width and height of the image must be at least one pixel".to_string(),
+        });
+    }
+    // The projection window and aspect of the camera are changed for each tile and restored afterwards, also if rendering fails
+    let mut original = Camera::new_orthographic(
+        context,
+        vec3(0.0, 0.0, 1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        1.0,
+        1.0,
+        1.0,
+    )?;
+    original.copy_from(camera)?;
+    let result = render_tiles(
+        context,
+        camera,
+        width,
+        height,
+        tile_size,
+        clear_state,
+        render,
+    );
+    let restored = camera.copy_from(&original);
+    let pixels = result?;
+    restored?;
+    Ok(pixels)
+}
+
+fn render_tiles<F: FnMut(Viewport, &Camera) -> Result<(), Error>>(
+    context: &Context,
+    camera: &mut Camera,
+    width: u32,
+    height: u32,
+    tile_size: u32,
+    clear_state: ClearState,
+    mut render: F,
+) -> Result<Vec<u8>, Error> {
+    let tile_width = tile_size.min(width);
+    let tile_height = tile_size.min(height);
+    let texture = ColorTargetTexture2D::<u8>::new(
+        context,
+        tile_width,
+        tile_height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )?;
+    let depth_texture = DepthTargetTexture2D::new(
+        context,
+        tile_width,
+        tile_height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )?;
+    let render_target = RenderTarget::new(context, &texture, &depth_texture)?;
+
+    camera.set_aspect(width as f32 / height as f32)?;
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    let mut y0 = 0;
+    while y0 < height {
+        let h = tile_height.min(height - y0);
+        let mut x0 = 0;
+        while x0 < width {
+            let w = tile_width.min(width - x0);
+            // The tiles are placed bottom-up while the screen coordinates are top-down
+            camera.set_projection_window(
+                vec2(
+                    x0 as f32 / width as f32,
+                    1.0 - (y0 + h) as f32 / height as f32,
+                ),
+                vec2(
+                    (x0 + w) as f32 / width as f32,
+                    1.0 - y0 as f32 / height as f32,
+                ),
+            )?;
+            let viewport = Viewport::new_at_origo(w, h);
+            render_target.write(clear_state, || render(viewport, camera))?;
+            let tile_pixels = texture.read(viewport)?;
+            for row in 0..h as usize {
+                let source = row * w as usize * 4;
+                let destination = ((y0 as usize + row) * width as usize + x0 as usize) * 4;
+                pixels[destination..destination + w as usize * 4]
+                    .copy_from_slice(&tile_pixels[source..source + w as usize * 4]);
+            }
+            x0 += w;
+        }
+        y0 += h;
+    }
+    Ok(pixels)
+}
+
+///
+/// Renders the scene seen from the given position in all directions into an equirectangular (360°) panorama with the given width and height,
+/// for example for sharing a 360° capture of a scene. The width should be twice the height.
+/// The scene is rendered into the six sides of a cube map, using the `render` closure which is called once for each side and must render the scene
+/// with the given viewport and camera, which is then converted using [cube_map_to_equirectangular].
+///
+/// Returns the RGBA color values of the panorama as a list of bytes with the bottom row first,
+/// in the same way as [Screen::read_color](crate::Screen::read_color), so it can be saved using
+/// [Saver::save_pixels](crate::Saver::save_pixels).
+///
+pub fn render_equirectangular<F: FnMut(Viewport, &Camera) -> Result<(), Error>>(
+    context: &Context,
+    position: Vec3,
+    z_near: f32,
+    z_far: f32,
+    width: u32,
+    height: u32,
+    clear_state: ClearState,
+    mut render: F,
+) -> Result<Vec<u8>, Error> {
+    let size = (width / 4).max(1);
+    let texture = ColorTargetTexture2D::<u8>::new(
+        context,
+        size,
+        size,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )?;
+    let depth_texture = DepthTargetTexture2D::new(
+        context,
+        size,
+        size,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )?;
+    let render_target = RenderTarget::new(context, &texture, &depth_texture)?;
+
+    // The view direction and up direction of the sides of the cube map in the order right, left, top, bottom, front and back
+    let sides = [
+        (vec3(1.0, 0.0, 0.0), vec3(0.0, -1.0, 0.0)),
+        (vec3(-1.0, 0.0, 0.0), vec3(0.0, -1.0, 0.0)),
+        (vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, 1.0)),
+        (vec3(0.0, -1.0, 0.0), vec3(0.0, 0.0, -1.0)),
+        (vec3(0.0, 0.0, 1.0), vec3(0.0, -1.0, 0.0)),
+        (vec3(0.0, 0.0, -1.0), vec3(0.0, -1.0, 0.0)),
+    ];
+    let viewport = Viewport::new_at_origo(size, size);
+    let mut camera = Camera::new_perspective(
+        context,
+        position,
+        position + sides[0].0,
+        sides[0].1,
+        degrees(90.0),
+        1.0,
+        z_near,
+        z_far,
+    )?;
+    let mut data = Vec::with_capacity(6 * size as usize * size as usize * 4);
+    for (direction, up) in sides.iter() {
+        camera.set_view(position, position + *direction, *up)?;
+        render_target.write(clear_state, || render(viewport, &camera))?;
+        // The rows are read bottom first which matches the order of the rows in a cube map side when the up direction is flipped
+        data.extend(texture.read(viewport)?);
+    }
+    let cube_map = TextureCubeMap::new(
+        context,
+        &CPUTexture {
+            data,
+            width: size,
+            height: size,
+            format: Format::RGBA,
+            // The rendered colors are in sRGB color space
+            is_srgb: true,
+            mip_map_filter: None,
+            wrap_s: Wrapping::ClampToEdge,
+            wrap_t: Wrapping::ClampToEdge,
+            wrap_r: Wrapping::ClampToEdge,
+            ..Default::default()
+        },
+    )?;
+    cube_map_to_equirectangular(context, &cube_map, width, height)
+}
+
+///
+/// Converts the given cube map, for example the texture of a [Skybox](crate::Skybox), into an equirectangular (360°) panorama with the given width and height,
+/// ie. the inverse of loading a skybox. The width should be twice the height.
+/// The center of the panorama is in the negative z direction and the top is in the positive y direction.
+///
+/// Returns the RGBA color values of the panorama as a list of bytes with the bottom row first,
+/// in the same way as [Screen::read_color](crate::Screen::read_color), so it can be saved using
+/// [Saver::save_pixels](crate::Saver::save_pixels).
+///
+pub fn cube_map_to_equirectangular(
+    context: &Context,
+    cube_map: &impl TextureCube,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, Error> {
+    let texture = ColorTargetTexture2D::<u8>::new(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )?;
+    let effect = ImageEffect::new(context, include_str!("effect/shaders/equirectangular.frag"))?;
+    let viewport = Viewport::new_at_origo(width, height);
+    texture.write(ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+        effect.use_texture_cube(cube_map, "cubeMap")?;
+        effect.apply(RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTestType::Always,
+            ..RenderStates::new(viewport)
+        })
+    })?;
+    texture.read(viewport)
+}
@@ -7,3 +7,9 @@ mod egui_gui;
 #[doc(inline)]
 #[cfg(feature = "egui-gui")]
 pub use egui_gui::*;
+
+#[cfg(feature = "widget-gui")]
+mod widget_gui;
+#[doc(inline)]
+#[cfg(feature = "widget-gui")]
+pub use widget_gui::*;
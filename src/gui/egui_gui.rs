@@ -27,18 +27,11 @@ impl GUI {
             height: 0,
             texture_version: 0,
             texture: None,
-            program: Program::from_source(
+            program: Program::from_source_with_preprocessor(
                 context,
-                &format!(
-                    "{}{}",
-                    include_str!("../core/shared.frag"),
-                    include_str!("shaders/egui.vert")
-                ),
-                &format!(
-                    "{}{}",
-                    include_str!("../core/shared.frag"),
-                    include_str!("shaders/egui.frag")
-                ),
+                include_str!("shaders/egui.vert"),
+                include_str!("shaders/egui.frag"),
+                &ShaderPreprocessor::new(),
             )?,
         })
     }
@@ -188,7 +181,7 @@ impl GUI {
                 ..Default::default()
             }),
             depth_test: DepthTestType::Always,
-            ..Default::default()
+            ..RenderStates::new(viewport)
         };
 
         self.program.use_texture(texture, "u_sampler")?;
@@ -199,8 +192,7 @@ impl GUI {
         self.program.use_attribute_vec4(&color_buffer, "a_srgba")?;
         self.program.use_attribute_vec2(&uv_buffer, "a_tc")?;
 
-        self.program
-            .draw_elements(render_states, CullType::None, viewport, &index_buffer);
+        self.program.draw_elements(render_states, &index_buffer);
         Ok(())
     }
 }
@@ -1,3 +1,5 @@
+#include "shared"
+
 uniform vec2 u_screen_size;
 
 in vec2 a_pos;
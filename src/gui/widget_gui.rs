@@ -0,0 +1,790 @@
+use crate::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+///
+/// The sizes and colors used by a [WidgetGUI], where all sizes are in logical pixels.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WidgetStyle {
+    /// The height of the text.
+    pub font_size: f32,
+    /// The width of the panel.
+    pub width: f32,
+    /// The width of the labels in front of [sliders](WidgetPanel::slider) and [text fields](WidgetPanel::text_field).
+    pub label_width: f32,
+    /// The space between the widgets and between the widgets and the edge of the panel.
+    pub spacing: f32,
+    /// The color of the text.
+    pub text_color: Vec4,
+    /// The color of the panel behind the widgets, or `None` for a transparent panel.
+    pub background_color: Option<Vec4>,
+    /// The color of buttons, check boxes, slider tracks and text fields.
+    pub widget_color: Vec4,
+    /// The color of a widget when the mouse is over it.
+    pub hover_color: Vec4,
+    /// The color of pressed buttons, checked check boxes, the filled part of sliders, the text cursor and the selected text.
+    pub accent_color: Vec4,
+}
+
+impl Default for WidgetStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 16.0,
+            width: 260.0,
+            label_width: 80.0,
+            spacing: 6.0,
+            text_color: vec4(0.95, 0.95, 0.95, 1.0),
+            background_color: Some(vec4(0.1, 0.1, 0.1, 0.8)),
+            widget_color: vec4(0.25, 0.25, 0.25, 1.0),
+            hover_color: vec4(0.35, 0.35, 0.35, 1.0),
+            accent_color: vec4(0.2, 0.45, 0.8, 1.0),
+        }
+    }
+}
+
+// The text field with keyboard focus, where the caret and the anchor (the other end of the selection) are byte indices into the text
+struct TextFocus {
+    id: u64,
+    caret: usize,
+    anchor: usize,
+    scroll: f32,
+}
+
+// The text in logical pixels with y down, the position is the start of the baseline
+struct DrawnText {
+    text: String,
+    position: Vec2,
+    color: Vec4,
+    clip: Option<(Vec2, Vec2)>,
+}
+
+#[derive(Default)]
+struct WidgetState {
+    pointer: Option<Vec2>,
+    pointer_down: bool,
+    active: Option<u64>,
+    focus: Option<TextFocus>,
+    copied_text: Option<String>,
+    ime_position: Option<(f64, f64)>,
+    shapes: VectorGraphic,
+    texts: Vec<DrawnText>,
+}
+
+///
+/// A minimal immediate mode GUI with a [button](WidgetPanel::button), [check box](WidgetPanel::checkbox), [slider](WidgetPanel::slider)
+/// and [text field](WidgetPanel::text_field), enough for control panels in demos without depending on a full GUI crate.
+/// The widgets are laid out from top to bottom in a panel, rendered using [vector graphics](VectorGraphic) and an [SdfFont]
+/// and driven by the events in the [FrameInput].
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let font: SdfFont = unimplemented!();
+/// # let mut frame_input: FrameInput = unimplemented!();
+/// let mut gui = WidgetGUI::new(&context, font).unwrap();
+/// let (mut speed, mut paused, mut name) = (1.0, false, String::from("Cube"));
+/// // Each frame:
+/// gui.update(&mut frame_input, (10.0, 10.0), |panel| {
+///     panel.slider("Speed", &mut speed, (0.0, 5.0));
+///     panel.checkbox("Paused", &mut paused);
+///     panel.text_field("Name", &mut name);
+///     if panel.button("Reset") {
+///         speed = 1.0;
+///     }
+/// }).unwrap();
+/// Screen::write(&context, ClearState::default(), || {
+///     // Render the scene and then the GUI on top
+///     gui.render()
+/// }).unwrap();
+/// ```
+///
+/// # Feature
+/// Only available when the `widget-gui` feature is enabled.
+///
+pub struct WidgetGUI {
+    context: Context,
+    font: SdfFont,
+    font_texture: Texture2D,
+    viewport: Viewport,
+    window_height: f32,
+    time: f64,
+    state: WidgetState,
+    /// The sizes and colors of the widgets.
+    pub style: WidgetStyle,
+}
+
+impl WidgetGUI {
+    ///
+    /// Creates a new GUI which uses the given font for the text.
+    /// The font must contain the characters which are used in the labels and typed into the text fields, usually the printable ASCII characters.
+    ///
+    pub fn new(context: &Context, font: SdfFont) -> Result<Self, Error> {
+        Ok(Self {
+            context: context.clone(),
+            font_texture: Texture2D::new(context, &font.texture)?,
+            font,
+            viewport: Viewport::new_at_origo(1, 1),
+            window_height: 1.0,
+            time: 0.0,
+            state: WidgetState::default(),
+            style: WidgetStyle::default(),
+        })
+    }
+
+    ///
+    /// Handles the events and lays out the widgets added in the callback function in a panel with the top left corner at the given position in logical pixels.
+    /// The mouse events over the panel and the key events while a text field has keyboard focus are marked as handled.
+    /// Returns whether or not the GUI consumed any events and therefore needs to be rendered again.
+    ///
+    /// The state of the widgets, for example which slider is being dragged, is tracked by the label of the widget,
+    /// so the labels of the widgets of the same kind must be unique.
+    ///
+    pub fn update<F: FnOnce(&mut WidgetPanel)>(
+        &mut self,
+        frame_input: &mut FrameInput,
+        position: (f32, f32),
+        callback: F,
+    ) -> Result<bool, Error> {
+        self.viewport = frame_input.viewport;
+        self.window_height = frame_input.window_height as f32;
+        self.time = frame_input.accumulated_time;
+
+        let mut pressed = None;
+        let mut released = false;
+        let mut edits = Vec::new();
+        for event in frame_input.events.iter() {
+            match event {
+                Event::MouseClick {
+                    state,
+                    button: MouseButton::Left,
+                    position,
+                    ..
+                } => {
+                    let position = vec2(position.0 as f32, position.1 as f32);
+                    self.state.pointer = Some(position);
+                    if *state == State::Pressed {
+                        pressed = Some(position);
+                        self.state.pointer_down = true;
+                    } else {
+                        released = true;
+                        self.state.pointer_down = false;
+                    }
+                }
+                Event::MouseMotion { position, .. } => {
+                    self.state.pointer = Some(vec2(position.0 as f32, position.1 as f32));
+                }
+                Event::MouseLeave => {
+                    self.state.pointer = None;
+                }
+                Event::Key { .. } | Event::Text(_) | Event::Paste(_) | Event::Copy | Event::Cut => {
+                    edits.push(event.clone())
+                }
+                _ => {}
+            }
+        }
+
+        let had_focus = self.state.focus.is_some();
+        let had_active = self.state.active.is_some();
+        self.state.shapes.shapes.clear();
+        self.state.texts.clear();
+        self.state.ime_position = None;
+        let origin = vec2(position.0, position.1);
+        let mut panel = WidgetPanel {
+            font: &self.font,
+            style: self.style,
+            state: &mut self.state,
+            origin,
+            cursor: origin + vec2(self.style.spacing, self.style.spacing),
+            pressed,
+            released,
+            edits: &edits,
+            time: self.time,
+            focus_claimed: false,
+            changed: false,
+        };
+        callback(&mut panel);
+        let bottom = panel.cursor.y;
+        let focus_claimed = panel.focus_claimed;
+        let mut changed = panel.changed;
+
+        let panel_max = vec2(origin.x + self.style.width, bottom);
+        if let Some(color) = self.style.background_color {
+            self.state
+                .shapes
+                .shapes
+                .insert(0, rectangle(origin, panel_max, color));
+        }
+        if pressed.is_some() && !focus_claimed {
+            self.state.focus = None;
+        }
+        if released || !self.state.pointer_down {
+            self.state.active = None;
+        }
+
+        let inside = |position: &(f64, f64)| {
+            let p = vec2(position.0 as f32, position.1 as f32);
+            p.x >= origin.x && p.x <= panel_max.x && p.y >= origin.y && p.y <= panel_max.y
+        };
+        let captures_pointer = had_active || self.state.active.is_some();
+        let captures_keys = had_focus || self.state.focus.is_some();
+        for event in frame_input.events.iter_mut() {
+            match event {
+                Event::MouseClick {
+                    position,
+                    ref mut handled,
+                    ..
+                }
+                | Event::MouseMotion {
+                    position,
+                    ref mut handled,
+                    ..
+                }
+                | Event::MouseWheel {
+                    position,
+                    ref mut handled,
+                    ..
+                } => {
+                    if captures_pointer || inside(position) {
+                        *handled = true;
+                        changed = true;
+                    }
+                }
+                Event::Key {
+                    ref mut handled, ..
+                } => {
+                    if captures_keys {
+                        *handled = true;
+                        changed = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(changed || captures_keys)
+    }
+
+    ///
+    /// Returns the text which was copied or cut from a text field during the last [update](Self::update), if any,
+    /// which should be written to the [clipboard](crate::Clipboard).
+    ///
+    pub fn take_copied_text(&mut self) -> Option<String> {
+        self.state.copied_text.take()
+    }
+
+    ///
+    /// Returns the position in logical pixels of the text cursor in the text field with keyboard focus, if any,
+    /// which can be used for [FrameOutput::ime_position](crate::FrameOutput::ime_position).
+    ///
+    pub fn ime_position(&self) -> Option<(f64, f64)> {
+        self.state.ime_position
+    }
+
+    ///
+    /// Render the widgets laid out in the last [update](Self::update) on top of the current content of the render target.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&self) -> Result<(), Error> {
+        if self.state.shapes.shapes.is_empty() && self.state.texts.is_empty() {
+            return Ok(());
+        }
+        // The camera covers the window in logical pixels
+        let device_pixel_ratio = self.viewport.height as f32 / self.window_height.max(1.0);
+        let (width, height) = (
+            self.viewport.width as f32 / device_pixel_ratio,
+            self.window_height,
+        );
+        let camera = Camera::new_orthographic(
+            &self.context,
+            vec3(0.5 * width, 0.5 * height, 1.0),
+            vec3(0.5 * width, 0.5 * height, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            width,
+            height,
+            2.0,
+        )?;
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTestType::Always,
+            blend: Some(BlendParameters::TRANSPARENCY),
+            ..RenderStates::new(self.viewport)
+        };
+        // The widgets are laid out in logical pixels with y down, so they are flipped to y up
+        let flip = Mat4::from_translation(vec3(0.0, self.window_height, 0.0))
+            * Mat4::from_nonuniform_scale(1.0, -1.0, 1.0);
+
+        let cpu_mesh = self.state.shapes.to_cpu_mesh(0.25)?;
+        if !cpu_mesh.positions.is_empty() {
+            let mut mesh = Mesh::new(&self.context, &cpu_mesh)?;
+            mesh.transformation = flip;
+            mesh.render_color(render_states, &camera)?;
+        }
+
+        for text in self.state.texts.iter() {
+            let cpu_mesh = self.font.text_mesh(&text.text);
+            if cpu_mesh.positions.is_empty() {
+                continue;
+            }
+            let mut mesh = Mesh::new(&self.context, &cpu_mesh)?;
+            let size = self.style.font_size;
+            mesh.transformation = flip
+                * Mat4::from_translation(vec3(text.position.x, text.position.y, 0.0))
+                * Mat4::from_nonuniform_scale(size, -size, 1.0);
+            let scissor = text.clip.map(|(min, max)| Viewport {
+                x: self.viewport.x + (min.x * device_pixel_ratio) as i32,
+                y: self.viewport.y + ((self.window_height - max.y) * device_pixel_ratio) as i32,
+                width: ((max.x - min.x).max(0.0) * device_pixel_ratio) as u32,
+                height: ((max.y - min.y).max(0.0) * device_pixel_ratio) as u32,
+            });
+            mesh.render_with_sdf(
+                &self.font_texture,
+                &SdfStyle {
+                    color: text.color,
+                    ..Default::default()
+                },
+                RenderStates {
+                    scissor,
+                    ..render_states
+                },
+                &camera,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// The panel which the widgets of a [WidgetGUI] are added to in the callback of [WidgetGUI::update].
+/// Each widget is placed below the previous widget.
+///
+pub struct WidgetPanel<'a> {
+    font: &'a SdfFont,
+    style: WidgetStyle,
+    state: &'a mut WidgetState,
+    origin: Vec2,
+    cursor: Vec2,
+    pressed: Option<Vec2>,
+    released: bool,
+    edits: &'a [Event],
+    time: f64,
+    focus_claimed: bool,
+    changed: bool,
+}
+
+impl<'a> WidgetPanel<'a> {
+    ///
+    /// Adds a line of text.
+    ///
+    pub fn label(&mut self, text: &str) {
+        let (min, max) = self.next_row();
+        self.text(text, min.x, min, max, self.style.text_color);
+    }
+
+    ///
+    /// Adds a button with the given text and returns whether it was clicked.
+    ///
+    pub fn button(&mut self, text: &str) -> bool {
+        let id = widget_id("button", text);
+        let (min, max) = self.next_row();
+        let clicked = self.click(id, min, max);
+        let color = if self.state.active == Some(id) {
+            self.style.accent_color
+        } else if self.hovered(min, max) {
+            self.style.hover_color
+        } else {
+            self.style.widget_color
+        };
+        self.state.shapes.shapes.push(rectangle(min, max, color));
+        let text_width = self.font.text_width(text) * self.style.font_size;
+        let x = 0.5 * (min.x + max.x - text_width);
+        self.text(text, x, min, max, self.style.text_color);
+        clicked
+    }
+
+    ///
+    /// Adds a check box with the given text, which toggles the value when clicked, and returns whether the value was changed.
+    ///
+    pub fn checkbox(&mut self, text: &str, value: &mut bool) -> bool {
+        let id = widget_id("checkbox", text);
+        let (min, max) = self.next_row();
+        let clicked = self.click(id, min, max);
+        if clicked {
+            *value = !*value;
+        }
+        let size = max.y - min.y;
+        let box_min = min + vec2(0.15 * size, 0.15 * size);
+        let box_max = min + vec2(0.85 * size, 0.85 * size);
+        let color = if self.hovered(min, max) {
+            self.style.hover_color
+        } else {
+            self.style.widget_color
+        };
+        self.state
+            .shapes
+            .shapes
+            .push(rectangle(box_min, box_max, color));
+        if *value {
+            let inset = 0.15 * size;
+            self.state.shapes.shapes.push(rectangle(
+                box_min + vec2(inset, inset),
+                box_max - vec2(inset, inset),
+                self.style.accent_color,
+            ));
+        }
+        self.text(text, min.x + 1.2 * size, min, max, self.style.text_color);
+        clicked
+    }
+
+    ///
+    /// Adds a slider with the given label in front, which sets the value within the given range when dragged,
+    /// and returns whether the value was changed.
+    ///
+    pub fn slider(&mut self, label: &str, value: &mut f32, range: (f32, f32)) -> bool {
+        let id = widget_id("slider", label);
+        let (min, max) = self.next_row();
+        self.text(label, min.x, min, max, self.style.text_color);
+        let track_min = vec2(min.x + self.style.label_width, min.y);
+        if self.pressed.map_or(false, |p| inside(p, track_min, max)) {
+            self.state.active = Some(id);
+        }
+        let mut changed = false;
+        if self.state.active == Some(id) {
+            if let Some(pointer) = self.state.pointer {
+                let factor = ((pointer.x - track_min.x) / (max.x - track_min.x).max(1.0))
+                    .max(0.0)
+                    .min(1.0);
+                let new_value = range.0 + factor * (range.1 - range.0);
+                changed = new_value != *value;
+                *value = new_value;
+            }
+        }
+        let factor = if range.1 != range.0 {
+            ((*value - range.0) / (range.1 - range.0)).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        let color = if self.state.active == Some(id) || self.hovered(track_min, max) {
+            self.style.hover_color
+        } else {
+            self.style.widget_color
+        };
+        self.state
+            .shapes
+            .shapes
+            .push(rectangle(track_min, max, color));
+        let filled_x = track_min.x + factor * (max.x - track_min.x);
+        self.state.shapes.shapes.push(rectangle(
+            track_min,
+            vec2(filled_x, max.y),
+            self.style.accent_color,
+        ));
+        let text = format!("{:.2}", value);
+        let text_width = self.font.text_width(&text) * self.style.font_size;
+        let x = 0.5 * (track_min.x + max.x - text_width);
+        self.text(&text, x, track_min, max, self.style.text_color);
+        self.changed |= changed;
+        changed
+    }
+
+    ///
+    /// Adds a single line text field with the given label in front, which gets keyboard focus when clicked,
+    /// and returns whether the text was changed.
+    /// The text cursor is moved with the arrow keys, home and end, and text is selected by dragging, by holding shift or by pressing Ctrl+A.
+    /// Pressing enter or escape or clicking outside the text field removes the keyboard focus.
+    ///
+    pub fn text_field(&mut self, label: &str, text: &mut String) -> bool {
+        let id = widget_id("text_field", label);
+        let (min, max) = self.next_row();
+        self.text(label, min.x, min, max, self.style.text_color);
+        let field_min = vec2(min.x + self.style.label_width, min.y);
+        let padding = 0.3 * self.style.font_size;
+        let text_x = field_min.x + padding;
+        let inner_width = (max.x - field_min.x - 2.0 * padding).max(0.0);
+
+        if let Some(pressed) = self.pressed {
+            if inside(pressed, field_min, max) {
+                let scroll = self.focus_scroll(id);
+                let index = self.index_at(text, pressed.x - text_x + scroll);
+                self.state.focus = Some(TextFocus {
+                    id,
+                    caret: index,
+                    anchor: index,
+                    scroll,
+                });
+                self.state.active = Some(id);
+                self.focus_claimed = true;
+            }
+        }
+
+        let mut changed = false;
+        let has_focus = self.state.focus.as_ref().map_or(false, |f| f.id == id);
+        if has_focus {
+            let mut focus = self.state.focus.take().unwrap();
+            focus.caret = clamp_to_boundary(text, focus.caret);
+            focus.anchor = clamp_to_boundary(text, focus.anchor);
+            if self.state.active == Some(id) && self.pressed.is_none() {
+                if let Some(pointer) = self.state.pointer {
+                    focus.caret = self.index_at(text, pointer.x - text_x + focus.scroll);
+                }
+            }
+            let mut keep_focus = true;
+            for event in self.edits.iter() {
+                let (start, end) = (focus.caret.min(focus.anchor), focus.caret.max(focus.anchor));
+                match event {
+                    Event::Text(inserted) | Event::Paste(inserted) => {
+                        let inserted: String =
+                            inserted.chars().filter(|c| !c.is_control()).collect();
+                        text.replace_range(start..end, &inserted);
+                        focus.caret = start + inserted.len();
+                        focus.anchor = focus.caret;
+                        changed = true;
+                    }
+                    Event::Copy | Event::Cut => {
+                        if start < end {
+                            self.state.copied_text = Some(text[start..end].to_string());
+                            if let Event::Cut = event {
+                                text.replace_range(start..end, "");
+                                focus.caret = start;
+                                focus.anchor = start;
+                                changed = true;
+                            }
+                        }
+                    }
+                    Event::Key {
+                        state: State::Pressed,
+                        kind,
+                        modifiers,
+                        ..
+                    } => {
+                        let extend = modifiers.shift == State::Pressed;
+                        match kind {
+                            Key::Backspace | Key::Delete => {
+                                let (start, end) = if start < end {
+                                    (start, end)
+                                } else if *kind == Key::Backspace {
+                                    (previous_boundary(text, start), start)
+                                } else {
+                                    (start, next_boundary(text, start))
+                                };
+                                if start < end {
+                                    text.replace_range(start..end, "");
+                                    changed = true;
+                                }
+                                focus.caret = start;
+                                focus.anchor = start;
+                            }
+                            Key::ArrowLeft => {
+                                focus.caret = if start < end && !extend {
+                                    start
+                                } else {
+                                    previous_boundary(text, focus.caret)
+                                };
+                            }
+                            Key::ArrowRight => {
+                                focus.caret = if start < end && !extend {
+                                    end
+                                } else {
+                                    next_boundary(text, focus.caret)
+                                };
+                            }
+                            Key::Home => focus.caret = 0,
+                            Key::End => focus.caret = text.len(),
+                            Key::A if modifiers.command == State::Pressed => {
+                                focus.anchor = 0;
+                                focus.caret = text.len();
+                                continue;
+                            }
+                            Key::Enter | Key::Escape => keep_focus = false,
+                            _ => continue,
+                        }
+                        if !extend && !matches!(kind, Key::Backspace | Key::Delete) {
+                            focus.anchor = focus.caret;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            // Scroll the text such that the text cursor is visible
+            let caret_x = self.font.text_width(&text[..focus.caret]) * self.style.font_size;
+            if caret_x - focus.scroll > inner_width {
+                focus.scroll = caret_x - inner_width;
+            } else if caret_x < focus.scroll {
+                focus.scroll = caret_x;
+            }
+            let text_width = self.font.text_width(text) * self.style.font_size;
+            focus.scroll = focus
+                .scroll
+                .min((text_width - inner_width).max(0.0))
+                .max(0.0);
+            if keep_focus {
+                self.state.focus = Some(focus);
+            } else if self.state.active == Some(id) {
+                self.state.active = None;
+            }
+        }
+
+        let color = if has_focus || self.hovered(field_min, max) {
+            self.style.hover_color
+        } else {
+            self.style.widget_color
+        };
+        self.state
+            .shapes
+            .shapes
+            .push(rectangle(field_min, max, color));
+        let clip_min = vec2(text_x, field_min.y);
+        let clip_max = vec2(text_x + inner_width, max.y);
+        let scroll = self.focus_scroll(id);
+        let focus = self
+            .state
+            .focus
+            .as_ref()
+            .filter(|f| f.id == id)
+            .map(|f| (f.caret, f.anchor));
+        if let Some((caret, anchor)) = focus {
+            let (font, font_size) = (self.font, self.style.font_size);
+            let x = |index: usize| {
+                (text_x + font.text_width(&text[..index]) * font_size - scroll)
+                    .max(clip_min.x)
+                    .min(clip_max.x)
+            };
+            let inset = 0.15 * (max.y - min.y);
+            let (start, end) = (caret.min(anchor), caret.max(anchor));
+            let mut selection_color = self.style.accent_color;
+            selection_color.w *= 0.5;
+            if start < end {
+                self.state.shapes.shapes.push(rectangle(
+                    vec2(x(start), min.y + inset),
+                    vec2(x(end), max.y - inset),
+                    selection_color,
+                ));
+            }
+            let caret_x = x(caret);
+            // The text cursor blinks with a period of about one second
+            if (self.time / 530.0) as u64 % 2 == 0 {
+                self.state.shapes.shapes.push(rectangle(
+                    vec2(caret_x, min.y + inset),
+                    vec2(caret_x + 1.5, max.y - inset),
+                    self.style.accent_color,
+                ));
+            }
+            self.state.ime_position = Some((caret_x as f64, (max.y - inset) as f64));
+        }
+        self.state.texts.push(DrawnText {
+            text: text.clone(),
+            position: vec2(text_x - scroll, self.baseline(min, max)),
+            color: self.style.text_color,
+            clip: Some((clip_min, clip_max)),
+        });
+        self.changed |= changed;
+        changed
+    }
+
+    ///
+    /// Adds empty space with the given height in logical pixels, for example to separate groups of widgets.
+    ///
+    pub fn space(&mut self, height: f32) {
+        self.cursor.y += height;
+    }
+
+    fn next_row(&mut self) -> (Vec2, Vec2) {
+        let min = self.cursor;
+        let max = vec2(
+            self.origin.x + self.style.width - self.style.spacing,
+            min.y + 1.8 * self.style.font_size,
+        );
+        self.cursor.y = max.y + self.style.spacing;
+        (min, max)
+    }
+
+    fn hovered(&self, min: Vec2, max: Vec2) -> bool {
+        self.state.pointer.map_or(false, |p| inside(p, min, max))
+    }
+
+    // Returns whether the widget is clicked, ie. pressed and released with the mouse over the widget
+    fn click(&mut self, id: u64, min: Vec2, max: Vec2) -> bool {
+        if self.pressed.map_or(false, |p| inside(p, min, max)) {
+            self.state.active = Some(id);
+        }
+        let clicked = self.released && self.state.active == Some(id) && self.hovered(min, max);
+        self.changed |= clicked;
+        clicked
+    }
+
+    fn baseline(&self, min: Vec2, max: Vec2) -> f32 {
+        0.5 * (min.y + max.y) + 0.35 * self.style.font_size
+    }
+
+    fn text(&mut self, text: &str, x: f32, min: Vec2, max: Vec2, color: Vec4) {
+        let position = vec2(x, self.baseline(min, max));
+        self.state.texts.push(DrawnText {
+            text: text.to_string(),
+            position,
+            color,
+            clip: None,
+        });
+    }
+
+    fn focus_scroll(&self, id: u64) -> f32 {
+        self.state
+            .focus
+            .as_ref()
+            .filter(|f| f.id == id)
+            .map_or(0.0, |f| f.scroll)
+    }
+
+    // Returns the byte index of the character boundary in the text closest to the given distance from the start of the text
+    fn index_at(&self, text: &str, x: f32) -> usize {
+        let mut best = (0, x.abs());
+        for (index, _) in text.char_indices().skip(1).chain(Some((text.len(), ' '))) {
+            let distance = (self.font.text_width(&text[..index]) * self.style.font_size - x).abs();
+            if distance < best.1 {
+                best = (index, distance);
+            }
+        }
+        best.0
+    }
+}
+
+fn widget_id(kind: &str, label: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    kind.hash(&mut hasher);
+    label.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn inside(p: Vec2, min: Vec2, max: Vec2) -> bool {
+    p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y
+}
+
+fn rectangle(min: Vec2, max: Vec2, color: Vec4) -> VectorShape {
+    VectorShape {
+        path: VectorPath::polygon(&[min, vec2(max.x, min.y), max, vec2(min.x, max.y)]),
+        fill: Some(color),
+        stroke: None,
+    }
+}
+
+fn clamp_to_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn previous_boundary(text: &str, index: usize) -> usize {
+    text[..index]
+        .char_indices()
+        .last()
+        .map_or(0, |(index, _)| index)
+}
+
+fn next_boundary(text: &str, index: usize) -> usize {
+    text[index..]
+        .chars()
+        .next()
+        .map_or(index, |c| index + c.len_utf8())
+}
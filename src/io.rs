@@ -7,6 +7,15 @@ mod loader;
 #[doc(inline)]
 pub use loader::*;
 
+#[cfg(feature = "archive-io")]
+mod archive;
+#[cfg(feature = "archive-io")]
+use archive::*;
+
+mod mesh_stream;
+#[doc(inline)]
+pub use mesh_stream::*;
+
 #[cfg(not(target_arch = "wasm32"))]
 mod saver;
 #[doc(inline)]
@@ -25,6 +34,9 @@ mod threed;
 #[cfg(feature = "3d-io")]
 pub use threed::*;
 
+#[cfg(feature = "scene-cache")]
+mod scene_cache;
+
 #[cfg(feature = "obj-io")]
 mod obj;
 #[doc(inline)]
@@ -37,6 +49,9 @@ mod gltf;
 #[cfg(feature = "gltf-io")]
 pub use self::gltf::*;
 
+#[cfg(feature = "svg-io")]
+mod svg;
+
 ///
 /// Error message from the [core](crate::io) module.
 ///
@@ -54,6 +69,9 @@ pub enum IOError {
     /// A .gltf parsing error.
     #[cfg(feature = "gltf-io")]
     Gltf(::gltf::Error),
+    /// An .exr parsing error.
+    #[cfg(feature = "exr-io")]
+    Exr(exr::error::Error),
     /// An IO error.
     #[cfg(not(target_arch = "wasm32"))]
     IO(std::io::Error),
@@ -97,6 +115,13 @@ impl From<::gltf::Error> for IOError {
     }
 }
 
+#[cfg(feature = "exr-io")]
+impl From<exr::error::Error> for IOError {
+    fn from(other: exr::error::Error) -> Self {
+        IOError::Exr(other)
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 impl From<std::io::Error> for IOError {
     fn from(other: std::io::Error) -> Self {
@@ -113,8 +138,8 @@ fn image_from_bytes(bytes: &[u8]) -> Result<crate::definition::CPUTexture<u8>, I
     let format = match img {
         DynamicImage::ImageLuma8(_) => Format::R,
         DynamicImage::ImageLumaA8(_) => Format::RG,
-        DynamicImage::ImageRgb8(_) => Format::SRGB,
-        DynamicImage::ImageRgba8(_) => Format::SRGBA,
+        DynamicImage::ImageRgb8(_) => Format::RGB,
+        DynamicImage::ImageRgba8(_) => Format::RGBA,
         DynamicImage::ImageBgr8(_) => unimplemented!(),
         DynamicImage::ImageBgra8(_) => unimplemented!(),
         DynamicImage::ImageLuma16(_) => unimplemented!(),
@@ -128,6 +153,90 @@ fn image_from_bytes(bytes: &[u8]) -> Result<crate::definition::CPUTexture<u8>, I
         width: img.width(),
         height: img.height(),
         format,
+        is_srgb: true,
+        ..Default::default()
+    })
+}
+
+#[cfg(feature = "image-io")]
+fn image_u16_from_bytes(bytes: &[u8]) -> Result<crate::definition::CPUTexture<u16>, IOError> {
+    use crate::definition::*;
+    use image::DynamicImage;
+    use image::GenericImageView;
+    let img = image::load_from_memory(bytes)?;
+    let (width, height) = (img.width(), img.height());
+    let (data, format) = match img {
+        DynamicImage::ImageLuma16(img) => (img.into_raw(), Format::R),
+        DynamicImage::ImageLumaA16(img) => (img.into_raw(), Format::RG),
+        DynamicImage::ImageRgb16(img) => (img.into_raw(), Format::RGB),
+        img => (img.to_rgba16().into_raw(), Format::RGBA),
+    };
+
+    Ok(CPUTexture {
+        data,
+        width,
+        height,
+        format,
+        ..Default::default()
+    })
+}
+
+#[cfg(feature = "image-io")]
+fn hdr_image_from_bytes(bytes: &[u8]) -> Result<crate::definition::CPUTexture<f32>, IOError> {
+    #[cfg(feature = "exr-io")]
+    {
+        if bytes.starts_with(&[0x76, 0x2f, 0x31, 0x01]) {
+            return exr_image_from_bytes(bytes);
+        }
+    }
+    use crate::definition::*;
+    let decoder = image::codecs::hdr::HdrDecoder::new(bytes)?;
+    let metadata = decoder.metadata();
+    let mut data = Vec::with_capacity(metadata.width as usize * metadata.height as usize * 3);
+    for pixel in decoder.read_image_hdr()? {
+        data.extend_from_slice(&pixel.0);
+    }
+
+    Ok(CPUTexture {
+        data,
+        width: metadata.width,
+        height: metadata.height,
+        format: Format::RGB,
+        ..Default::default()
+    })
+}
+
+#[cfg(feature = "exr-io")]
+fn exr_image_from_bytes(bytes: &[u8]) -> Result<crate::definition::CPUTexture<f32>, IOError> {
+    use crate::definition::*;
+    use exr::prelude::*;
+    let image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .rgba_channels(
+            |resolution, _| {
+                (
+                    resolution.width(),
+                    vec![0.0; resolution.width() * resolution.height() * 4],
+                )
+            },
+            |(width, data): &mut (usize, Vec<f32>),
+             position,
+             (r, g, b, a): (f32, f32, f32, f32)| {
+                let index = 4 * (position.y() * *width + position.x());
+                data[index..index + 4].copy_from_slice(&[r, g, b, a]);
+            },
+        )
+        .first_valid_layer()
+        .all_attributes()
+        .from_buffered(std::io::Cursor::new(bytes))?;
+    let size = image.layer_data.size;
+
+    Ok(CPUTexture {
+        data: image.layer_data.channel_data.pixels.1,
+        width: size.width() as u32,
+        height: size.height() as u32,
+        format: Format::RGBA,
         ..Default::default()
     })
 }
@@ -0,0 +1,413 @@
+use std::io::{Error, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+///
+/// The maximum number of bytes that are extracted from a single archive. This protects against archives that are small,
+/// but decompress to an amount of data that cannot fit in memory.
+///
+const MAX_EXTRACTED_SIZE: usize = 1 << 30;
+
+///
+/// Returns whether the resource at the given path is an archive which is extracted by the [Loader](crate::Loader).
+///
+pub(crate) fn is_archive(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    name.ends_with(".zip")
+        || name.ends_with(".tar")
+        || name.ends_with(".tar.gz")
+        || name.ends_with(".tgz")
+        || name.ends_with(".tar.zst")
+        || name.ends_with(".tzst")
+}
+
+///
+/// Extracts the files in the given .zip, .tar, .tar.gz or .tar.zst archive and returns them together with their internal paths.
+/// Returns an error if the archive is corrupt or if more than [MAX_EXTRACTED_SIZE] bytes would be decompressed.
+///
+pub(crate) fn extract_archive(path: &Path, bytes: &[u8]) -> Result<Vec<(PathBuf, Vec<u8>)>, Error> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if name.ends_with(".zip") {
+        extract_zip(bytes)
+    } else if name.ends_with(".tar") {
+        extract_tar(bytes)
+    } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+        extract_tar(&decompress_zstd(bytes)?)
+    } else {
+        extract_tar(&decompress_gzip(bytes)?)
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn add(a: usize, b: usize) -> Result<usize, Error> {
+    a.checked_add(b)
+        .ok_or_else(|| invalid("Offset out of range in archive"))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<usize, Error> {
+    bytes
+        .get(offset..add(offset, 2)?)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+        .ok_or_else(|| invalid("Unexpected end of zip archive"))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<usize, Error> {
+    bytes
+        .get(offset..add(offset, 4)?)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or_else(|| invalid("Unexpected end of zip archive"))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<usize, Error> {
+    let low = read_u32(bytes, offset)? as u64;
+    let high = read_u32(bytes, add(offset, 4)?)? as u64;
+    let value = low | (high << 32);
+    if value > usize::MAX as u64 {
+        Err(invalid("Zip archive too large"))?;
+    }
+    Ok(value as usize)
+}
+
+// The value of the 16 and 32 bit fields that are stored in the ZIP64 extensions instead
+const ZIP64_U16: usize = 0xFFFF;
+const ZIP64_U32: usize = 0xFFFFFFFF;
+
+fn extract_zip(bytes: &[u8]) -> Result<Vec<(PathBuf, Vec<u8>)>, Error> {
+    // The end of central directory record is at the end of the file, possibly followed by a comment
+    const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+    if bytes.len() < END_OF_CENTRAL_DIRECTORY_SIZE {
+        Err(invalid("Not a zip archive"))?;
+    }
+    let last = bytes.len() - END_OF_CENTRAL_DIRECTORY_SIZE;
+    let end = (last.saturating_sub(u16::MAX as usize)..=last)
+        .rev()
+        .find(|i| bytes[*i..*i + 4] == [0x50, 0x4b, 0x05, 0x06])
+        .ok_or_else(|| invalid("Not a zip archive"))?;
+    let mut entry_count = read_u16(bytes, end + 10)?;
+    let mut offset = read_u32(bytes, end + 16)?;
+    // Archives with too many or too large files have a ZIP64 end of central directory record, which is found using the locator just before the end record
+    if (entry_count == ZIP64_U16 || offset == ZIP64_U32)
+        && end >= 20
+        && read_u32(bytes, end - 20)? == 0x07064b50
+    {
+        let zip64_end = read_u64(bytes, end - 20 + 8)?;
+        if read_u32(bytes, zip64_end)? != 0x06064b50 {
+            Err(invalid("Corrupt zip64 end of central directory"))?;
+        }
+        entry_count = read_u64(bytes, add(zip64_end, 32)?)?;
+        offset = read_u64(bytes, add(zip64_end, 48)?)?;
+    }
+
+    let mut files = Vec::new();
+    let mut extracted_size: usize = 0;
+    for _ in 0..entry_count {
+        if read_u32(bytes, offset)? != 0x02014b50 {
+            Err(invalid("Corrupt zip central directory"))?;
+        }
+        let method = read_u16(bytes, add(offset, 10)?)?;
+        let mut compressed_size = read_u32(bytes, add(offset, 20)?)?;
+        let mut uncompressed_size = read_u32(bytes, add(offset, 24)?)?;
+        let name_length = read_u16(bytes, add(offset, 28)?)?;
+        let extra_length = read_u16(bytes, add(offset, 30)?)?;
+        let comment_length = read_u16(bytes, add(offset, 32)?)?;
+        let mut local_offset = read_u32(bytes, add(offset, 42)?)?;
+        let name_offset = add(offset, 46)?;
+        let name = String::from_utf8_lossy(
+            bytes
+                .get(name_offset..add(name_offset, name_length)?)
+                .ok_or_else(|| invalid("Unexpected end of zip archive"))?,
+        )
+        .to_string();
+
+        // The sizes and offset that do not fit in 32 bits are stored in the ZIP64 extra field,
+        // which only contains the values that are replaced and in this order
+        if uncompressed_size == ZIP64_U32
+            || compressed_size == ZIP64_U32
+            || local_offset == ZIP64_U32
+        {
+            let mut extra = add(name_offset, name_length)?;
+            let extra_end = add(extra, extra_length)?;
+            let mut found = false;
+            while add(extra, 4)? <= extra_end {
+                let id = read_u16(bytes, extra)?;
+                let size = read_u16(bytes, add(extra, 2)?)?;
+                if id == 0x0001 {
+                    let mut field = add(extra, 4)?;
+                    if uncompressed_size == ZIP64_U32 {
+                        uncompressed_size = read_u64(bytes, field)?;
+                        field = add(field, 8)?;
+                    }
+                    if compressed_size == ZIP64_U32 {
+                        compressed_size = read_u64(bytes, field)?;
+                        field = add(field, 8)?;
+                    }
+                    if local_offset == ZIP64_U32 {
+                        local_offset = read_u64(bytes, field)?;
+                    }
+                    found = true;
+                    break;
+                }
+                extra = add(extra, add(4, size)?)?;
+            }
+            if !found {
+                Err(invalid(&format!(
+                    "Missing zip64 extra field for {} in zip archive",
+                    name
+                )))?;
+            }
+        }
+        offset = add(
+            name_offset,
+            add(name_length, add(extra_length, comment_length)?)?,
+        )?;
+        if name.ends_with('/') {
+            continue;
+        }
+
+        extracted_size = add(extracted_size, uncompressed_size)?;
+        if extracted_size > MAX_EXTRACTED_SIZE {
+            Err(invalid("Zip archive too large to extract"))?;
+        }
+        if read_u32(bytes, local_offset)? != 0x04034b50 {
+            Err(invalid("Corrupt zip file header"))?;
+        }
+        let data_offset = add(
+            add(local_offset, 30)?,
+            add(
+                read_u16(bytes, add(local_offset, 26)?)?,
+                read_u16(bytes, add(local_offset, 28)?)?,
+            )?,
+        )?;
+        let data = bytes
+            .get(data_offset..add(data_offset, compressed_size)?)
+            .ok_or_else(|| invalid("Unexpected end of zip archive"))?;
+        let data = match method {
+            0 if data.len() == uncompressed_size => data.to_vec(),
+            8 => inflate(data, uncompressed_size, uncompressed_size)
+                .filter(|data| data.len() == uncompressed_size)
+                .ok_or_else(|| invalid(&format!("Could not decompress {} in zip archive", name)))?,
+            0 => Err(invalid(&format!("Wrong size of {} in zip archive", name)))?,
+            _ => Err(invalid(&format!(
+                "Unsupported compression method {} for {} in zip archive",
+                method, name
+            )))?,
+        };
+        files.push((PathBuf::from(name), data));
+    }
+    Ok(files)
+}
+
+fn extract_tar(bytes: &[u8]) -> Result<Vec<(PathBuf, Vec<u8>)>, Error> {
+    const BLOCK_SIZE: usize = 512;
+    let field = |header: &[u8], start: usize, end: usize| {
+        let field = &header[start..end];
+        let length = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+        String::from_utf8_lossy(&field[..length]).trim().to_string()
+    };
+    // Sizes are octal numbers, except large sizes which are big-endian binary numbers marked by the highest bit
+    let size_field = |header: &[u8]| -> Result<usize, Error> {
+        let field_bytes = &header[124..136];
+        if field_bytes[0] & 0x80 != 0 {
+            field_bytes[1..].iter().try_fold(0usize, |size, b| {
+                size.checked_mul(256)
+                    .and_then(|size| size.checked_add(*b as usize))
+                    .ok_or_else(|| invalid("Tar archive too large"))
+            })
+        } else {
+            usize::from_str_radix(&field(header, 124, 136), 8)
+                .map_err(|_| invalid("Corrupt tar header"))
+        }
+    };
+    let mut files = Vec::new();
+    let mut offset: usize = 0;
+    // The path and size of the next entry given by a preceding GNU long name or pax extended header
+    let mut next_name: Option<String> = None;
+    let mut next_size: Option<usize> = None;
+    while add(offset, BLOCK_SIZE)? <= bytes.len() {
+        let header = &bytes[offset..offset + BLOCK_SIZE];
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+        let mut name = field(header, 0, 100);
+        let prefix = field(header, 345, 500);
+        if header[257..262] == *b"ustar" && !prefix.is_empty() {
+            name = format!("{}/{}", prefix, name);
+        }
+        let type_flag = header[156];
+        let size = if type_flag == b'L' || type_flag == b'x' {
+            size_field(header)?
+        } else {
+            next_size
+                .take()
+                .map(Ok)
+                .unwrap_or_else(|| size_field(header))?
+        };
+        offset += BLOCK_SIZE;
+        let data = bytes
+            .get(offset..add(offset, size)?)
+            .ok_or_else(|| invalid("Unexpected end of tar archive"))?;
+        match type_flag {
+            b'L' => {
+                let length = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+                next_name = Some(String::from_utf8_lossy(&data[..length]).to_string());
+            }
+            b'x' => {
+                for (key, value) in pax_records(data)? {
+                    match key.as_str() {
+                        "path" => next_name = Some(value),
+                        "size" => {
+                            next_size = Some(
+                                value
+                                    .parse()
+                                    .map_err(|_| invalid("Corrupt pax size in tar archive"))?,
+                            )
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // Global pax headers and GNU long link names do not affect the extracted files
+            b'g' | b'K' => {}
+            _ => {
+                let name = next_name.take().unwrap_or(name);
+                next_size = None;
+                if type_flag == b'0' || type_flag == 0 || type_flag == b'7' {
+                    files.push((PathBuf::from(name), data.to_vec()));
+                }
+            }
+        }
+        offset = add(offset, add(size, BLOCK_SIZE - 1)? / BLOCK_SIZE * BLOCK_SIZE)?;
+    }
+    if next_name.is_some() || next_size.is_some() {
+        Err(invalid(
+            "Missing entry after extended header in tar archive",
+        ))?;
+    }
+    Ok(files)
+}
+
+///
+/// Parses the records of a pax extended header, each of the form `"<length> <key>=<value>\n"`, where the length is the length of the whole record.
+///
+fn pax_records(data: &[u8]) -> Result<Vec<(String, String)>, Error> {
+    let corrupt = || invalid("Corrupt pax header in tar archive");
+    let mut records = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() && rest[0] != 0 {
+        let space = rest.iter().position(|b| *b == b' ').ok_or_else(corrupt)?;
+        let length: usize = std::str::from_utf8(&rest[..space])
+            .ok()
+            .and_then(|length| length.parse().ok())
+            .ok_or_else(corrupt)?;
+        if length <= space + 1 || length > rest.len() || rest[length - 1] != b'\n' {
+            Err(corrupt())?;
+        }
+        let record = String::from_utf8_lossy(&rest[space + 1..length - 1]).to_string();
+        let equals = record.find('=').ok_or_else(corrupt)?;
+        records.push((
+            record[..equals].to_string(),
+            record[equals + 1..].to_string(),
+        ));
+        rest = &rest[length..];
+    }
+    Ok(records)
+}
+
+fn decompress_gzip(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    const FLAG_EXTRA: u8 = 4;
+    const FLAG_NAME: u8 = 8;
+    const FLAG_COMMENT: u8 = 16;
+    const FLAG_HEADER_CRC: u8 = 2;
+    if bytes.len() < 18 || bytes[0..3] != [0x1f, 0x8b, 8] {
+        Err(invalid("Not a gzip file"))?;
+    }
+    let flags = bytes[3];
+    let mut offset: usize = 10;
+    if flags & FLAG_EXTRA != 0 {
+        offset = add(offset, add(2, read_u16(bytes, offset)?)?)?;
+    }
+    for flag in [FLAG_NAME, FLAG_COMMENT].iter() {
+        if flags & flag != 0 {
+            offset = add(
+                offset,
+                bytes
+                    .get(offset..)
+                    .and_then(|rest| rest.iter().position(|b| *b == 0))
+                    .ok_or_else(|| invalid("Corrupt gzip header"))?
+                    + 1,
+            )?;
+        }
+    }
+    if flags & FLAG_HEADER_CRC != 0 {
+        offset = add(offset, 2)?;
+    }
+    let data = bytes
+        .get(offset..)
+        .ok_or_else(|| invalid("Corrupt gzip header"))?;
+    inflate(data, data.len().saturating_mul(4), MAX_EXTRACTED_SIZE)
+        .ok_or_else(|| invalid("Could not decompress gzip file"))
+}
+
+///
+/// Decompresses the raw deflate data into a buffer which starts with the given size and grows up to the given maximum size.
+/// Returns `None` if the data is corrupt or decompresses to more than the maximum size.
+///
+fn inflate(data: &[u8], initial_size: usize, max_size: usize) -> Option<Vec<u8>> {
+    use miniz_oxide::inflate::core::{decompress, inflate_flags, DecompressorOxide};
+    use miniz_oxide::inflate::TINFLStatus;
+    let mut output = vec![0; initial_size.min(max_size)];
+    let mut decompressor = Box::<DecompressorOxide>::default();
+    let mut input_position = 0;
+    let mut output_position = 0;
+    loop {
+        let (status, input_read, output_written) = decompress(
+            &mut decompressor,
+            &data[input_position..],
+            &mut output,
+            output_position,
+            inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+        );
+        input_position += input_read;
+        output_position += output_written;
+        match status {
+            TINFLStatus::Done => {
+                output.truncate(output_position);
+                return Some(output);
+            }
+            TINFLStatus::HasMoreOutput if output.len() < max_size => {
+                let size = output.len().saturating_mul(2).max(1024).min(max_size);
+                output.resize(size, 0);
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn decompress_zstd(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut input = bytes;
+    let mut output = Vec::new();
+    // A zstd file can consist of several frames which are decompressed one after the other
+    while !input.is_empty() {
+        let remaining = MAX_EXTRACTED_SIZE - output.len();
+        let decoder = ruzstd::StreamingDecoder::new(&mut input)
+            .map_err(|e| invalid(&format!("Could not decompress zstd file: {}", e)))?;
+        decoder
+            .take(remaining as u64 + 1)
+            .read_to_end(&mut output)
+            .map_err(|e| invalid(&format!("Could not decompress zstd file: {}", e)))?;
+        if output.len() > MAX_EXTRACTED_SIZE {
+            Err(invalid("Zstd file too large to decompress"))?;
+        }
+    }
+    Ok(output)
+}
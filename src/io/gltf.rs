@@ -1,6 +1,7 @@
 use crate::definition::*;
 use crate::io::*;
 use ::gltf::Gltf;
+use std::collections::HashMap;
 use std::path::Path;
 
 impl<'a> Loaded<'a> {
@@ -10,6 +11,7 @@ impl<'a> Loaded<'a> {
     ) -> Result<(Vec<CPUMesh>, Vec<CPUMaterial>), IOError> {
         let mut cpu_meshes = Vec::new();
         let mut cpu_materials = Vec::new();
+        let mut images = HashMap::new();
 
         let bytes = self.bytes(path.as_ref())?;
         let gltf = Gltf::from_slice(bytes)?;
@@ -24,6 +26,7 @@ impl<'a> Loaded<'a> {
                     &buffers,
                     &mut cpu_meshes,
                     &mut cpu_materials,
+                    &mut images,
                 )?;
             }
         }
@@ -38,6 +41,7 @@ fn parse_tree<'a>(
     buffers: &[::gltf::buffer::Data],
     cpu_meshes: &mut Vec<CPUMesh>,
     cpu_materials: &mut Vec<CPUMaterial>,
+    images: &mut HashMap<usize, CPUTexture<u8>>,
 ) -> Result<(), IOError> {
     if let Some(mesh) = node.mesh() {
         let name: String = mesh
@@ -107,13 +111,15 @@ fn parse_tree<'a>(
                     let pbr = material.pbr_metallic_roughness();
                     let color = pbr.base_color_factor();
                     let color_texture = if let Some(info) = pbr.base_color_texture() {
-                        Some(parse_texture(loaded, path, buffers, info)?)
+                        Some(parse_texture(loaded, path, buffers, images, info)?)
                     } else {
                         None
                     };
                     let metallic_roughness_texture =
                         if let Some(info) = pbr.metallic_roughness_texture() {
-                            Some(parse_texture(loaded, path, buffers, info)?)
+                            let mut texture = parse_texture(loaded, path, buffers, images, info)?;
+                            texture.is_srgb = false;
+                            Some(texture)
                         } else {
                             None
                         };
@@ -127,6 +133,14 @@ fn parse_tree<'a>(
                         diffuse_intensity: Some(1.0),
                         specular_intensity: Some(pbr.metallic_factor()),
                         specular_power: Some(pbr.roughness_factor()),
+                        double_sided: Some(material.double_sided()),
+                        alpha_mode: Some(match material.alpha_mode() {
+                            ::gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
+                            ::gltf::material::AlphaMode::Mask => AlphaMode::Mask {
+                                cutoff: material.alpha_cutoff(),
+                            },
+                            ::gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+                        }),
                     });
                 }
 
@@ -163,7 +177,15 @@ fn parse_tree<'a>(
     }
 
     for child in node.children() {
-        parse_tree(&child, loaded, path, buffers, cpu_meshes, cpu_materials)?;
+        parse_tree(
+            &child,
+            loaded,
+            path,
+            buffers,
+            cpu_meshes,
+            cpu_materials,
+            images,
+        )?;
     }
     Ok(())
 }
@@ -172,10 +194,15 @@ fn parse_texture<'a>(
     loaded: &'a Loaded,
     path: &Path,
     buffers: &[::gltf::buffer::Data],
+    images: &mut HashMap<usize, CPUTexture<u8>>,
     info: ::gltf::texture::Info,
 ) -> Result<CPUTexture<u8>, IOError> {
     let gltf_texture = info.texture();
     let gltf_image = gltf_texture.source();
+    // The same image is often referenced by several textures and materials, so only decode it once
+    if let Some(tex) = images.get(&gltf_image.index()) {
+        return Ok(tex.clone());
+    }
     let gltf_source = gltf_image.source();
     let tex = match gltf_source {
         ::gltf::image::Source::Uri { uri, .. } => loaded.image(path.join(Path::new(uri)))?,
@@ -193,5 +220,6 @@ fn parse_texture<'a>(
         }
     };
     // TODO: Parse sampling parameters
+    images.insert(gltf_image.index(), tex.clone());
     Ok(tex)
 }
@@ -42,6 +42,33 @@ impl<'a> Loaded<'a> {
     }
 }
 
+///
+/// Options used when [loading](crate::Loader::load_with_options) resources.
+///
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    /// Additional HTTP headers sent with each request when loading a resource over HTTP, for example an authorization header.
+    /// On desktop, they are not sent when the request is redirected to another origin. Ignored when loading from disk.
+    pub headers: Vec<(String, String)>,
+}
+
+impl LoadOptions {
+    ///
+    /// Returns these options with the given HTTP header added.
+    ///
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    ///
+    /// Returns these options with an authorization header with the given bearer token added.
+    ///
+    pub fn with_bearer_token(self, token: &str) -> Self {
+        self.with_header("Authorization", &format!("Bearer {}", token))
+    }
+}
+
 ///
 /// Functionality for loading any type of resource runtime on both desktop and web.
 ///
@@ -73,13 +100,38 @@ impl Loader {
         G: 'static + Fn(f32),
         F: 'static + FnOnce(&mut Loaded),
         P: AsRef<Path>,
+    {
+        Self::load_with_options(paths, &LoadOptions::default(), progress_callback, on_done);
+    }
+
+    ///
+    /// Loads all of the resources in the given paths using the given options, for example to send authentication headers when loading from a private server,
+    /// then calls `on_done` with all of the [loaded resources](crate::Loaded).
+    /// Will continuously call `progress_callback` while loading.
+    ///
+    /// If the `archive-io` feature is enabled, the files in .zip (including ZIP64), .tar, .tar.gz and .tar.zst archives are extracted
+    /// and can be accessed in the [loaded resources](crate::Loaded) using their path inside the archive.
+    /// If an archive is corrupt or extracts to more than 1 GiB, the archive itself is loaded with an error instead.
+    ///
+    /// On desktop, paths starting with `http://` or `https://` are loaded over HTTP(S) if the `http-io` feature is enabled,
+    /// all other paths are loaded from disk. The additional [headers](LoadOptions::headers) are not sent when a request is redirected to another origin.
+    ///
+    pub fn load_with_options<F, G, P>(
+        paths: &[P],
+        options: &LoadOptions,
+        progress_callback: G,
+        on_done: F,
+    ) where
+        G: 'static + Fn(f32),
+        F: 'static + FnOnce(&mut Loaded),
+        P: AsRef<Path>,
     {
         let loads = Rc::new(RefCell::new(HashMap::new()));
         for path in paths {
             loads
                 .borrow_mut()
                 .insert(path.as_ref().to_path_buf(), Ok(Vec::new()));
-            Self::load_file(path, loads.clone());
+            Self::load_file(path, options, loads.clone());
         }
         info!("Loading started...");
         Self::wait_local(loads.clone(), progress_callback, on_done);
@@ -110,6 +162,8 @@ impl Loader {
                 Self::wait_local(loads, progress_callback, on_done);
             } else {
                 info!("Loading done.");
+                #[cfg(feature = "archive-io")]
+                Self::extract_archives(&mut loads.borrow_mut());
                 on_done(&mut Loaded {
                     loaded: &mut loads.borrow_mut(),
                 });
@@ -117,6 +171,29 @@ impl Loader {
         });
     }
 
+    #[cfg(feature = "archive-io")]
+    fn extract_archives(loaded: &mut HashMap<PathBuf, Result<Vec<u8>, std::io::Error>>) {
+        let archive_paths: Vec<PathBuf> = loaded
+            .keys()
+            .filter(|path| is_archive(path))
+            .cloned()
+            .collect();
+        for path in archive_paths {
+            if let Some(Ok(bytes)) = loaded.get(&path) {
+                match extract_archive(&path, bytes) {
+                    Ok(files) => {
+                        for (file_path, data) in files {
+                            loaded.insert(file_path, Ok(data));
+                        }
+                    }
+                    Err(e) => {
+                        loaded.insert(path, Err(e));
+                    }
+                }
+            }
+        }
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     fn sleep<F>(millis: u64, fun: F)
     where
@@ -137,35 +214,33 @@ impl Loader {
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    fn load_file<P: AsRef<Path>>(path: P, loads: RefLoaded) {
-        let file = std::fs::File::open(path.as_ref());
-        match file {
-            Ok(mut f) => {
-                use std::io::prelude::*;
-                let mut bytes = Vec::new();
-                let result = f.read_to_end(&mut bytes).and(Ok(bytes));
-                loads
-                    .borrow_mut()
-                    .insert(path.as_ref().to_path_buf(), result);
-            }
-            Err(e) => {
-                loads
-                    .borrow_mut()
-                    .insert(path.as_ref().to_path_buf(), Err(e));
+    fn load_file<P: AsRef<Path>>(path: P, options: &LoadOptions, loads: RefLoaded) {
+        let result = match path.as_ref().to_str() {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                http_get(url, &options.headers)
             }
-        }
+            _ => std::fs::read(path.as_ref()),
+        };
+        loads
+            .borrow_mut()
+            .insert(path.as_ref().to_path_buf(), result);
     }
 
     #[cfg(target_arch = "wasm32")]
-    fn load_file<P: AsRef<Path>>(path: P, loads: RefLoaded) {
+    fn load_file<P: AsRef<Path>>(path: P, options: &LoadOptions, loads: RefLoaded) {
         wasm_bindgen_futures::spawn_local(Self::load_file_async(
             path.as_ref().to_path_buf(),
+            options.headers.clone(),
             loads,
         ));
     }
 
     #[cfg(target_arch = "wasm32")]
-    async fn load_file_async<P: AsRef<Path>>(path: P, loads: RefLoaded) {
+    async fn load_file_async<P: AsRef<Path>>(
+        path: P,
+        headers: Vec<(String, String)>,
+        loads: RefLoaded,
+    ) {
         use wasm_bindgen::prelude::*;
         use wasm_bindgen::JsCast;
         use wasm_bindgen_futures::JsFuture;
@@ -181,6 +256,9 @@ impl Loader {
             .headers()
             .set("Accept", "application/octet-stream")
             .unwrap();
+        for (name, value) in headers.iter() {
+            request.headers().set(name, value).unwrap();
+        }
 
         let window = web_sys::window().unwrap();
         let resp_value = JsFuture::from(window.fetch_with_request(&request))
@@ -196,3 +274,72 @@ impl Loader {
         );
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+fn http_get(url: &str, headers: &[(String, String)]) -> Result<Vec<u8>, std::io::Error> {
+    use std::io::Read;
+    let mut data = Vec::new();
+    http_reader(url, headers)?.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+#[cfg(all(feature = "http-io", not(target_arch = "wasm32")))]
+pub(super) fn http_reader(
+    url: &str,
+    headers: &[(String, String)],
+) -> Result<Box<dyn std::io::Read + Send>, std::io::Error> {
+    use std::io::{Error, ErrorKind};
+    use std::time::Duration;
+    let invalid_url = |e: url::ParseError| Error::new(ErrorKind::InvalidInput, e.to_string());
+    // Redirects are followed manually, since the additional headers must not be sent to another origin
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(10))
+        .timeout_read(Duration::from_secs(30))
+        .redirects(0)
+        .build();
+    let origin = url::Url::parse(url).map_err(invalid_url)?.origin();
+    let mut url = url::Url::parse(url).map_err(invalid_url)?;
+    for _ in 0..5 {
+        let mut request = agent
+            .request_url("GET", &url)
+            .set("Accept", "application/octet-stream");
+        if url.origin() == origin {
+            for (name, value) in headers.iter() {
+                request = request.set(name, value);
+            }
+        }
+        let response = request.call().map_err(|e| match e {
+            ureq::Error::Status(status, _) => Error::new(
+                ErrorKind::Other,
+                format!("Request to {} failed with HTTP status {}", url, status),
+            ),
+            ureq::Error::Transport(e) => Error::new(ErrorKind::Other, e.to_string()),
+        })?;
+        match (response.status(), response.header("location")) {
+            (300..=399, Some(location)) => {
+                url = url.join(location).map_err(invalid_url)?;
+            }
+            _ => {
+                return Ok(Box::new(response.into_reader()));
+            }
+        }
+    }
+    Err(Error::new(
+        ErrorKind::Other,
+        format!("Too many redirects when loading {}", url),
+    ))
+}
+
+#[cfg(all(not(feature = "http-io"), not(target_arch = "wasm32")))]
+pub(super) fn http_reader(
+    url: &str,
+    _headers: &[(String, String)],
+) -> Result<Box<dyn std::io::Read + Send>, std::io::Error> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!(
+            "Loading over HTTP requires the http-io feature, could not load {}",
+            url
+        ),
+    ))
+}
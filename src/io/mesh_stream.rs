@@ -0,0 +1,487 @@
+use crate::definition::*;
+use crate::io::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+
+///
+/// A stream of triangle meshes which arrives in chunks while loading, which makes it possible to upload and show a very large model
+/// progressively instead of waiting until everything is loaded, see for example [StreamedMesh](crate::StreamedMesh).
+///
+/// A .obj file can be parsed while it is read from disk or downloaded using [MeshStream::load_obj], in which case the first chunks
+/// arrive long before the whole file is loaded. On desktop, the file is read and parsed on a background thread
+/// and on web, the response is read using the streams of the fetch API and parsed as the data arrives.
+/// Any other format can be streamed using [MeshStream::load], in which case the resources are loaded completely before parsing
+/// and the chunks of each mesh arrive as soon as that mesh is parsed.
+///
+pub struct MeshStream {
+    receiver: Receiver<Result<CPUMesh, IOError>>,
+    is_done: bool,
+    chunk_count: usize,
+}
+
+impl MeshStream {
+    ///
+    /// Starts loading the resources in the given paths and calls `parse` with the [loaded resources](crate::Loaded) when loaded.
+    /// `parse` should call the given function with each mesh as soon as it is parsed, which splits the mesh into chunks of at most
+    /// the given number of triangles and sends them to the stream right away. The chunks can be received using [MeshStream::next_chunk].
+    /// On desktop, the resources are loaded and parsed on a background thread.
+    ///
+    pub fn load<P, F>(paths: &[P], max_triangles_per_chunk: usize, parse: F) -> Self
+    where
+        P: AsRef<Path>,
+        F: 'static + Send + FnOnce(&mut Loaded, &mut dyn FnMut(CPUMesh)) -> Result<(), IOError>,
+    {
+        let (sender, receiver) = channel();
+        let paths: Vec<PathBuf> = paths.iter().map(|p| p.as_ref().to_path_buf()).collect();
+        let load = move || {
+            Loader::load(&paths, move |loaded| {
+                let mut send = |cpu_mesh: CPUMesh| {
+                    for chunk in split_into_chunks(cpu_mesh, max_triangles_per_chunk.max(1)) {
+                        // The stream might be dropped, in which case the chunks are discarded
+                        sender.send(Ok(chunk)).ok();
+                    }
+                };
+                if let Err(e) = parse(loaded, &mut send) {
+                    sender.send(Err(e)).ok();
+                }
+            })
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(load);
+        #[cfg(target_arch = "wasm32")]
+        load();
+        Self::new(receiver)
+    }
+
+    ///
+    /// Starts loading the .obj file at the given path and parses it while it arrives.
+    /// The triangles are sent to the stream in chunks of at most the given number of triangles, which can be received using [MeshStream::next_chunk].
+    /// A new chunk is also started for each object and each material in the file.
+    /// Only the positions, normals and uv coordinates of the faces are parsed, materials must be loaded separately, for example using [Loaded::obj](crate::Loaded::obj).
+    ///
+    /// On desktop, paths starting with `http://` or `https://` are loaded over HTTP(S) if the `http-io` feature is enabled,
+    /// all other paths are read from disk.
+    ///
+    pub fn load_obj<P: AsRef<Path>>(path: P, max_triangles_per_chunk: usize) -> Self {
+        let (sender, receiver) = channel();
+        let path = path.as_ref().to_path_buf();
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(move || {
+            if let Err(e) = Self::read_obj(&path, &sender, max_triangles_per_chunk) {
+                sender.send(Err(e)).ok();
+            }
+        });
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(e) = Self::fetch_obj(&path, &sender, max_triangles_per_chunk).await {
+                sender.send(Err(e)).ok();
+            }
+        });
+        Self::new(receiver)
+    }
+
+    fn new(receiver: Receiver<Result<CPUMesh, IOError>>) -> Self {
+        Self {
+            receiver,
+            is_done: false,
+            chunk_count: 0,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_obj(
+        path: &Path,
+        sender: &Sender<Result<CPUMesh, IOError>>,
+        max_triangles_per_chunk: usize,
+    ) -> Result<(), IOError> {
+        use std::io::{ErrorKind, Read};
+        let mut reader = match path.to_str() {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                super::loader::http_reader(url, &[])?
+            }
+            _ => Box::new(std::fs::File::open(path)?),
+        };
+        let mut parser = ObjParser::new(path, max_triangles_per_chunk);
+        let mut buffer = vec![0; 1 << 16];
+        loop {
+            let count = match reader.read(&mut buffer) {
+                Ok(count) => count,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => Err(e)?,
+            };
+            let chunks = if count == 0 {
+                parser.finish()?
+            } else {
+                parser.push(&buffer[..count])?
+            };
+            for chunk in chunks {
+                if sender.send(Ok(chunk)).is_err() {
+                    // The stream is dropped so there is no need to continue
+                    return Ok(());
+                }
+            }
+            if count == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn fetch_obj(
+        path: &Path,
+        sender: &Sender<Result<CPUMesh, IOError>>,
+        max_triangles_per_chunk: usize,
+    ) -> Result<(), IOError> {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+        use web_sys::{ReadableStreamDefaultReader, Request, RequestInit, RequestMode, Response};
+
+        let url = path.to_str().unwrap();
+        let failed = |message: &str| IOError::FailedToLoad {
+            message: format!("Could not load {}: {}", url, message),
+        };
+        let mut opts = RequestInit::new();
+        opts.method("GET");
+        opts.mode(RequestMode::Cors);
+        let request =
+            Request::new_with_str_and_init(url, &opts).map_err(|_| failed("Invalid request"))?;
+        let window = web_sys::window().unwrap();
+        let response: Response = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(|_| failed("The request failed"))?
+            .dyn_into()
+            .unwrap();
+        if !response.ok() {
+            return Err(failed(&format!("HTTP status {}", response.status())));
+        }
+        let reader: ReadableStreamDefaultReader = response
+            .body()
+            .ok_or_else(|| failed("The response has no body"))?
+            .get_reader()
+            .unchecked_into();
+
+        let mut parser = ObjParser::new(path, max_triangles_per_chunk);
+        loop {
+            let result = JsFuture::from(reader.read())
+                .await
+                .map_err(|_| failed("Reading the response failed"))?;
+            let done = js_sys::Reflect::get(&result, &"done".into())
+                .map(|done| done.is_truthy())
+                .unwrap_or(true);
+            let chunks = if done {
+                parser.finish()?
+            } else {
+                let value = js_sys::Reflect::get(&result, &"value".into())
+                    .map_err(|_| failed("Reading the response failed"))?;
+                parser.push(&js_sys::Uint8Array::new(&value).to_vec())?
+            };
+            for chunk in chunks {
+                if sender.send(Ok(chunk)).is_err() {
+                    // The stream is dropped so there is no need to continue
+                    return Ok(());
+                }
+            }
+            if done {
+                return Ok(());
+            }
+        }
+    }
+
+    ///
+    /// Returns the next chunk if one has arrived, otherwise `None`. Never blocks.
+    /// Returns an error if the loading or parsing failed.
+    ///
+    pub fn next_chunk(&mut self) -> Option<Result<CPUMesh, IOError>> {
+        match self.receiver.try_recv() {
+            Ok(chunk) => {
+                self.chunk_count += 1;
+                Some(chunk)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.is_done = true;
+                None
+            }
+        }
+    }
+
+    ///
+    /// Returns whether all of the chunks have been received.
+    ///
+    pub fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    ///
+    /// Returns the number of chunks received so far.
+    ///
+    pub fn chunk_count(&self) -> usize {
+        self.chunk_count
+    }
+}
+
+fn split_into_chunks(cpu_mesh: CPUMesh, max_triangles: usize) -> Vec<CPUMesh> {
+    let indices = cpu_mesh.indices.as_ref().map(|indices| indices.into_u32());
+    let triangle_count = indices
+        .as_ref()
+        .map(|indices| indices.len() / 3)
+        .unwrap_or(cpu_mesh.positions.len() / 9);
+    if triangle_count <= max_triangles {
+        return vec![cpu_mesh];
+    }
+
+    let mut chunks = Vec::new();
+    for (chunk_index, first_triangle) in (0..triangle_count).step_by(max_triangles).enumerate() {
+        let last_triangle = (first_triangle + max_triangles).min(triangle_count);
+        let vertices: Vec<u32> = if let Some(ref indices) = indices {
+            indices[first_triangle * 3..last_triangle * 3].to_vec()
+        } else {
+            (first_triangle as u32 * 3..last_triangle as u32 * 3).collect()
+        };
+
+        // Only keep the vertices used by the triangles in this chunk
+        let mut new_indices = Vec::with_capacity(vertices.len());
+        let mut new_index_of = HashMap::new();
+        let mut used = Vec::new();
+        for vertex in vertices {
+            let next_index = used.len() as u32;
+            let index = *new_index_of.entry(vertex).or_insert_with(|| {
+                used.push(vertex as usize);
+                next_index
+            });
+            new_indices.push(index);
+        }
+        let extract = |values: &Vec<f32>, size: usize| -> Vec<f32> {
+            used.iter()
+                .flat_map(|v| values[v * size..(v + 1) * size].iter().cloned())
+                .collect()
+        };
+        chunks.push(CPUMesh {
+            name: format!("{}_{}", cpu_mesh.name, chunk_index),
+            material_name: cpu_mesh.material_name.clone(),
+            positions: extract(&cpu_mesh.positions, 3),
+            indices: Some(Indices::U32(new_indices)),
+            normals: cpu_mesh.normals.as_ref().map(|normals| extract(normals, 3)),
+            uvs: cpu_mesh.uvs.as_ref().map(|uvs| extract(uvs, 2)),
+            colors: cpu_mesh.colors.as_ref().map(|colors| {
+                used.iter()
+                    .flat_map(|v| colors[v * 4..(v + 1) * 4].iter().cloned())
+                    .collect()
+            }),
+        });
+    }
+    chunks
+}
+
+// Parses a .obj file while it arrives and collects the triangles into chunks of at most the given number of triangles
+struct ObjParser {
+    max_triangles: usize,
+    incomplete_line: Vec<u8>,
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    object_name: String,
+    material_name: Option<String>,
+    chunk: ObjChunk,
+    chunk_count: usize,
+    finished_chunks: Vec<CPUMesh>,
+}
+
+#[derive(Default)]
+struct ObjChunk {
+    positions: Vec<f32>,
+    normals: Option<Vec<f32>>,
+    uvs: Option<Vec<f32>>,
+    indices: Vec<u32>,
+    index_of: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+}
+
+impl ObjParser {
+    fn new(path: &Path, max_triangles: usize) -> Self {
+        Self {
+            max_triangles: max_triangles.max(1),
+            incomplete_line: Vec::new(),
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            object_name: path
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .unwrap_or("obj")
+                .to_owned(),
+            material_name: None,
+            chunk: ObjChunk::default(),
+            chunk_count: 0,
+            finished_chunks: Vec::new(),
+        }
+    }
+
+    // Parses all of the complete lines and returns the chunks which are finished
+    fn push(&mut self, bytes: &[u8]) -> Result<Vec<CPUMesh>, IOError> {
+        let mut start = 0;
+        for end in (0..bytes.len()).filter(|i| bytes[*i] == b'\n') {
+            if self.incomplete_line.is_empty() {
+                self.parse_line(&bytes[start..end])?;
+            } else {
+                let mut line = std::mem::take(&mut self.incomplete_line);
+                line.extend_from_slice(&bytes[start..end]);
+                self.parse_line(&line)?;
+            }
+            start = end + 1;
+        }
+        self.incomplete_line.extend_from_slice(&bytes[start..]);
+        Ok(std::mem::take(&mut self.finished_chunks))
+    }
+
+    // Parses the last line and returns the remaining chunks
+    fn finish(&mut self) -> Result<Vec<CPUMesh>, IOError> {
+        let line = std::mem::take(&mut self.incomplete_line);
+        self.parse_line(&line)?;
+        self.finish_chunk();
+        Ok(std::mem::take(&mut self.finished_chunks))
+    }
+
+    fn parse_line(&mut self, line: &[u8]) -> Result<(), IOError> {
+        let line = String::from_utf8_lossy(line);
+        let line = line.split('#').next().unwrap();
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let v = parse_floats(tokens, 3).ok_or_else(|| invalid_line(line))?;
+                self.positions.push([v[0], v[1], v[2]]);
+            }
+            Some("vn") => {
+                let v = parse_floats(tokens, 3).ok_or_else(|| invalid_line(line))?;
+                self.normals.push([v[0], v[1], v[2]]);
+            }
+            Some("vt") => {
+                let v = parse_floats(tokens, 1).ok_or_else(|| invalid_line(line))?;
+                self.uvs.push([v[0], v.get(1).cloned().unwrap_or(0.0)]);
+            }
+            Some("f") => {
+                let vertices = tokens
+                    .map(|token| self.parse_face_vertex(token))
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|vertices| vertices.len() >= 3)
+                    .ok_or_else(|| invalid_line(line))?;
+                for i in 1..vertices.len() - 1 {
+                    self.add_triangle(&[vertices[0], vertices[i], vertices[i + 1]]);
+                }
+            }
+            Some("o") => {
+                self.finish_chunk();
+                self.object_name = tokens.collect::<Vec<_>>().join(" ");
+            }
+            Some("usemtl") => {
+                self.finish_chunk();
+                self.material_name = tokens.next().map(|name| name.to_owned());
+            }
+            // Groups, smoothing groups, lines, points etc. are ignored
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Returns the position, uv and normal index of a face vertex, for example 1/2/3, 1//3 or -1
+    fn parse_face_vertex(&self, token: &str) -> Option<(usize, Option<usize>, Option<usize>)> {
+        let mut parts = token.split('/');
+        let position = resolve_index(parts.next(), self.positions.len())??;
+        let uv = resolve_index(parts.next(), self.uvs.len())?;
+        let normal = resolve_index(parts.next(), self.normals.len())?;
+        Some((position, uv, normal))
+    }
+
+    fn add_triangle(&mut self, vertices: &[(usize, Option<usize>, Option<usize>); 3]) {
+        if self.chunk.indices.len() / 3 >= self.max_triangles {
+            self.finish_chunk();
+        }
+        let chunk = &mut self.chunk;
+        for vertex in vertices.iter() {
+            let vertex_count = chunk.positions.len() / 3;
+            let index = match chunk.index_of.get(vertex) {
+                Some(index) => *index,
+                None => {
+                    chunk.index_of.insert(*vertex, vertex_count as u32);
+                    chunk.positions.extend_from_slice(&self.positions[vertex.0]);
+                    // Vertices without uv coordinates or normals in a chunk where other vertices have them are given zeros
+                    if let Some(uv) = vertex.1 {
+                        chunk
+                            .uvs
+                            .get_or_insert_with(|| vec![0.0; vertex_count * 2])
+                            .extend_from_slice(&self.uvs[uv]);
+                    } else if let Some(ref mut uvs) = chunk.uvs {
+                        uvs.extend_from_slice(&[0.0; 2]);
+                    }
+                    if let Some(normal) = vertex.2 {
+                        chunk
+                            .normals
+                            .get_or_insert_with(|| vec![0.0; vertex_count * 3])
+                            .extend_from_slice(&self.normals[normal]);
+                    } else if let Some(ref mut normals) = chunk.normals {
+                        normals.extend_from_slice(&[0.0; 3]);
+                    }
+                    vertex_count as u32
+                }
+            };
+            chunk.indices.push(index);
+        }
+    }
+
+    fn finish_chunk(&mut self) {
+        if self.chunk.indices.is_empty() {
+            return;
+        }
+        let chunk = std::mem::take(&mut self.chunk);
+        self.finished_chunks.push(CPUMesh {
+            name: format!("{}_{}", self.object_name, self.chunk_count),
+            material_name: self.material_name.clone(),
+            positions: chunk.positions,
+            indices: Some(Indices::U32(chunk.indices)),
+            normals: chunk.normals,
+            uvs: chunk.uvs,
+            colors: None,
+        });
+        self.chunk_count += 1;
+    }
+}
+
+// Parses at least the given number of floats and ignores the rest, for example the w component of a position
+fn parse_floats<'a>(tokens: impl Iterator<Item = &'a str>, min_count: usize) -> Option<Vec<f32>> {
+    let values = tokens
+        .take(3)
+        .map(|token| token.parse::<f32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if values.len() >= min_count {
+        Some(values)
+    } else {
+        None
+    }
+}
+
+// Resolves a one-based or negative (relative to the end) index into the given number of elements,
+// returns Some(None) if the index is not specified and None if it is invalid
+fn resolve_index(index: Option<&str>, count: usize) -> Option<Option<usize>> {
+    match index {
+        None | Some("") => Some(None),
+        Some(index) => {
+            let index = index.parse::<i64>().ok()?;
+            let resolved = if index < 0 {
+                count as i64 + index
+            } else {
+                index - 1
+            };
+            if resolved >= 0 && (resolved as usize) < count {
+                Some(Some(resolved as usize))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn invalid_line(line: &str) -> IOError {
+    IOError::FailedToLoad {
+        message: format!("Invalid line in .obj file: {}", line.trim()),
+    }
+}
@@ -0,0 +1,313 @@
+use crate::definition::*;
+use crate::io::*;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"3DSC";
+const VERSION: u8 = 2;
+
+impl<'a> Loaded<'a> {
+    ///
+    /// Deserialize a loaded scene cache, saved using [Saver::save_scene_cache], into a list of meshes and materials.
+    /// Since the meshes, materials and textures are stored exactly as they were when saved, no further processing is needed,
+    /// which makes loading a scene cache much faster than for example parsing a glTF file and decoding the images.
+    ///
+    /// # Feature
+    /// Only available when the `scene-cache` feature is enabled.
+    ///
+    pub fn scene_cache<P: AsRef<Path>>(
+        &'a self,
+        path: P,
+    ) -> Result<(Vec<CPUMesh>, Vec<CPUMaterial>), IOError> {
+        let bytes = self.bytes(path.as_ref())?;
+        if bytes.len() < MAGIC.len() + 1 || &bytes[..MAGIC.len()] != MAGIC {
+            Err(IOError::FailedToLoad {
+                message: format!("{:?} is not a scene cache", path.as_ref()),
+            })?;
+        }
+        if bytes[MAGIC.len()] != VERSION {
+            Err(IOError::FailedToLoad {
+                message: format!(
+                    "The scene cache {:?} has version {} but version {} is expected",
+                    path.as_ref(),
+                    bytes[MAGIC.len()],
+                    VERSION
+                ),
+            })?;
+        }
+        let decompressed = miniz_oxide::inflate::decompress_to_vec(&bytes[MAGIC.len() + 1..])
+            .map_err(|e| IOError::FailedToLoad {
+                message: format!(
+                    "The scene cache {:?} could not be decompressed: {:?}",
+                    path.as_ref(),
+                    e
+                ),
+            })?;
+        let scene = bincode::deserialize::<SceneCache>(&decompressed)?;
+
+        let cpu_meshes = scene
+            .meshes
+            .into_iter()
+            .map(|mesh| CPUMesh {
+                name: mesh.name,
+                material_name: mesh.material_name,
+                positions: mesh.positions,
+                indices: mesh.indices.map(|indices| match indices {
+                    SceneCacheIndices::U8(ind) => Indices::U8(ind),
+                    SceneCacheIndices::U16(ind) => Indices::U16(ind),
+                    SceneCacheIndices::U32(ind) => Indices::U32(ind),
+                }),
+                normals: mesh.normals,
+                uvs: mesh.uvs,
+                colors: mesh.colors,
+            })
+            .collect();
+        let mut cpu_materials = Vec::new();
+        for material in scene.materials {
+            cpu_materials.push(CPUMaterial {
+                name: material.name,
+                color: material.color,
+                color_texture: material
+                    .color_texture
+                    .map(|texture| texture.into_cpu_texture())
+                    .transpose()?,
+                diffuse_intensity: material.diffuse_intensity,
+                specular_intensity: material.specular_intensity,
+                specular_power: material.specular_power,
+                metallic_factor: material.metallic_factor,
+                roughness_factor: material.roughness_factor,
+                metallic_roughness_texture: material
+                    .metallic_roughness_texture
+                    .map(|texture| texture.into_cpu_texture())
+                    .transpose()?,
+                double_sided: material.double_sided,
+                alpha_mode: material.alpha_mode.map(|mode| match mode {
+                    SceneCacheAlphaMode::Opaque => AlphaMode::Opaque,
+                    SceneCacheAlphaMode::Mask(cutoff) => AlphaMode::Mask { cutoff },
+                    SceneCacheAlphaMode::Blend => AlphaMode::Blend,
+                }),
+            });
+        }
+        Ok((cpu_meshes, cpu_materials))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Saver {
+    ///
+    /// Saves the given meshes and materials, including the texture data, as one compressed binary scene cache file,
+    /// which can be loaded using [Loaded::scene_cache].
+    /// This is useful for snapshotting a scene after it has been loaded from for example glTF and PNG files and processed,
+    /// to decrease the startup time on subsequent runs.
+    ///
+    /// # Feature
+    /// Only available when the `scene-cache` feature is enabled.
+    ///
+    pub fn save_scene_cache<P: AsRef<Path>>(
+        path: P,
+        cpu_meshes: &[CPUMesh],
+        cpu_materials: &[CPUMaterial],
+    ) -> Result<(), IOError> {
+        let scene = SceneCache {
+            meshes: cpu_meshes
+                .iter()
+                .map(|mesh| SceneCacheMesh {
+                    name: mesh.name.clone(),
+                    material_name: mesh.material_name.clone(),
+                    positions: mesh.positions.clone(),
+                    indices: mesh.indices.as_ref().map(|indices| match indices {
+                        Indices::U8(ind) => SceneCacheIndices::U8(ind.clone()),
+                        Indices::U16(ind) => SceneCacheIndices::U16(ind.clone()),
+                        Indices::U32(ind) => SceneCacheIndices::U32(ind.clone()),
+                    }),
+                    normals: mesh.normals.clone(),
+                    uvs: mesh.uvs.clone(),
+                    colors: mesh.colors.clone(),
+                })
+                .collect(),
+            materials: cpu_materials
+                .iter()
+                .map(|material| SceneCacheMaterial {
+                    name: material.name.clone(),
+                    color: material.color,
+                    color_texture: material
+                        .color_texture
+                        .as_ref()
+                        .map(SceneCacheTexture::from_cpu_texture),
+                    diffuse_intensity: material.diffuse_intensity,
+                    specular_intensity: material.specular_intensity,
+                    specular_power: material.specular_power,
+                    metallic_factor: material.metallic_factor,
+                    roughness_factor: material.roughness_factor,
+                    metallic_roughness_texture: material
+                        .metallic_roughness_texture
+                        .as_ref()
+                        .map(SceneCacheTexture::from_cpu_texture),
+                    double_sided: material.double_sided,
+                    alpha_mode: material.alpha_mode.map(|mode| match mode {
+                        AlphaMode::Opaque => SceneCacheAlphaMode::Opaque,
+                        AlphaMode::Mask { cutoff } => SceneCacheAlphaMode::Mask(cutoff),
+                        AlphaMode::Blend => SceneCacheAlphaMode::Blend,
+                    }),
+                })
+                .collect(),
+        };
+        let serialized = bincode::serialize(&scene)?;
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        // A low compression level since the decompression speed is the same and saving is much faster
+        bytes.extend(miniz_oxide::deflate::compress_to_vec(&serialized, 3));
+        Self::save_file(path, &bytes)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SceneCache {
+    pub meshes: Vec<SceneCacheMesh>,
+    pub materials: Vec<SceneCacheMaterial>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+enum SceneCacheIndices {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SceneCacheMesh {
+    pub name: String,
+    pub material_name: Option<String>,
+    pub positions: Vec<f32>,
+    pub indices: Option<SceneCacheIndices>,
+    pub normals: Option<Vec<f32>>,
+    pub uvs: Option<Vec<f32>>,
+    pub colors: Option<Vec<u8>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+enum SceneCacheAlphaMode {
+    Opaque,
+    Mask(f32),
+    Blend,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SceneCacheMaterial {
+    pub name: String,
+    pub color: Option<(f32, f32, f32, f32)>,
+    pub color_texture: Option<SceneCacheTexture>,
+    pub diffuse_intensity: Option<f32>,
+    pub specular_intensity: Option<f32>,
+    pub specular_power: Option<f32>,
+    pub metallic_factor: Option<f32>,
+    pub roughness_factor: Option<f32>,
+    pub metallic_roughness_texture: Option<SceneCacheTexture>,
+    pub double_sided: Option<bool>,
+    pub alpha_mode: Option<SceneCacheAlphaMode>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SceneCacheTexture {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub format: u8,
+    pub is_srgb: bool,
+    pub min_filter: u8,
+    pub mag_filter: u8,
+    pub mip_map_filter: Option<u8>,
+    pub wrap_s: u8,
+    pub wrap_t: u8,
+    pub wrap_r: u8,
+    pub non_power_of_two: u8,
+}
+
+impl SceneCacheTexture {
+    fn from_cpu_texture(texture: &CPUTexture<u8>) -> Self {
+        let format = match texture.format {
+            Format::R => 0,
+            Format::RG => 1,
+            Format::RGB => 2,
+            Format::SRGB => 3,
+            Format::RGBA => 4,
+            Format::SRGBA => 5,
+        };
+        let interpolation = |interpolation: Interpolation| match interpolation {
+            Interpolation::Nearest => 0,
+            Interpolation::Linear => 1,
+        };
+        let wrapping = |wrapping: Wrapping| match wrapping {
+            Wrapping::Repeat => 0,
+            Wrapping::MirroredRepeat => 1,
+            Wrapping::ClampToEdge => 2,
+        };
+        let non_power_of_two = match texture.non_power_of_two {
+            NonPowerOfTwo::Allow => 0,
+            NonPowerOfTwo::Restrict => 1,
+            NonPowerOfTwo::Resize => 2,
+        };
+        Self {
+            data: texture.data.clone(),
+            width: texture.width,
+            height: texture.height,
+            depth: texture.depth,
+            format,
+            is_srgb: texture.is_srgb,
+            min_filter: interpolation(texture.min_filter),
+            mag_filter: interpolation(texture.mag_filter),
+            mip_map_filter: texture.mip_map_filter.map(interpolation),
+            wrap_s: wrapping(texture.wrap_s),
+            wrap_t: wrapping(texture.wrap_t),
+            wrap_r: wrapping(texture.wrap_r),
+            non_power_of_two,
+        }
+    }
+
+    fn into_cpu_texture(self) -> Result<CPUTexture<u8>, IOError> {
+        let corrupt = || IOError::FailedToLoad {
+            message: "The scene cache contains a corrupt texture".to_string(),
+        };
+        let format = match self.format {
+            0 => Format::R,
+            1 => Format::RG,
+            2 => Format::RGB,
+            3 => Format::SRGB,
+            4 => Format::RGBA,
+            5 => Format::SRGBA,
+            _ => Err(corrupt())?,
+        };
+        let interpolation = |value: u8| match value {
+            0 => Ok(Interpolation::Nearest),
+            1 => Ok(Interpolation::Linear),
+            _ => Err(corrupt()),
+        };
+        let wrapping = |value: u8| match value {
+            0 => Ok(Wrapping::Repeat),
+            1 => Ok(Wrapping::MirroredRepeat),
+            2 => Ok(Wrapping::ClampToEdge),
+            _ => Err(corrupt()),
+        };
+        let non_power_of_two = match self.non_power_of_two {
+            0 => NonPowerOfTwo::Allow,
+            1 => NonPowerOfTwo::Restrict,
+            2 => NonPowerOfTwo::Resize,
+            _ => Err(corrupt())?,
+        };
+        Ok(CPUTexture {
+            data: self.data,
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            format,
+            is_srgb: self.is_srgb,
+            min_filter: interpolation(self.min_filter)?,
+            mag_filter: interpolation(self.mag_filter)?,
+            mip_map_filter: self.mip_map_filter.map(interpolation).transpose()?,
+            wrap_s: wrapping(self.wrap_s)?,
+            wrap_t: wrapping(self.wrap_t)?,
+            wrap_r: wrapping(self.wrap_r)?,
+            non_power_of_two,
+        })
+    }
+}
@@ -0,0 +1,439 @@
+use crate::definition::*;
+use crate::io::*;
+use crate::math::*;
+use std::path::Path;
+
+impl<'a> Loaded<'a> {
+    ///
+    /// Deserialize a loaded .svg file into a [VectorGraphic] in the xy-plane with the y-axis pointing up, ie. the SVG y-axis is flipped.
+    /// Only a subset of SVG is supported: the `path`, `rect`, `circle`, `ellipse`, `line`, `polyline` and `polygon` elements,
+    /// the `fill`, `stroke`, `stroke-width`, `opacity`, `fill-opacity` and `stroke-opacity` attributes or style properties, which are inherited from groups,
+    /// and `translate` and `scale` transforms. Other elements and attributes, for example text, gradients and rotations, are ignored.
+    ///
+    /// # Feature
+    /// Only available when the `svg-io` feature is enabled.
+    ///
+    pub fn svg<P: AsRef<Path>>(&'a self, path: P) -> Result<VectorGraphic, IOError> {
+        let bytes = self.bytes(path.as_ref())?;
+        let text = std::str::from_utf8(bytes).map_err(|e| IOError::FailedToLoad {
+            message: format!("The svg file is not valid UTF-8: {}", e),
+        })?;
+        let document = roxmltree::Document::parse(text).map_err(|e| IOError::FailedToLoad {
+            message: format!("Could not parse the svg file: {}", e),
+        })?;
+        let mut graphic = VectorGraphic::default();
+        parse_node(document.root_element(), Style::default(), &mut graphic)?;
+        Ok(graphic)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Style {
+    fill: Option<Vec4>,
+    stroke: Option<Vec4>,
+    stroke_width: f32,
+    opacity: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+    translation: Vec2,
+    scale: Vec2,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: Some(vec4(0.0, 0.0, 0.0, 1.0)),
+            stroke: None,
+            stroke_width: 1.0,
+            opacity: 1.0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            translation: vec2(0.0, 0.0),
+            scale: vec2(1.0, 1.0),
+        }
+    }
+}
+
+impl Style {
+    fn inherit(&self, node: roxmltree::Node) -> Self {
+        let mut style = *self;
+        let mut properties: Vec<(&str, &str)> =
+            node.attributes().map(|a| (a.name(), a.value())).collect();
+        if let Some(declarations) = node.attribute("style") {
+            properties.extend(declarations.split(';').filter_map(|declaration| {
+                let mut parts = declaration.splitn(2, ':');
+                Some((parts.next()?.trim(), parts.next()?.trim()))
+            }));
+        }
+        for (name, value) in properties {
+            match name {
+                "fill" => style.fill = parse_color(value).unwrap_or(style.fill),
+                "stroke" => style.stroke = parse_color(value).unwrap_or(style.stroke),
+                "stroke-width" => style.stroke_width = parse_number(value, style.stroke_width),
+                "opacity" => style.opacity *= parse_number(value, 1.0),
+                "fill-opacity" => style.fill_opacity = parse_number(value, 1.0),
+                "stroke-opacity" => style.stroke_opacity = parse_number(value, 1.0),
+                "transform" => {
+                    let (translation, scale) = parse_transform(value);
+                    style.translation +=
+                        vec2(style.scale.x * translation.x, style.scale.y * translation.y);
+                    style.scale = vec2(style.scale.x * scale.x, style.scale.y * scale.y);
+                }
+                _ => {}
+            }
+        }
+        style
+    }
+
+    fn transform(&self, p: Vec2) -> Vec2 {
+        vec2(
+            self.translation.x + self.scale.x * p.x,
+            -(self.translation.y + self.scale.y * p.y),
+        )
+    }
+}
+
+fn parse_node(
+    node: roxmltree::Node,
+    parent_style: Style,
+    graphic: &mut VectorGraphic,
+) -> Result<(), IOError> {
+    let style = parent_style.inherit(node);
+    let number = |name: &str| parse_number(node.attribute(name).unwrap_or("0"), 0.0);
+    let points = || {
+        let values: Vec<f32> = node
+            .attribute("points")
+            .unwrap_or("")
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| s.parse().ok())
+            .collect();
+        values
+            .chunks_exact(2)
+            .map(|p| vec2(p[0], p[1]))
+            .collect::<Vec<_>>()
+    };
+    let path = match node.tag_name().name() {
+        "path" => Some(parse_path_data(node.attribute("d").unwrap_or(""))?),
+        "rect" => {
+            let (x, y, w, h) = (number("x"), number("y"), number("width"), number("height"));
+            Some(VectorPath::polygon(&[
+                vec2(x, y),
+                vec2(x + w, y),
+                vec2(x + w, y + h),
+                vec2(x, y + h),
+            ]))
+        }
+        "circle" => Some(VectorPath::circle(
+            vec2(number("cx"), number("cy")),
+            number("r"),
+        )),
+        "ellipse" => Some(VectorPath::ellipse(
+            vec2(number("cx"), number("cy")),
+            vec2(number("rx"), number("ry")),
+        )),
+        "line" => Some(VectorPath::polyline(&[
+            vec2(number("x1"), number("y1")),
+            vec2(number("x2"), number("y2")),
+        ])),
+        "polyline" => Some(VectorPath::polyline(&points())),
+        "polygon" => Some(VectorPath::polygon(&points())),
+        "defs" | "clipPath" | "mask" | "pattern" | "linearGradient" | "radialGradient" | "text" => {
+            return Ok(())
+        }
+        _ => None,
+    };
+
+    if let Some(path) = path {
+        let transform = |p: &Vec2| style.transform(*p);
+        let path = VectorPath {
+            commands: path
+                .commands
+                .iter()
+                .map(|command| match command {
+                    PathCommand::MoveTo(p) => PathCommand::MoveTo(transform(p)),
+                    PathCommand::LineTo(p) => PathCommand::LineTo(transform(p)),
+                    PathCommand::QuadraticTo(c, p) => {
+                        PathCommand::QuadraticTo(transform(c), transform(p))
+                    }
+                    PathCommand::CubicTo(c1, c2, p) => {
+                        PathCommand::CubicTo(transform(c1), transform(c2), transform(p))
+                    }
+                    PathCommand::Close => PathCommand::Close,
+                })
+                .collect(),
+        };
+        let scale = 0.5 * (style.scale.x.abs() + style.scale.y.abs());
+        graphic.shapes.push(VectorShape {
+            path,
+            fill: style.fill.map(|c| {
+                let mut c = c;
+                c.w *= style.opacity * style.fill_opacity;
+                c
+            }),
+            stroke: style.stroke.map(|c| {
+                let mut c = c;
+                c.w *= style.opacity * style.stroke_opacity;
+                (c, style.stroke_width * scale)
+            }),
+        });
+    }
+    for child in node.children().filter(|n| n.is_element()) {
+        parse_node(child, style, graphic)?;
+    }
+    Ok(())
+}
+
+// Parses the path data into a path with the same coordinates, ie. without flipping the y-axis
+fn parse_path_data(data: &str) -> Result<VectorPath, IOError> {
+    let mut tokens = Vec::new();
+    let mut number = String::new();
+    let flush = |number: &mut String, tokens: &mut Vec<PathToken>| -> Result<(), IOError> {
+        if !number.is_empty() {
+            tokens.push(PathToken::Number(number.parse().map_err(|_| {
+                IOError::FailedToLoad {
+                    message: format!("Invalid number {} in svg path data.", number),
+                }
+            })?));
+            number.clear();
+        }
+        Ok(())
+    };
+    for c in data.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            '.' => {
+                if number.contains('.') && !number.contains('e') {
+                    flush(&mut number, &mut tokens)?;
+                }
+                number.push(c);
+            }
+            '-' | '+' => {
+                if !number.ends_with('e') && !number.ends_with('E') {
+                    flush(&mut number, &mut tokens)?;
+                }
+                number.push(c);
+            }
+            'e' | 'E' if !number.is_empty() => number.push(c),
+            ',' | ' ' | '\t' | '\n' | '\r' => flush(&mut number, &mut tokens)?,
+            _ => {
+                flush(&mut number, &mut tokens)?;
+                tokens.push(PathToken::Command(c));
+            }
+        }
+    }
+    flush(&mut number, &mut tokens)?;
+
+    let mut path = VectorPath::new();
+    let mut current = vec2(0.0, 0.0);
+    let mut start = current;
+    // The second control point of the previous curve, for the smooth curve commands
+    let mut last_control: Option<(char, Vec2)> = None;
+    let mut index = 0;
+    let mut command = ' ';
+    while index < tokens.len() {
+        if let PathToken::Command(c) = tokens[index] {
+            command = c;
+            index += 1;
+        }
+        let relative = command.is_ascii_lowercase();
+        let mut take = |count: usize| -> Result<Vec<f32>, IOError> {
+            let values = tokens
+                .get(index..index + count)
+                .and_then(|t| {
+                    t.iter()
+                        .map(|t| match t {
+                            PathToken::Number(n) => Some(*n),
+                            PathToken::Command(_) => None,
+                        })
+                        .collect::<Option<Vec<f32>>>()
+                })
+                .ok_or(IOError::FailedToLoad {
+                    message: format!(
+                        "Missing numbers for the {} command in svg path data.",
+                        command
+                    ),
+                })?;
+            index += count;
+            Ok(values)
+        };
+        let point = |x: f32, y: f32| {
+            if relative {
+                current + vec2(x, y)
+            } else {
+                vec2(x, y)
+            }
+        };
+        let upper = command.to_ascii_uppercase();
+        let mut control = None;
+        match upper {
+            'M' => {
+                let v = take(2)?;
+                current = point(v[0], v[1]);
+                start = current;
+                path = path.move_to(current);
+                // Subsequent pairs of numbers are implicit line commands
+                command = if relative { 'l' } else { 'L' };
+            }
+            'L' => {
+                let v = take(2)?;
+                current = point(v[0], v[1]);
+                path = path.line_to(current);
+            }
+            'H' => {
+                let v = take(1)?;
+                current.x = if relative { current.x + v[0] } else { v[0] };
+                path = path.line_to(current);
+            }
+            'V' => {
+                let v = take(1)?;
+                current.y = if relative { current.y + v[0] } else { v[0] };
+                path = path.line_to(current);
+            }
+            'Q' | 'T' => {
+                let c = if upper == 'Q' {
+                    let v = take(2)?;
+                    point(v[0], v[1])
+                } else {
+                    reflected_control(last_control, 'Q', current)
+                };
+                let v = take(2)?;
+                let to = point(v[0], v[1]);
+                path = path.quadratic_to(c, to);
+                control = Some(('Q', c));
+                current = to;
+            }
+            'C' | 'S' => {
+                let c1 = if upper == 'C' {
+                    let v = take(2)?;
+                    point(v[0], v[1])
+                } else {
+                    reflected_control(last_control, 'C', current)
+                };
+                let v = take(4)?;
+                let c2 = point(v[0], v[1]);
+                let to = point(v[2], v[3]);
+                path = path.cubic_to(c1, c2, to);
+                control = Some(('C', c2));
+                current = to;
+            }
+            'A' => {
+                // Arcs are approximated by a straight line to the end point
+                let v = take(7)?;
+                current = point(v[5], v[6]);
+                path = path.line_to(current);
+            }
+            'Z' => {
+                path = path.close();
+                current = start;
+                command = ' ';
+            }
+            _ => {
+                return Err(IOError::FailedToLoad {
+                    message: format!("Unsupported command {} in svg path data.", command),
+                })
+            }
+        }
+        last_control = control;
+    }
+    Ok(path)
+}
+
+fn reflected_control(last_control: Option<(char, Vec2)>, curve: char, current: Vec2) -> Vec2 {
+    match last_control {
+        Some((c, control)) if c == curve => 2.0 * current - control,
+        _ => current,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum PathToken {
+    Command(char),
+    Number(f32),
+}
+
+fn parse_number(value: &str, default: f32) -> f32 {
+    value
+        .trim()
+        .trim_end_matches("px")
+        .parse()
+        .unwrap_or(default)
+}
+
+// Returns None if the color cannot be parsed, Some(None) for no color and otherwise the color
+fn parse_color(value: &str) -> Option<Option<Vec4>> {
+    let value = value.trim();
+    let rgb =
+        |r: u32, g: u32, b: u32| Some(Some(vec4(r as f32, g as f32, b as f32, 255.0) / 255.0));
+    if value == "none" {
+        return Some(None);
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits: Vec<u32> = hex.chars().filter_map(|c| c.to_digit(16)).collect();
+        return match digits.len() {
+            3 => rgb(digits[0] * 17, digits[1] * 17, digits[2] * 17),
+            6 => rgb(
+                digits[0] * 16 + digits[1],
+                digits[2] * 16 + digits[3],
+                digits[4] * 16 + digits[5],
+            ),
+            _ => None,
+        };
+    }
+    if let Some(values) = value.strip_prefix("rgb(").and_then(|v| v.strip_suffix(')')) {
+        let values: Vec<u32> = values
+            .split(',')
+            .filter_map(|v| v.trim().parse().ok())
+            .collect();
+        return if values.len() == 3 {
+            rgb(values[0], values[1], values[2])
+        } else {
+            None
+        };
+    }
+    match value {
+        "black" => rgb(0, 0, 0),
+        "white" => rgb(255, 255, 255),
+        "red" => rgb(255, 0, 0),
+        "green" => rgb(0, 128, 0),
+        "lime" => rgb(0, 255, 0),
+        "blue" => rgb(0, 0, 255),
+        "yellow" => rgb(255, 255, 0),
+        "cyan" | "aqua" => rgb(0, 255, 255),
+        "magenta" | "fuchsia" => rgb(255, 0, 255),
+        "gray" | "grey" => rgb(128, 128, 128),
+        "orange" => rgb(255, 165, 0),
+        "purple" => rgb(128, 0, 128),
+        "brown" => rgb(165, 42, 42),
+        _ => None,
+    }
+}
+
+// Parses the translate and scale parts of a transform attribute and returns the translation and scale
+fn parse_transform(value: &str) -> (Vec2, Vec2) {
+    let mut translation = vec2(0.0, 0.0);
+    let mut scale = vec2(1.0, 1.0);
+    for part in value.split(')') {
+        let mut split = part.splitn(2, '(');
+        let (name, arguments) = match (split.next(), split.next()) {
+            (Some(name), Some(arguments)) => (name.trim().trim_start_matches(','), arguments),
+            _ => continue,
+        };
+        let arguments: Vec<f32> = arguments
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter_map(|v| v.parse().ok())
+            .collect();
+        match (name.trim(), arguments.len()) {
+            ("translate", 1) => {
+                translation += vec2(scale.x * arguments[0], 0.0);
+            }
+            ("translate", 2) => {
+                translation += vec2(scale.x * arguments[0], scale.y * arguments[1]);
+            }
+            ("scale", 1) => scale *= arguments[0],
+            ("scale", 2) => scale = vec2(scale.x * arguments[0], scale.y * arguments[1]),
+            _ => {}
+        }
+    }
+    (translation, scale)
+}
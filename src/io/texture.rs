@@ -15,6 +15,31 @@ impl<'a> Loaded<'a> {
         image_from_bytes(self.bytes(path)?)
     }
 
+    ///
+    /// Deserialize the loaded image resource at the given path into a [CPUTexture](crate::CPUTexture) with 16 bit values using
+    /// the [image](https://crates.io/crates/image/main.rs) crate, for example a 16 bit PNG image containing scientific data.
+    /// Images with 8 bit values are converted to 16 bit values.
+    /// The values are not converted from sRGB color space and the texture is stored as half floats in the [0..1] range on the GPU.
+    ///
+    /// # Feature
+    /// Only available when the `image-io` feature is enabled.
+    ///
+    pub fn image_u16<P: AsRef<Path>>(&'a self, path: P) -> Result<CPUTexture<u16>, IOError> {
+        image_u16_from_bytes(self.bytes(path)?)
+    }
+
+    ///
+    /// Deserialize the loaded high dynamic range image resource at the given path into a [CPUTexture](crate::CPUTexture) with float values,
+    /// for example an environment map.
+    /// Supports Radiance .hdr images and, if the `exr-io` feature is enabled, OpenEXR images.
+    ///
+    /// # Feature
+    /// Only available when the `image-io` feature is enabled.
+    ///
+    pub fn hdr_image<P: AsRef<Path>>(&'a self, path: P) -> Result<CPUTexture<f32>, IOError> {
+        hdr_image_from_bytes(self.bytes(path)?)
+    }
+
     ///
     /// Deserialize the 6 loaded image resources at the given paths into a [CPUTexture](crate::CPUTexture) using
     /// the [image](https://crates.io/crates/image/main.rs) crate.
@@ -46,6 +71,184 @@ impl<'a> Loaded<'a> {
         right.data.extend(back.data);
         Ok(right)
     }
+
+    ///
+    /// Deserialize the loaded image resource at the given path, which contains all six faces of a cube map in one of the
+    /// [cube map layouts](CubeMapLayout), into a [CPUTexture](crate::CPUTexture) using the [image](https://crates.io/crates/image/main.rs) crate.
+    /// The layout is detected from the aspect ratio of the image.
+    /// The CPUTexture can then be used to create a [TextureCubeMap](crate::TextureCubeMap), for example for a [Skybox](crate::Skybox).
+    ///
+    /// # Feature
+    /// Only available when the `image-io` feature is enabled.
+    ///
+    pub fn cube_image_from_cross<P: AsRef<Path>>(
+        &'a self,
+        path: P,
+    ) -> Result<CPUTexture<u8>, IOError> {
+        cube_from_layout(self.image(path)?)
+    }
+
+    ///
+    /// Deserialize the loaded high dynamic range image resource at the given path, which contains all six faces of a cube map in one of the
+    /// [cube map layouts](CubeMapLayout), into a [CPUTexture](crate::CPUTexture) with float values, see [hdr_image](Self::hdr_image).
+    /// The layout is detected from the aspect ratio of the image.
+    ///
+    /// # Feature
+    /// Only available when the `image-io` feature is enabled.
+    ///
+    pub fn hdr_cube_image_from_cross<P: AsRef<Path>>(
+        &'a self,
+        path: P,
+    ) -> Result<CPUTexture<f32>, IOError> {
+        cube_from_layout(self.hdr_image(path)?)
+    }
+}
+
+///
+/// The layout of the six faces of a cube map stored in a single image.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CubeMapLayout {
+    /// Four faces wide and three faces high with the top and bottom faces above and below the front face:
+    /// the middle row contains left, front, right and back.
+    HorizontalCross,
+    /// Three faces wide and four faces high with the top, front, bottom and back faces in the middle column,
+    /// where the back face is upside down, and the left and right faces on the sides of the front face.
+    VerticalCross,
+    /// Three faces wide and two faces high: right, left and top in the first row and bottom, front and back in the second row.
+    Strip3x2,
+    /// Six faces wide in the order right, left, top, bottom, front and back.
+    HorizontalStrip,
+    /// Six faces high in the order right, left, top, bottom, front and back.
+    VerticalStrip,
+}
+
+impl CubeMapLayout {
+    ///
+    /// Returns the layout of an image with the given size based on its aspect ratio, or `None` if the aspect ratio does not match any layout.
+    ///
+    pub fn detect(width: u32, height: u32) -> Option<Self> {
+        if width * 3 == height * 4 {
+            Some(Self::HorizontalCross)
+        } else if width * 4 == height * 3 {
+            Some(Self::VerticalCross)
+        } else if width * 2 == height * 3 {
+            Some(Self::Strip3x2)
+        } else if width == height * 6 {
+            Some(Self::HorizontalStrip)
+        } else if width * 6 == height {
+            Some(Self::VerticalStrip)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Returns the number of faces in each row and the number of rows.
+    ///
+    fn grid_size(&self) -> (u32, u32) {
+        match self {
+            Self::HorizontalCross => (4, 3),
+            Self::VerticalCross => (3, 4),
+            Self::Strip3x2 => (3, 2),
+            Self::HorizontalStrip => (6, 1),
+            Self::VerticalStrip => (1, 6),
+        }
+    }
+
+    ///
+    /// Returns the column and row of each face in the order right, left, top, bottom, front and back
+    /// and whether the face is upside down.
+    ///
+    fn faces(&self) -> [(u32, u32, bool); 6] {
+        match self {
+            Self::HorizontalCross => [
+                (2, 1, false),
+                (0, 1, false),
+                (1, 0, false),
+                (1, 2, false),
+                (1, 1, false),
+                (3, 1, false),
+            ],
+            Self::VerticalCross => [
+                (2, 1, false),
+                (0, 1, false),
+                (1, 0, false),
+                (1, 2, false),
+                (1, 1, false),
+                (1, 3, true),
+            ],
+            Self::Strip3x2 => [
+                (0, 0, false),
+                (1, 0, false),
+                (2, 0, false),
+                (0, 1, false),
+                (1, 1, false),
+                (2, 1, false),
+            ],
+            Self::HorizontalStrip => [
+                (0, 0, false),
+                (1, 0, false),
+                (2, 0, false),
+                (3, 0, false),
+                (4, 0, false),
+                (5, 0, false),
+            ],
+            Self::VerticalStrip => [
+                (0, 0, false),
+                (0, 1, false),
+                (0, 2, false),
+                (0, 3, false),
+                (0, 4, false),
+                (0, 5, false),
+            ],
+        }
+    }
+}
+
+fn cube_from_layout<T: TextureDataType>(texture: CPUTexture<T>) -> Result<CPUTexture<T>, IOError> {
+    let layout =
+        CubeMapLayout::detect(texture.width, texture.height).ok_or(IOError::FailedToLoad {
+            message: format!(
+                "Could not detect the cube map layout of an image with size {}x{}",
+                texture.width, texture.height
+            ),
+        })?;
+    let (columns, rows) = layout.grid_size();
+    let face_size = texture.width / columns;
+    if face_size * columns != texture.width || face_size * rows != texture.height {
+        Err(IOError::FailedToLoad {
+            message: format!(
+                "The size {}x{} of the cube map image is not a multiple of the face size",
+                texture.width, texture.height
+            ),
+        })?;
+    }
+    let channels = texture.format.color_channel_count() as usize;
+    let face_size = face_size as usize;
+    let row_length = texture.width as usize * channels;
+    let mut data = Vec::with_capacity(face_size * face_size * channels * 6);
+    for (column, row, upside_down) in layout.faces().iter() {
+        for y in 0..face_size {
+            let y = if *upside_down { face_size - 1 - y } else { y };
+            let start = (*row as usize * face_size + y) * row_length
+                + *column as usize * face_size * channels;
+            let pixels = &texture.data[start..start + face_size * channels];
+            if *upside_down {
+                for pixel in pixels.chunks(channels).rev() {
+                    data.extend_from_slice(pixel);
+                }
+            } else {
+                data.extend_from_slice(pixels);
+            }
+        }
+    }
+    Ok(CPUTexture {
+        data,
+        width: face_size as u32,
+        height: face_size as u32,
+        ..texture
+    })
 }
 
 #[cfg(not(target_arch = "wasm32"))]
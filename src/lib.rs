@@ -5,7 +5,19 @@
 //! See the [README](https://crates.io/crates/three-d) for more information and
 //! the [examples](https://github.com/asny/three-d/tree/master/examples) for how to use it.
 //!
+//! Without the default `std` feature, the crate is `no_std` and only contains the [math](crate::math) and the CPU-side [definitions](crate::definition),
+//! which then only depend on `core` and `alloc` (except for [cgmath](https://crates.io/crates/cgmath) which still requires the standard library).
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod error;
+#[doc(inline)]
+pub use error::*;
 
+#[cfg(feature = "std")]
 pub mod context;
 
 pub mod math;
@@ -16,36 +28,64 @@ pub mod definition;
 #[doc(inline)]
 pub use definition::*;
 
+#[cfg(feature = "std")]
+pub mod procedural;
+#[doc(inline)]
+#[cfg(feature = "std")]
+pub use procedural::*;
+
+#[cfg(feature = "std")]
 pub mod core;
 #[doc(inline)]
+#[cfg(feature = "std")]
 pub use crate::core::*;
 
+#[cfg(feature = "std")]
 pub mod camera;
 #[doc(inline)]
+#[cfg(feature = "std")]
 pub use camera::*;
 
+#[cfg(feature = "std")]
 pub mod frame;
 #[doc(inline)]
+#[cfg(feature = "std")]
 pub use frame::*;
 
+#[cfg(feature = "renderer")]
 pub mod object;
 #[doc(inline)]
+#[cfg(feature = "renderer")]
 pub use object::*;
 
+#[cfg(feature = "std")]
 pub mod effect;
 #[doc(inline)]
+#[cfg(feature = "std")]
 pub use effect::*;
 
+#[cfg(feature = "renderer")]
 pub mod light;
 #[doc(inline)]
+#[cfg(feature = "renderer")]
 pub use light::*;
 
+#[cfg(feature = "renderer")]
 pub mod function;
 #[doc(inline)]
+#[cfg(feature = "renderer")]
 pub use function::*;
 
+#[cfg(feature = "renderer")]
+pub mod path_tracing;
+#[doc(inline)]
+#[cfg(feature = "renderer")]
+pub use path_tracing::*;
+
+#[cfg(feature = "std")]
 pub mod io;
 #[doc(inline)]
+#[cfg(feature = "std")]
 pub use io::*;
 
 #[cfg(feature = "phong-renderer")]
@@ -54,10 +94,32 @@ pub mod phong;
 #[cfg(feature = "phong-renderer")]
 pub use phong::*;
 
+#[cfg(feature = "rapier-physics")]
+pub mod physics;
+#[doc(inline)]
+#[cfg(feature = "rapier-physics")]
+pub use physics::*;
+
+#[cfg(feature = "std")]
 pub mod window;
 #[doc(inline)]
+#[cfg(feature = "std")]
 pub use window::*;
 
+#[cfg(feature = "std")]
 pub mod gui;
 #[doc(inline)]
+#[cfg(feature = "std")]
 pub use gui::*;
+
+#[cfg(feature = "wgpu-backend")]
+pub mod wgpu_backend;
+#[doc(inline)]
+#[cfg(feature = "wgpu-backend")]
+pub use wgpu_backend::*;
+
+#[cfg(feature = "test-support")]
+pub mod testing;
+#[doc(inline)]
+#[cfg(feature = "test-support")]
+pub use testing::*;
@@ -12,6 +12,14 @@ pub struct DirectionalLight {
     light_buffer: UniformBuffer,
     shadow_texture: DepthTargetTexture2D,
     shadow_camera: Option<Camera>,
+    baked_shadow: Option<BakedShadowMap>,
+    layer_mask: u32,
+}
+
+struct BakedShadowMap {
+    texture: DepthTargetTexture2D,
+    parameters: Vec<f32>,
+    is_current: bool,
 }
 
 impl DirectionalLight {
@@ -23,7 +31,7 @@ impl DirectionalLight {
     ) -> Result<DirectionalLight, Error> {
         let mut light = DirectionalLight {
             context: context.clone(),
-            light_buffer: UniformBuffer::new(context, &[3u32, 1, 3, 1, 16])?,
+            light_buffer: UniformBuffer::new(context, &[3u32, 1, 3, 1, 16, 4])?,
             shadow_texture: DepthTargetTexture2D::new(
                 context,
                 1,
@@ -33,22 +41,51 @@ impl DirectionalLight {
                 DepthFormat::Depth32F,
             )?,
             shadow_camera: None,
+            baked_shadow: None,
+            layer_mask: crate::ALL_LAYERS,
         };
 
         light.set_intensity(intensity);
         light.set_color(color);
         light.set_direction(direction);
+        light.light_buffer.update(5, &[0.0, 1.0, 0.0, 1.0])?;
         Ok(light)
     }
 
+    ///
+    /// Sets the render layers this light affects as a bit mask, for example to only light the objects in one room.
+    /// The light only affects geometries with a [layer mask](crate::Geometry::layer_mask) which has at least one layer in common with this mask and only those geometries cast shadows.
+    /// All layers are affected by default.
+    ///
+    pub fn set_layer_mask(&mut self, layer_mask: u32) {
+        self.layer_mask = layer_mask;
+        self.baked_shadow = None;
+    }
+
+    ///
+    /// Returns the render layers this light affects, see [set_layer_mask](Self::set_layer_mask).
+    ///
+    pub fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
+
     pub fn set_color(&mut self, color: &Vec3) {
         self.light_buffer.update(0, &color.to_slice()).unwrap();
     }
 
+    pub fn color(&self) -> Vec3 {
+        let c = self.light_buffer.get(0).unwrap();
+        vec3(c[0], c[1], c[2])
+    }
+
     pub fn set_intensity(&mut self, intensity: f32) {
         self.light_buffer.update(1, &[intensity]).unwrap();
     }
 
+    pub fn intensity(&self) -> f32 {
+        self.light_buffer.get(1).unwrap()[0]
+    }
+
     pub fn set_direction(&mut self, direction: &Vec3) {
         self.light_buffer
             .update(2, &direction.normalize().to_slice())
@@ -60,8 +97,28 @@ impl DirectionalLight {
         vec3(d[0], d[1], d[2])
     }
 
+    ///
+    /// Sets the size of the light, which makes the shadows soft using percentage-closer soft shadows (PCSS),
+    /// such that the shadows are sharp close to the shadow caster and soften with the distance from the caster.
+    /// Since the light is infinitely far away, the size is given as the width of the penumbra for each unit of distance between the caster and the receiver.
+    /// A size of zero, which is the default, gives shadows with a fixed small amount of filtering.
+    ///
+    pub fn set_light_size(&mut self, light_size: f32) {
+        let mut parameters = self.light_buffer.get(5).unwrap().to_vec();
+        parameters[0] = light_size.max(0.0);
+        self.light_buffer.update(5, &parameters).unwrap();
+    }
+
+    ///
+    /// Returns the size of the light, see [set_light_size](Self::set_light_size).
+    ///
+    pub fn light_size(&self) -> f32 {
+        self.light_buffer.get(5).unwrap()[0]
+    }
+
     pub fn clear_shadow_map(&mut self) {
         self.shadow_camera = None;
+        self.baked_shadow = None;
         self.shadow_texture = DepthTargetTexture2D::new(
             &self.context,
             1,
@@ -83,6 +140,107 @@ impl DirectionalLight {
         texture_width: u32,
         texture_height: u32,
         geometries: &[&dyn Geometry],
+    ) -> Result<(), Error> {
+        crate::profile_scope!("shadow pass");
+        self.update_shadow_camera(target, frustrum_width, frustrum_height, frustrum_depth)?;
+        if let Some(ref mut baked_shadow) = self.baked_shadow {
+            baked_shadow.is_current = false;
+        }
+        self.shadow_texture = new_shadow_texture(&self.context, texture_width, texture_height)?;
+        render_shadow_map(
+            &self.shadow_texture,
+            self.shadow_camera.as_ref().unwrap(),
+            Some(1.0),
+            geometries,
+        )?;
+        self.light_buffer.update(3, &[1.0])?;
+        Ok(())
+    }
+
+    ///
+    /// Generates a shadow map like [generate_shadow_map](Self::generate_shadow_map), but the static geometries, which do not move,
+    /// are only rendered once into a baked shadow map which is reused until the shadow parameters or the direction of the light change
+    /// or until the baked shadow map is [invalidated](Self::invalidate_baked_shadow_map), for example when a static object has moved.
+    /// The dynamic geometries are rendered on top of the baked shadow map each time this is called.
+    /// If there are no dynamic geometries, nothing is rendered at all when the baked shadow map is reused,
+    /// so this can be called each frame for a scene that is completely static.
+    ///
+    pub fn generate_shadow_map_with_static(
+        &mut self,
+        target: &Vec3,
+        frustrum_width: f32,
+        frustrum_height: f32,
+        frustrum_depth: f32,
+        texture_width: u32,
+        texture_height: u32,
+        static_geometries: &[&dyn Geometry],
+        dynamic_geometries: &[&dyn Geometry],
+    ) -> Result<(), Error> {
+        crate::profile_scope!("shadow pass");
+        let direction = self.direction();
+        let parameters = vec![
+            target.x,
+            target.y,
+            target.z,
+            frustrum_width,
+            frustrum_height,
+            frustrum_depth,
+            texture_width as f32,
+            texture_height as f32,
+            direction.x,
+            direction.y,
+            direction.z,
+        ];
+        self.update_shadow_camera(target, frustrum_width, frustrum_height, frustrum_depth)?;
+        let camera = self.shadow_camera.as_ref().unwrap();
+        if self
+            .baked_shadow
+            .as_ref()
+            .map(|baked_shadow| baked_shadow.parameters != parameters)
+            .unwrap_or(true)
+        {
+            let texture = new_shadow_texture(&self.context, texture_width, texture_height)?;
+            render_shadow_map(&texture, camera, Some(1.0), static_geometries)?;
+            self.baked_shadow = Some(BakedShadowMap {
+                texture,
+                parameters,
+                is_current: false,
+            });
+        }
+        let baked_shadow = self.baked_shadow.as_mut().unwrap();
+        if !baked_shadow.is_current || !dynamic_geometries.is_empty() {
+            if self.shadow_texture.width() != texture_width
+                || self.shadow_texture.height() != texture_height
+            {
+                self.shadow_texture =
+                    new_shadow_texture(&self.context, texture_width, texture_height)?;
+            }
+            baked_shadow.texture.copy_to(
+                CopyDestination::<u8>::DepthTexture(&self.shadow_texture),
+                Viewport::new_at_origo(texture_width, texture_height),
+            )?;
+            render_shadow_map(&self.shadow_texture, camera, None, dynamic_geometries)?;
+            baked_shadow.is_current = dynamic_geometries.is_empty();
+        }
+        self.light_buffer.update(3, &[1.0])?;
+        Ok(())
+    }
+
+    ///
+    /// Invalidates the baked shadow map of the static geometries, so that it is rendered again
+    /// the next time [generate_shadow_map_with_static](Self::generate_shadow_map_with_static) is called,
+    /// for example when a static object has moved.
+    ///
+    pub fn invalidate_baked_shadow_map(&mut self) {
+        self.baked_shadow = None;
+    }
+
+    fn update_shadow_camera(
+        &mut self,
+        target: &Vec3,
+        frustrum_width: f32,
+        frustrum_height: f32,
+        frustrum_depth: f32,
     ) -> Result<(), Error> {
         let direction = self.direction();
         let up = compute_up_direction(direction);
@@ -96,41 +254,27 @@ impl DirectionalLight {
             frustrum_height,
             frustrum_depth,
         )?);
+        self.shadow_camera
+            .as_mut()
+            .unwrap()
+            .set_layer_mask(self.layer_mask);
         self.light_buffer.update(
             4,
             &shadow_matrix(self.shadow_camera.as_ref().unwrap()).to_slice(),
         )?;
-
-        self.shadow_texture = DepthTargetTexture2D::new(
-            &self.context,
-            texture_width,
-            texture_height,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            DepthFormat::Depth32F,
-        )
-        .unwrap();
-        self.shadow_texture.write(Some(1.0), || {
-            let viewport = Viewport::new_at_origo(texture_width, texture_height);
-            for geometry in geometries {
-                if geometry
-                    .aabb()
-                    .map(|aabb| self.shadow_camera.as_ref().unwrap().in_frustum(&aabb))
-                    .unwrap_or(true)
-                {
-                    geometry.render_depth(
-                        RenderStates::default(),
-                        viewport,
-                        self.shadow_camera.as_ref().unwrap(),
-                    )?;
-                }
-            }
-            Ok(())
-        })?;
-        self.light_buffer.update(3, &[1.0])?;
+        let light_size = self.light_size();
+        self.light_buffer
+            .update(5, &[light_size, 1.0 / frustrum_width, 0.0, frustrum_depth])?;
         Ok(())
     }
 
+    ///
+    /// Returns the camera used for generating the shadow map or `None` if no shadow map has been generated.
+    ///
+    pub fn shadow_camera(&self) -> Option<&Camera> {
+        self.shadow_camera.as_ref()
+    }
+
     pub fn shadow_map(&self) -> &DepthTargetTexture2D {
         &self.shadow_texture
     }
@@ -140,6 +284,43 @@ impl DirectionalLight {
     }
 }
 
+fn new_shadow_texture(
+    context: &Context,
+    width: u32,
+    height: u32,
+) -> Result<DepthTargetTexture2D, Error> {
+    DepthTargetTexture2D::new(
+        context,
+        width,
+        height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )
+}
+
+fn render_shadow_map(
+    texture: &DepthTargetTexture2D,
+    camera: &Camera,
+    clear_state: Option<f32>,
+    geometries: &[&dyn Geometry],
+) -> Result<(), Error> {
+    texture.write(clear_state, || {
+        let viewport = Viewport::new_at_origo(texture.width(), texture.height());
+        for geometry in geometries {
+            if geometry
+                .aabb()
+                .map(|aabb| camera.in_frustum(&aabb))
+                .unwrap_or(true)
+                && geometry.layer_mask() & camera.layer_mask() != 0
+            {
+                geometry.render_depth(RenderStates::new(viewport), camera)?;
+            }
+        }
+        Ok(())
+    })
+}
+
 fn shadow_matrix(camera: &Camera) -> Mat4 {
     let bias_matrix = crate::Mat4::new(
         0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.5, 0.5, 0.5, 1.0,
@@ -6,6 +6,7 @@ use crate::math::*;
 ///
 pub struct PointLight {
     light_buffer: UniformBuffer,
+    layer_mask: u32,
 }
 
 impl PointLight {
@@ -20,6 +21,7 @@ impl PointLight {
     ) -> Result<PointLight, Error> {
         let mut light = PointLight {
             light_buffer: UniformBuffer::new(context, &[3u32, 1, 1, 1, 1, 1, 3, 1])?,
+            layer_mask: crate::ALL_LAYERS,
         };
 
         light.set_intensity(intensity);
@@ -33,24 +35,62 @@ impl PointLight {
         Ok(light)
     }
 
+    ///
+    /// Sets the render layers this light affects as a bit mask, for example to only light the objects in one room.
+    /// The light only affects geometries with a [layer mask](crate::Geometry::layer_mask) which has at least one layer in common with this mask.
+    /// All layers are affected by default.
+    ///
+    pub fn set_layer_mask(&mut self, layer_mask: u32) {
+        self.layer_mask = layer_mask;
+    }
+
+    ///
+    /// Returns the render layers this light affects, see [set_layer_mask](Self::set_layer_mask).
+    ///
+    pub fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
+
     pub fn set_color(&mut self, color: &Vec3) {
         self.light_buffer.update(0, &color.to_slice()).unwrap();
     }
 
+    pub fn color(&self) -> Vec3 {
+        let c = self.light_buffer.get(0).unwrap();
+        vec3(c[0], c[1], c[2])
+    }
+
     pub fn set_intensity(&mut self, intensity: f32) {
         self.light_buffer.update(1, &[intensity]).unwrap();
     }
 
+    pub fn intensity(&self) -> f32 {
+        self.light_buffer.get(1).unwrap()[0]
+    }
+
     pub fn set_attenuation(&mut self, constant: f32, linear: f32, exponential: f32) {
         self.light_buffer.update(2, &[constant]).unwrap();
         self.light_buffer.update(3, &[linear]).unwrap();
         self.light_buffer.update(4, &[exponential]).unwrap();
     }
 
+    pub fn attenuation(&self) -> (f32, f32, f32) {
+        (
+            self.light_buffer.get(2).unwrap()[0],
+            self.light_buffer.get(3).unwrap()[0],
+            self.light_buffer.get(4).unwrap()[0],
+        )
+    }
+
     pub fn set_position(&mut self, position: &Vec3) {
         self.light_buffer.update(6, &position.to_slice()).unwrap();
     }
 
+    pub fn position(&self) -> Vec3 {
+        let p = self.light_buffer.get(6).unwrap();
+        vec3(p[0], p[1], p[2])
+    }
+
     pub fn buffer(&self) -> &UniformBuffer {
         &self.light_buffer
     }
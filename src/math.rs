@@ -1,5 +1,8 @@
 //!
 //! Math functionality.
+//! Only uses `core` and `alloc`, with the float functions provided by libm when the `std` feature is disabled.
+//! However, [cgmath](https://crates.io/crates/cgmath) which the math types are based on requires the standard library,
+//! so building for a target without the standard library is not yet possible.
 //!
 
 mod math;
@@ -12,11 +12,11 @@ pub struct AxisAlignedBoundingBox {
 impl AxisAlignedBoundingBox {
     fn empty() -> Self {
         Self {
-            min: vec3(std::f32::INFINITY, std::f32::INFINITY, std::f32::INFINITY),
+            min: vec3(core::f32::INFINITY, core::f32::INFINITY, core::f32::INFINITY),
             max: vec3(
-                std::f32::NEG_INFINITY,
-                std::f32::NEG_INFINITY,
-                std::f32::NEG_INFINITY,
+                core::f32::NEG_INFINITY,
+                core::f32::NEG_INFINITY,
+                core::f32::NEG_INFINITY,
             ),
         }
     }
@@ -1,8 +1,15 @@
+#[cfg(feature = "std")]
 pub(crate) use cgmath::ortho;
+#[cfg(feature = "std")]
 pub(crate) use cgmath::perspective;
 #[doc(hidden)]
 pub use cgmath::prelude::*;
-use cgmath::{Deg, Matrix2, Matrix3, Matrix4, Point3, Rad, Vector2, Vector3, Vector4};
+use cgmath::{Deg, Matrix2, Matrix3, Matrix4, Point3, Quaternion, Rad, Vector2, Vector3, Vector4};
+// Without the standard library, the float functions (sqrt, sin, cos etc.) are provided by libm through num-traits.
+// The import is unused when another crate links the standard library, since its float functions then take precedence.
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+pub(crate) use num_traits::Float;
 
 pub type Vec2 = Vector2<f32>;
 pub type Vec3 = Vector3<f32>;
@@ -13,6 +20,11 @@ pub type Mat4 = Matrix4<f32>;
 pub type Point = Point3<f32>;
 pub type Degrees = Deg<f32>;
 pub type Radians = Rad<f32>;
+pub type Quat = Quaternion<f32>;
+/// A vector in double precision, for example a position in a large world, see [LargeWorldCamera](crate::LargeWorldCamera).
+pub type DVec3 = Vector3<f64>;
+/// A matrix in double precision, for example a transformation in a large world, see [LargeWorldCamera](crate::LargeWorldCamera).
+pub type DMat4 = Matrix4<f64>;
 
 pub fn vec2(x: f32, y: f32) -> Vec2 {
     Vector2::new(x, y)
@@ -26,6 +38,12 @@ pub fn vec4(x: f32, y: f32, z: f32, w: f32) -> Vec4 {
     Vector4::new(x, y, z, w)
 }
 
+pub fn dvec3(x: f64, y: f64, z: f64) -> DVec3 {
+    Vector3::new(x, y, z)
+}
+
+// Only used when transferring data to the GPU
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) trait Vec2Ext {
     fn to_slice(&self) -> [f32; 2];
 }
@@ -36,6 +54,7 @@ impl Vec2Ext for Vec2 {
     }
 }
 
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) trait Vec3Ext {
     fn to_slice(&self) -> [f32; 3];
 }
@@ -46,6 +65,7 @@ impl Vec3Ext for Vec3 {
     }
 }
 
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) trait Vec4Ext {
     fn to_slice(&self) -> [f32; 4];
 }
@@ -56,6 +76,7 @@ impl Vec4Ext for Vec4 {
     }
 }
 
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) trait Mat2Ext {
     fn to_slice(&self) -> [f32; 4];
 }
@@ -66,6 +87,7 @@ impl Mat2Ext for Mat2 {
     }
 }
 
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) trait Mat3Ext {
     fn to_slice(&self) -> [f32; 9];
 }
@@ -79,6 +101,7 @@ impl Mat3Ext for Mat3 {
     }
 }
 
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) trait Mat4Ext {
     fn to_slice(&self) -> [f32; 16];
 }
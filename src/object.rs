@@ -6,6 +6,10 @@ mod mesh;
 #[doc(inline)]
 pub use mesh::*;
 
+mod streamed_mesh;
+#[doc(inline)]
+pub use streamed_mesh::*;
+
 mod instanced_mesh;
 #[doc(inline)]
 pub use instanced_mesh::*;
@@ -18,6 +22,10 @@ mod imposters;
 #[doc(inline)]
 pub use imposters::*;
 
+mod billboard;
+#[doc(inline)]
+pub use billboard::*;
+
 mod particles;
 #[doc(inline)]
 pub use particles::*;
@@ -25,3 +33,55 @@ pub use particles::*;
 mod axes;
 #[doc(inline)]
 pub use axes::*;
+
+mod grid;
+#[doc(inline)]
+pub use grid::*;
+
+mod debug_lines;
+#[doc(inline)]
+pub use debug_lines::*;
+
+mod gizmo;
+#[doc(inline)]
+pub use gizmo::*;
+
+mod section_plane;
+#[doc(inline)]
+pub use section_plane::*;
+
+mod minimap;
+#[doc(inline)]
+pub use minimap::*;
+
+mod planar_reflection;
+#[doc(inline)]
+pub use planar_reflection::*;
+
+mod portal;
+#[doc(inline)]
+pub use portal::*;
+
+mod label_layer;
+#[doc(inline)]
+pub use label_layer::*;
+
+mod skinned_mesh;
+#[doc(inline)]
+pub use skinned_mesh::*;
+
+mod cloth;
+#[doc(inline)]
+pub use cloth::*;
+
+#[cfg(feature = "sdf-text")]
+mod measurements;
+#[doc(inline)]
+#[cfg(feature = "sdf-text")]
+pub use measurements::*;
+
+#[cfg(feature = "plot")]
+mod plot;
+#[doc(inline)]
+#[cfg(feature = "plot")]
+pub use plot::*;
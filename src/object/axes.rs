@@ -13,6 +13,7 @@ pub struct Axes {
     x: Mesh,
     y: Mesh,
     z: Mesh,
+    transformation: Mat4,
 }
 
 impl Axes {
@@ -20,40 +21,94 @@ impl Axes {
     /// Creates a new axes object consisting of three arrows with the given radius and length.
     ///
     pub fn new(context: &Context, radius: f32, length: f32) -> Result<Self, Error> {
-        let x = Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?;
-        let mut y = Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?;
-        let mut z = Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?;
-        y.transformation = Mat4::from_angle_z(degrees(90.0));
-        z.transformation = Mat4::from_angle_y(degrees(-90.0));
-        Ok(Self { x, y, z })
+        let mut axes = Self {
+            x: Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?,
+            y: Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?,
+            z: Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?,
+            transformation: Mat4::identity(),
+        };
+        axes.set_transformation(&Mat4::identity());
+        Ok(axes)
+    }
+
+    ///
+    /// Returns the transformation applied to the axes.
+    ///
+    pub fn transformation(&self) -> &Mat4 {
+        &self.transformation
+    }
+
+    ///
+    /// Sets the transformation applied to the axes, which can be used to position, orientate and scale the axes.
+    /// It is also used when the axes are rendered as a [Geometry], for example into a shadow map.
+    ///
+    pub fn set_transformation(&mut self, transformation: &Mat4) {
+        self.transformation = *transformation;
+        self.x.transformation = *transformation;
+        self.y.transformation = transformation * Mat4::from_angle_z(degrees(90.0));
+        self.z.transformation = transformation * Mat4::from_angle_y(degrees(-90.0));
     }
 
     ///
     /// Render the axes.
     /// Must be called in a render target render function,
     /// for example in the callback function of [Screen::write](crate::Screen::write).
-    /// The transformation can be used to position, orientate and scale the axes.
+    /// The axes are positioned, orientated and scaled by the [transformation](Self::set_transformation),
+    /// which is the same transformation that is used when rendering them as a [Geometry].
     ///
     pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
         self.x.render_with_color(
             &vec4(1.0, 0.0, 0.0, 1.0),
-            RenderStates::default(),
-            viewport,
+            RenderStates::new(viewport),
             camera,
         )?;
         self.y.render_with_color(
             &vec4(0.0, 1.0, 0.0, 1.0),
-            RenderStates::default(),
-            viewport,
+            RenderStates::new(viewport),
             camera,
         )?;
         self.z.render_with_color(
             &vec4(0.0, 0.0, 1.0, 1.0),
-            RenderStates::default(),
-            viewport,
+            RenderStates::new(viewport),
             camera,
         )?;
 
         Ok(())
     }
 }
+
+impl Geometry for Axes {
+    fn render_depth_to_red(
+        &self,
+        render_states: RenderStates,
+        camera: &Camera,
+        max_depth: f32,
+    ) -> Result<(), Error> {
+        self.x
+            .render_depth_to_red(render_states, camera, max_depth)?;
+        self.y
+            .render_depth_to_red(render_states, camera, max_depth)?;
+        self.z
+            .render_depth_to_red(render_states, camera, max_depth)?;
+        Ok(())
+    }
+
+    fn render_depth(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
+        self.x.render_depth(render_states, camera)?;
+        self.y.render_depth(render_states, camera)?;
+        self.z.render_depth(render_states, camera)?;
+        Ok(())
+    }
+
+    fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
+        let mut aabb = self.x.aabb()?;
+        aabb.expand_with_aabb(&self.y.aabb()?);
+        aabb.expand_with_aabb(&self.z.aabb()?);
+        Some(aabb)
+    }
+}
+
+///
+/// An alias for [Axes](Axes), the RGB arrows helper which is usually used together with the [GridHelper](crate::GridHelper).
+///
+pub type AxesHelper = Axes;
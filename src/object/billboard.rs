@@ -0,0 +1,179 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use std::rc::Rc;
+
+///
+/// A textured quad positioned in the world which always faces the camera, for example used for labels, particles like sprites,
+/// trees and as a stand-in for a detailed object far away from the camera.
+/// The billboard either rotates freely to face the camera or rotates only around an [axis](Billboard::axis),
+/// for example the up direction for trees which should stay upright.
+///
+pub struct Billboard {
+    program: Program,
+    position_buffer: VertexBuffer,
+    uv_buffer: VertexBuffer,
+    texture: Rc<dyn Texture>,
+    is_imposter: bool,
+    /// The position of the center of the billboard in world space.
+    pub position: Vec3,
+    /// The width and height of the billboard in world space.
+    pub size: Vec2,
+    /// If specified, the billboard only rotates around this axis to face the camera.
+    pub axis: Option<Vec3>,
+    /// The color which is multiplied with the color of the texture.
+    pub color: Vec4,
+}
+
+impl Billboard {
+    ///
+    /// Constructs a new billboard showing the given texture, for example a [Texture2D] loaded from an image.
+    ///
+    pub fn new(context: &Context, texture: Rc<dyn Texture>) -> Result<Self, Error> {
+        let program = Program::from_source_with_preprocessor(
+            context,
+            include_str!("shaders/billboard.vert"),
+            include_str!("shaders/billboard.frag"),
+            &ShaderPreprocessor::new(),
+        )?;
+        let positions = vec![
+            -0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.5, 0.5, 0.0, 0.5, 0.5, 0.0, -0.5, 0.5, 0.0, -0.5,
+            -0.5, 0.0,
+        ];
+        let uvs = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0];
+        Ok(Self {
+            program,
+            position_buffer: VertexBuffer::new_with_static(context, &positions)?,
+            uv_buffer: VertexBuffer::new_with_static(context, &uvs)?,
+            texture,
+            is_imposter: false,
+            position: vec3(0.0, 0.0, 0.0),
+            size: vec2(1.0, 1.0),
+            axis: None,
+            color: vec4(1.0, 1.0, 1.0, 1.0),
+        })
+    }
+
+    ///
+    /// Constructs a new billboard which is an imposter for the objects rendered in the `render` closure inside the given axis aligned bounding box.
+    /// The objects are rendered once from the positive z direction into a texture with the given maximum width and height,
+    /// and the [position](Billboard::position) and [size](Billboard::size) of the billboard is set to cover the bounding box.
+    ///
+    pub fn new_imposter<F: FnOnce(Viewport, &Camera) -> Result<(), Error>>(
+        context: &Context,
+        render: F,
+        aabb: (Vec3, Vec3),
+        max_texture_size: u32,
+    ) -> Result<Self, Error> {
+        let (min, max) = aabb;
+        let width = (max.x - min.x).max(0.001);
+        let height = (max.y - min.y).max(0.001);
+        let depth = (max.z - min.z).max(0.001);
+        let center = 0.5 * min + 0.5 * max;
+        let camera = Camera::new_orthographic(
+            context,
+            center + vec3(0.0, 0.0, depth),
+            center,
+            vec3(0.0, 1.0, 0.0),
+            width,
+            height,
+            2.0 * depth,
+        )?;
+
+        let texture_width = ((max_texture_size as f32 * (width / height).min(1.0)) as u32).max(1);
+        let texture_height = ((max_texture_size as f32 * (height / width).min(1.0)) as u32).max(1);
+        let texture = ColorTargetTexture2D::<u8>::new(
+            context,
+            texture_width,
+            texture_height,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        let depth_texture = DepthTargetTexture2D::new(
+            context,
+            texture_width,
+            texture_height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            DepthFormat::Depth32F,
+        )?;
+        RenderTarget::new(context, &texture, &depth_texture)?.write(
+            ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0),
+            || {
+                render(
+                    Viewport::new_at_origo(texture_width, texture_height),
+                    &camera,
+                )
+            },
+        )?;
+
+        let mut billboard = Self::new(context, Rc::new(texture))?;
+        billboard.is_imposter = true;
+        billboard.position = center;
+        billboard.size = vec2(width, height);
+        Ok(billboard)
+    }
+
+    ///
+    /// Returns the texture shown on the billboard.
+    ///
+    pub fn texture(&self) -> &Rc<dyn Texture> {
+        &self.texture
+    }
+
+    ///
+    /// Sets the texture shown on the billboard, for example a [Texture2D] loaded from an image.
+    ///
+    pub fn set_texture(&mut self, texture: Rc<dyn Texture>) {
+        self.texture = texture;
+        self.is_imposter = false;
+    }
+
+    ///
+    /// Render the billboard with transparency blending.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        let (right, up) = if let Some(axis) = self.axis {
+            let up = axis.normalize();
+            let mut right = up.cross(camera.position() - self.position);
+            if right.magnitude2() < 0.000001 {
+                // The camera is looking along the axis
+                right = camera.right_direction();
+            }
+            (right.normalize(), up)
+        } else {
+            let right = camera.right_direction().normalize();
+            (right, right.cross(camera.view_direction()).normalize())
+        };
+
+        self.program
+            .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
+        self.program.use_uniform_vec3("center", &self.position)?;
+        self.program.use_uniform_vec3("right", &right)?;
+        self.program.use_uniform_vec3("up", &up)?;
+        self.program.use_uniform_vec2("size", &self.size)?;
+        self.program.use_uniform_vec4("color", &self.color)?;
+        self.program
+            .use_uniform_int("is_imposter", &(self.is_imposter as i32))?;
+        self.program.use_texture(self.texture.as_ref(), "tex")?;
+        self.program
+            .use_attribute_vec3(&self.position_buffer, "position")?;
+        self.program
+            .use_attribute_vec2(&self.uv_buffer, "uv_coordinates")?;
+        self.program.draw_arrays(
+            RenderStates {
+                blend: Some(BlendParameters::TRANSPARENCY),
+                ..RenderStates::new(viewport)
+            },
+            6,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// A shape which the particles of a [Cloth] collide with.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ClothCollider {
+    /// A sphere, for example approximating the body of a character wearing a cape.
+    Sphere { center: Vec3, radius: f32 },
+    /// An infinite plane through the point with the given normal, for example the ground. The cloth is kept on the side the normal points to.
+    Plane { point: Vec3, normal: Vec3 },
+}
+
+impl ClothCollider {
+    fn resolve(&self, position: Vec3) -> Vec3 {
+        match *self {
+            ClothCollider::Sphere { center, radius } => {
+                let offset = position - center;
+                let distance = offset.magnitude();
+                if distance < radius && distance > 0.0 {
+                    center + offset * (radius / distance)
+                } else {
+                    position
+                }
+            }
+            ClothCollider::Plane { point, normal } => {
+                let normal = normal.normalize();
+                let distance = (position - point).dot(normal);
+                if distance < 0.0 {
+                    position - normal * distance
+                } else {
+                    position
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Constraint {
+    a: usize,
+    b: usize,
+    rest_length: f32,
+}
+
+///
+/// A piece of cloth, for example a flag, a cape or a curtain, simulated on the CPU as a grid of particles connected by springs.
+/// The particles are kept together by structural (neighbours), shear (diagonal neighbours) and bend (every second particle) constraints
+/// and collide with the added [colliders](ClothCollider). The simulated positions are written to a dynamic [Mesh] each update.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let frame_input: FrameInput = unimplemented!();
+/// let mut flag = Cloth::new(&context, 2.0, 1.0, 20, 10, &Mat4::from_angle_y(degrees(90.0))).unwrap();
+/// flag.pin_column(0);
+/// flag.wind = vec3(5.0, 0.0, 2.0);
+/// // Each frame:
+/// flag.update(frame_input.elapsed_time).unwrap();
+/// // Render flag.mesh() like any other mesh
+/// ```
+///
+pub struct Cloth {
+    mesh: Mesh,
+    columns: usize,
+    rows: usize,
+    positions: Vec<Vec3>,
+    previous_positions: Vec<Vec3>,
+    pinned: Vec<bool>,
+    constraints: Vec<Constraint>,
+    colliders: Vec<ClothCollider>,
+    accumulated_time: f32,
+    /// The acceleration applied to all particles, in units per second squared.
+    pub gravity: Vec3,
+    /// The velocity of the wind, which pushes the cloth along its normals.
+    pub wind: Vec3,
+    /// The fraction of the velocity of the particles which is lost each second, between 0 and 1.
+    pub damping: f32,
+    /// How stiff the springs are, between 0 (no stiffness) and 1 (the constraints are fully satisfied each iteration).
+    pub stiffness: f32,
+    /// The number of times the constraints are relaxed each simulation step, more iterations give less stretchy cloth.
+    pub iterations: u32,
+}
+
+impl Cloth {
+    ///
+    /// Creates a new cloth with the given size and number of particles in each direction, which must be at least two.
+    /// The cloth is placed in the xy plane, centered at the origin with the first row at the top (positive y),
+    /// and then transformed to world space by the given transformation. The simulation is done in world space.
+    ///
+    pub fn new(
+        context: &Context,
+        width: f32,
+        height: f32,
+        columns: usize,
+        rows: usize,
+        transformation: &Mat4,
+    ) -> Result<Self, Error> {
+        if columns < 2 || rows < 2 {
+            Err(Error::MeshError {
+                message: "A cloth must have at least two columns and two rows of particles"
+                    .to_string(),
+            })?;
+        }
+        let mut positions = Vec::with_capacity(columns * rows);
+        let mut uvs = Vec::with_capacity(columns * rows * 2);
+        for row in 0..rows {
+            for column in 0..columns {
+                let u = column as f32 / (columns - 1) as f32;
+                let v = row as f32 / (rows - 1) as f32;
+                let p = vec3((u - 0.5) * width, (0.5 - v) * height, 0.0);
+                positions.push((transformation * p.extend(1.0)).truncate());
+                uvs.push(u);
+                uvs.push(1.0 - v);
+            }
+        }
+        let mut indices = Vec::with_capacity((columns - 1) * (rows - 1) * 6);
+        for row in 0..rows - 1 {
+            for column in 0..columns - 1 {
+                let i = (row * columns + column) as u32;
+                let below = i + columns as u32;
+                indices.extend(&[i, below, i + 1, i + 1, below, below + 1]);
+            }
+        }
+
+        let index = |column: usize, row: usize| row * columns + column;
+        let mut pairs = Vec::new();
+        for row in 0..rows {
+            for column in 0..columns {
+                let i = index(column, row);
+                // Structural
+                if column + 1 < columns {
+                    pairs.push((i, index(column + 1, row)));
+                }
+                if row + 1 < rows {
+                    pairs.push((i, index(column, row + 1)));
+                }
+                // Shear
+                if column + 1 < columns && row + 1 < rows {
+                    pairs.push((i, index(column + 1, row + 1)));
+                    pairs.push((index(column + 1, row), index(column, row + 1)));
+                }
+                // Bend
+                if column + 2 < columns {
+                    pairs.push((i, index(column + 2, row)));
+                }
+                if row + 2 < rows {
+                    pairs.push((i, index(column, row + 2)));
+                }
+            }
+        }
+        let constraints = pairs
+            .into_iter()
+            .map(|(a, b)| Constraint {
+                a,
+                b,
+                rest_length: (positions[a] - positions[b]).magnitude(),
+            })
+            .collect();
+
+        let mut cpu_mesh = CPUMesh {
+            name: "cloth".to_string(),
+            positions: positions.iter().flat_map(|p| p.to_slice()).collect(),
+            indices: Some(Indices::U32(indices)),
+            uvs: Some(uvs),
+            ..Default::default()
+        };
+        cpu_mesh.compute_normals();
+        let mut mesh = Mesh::new(context, &cpu_mesh)?;
+        mesh.cull = Some(CullType::None);
+        Ok(Self {
+            mesh,
+            columns,
+            rows,
+            previous_positions: positions.clone(),
+            pinned: vec![false; positions.len()],
+            positions,
+            constraints,
+            colliders: Vec::new(),
+            accumulated_time: 0.0,
+            gravity: vec3(0.0, -9.82, 0.0),
+            wind: vec3(0.0, 0.0, 0.0),
+            damping: 0.2,
+            stiffness: 1.0,
+            iterations: 8,
+        })
+    }
+
+    ///
+    /// Returns the mesh with the simulated positions and normals, which can be rendered like any other [Mesh].
+    ///
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    ///
+    /// Returns the number of particles in each row and the number of rows.
+    ///
+    pub fn size(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    ///
+    /// Returns the current position in world space of the particle in the given column and row.
+    ///
+    pub fn position(&self, column: usize, row: usize) -> Vec3 {
+        self.positions[row * self.columns + column]
+    }
+
+    ///
+    /// Pins the particle in the given column and row, so that it is not moved by the simulation.
+    ///
+    pub fn pin(&mut self, column: usize, row: usize) {
+        self.pinned[row * self.columns + column] = true;
+    }
+
+    ///
+    /// Unpins the particle in the given column and row, so that it is moved by the simulation again.
+    ///
+    pub fn unpin(&mut self, column: usize, row: usize) {
+        self.pinned[row * self.columns + column] = false;
+    }
+
+    ///
+    /// Pins all particles in the given row, for example the top row (0) of a curtain or a cape.
+    ///
+    pub fn pin_row(&mut self, row: usize) {
+        for column in 0..self.columns {
+            self.pin(column, row);
+        }
+    }
+
+    ///
+    /// Pins all particles in the given column, for example the first column (0) of a flag attached to a pole.
+    ///
+    pub fn pin_column(&mut self, column: usize) {
+        for row in 0..self.rows {
+            self.pin(column, row);
+        }
+    }
+
+    ///
+    /// Moves the particle in the given column and row to the given position in world space, for example to move a pinned particle
+    /// along with the shoulders of a character wearing a cape.
+    ///
+    pub fn set_position(&mut self, column: usize, row: usize, position: Vec3) {
+        let index = row * self.columns + column;
+        self.positions[index] = position;
+        self.previous_positions[index] = position;
+    }
+
+    ///
+    /// Adds a shape which the cloth collides with.
+    ///
+    pub fn add_collider(&mut self, collider: ClothCollider) {
+        self.colliders.push(collider);
+    }
+
+    ///
+    /// Returns the colliders, which can be moved each frame, for example to follow a character.
+    ///
+    pub fn colliders_mut(&mut self) -> &mut Vec<ClothCollider> {
+        &mut self.colliders
+    }
+
+    ///
+    /// Advances the simulation by the given time in milliseconds, see [FrameInput::elapsed_time](crate::FrameInput::elapsed_time),
+    /// using fixed time steps, and updates the positions and normals of the [mesh](Self::mesh).
+    ///
+    pub fn update(&mut self, elapsed_time: f64) -> Result<(), Error> {
+        const TIME_STEP: f32 = 1.0 / 120.0;
+        const MAX_STEPS: u32 = 8;
+        self.accumulated_time += elapsed_time as f32 * 0.001;
+        let mut steps = 0;
+        while self.accumulated_time >= TIME_STEP && steps < MAX_STEPS {
+            self.step(TIME_STEP);
+            self.accumulated_time -= TIME_STEP;
+            steps += 1;
+        }
+        if steps == MAX_STEPS {
+            // Drop the remaining time instead of trying to catch up after a long frame
+            self.accumulated_time = 0.0;
+        }
+        if steps > 0 {
+            self.mesh.update_positions(
+                &self
+                    .positions
+                    .iter()
+                    .flat_map(|p| p.to_slice())
+                    .collect::<Vec<f32>>(),
+            )?;
+            self.mesh.update_normals(&self.compute_normals())?;
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, time_step: f32) {
+        let normals = if self.wind.magnitude2() > 0.0 {
+            Some(self.compute_normals())
+        } else {
+            None
+        };
+        let keep = (1.0 - self.damping).max(0.0).min(1.0).powf(time_step);
+        for i in 0..self.positions.len() {
+            if self.pinned[i] {
+                self.previous_positions[i] = self.positions[i];
+                continue;
+            }
+            let mut acceleration = self.gravity;
+            if let Some(ref normals) = normals {
+                let normal = vec3(normals[i * 3], normals[i * 3 + 1], normals[i * 3 + 2]);
+                let velocity = (self.positions[i] - self.previous_positions[i]) / time_step;
+                acceleration += normal * normal.dot(self.wind - velocity);
+            }
+            let position = self.positions[i];
+            self.positions[i] = position
+                + (position - self.previous_positions[i]) * keep
+                + acceleration * time_step * time_step;
+            self.previous_positions[i] = position;
+        }
+
+        for _ in 0..self.iterations {
+            for constraint in self.constraints.iter() {
+                let (a, b) = (constraint.a, constraint.b);
+                let weight_a = if self.pinned[a] { 0.0 } else { 1.0 };
+                let weight_b = if self.pinned[b] { 0.0 } else { 1.0 };
+                let total_weight = weight_a + weight_b;
+                let delta = self.positions[b] - self.positions[a];
+                let length = delta.magnitude();
+                if total_weight == 0.0 || length == 0.0 {
+                    continue;
+                }
+                let correction = delta
+                    * (self.stiffness * (length - constraint.rest_length)
+                        / (length * total_weight));
+                self.positions[a] += correction * weight_a;
+                self.positions[b] -= correction * weight_b;
+            }
+            for i in 0..self.positions.len() {
+                if !self.pinned[i] {
+                    for collider in self.colliders.iter() {
+                        self.positions[i] = collider.resolve(self.positions[i]);
+                    }
+                }
+            }
+        }
+    }
+
+    fn compute_normals(&self) -> Vec<f32> {
+        let mut normals = Vec::with_capacity(self.positions.len() * 3);
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let left = self.position(column.saturating_sub(1), row);
+                let right = self.position((column + 1).min(self.columns - 1), row);
+                let up = self.position(column, row.saturating_sub(1));
+                let down = self.position(column, (row + 1).min(self.rows - 1));
+                let normal = (right - left).cross(up - down);
+                let normal = if normal.magnitude2() > 0.0 {
+                    normal.normalize()
+                } else {
+                    vec3(0.0, 0.0, 1.0)
+                };
+                normals.extend(&normal.to_slice());
+            }
+        }
+        normals
+    }
+}
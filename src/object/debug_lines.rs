@@ -0,0 +1,281 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::light::*;
+use crate::math::*;
+
+///
+/// A collection of colored line segments used for debug visualizations, for example of
+/// [bounding boxes](DebugLines::add_aabb), [camera frustums](DebugLines::add_frustum) and
+/// [light sources](DebugLines::add_spot_light).
+/// Add the lines that should be visualized and call [render](DebugLines::render),
+/// the lines are kept until [clear](DebugLines::clear) is called.
+///
+pub struct DebugLines {
+    program: Program,
+    positions: VertexBuffer,
+    colors: VertexBuffer,
+    cpu_positions: Vec<f32>,
+    cpu_colors: Vec<f32>,
+    is_updated: bool,
+    /// Whether or not the lines are hidden behind other objects in the scene.
+    pub depth_test: bool,
+}
+
+impl DebugLines {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        let program = Program::from_source_with_preprocessor(
+            context,
+            "#include \"camera\"
+            in vec3 position;
+            in vec4 color;
+            out vec4 col;
+            void main()
+            {
+                col = color;
+                gl_Position = camera.viewProjection * vec4(position, 1.0);
+            }",
+            "#include \"shared\"
+            in vec4 col;
+            layout (location = 0) out vec4 outColor;
+            void main()
+            {
+                outColor = vec4(srgb_from_rgb(col.rgb), col.a);
+            }",
+            &ShaderPreprocessor::new(),
+        )?;
+        Ok(Self {
+            program,
+            positions: VertexBuffer::new(context)?,
+            colors: VertexBuffer::new(context)?,
+            cpu_positions: Vec::new(),
+            cpu_colors: Vec::new(),
+            is_updated: true,
+            depth_test: true,
+        })
+    }
+
+    ///
+    /// Removes all lines.
+    ///
+    pub fn clear(&mut self) {
+        self.cpu_positions.clear();
+        self.cpu_colors.clear();
+        self.is_updated = false;
+    }
+
+    ///
+    /// Adds a line segment between the two given points.
+    ///
+    pub fn add_line(&mut self, start: &Vec3, end: &Vec3, color: &Vec4) {
+        self.cpu_positions.extend(&start.to_slice());
+        self.cpu_positions.extend(&end.to_slice());
+        self.cpu_colors.extend(&color.to_slice());
+        self.cpu_colors.extend(&color.to_slice());
+        self.is_updated = false;
+    }
+
+    ///
+    /// Adds the twelve edges of the given axis aligned bounding box,
+    /// for example the bounding box of a geometry given by [Geometry::aabb](crate::Geometry::aabb).
+    ///
+    pub fn add_aabb(&mut self, aabb: &AxisAlignedBoundingBox, color: &Vec4) {
+        let min = aabb.min();
+        let max = aabb.max();
+        let corners = [
+            vec3(min.x, min.y, min.z),
+            vec3(max.x, min.y, min.z),
+            vec3(max.x, max.y, min.z),
+            vec3(min.x, max.y, min.z),
+            vec3(min.x, min.y, max.z),
+            vec3(max.x, min.y, max.z),
+            vec3(max.x, max.y, max.z),
+            vec3(min.x, max.y, max.z),
+        ];
+        self.add_box(&corners, color);
+    }
+
+    ///
+    /// Adds the twelve edges of the view frustum of the given camera.
+    /// Works for both perspective and orthographic cameras, for example the [shadow camera](crate::SpotLight::shadow_camera) of a light.
+    /// If the far plane is infinitely far away, the frustum is cut off at 1000 times the distance to the near plane.
+    /// Returns an error if the view-projection matrix of the camera cannot be inverted.
+    ///
+    pub fn add_frustum(&mut self, camera: &Camera, color: &Vec4) -> Result<(), Error> {
+        let inverse = (camera.projection() * camera.view())
+            .invert()
+            .ok_or_else(|| Error::CameraError {
+                message: "Cannot add the frustum of a camera with a view-projection matrix which cannot be inverted".to_string(),
+            })?;
+        let mut corners = [vec3(0.0, 0.0, 0.0); 8];
+        let (near, far) = (camera.near_ndc(), camera.far_ndc());
+        let ndc = [
+            vec3(-1.0, -1.0, near),
+            vec3(1.0, -1.0, near),
+            vec3(1.0, 1.0, near),
+            vec3(-1.0, 1.0, near),
+            vec3(-1.0, -1.0, far),
+            vec3(1.0, -1.0, far),
+            vec3(1.0, 1.0, far),
+            vec3(-1.0, 1.0, far),
+        ];
+        for i in 0..8 {
+            let p = inverse * ndc[i].extend(1.0);
+            corners[i] = p.truncate() / p.w;
+        }
+        if camera.z_near_far().1.is_infinite() {
+            // The far corners are at infinity, so they are instead placed on the rays through the near corners
+            for i in 0..4 {
+                corners[i + 4] = camera.position() + (corners[i] - camera.position()) * 1000.0;
+            }
+        }
+        self.add_box(&corners, color);
+        Ok(())
+    }
+
+    ///
+    /// Adds three circles visualizing the sphere of influence of the given point light,
+    /// ie. the sphere outside which the attenuated light is less than 1% of the light intensity.
+    ///
+    pub fn add_point_light(&mut self, light: &PointLight, color: &Vec4) {
+        let position = light.position();
+        let radius = influence_radius(light.attenuation());
+        for normal in [
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+        ]
+        .iter()
+        {
+            self.add_circle(&position, normal, radius, color);
+        }
+    }
+
+    ///
+    /// Adds the cone of the given spot light with the given length and the frustum of its shadow camera, if it has a shadow map.
+    /// Returns an error if the frustum cannot be added, see [add_frustum](Self::add_frustum).
+    ///
+    pub fn add_spot_light(
+        &mut self,
+        light: &SpotLight,
+        length: f32,
+        color: &Vec4,
+    ) -> Result<(), Error> {
+        let position = light.position();
+        let direction = light.direction();
+        let radius = length * degrees(light.cutoff()).tan();
+        let center = position + direction * length;
+        let (tangent, bitangent) = tangents(direction);
+        for i in 0..8 {
+            let angle = radians(2.0 * std::f32::consts::PI * i as f32 / 8.0);
+            let p = center + (tangent * angle.cos() + bitangent * angle.sin()) * radius;
+            self.add_line(&position, &p, color);
+        }
+        self.add_circle(&center, &direction, radius, color);
+        if let Some(camera) = light.shadow_camera() {
+            self.add_frustum(camera, color)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Adds an arrow with the given length visualizing the direction of the given directional light, pointing towards the given position,
+    /// and the frustum of its shadow camera, if it has a shadow map.
+    /// Returns an error if the frustum cannot be added, see [add_frustum](Self::add_frustum).
+    ///
+    pub fn add_directional_light(
+        &mut self,
+        light: &DirectionalLight,
+        position: &Vec3,
+        length: f32,
+        color: &Vec4,
+    ) -> Result<(), Error> {
+        let direction = light.direction();
+        let start = position - direction * length;
+        self.add_line(&start, position, color);
+        let (tangent, bitangent) = tangents(direction);
+        let head = position - direction * 0.2 * length;
+        for side in [tangent, -tangent, bitangent, -bitangent].iter() {
+            self.add_line(position, &(head + side * 0.1 * length), color);
+        }
+        if let Some(camera) = light.shadow_camera() {
+            self.add_frustum(camera, color)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Render the lines.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&mut self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        if !self.is_updated {
+            self.positions.fill_with_dynamic(&self.cpu_positions);
+            self.colors.fill_with_dynamic(&self.cpu_colors);
+            self.is_updated = true;
+        }
+        if self.cpu_positions.is_empty() {
+            return Ok(());
+        }
+        let render_states = RenderStates {
+            depth_test: if self.depth_test {
+                DepthTestType::LessOrEqual
+            } else {
+                DepthTestType::Always
+            },
+            blend: Some(BlendParameters::TRANSPARENCY),
+            ..RenderStates::new(viewport)
+        };
+        self.program
+            .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
+        self.program
+            .use_attribute_vec3(&self.positions, "position")?;
+        self.program.use_attribute_vec4(&self.colors, "color")?;
+        self.program
+            .draw_lines(render_states, self.cpu_positions.len() as u32 / 3);
+        Ok(())
+    }
+
+    fn add_box(&mut self, corners: &[Vec3; 8], color: &Vec4) {
+        for i in 0..4 {
+            self.add_line(&corners[i], &corners[(i + 1) % 4], color);
+            self.add_line(&corners[i + 4], &corners[(i + 1) % 4 + 4], color);
+            self.add_line(&corners[i], &corners[i + 4], color);
+        }
+    }
+
+    fn add_circle(&mut self, center: &Vec3, normal: &Vec3, radius: f32, color: &Vec4) {
+        let (tangent, bitangent) = tangents(*normal);
+        let subdivisions = 32;
+        let point = |i: u32| {
+            let angle = 2.0 * std::f32::consts::PI * i as f32 / subdivisions as f32;
+            center + (tangent * angle.cos() + bitangent * angle.sin()) * radius
+        };
+        for i in 0..subdivisions {
+            self.add_line(&point(i), &point(i + 1), color);
+        }
+    }
+}
+
+fn tangents(direction: Vec3) -> (Vec3, Vec3) {
+    let up = if direction.x.abs() > 0.9 {
+        vec3(0.0, 1.0, 0.0)
+    } else {
+        vec3(1.0, 0.0, 0.0)
+    };
+    let tangent = up.cross(direction).normalize();
+    let bitangent = direction.cross(tangent).normalize();
+    (tangent, bitangent)
+}
+
+fn influence_radius((constant, linear, exponential): (f32, f32, f32)) -> f32 {
+    // Solves constant + linear * d + exponential * d^2 = 100
+    let c = constant - 100.0;
+    if exponential.abs() > 0.00001 {
+        (-linear + (linear * linear - 4.0 * exponential * c).max(0.0).sqrt()) / (2.0 * exponential)
+    } else if linear.abs() > 0.00001 {
+        -c / linear
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,455 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::frame::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// The type of transformation a [Gizmo](Gizmo) manipulates.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GizmoMode {
+    /// Arrow handles which translates along the x, y and z axes.
+    Translate,
+    /// Ring handles which rotates around the x, y and z axes.
+    Rotate,
+    /// Sphere-tipped handles which scales along the x, y and z axes.
+    Scale,
+}
+
+///
+/// One of the three handles of a [Gizmo](Gizmo).
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GizmoAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl GizmoAxis {
+    fn direction(&self) -> Vec3 {
+        match self {
+            GizmoAxis::X => vec3(1.0, 0.0, 0.0),
+            GizmoAxis::Y => vec3(0.0, 1.0, 0.0),
+            GizmoAxis::Z => vec3(0.0, 0.0, 1.0),
+        }
+    }
+
+    fn color(&self) -> Vec4 {
+        match self {
+            GizmoAxis::X => vec4(1.0, 0.0, 0.0, 1.0),
+            GizmoAxis::Y => vec4(0.0, 1.0, 0.0, 1.0),
+            GizmoAxis::Z => vec4(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            GizmoAxis::X => 0,
+            GizmoAxis::Y => 1,
+            GizmoAxis::Z => 2,
+        }
+    }
+}
+
+const AXES: [GizmoAxis; 3] = [GizmoAxis::X, GizmoAxis::Y, GizmoAxis::Z];
+
+///
+/// An editor gizmo which renders translate, rotate or scale handles at the position of an object
+/// and lets the user drag the handles with the mouse.
+/// The handles are aligned with the world axes and are always rendered on top of the rest of the scene.
+/// Use [handle_events](Gizmo::handle_events) each frame to pick and drag the handles and apply the returned transformation delta to the object,
+/// for example `mesh.transformation = delta * mesh.transformation`.
+///
+pub struct Gizmo {
+    /// The type of transformation the gizmo manipulates.
+    pub mode: GizmoMode,
+    position: Vec3,
+    size: f32,
+    arrows: [Mesh; 3],
+    rings: [Mesh; 3],
+    scale_handles: [Mesh; 3],
+    scale_tips: [Mesh; 3],
+    hovered: Option<GizmoAxis>,
+    drag: Option<(GizmoAxis, Vec3)>,
+}
+
+impl Gizmo {
+    ///
+    /// Creates a new gizmo in the given mode, where the handles have the given size in world space.
+    ///
+    pub fn new(context: &Context, mode: GizmoMode, size: f32) -> Result<Self, Error> {
+        let radius = 0.03 * size;
+        let arrow = CPUMesh::arrow(2.0 * radius, size, 16);
+        let ring = torus(size, 0.5 * radius, 64, 8);
+        let handle = CPUMesh::cylinder(radius, 0.9 * size, 16);
+        let tip = CPUMesh::sphere(2.0 * radius);
+        let mut gizmo = Self {
+            mode,
+            position: vec3(0.0, 0.0, 0.0),
+            size,
+            arrows: [
+                Mesh::new(context, &arrow)?,
+                Mesh::new(context, &arrow)?,
+                Mesh::new(context, &arrow)?,
+            ],
+            rings: [
+                Mesh::new(context, &ring)?,
+                Mesh::new(context, &ring)?,
+                Mesh::new(context, &ring)?,
+            ],
+            scale_handles: [
+                Mesh::new(context, &handle)?,
+                Mesh::new(context, &handle)?,
+                Mesh::new(context, &handle)?,
+            ],
+            scale_tips: [
+                Mesh::new(context, &tip)?,
+                Mesh::new(context, &tip)?,
+                Mesh::new(context, &tip)?,
+            ],
+            hovered: None,
+            drag: None,
+        };
+        gizmo.update_transformations();
+        Ok(gizmo)
+    }
+
+    ///
+    /// Returns the position of the gizmo in world space.
+    ///
+    pub fn position(&self) -> &Vec3 {
+        &self.position
+    }
+
+    ///
+    /// Places the gizmo at the given position in world space, usually the position of the object it manipulates.
+    ///
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+        self.update_transformations();
+    }
+
+    ///
+    /// Places the gizmo at the translation part of the given transformation, usually the transformation of the object it manipulates.
+    ///
+    pub fn set_transformation(&mut self, transformation: &Mat4) {
+        self.set_position(transformation.w.truncate());
+    }
+
+    ///
+    /// Returns the handle currently hovered by the mouse, if any.
+    ///
+    pub fn hovered(&self) -> Option<GizmoAxis> {
+        self.hovered
+    }
+
+    ///
+    /// Returns whether or not a handle is currently being dragged.
+    ///
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    ///
+    /// Picks and drags the handles of the gizmo using the mouse events in the frame input.
+    /// The events that are consumed by the gizmo are marked as handled, so that for example camera controls can ignore them.
+    /// Returns the transformation delta caused by dragging a handle since the last call, or `None` if no handle was dragged.
+    ///
+    pub fn handle_events(&mut self, camera: &Camera, frame_input: &mut FrameInput) -> Option<Mat4> {
+        let window_size = (
+            frame_input.window_width as f64,
+            frame_input.window_height as f64,
+        );
+        let mut delta: Option<Mat4> = None;
+        for event in frame_input.events.iter_mut() {
+            match event {
+                Event::MouseClick {
+                    state,
+                    button,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if *handled || *button != MouseButton::Left {
+                        continue;
+                    }
+                    let screen_coordinates = screen_coordinates(*position, window_size);
+                    match state {
+                        State::Pressed => {
+                            self.hovered = self.pick(camera, screen_coordinates);
+                            if let Some(axis) = self.hovered {
+                                if let Some(point) =
+                                    self.drag_point(camera, screen_coordinates, axis)
+                                {
+                                    self.drag = Some((axis, point));
+                                    *handled = true;
+                                }
+                            }
+                        }
+                        State::Released => {
+                            if self.drag.take().is_some() {
+                                *handled = true;
+                            }
+                        }
+                    }
+                }
+                Event::MouseMotion {
+                    position, handled, ..
+                } => {
+                    if *handled {
+                        continue;
+                    }
+                    let screen_coordinates = screen_coordinates(*position, window_size);
+                    if let Some((axis, last_point)) = self.drag {
+                        if let Some(point) = self.drag_point(camera, screen_coordinates, axis) {
+                            let step = self.transformation_delta(axis, last_point, point);
+                            delta = Some(delta.map(|d| step * d).unwrap_or(step));
+                            if self.mode == GizmoMode::Translate {
+                                self.set_position(self.position + point - last_point);
+                            }
+                            self.drag = Some((axis, point));
+                        }
+                        *handled = true;
+                    } else {
+                        self.hovered = self.pick(camera, screen_coordinates);
+                    }
+                }
+                _ => {}
+            }
+        }
+        delta
+    }
+
+    ///
+    /// Render the gizmo on top of the rest of the scene.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        let render_states = RenderStates {
+            depth_test: DepthTestType::Always,
+            ..RenderStates::new(viewport)
+        };
+        for axis in AXES.iter() {
+            let active = self.drag.map(|(a, _)| a).or(self.hovered) == Some(*axis);
+            let color = if active {
+                vec4(1.0, 1.0, 0.0, 1.0)
+            } else {
+                axis.color()
+            };
+            let i = axis.index();
+            match self.mode {
+                GizmoMode::Translate => {
+                    self.arrows[i].render_with_color(&color, render_states, camera)?;
+                }
+                GizmoMode::Rotate => {
+                    self.rings[i].render_with_color(&color, render_states, camera)?;
+                }
+                GizmoMode::Scale => {
+                    self.scale_handles[i].render_with_color(&color, render_states, camera)?;
+                    self.scale_tips[i].render_with_color(&color, render_states, camera)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn update_transformations(&mut self) {
+        let translation = Mat4::from_translation(self.position);
+        // Orientations which rotate the x-axis onto each of the three axes
+        let orientations = [
+            Mat4::identity(),
+            Mat4::from_angle_z(degrees(90.0)),
+            Mat4::from_angle_y(degrees(-90.0)),
+        ];
+        for axis in AXES.iter() {
+            let i = axis.index();
+            let transformation = translation * orientations[i];
+            self.arrows[i].transformation = transformation;
+            self.rings[i].transformation = transformation;
+            self.scale_handles[i].transformation = transformation;
+            self.scale_tips[i].transformation =
+                translation * Mat4::from_translation(axis.direction() * 0.9 * self.size);
+        }
+    }
+
+    fn pick(&self, camera: &Camera, screen_coordinates: (f32, f32)) -> Option<GizmoAxis> {
+        let ray_origin = camera.position_at(screen_coordinates);
+        let ray_direction = camera.view_direction_at(screen_coordinates);
+        let threshold = 0.1 * self.size;
+        let mut closest: Option<(GizmoAxis, f32)> = None;
+        for axis in AXES.iter() {
+            let hit = match self.mode {
+                GizmoMode::Translate | GizmoMode::Scale => closest_point_on_axis(
+                    ray_origin,
+                    ray_direction,
+                    self.position,
+                    axis.direction(),
+                )
+                .filter(|(s, distance, _)| *s >= 0.0 && *s <= self.size && *distance < threshold)
+                .map(|(_, _, depth)| depth),
+                GizmoMode::Rotate => ray_plane_intersection(
+                    ray_origin,
+                    ray_direction,
+                    self.position,
+                    axis.direction(),
+                )
+                .filter(|(point, _)| {
+                    ((point - self.position).magnitude() - self.size).abs() < threshold
+                })
+                .map(|(_, depth)| depth),
+            };
+            if let Some(depth) = hit {
+                if closest.map(|(_, d)| depth < d).unwrap_or(true) {
+                    closest = Some((*axis, depth));
+                }
+            }
+        }
+        closest.map(|(axis, _)| axis)
+    }
+
+    fn drag_point(
+        &self,
+        camera: &Camera,
+        screen_coordinates: (f32, f32),
+        axis: GizmoAxis,
+    ) -> Option<Vec3> {
+        let ray_origin = camera.position_at(screen_coordinates);
+        let ray_direction = camera.view_direction_at(screen_coordinates);
+        match self.mode {
+            GizmoMode::Translate | GizmoMode::Scale => {
+                closest_point_on_axis(ray_origin, ray_direction, self.position, axis.direction())
+                    .map(|(s, _, _)| self.position + axis.direction() * s)
+            }
+            GizmoMode::Rotate => {
+                ray_plane_intersection(ray_origin, ray_direction, self.position, axis.direction())
+                    .map(|(point, _)| point)
+            }
+        }
+    }
+
+    fn transformation_delta(&self, axis: GizmoAxis, from: Vec3, to: Vec3) -> Mat4 {
+        let direction = axis.direction();
+        match self.mode {
+            GizmoMode::Translate => Mat4::from_translation(to - from),
+            GizmoMode::Rotate => {
+                let v0 = from - self.position;
+                let v1 = to - self.position;
+                let angle = v0.cross(v1).dot(direction).atan2(v0.dot(v1));
+                Mat4::from_translation(self.position)
+                    * Mat4::from_axis_angle(direction, radians(angle))
+                    * Mat4::from_translation(-self.position)
+            }
+            GizmoMode::Scale => {
+                let s0 = (from - self.position).dot(direction);
+                let s1 = (to - self.position).dot(direction);
+                if s0.abs() < 0.0001 {
+                    return Mat4::identity();
+                }
+                let factor = s1 / s0;
+                let scale = vec3(1.0, 1.0, 1.0) + direction * (factor - 1.0);
+                Mat4::from_translation(self.position)
+                    * Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z)
+                    * Mat4::from_translation(-self.position)
+            }
+        }
+    }
+}
+
+fn screen_coordinates(position: (f64, f64), window_size: (f64, f64)) -> (f32, f32) {
+    (
+        (position.0 / window_size.0) as f32,
+        (position.1 / window_size.1) as f32,
+    )
+}
+
+///
+/// Returns the parameter of the point on the axis closest to the ray,
+/// the distance between the ray and the axis at that point and the depth along the ray.
+///
+fn closest_point_on_axis(
+    ray_origin: Vec3,
+    ray_direction: Vec3,
+    axis_origin: Vec3,
+    axis_direction: Vec3,
+) -> Option<(f32, f32, f32)> {
+    let w = axis_origin - ray_origin;
+    let b = axis_direction.dot(ray_direction);
+    let denominator = 1.0 - b * b;
+    if denominator.abs() < 0.0001 {
+        return None;
+    }
+    let s = (b * ray_direction.dot(w) - axis_direction.dot(w)) / denominator;
+    let t = ray_direction.dot(w) + b * s;
+    if t < 0.0 {
+        return None;
+    }
+    let distance = (w + axis_direction * s - ray_direction * t).magnitude();
+    Some((s, distance, t))
+}
+
+fn ray_plane_intersection(
+    ray_origin: Vec3,
+    ray_direction: Vec3,
+    plane_origin: Vec3,
+    plane_normal: Vec3,
+) -> Option<(Vec3, f32)> {
+    let denominator = ray_direction.dot(plane_normal);
+    if denominator.abs() < 0.0001 {
+        return None;
+    }
+    let t = (plane_origin - ray_origin).dot(plane_normal) / denominator;
+    if t < 0.0 {
+        return None;
+    }
+    Some((ray_origin + ray_direction * t, t))
+}
+
+///
+/// A torus in the yz-plane, ie. around the x-axis, with the given major and minor radius.
+///
+fn torus(
+    major_radius: f32,
+    minor_radius: f32,
+    major_subdivisions: u32,
+    minor_subdivisions: u32,
+) -> CPUMesh {
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    for i in 0..major_subdivisions {
+        let major_angle = 2.0 * std::f32::consts::PI * i as f32 / major_subdivisions as f32;
+        let center = vec3(0.0, major_angle.cos(), major_angle.sin());
+        for j in 0..minor_subdivisions {
+            let minor_angle = 2.0 * std::f32::consts::PI * j as f32 / minor_subdivisions as f32;
+            let p = center * (major_radius + minor_radius * minor_angle.cos())
+                + vec3(minor_radius * minor_angle.sin(), 0.0, 0.0);
+            positions.push(p.x);
+            positions.push(p.y);
+            positions.push(p.z);
+        }
+    }
+    for i in 0..major_subdivisions {
+        let i1 = (i + 1) % major_subdivisions;
+        for j in 0..minor_subdivisions {
+            let j1 = (j + 1) % minor_subdivisions;
+            indices.push((i * minor_subdivisions + j) as u16);
+            indices.push((i1 * minor_subdivisions + j) as u16);
+            indices.push((i1 * minor_subdivisions + j1) as u16);
+
+            indices.push((i * minor_subdivisions + j) as u16);
+            indices.push((i1 * minor_subdivisions + j1) as u16);
+            indices.push((i * minor_subdivisions + j1) as u16);
+        }
+    }
+    let mut mesh = CPUMesh {
+        name: "torus".to_string(),
+        positions,
+        indices: Some(Indices::U16(indices)),
+        ..Default::default()
+    };
+    mesh.compute_normals();
+    mesh
+}
@@ -0,0 +1,116 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+
+///
+/// An infinite-looking grid in the xz-plane which follows the camera and fades out with the distance to the camera.
+/// Every [major_line_every](GridHelper::major_line_every) line is a major line drawn with the major color.
+/// Used for easily getting a sense of placement and scale in the 3D world.
+///
+pub struct GridHelper {
+    program: Program,
+    positions: VertexBuffer,
+    /// The distance between two minor lines.
+    pub cell_size: f32,
+    /// The number of minor cells between two major lines.
+    pub major_line_every: u32,
+    /// The color of the minor lines.
+    pub minor_color: Vec4,
+    /// The color of the major lines.
+    pub major_color: Vec4,
+    /// The distance from the camera at which the grid is completely faded out.
+    pub fade_distance: f32,
+    /// The y-coordinate of the grid plane.
+    pub height: f32,
+}
+
+impl GridHelper {
+    ///
+    /// Creates a new grid with the given distance between the minor lines, a major line for every 10 minor lines
+    /// and which is faded out at a distance of 100 cells from the camera.
+    ///
+    pub fn new(context: &Context, cell_size: f32) -> Result<Self, Error> {
+        let program = Program::from_source_with_preprocessor(
+            context,
+            include_str!("shaders/grid.vert"),
+            include_str!("shaders/grid.frag"),
+            &ShaderPreprocessor::new(),
+        )?;
+        let positions = VertexBuffer::new_with_static(
+            context,
+            &[
+                -1.0, 0.0, -1.0, 1.0, 0.0, -1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, -1.0, 0.0, 1.0,
+                -1.0, 0.0, -1.0,
+            ],
+        )?;
+        Ok(Self {
+            program,
+            positions,
+            cell_size,
+            major_line_every: 10,
+            minor_color: vec4(0.5, 0.5, 0.5, 0.5),
+            major_color: vec4(0.8, 0.8, 0.8, 0.8),
+            fade_distance: 100.0 * cell_size,
+            height: 0.0,
+        })
+    }
+
+    ///
+    /// Render the grid.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    /// Should be rendered after the opaque objects in the scene since the grid is transparent.
+    ///
+    pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Some(BlendParameters::TRANSPARENCY),
+            ..RenderStates::new(viewport)
+        };
+        self.program
+            .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
+        self.program.use_uniform_float("height", &self.height)?;
+        self.program
+            .use_uniform_float("fadeDistance", &self.fade_distance)?;
+        self.program
+            .use_uniform_float("cellSize", &self.cell_size)?;
+        self.program.use_uniform_float(
+            "majorCellSize",
+            &(self.cell_size * self.major_line_every.max(1) as f32),
+        )?;
+        self.program
+            .use_uniform_vec4("minorColor", &self.minor_color)?;
+        self.program
+            .use_uniform_vec4("majorColor", &self.major_color)?;
+        self.program
+            .use_attribute_vec3(&self.positions, "position")?;
+        self.program.draw_arrays(render_states, 6);
+        Ok(())
+    }
+
+    ///
+    /// Returns the given point moved to the closest intersection of two minor grid lines in the grid plane.
+    ///
+    pub fn snap(&self, point: Vec3) -> Vec3 {
+        let snap = |value: f32| (value / self.cell_size).round() * self.cell_size;
+        vec3(snap(point.x), self.height, snap(point.z))
+    }
+
+    ///
+    /// Returns the point in the grid plane under the given screen coordinates, see [Camera::view_direction_at],
+    /// snapped to the closest intersection of two minor grid lines, for example when placing objects or measuring on the grid.
+    /// Returns `None` if the grid plane is not visible at the given screen coordinates.
+    ///
+    pub fn pick(&self, camera: &Camera, screen_coordinates: (f32, f32)) -> Option<Vec3> {
+        let origin = camera.position_at(screen_coordinates);
+        let direction = camera.view_direction_at(screen_coordinates);
+        if direction.y.abs() < 0.0001 {
+            return None;
+        }
+        let t = (self.height - origin.y) / direction.y;
+        if t < 0.0 {
+            return None;
+        }
+        Some(self.snap(origin + direction * t))
+    }
+}
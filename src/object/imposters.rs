@@ -28,14 +28,11 @@ impl Imposters {
         let positions_buffer = VertexBuffer::new(&context)?;
         let uvs_buffer = VertexBuffer::new_with_static(&context, &uvs)?;
 
-        let program = Program::from_source(
+        let program = Program::from_source_with_preprocessor(
             context,
             include_str!("shaders/imposter.vert"),
-            &format!(
-                "{}{}",
-                include_str!("../core/shared.frag"),
-                include_str!("shaders/imposter.frag")
-            ),
+            include_str!("shaders/imposter.frag"),
+            &ShaderPreprocessor::new(),
         )?;
 
         let center_buffer = VertexBuffer::new(context)?;
@@ -163,12 +160,13 @@ impl Imposters {
                 rgb_equation: BlendEquationType::Add,
                 alpha_equation: BlendEquationType::Add,
             }),
-            ..Default::default()
+            cull: CullType::Back,
+            ..RenderStates::new(viewport)
         };
         self.program
             .use_uniform_int("no_views", &(NO_VIEW_ANGLES as i32))?;
         self.program
-            .use_uniform_block(camera.uniform_buffer(), "Camera");
+            .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
 
         self.program.use_texture_array(&self.texture, "tex")?;
 
@@ -181,13 +179,8 @@ impl Imposters {
             .use_attribute_vec3_divisor(&self.center_buffer, "center", 1)?;
         self.program
             .use_attribute_divisor(&self.rotation_buffer, "theta", 1)?;
-        self.program.draw_arrays_instanced(
-            render_states,
-            CullType::Back,
-            viewport,
-            6,
-            self.instance_count,
-        );
+        self.program
+            .draw_arrays_instanced(render_states, 6, self.instance_count);
         Ok(())
     }
 }
@@ -16,10 +16,11 @@ impl InstancedMeshProgram {
     ///
     /// Constructs a new shader program for rendering instanced meshes. The fragment shader can use the fragments position by adding `in vec3 pos;`,
     /// its normal by `in vec3 nor;`, its uv coordinates by `in vec2 uvs;` and its per vertex color by `in vec4 col;` to the shader source code.
+    /// The shader source is processed with a default [ShaderPreprocessor], so it can include the built-in shader chunks.
     ///
     pub fn new(context: &Context, fragment_shader_source: &str) -> Result<Self, Error> {
         Ok(Self {
-            mesh_program: MeshProgram::new_internal(context, fragment_shader_source, true)?,
+            mesh_program: MeshProgram::new_internal(context, fragment_shader_source, true, None)?,
         })
     }
 }
@@ -46,8 +47,12 @@ pub struct InstancedMesh {
     instance_buffer1: VertexBuffer,
     instance_buffer2: VertexBuffer,
     instance_buffer3: VertexBuffer,
-    pub cull: CullType,
+    /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped when rendering, which overrides the
+    /// [cull](crate::RenderStates::cull) setting of the render states if set. Not set by default.
+    pub cull: Option<CullType>,
     pub transformation: Mat4,
+    /// The render layers the mesh belongs to, see [Geometry::layer_mask].
+    pub layer_mask: u32,
 }
 
 impl InstancedMesh {
@@ -99,8 +104,9 @@ impl InstancedMesh {
             instance_buffer1: VertexBuffer::new(context)?,
             instance_buffer2: VertexBuffer::new(context)?,
             instance_buffer3: VertexBuffer::new(context)?,
-            cull: CullType::None,
+            cull: None,
             transformation: Mat4::identity(),
+            layer_mask: ALL_LAYERS,
         };
         mesh.update_transformations(transformations);
         unsafe {
@@ -118,12 +124,7 @@ impl InstancedMesh {
     /// # Errors
     /// Will return an error if the instanced mesh has no colors.
     ///
-    pub fn render_color(
-        &self,
-        render_states: RenderStates,
-        viewport: Viewport,
-        camera: &Camera,
-    ) -> Result<(), Error> {
+    pub fn render_color(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAM_PER_VERTEX_COLOR.is_none() {
                 PROGRAM_PER_VERTEX_COLOR = Some(InstancedMeshProgram::new(
@@ -133,7 +134,7 @@ impl InstancedMesh {
             }
             PROGRAM_PER_VERTEX_COLOR.as_ref().unwrap()
         };
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     ///
@@ -146,7 +147,6 @@ impl InstancedMesh {
         &self,
         color: &Vec4,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
         let program = unsafe {
@@ -159,7 +159,7 @@ impl InstancedMesh {
             PROGRAM_COLOR.as_ref().unwrap()
         };
         program.use_uniform_vec4("color", color)?;
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     ///
@@ -175,7 +175,6 @@ impl InstancedMesh {
         &self,
         texture: &impl Texture,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
         let program = unsafe {
@@ -188,7 +187,7 @@ impl InstancedMesh {
             PROGRAM_TEXTURE.as_ref().unwrap()
         };
         program.use_texture(texture, "tex")?;
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     ///
@@ -206,7 +205,6 @@ impl InstancedMesh {
         &self,
         program: &InstancedMeshProgram,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
         program.use_attribute_vec4_divisor(&self.instance_buffer1, "row1", 1)?;
@@ -214,7 +212,10 @@ impl InstancedMesh {
         program.use_attribute_vec4_divisor(&self.instance_buffer3, "row3", 1)?;
 
         program.use_uniform_mat4("modelMatrix", &self.transformation)?;
-        program.use_uniform_block(camera.uniform_buffer(), "Camera");
+        program.use_uniform_block(
+            camera.uniform_buffer_for_viewport(render_states.viewport)?,
+            "Camera",
+        )?;
 
         program.use_attribute_vec3(&self.position_buffer, "position")?;
         if program.mesh_program.use_uvs {
@@ -242,17 +243,13 @@ impl InstancedMesh {
 
         if let Some(ref index_buffer) = self.index_buffer {
             program.draw_elements_instanced(
-                render_states,
-                self.cull,
-                viewport,
+                render_states.override_cull(self.cull),
                 index_buffer,
                 self.instance_count,
             );
         } else {
             program.draw_arrays_instanced(
-                render_states,
-                self.cull,
-                viewport,
+                render_states.override_cull(self.cull),
                 self.position_buffer.count() as u32 / 3,
                 self.instance_count,
             );
@@ -295,7 +292,6 @@ impl Geometry for InstancedMesh {
     fn render_depth_to_red(
         &self,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
         max_depth: f32,
     ) -> Result<(), Error> {
@@ -309,28 +305,27 @@ impl Geometry for InstancedMesh {
             PROGRAM_PICK.as_ref().unwrap()
         };
         program.use_uniform_float("maxDistance", &max_depth)?;
-        self.render(program, render_states, viewport, camera)?;
+        self.render(program, render_states, camera)?;
         Ok(())
     }
 
-    fn render_depth(
-        &self,
-        render_states: RenderStates,
-        viewport: Viewport,
-        camera: &Camera,
-    ) -> Result<(), Error> {
+    fn render_depth(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAM_DEPTH.is_none() {
                 PROGRAM_DEPTH = Some(InstancedMeshProgram::new(&self.context, "void main() {}")?);
             }
             PROGRAM_DEPTH.as_ref().unwrap()
         };
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
         None // TODO: Compute bounding box
     }
+
+    fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
 }
 
 impl Drop for InstancedMesh {
@@ -0,0 +1,254 @@
+use crate::camera::*;
+use crate::core::*;
+#[cfg(feature = "sdf-text")]
+use crate::definition::*;
+use crate::math::*;
+#[cfg(feature = "sdf-text")]
+use std::cell::RefCell;
+#[cfg(feature = "sdf-text")]
+use std::collections::HashMap;
+use std::rc::Rc;
+
+///
+/// The content of a [Label].
+///
+#[derive(Clone)]
+pub enum LabelContent {
+    /// A texture, for example an icon, with the given width and height in pixels.
+    Texture {
+        texture: Rc<dyn Texture>,
+        size: Vec2,
+    },
+    /// A text with the given height in pixels, rendered with the [font](LabelLayer::set_font) of the label layer.
+    ///
+    /// # Feature
+    /// Only available when the `sdf-text` feature is enabled.
+    #[cfg(feature = "sdf-text")]
+    Text { text: String, size: f32 },
+}
+
+///
+/// A label in a [LabelLayer] which is anchored to a point in the world but rendered in screen space, ie. it has the same size regardless of the distance to the camera.
+///
+#[derive(Clone)]
+pub struct Label {
+    /// The point in world space which the label is anchored to.
+    pub position: Vec3,
+    /// The content of the label.
+    pub content: LabelContent,
+    /// The color which is multiplied with the texture or used for the text.
+    pub color: Vec4,
+    /// The point of the label which is placed at the anchor point, where (0, 0) is the bottom left corner and (1, 1) is the top right corner of the label.
+    pub alignment: Vec2,
+    /// An offset in pixels from the anchor point.
+    pub offset: Vec2,
+}
+
+impl Label {
+    ///
+    /// Constructs a new white label centered above the given point.
+    ///
+    pub fn new(position: Vec3, content: LabelContent) -> Self {
+        Self {
+            position,
+            content,
+            color: vec4(1.0, 1.0, 1.0, 1.0),
+            alignment: vec2(0.5, 0.0),
+            offset: vec2(0.0, 4.0),
+        }
+    }
+}
+
+#[cfg(feature = "sdf-text")]
+struct TextGeometry {
+    position_buffer: VertexBuffer,
+    uv_buffer: VertexBuffer,
+    index_buffer: ElementBuffer,
+    width: f32,
+}
+
+///
+/// A layer of labels, for example names of parts in a CAD model, atoms in a molecule or players in a game,
+/// which are anchored to points in the world but rendered in screen space on top of the scene.
+/// The anchor points are projected with the camera each time the labels are rendered, so the labels follow the points when the camera or the points move.
+///
+pub struct LabelLayer {
+    #[cfg(feature = "sdf-text")]
+    context: Context,
+    program: Program,
+    position_buffer: VertexBuffer,
+    uv_buffer: VertexBuffer,
+    #[cfg(feature = "sdf-text")]
+    font: Option<(SdfFont, Texture2D)>,
+    #[cfg(feature = "sdf-text")]
+    text_geometries: RefCell<HashMap<String, TextGeometry>>,
+    /// The labels in the layer.
+    pub labels: Vec<Label>,
+    /// The opacity of the labels which anchor points are hidden behind other objects, where 0 hides the labels completely.
+    /// Only used when a depth texture is given when [rendering](LabelLayer::render).
+    pub occluded_opacity: f32,
+    /// The depth difference, in the range [0..1] of the depth texture, within which an anchor point is considered visible.
+    pub depth_bias: f32,
+}
+
+impl LabelLayer {
+    ///
+    /// Constructs a new empty label layer.
+    ///
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        let program = Program::from_source_with_preprocessor(
+            context,
+            include_str!("shaders/label.vert"),
+            include_str!("shaders/label.frag"),
+            &ShaderPreprocessor::new(),
+        )?;
+        let positions = vec![
+            0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0,
+            0.0,
+        ];
+        let uvs = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0];
+        Ok(Self {
+            #[cfg(feature = "sdf-text")]
+            context: context.clone(),
+            program,
+            position_buffer: VertexBuffer::new_with_static(context, &positions)?,
+            uv_buffer: VertexBuffer::new_with_static(context, &uvs)?,
+            #[cfg(feature = "sdf-text")]
+            font: None,
+            #[cfg(feature = "sdf-text")]
+            text_geometries: RefCell::new(HashMap::new()),
+            labels: Vec::new(),
+            occluded_opacity: 0.0,
+            depth_bias: 0.0001,
+        })
+    }
+
+    ///
+    /// Sets the font used for rendering [text labels](LabelContent::Text).
+    ///
+    /// # Feature
+    /// Only available when the `sdf-text` feature is enabled.
+    ///
+    #[cfg(feature = "sdf-text")]
+    pub fn set_font(&mut self, font: SdfFont) -> Result<(), Error> {
+        let texture = Texture2D::new(&self.context, &font.texture)?;
+        self.font = Some((font, texture));
+        self.text_geometries.borrow_mut().clear();
+        Ok(())
+    }
+
+    ///
+    /// Render the labels on top of the current content of the render target.
+    /// If a depth texture containing the depth of the scene rendered with the same camera is given,
+    /// the labels which anchor points are hidden behind other objects are rendered with the [occluded opacity](LabelLayer::occluded_opacity).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if there are text labels and no [font](LabelLayer::set_font) has been set.
+    ///
+    pub fn render(
+        &self,
+        viewport: Viewport,
+        camera: &Camera,
+        depth_texture: Option<&DepthTargetTexture2D>,
+    ) -> Result<(), Error> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTestType::Always,
+            blend: Some(BlendParameters::TRANSPARENCY),
+            ..RenderStates::new(viewport)
+        };
+        self.program
+            .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
+        self.program.use_uniform_vec2(
+            "viewportSize",
+            &vec2(viewport.width as f32, viewport.height as f32),
+        )?;
+        self.program
+            .use_uniform_int("useDepthTexture", &(depth_texture.is_some() as i32))?;
+        if let Some(depth_texture) = depth_texture {
+            self.program.use_texture(depth_texture, "depthTexture")?;
+        }
+        self.program
+            .use_uniform_float("occludedOpacity", &self.occluded_opacity)?;
+        self.program
+            .use_uniform_float("depthBias", &self.depth_bias)?;
+
+        #[cfg(feature = "sdf-text")]
+        let mut used_geometries = HashMap::new();
+        for label in self.labels.iter() {
+            self.program.use_uniform_vec3("anchor", &label.position)?;
+            self.program.use_uniform_vec4("color", &label.color)?;
+            match label.content {
+                LabelContent::Texture { ref texture, size } => {
+                    self.program.use_uniform_int("isSdf", &0)?;
+                    self.program.use_texture(texture.as_ref(), "tex")?;
+                    self.program.use_uniform_vec2("size", &size)?;
+                    self.program.use_uniform_vec2(
+                        "offset",
+                        &(label.offset
+                            - vec2(label.alignment.x * size.x, label.alignment.y * size.y)),
+                    )?;
+                    self.program
+                        .use_attribute_vec3(&self.position_buffer, "position")?;
+                    self.program
+                        .use_attribute_vec2(&self.uv_buffer, "uv_coordinates")?;
+                    self.program.draw_arrays(render_states, 6);
+                }
+                #[cfg(feature = "sdf-text")]
+                LabelContent::Text { ref text, size } => {
+                    let (font, font_texture) = self.font.as_ref().ok_or(Error::FontError {
+                        message: "A font must be set before rendering text labels.".to_string(),
+                    })?;
+                    if !used_geometries.contains_key(text) {
+                        let geometry = match self.text_geometries.borrow_mut().remove(text) {
+                            Some(geometry) => geometry,
+                            None => self.text_geometry(font, text)?,
+                        };
+                        used_geometries.insert(text.clone(), geometry);
+                    }
+                    let geometry = &used_geometries[text];
+                    self.program.use_uniform_int("isSdf", &1)?;
+                    self.program.use_texture(font_texture, "tex")?;
+                    self.program.use_uniform_vec2("size", &vec2(size, size))?;
+                    // The text is one unit high, from the baseline to the top of the capital letters
+                    self.program.use_uniform_vec2(
+                        "offset",
+                        &(label.offset
+                            - vec2(
+                                label.alignment.x * geometry.width * size,
+                                label.alignment.y * size,
+                            )),
+                    )?;
+                    self.program
+                        .use_attribute_vec3(&geometry.position_buffer, "position")?;
+                    self.program
+                        .use_attribute_vec2(&geometry.uv_buffer, "uv_coordinates")?;
+                    self.program
+                        .draw_elements(render_states, &geometry.index_buffer);
+                }
+            }
+        }
+        // Keep the geometries of the texts that are still in use
+        #[cfg(feature = "sdf-text")]
+        self.text_geometries.replace(used_geometries);
+        Ok(())
+    }
+
+    #[cfg(feature = "sdf-text")]
+    fn text_geometry(&self, font: &SdfFont, text: &str) -> Result<TextGeometry, Error> {
+        let mesh = font.text_mesh(text);
+        let width = if mesh.positions.is_empty() {
+            0.0
+        } else {
+            mesh.compute_aabb().max().x
+        };
+        Ok(TextGeometry {
+            position_buffer: VertexBuffer::new_with_static(&self.context, &mesh.positions)?,
+            uv_buffer: VertexBuffer::new_with_static(&self.context, mesh.uvs.as_ref().unwrap())?,
+            index_buffer: ElementBuffer::new(&self.context, &mesh.indices.unwrap().into_u32())?,
+            width,
+        })
+    }
+}
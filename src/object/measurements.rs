@@ -0,0 +1,342 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// A measurement in a [Measurements] object.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Measurement {
+    /// The distance between two points, shown as a dimension line with the distance in the middle.
+    Distance { start: Vec3, end: Vec3 },
+    /// The angle at the vertex between the lines to the two other points, shown as an arc with the angle in degrees.
+    Angle {
+        first: Vec3,
+        vertex: Vec3,
+        second: Vec3,
+    },
+}
+
+impl Measurement {
+    ///
+    /// Returns the measured value, ie. the distance or the angle in degrees.
+    ///
+    pub fn value(&self) -> f32 {
+        match *self {
+            Measurement::Distance { start, end } => (end - start).magnitude(),
+            Measurement::Angle {
+                first,
+                vertex,
+                second,
+            } => {
+                let a = first - vertex;
+                let b = second - vertex;
+                if a.magnitude2() == 0.0 || b.magnitude2() == 0.0 {
+                    0.0
+                } else {
+                    Degrees::from(a.angle(b)).0
+                }
+            }
+        }
+    }
+
+    fn points(&self) -> Vec<Vec3> {
+        match *self {
+            Measurement::Distance { start, end } => vec![start, end],
+            Measurement::Angle {
+                first,
+                vertex,
+                second,
+            } => vec![first, vertex, second],
+        }
+    }
+}
+
+///
+/// The kind of measurement which is created when points are added with [Measurements::add_point].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeasurementMode {
+    /// Two points are measured, the start and the end.
+    Distance,
+    /// Three points are measured, the first point, the vertex and the second point.
+    Angle,
+}
+
+///
+/// Distance and angle measurements for viewer applications, rendered as dimension lines and arcs with the measured value as text on top of the scene.
+/// The points are usually picked with [Camera::pick] or [GridHelper::pick] and added one at a time:
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let font: SdfFont = unimplemented!();
+/// # let (camera, viewport, model): (Camera, Viewport, Mesh) = unimplemented!();
+/// # let screen_coordinates = (0.5, 0.5);
+/// let mut measurements = Measurements::new(&context, font).unwrap();
+/// measurements.mode = MeasurementMode::Angle;
+/// // When the user clicks:
+/// if let Some(point) = camera.pick(screen_coordinates, 100.0, &[&model]).unwrap() {
+///     measurements.add_point(measurements.snap(point, &camera, viewport, 10.0));
+/// }
+/// // Each frame after the scene is rendered:
+/// measurements.render(viewport, &camera).unwrap();
+/// ```
+///
+/// # Feature
+/// Only available when the `sdf-text` feature is enabled.
+///
+pub struct Measurements {
+    lines: DebugLines,
+    labels: LabelLayer,
+    measurements: Vec<Measurement>,
+    pending_points: Vec<Vec3>,
+    /// The kind of measurement created when points are added with [add_point](Measurements::add_point).
+    pub mode: MeasurementMode,
+    /// The color of the lines and the text.
+    pub color: Vec4,
+    /// The height of the text in pixels.
+    pub font_size: f32,
+    /// The number of decimals of the measured values.
+    pub decimals: usize,
+    /// The unit written after the measured distances, for example "m" or "mm".
+    pub unit: String,
+}
+
+impl Measurements {
+    ///
+    /// Creates a new object without measurements which uses the given font for the measured values.
+    /// The font must contain the digits, the decimal point, the characters of the [unit](Measurements::unit) and the degree sign (°).
+    ///
+    pub fn new(context: &Context, font: SdfFont) -> Result<Self, Error> {
+        let mut labels = LabelLayer::new(context)?;
+        labels.set_font(font)?;
+        let mut lines = DebugLines::new(context)?;
+        lines.depth_test = false;
+        Ok(Self {
+            lines,
+            labels,
+            measurements: Vec::new(),
+            pending_points: Vec::new(),
+            mode: MeasurementMode::Distance,
+            color: vec4(1.0, 0.8, 0.0, 1.0),
+            font_size: 16.0,
+            decimals: 2,
+            unit: String::new(),
+        })
+    }
+
+    ///
+    /// Adds a point to the measurement in progress, which is completed and returned when it has enough points for the current [mode](Measurements::mode).
+    ///
+    pub fn add_point(&mut self, point: Vec3) -> Option<Measurement> {
+        self.pending_points.push(point);
+        let p = &self.pending_points;
+        let measurement = match self.mode {
+            MeasurementMode::Distance if p.len() >= 2 => Some(Measurement::Distance {
+                start: p[0],
+                end: p[1],
+            }),
+            MeasurementMode::Angle if p.len() >= 3 => Some(Measurement::Angle {
+                first: p[0],
+                vertex: p[1],
+                second: p[2],
+            }),
+            _ => None,
+        };
+        if let Some(measurement) = measurement {
+            self.pending_points.clear();
+            self.measurements.push(measurement);
+        }
+        measurement
+    }
+
+    ///
+    /// Returns the points added to the measurement in progress.
+    ///
+    pub fn pending_points(&self) -> &[Vec3] {
+        &self.pending_points
+    }
+
+    ///
+    /// Removes the points added to the measurement in progress.
+    ///
+    pub fn cancel(&mut self) {
+        self.pending_points.clear();
+    }
+
+    ///
+    /// Adds a completed measurement.
+    ///
+    pub fn add(&mut self, measurement: Measurement) {
+        self.measurements.push(measurement);
+    }
+
+    ///
+    /// Returns the completed measurements.
+    ///
+    pub fn measurements(&self) -> &[Measurement] {
+        &self.measurements
+    }
+
+    ///
+    /// Removes and returns the measurement with the given index.
+    ///
+    pub fn remove(&mut self, index: usize) -> Measurement {
+        self.measurements.remove(index)
+    }
+
+    ///
+    /// Removes all measurements and the measurement in progress.
+    ///
+    pub fn clear(&mut self) {
+        self.measurements.clear();
+        self.pending_points.clear();
+    }
+
+    ///
+    /// Returns the point of the existing measurements, including the measurement in progress, which is closest to the given point on the screen
+    /// if it is within the given distance in pixels, otherwise the given point is returned.
+    /// Use this to measure from the exact same points as other measurements.
+    ///
+    pub fn snap(
+        &self,
+        point: Vec3,
+        camera: &Camera,
+        viewport: Viewport,
+        pixel_distance: f32,
+    ) -> Vec3 {
+        let screen_position = |p: Vec3| {
+            let clip = camera.projection() * camera.view() * p.extend(1.0);
+            if clip.w <= 0.0 {
+                None
+            } else {
+                Some(vec2(
+                    (0.5 + 0.5 * clip.x / clip.w) * viewport.width as f32,
+                    (0.5 + 0.5 * clip.y / clip.w) * viewport.height as f32,
+                ))
+            }
+        };
+        let target = match screen_position(point) {
+            Some(target) => target,
+            None => return point,
+        };
+        self.measurements
+            .iter()
+            .flat_map(|m| m.points())
+            .chain(self.pending_points.iter().cloned())
+            .filter_map(|p| screen_position(p).map(|s| (p, (s - target).magnitude())))
+            .filter(|(_, distance)| *distance <= pixel_distance)
+            .fold(
+                None,
+                |closest: Option<(Vec3, f32)>, candidate| match closest {
+                    Some(closest) if closest.1 <= candidate.1 => Some(closest),
+                    _ => Some(candidate),
+                },
+            )
+            .map_or(point, |(p, _)| p)
+    }
+
+    ///
+    /// Render the measurements and the points of the measurement in progress on top of the current content of the render target.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&mut self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        self.lines.clear();
+        self.labels.labels.clear();
+        for measurement in self.measurements.clone().iter() {
+            match *measurement {
+                Measurement::Distance { start, end } => {
+                    self.add_dimension_line(start, end, camera);
+                    let text = format!("{:.*}{}", self.decimals, measurement.value(), self.unit);
+                    self.add_label(0.5 * (start + end), text);
+                }
+                Measurement::Angle {
+                    first,
+                    vertex,
+                    second,
+                } => {
+                    self.lines.add_line(&vertex, &first, &self.color);
+                    self.lines.add_line(&vertex, &second, &self.color);
+                    if let Some(label_position) = self.add_arc(first, vertex, second) {
+                        let text = format!("{:.*}°", self.decimals, measurement.value());
+                        self.add_label(label_position, text);
+                    }
+                }
+            }
+        }
+        for i in 0..self.pending_points.len() {
+            let point = self.pending_points[i];
+            self.add_cross(point, camera);
+            if i > 0 {
+                self.lines
+                    .add_line(&self.pending_points[i - 1], &point, &self.color);
+            }
+        }
+        self.lines.render(viewport, camera)?;
+        self.labels.render(viewport, camera, None)
+    }
+
+    fn add_label(&mut self, position: Vec3, text: String) {
+        let mut label = Label::new(
+            position,
+            LabelContent::Text {
+                text,
+                size: self.font_size,
+            },
+        );
+        label.color = self.color;
+        self.labels.labels.push(label);
+    }
+
+    // Adds a line between the points with ticks at the ends perpendicular to the line and the view direction
+    fn add_dimension_line(&mut self, start: Vec3, end: Vec3, camera: &Camera) {
+        self.lines.add_line(&start, &end, &self.color);
+        let direction = end - start;
+        let tick = direction.cross(camera.view_direction());
+        if tick.magnitude2() > 0.0 {
+            let tick = tick.normalize() * 0.03 * direction.magnitude();
+            for p in [start, end].iter() {
+                self.lines.add_line(&(p - tick), &(p + tick), &self.color);
+            }
+        }
+    }
+
+    // Adds a cross at the point in the plane facing the camera with a size relative to the distance to the camera
+    fn add_cross(&mut self, point: Vec3, camera: &Camera) {
+        let size = 0.01 * (point - camera.position()).magnitude();
+        let direction = camera.view_direction();
+        let right = direction.cross(*camera.up()).normalize() * size;
+        let up = right.cross(direction).normalize() * size;
+        self.lines
+            .add_line(&(point - right), &(point + right), &self.color);
+        self.lines
+            .add_line(&(point - up), &(point + up), &self.color);
+    }
+
+    // Adds an arc between the lines from the vertex to the points and returns the position of the label at the middle of the arc
+    fn add_arc(&mut self, first: Vec3, vertex: Vec3, second: Vec3) -> Option<Vec3> {
+        let a = first - vertex;
+        let b = second - vertex;
+        let normal = a.cross(b);
+        if normal.magnitude2() == 0.0 {
+            return None;
+        }
+        let radius = 0.3 * a.magnitude().min(b.magnitude());
+        let angle = a.angle(b);
+        let rotation_axis = normal.normalize();
+        let start = a.normalize() * radius;
+        let point =
+            |factor: f32| vertex + Mat3::from_axis_angle(rotation_axis, angle * factor) * start;
+        let subdivisions = 16;
+        for i in 0..subdivisions {
+            let p0 = point(i as f32 / subdivisions as f32);
+            let p1 = point((i + 1) as f32 / subdivisions as f32);
+            self.lines.add_line(&p0, &p1, &self.color);
+        }
+        Some(point(0.5))
+    }
+}
@@ -13,21 +13,24 @@ pub struct MeshProgram {
     pub(in crate::object) use_normals: bool,
     pub(in crate::object) use_uvs: bool,
     pub(in crate::object) use_colors: bool,
+    pub(in crate::object) max_joints: Option<usize>,
 }
 
 impl MeshProgram {
     ///
     /// Constructs a new shader program for rendering meshes. The fragment shader can use the fragments position in world space by adding `in vec3 pos;`,
     /// its normal by `in vec3 nor;`, its uv coordinates by `in vec2 uvs;` and its per vertex color by `in vec4 col;` to the shader source code.
+    /// The shader source is processed with a default [ShaderPreprocessor], so it can include the built-in shader chunks, for example `#include "shared"`.
     ///
     pub fn new(context: &Context, fragment_shader_source: &str) -> Result<Self, Error> {
-        Self::new_internal(context, fragment_shader_source, false)
+        Self::new_internal(context, fragment_shader_source, false, None)
     }
 
     pub(in crate::object) fn new_internal(
         context: &Context,
         fragment_shader_source: &str,
         instanced: bool,
+        max_joints: Option<usize>,
     ) -> Result<Self, Error> {
         let use_positions = fragment_shader_source.find("in vec3 pos;").is_some();
         let use_normals = fragment_shader_source.find("in vec3 nor;").is_some();
@@ -62,6 +65,16 @@ impl MeshProgram {
             } else {
                 ""
             },
+            if let Some(max_joints) = max_joints {
+                format!(
+                    "uniform mat4 jointMatrices[{}];
+                    in vec4 joints;
+                    in vec4 weights;",
+                    max_joints
+                )
+            } else {
+                "".to_string()
+            },
             if instanced {
                 "
                     mat4 transform;
@@ -73,12 +86,23 @@ impl MeshProgram {
             } else {
                 ""
             },
+            if max_joints.is_some() {
+                "mat4 skin = weights.x * jointMatrices[int(joints.x)]
+                        + weights.y * jointMatrices[int(joints.y)]
+                        + weights.z * jointMatrices[int(joints.z)]
+                        + weights.w * jointMatrices[int(joints.w)];
+                    local2World *= skin;"
+            } else {
+                ""
+            },
             if use_positions {
                 "pos = worldPosition.xyz;"
             } else {
                 ""
             },
-            if use_normals {
+            if use_normals && max_joints.is_some() {
+                "nor = mat3(normalMatrix) * mat3(skin) * normal;"
+            } else if use_normals {
                 "nor = mat3(normalMatrix) * normal;"
             } else {
                 ""
@@ -87,12 +111,18 @@ impl MeshProgram {
             if use_colors { "col = color;" } else { "" }
         );
 
-        let program = Program::from_source(context, vertex_shader_source, fragment_shader_source)?;
+        let program = Program::from_source_with_preprocessor(
+            context,
+            vertex_shader_source,
+            fragment_shader_source,
+            &ShaderPreprocessor::new(),
+        )?;
         Ok(Self {
             program,
             use_normals,
             use_uvs,
             use_colors,
+            max_joints,
         })
     }
 }
@@ -105,21 +135,49 @@ impl std::ops::Deref for MeshProgram {
     }
 }
 
+///
+/// The appearance of the shapes when rendering a mesh with a signed distance field texture using [Mesh::render_with_sdf].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SdfStyle {
+    /// The color inside the shapes.
+    pub color: Vec4,
+    /// The color of the outline around the shapes.
+    pub outline_color: Vec4,
+    /// The width of the outline, where 0 is no outline and 0.5 is the full spread of the signed distance field.
+    pub outline_width: f32,
+}
+
+impl Default for SdfStyle {
+    fn default() -> Self {
+        Self {
+            color: vec4(1.0, 1.0, 1.0, 1.0),
+            outline_color: vec4(0.0, 0.0, 0.0, 1.0),
+            outline_width: 0.0,
+        }
+    }
+}
+
 ///
 /// A triangle mesh which can be rendered with one of the default render functions or with a custom [MeshProgram](MeshProgram).
 /// See also [PhongMesh](crate::PhongMesh) for rendering a mesh with lighting.
 ///
 pub struct Mesh {
     context: Context,
-    position_buffer: Rc<VertexBuffer>,
-    normal_buffer: Option<Rc<VertexBuffer>>,
+    vertex_buffers: VertexBuffers,
+    vertex_count: u32,
     index_buffer: Option<Rc<ElementBuffer>>,
-    uv_buffer: Option<Rc<VertexBuffer>>,
-    color_buffer: Option<Rc<VertexBuffer>>,
     aabb: AxisAlignedBoundingBox,
     pub name: String,
-    pub cull: CullType,
+    /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped when rendering, which overrides the
+    /// [cull](crate::RenderStates::cull) setting of the render states if set. Not set by default.
+    pub cull: Option<CullType>,
     pub transformation: Mat4,
+    /// The type of primitives that the vertices, or the indices if the mesh has indices, are assembled into.
+    /// Triangles by default, but can for example be set to a triangle strip for rendering strip geometry without converting it.
+    pub primitive_type: PrimitiveType,
+    /// The render layers the mesh belongs to, see [Geometry::layer_mask].
+    pub layer_mask: u32,
 }
 
 impl Mesh {
@@ -134,15 +192,6 @@ impl Mesh {
         } else {
             None
         };
-        let index_buffer = if let Some(ref indices) = cpu_mesh.indices {
-            Some(Rc::new(match indices {
-                Indices::U8(ind) => ElementBuffer::new(context, ind)?,
-                Indices::U16(ind) => ElementBuffer::new(context, ind)?,
-                Indices::U32(ind) => ElementBuffer::new(context, ind)?,
-            }))
-        } else {
-            None
-        };
         let uv_buffer = if let Some(ref uvs) = cpu_mesh.uvs {
             Some(Rc::new(VertexBuffer::new_with_static(context, uvs)?))
         } else {
@@ -153,23 +202,157 @@ impl Mesh {
         } else {
             None
         };
+        Self::new_with_buffers(
+            context,
+            cpu_mesh,
+            VertexBuffers::Separate {
+                position_buffer,
+                normal_buffer,
+                uv_buffer,
+                color_buffer,
+            },
+        )
+    }
+
+    ///
+    /// Copies the per vertex data defined in the given [CPUMesh](crate::CPUMesh) to the GPU like [new](Mesh::new),
+    /// except that all the per vertex data is interleaved in a single vertex buffer (see [VertexLayout](crate::VertexLayout)).
+    /// This reduces the number of buffers that needs to be bound when rendering and improves cache locality
+    /// when all of the per vertex data is used, but is inefficient if only a few of the attributes are used.
+    ///
+    pub fn new_interleaved(context: &Context, cpu_mesh: &CPUMesh) -> Result<Self, Error> {
+        let mut layout = VertexLayout::new();
+        layout.push("position", 3);
+        if cpu_mesh.normals.is_some() {
+            layout.push("normal", 3);
+        }
+        if cpu_mesh.uvs.is_some() {
+            layout.push("uv_coordinates", 2);
+        }
+        if cpu_mesh.colors.is_some() {
+            layout.push("color", 4);
+        }
+        let vertex_count = cpu_mesh.positions.len() / 3;
+        let mut data = Vec::with_capacity(vertex_count * layout.stride as usize);
+        for i in 0..vertex_count {
+            data.extend(&cpu_mesh.positions[i * 3..i * 3 + 3]);
+            if let Some(ref normals) = cpu_mesh.normals {
+                data.extend(&normals[i * 3..i * 3 + 3]);
+            }
+            if let Some(ref uvs) = cpu_mesh.uvs {
+                data.extend(&uvs[i * 2..i * 2 + 2]);
+            }
+            if let Some(ref colors) = cpu_mesh.colors {
+                data.extend(colors[i * 4..i * 4 + 4].iter().map(|c| *c as f32));
+            }
+        }
+        Self::new_with_buffers(
+            context,
+            cpu_mesh,
+            VertexBuffers::Interleaved {
+                buffer: Rc::new(VertexBuffer::new_with_static(context, &data)?),
+                layout: Rc::new(layout),
+            },
+        )
+    }
+
+    fn new_with_buffers(
+        context: &Context,
+        cpu_mesh: &CPUMesh,
+        vertex_buffers: VertexBuffers,
+    ) -> Result<Self, Error> {
+        let index_buffer = if let Some(ref indices) = cpu_mesh.indices {
+            Some(Rc::new(match indices {
+                Indices::U8(ind) => ElementBuffer::new(context, ind)?,
+                Indices::U16(ind) => ElementBuffer::new(context, ind)?,
+                Indices::U32(ind) => ElementBuffer::new(context, ind)?,
+            }))
+        } else {
+            None
+        };
         unsafe {
             MESH_COUNT += 1;
         }
         Ok(Mesh {
             context: context.clone(),
-            position_buffer,
-            normal_buffer,
+            vertex_buffers,
+            vertex_count: cpu_mesh.positions.len() as u32 / 3,
             index_buffer,
-            uv_buffer,
-            color_buffer,
             aabb: cpu_mesh.compute_aabb(),
             name: cpu_mesh.name.clone(),
             transformation: Mat4::identity(),
-            cull: CullType::None,
+            cull: None,
+            primitive_type: PrimitiveType::Triangles,
+            layer_mask: ALL_LAYERS,
         })
     }
 
+    ///
+    /// Updates the positions of the mesh, for example for deforming meshes, and recomputes the bounding box.
+    /// The data is written into the existing vertex buffer instead of creating a new buffer,
+    /// unless the buffer is shared with a clone of this mesh in which case this mesh gets a new buffer.
+    ///
+    /// # Errors
+    /// Will return an error if the number of positions is different from the number of vertices in the mesh
+    /// or if the mesh is [interleaved](Mesh::new_interleaved).
+    ///
+    pub fn update_positions(&mut self, positions: &[f32]) -> Result<(), Error> {
+        self.check_vertex_count(positions.len(), 3)?;
+        match self.vertex_buffers {
+            VertexBuffers::Separate {
+                ref mut position_buffer,
+                ..
+            } => update_buffer(&self.context, position_buffer, positions)?,
+            VertexBuffers::Interleaved { .. } => return Err(interleaved_update_error()),
+        }
+        self.aabb = AxisAlignedBoundingBox::new_with_positions(positions);
+        Ok(())
+    }
+
+    ///
+    /// Updates the normals of the mesh, for example after updating the [positions](Mesh::update_positions) of a deforming mesh.
+    /// The data is written into the existing vertex buffer instead of creating a new buffer,
+    /// unless the buffer is shared with a clone of this mesh in which case this mesh gets a new buffer.
+    ///
+    /// # Errors
+    /// Will return an error if the number of normals is different from the number of vertices in the mesh
+    /// or if the mesh is [interleaved](Mesh::new_interleaved).
+    ///
+    pub fn update_normals(&mut self, normals: &[f32]) -> Result<(), Error> {
+        self.check_vertex_count(normals.len(), 3)?;
+        match self.vertex_buffers {
+            VertexBuffers::Separate {
+                ref mut normal_buffer,
+                ..
+            } => {
+                if let Some(ref mut normal_buffer) = normal_buffer {
+                    update_buffer(&self.context, normal_buffer, normals)?;
+                } else {
+                    *normal_buffer = Some(Rc::new(VertexBuffer::new_with_dynamic(
+                        &self.context,
+                        normals,
+                    )?));
+                }
+            }
+            VertexBuffers::Interleaved { .. } => return Err(interleaved_update_error()),
+        }
+        Ok(())
+    }
+
+    fn check_vertex_count(&self, length: usize, size: usize) -> Result<(), Error> {
+        if length != self.vertex_count as usize * size {
+            Err(Error::MeshError {
+                message: format!(
+                    "Expected data for {} vertices, but got data for {} vertices.",
+                    self.vertex_count,
+                    length / size
+                ),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     ///
     /// Render the mesh with a color per triangle vertex. The colors are defined when constructing the mesh.
     /// Must be called in a render target render function,
@@ -178,26 +361,17 @@ impl Mesh {
     /// # Errors
     /// Will return an error if the mesh has no colors.
     ///
-    pub fn render_color(
-        &self,
-        render_states: RenderStates,
-        viewport: Viewport,
-        camera: &Camera,
-    ) -> Result<(), Error> {
+    pub fn render_color(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAM_PER_VERTEX_COLOR.is_none() {
                 PROGRAM_PER_VERTEX_COLOR = Some(MeshProgram::new(
                     &self.context,
-                    &format!(
-                        "{}{}",
-                        include_str!("../core/shared.frag"),
-                        include_str!("shaders/mesh_vertex_color.frag")
-                    ),
+                    include_str!("shaders/mesh_vertex_color.frag"),
                 )?);
             }
             PROGRAM_PER_VERTEX_COLOR.as_ref().unwrap()
         };
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     ///
@@ -209,24 +383,19 @@ impl Mesh {
         &self,
         color: &Vec4,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAM_COLOR.is_none() {
                 PROGRAM_COLOR = Some(MeshProgram::new(
                     &self.context,
-                    &format!(
-                        "{}{}",
-                        include_str!("../core/shared.frag"),
-                        include_str!("shaders/mesh_color.frag")
-                    ),
+                    include_str!("shaders/mesh_color.frag"),
                 )?);
             }
             PROGRAM_COLOR.as_ref().unwrap()
         };
         program.use_uniform_vec4("color", color)?;
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     ///
@@ -237,26 +406,17 @@ impl Mesh {
     /// # Errors
     /// Will return an error if the mesh has no uv coordinates.
     ///
-    pub fn render_uvs(
-        &self,
-        render_states: RenderStates,
-        viewport: Viewport,
-        camera: &Camera,
-    ) -> Result<(), Error> {
+    pub fn render_uvs(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAM_UVS.is_none() {
                 PROGRAM_UVS = Some(MeshProgram::new(
                     &self.context,
-                    &format!(
-                        "{}{}",
-                        include_str!("../core/shared.frag"),
-                        include_str!("shaders/mesh_uvs.frag")
-                    ),
+                    include_str!("shaders/mesh_uvs.frag"),
                 )?);
             }
             PROGRAM_UVS.as_ref().unwrap()
         };
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     ///
@@ -270,23 +430,18 @@ impl Mesh {
     pub fn render_normals(
         &self,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAM_NORMALS.is_none() {
                 PROGRAM_NORMALS = Some(MeshProgram::new(
                     &self.context,
-                    &format!(
-                        "{}{}",
-                        include_str!("../core/shared.frag"),
-                        include_str!("shaders/mesh_normals.frag")
-                    ),
+                    include_str!("shaders/mesh_normals.frag"),
                 )?);
             }
             PROGRAM_NORMALS.as_ref().unwrap()
         };
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     ///
@@ -301,24 +456,52 @@ impl Mesh {
         &self,
         texture: &impl Texture,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAM_TEXTURE.is_none() {
                 PROGRAM_TEXTURE = Some(MeshProgram::new(
                     &self.context,
-                    &format!(
-                        "{}{}",
-                        include_str!("../core/shared.frag"),
-                        include_str!("shaders/mesh_texture.frag")
-                    ),
+                    include_str!("shaders/mesh_texture.frag"),
                 )?);
             }
             PROGRAM_TEXTURE.as_ref().unwrap()
         };
         program.use_texture(texture, "tex")?;
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
+    }
+
+    ///
+    /// Render the mesh with the given [signed distance field](crate::signed_distance_field) texture, for example the texture of an [SdfFont](crate::SdfFont),
+    /// such that the edges of the shapes in the texture are crisp and antialiased at any scale.
+    /// The pixels outside the shapes are discarded and the edges are blended, so the render states should usually enable [transparency blending](BlendParameters::TRANSPARENCY).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if the mesh has no uv coordinates.
+    ///
+    pub fn render_with_sdf(
+        &self,
+        texture: &impl Texture,
+        style: &SdfStyle,
+        render_states: RenderStates,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let program = unsafe {
+            if PROGRAM_SDF.is_none() {
+                PROGRAM_SDF = Some(MeshProgram::new(
+                    &self.context,
+                    include_str!("shaders/mesh_sdf.frag"),
+                )?);
+            }
+            PROGRAM_SDF.as_ref().unwrap()
+        };
+        program.use_texture(texture, "tex")?;
+        program.use_uniform_vec4("color", &style.color)?;
+        program.use_uniform_vec4("outlineColor", &style.outline_color)?;
+        program.use_uniform_float("outlineWidth", &style.outline_width)?;
+        self.render(program, render_states, camera)
     }
 
     ///
@@ -335,55 +518,131 @@ impl Mesh {
         &self,
         program: &MeshProgram,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
         program.use_uniform_mat4("modelMatrix", &self.transformation)?;
-        program.use_uniform_block(camera.uniform_buffer(), "Camera");
-
-        program.use_attribute_vec3(&self.position_buffer, "position")?;
-        if program.use_uvs {
-            let uv_buffer = self.uv_buffer.as_ref().ok_or(Error::MeshError {
-                message:
-                    "The mesh shader program needs uv coordinates, but the mesh does not have any."
-                        .to_string(),
-            })?;
-            program.use_attribute_vec2(uv_buffer, "uv_coordinates")?;
-        }
+        program.use_uniform_block(
+            camera.uniform_buffer_for_viewport(render_states.viewport)?,
+            "Camera",
+        )?;
+
         if program.use_normals {
-            let normal_buffer = self.normal_buffer.as_ref().ok_or(
-                Error::MeshError {message: "The mesh shader program needs normals, but the mesh does not have any. Consider calculating the normals on the CPUMesh.".to_string()})?;
             program.use_uniform_mat4(
                 "normalMatrix",
                 &self.transformation.invert().unwrap().transpose(),
             )?;
-            program.use_attribute_vec3(normal_buffer, "normal")?;
         }
-        if program.use_colors {
-            let color_buffer = self.color_buffer.as_ref().ok_or(
-                Error::MeshError {message: "The mesh shader program needs per vertex colors, but the mesh does not have any.".to_string()})?;
-            program.use_attribute_vec4(color_buffer, "color")?;
+        match self.vertex_buffers {
+            VertexBuffers::Separate {
+                ref position_buffer,
+                ref normal_buffer,
+                ref uv_buffer,
+                ref color_buffer,
+            } => {
+                program.use_attribute_vec3(position_buffer, "position")?;
+                if program.use_uvs {
+                    let uv_buffer = uv_buffer.as_ref().ok_or(Error::MeshError {
+                        message: MISSING_UVS_MESSAGE.to_string(),
+                    })?;
+                    program.use_attribute_vec2(uv_buffer, "uv_coordinates")?;
+                }
+                if program.use_normals {
+                    let normal_buffer = normal_buffer.as_ref().ok_or(Error::MeshError {
+                        message: MISSING_NORMALS_MESSAGE.to_string(),
+                    })?;
+                    program.use_attribute_vec3(normal_buffer, "normal")?;
+                }
+                if program.use_colors {
+                    let color_buffer = color_buffer.as_ref().ok_or(Error::MeshError {
+                        message: MISSING_COLORS_MESSAGE.to_string(),
+                    })?;
+                    program.use_attribute_vec4(color_buffer, "color")?;
+                }
+            }
+            VertexBuffers::Interleaved {
+                ref buffer,
+                ref layout,
+            } => {
+                program.use_attribute_interleaved(buffer, layout, "position")?;
+                for (used, name, message) in [
+                    (program.use_uvs, "uv_coordinates", MISSING_UVS_MESSAGE),
+                    (program.use_normals, "normal", MISSING_NORMALS_MESSAGE),
+                    (program.use_colors, "color", MISSING_COLORS_MESSAGE),
+                ]
+                .iter()
+                {
+                    if *used {
+                        if layout.attribute(name).is_none() {
+                            return Err(Error::MeshError {
+                                message: message.to_string(),
+                            });
+                        }
+                        program.use_attribute_interleaved(buffer, layout, name)?;
+                    }
+                }
+            }
         }
 
         if let Some(ref index_buffer) = self.index_buffer {
-            program.draw_elements(render_states, self.cull, viewport, index_buffer);
+            program.draw_elements_with_primitive_type(
+                render_states.override_cull(self.cull),
+                self.primitive_type,
+                index_buffer,
+            );
         } else {
-            program.draw_arrays(
-                render_states,
-                self.cull,
-                viewport,
-                self.position_buffer.count() as u32 / 3,
+            program.draw_arrays_with_primitive_type(
+                render_states.override_cull(self.cull),
+                self.primitive_type,
+                self.vertex_count,
             );
         }
         Ok(())
     }
 }
 
+#[derive(Clone)]
+enum VertexBuffers {
+    Separate {
+        position_buffer: Rc<VertexBuffer>,
+        normal_buffer: Option<Rc<VertexBuffer>>,
+        uv_buffer: Option<Rc<VertexBuffer>>,
+        color_buffer: Option<Rc<VertexBuffer>>,
+    },
+    Interleaved {
+        buffer: Rc<VertexBuffer>,
+        layout: Rc<VertexLayout>,
+    },
+}
+
+fn update_buffer(
+    context: &Context,
+    buffer: &mut Rc<VertexBuffer>,
+    data: &[f32],
+) -> Result<(), Error> {
+    if let Some(buffer) = Rc::get_mut(buffer) {
+        buffer.update_with(0, data)
+    } else {
+        *buffer = Rc::new(VertexBuffer::new_with_dynamic(context, data)?);
+        Ok(())
+    }
+}
+
+fn interleaved_update_error() -> Error {
+    Error::MeshError {
+        message: "The vertex data of an interleaved mesh cannot be updated, construct the mesh with Mesh::new instead.".to_string(),
+    }
+}
+
+const MISSING_UVS_MESSAGE: &str =
+    "The mesh shader program needs uv coordinates, but the mesh does not have any.";
+const MISSING_NORMALS_MESSAGE: &str = "The mesh shader program needs normals, but the mesh does not have any. Consider calculating the normals on the CPUMesh.";
+const MISSING_COLORS_MESSAGE: &str =
+    "The mesh shader program needs per vertex colors, but the mesh does not have any.";
+
 impl Geometry for Mesh {
     fn render_depth_to_red(
         &self,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
         max_depth: f32,
     ) -> Result<(), Error> {
@@ -397,23 +656,18 @@ impl Geometry for Mesh {
             PROGRAM_PICK.as_ref().unwrap()
         };
         program.use_uniform_float("maxDistance", &max_depth)?;
-        self.render(program, render_states, viewport, camera)?;
+        self.render(program, render_states, camera)?;
         Ok(())
     }
 
-    fn render_depth(
-        &self,
-        render_states: RenderStates,
-        viewport: Viewport,
-        camera: &Camera,
-    ) -> Result<(), Error> {
+    fn render_depth(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAM_DEPTH.is_none() {
                 PROGRAM_DEPTH = Some(MeshProgram::new(&self.context, "void main() {}")?);
             }
             PROGRAM_DEPTH.as_ref().unwrap()
         };
-        self.render(program, render_states, viewport, camera)
+        self.render(program, render_states, camera)
     }
 
     fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
@@ -421,6 +675,10 @@ impl Geometry for Mesh {
         aabb.transform(&self.transformation);
         Some(aabb)
     }
+
+    fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
 }
 
 impl Clone for Mesh {
@@ -430,15 +688,15 @@ impl Clone for Mesh {
         }
         Self {
             context: self.context.clone(),
-            position_buffer: self.position_buffer.clone(),
-            normal_buffer: self.normal_buffer.clone(),
+            vertex_buffers: self.vertex_buffers.clone(),
+            vertex_count: self.vertex_count,
             index_buffer: self.index_buffer.clone(),
-            uv_buffer: self.uv_buffer.clone(),
-            color_buffer: self.color_buffer.clone(),
             aabb: self.aabb.clone(),
             name: self.name.clone(),
             cull: self.cull.clone(),
             transformation: self.transformation.clone(),
+            primitive_type: self.primitive_type,
+            layer_mask: self.layer_mask,
         }
     }
 }
@@ -455,6 +713,7 @@ impl Drop for Mesh {
                 PROGRAM_UVS = None;
                 PROGRAM_NORMALS = None;
                 PROGRAM_PER_VERTEX_COLOR = None;
+                PROGRAM_SDF = None;
             }
         }
     }
@@ -467,4 +726,5 @@ static mut PROGRAM_UVS: Option<MeshProgram> = None;
 static mut PROGRAM_NORMALS: Option<MeshProgram> = None;
 static mut PROGRAM_PICK: Option<MeshProgram> = None;
 static mut PROGRAM_PER_VERTEX_COLOR: Option<MeshProgram> = None;
+static mut PROGRAM_SDF: Option<MeshProgram> = None;
 static mut MESH_COUNT: u32 = 0;
@@ -0,0 +1,300 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::effect::*;
+use crate::frame::*;
+use crate::math::*;
+
+///
+/// The corner of the screen a [Minimap] is placed in.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MinimapCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+///
+/// A minimap or picture-in-picture view, which renders the scene from a secondary camera into a texture
+/// and composites it into a corner of the screen with a border.
+/// The projection of the secondary camera always has the aspect ratio of the minimap, so the scene is not stretched.
+/// Optionally, a click in the minimap can be used to teleport, see [handle_events](Minimap::handle_events).
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let (mut frame_input, mut camera, terrain): (FrameInput, Camera, Mesh) = unimplemented!();
+/// let top_down = Camera::new_orthographic(&context, vec3(0.0, 100.0, 0.0), vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0), 200.0, 200.0, 200.0).unwrap();
+/// let mut minimap = Minimap::new(&context, top_down, 256, 256).unwrap();
+/// // Each frame:
+/// if let Some(point) = minimap.handle_events(&mut frame_input, 200.0, &[&terrain]).unwrap() {
+///     let offset = point - *camera.target();
+///     camera.set_view(*camera.position() + offset, point, *camera.up()).unwrap();
+/// }
+/// minimap.update(|viewport, camera| terrain.render_with_color(&vec4(0.2, 0.6, 0.2, 1.0), RenderStates::new(viewport), camera)).unwrap();
+/// Screen::write(&context, ClearState::default(), || {
+///     // Render the scene from the main camera ...
+///     minimap.render(frame_input.viewport)
+/// }).unwrap();
+/// ```
+///
+pub struct Minimap {
+    context: Context,
+    camera: Camera,
+    color_texture: ColorTargetTexture2D<u8>,
+    depth_texture: DepthTargetTexture2D,
+    effect: ImageEffect,
+    /// The corner of the screen the minimap is placed in.
+    pub corner: MinimapCorner,
+    /// The distance in physical pixels between the minimap and the edges of the screen.
+    pub margin: u32,
+    /// The width in physical pixels of the border around the minimap.
+    pub border_width: u32,
+    /// The color of the border around the minimap.
+    pub border_color: Vec4,
+    /// The color of the background of the minimap.
+    pub clear_color: Vec4,
+}
+
+impl Minimap {
+    ///
+    /// Creates a new minimap showing the view of the given camera, which has the given size in physical pixels on the screen.
+    ///
+    pub fn new(context: &Context, camera: Camera, width: u32, height: u32) -> Result<Self, Error> {
+        let (color_texture, depth_texture) = Self::new_textures(context, width, height)?;
+        let mut minimap = Self {
+            context: context.clone(),
+            camera,
+            color_texture,
+            depth_texture,
+            effect: ImageEffect::new(context, include_str!("shaders/minimap.frag"))?,
+            corner: MinimapCorner::TopRight,
+            margin: 10,
+            border_width: 2,
+            border_color: vec4(1.0, 1.0, 1.0, 1.0),
+            clear_color: vec4(0.0, 0.0, 0.0, 1.0),
+        };
+        minimap.update_camera()?;
+        Ok(minimap)
+    }
+
+    ///
+    /// Returns the camera the scene is rendered from in the minimap.
+    ///
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    ///
+    /// Returns the camera the scene is rendered from in the minimap, for example to follow the player.
+    /// Note that the aspect ratio of the projection is set when the minimap is [updated](Self::update).
+    ///
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    ///
+    /// Returns the size of the minimap in physical pixels.
+    ///
+    pub fn size(&self) -> (u32, u32) {
+        (self.color_texture.width(), self.color_texture.height())
+    }
+
+    ///
+    /// Sets the size of the minimap in physical pixels.
+    ///
+    pub fn set_size(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        if (width, height) != self.size() {
+            let (color_texture, depth_texture) = Self::new_textures(&self.context, width, height)?;
+            self.color_texture = color_texture;
+            self.depth_texture = depth_texture;
+            self.update_camera()?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the region of the screen with the given viewport covered by the minimap, including the border.
+    ///
+    pub fn viewport(&self, screen_viewport: Viewport) -> Viewport {
+        let (width, height) = self.size();
+        let left = screen_viewport.x + self.margin as i32;
+        let right = screen_viewport.x + screen_viewport.width as i32 - (width + self.margin) as i32;
+        let bottom = screen_viewport.y + self.margin as i32;
+        let top = screen_viewport.y + screen_viewport.height as i32 - (height + self.margin) as i32;
+        let (x, y) = match self.corner {
+            MinimapCorner::TopLeft => (left, top),
+            MinimapCorner::TopRight => (right, top),
+            MinimapCorner::BottomLeft => (left, bottom),
+            MinimapCorner::BottomRight => (right, bottom),
+        };
+        Viewport {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    ///
+    /// Renders the scene into the minimap. The `render` closure is called with the viewport and the camera of the minimap
+    /// and should render the objects that are visible in the minimap.
+    /// Must be called outside of a render target render function, for example before [Screen::write](crate::Screen::write).
+    ///
+    pub fn update<F: FnOnce(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        render: F,
+    ) -> Result<(), Error> {
+        self.update_camera()?;
+        let (width, height) = self.size();
+        let camera = &self.camera;
+        RenderTarget::new(&self.context, &self.color_texture, &self.depth_texture)?.write(
+            ClearState::color_and_depth(
+                self.clear_color.x,
+                self.clear_color.y,
+                self.clear_color.z,
+                self.clear_color.w,
+                1.0,
+            ),
+            || render(Viewport::new_at_origo(width, height), camera),
+        )
+    }
+
+    ///
+    /// Composites the minimap with its border into its corner of the screen with the given viewport.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&self, screen_viewport: Viewport) -> Result<(), Error> {
+        let (width, height) = self.size();
+        self.effect.use_texture(&self.color_texture, "colorMap")?;
+        self.effect
+            .use_uniform_vec2("size", &vec2(width as f32, height as f32))?;
+        self.effect
+            .use_uniform_float("borderWidth", &(self.border_width as f32))?;
+        self.effect
+            .use_uniform_vec4("borderColor", &self.border_color)?;
+        self.effect.apply(RenderStates {
+            depth_test: DepthTestType::Always,
+            write_mask: WriteMask::COLOR,
+            blend: Some(BlendParameters::TRANSPARENCY),
+            ..RenderStates::new(self.viewport(screen_viewport))
+        })
+    }
+
+    ///
+    /// Returns whether the given position in logical pixels, for example the position of a mouse [event](crate::Event), is inside the minimap.
+    ///
+    pub fn is_inside(&self, frame_input: &FrameInput, logical_position: (f64, f64)) -> bool {
+        is_inside(
+            frame_input.screen_coordinates(logical_position, self.viewport(frame_input.viewport)),
+        )
+    }
+
+    ///
+    /// Handles clicks with the left mouse button inside the minimap, which are marked as handled, so that for example camera controls can ignore them.
+    /// If a click hits one of the given objects as seen from the camera of the minimap, the clicked position in world space is returned,
+    /// which can for example be used to teleport the player or move the main camera, see [Camera::pick].
+    ///
+    pub fn handle_events(
+        &self,
+        frame_input: &mut FrameInput,
+        max_depth: f32,
+        objects: &[&dyn Geometry],
+    ) -> Result<Option<Vec3>, Error> {
+        let viewport = self.viewport(frame_input.viewport);
+        // The events are borrowed mutably below, so the position conversion cannot use the frame input
+        let screen_height = frame_input.viewport.height;
+        let device_pixel_ratio = frame_input.device_pixel_ratio;
+        let mut clicked = None;
+        for event in frame_input.events.iter_mut() {
+            if let Event::MouseClick {
+                state,
+                button,
+                position,
+                handled,
+                ..
+            } = event
+            {
+                if *handled || *button != MouseButton::Left {
+                    continue;
+                }
+                let (u, v) =
+                    screen_coordinates(*position, viewport, screen_height, device_pixel_ratio);
+                if is_inside((u, v)) {
+                    *handled = true;
+                    if *state == State::Pressed {
+                        clicked = Some((u, v));
+                    }
+                }
+            }
+        }
+        if let Some(screen_coordinates) = clicked {
+            self.camera.pick(screen_coordinates, max_depth, objects)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn update_camera(&mut self) -> Result<(), Error> {
+        let (width, height) = self.size();
+        self.camera.set_aspect(width as f32 / height as f32)?;
+        self.camera
+            .set_viewport(Viewport::new_at_origo(width, height))
+    }
+
+    fn new_textures(
+        context: &Context,
+        width: u32,
+        height: u32,
+    ) -> Result<(ColorTargetTexture2D<u8>, DepthTargetTexture2D), Error> {
+        Ok((
+            ColorTargetTexture2D::new(
+                context,
+                width,
+                height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )?,
+            DepthTargetTexture2D::new(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )?,
+        ))
+    }
+}
+
+fn is_inside(screen_coordinates: (f32, f32)) -> bool {
+    let (u, v) = screen_coordinates;
+    u >= 0.0 && u <= 1.0 && v >= 0.0 && v <= 1.0
+}
+
+///
+/// Same as [FrameInput::screen_coordinates] without borrowing the frame input.
+///
+fn screen_coordinates(
+    logical_position: (f64, f64),
+    viewport: Viewport,
+    screen_height: u32,
+    device_pixel_ratio: f64,
+) -> (f32, f32) {
+    let (x, y) = (
+        logical_position.0 * device_pixel_ratio,
+        logical_position.1 * device_pixel_ratio,
+    );
+    let top = screen_height as f64 - (viewport.y as f64 + viewport.height as f64);
+    (
+        ((x - viewport.x as f64) / viewport.width as f64) as f32,
+        ((y - top) / viewport.height as f64) as f32,
+    )
+}
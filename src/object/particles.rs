@@ -5,7 +5,8 @@ use crate::math::*;
 
 ///
 /// Shader program used for rendering [Particles](Particles).
-/// The fragment shader code can use position (`in vec3 pos;`) normal (`in vec3 nor;`) and uv coordinates (`in vec2 uvs;`).
+/// The fragment shader code can use position (`in vec3 pos;`) normal (`in vec3 nor;`) and uv coordinates (`in vec2 uvs;`)
+/// and can include the built-in shader chunks, since it is processed with a default [ShaderPreprocessor].
 ///
 pub struct ParticlesProgram {
     program: Program,
@@ -19,14 +20,7 @@ impl ParticlesProgram {
         let use_normals = fragment_shader_source.find("in vec3 nor;").is_some();
         let use_uvs = fragment_shader_source.find("in vec2 uvs;").is_some();
         let vertex_shader_source = &format!("
-                layout (std140) uniform Camera
-                {{
-                    mat4 viewProjection;
-                    mat4 view;
-                    mat4 projection;
-                    vec3 position;
-                    float padding;
-                }} camera;
+                #include \"camera\"
 
                 uniform float time;
                 uniform vec3 acceleration;
@@ -65,7 +59,12 @@ impl ParticlesProgram {
                 if use_uvs { "uvs = uv_coordinates;" } else {""}
         );
 
-        let program = Program::from_source(context, vertex_shader_source, fragment_shader_source)?;
+        let program = Program::from_source_with_preprocessor(
+            context,
+            vertex_shader_source,
+            fragment_shader_source,
+            &ShaderPreprocessor::new(),
+        )?;
         Ok(Self {
             program,
             use_normals,
@@ -106,7 +105,9 @@ pub struct Particles {
     index_buffer: Option<ElementBuffer>,
     pub acceleration: Vec3,
     instance_count: u32,
-    pub cull: CullType,
+    /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped when rendering, which overrides the
+    /// [cull](crate::RenderStates::cull) setting of the render states if set. Not set by default.
+    pub cull: Option<CullType>,
     pub transformation: Mat4,
 }
 
@@ -142,7 +143,7 @@ impl Particles {
             start_velocity_buffer: VertexBuffer::new(context)?,
             acceleration: *acceleration,
             instance_count: 0,
-            cull: CullType::None,
+            cull: None,
             transformation: Mat4::identity(),
         })
     }
@@ -179,14 +180,16 @@ impl Particles {
         &self,
         program: &ParticlesProgram,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
         time: f32,
     ) -> Result<(), Error> {
         program.use_uniform_mat4("modelMatrix", &self.transformation)?;
         program.use_uniform_vec3("acceleration", &self.acceleration)?;
         program.use_uniform_float("time", &time)?;
-        program.use_uniform_block(camera.uniform_buffer(), "Camera");
+        program.use_uniform_block(
+            camera.uniform_buffer_for_viewport(render_states.viewport)?,
+            "Camera",
+        )?;
 
         program.use_attribute_vec3_divisor(&self.start_position_buffer, "start_position", 1)?;
         program.use_attribute_vec3_divisor(&self.start_velocity_buffer, "start_velocity", 1)?;
@@ -208,17 +211,13 @@ impl Particles {
 
         if let Some(ref index_buffer) = self.index_buffer {
             program.draw_elements_instanced(
-                render_states,
-                self.cull,
-                viewport,
+                render_states.override_cull(self.cull),
                 index_buffer,
                 self.instance_count,
             );
         } else {
             program.draw_arrays_instanced(
-                render_states,
-                self.cull,
-                viewport,
+                render_states.override_cull(self.cull),
                 self.position_buffer.count() as u32 / 3,
                 self.instance_count,
             );
@@ -0,0 +1,251 @@
+use crate::camera::*;
+use crate::context::consts;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// A planar mirror, for example a mirror on a wall, a polished floor or a calm water surface, which reflects the scene correctly
+/// as opposed to a reflection from a cube map, which is only correct for objects far away from the reflecting surface.
+/// Each frame, the scene is rendered into a texture from the main camera mirrored in the plane of the mirror,
+/// where the objects behind the mirror are clipped by an oblique near plane, see [Camera::set_clip_plane].
+/// The mirror is then rendered with a material which looks up the reflection at the position of each fragment on the screen.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let (frame_input, camera, scene): (FrameInput, Camera, Mesh) = unimplemented!();
+/// let mut mirror = PlanarReflection::new(&context, vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), 10.0, 10.0).unwrap();
+/// // Each frame before rendering to the screen:
+/// mirror.update(&camera, frame_input.viewport, |viewport, camera| {
+///     scene.render_with_color(&vec4(1.0, 0.0, 0.0, 1.0), RenderStates::new(viewport), camera)
+/// }).unwrap();
+/// Screen::write(&context, ClearState::default(), || {
+///     scene.render_with_color(&vec4(1.0, 0.0, 0.0, 1.0), RenderStates::new(frame_input.viewport), &camera)?;
+///     mirror.render(RenderStates::new(frame_input.viewport), &camera)
+/// }).unwrap();
+/// ```
+///
+pub struct PlanarReflection {
+    context: Context,
+    point: Vec3,
+    normal: Vec3,
+    mesh: Mesh,
+    program: MeshProgram,
+    camera: Camera,
+    color_texture: Option<ColorTargetTexture2D<u8>>,
+    depth_texture: Option<DepthTargetTexture2D>,
+    /// The color of the mirror which is mixed with the reflection according to the [reflectivity](Self::reflectivity).
+    pub color: Vec4,
+    /// The color the reflection is multiplied with, for example a slightly blue tint for water.
+    pub tint: Vec4,
+    /// How much of the reflection is visible, between 0 (only the [color](Self::color) is visible) and 1 (a perfect mirror).
+    pub reflectivity: f32,
+    /// The resolution of the reflection texture relative to the resolution of the viewport, for example 0.5 for half the resolution.
+    pub resolution_scale: f32,
+    /// The background color of the reflection where no objects are reflected.
+    pub clear_color: Vec4,
+}
+
+impl PlanarReflection {
+    ///
+    /// Creates a new rectangular mirror centered at the given point, facing in the direction of the given normal and with the given width and height.
+    ///
+    pub fn new(
+        context: &Context,
+        point: Vec3,
+        normal: Vec3,
+        width: f32,
+        height: f32,
+    ) -> Result<Self, Error> {
+        let mut mesh = Mesh::new(context, &CPUMesh::square(1.0))?;
+        mesh.cull = Some(CullType::Back);
+        let mut planar_reflection = Self {
+            context: context.clone(),
+            point,
+            normal: normal.normalize(),
+            mesh,
+            program: MeshProgram::new(context, include_str!("shaders/planar_reflection.frag"))?,
+            camera: Camera::new_perspective(
+                context,
+                vec3(0.0, 0.0, 5.0),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                degrees(45.0),
+                1.0,
+                0.1,
+                100.0,
+            )?,
+            color_texture: None,
+            depth_texture: None,
+            color: vec4(0.0, 0.0, 0.0, 1.0),
+            tint: vec4(1.0, 1.0, 1.0, 1.0),
+            reflectivity: 1.0,
+            resolution_scale: 1.0,
+            clear_color: vec4(0.0, 0.0, 0.0, 1.0),
+        };
+        planar_reflection.set_size(width, height);
+        Ok(planar_reflection)
+    }
+
+    ///
+    /// Returns the point in world space at the center of the mirror.
+    ///
+    pub fn point(&self) -> Vec3 {
+        self.point
+    }
+
+    ///
+    /// Returns the direction in world space the mirror is facing.
+    ///
+    pub fn normal(&self) -> Vec3 {
+        self.normal
+    }
+
+    ///
+    /// Moves the mirror so that it is centered at the given point and faces in the direction of the given normal, while keeping its size.
+    ///
+    pub fn set_plane(&mut self, point: Vec3, normal: Vec3) {
+        let scale = vec3(
+            self.mesh.transformation.x.truncate().magnitude(),
+            self.mesh.transformation.y.truncate().magnitude(),
+            1.0,
+        );
+        self.point = point;
+        self.normal = normal.normalize();
+        self.update_transformation(scale);
+    }
+
+    ///
+    /// Sets the width and height of the mirror.
+    ///
+    pub fn set_size(&mut self, width: f32, height: f32) {
+        self.update_transformation(vec3(width, height, 1.0));
+    }
+
+    ///
+    /// Returns the mirrored camera used for rendering the reflection in the last [update](Self::update).
+    ///
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    ///
+    /// Returns the texture with the reflection rendered in the last [update](Self::update), if any.
+    ///
+    pub fn texture(&self) -> Option<&ColorTargetTexture2D<u8>> {
+        self.color_texture.as_ref()
+    }
+
+    ///
+    /// Renders the reflection of the scene as seen from the given camera, which must be the camera that the mirror is rendered with,
+    /// into a texture with the size of the given viewport scaled by the [resolution scale](Self::resolution_scale).
+    /// The `render` closure is called with the viewport of the texture and the mirrored camera and should render the objects that are reflected.
+    /// The winding order of the triangles is reversed while rendering the reflection, so culling works as usual.
+    /// Must be called outside of a render target render function, for example before [Screen::write](crate::Screen::write).
+    ///
+    pub fn update<F: FnOnce(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        camera: &Camera,
+        viewport: Viewport,
+        render: F,
+    ) -> Result<(), Error> {
+        let width = ((viewport.width as f32 * self.resolution_scale) as u32).max(1);
+        let height = ((viewport.height as f32 * self.resolution_scale) as u32).max(1);
+        if self
+            .color_texture
+            .as_ref()
+            .map(|t| t.width() != width || t.height() != height)
+            .unwrap_or(true)
+        {
+            self.color_texture = Some(ColorTargetTexture2D::new(
+                &self.context,
+                width,
+                height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )?);
+            self.depth_texture = Some(DepthTargetTexture2D::new(
+                &self.context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )?);
+        }
+
+        self.camera.copy_from(camera)?;
+        self.camera.mirror_in_plane(self.point, self.normal)?;
+        self.camera
+            .set_clip_plane(Some(self.normal.extend(-self.normal.dot(self.point))))?;
+        self.camera
+            .set_viewport(Viewport::new_at_origo(width, height))?;
+
+        let context = &self.context;
+        let mirrored_camera = &self.camera;
+        RenderTarget::new(
+            context,
+            self.color_texture.as_ref().unwrap(),
+            self.depth_texture.as_ref().unwrap(),
+        )?
+        .write(
+            ClearState::color_and_depth(
+                self.clear_color.x,
+                self.clear_color.y,
+                self.clear_color.z,
+                self.clear_color.w,
+                1.0,
+            ),
+            || {
+                // The mirrored view reverses the winding order of the triangles
+                context.front_face(consts::CW);
+                let result = render(Viewport::new_at_origo(width, height), mirrored_camera);
+                context.front_face(consts::CCW);
+                result
+            },
+        )
+    }
+
+    ///
+    /// Render the mirror with the reflection rendered in the last [update](Self::update), using the same camera and viewport as given to the update.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if the reflection has not been rendered by calling [update](Self::update).
+    ///
+    pub fn render(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
+        let texture = self.color_texture.as_ref().ok_or(Error::MeshError {
+            message: "The reflection must be rendered using PlanarReflection::update before the mirror is rendered.".to_string(),
+        })?;
+        self.program.use_texture(texture, "reflectionMap")?;
+        self.program.use_uniform_vec4(
+            "screenViewport",
+            &vec4(
+                render_states.viewport.x as f32,
+                render_states.viewport.y as f32,
+                render_states.viewport.width as f32,
+                render_states.viewport.height as f32,
+            ),
+        )?;
+        self.program.use_uniform_vec4("color", &self.color)?;
+        self.program.use_uniform_vec4("tint", &self.tint)?;
+        self.program
+            .use_uniform_float("reflectivity", &self.reflectivity)?;
+        self.mesh.render(&self.program, render_states, camera)
+    }
+
+    fn update_transformation(&mut self, scale: Vec3) {
+        let rotation: Mat4 =
+            Quat::from_arc(vec3(0.0, 0.0, 1.0), self.normal, Some(vec3(0.0, 1.0, 0.0))).into();
+        self.mesh.transformation = Mat4::from_translation(self.point)
+            * rotation
+            * Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z);
+    }
+}
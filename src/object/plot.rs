@@ -0,0 +1,284 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// How the points of a [PlotSeries] are drawn.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PlotStyle {
+    /// The points are connected by a line with the given width in pixels.
+    Line { width: f32 },
+    /// Each point is drawn as a dot with the given radius in pixels.
+    Scatter { radius: f32 },
+}
+
+///
+/// A series of data points in a [Plot].
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotSeries {
+    /// The data points.
+    pub points: Vec<Vec2>,
+    /// The color of the series.
+    pub color: Vec4,
+    /// How the points are drawn.
+    pub style: PlotStyle,
+}
+
+///
+/// A minimal 2D plot with line and scatter series and axes with tick labels,
+/// for example for in-app telemetry or scientific dashboards.
+/// The plot is drawn into a viewport of the current render target, which can also be a texture.
+///
+/// # Feature
+/// Only available when the `plot` feature is enabled.
+///
+pub struct Plot {
+    context: Context,
+    font: SdfFont,
+    font_texture: Texture2D,
+    /// The series of data points in the plot.
+    pub series: Vec<PlotSeries>,
+    /// The range of the x-axis, or `None` if the range is computed from the data.
+    pub x_range: Option<(f32, f32)>,
+    /// The range of the y-axis, or `None` if the range is computed from the data.
+    pub y_range: Option<(f32, f32)>,
+    /// The approximate number of ticks on each axis.
+    pub tick_count: u32,
+    /// The color of the axes, the ticks and the tick labels.
+    pub axis_color: Vec4,
+    /// The color of the background of the plot area, or `None` for a transparent background.
+    pub background_color: Option<Vec4>,
+    /// The height of the tick labels in pixels.
+    pub font_size: f32,
+}
+
+impl Plot {
+    ///
+    /// Constructs a new empty plot which uses the given font for the tick labels.
+    /// The font must contain the digits, the minus sign and the decimal point.
+    ///
+    pub fn new(context: &Context, font: SdfFont) -> Result<Self, Error> {
+        Ok(Self {
+            context: context.clone(),
+            font_texture: Texture2D::new(context, &font.texture)?,
+            font,
+            series: Vec::new(),
+            x_range: None,
+            y_range: None,
+            tick_count: 5,
+            axis_color: vec4(0.0, 0.0, 0.0, 1.0),
+            background_color: Some(vec4(1.0, 1.0, 1.0, 1.0)),
+            font_size: 14.0,
+        })
+    }
+
+    ///
+    /// Render the plot into the given viewport.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write) or [ColorTargetTexture2D::write].
+    ///
+    pub fn render(&self, viewport: Viewport) -> Result<(), Error> {
+        let (width, height) = (viewport.width as f32, viewport.height as f32);
+        let margin_left = 4.0 * self.font_size;
+        let margin_bottom = 2.5 * self.font_size;
+        let margin = 0.5 * self.font_size;
+        let area_min = vec2(margin_left, margin_bottom);
+        let area_max = vec2(width - margin, height - margin);
+        if area_max.x <= area_min.x || area_max.y <= area_min.y {
+            return Ok(());
+        }
+
+        let (x_min, x_max) = self.x_range.unwrap_or_else(|| self.data_range(|p| p.x));
+        let (y_min, y_max) = self.y_range.unwrap_or_else(|| self.data_range(|p| p.y));
+        let to_pixels = |p: Vec2| {
+            vec2(
+                area_min.x + (p.x - x_min) / (x_max - x_min) * (area_max.x - area_min.x),
+                area_min.y + (p.y - y_min) / (y_max - y_min) * (area_max.y - area_min.y),
+            )
+        };
+
+        let mut graphic = VectorGraphic::default();
+        let mut labels = Vec::new();
+        if let Some(color) = self.background_color {
+            graphic.shapes.push(VectorShape {
+                path: VectorPath::polygon(&[
+                    area_min,
+                    vec2(area_max.x, area_min.y),
+                    area_max,
+                    vec2(area_min.x, area_max.y),
+                ]),
+                fill: Some(color),
+                stroke: None,
+            });
+        }
+        let axis = |path: VectorPath| VectorShape {
+            path,
+            fill: None,
+            stroke: Some((self.axis_color, 1.0)),
+        };
+        graphic.shapes.push(axis(VectorPath::polyline(&[
+            vec2(area_min.x, area_max.y),
+            area_min,
+            vec2(area_max.x, area_min.y),
+        ])));
+        let tick_length = 0.3 * self.font_size;
+        for (value, text) in ticks(x_min, x_max, self.tick_count) {
+            let x = to_pixels(vec2(value, y_min)).x;
+            graphic.shapes.push(axis(VectorPath::polyline(&[
+                vec2(x, area_min.y),
+                vec2(x, area_min.y - tick_length),
+            ])));
+            labels.push((
+                text,
+                vec2(x, area_min.y - tick_length - self.font_size),
+                0.5,
+            ));
+        }
+        for (value, text) in ticks(y_min, y_max, self.tick_count) {
+            let y = to_pixels(vec2(x_min, value)).y;
+            graphic.shapes.push(axis(VectorPath::polyline(&[
+                vec2(area_min.x, y),
+                vec2(area_min.x - tick_length, y),
+            ])));
+            labels.push((
+                text,
+                vec2(area_min.x - 2.0 * tick_length, y - 0.35 * self.font_size),
+                1.0,
+            ));
+        }
+
+        let mut data = VectorGraphic::default();
+        for series in self.series.iter() {
+            let points: Vec<Vec2> = series.points.iter().map(|p| to_pixels(*p)).collect();
+            match series.style {
+                PlotStyle::Line { width } => data.shapes.push(VectorShape {
+                    path: VectorPath::polyline(&points),
+                    fill: None,
+                    stroke: Some((series.color, width)),
+                }),
+                PlotStyle::Scatter { radius } => {
+                    for p in points.iter() {
+                        data.shapes.push(VectorShape {
+                            path: VectorPath::circle(*p, radius),
+                            fill: Some(series.color),
+                            stroke: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        let camera = Camera::new_orthographic(
+            &self.context,
+            vec3(0.5 * width, 0.5 * height, 1.0),
+            vec3(0.5 * width, 0.5 * height, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            width,
+            height,
+            2.0,
+        )?;
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTestType::Always,
+            blend: Some(BlendParameters::TRANSPARENCY),
+            ..RenderStates::new(viewport)
+        };
+
+        let frame = graphic.to_cpu_mesh(0.25)?;
+        Mesh::new(&self.context, &frame)?.render_color(render_states, &camera)?;
+        let data = data.to_cpu_mesh(0.25)?;
+        if !data.positions.is_empty() {
+            // The lines are clipped to the plot area
+            let scissor = Viewport {
+                x: viewport.x + area_min.x as i32,
+                y: viewport.y + area_min.y as i32,
+                width: (area_max.x - area_min.x) as u32,
+                height: (area_max.y - area_min.y) as u32,
+            };
+            Mesh::new(&self.context, &data)?.render_color(
+                RenderStates {
+                    scissor: Some(scissor),
+                    ..render_states
+                },
+                &camera,
+            )?;
+        }
+
+        let label_meshes: Vec<CPUMesh> = labels
+            .iter()
+            .map(|(text, _, _)| self.font.text_mesh(text))
+            .collect();
+        let transformations: Vec<Mat4> = labels
+            .iter()
+            .zip(label_meshes.iter())
+            .map(|((_, position, alignment), mesh)| {
+                let text_width = mesh.compute_aabb().max().x * self.font_size;
+                Mat4::from_translation(vec3(position.x - alignment * text_width, position.y, 0.0))
+                    * Mat4::from_scale(self.font_size)
+            })
+            .collect();
+        if !label_meshes.is_empty() {
+            let text = Mesh::new(
+                &self.context,
+                &CPUMesh::merge_with_transformations(&label_meshes, &transformations),
+            )?;
+            text.render_with_sdf(
+                &self.font_texture,
+                &SdfStyle {
+                    color: self.axis_color,
+                    ..Default::default()
+                },
+                render_states,
+                &camera,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn data_range(&self, value: impl Fn(&Vec2) -> f32) -> (f32, f32) {
+        let (min, max) = self
+            .series
+            .iter()
+            .flat_map(|s| s.points.iter())
+            .map(|p| value(p))
+            .filter(|v| v.is_finite())
+            .fold((std::f32::MAX, std::f32::MIN), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+        if min > max {
+            (0.0, 1.0)
+        } else if max - min < std::f32::EPSILON * max.abs().max(1.0) {
+            (min - 0.5, max + 0.5)
+        } else {
+            (min, max)
+        }
+    }
+}
+
+// Returns the values and labels of the ticks in the given range with a step of 1, 2 or 5 times a power of ten
+fn ticks(min: f32, max: f32, count: u32) -> Vec<(f32, String)> {
+    let range = max - min;
+    if !(range > 0.0) || !range.is_finite() {
+        return Vec::new();
+    }
+    let rough_step = range / count.max(1) as f32;
+    let magnitude = 10.0f32.powf(rough_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|f| f * magnitude)
+        .find(|step| *step >= rough_step)
+        .unwrap_or(10.0 * magnitude);
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last)
+        .map(|i| {
+            let value = i as f32 * step;
+            (value, format!("{:.*}", decimals, value))
+        })
+        .collect()
+}
@@ -0,0 +1,272 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// A portal, ie. a rectangular opening at the entrance through which the scene is seen as if looking out of the exit,
+/// for example for teleporters or non-euclidean spaces. Both the entrance and the exit are rectangles in the xy-plane of their transformation,
+/// seen through from the positive z side.
+///
+/// The view through the portal is rendered directly to the screen, where the pixels covered by the portal are marked in the stencil buffer,
+/// so it only works when rendering to the [Screen](crate::Screen), see [RenderStates::stencil].
+/// When the exit is visible through the entrance, the portal is rendered recursively up to the [maximum recursion depth](Portal::max_recursion_depth),
+/// and the innermost portal is filled with the [recursion color](Portal::recursion_color).
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let (frame_input, camera, scene): (FrameInput, Camera, Mesh) = unimplemented!();
+/// let mut portal = Portal::new(&context, 2.0, 3.0, Mat4::from_translation(vec3(0.0, 1.5, 0.0)),
+///     Mat4::from_translation(vec3(20.0, 1.5, 0.0)) * Mat4::from_angle_y(degrees(90.0))).unwrap();
+/// Screen::write(&context, ClearState::default(), || {
+///     scene.render_with_color(&vec4(1.0, 0.0, 0.0, 1.0), RenderStates::new(frame_input.viewport), &camera)?;
+///     portal.render(frame_input.viewport, &camera, |render_states, camera| {
+///         scene.render_with_color(&vec4(1.0, 0.0, 0.0, 1.0), render_states, camera)
+///     })
+/// }).unwrap();
+/// ```
+///
+pub struct Portal {
+    context: Context,
+    mesh: Mesh,
+    width: f32,
+    height: f32,
+    entrance: Mat4,
+    exit: Mat4,
+    cameras: Vec<Camera>,
+    // Writes the depth of the far plane, which clears the depth inside the portal
+    far_depth_program: MeshProgram,
+    /// The number of times the portal is rendered when it is visible through itself, at least one to see through the portal at all.
+    pub max_recursion_depth: u32,
+    /// The color of the innermost portal when the [maximum recursion depth](Portal::max_recursion_depth) is reached.
+    pub recursion_color: Vec4,
+}
+
+impl Portal {
+    ///
+    /// Creates a new portal with the given width and height, where the entrance and the exit are placed by the given transformations.
+    ///
+    pub fn new(
+        context: &Context,
+        width: f32,
+        height: f32,
+        entrance: Mat4,
+        exit: Mat4,
+    ) -> Result<Self, Error> {
+        let mut portal = Self {
+            context: context.clone(),
+            mesh: Mesh::new(context, &CPUMesh::square(1.0))?,
+            width,
+            height,
+            entrance,
+            exit,
+            cameras: Vec::new(),
+            far_depth_program: MeshProgram::new(
+                context,
+                "uniform float farDepth;
+                layout (location = 0) out vec4 outColor;
+                void main()
+                {
+                    outColor = vec4(0.0);
+                    gl_FragDepth = farDepth;
+                }",
+            )?,
+            max_recursion_depth: 3,
+            recursion_color: vec4(0.0, 0.0, 0.0, 1.0),
+        };
+        portal.set_entrance(entrance);
+        Ok(portal)
+    }
+
+    ///
+    /// Returns the transformation of the entrance.
+    ///
+    pub fn entrance(&self) -> &Mat4 {
+        &self.entrance
+    }
+
+    ///
+    /// Moves the entrance to the given transformation.
+    ///
+    pub fn set_entrance(&mut self, entrance: Mat4) {
+        self.entrance = entrance;
+        self.mesh.transformation =
+            entrance * Mat4::from_nonuniform_scale(self.width, self.height, 1.0);
+    }
+
+    ///
+    /// Returns the transformation of the exit.
+    ///
+    pub fn exit(&self) -> &Mat4 {
+        &self.exit
+    }
+
+    ///
+    /// Moves the exit to the given transformation.
+    ///
+    pub fn set_exit(&mut self, exit: Mat4) {
+        self.exit = exit;
+    }
+
+    ///
+    /// Returns the transformation from the space in front of the entrance to the space in front of the exit,
+    /// ie. an object in front of the entrance appears in front of the exit when transformed by this transformation.
+    /// Use this to teleport objects passing through the entrance, see [crossed](Self::crossed).
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        // Looking into the front of the entrance is looking out of the front of the exit, so the exit is turned around
+        self.exit
+            * Mat4::from_angle_y(degrees(180.0))
+            * self.entrance.invert().unwrap_or(Mat4::identity())
+    }
+
+    ///
+    /// Returns whether the line segment from the first to the second position passes through the entrance from the front to the back,
+    /// for example to detect when the player moves through the portal between two frames.
+    ///
+    pub fn crossed(&self, from: Vec3, to: Vec3) -> bool {
+        let inverse = match self.entrance.invert() {
+            Some(inverse) => inverse,
+            None => return false,
+        };
+        let from = (inverse * from.extend(1.0)).truncate();
+        let to = (inverse * to.extend(1.0)).truncate();
+        if from.z < 0.0 || to.z >= 0.0 {
+            return false;
+        }
+        let p = from + (to - from) * (from.z / (from.z - to.z));
+        p.x.abs() <= 0.5 * self.width && p.y.abs() <= 0.5 * self.height
+    }
+
+    ///
+    /// Renders the view through the portal, after the rest of the scene has been rendered with the given camera and viewport.
+    /// The `render` closure is called once for each recursion level with render states that limit the rendering to the part of the screen covered
+    /// by the portal and the camera looking out of the exit, and should render the scene using these render states,
+    /// ie. `RenderStates { blend: ..., ..render_states }` if other render states are needed.
+    /// The depth of the portal is written to the depth buffer afterwards, so the portal occludes objects behind it that are rendered later.
+    /// Must be called in the render function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render<F: FnMut(RenderStates, &Camera) -> Result<(), Error>>(
+        &mut self,
+        viewport: Viewport,
+        camera: &Camera,
+        mut render: F,
+    ) -> Result<(), Error> {
+        if self.max_recursion_depth == 0 {
+            return Ok(());
+        }
+        let max_recursion_depth = self.max_recursion_depth.min(255) as usize;
+        while self.cameras.len() < max_recursion_depth {
+            self.cameras.push(Camera::new_perspective(
+                &self.context,
+                vec3(0.0, 0.0, 5.0),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                degrees(45.0),
+                1.0,
+                0.1,
+                100.0,
+            )?);
+        }
+        let transformation = self.transformation();
+        let exit_normal = (self.exit * vec4(0.0, 0.0, 1.0, 0.0))
+            .truncate()
+            .normalize();
+        let exit_position = self.exit.w.truncate();
+        let clip_plane = exit_normal.extend(-exit_normal.dot(exit_position));
+        for i in 0..max_recursion_depth {
+            let (previous, next) = self.cameras.split_at_mut(i);
+            let previous = previous.last().unwrap_or(camera);
+            let next = &mut next[0];
+            next.copy_from(previous)?;
+            next.set_view(
+                (transformation * previous.position().extend(1.0)).truncate(),
+                (transformation * previous.target().extend(1.0)).truncate(),
+                (transformation * previous.up().extend(0.0)).truncate(),
+            )?;
+            next.set_clip_plane(Some(clip_plane))?;
+            next.set_viewport(viewport)?;
+        }
+
+        clear_stencil(&self.context, 0);
+        for level in 1..=max_recursion_depth {
+            let outer_camera = if level == 1 {
+                camera
+            } else {
+                &self.cameras[level - 2]
+            };
+            // Mark the visible part of the portal inside the previous level
+            self.mesh.render_with_color(
+                &self.recursion_color,
+                RenderStates {
+                    write_mask: WriteMask::NONE,
+                    cull: CullType::Back,
+                    stencil: Some(StencilTest {
+                        test: StencilTestType::Equal,
+                        reference: level as i32 - 1,
+                        write_mask: 0xFF,
+                        pass: StencilOperation::Increment,
+                        ..StencilTest::NOT_ZERO
+                    }),
+                    ..RenderStates::new(viewport)
+                },
+                outer_camera,
+            )?;
+            let inside = RenderStates {
+                stencil: Some(StencilTest {
+                    test: StencilTestType::Equal,
+                    reference: level as i32,
+                    ..StencilTest::NOT_ZERO
+                }),
+                ..RenderStates::new(viewport)
+            };
+            // Clear the depth inside the portal so the view through the portal is not occluded by the portal itself
+            self.far_depth_program
+                .use_uniform_float("farDepth", &outer_camera.far_depth())?;
+            self.mesh.render(
+                &self.far_depth_program,
+                RenderStates {
+                    write_mask: WriteMask::DEPTH,
+                    depth_test: DepthTestType::Always,
+                    cull: CullType::Back,
+                    ..inside
+                },
+                outer_camera,
+            )?;
+            render(inside, &self.cameras[level - 1])?;
+        }
+
+        // Fill the portals which are visible at the deepest level
+        self.mesh.render_with_color(
+            &self.recursion_color,
+            RenderStates {
+                cull: CullType::Back,
+                stencil: Some(StencilTest {
+                    test: StencilTestType::Equal,
+                    reference: max_recursion_depth as i32,
+                    ..StencilTest::NOT_ZERO
+                }),
+                ..RenderStates::new(viewport)
+            },
+            &self.cameras[max_recursion_depth - 1],
+        )?;
+        // Write the depth of the portal itself, so that it occludes the objects behind it
+        self.mesh.render_with_color(
+            &vec4(0.0, 0.0, 0.0, 0.0),
+            RenderStates {
+                write_mask: WriteMask::DEPTH,
+                depth_test: DepthTestType::Always,
+                cull: CullType::Back,
+                stencil: Some(StencilTest {
+                    test: StencilTestType::LessOrEqual,
+                    reference: 1,
+                    ..StencilTest::NOT_ZERO
+                }),
+                ..RenderStates::new(viewport)
+            },
+            camera,
+        )
+    }
+}
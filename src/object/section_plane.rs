@@ -0,0 +1,333 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::frame::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// How the cut surface of the objects sectioned by a [SectionPlane] is filled.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SectionFill {
+    /// The cut surface is filled with the [cap color](SectionPlane::cap_color).
+    Solid,
+    /// The cut surface is filled with the [cap color](SectionPlane::cap_color) and hatched with parallel lines,
+    /// as is common in engineering and architecture drawings.
+    Hatch {
+        /// The color of the lines.
+        color: Vec4,
+        /// The distance between the lines in world space.
+        spacing: f32,
+        /// The width of the lines in world space.
+        width: f32,
+        /// The angle of the lines in the plane.
+        angle: Degrees,
+    },
+}
+
+///
+/// A section plane for engineering and architecture viewers, which cuts away the part of the objects on the side the normal of the plane points to
+/// and fills the cut surface of closed objects with a solid color or a hatch pattern.
+/// The plane can be moved and rotated by the user with a [Gizmo] using [handle_events](SectionPlane::handle_events).
+///
+/// The cut surface is found using the stencil buffer, so it is only filled when rendering to the [Screen](crate::Screen), see [RenderStates::stencil].
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let (mut frame_input, camera, model): (FrameInput, Camera, Mesh) = unimplemented!();
+/// let mut section = SectionPlane::new(&context, vec3(0.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0), 10.0).unwrap();
+/// // Each frame before the camera control handles the events:
+/// section.handle_events(&camera, &mut frame_input);
+/// Screen::write(&context, ClearState::default(), || {
+///     section.render_clipped_with_color(&model, &vec4(0.8, 0.8, 0.8, 1.0), RenderStates::new(frame_input.viewport), &camera)?;
+///     section.render_caps(&[&model], frame_input.viewport, &camera)?;
+///     section.render_gizmo(frame_input.viewport, &camera)?;
+///     Ok(())
+/// }).unwrap();
+/// ```
+///
+pub struct SectionPlane {
+    context: Context,
+    origin: Vec3,
+    normal: Vec3,
+    size: f32,
+    gizmo: Gizmo,
+    cap: Mesh,
+    color_program: MeshProgram,
+    stencil_program: MeshProgram,
+    cap_program: MeshProgram,
+    /// The color of the cut surface.
+    pub cap_color: Vec4,
+    /// How the cut surface is filled.
+    pub fill: SectionFill,
+}
+
+impl SectionPlane {
+    ///
+    /// Creates a new section plane through the given point with the given normal.
+    /// The cut surface is only filled within a square with the given size centered at the point, so it should be larger than the sectioned objects.
+    ///
+    pub fn new(context: &Context, origin: Vec3, normal: Vec3, size: f32) -> Result<Self, Error> {
+        let mut section_plane = Self {
+            context: context.clone(),
+            origin,
+            normal: normal.normalize(),
+            size,
+            gizmo: Gizmo::new(context, GizmoMode::Rotate, 0.1 * size)?,
+            cap: Mesh::new(context, &CPUMesh::square(1.0))?,
+            color_program: MeshProgram::new(
+                context,
+                &clipped_source(include_str!("shaders/mesh_color.frag"))?,
+            )?,
+            stencil_program: MeshProgram::new(
+                context,
+                &clipped_source(
+                    "layout (location = 0) out vec4 outColor;
+                    void main()
+                    {
+                        outColor = vec4(0.0);
+                    }",
+                )?,
+            )?,
+            cap_program: MeshProgram::new(context, include_str!("shaders/section_cap.frag"))?,
+            cap_color: vec4(0.6, 0.6, 0.6, 1.0),
+            fill: SectionFill::Hatch {
+                color: vec4(0.1, 0.1, 0.1, 1.0),
+                spacing: 0.02 * size,
+                width: 0.004 * size,
+                angle: degrees(45.0),
+            },
+        };
+        section_plane.update_transformations();
+        Ok(section_plane)
+    }
+
+    ///
+    /// Returns the point in world space the plane goes through.
+    ///
+    pub fn origin(&self) -> Vec3 {
+        self.origin
+    }
+
+    ///
+    /// Returns the normal of the plane, which points towards the side that is cut away.
+    ///
+    pub fn normal(&self) -> Vec3 {
+        self.normal
+    }
+
+    ///
+    /// Moves the plane so that it goes through the given point with the given normal.
+    ///
+    pub fn set(&mut self, origin: Vec3, normal: Vec3) {
+        self.origin = origin;
+        self.normal = normal.normalize();
+        self.update_transformations();
+    }
+
+    ///
+    /// Returns the plane equation `(a, b, c, d)`, where a point `p` is cut away if `a * p.x + b * p.y + c * p.z + d > 0`.
+    ///
+    pub fn plane(&self) -> Vec4 {
+        self.normal.extend(-self.normal.dot(self.origin))
+    }
+
+    ///
+    /// Sets whether the gizmo moves ([GizmoMode::Translate]) or rotates ([GizmoMode::Rotate]) the plane, the default is to rotate.
+    /// The plane cannot be scaled, so [GizmoMode::Scale] disables the gizmo.
+    ///
+    pub fn set_gizmo_mode(&mut self, mode: GizmoMode) {
+        self.gizmo.mode = mode;
+    }
+
+    ///
+    /// Moves or rotates the plane when the user drags the handles of the gizmo, see [Gizmo::handle_events].
+    /// The events that are consumed by the gizmo are marked as handled, so that for example camera controls can ignore them.
+    /// Returns whether the plane was changed.
+    ///
+    pub fn handle_events(&mut self, camera: &Camera, frame_input: &mut FrameInput) -> bool {
+        if self.gizmo.mode == GizmoMode::Scale {
+            return false;
+        }
+        if let Some(delta) = self.gizmo.handle_events(camera, frame_input) {
+            let origin = (delta * self.origin.extend(1.0)).truncate();
+            let normal = (delta * self.normal.extend(0.0)).truncate();
+            self.set(origin, normal);
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// Constructs a shader program with the given fragment shader which renders the part of a [Mesh] on the side of the plane that is kept,
+    /// see [MeshProgram::new] for the inputs available in the fragment shader. Use it with [render_clipped](Self::render_clipped).
+    ///
+    /// # Errors
+    /// Will return an error if the fragment shader does not have a `void main()` function.
+    ///
+    pub fn program(&self, fragment_shader_source: &str) -> Result<MeshProgram, Error> {
+        MeshProgram::new(&self.context, &clipped_source(fragment_shader_source)?)
+    }
+
+    ///
+    /// Render the part of the mesh on the side of the plane that is kept with the given [MeshProgram], which must be constructed using [program](Self::program).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_clipped(
+        &self,
+        mesh: &Mesh,
+        program: &MeshProgram,
+        render_states: RenderStates,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        program.use_uniform_vec4("sectionPlane", &self.plane())?;
+        mesh.render(program, render_states, camera)
+    }
+
+    ///
+    /// Render the part of the mesh on the side of the plane that is kept with the given color.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_clipped_with_color(
+        &self,
+        mesh: &Mesh,
+        color: &Vec4,
+        render_states: RenderStates,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        self.color_program.use_uniform_vec4("color", color)?;
+        self.render_clipped(mesh, &self.color_program, render_states, camera)
+    }
+
+    ///
+    /// Fills the cut surface of the given meshes, which must be closed, after the clipped meshes are rendered.
+    /// For each mesh, the number of surfaces of the clipped mesh covering each pixel is counted in the stencil buffer,
+    /// and the plane is rendered where the count is odd, ie. where the plane is inside the mesh.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_caps(
+        &self,
+        meshes: &[&Mesh],
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let (hatch_color, hatch_spacing, hatch_width, hatch_angle) = match self.fill {
+            SectionFill::Solid => (self.cap_color, 1.0, 0.0, degrees(0.0)),
+            SectionFill::Hatch {
+                color,
+                spacing,
+                width,
+                angle,
+            } => (color, spacing.max(0.0001), width, angle),
+        };
+        let (tangent, bitangent) = tangents(self.normal);
+        let hatch_angle = Radians::from(hatch_angle).0;
+        let hatch_direction = tangent * hatch_angle.cos() + bitangent * hatch_angle.sin();
+        self.cap_program
+            .use_uniform_vec4("capColor", &self.cap_color)?;
+        self.cap_program
+            .use_uniform_vec4("hatchColor", &hatch_color)?;
+        self.cap_program.use_uniform_vec3("origin", &self.origin)?;
+        self.cap_program
+            .use_uniform_vec3("hatchDirection", &hatch_direction)?;
+        self.cap_program
+            .use_uniform_float("hatchSpacing", &hatch_spacing)?;
+        self.cap_program
+            .use_uniform_float("hatchWidth", &hatch_width)?;
+
+        let stencil_states = RenderStates {
+            write_mask: WriteMask::NONE,
+            depth_test: DepthTestType::Always,
+            cull: CullType::None,
+            stencil: Some(StencilTest {
+                test: StencilTestType::Always,
+                write_mask: 0xFF,
+                pass: StencilOperation::Invert,
+                ..StencilTest::NOT_ZERO
+            }),
+            ..RenderStates::new(viewport)
+        };
+        let cap_states = RenderStates {
+            cull: CullType::None,
+            stencil: Some(StencilTest::NOT_ZERO),
+            ..RenderStates::new(viewport)
+        };
+        for mesh in meshes {
+            clear_stencil(&self.context, 0);
+            // Both the front and back faces must be counted regardless of the cull setting of the mesh
+            let mut mesh = (*mesh).clone();
+            mesh.cull = Some(CullType::None);
+            self.render_clipped(&mesh, &self.stencil_program, stencil_states, camera)?;
+            self.cap.render(&self.cap_program, cap_states, camera)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Render the gizmo for moving or rotating the plane on top of the rest of the scene.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_gizmo(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        if self.gizmo.mode == GizmoMode::Scale {
+            return Ok(());
+        }
+        self.gizmo.render(viewport, camera)
+    }
+
+    fn update_transformations(&mut self) {
+        let (tangent, bitangent) = tangents(self.normal);
+        self.cap.transformation = Mat4::from_translation(self.origin)
+            * Mat4::from(Mat3::from_cols(tangent, bitangent, self.normal))
+            * Mat4::from_scale(self.size);
+        self.gizmo.set_position(self.origin);
+    }
+}
+
+///
+/// Returns two unit vectors which are orthogonal to each other and to the given normal.
+///
+fn tangents(normal: Vec3) -> (Vec3, Vec3) {
+    let up = if normal.y.abs() < 0.99 {
+        vec3(0.0, 1.0, 0.0)
+    } else {
+        vec3(1.0, 0.0, 0.0)
+    };
+    let tangent = up.cross(normal).normalize();
+    (tangent, normal.cross(tangent))
+}
+
+///
+/// Returns the given fragment shader source where the main function discards the fragments on the side of the plane that is cut away.
+///
+fn clipped_source(fragment_shader_source: &str) -> Result<String, Error> {
+    if fragment_shader_source.find("void main()").is_none() {
+        Err(Error::MeshError {
+            message: "The fragment shader must have a void main() function to be clipped by a section plane.".to_string(),
+        })?;
+    }
+    Ok(format!(
+        "{}
+        {}
+        uniform vec4 sectionPlane;
+        void main()
+        {{
+            if (dot(sectionPlane, vec4(pos, 1.0)) > 0.0) {{
+                discard;
+            }}
+            sectionPlaneMain();
+        }}",
+        fragment_shader_source.replacen("void main()", "void sectionPlaneMain()", 1),
+        if fragment_shader_source.find("in vec3 pos;").is_some() {
+            ""
+        } else {
+            "in vec3 pos;"
+        }
+    ))
+}
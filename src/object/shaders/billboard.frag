@@ -0,0 +1,26 @@
+#include "shared"
+
+uniform sampler2D tex;
+uniform vec4 color;
+uniform int is_imposter;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 col;
+    if(is_imposter == 1) {
+        // The texture is rendered by this crate, ie. the colors are already in sRGB color space and the first row is the bottom row
+        col = texture(tex, uvs);
+        col.rgb = rgb_from_srgb(col.rgb);
+    } else {
+        col = texture(tex, vec2(uvs.x, 1.0 - uvs.y));
+    }
+    col *= color;
+    if(col.a < 0.01) {
+        discard;
+    }
+    outColor = vec4(srgb_from_rgb(col.rgb), col.a);
+}
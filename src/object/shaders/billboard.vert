@@ -0,0 +1,19 @@
+
+#include "camera"
+
+uniform vec3 center;
+uniform vec3 right;
+uniform vec3 up;
+uniform vec2 size;
+
+in vec3 position;
+in vec2 uv_coordinates;
+
+out vec2 uvs;
+
+void main()
+{
+    uvs = uv_coordinates;
+    vec3 world_position = center + size.x * position.x * right + size.y * position.y * up;
+    gl_Position = camera.viewProjection * vec4(world_position, 1.0);
+}
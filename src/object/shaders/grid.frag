@@ -0,0 +1,33 @@
+#include "shared"
+
+#include "camera"
+
+uniform float cellSize;
+uniform float majorCellSize;
+uniform float fadeDistance;
+uniform vec4 minorColor;
+uniform vec4 majorColor;
+
+in vec3 pos;
+
+layout (location = 0) out vec4 outColor;
+
+float line_intensity(float size)
+{
+    vec2 coords = pos.xz / size;
+    vec2 grid = abs(fract(coords - 0.5) - 0.5) / fwidth(coords);
+    return 1.0 - min(min(grid.x, grid.y), 1.0);
+}
+
+void main()
+{
+    float minor = line_intensity(cellSize);
+    float major = line_intensity(majorCellSize);
+    vec4 color = mix(minorColor * vec4(1.0, 1.0, 1.0, minor), majorColor, major);
+    float fade = 1.0 - smoothstep(0.5 * fadeDistance, fadeDistance, distance(pos.xz, camera.position.xz));
+    color.a *= fade;
+    if(color.a < 0.005) {
+        discard;
+    }
+    outColor = vec4(srgb_from_rgb(color.rgb), color.a);
+}
@@ -0,0 +1,15 @@
+
+#include "camera"
+
+uniform float height;
+uniform float fadeDistance;
+
+in vec3 position;
+
+out vec3 pos;
+
+void main()
+{
+    pos = vec3(camera.position.x + fadeDistance * position.x, height, camera.position.z + fadeDistance * position.z);
+    gl_Position = camera.viewProjection * vec4(pos, 1.);
+}
@@ -1,3 +1,5 @@
+#include "shared"
+
 uniform sampler2DArray tex;
 
 uniform int no_views;
@@ -1,12 +1,5 @@
 
-layout (std140) uniform Camera
-{
-    mat4 viewProjection;
-    mat4 view;
-    mat4 projection;
-    vec3 position;
-    float padding;
-} camera;
+#include "camera"
 
 in vec3 center;
 in float theta;
@@ -0,0 +1,28 @@
+#include "shared"
+
+uniform sampler2D tex;
+uniform int isSdf;
+uniform vec4 color;
+
+in vec2 uvs;
+in float visibility;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 col;
+    if(isSdf == 1) {
+        float distance = texture(tex, vec2(uvs.x, 1.0 - uvs.y)).r;
+        float smoothing = max(0.5 * fwidth(distance), 0.0001);
+        col = color;
+        col.a *= smoothstep(0.5 - smoothing, 0.5 + smoothing, distance);
+    } else {
+        col = texture(tex, vec2(uvs.x, 1.0 - uvs.y)) * color;
+    }
+    col.a *= visibility;
+    if(col.a < 0.01) {
+        discard;
+    }
+    outColor = vec4(srgb_from_rgb(col.rgb), col.a);
+}
@@ -0,0 +1,38 @@
+#include "camera"
+
+uniform vec3 anchor;
+uniform vec2 offset;
+uniform vec2 size;
+uniform vec2 viewportSize;
+uniform sampler2D depthTexture;
+uniform int useDepthTexture;
+uniform float occludedOpacity;
+uniform float depthBias;
+
+in vec3 position;
+in vec2 uv_coordinates;
+
+out vec2 uvs;
+out float visibility;
+
+void main()
+{
+    uvs = uv_coordinates;
+    vec4 clip = camera.viewProjection * vec4(anchor, 1.0);
+    if(clip.w <= 0.0) {
+        // The anchor is behind the camera
+        gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+        visibility = 0.0;
+        return;
+    }
+    vec3 ndc = clip.xyz / clip.w;
+    visibility = 1.0;
+    if(useDepthTexture == 1) {
+        float sceneDepth = texture(depthTexture, 0.5 * ndc.xy + 0.5).r;
+        if(0.5 * ndc.z + 0.5 > sceneDepth + depthBias) {
+            visibility = occludedOpacity;
+        }
+    }
+    vec2 pixels = position.xy * size + offset;
+    gl_Position = vec4(ndc.xy + 2.0 * pixels / viewportSize, 0.0, 1.0);
+}
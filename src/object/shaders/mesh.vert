@@ -1,11 +1,4 @@
-layout (std140) uniform Camera
-{{
-    mat4 viewProjection;
-    mat4 view;
-    mat4 projection;
-    vec3 position;
-    float padding;
-}} camera;
+#include "camera"
 
 uniform mat4 modelMatrix;
 in vec3 position;
@@ -15,11 +8,13 @@ in vec3 position;
 {} // Normals in/out
 {} // UV coordinates in/out
 {} // Colors in/out
+{} // Skinning in
 
 void main()
 {{
     mat4 local2World = modelMatrix;
     {} // Instancing
+    {} // Skinning
     vec4 worldPosition = local2World * vec4(position, 1.);
     gl_Position = camera.viewProjection * worldPosition;
     {} // Position
@@ -1,3 +1,4 @@
+#include "shared"
 
 uniform vec4 color;
 
@@ -1,3 +1,4 @@
+#include "shared"
 
 in vec3 nor;
 
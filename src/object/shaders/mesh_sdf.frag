@@ -0,0 +1,30 @@
+#include "shared"
+
+uniform sampler2D tex;
+uniform vec4 color;
+uniform vec4 outlineColor;
+uniform float outlineWidth;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float distance = texture(tex, vec2(uvs.x, 1.0 - uvs.y)).r;
+    // The width of the antialiased edge is one pixel on the screen regardless of the scale
+    float smoothing = max(0.5 * fwidth(distance), 0.0001);
+    float inside = smoothstep(0.5 - smoothing, 0.5 + smoothing, distance);
+    vec4 col = color;
+    if(outlineWidth > 0.0) {
+        float edge = 0.5 - outlineWidth;
+        col = mix(outlineColor, color, inside);
+        col.a *= smoothstep(edge - smoothing, edge + smoothing, distance);
+    } else {
+        col.a *= inside;
+    }
+    if(col.a < 0.01) {
+        discard;
+    }
+    outColor = vec4(srgb_from_rgb(col.rgb), col.a);
+}
@@ -1,3 +1,4 @@
+#include "shared"
 
 uniform sampler2D tex;
 
@@ -1,3 +1,4 @@
+#include "shared"
 
 in vec2 uvs;
 
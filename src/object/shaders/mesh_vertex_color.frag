@@ -1,3 +1,4 @@
+#include "shared"
 
 in vec4 col;
 
@@ -0,0 +1,23 @@
+#include "shared"
+
+uniform sampler2D colorMap;
+uniform vec2 size;
+uniform float borderWidth;
+uniform vec4 borderColor;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    vec2 pixel = uv * size;
+    if (pixel.x < borderWidth || pixel.y < borderWidth || pixel.x > size.x - borderWidth || pixel.y > size.y - borderWidth)
+    {
+        color = vec4(srgb_from_rgb(borderColor.rgb), borderColor.a);
+    }
+    else
+    {
+        color = texture(colorMap, uv);
+    }
+}
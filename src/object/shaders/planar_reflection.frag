@@ -0,0 +1,17 @@
+#include "shared"
+
+uniform sampler2D reflectionMap;
+uniform vec4 screenViewport;
+uniform vec4 color;
+uniform vec4 tint;
+uniform float reflectivity;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 uv = (gl_FragCoord.xy - screenViewport.xy) / screenViewport.zw;
+    // The reflection is already in sRGB color space
+    vec3 reflection = texture(reflectionMap, uv).rgb * srgb_from_rgb(tint.rgb);
+    outColor = vec4(mix(srgb_from_rgb(color.rgb), reflection, reflectivity), color.a);
+}
@@ -0,0 +1,19 @@
+#include "shared"
+
+uniform vec4 capColor;
+uniform vec4 hatchColor;
+uniform vec3 origin;
+uniform vec3 hatchDirection;
+uniform float hatchSpacing;
+uniform float hatchWidth;
+
+in vec3 pos;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float distance = fract(dot(pos - origin, hatchDirection) / hatchSpacing) * hatchSpacing;
+    vec4 color = distance < hatchWidth ? hatchColor : capColor;
+    outColor = vec4(srgb_from_rgb(color.rgb), color.a);
+}
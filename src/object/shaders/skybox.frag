@@ -1,18 +1,15 @@
+#include "shared"
+
 uniform samplerCube texture0;
+uniform float lod;
 
-layout (std140) uniform Camera
-{
-    mat4 viewProjection;
-    mat4 view;
-    mat4 projection;
-    vec3 position;
-    float padding;
-} camera;
+#include "camera"
 
 in vec3 coords;
 
 layout (location = 0) out vec4 outColor;
 
 void main() {
-    outColor = vec4(srgb_from_rgb(texture(texture0, coords).rgb), 1.0);
+    vec3 color = lod < 0.0 ? texture(texture0, coords).rgb : textureLod(texture0, coords, lod).rgb;
+    outColor = vec4(srgb_from_rgb(color), 1.0);
 }
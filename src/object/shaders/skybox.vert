@@ -1,12 +1,7 @@
 
-layout (std140) uniform Camera
-{
-    mat4 viewProjection;
-    mat4 view;
-    mat4 projection;
-    vec3 position;
-    float padding;
-} camera;
+#include "camera"
+
+uniform float farNdc;
 
 in vec3 position;
 
@@ -15,5 +10,7 @@ out vec3 coords;
 void main()
 {
     coords = position;
-    gl_Position = (camera.projection * mat4(mat3(camera.view)) * vec4(position, 1.)).xyww;
+    // Place the skybox on the far plane
+    vec4 p = camera.projection * mat4(mat3(camera.view)) * vec4(position, 1.);
+    gl_Position = vec4(p.xy, farNdc * p.w, p.w);
 }
\ No newline at end of file
@@ -0,0 +1,271 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// Where the vertices of a [SkinnedMesh] are deformed by the joints of the [Skeleton].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SkinningMode {
+    /// The vertices are deformed in the vertex shader using an array of joint matrices uniforms, which is fast,
+    /// but the number of joints is limited by the number of uniforms available in the vertex shader.
+    Gpu,
+    /// The vertices are deformed on the CPU each frame and the vertex buffers are updated,
+    /// which works for any number of joints but is slower for meshes with many vertices.
+    Cpu,
+}
+
+///
+/// A triangle mesh which is deformed by the joints of a [Skeleton], for example an animated character.
+/// The mesh is deformed on the GPU if the device has enough uniforms for the joint matrices,
+/// otherwise it falls back to deforming the mesh on the CPU (see [SkinningMode]).
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let (cpu_mesh, skin, mut skeleton): (CPUMesh, CPUSkin, Skeleton) = unimplemented!();
+/// # let (render_states, camera): (RenderStates, Camera) = unimplemented!();
+/// let mut mesh = SkinnedMesh::new(&context, &cpu_mesh, &skin, skeleton.joints().len()).unwrap();
+/// let program = mesh.program("in vec3 nor; layout (location = 0) out vec4 outColor; void main() { outColor = vec4(nor, 1.0); }").unwrap();
+/// // Each frame after the skeleton is posed:
+/// skeleton.update();
+/// mesh.update(&skeleton).unwrap();
+/// mesh.render(&program, render_states, &camera).unwrap();
+/// ```
+///
+pub struct SkinnedMesh {
+    context: Context,
+    mesh: Mesh,
+    mode: SkinningMode,
+    joint_count: usize,
+    skin: CPUSkin,
+    positions: Vec<f32>,
+    normals: Option<Vec<f32>>,
+    joint_buffer: Option<VertexBuffer>,
+    weight_buffer: Option<VertexBuffer>,
+    skinning_matrices: Vec<Mat4>,
+}
+
+impl SkinnedMesh {
+    ///
+    /// Creates a new skinned mesh from the given mesh in the bind pose and the joints and weights of each vertex.
+    /// The skinning mode is selected automatically based on the number of joints in the skeleton and the number of uniforms
+    /// available in the vertex shader on this device, see [Context::get_max_vertex_uniform_vectors].
+    ///
+    /// # Errors
+    /// Will return an error if the skin does not have four joints and four weights for each vertex of the mesh.
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_mesh: &CPUMesh,
+        skin: &CPUSkin,
+        joint_count: usize,
+    ) -> Result<Self, Error> {
+        let mode = if Self::gpu_skinning_supported(context, joint_count) {
+            SkinningMode::Gpu
+        } else {
+            SkinningMode::Cpu
+        };
+        Self::new_with_mode(context, cpu_mesh, skin, joint_count, mode)
+    }
+
+    ///
+    /// Creates a new skinned mesh like [new](Self::new), but with the given skinning mode instead of selecting it automatically.
+    ///
+    /// # Errors
+    /// Will return an error if the skin does not have four joints and four weights for each vertex of the mesh
+    /// or if GPU skinning is requested but the device does not have enough uniforms for the given number of joints.
+    ///
+    pub fn new_with_mode(
+        context: &Context,
+        cpu_mesh: &CPUMesh,
+        skin: &CPUSkin,
+        joint_count: usize,
+        mode: SkinningMode,
+    ) -> Result<Self, Error> {
+        let vertex_count = cpu_mesh.positions.len() / 3;
+        if skin.joints.len() != vertex_count * 4 || skin.weights.len() != vertex_count * 4 {
+            Err(Error::MeshError {
+                message: format!(
+                    "The skin must have four joints and four weights for each of the {} vertices",
+                    vertex_count
+                ),
+            })?;
+        }
+        if let Some(joint) = skin
+            .joints
+            .iter()
+            .find(|joint| **joint as usize >= joint_count)
+        {
+            Err(Error::MeshError {
+                message: format!(
+                    "The skin refers to joint {}, but there are only {} joints",
+                    joint, joint_count
+                ),
+            })?;
+        }
+        if mode == SkinningMode::Gpu && !Self::gpu_skinning_supported(context, joint_count) {
+            Err(Error::MeshError {
+                message: format!(
+                    "The device does not have enough vertex shader uniforms for GPU skinning with {} joints",
+                    joint_count
+                ),
+            })?;
+        }
+        let (joint_buffer, weight_buffer) = if mode == SkinningMode::Gpu {
+            let joints: Vec<f32> = skin.joints.iter().map(|joint| *joint as f32).collect();
+            (
+                Some(VertexBuffer::new_with_static(context, &joints)?),
+                Some(VertexBuffer::new_with_static(context, &skin.weights)?),
+            )
+        } else {
+            (None, None)
+        };
+        Ok(Self {
+            context: context.clone(),
+            mesh: Mesh::new(context, cpu_mesh)?,
+            mode,
+            joint_count,
+            skin: skin.clone(),
+            positions: cpu_mesh.positions.clone(),
+            normals: cpu_mesh.normals.clone(),
+            joint_buffer,
+            weight_buffer,
+            skinning_matrices: vec![Mat4::identity(); joint_count],
+        })
+    }
+
+    fn gpu_skinning_supported(context: &Context, joint_count: usize) -> bool {
+        // Leave room for the model and normal matrices and the uniforms of the fragment shader
+        const RESERVED_UNIFORM_VECTORS: usize = 16;
+        joint_count * 4 + RESERVED_UNIFORM_VECTORS
+            <= context.get_max_vertex_uniform_vectors() as usize
+    }
+
+    ///
+    /// Returns whether the mesh is deformed on the GPU or on the CPU.
+    ///
+    pub fn mode(&self) -> SkinningMode {
+        self.mode
+    }
+
+    ///
+    /// Returns the transformation of the mesh from model space to world space.
+    ///
+    pub fn transformation(&self) -> &Mat4 {
+        &self.mesh.transformation
+    }
+
+    ///
+    /// Sets the transformation of the mesh from model space to world space.
+    ///
+    pub fn set_transformation(&mut self, transformation: &Mat4) {
+        self.mesh.transformation = *transformation;
+    }
+
+    ///
+    /// Deforms the mesh to the current pose of the given skeleton, call this each frame after [Skeleton::update].
+    /// When skinning on the CPU, the vertices are transformed and the vertex buffers are updated.
+    ///
+    /// # Errors
+    /// Will return an error if the skeleton does not have the number of joints given when constructing the mesh.
+    ///
+    pub fn update(&mut self, skeleton: &Skeleton) -> Result<(), Error> {
+        if skeleton.joints().len() != self.joint_count {
+            Err(Error::MeshError {
+                message: format!(
+                    "Expected a skeleton with {} joints, but got {} joints",
+                    self.joint_count,
+                    skeleton.joints().len()
+                ),
+            })?;
+        }
+        self.skinning_matrices = skeleton.skinning_matrices();
+        if self.mode == SkinningMode::Cpu {
+            let vertex_count = self.positions.len() / 3;
+            let mut positions = Vec::with_capacity(self.positions.len());
+            let mut normals = self
+                .normals
+                .as_ref()
+                .map(|normals| Vec::with_capacity(normals.len()));
+            for i in 0..vertex_count {
+                let mut skin = Mat4::zero();
+                for j in 0..4 {
+                    let weight = self.skin.weights[i * 4 + j];
+                    if weight != 0.0 {
+                        skin +=
+                            self.skinning_matrices[self.skin.joints[i * 4 + j] as usize] * weight;
+                    }
+                }
+                let p = &self.positions[i * 3..i * 3 + 3];
+                positions.extend(&(skin * vec4(p[0], p[1], p[2], 1.0)).truncate().to_slice());
+                if let (Some(normals), Some(bind_normals)) =
+                    (normals.as_mut(), self.normals.as_ref())
+                {
+                    let n = &bind_normals[i * 3..i * 3 + 3];
+                    let normal = (skin * vec4(n[0], n[1], n[2], 0.0)).truncate();
+                    let normal = if normal.magnitude2() > 0.0 {
+                        normal.normalize()
+                    } else {
+                        normal
+                    };
+                    normals.extend(&normal.to_slice());
+                }
+            }
+            self.mesh.update_positions(&positions)?;
+            if let Some(normals) = normals {
+                self.mesh.update_normals(&normals)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Constructs a shader program for rendering this mesh in its skinning mode with the given fragment shader,
+    /// see [MeshProgram::new] for the inputs available in the fragment shader.
+    /// The program can be used for all skinned meshes with the same skinning mode and at most the same number of joints.
+    ///
+    pub fn program(&self, fragment_shader_source: &str) -> Result<MeshProgram, Error> {
+        MeshProgram::new_internal(
+            &self.context,
+            fragment_shader_source,
+            false,
+            if self.mode == SkinningMode::Gpu {
+                Some(self.joint_count)
+            } else {
+                None
+            },
+        )
+    }
+
+    ///
+    /// Render the mesh with the given [MeshProgram], which must be constructed using [program](Self::program).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if the program does not match the skinning mode of this mesh
+    /// or if the program requires an attribute which the mesh does not have, see [Mesh::render].
+    ///
+    pub fn render(
+        &self,
+        program: &MeshProgram,
+        render_states: RenderStates,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        match (self.mode, program.max_joints) {
+            (SkinningMode::Gpu, Some(max_joints)) if max_joints >= self.joint_count => {
+                program.use_uniform_mat4_array("jointMatrices", &self.skinning_matrices)?;
+                program.use_attribute_vec4(self.joint_buffer.as_ref().unwrap(), "joints")?;
+                program.use_attribute_vec4(self.weight_buffer.as_ref().unwrap(), "weights")?;
+            }
+            (SkinningMode::Cpu, None) => {}
+            _ => Err(Error::MeshError {
+                message: "The mesh shader program does not match the skinning mode of the skinned mesh, construct it with SkinnedMesh::program.".to_string(),
+            })?,
+        }
+        self.mesh.render(program, render_states, camera)
+    }
+}
@@ -10,6 +10,9 @@ pub struct Skybox {
     program: Program,
     vertex_buffer: VertexBuffer,
     texture: TextureCubeMap,
+    /// The mip level of the cube map which is shown, for example a blurry background from a prefiltered environment map,
+    /// or `None` to sample the cube map using the usual filtering.
+    pub lod: Option<f32>,
 }
 
 impl Skybox {
@@ -26,14 +29,11 @@ impl Skybox {
     }
 
     pub fn new_with_texture(context: &Context, texture: TextureCubeMap) -> Result<Skybox, Error> {
-        let program = Program::from_source(
+        let program = Program::from_source_with_preprocessor(
             context,
             include_str!("shaders/skybox.vert"),
-            &format!(
-                "{}{}",
-                include_str!("../core/shared.frag"),
-                include_str!("shaders/skybox.frag")
-            ),
+            include_str!("shaders/skybox.frag"),
+            &ShaderPreprocessor::new(),
         )?;
 
         let vertex_buffer = VertexBuffer::new_with_static(context, &get_positions())?;
@@ -42,6 +42,7 @@ impl Skybox {
             program,
             vertex_buffer,
             texture,
+            lod: None,
         })
     }
 
@@ -53,18 +54,22 @@ impl Skybox {
     pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
         let render_states = RenderStates {
             depth_test: DepthTestType::LessOrEqual,
-            ..Default::default()
+            cull: CullType::Front,
+            ..RenderStates::new(viewport)
         };
 
         self.program.use_texture_cube(&self.texture, "texture0")?;
         self.program
-            .use_uniform_block(camera.uniform_buffer(), "Camera");
+            .use_uniform_float("lod", &self.lod.unwrap_or(-1.0))?;
+        self.program
+            .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
+        self.program
+            .use_uniform_float("farNdc", &camera.far_ndc())?;
 
         self.program
             .use_attribute_vec3(&self.vertex_buffer, "position")?;
 
-        self.program
-            .draw_arrays(render_states, CullType::Front, viewport, 36);
+        self.program.draw_arrays(render_states, 36);
         Ok(())
     }
 
@@ -0,0 +1,179 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::io::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// A triangle mesh which is uploaded to the GPU in chunks as they arrive from a [MeshStream],
+/// so that a partially loaded model is shown while a very large model is loading.
+/// Call [update](StreamedMesh::update) each frame to upload the chunks that have arrived since the last frame.
+///
+pub struct StreamedMesh {
+    context: Context,
+    stream: MeshStream,
+    meshes: Vec<Mesh>,
+    transformation: Mat4,
+    /// The maximum number of chunks which are uploaded in each call to [update](StreamedMesh::update), to avoid stalling a frame.
+    pub max_chunks_per_update: usize,
+}
+
+impl StreamedMesh {
+    ///
+    /// Creates a new streamed mesh which uploads the chunks from the given stream.
+    ///
+    pub fn new(context: &Context, stream: MeshStream) -> Self {
+        Self {
+            context: context.clone(),
+            stream,
+            meshes: Vec::new(),
+            transformation: Mat4::identity(),
+            max_chunks_per_update: 4,
+        }
+    }
+
+    ///
+    /// Uploads the chunks that have arrived since the last call, but at most [max_chunks_per_update](StreamedMesh::max_chunks_per_update) chunks.
+    /// Returns whether any chunks were uploaded.
+    ///
+    /// # Errors
+    /// Will return an error if the loading or parsing of the stream failed.
+    ///
+    pub fn update(&mut self) -> Result<bool, Error> {
+        let mut uploaded = false;
+        for _ in 0..self.max_chunks_per_update {
+            match self.stream.next_chunk() {
+                Some(Ok(cpu_mesh)) => {
+                    let mut mesh = Mesh::new(&self.context, &cpu_mesh)?;
+                    mesh.transformation = self.transformation;
+                    self.meshes.push(mesh);
+                    uploaded = true;
+                }
+                Some(Err(e)) => Err(Error::MeshError {
+                    message: format!("Failed to stream mesh: {:?}", e),
+                })?,
+                None => break,
+            }
+        }
+        Ok(uploaded)
+    }
+
+    ///
+    /// Returns whether all chunks have been uploaded.
+    ///
+    pub fn is_loaded(&self) -> bool {
+        self.stream.is_done()
+    }
+
+    ///
+    /// Returns the meshes for the chunks uploaded so far.
+    ///
+    pub fn meshes(&self) -> &[Mesh] {
+        &self.meshes
+    }
+
+    ///
+    /// Returns the transformation applied to all chunks.
+    ///
+    pub fn transformation(&self) -> &Mat4 {
+        &self.transformation
+    }
+
+    ///
+    /// Sets the transformation applied to all chunks, also the chunks which are uploaded later.
+    ///
+    pub fn set_transformation(&mut self, transformation: &Mat4) {
+        self.transformation = *transformation;
+        for mesh in self.meshes.iter_mut() {
+            mesh.transformation = *transformation;
+        }
+    }
+
+    ///
+    /// Render the chunks uploaded so far with a color per triangle vertex, see [Mesh::render_color].
+    ///
+    pub fn render_color(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
+        for mesh in self.meshes.iter() {
+            mesh.render_color(render_states, camera)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Render the chunks uploaded so far with the given color, see [Mesh::render_with_color].
+    ///
+    pub fn render_with_color(
+        &self,
+        color: &Vec4,
+        render_states: RenderStates,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        for mesh in self.meshes.iter() {
+            mesh.render_with_color(color, render_states, camera)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Render the chunks uploaded so far with the given texture, see [Mesh::render_with_texture].
+    ///
+    pub fn render_with_texture(
+        &self,
+        texture: &impl Texture,
+        render_states: RenderStates,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        for mesh in self.meshes.iter() {
+            mesh.render_with_texture(texture, render_states, camera)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Render the chunks uploaded so far with the given [MeshProgram], see [Mesh::render].
+    ///
+    pub fn render(
+        &self,
+        program: &MeshProgram,
+        render_states: RenderStates,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        for mesh in self.meshes.iter() {
+            mesh.render(program, render_states, camera)?;
+        }
+        Ok(())
+    }
+}
+
+impl Geometry for StreamedMesh {
+    fn render_depth_to_red(
+        &self,
+        render_states: RenderStates,
+        camera: &Camera,
+        max_depth: f32,
+    ) -> Result<(), Error> {
+        for mesh in self.meshes.iter() {
+            mesh.render_depth_to_red(render_states, camera, max_depth)?;
+        }
+        Ok(())
+    }
+
+    fn render_depth(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
+        for mesh in self.meshes.iter() {
+            mesh.render_depth(render_states, camera)?;
+        }
+        Ok(())
+    }
+
+    fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
+        let mut meshes = self.meshes.iter();
+        let mut aabb = meshes.next()?.aabb()?;
+        for mesh in meshes {
+            if let Some(other) = mesh.aabb() {
+                aabb.expand_with_aabb(&other);
+            }
+        }
+        Some(aabb)
+    }
+}
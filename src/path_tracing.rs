@@ -0,0 +1,450 @@
+//!
+//! An offline path tracer which renders a reference image on the CPU from the same scene description as the realtime renderers.
+//!
+
+use crate::camera::*;
+use crate::definition::*;
+use crate::light::*;
+use crate::math::*;
+
+///
+/// An offline path tracer which renders a reference image of a set of [CPUMesh]es with [CPUMaterial]s and lights as seen from a [Camera].
+/// This can for example be used to validate the output of the realtime pipelines or to produce high-quality stills of a scene.
+///
+/// The direct lighting uses the same Phong lighting model as the [realtime pipelines](crate::PhongForwardPipeline),
+/// but all lights cast exact shadows. In addition, the light is bounced between the surfaces
+/// and the ambient light is treated as light coming equally from all directions, which is blocked by the surrounding surfaces.
+///
+pub struct PathTracer<'a> {
+    meshes: &'a [CPUMesh],
+    materials: Vec<Option<&'a CPUMaterial>>,
+    normals: Vec<Vec<f32>>,
+    indices: Vec<Vec<u32>>,
+    bvh: BoundingVolumeHierarchy,
+    /// The number of paths traced through each pixel.
+    pub samples_per_pixel: u32,
+    /// The maximum number of times the light is bounced between the surfaces.
+    pub max_bounces: u32,
+    /// The color of the pixels where no surface is visible.
+    pub background_color: Vec4,
+    /// The distance the rays start from the surfaces to avoid hitting the surface itself.
+    pub bias: f32,
+}
+
+impl<'a> PathTracer<'a> {
+    ///
+    /// Constructs a new path tracer for the given meshes, where the material of each mesh is the material
+    /// with the same name as the [material name](CPUMesh::material_name) of the mesh.
+    /// The meshes without a material are rendered with the default material.
+    ///
+    pub fn new(meshes: &'a [CPUMesh], materials: &'a [CPUMaterial]) -> Self {
+        let indices: Vec<Vec<u32>> = meshes
+            .iter()
+            .map(|mesh| {
+                mesh.indices
+                    .as_ref()
+                    .map(|indices| indices.into_u32())
+                    .unwrap_or_else(|| (0..mesh.positions.len() as u32 / 3).collect())
+            })
+            .collect();
+        let normals = meshes
+            .iter()
+            .zip(indices.iter())
+            .map(|(mesh, indices)| match mesh.normals {
+                Some(ref normals) => normals.clone(),
+                None => {
+                    let mut mesh = CPUMesh {
+                        positions: mesh.positions.clone(),
+                        indices: Some(Indices::U32(indices.clone())),
+                        ..Default::default()
+                    };
+                    mesh.compute_normals();
+                    mesh.normals.unwrap()
+                }
+            })
+            .collect();
+        Self {
+            meshes,
+            materials: meshes
+                .iter()
+                .map(|mesh| {
+                    mesh.material_name
+                        .as_ref()
+                        .and_then(|name| materials.iter().find(|m| &m.name == name))
+                })
+                .collect(),
+            normals,
+            indices,
+            bvh: BoundingVolumeHierarchy::new(meshes),
+            samples_per_pixel: 64,
+            max_bounces: 3,
+            background_color: vec4(0.0, 0.0, 0.0, 0.0),
+            bias: 0.0001,
+        }
+    }
+
+    ///
+    /// Renders an image with the given width and height of the scene as seen from the given camera and lit by the given lights.
+    /// The aspect of the camera should match the aspect of the image.
+    ///
+    /// Returns the RGBA color values of the image as a list of bytes with the bottom row first,
+    /// in the same way as [Screen::read_color](crate::Screen::read_color), so it can be saved using
+    /// [Saver::save_pixels](crate::Saver::save_pixels).
+    ///
+    pub fn render(
+        &self,
+        camera: &Camera,
+        width: u32,
+        height: u32,
+        ambient_light: Option<&AmbientLight>,
+        directional_lights: &[&DirectionalLight],
+        spot_lights: &[&SpotLight],
+        point_lights: &[&PointLight],
+    ) -> Vec<u8> {
+        let lights = Lights {
+            ambient: ambient_light
+                .map(|light| light.color * light.intensity)
+                .unwrap_or(vec3(0.0, 0.0, 0.0)),
+            directional: directional_lights
+                .iter()
+                .map(|light| (light.color() * light.intensity(), light.direction()))
+                .collect(),
+            spot: spot_lights
+                .iter()
+                .map(|light| SpotLightParameters {
+                    color: light.color() * light.intensity(),
+                    position: light.position(),
+                    direction: light.direction(),
+                    cutoff: light.cutoff().to_radians(),
+                    attenuation: light.attenuation(),
+                })
+                .collect(),
+            point: point_lights
+                .iter()
+                .map(|light| {
+                    (
+                        light.color() * light.intensity(),
+                        light.position(),
+                        light.attenuation(),
+                    )
+                })
+                .collect(),
+        };
+
+        let samples_per_pixel = self.samples_per_pixel.max(1);
+        let mut pixels = Vec::with_capacity(4 * width as usize * height as usize);
+        // The rows are rendered bottom first
+        for y in (0..height).rev() {
+            for x in 0..width {
+                let mut random = Random::new(y * width + x);
+                let mut color = vec4(0.0, 0.0, 0.0, 0.0);
+                for _ in 0..samples_per_pixel {
+                    let screen_coordinates = (
+                        (x as f32 + random.next()) / width as f32,
+                        (y as f32 + random.next()) / height as f32,
+                    );
+                    color += self.trace(
+                        camera.position_at(screen_coordinates),
+                        camera.view_direction_at(screen_coordinates),
+                        &lights,
+                        &mut random,
+                    );
+                }
+                color /= samples_per_pixel as f32;
+                pixels.extend_from_slice(&[
+                    srgb_from_rgb(color.x),
+                    srgb_from_rgb(color.y),
+                    srgb_from_rgb(color.z),
+                    (color.w.max(0.0).min(1.0) * 255.0).round() as u8,
+                ]);
+            }
+        }
+        pixels
+    }
+
+    fn trace(&self, origin: Vec3, direction: Vec3, lights: &Lights, random: &mut Random) -> Vec4 {
+        let mut radiance = vec3(0.0, 0.0, 0.0);
+        let mut throughput = vec3(1.0, 1.0, 1.0);
+        let mut origin = origin;
+        let mut direction = direction;
+        for bounce in 0..=self.max_bounces {
+            let hit = match self.bvh.intersect(origin, direction, std::f32::MAX) {
+                Some(hit) => hit,
+                None => {
+                    if bounce == 0 {
+                        return self.background_color;
+                    }
+                    radiance += mul(throughput, lights.ambient);
+                    break;
+                }
+            };
+            let surface = self.surface(&hit, direction);
+            let position = origin + direction * hit.distance;
+            let shading_origin = position + self.bias * surface.normal;
+
+            for (color, light_direction) in lights.directional.iter() {
+                let to_light = -light_direction.normalize();
+                if !self
+                    .bvh
+                    .is_occluded(shading_origin, to_light, std::f32::MAX)
+                {
+                    radiance += mul(
+                        throughput,
+                        self.phong(&surface, *color, to_light, direction),
+                    );
+                }
+            }
+            for light in lights.spot.iter() {
+                let light_direction = (position - light.position).normalize();
+                let angle = light_direction.dot(light.direction.normalize()).acos();
+                if angle < light.cutoff {
+                    let falloff = 1.0 - smoothstep(0.75 * light.cutoff, light.cutoff, angle);
+                    radiance += mul(
+                        throughput,
+                        self.attenuated(
+                            &surface,
+                            light.color * falloff,
+                            light.position,
+                            light.attenuation,
+                            shading_origin,
+                            position,
+                            direction,
+                        ),
+                    );
+                }
+            }
+            for (color, light_position, attenuation) in lights.point.iter() {
+                radiance += mul(
+                    throughput,
+                    self.attenuated(
+                        &surface,
+                        *color,
+                        *light_position,
+                        *attenuation,
+                        shading_origin,
+                        position,
+                        direction,
+                    ),
+                );
+            }
+
+            // Continue the path in a cosine weighted direction in the hemisphere around the normal, where the cosine and the
+            // probability cancel out such that the throughput is only multiplied by the surface color
+            throughput = mul(throughput, surface.color);
+            if throughput.x.max(throughput.y).max(throughput.z) <= 0.0 {
+                break;
+            }
+            origin = shading_origin;
+            direction = cosine_weighted_direction(surface.normal, random);
+        }
+        radiance.extend(1.0)
+    }
+
+    fn attenuated(
+        &self,
+        surface: &Surface,
+        color: Vec3,
+        light_position: Vec3,
+        attenuation: (f32, f32, f32),
+        shading_origin: Vec3,
+        position: Vec3,
+        view_direction: Vec3,
+    ) -> Vec3 {
+        let to_light = light_position - position;
+        let distance = to_light.magnitude();
+        if distance <= 0.0 {
+            return vec3(0.0, 0.0, 0.0);
+        }
+        let to_light = to_light / distance;
+        if self
+            .bvh
+            .is_occluded(shading_origin, to_light, distance - self.bias)
+        {
+            return vec3(0.0, 0.0, 0.0);
+        }
+        let (constant, linear, exponential) = attenuation;
+        let attenuation = constant + linear * distance + exponential * distance * distance;
+        self.phong(surface, color, to_light, view_direction) / attenuation.max(1.0)
+    }
+
+    // The same lighting model as in the realtime pipelines
+    fn phong(&self, surface: &Surface, color: Vec3, to_light: Vec3, view_direction: Vec3) -> Vec3 {
+        let diffuse_factor = surface.normal.dot(to_light);
+        if diffuse_factor <= 0.0 {
+            return vec3(0.0, 0.0, 0.0);
+        }
+        let mut light = color * surface.diffuse_intensity * diffuse_factor;
+        let reflected = -to_light + 2.0 * to_light.dot(surface.normal) * surface.normal;
+        let specular_factor = (-view_direction).dot(reflected.normalize());
+        if specular_factor > 0.0 {
+            light +=
+                color * surface.specular_intensity * specular_factor.powf(surface.specular_power);
+        }
+        mul(surface.color, light)
+    }
+
+    fn surface(&self, hit: &RayHit, direction: Vec3) -> Surface {
+        let mesh = &self.meshes[hit.mesh_index];
+        let indices = &self.indices[hit.mesh_index];
+        let normals = &self.normals[hit.mesh_index];
+        let vertices = [
+            indices[3 * hit.triangle_index] as usize,
+            indices[3 * hit.triangle_index + 1] as usize,
+            indices[3 * hit.triangle_index + 2] as usize,
+        ];
+        let weights = [
+            1.0 - hit.barycentric.x - hit.barycentric.y,
+            hit.barycentric.x,
+            hit.barycentric.y,
+        ];
+        let interpolate = |values: &[f32], size: usize| {
+            let mut result = [0.0; 3];
+            for (vertex, weight) in vertices.iter().zip(weights.iter()) {
+                for i in 0..size {
+                    result[i] += weight * values[size * vertex + i];
+                }
+            }
+            result
+        };
+
+        let position = |i: usize| {
+            vec3(
+                mesh.positions[3 * vertices[i]],
+                mesh.positions[3 * vertices[i] + 1],
+                mesh.positions[3 * vertices[i] + 2],
+            )
+        };
+        let n = interpolate(normals, 3);
+        let mut normal = vec3(n[0], n[1], n[2]);
+        if normal.magnitude2() < std::f32::EPSILON {
+            normal = (position(1) - position(0)).cross(position(2) - position(0));
+        }
+        normal = normal.normalize();
+        // Both sides of the triangles are shaded
+        if normal.dot(direction) > 0.0 {
+            normal = -normal;
+        }
+
+        let material = self.materials[hit.mesh_index];
+        let color = match (material.and_then(|m| m.color_texture.as_ref()), &mesh.uvs) {
+            (Some(texture), Some(uvs)) => {
+                let uv = interpolate(uvs, 2);
+                sample(texture, uv[0], uv[1])
+            }
+            _ => material
+                .and_then(|m| m.color)
+                .map(|(r, g, b, _)| vec3(r, g, b))
+                .unwrap_or(vec3(1.0, 1.0, 1.0)),
+        };
+        Surface {
+            normal,
+            color,
+            diffuse_intensity: material.and_then(|m| m.diffuse_intensity).unwrap_or(0.5),
+            specular_intensity: material.and_then(|m| m.specular_intensity).unwrap_or(0.2),
+            specular_power: material.and_then(|m| m.specular_power).unwrap_or(6.0),
+        }
+    }
+}
+
+struct Lights {
+    ambient: Vec3,
+    directional: Vec<(Vec3, Vec3)>,
+    spot: Vec<SpotLightParameters>,
+    point: Vec<(Vec3, Vec3, (f32, f32, f32))>,
+}
+
+struct SpotLightParameters {
+    color: Vec3,
+    position: Vec3,
+    direction: Vec3,
+    cutoff: f32,
+    attenuation: (f32, f32, f32),
+}
+
+struct Surface {
+    normal: Vec3,
+    color: Vec3,
+    diffuse_intensity: f32,
+    specular_intensity: f32,
+    specular_power: f32,
+}
+
+// A xorshift random number generator which makes the rendering deterministic
+struct Random {
+    state: u32,
+}
+
+impl Random {
+    fn new(seed: u32) -> Self {
+        let mut random = Self {
+            state: seed.wrapping_mul(2_654_435_761) ^ 0x9e37_79b9,
+        };
+        if random.state == 0 {
+            random.state = 1;
+        }
+        random
+    }
+
+    fn next(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+fn cosine_weighted_direction(normal: Vec3, random: &mut Random) -> Vec3 {
+    let tangent = if normal.x.abs() > 0.9 {
+        normal.cross(vec3(0.0, 1.0, 0.0)).normalize()
+    } else {
+        normal.cross(vec3(1.0, 0.0, 0.0)).normalize()
+    };
+    let bitangent = normal.cross(tangent);
+    let u1 = random.next();
+    let u2 = random.next();
+    let r = u1.sqrt();
+    let phi = 2.0 * std::f32::consts::PI * u2;
+    r * phi.cos() * tangent + r * phi.sin() * bitangent + (1.0 - u1).sqrt() * normal
+}
+
+// Returns the linear color of the texel at the given uv coordinates, where the first row of the texture is at v = 1
+fn sample(texture: &CPUTexture<u8>, u: f32, v: f32) -> Vec3 {
+    let x = ((u - u.floor()) * texture.width as f32) as usize;
+    let y = ((v.ceil() - v) * texture.height as f32) as usize;
+    let x = x.min(texture.width as usize - 1);
+    let y = y.min(texture.height as usize - 1);
+    let channels = texture.format.color_channel_count() as usize;
+    let i = channels * (y * texture.width as usize + x);
+    let value = |c: usize| {
+        let value = texture.data[i + c.min(channels - 1)] as f32 / 255.0;
+        if texture.is_srgb {
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        } else {
+            value
+        }
+    };
+    vec3(value(0), value(1), value(2))
+}
+
+fn mul(a: Vec3, b: Vec3) -> Vec3 {
+    vec3(a.x * b.x, a.y * b.y, a.z * b.z)
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).max(0.0).min(1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn srgb_from_rgb(value: f32) -> u8 {
+    let value = value.max(0.0).min(1.0);
+    let value = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (value * 255.0).round() as u8
+}
@@ -10,6 +10,10 @@ mod geometry;
 #[doc(inline)]
 pub use geometry::*;
 
+mod forward_pipeline;
+#[doc(inline)]
+pub use forward_pipeline::*;
+
 mod deferred_pipeline;
 #[doc(inline)]
 pub use deferred_pipeline::*;
@@ -28,6 +32,9 @@ fn phong_fragment_shader(
     spot_lights: usize,
     point_lights: usize,
 ) -> String {
+    let use_contact_shadows = surface_functionality
+        .find("float contact_shadow(")
+        .is_some();
     let mut dir_uniform = String::new();
     let mut dir_fun = String::new();
     for i in 0..directional_lights {
@@ -42,7 +49,8 @@ fn phong_fragment_shader(
         ));
         dir_fun.push_str(&format!("
                     color.rgb += calculate_directional_light(directionalLight{}, surface.color.rgb, surface.position, surface.normal,
-                        surface.diffuse_intensity, surface.specular_intensity, surface.specular_power, directionalShadowMap{});", i, i));
+                        surface.diffuse_intensity, surface.specular_intensity, surface.specular_power, directionalShadowMap{}){};", i, i,
+                        if use_contact_shadows {format!(" * contact_shadow(surface.position, directionalLight{}.direction)", i)} else {String::new()}));
     }
     let mut spot_uniform = String::new();
     let mut spot_fun = String::new();
@@ -58,7 +66,8 @@ fn phong_fragment_shader(
         ));
         spot_fun.push_str(&format!("
                     color.rgb += calculate_spot_light(spotLight{}, surface.color.rgb, surface.position, surface.normal,
-                        surface.diffuse_intensity, surface.specular_intensity, surface.specular_power, spotShadowMap{});", i, i));
+                        surface.diffuse_intensity, surface.specular_intensity, surface.specular_power, spotShadowMap{}){};", i, i,
+                        if use_contact_shadows {format!(" * contact_shadow(surface.position, normalize(surface.position - spotLight{}.position))", i)} else {String::new()}));
     }
     let mut point_uniform = String::new();
     let mut point_fun = String::new();
@@ -77,11 +86,10 @@ fn phong_fragment_shader(
     }
 
     format!(
-        "{}\n{}\n{}",
-        &include_str!("phong/shaders/light_shared.frag"),
+        "#include \"lights\"\n{}\n{}",
         surface_functionality,
         &format!(
-            "{}
+            "#include \"shared\"
                 uniform vec3 ambientColor;
                 layout (location = 0) out vec4 color;
 
@@ -99,7 +107,6 @@ fn phong_fragment_shader(
                     color.rgb = srgb_from_rgb(color.rgb);
                 }}
                 ",
-            include_str!("core/shared.frag"),
             &dir_uniform,
             &spot_uniform,
             &point_uniform,
@@ -142,18 +149,18 @@ fn bind_lights(
         effect.use_uniform_block(
             directional_lights[i].buffer(),
             &format!("DirectionalLightUniform{}", i),
-        );
+        )?;
     }
 
     // Spot light
     for i in 0..spot_lights.len() {
         effect.use_texture(spot_lights[i].shadow_map(), &format!("spotShadowMap{}", i))?;
-        effect.use_uniform_block(spot_lights[i].buffer(), &format!("SpotLightUniform{}", i));
+        effect.use_uniform_block(spot_lights[i].buffer(), &format!("SpotLightUniform{}", i))?;
     }
 
     // Point light
     for i in 0..point_lights.len() {
-        effect.use_uniform_block(point_lights[i].buffer(), &format!("PointLightUniform{}", i));
+        effect.use_uniform_block(point_lights[i].buffer(), &format!("PointLightUniform{}", i))?;
     }
     Ok(())
 }
@@ -22,6 +22,97 @@ pub enum DebugType {
     NONE,
 }
 
+///
+/// The precision of the geometry buffer (G-buffer) written in the [geometry pass](PhongDeferredPipeline::geometry_pass) of the deferred pipeline.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GBufferPrecision {
+    /// 8 bits per channel (RGBA8), uses the least memory bandwidth which is important on mobile GPUs.
+    Low,
+    /// 16 bit floats per channel (RGBA16F), gives more precise normals and allows high dynamic range colors.
+    /// Requires the `EXT_color_buffer_float` extension on web.
+    High,
+}
+
+impl Default for GBufferPrecision {
+    fn default() -> Self {
+        Self::Low
+    }
+}
+
+///
+/// Short screen-space shadows which are added to the shadows of the directional and spot lights in the [light pass](PhongDeferredPipeline::light_pass),
+/// see [PhongDeferredPipeline::contact_shadows]. A ray is marched a short distance from each pixel towards the light using the depth buffer,
+/// which gives fine shadows where objects touch the ground or each other that are too small to be resolved by the shadow maps.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ContactShadows {
+    /// The length of the ray in world units.
+    pub length: f32,
+    /// The number of steps along the ray, more steps give more precise shadows but are more expensive.
+    pub steps: u32,
+    /// The assumed thickness in world units of the geometry in the depth buffer, the ray is only blocked if it is at most this distance behind the geometry.
+    pub thickness: f32,
+    /// How dark the contact shadows are, between 0 (no shadow) and 1 (no light).
+    pub intensity: f32,
+}
+
+impl Default for ContactShadows {
+    fn default() -> Self {
+        Self {
+            length: 0.2,
+            steps: 16,
+            thickness: 0.05,
+            intensity: 1.0,
+        }
+    }
+}
+
+enum GeometryPassTexture {
+    Low(ColorTargetTexture2DArray<u8>),
+    High(ColorTargetTexture2DArray<u16>),
+}
+
+impl GeometryPassTexture {
+    fn new(
+        context: &Context,
+        width: u32,
+        height: u32,
+        precision: GBufferPrecision,
+    ) -> Result<Self, Error> {
+        Ok(match precision {
+            GBufferPrecision::Low => Self::Low(Self::new_array(context, width, height)?),
+            GBufferPrecision::High => Self::High(Self::new_array(context, width, height)?),
+        })
+    }
+
+    fn new_array<T: TextureDataType>(
+        context: &Context,
+        width: u32,
+        height: u32,
+    ) -> Result<ColorTargetTexture2DArray<T>, Error> {
+        ColorTargetTexture2DArray::<T>::new(
+            context,
+            width,
+            height,
+            2,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )
+    }
+
+    fn texture_array(&self) -> &dyn TextureArray {
+        match self {
+            Self::Low(texture) => texture,
+            Self::High(texture) => texture,
+        }
+    }
+}
+
 ///
 /// Deferred pipeline based on the Phong reflection model supporting a performance-limited
 /// amount of directional, point and spot lights with shadows.
@@ -34,7 +125,12 @@ pub struct PhongDeferredPipeline {
     /// Set this to visualize the positions, normals etc. for debug purposes.
     ///
     pub debug_type: DebugType,
-    geometry_pass_texture: Option<ColorTargetTexture2DArray<u8>>,
+    ///
+    /// Set this to add [contact shadows](ContactShadows) to the shadows of the directional and spot lights. Disabled by default.
+    ///
+    pub contact_shadows: Option<ContactShadows>,
+    precision: GBufferPrecision,
+    geometry_pass_texture: Option<GeometryPassTexture>,
     geometry_pass_depth_texture: Option<DepthTargetTexture2DArray>,
 }
 
@@ -43,23 +139,24 @@ impl PhongDeferredPipeline {
     /// Constructor.
     ///
     pub fn new(context: &Context) -> Result<Self, Error> {
+        Self::new_with_precision(context, GBufferPrecision::default())
+    }
+
+    ///
+    /// Constructs a new deferred pipeline where the geometry buffer has the given precision.
+    ///
+    pub fn new_with_precision(
+        context: &Context,
+        precision: GBufferPrecision,
+    ) -> Result<Self, Error> {
         let renderer = Self {
             context: context.clone(),
             program_map: HashMap::new(),
             debug_effect: None,
             debug_type: DebugType::NONE,
-            geometry_pass_texture: Some(ColorTargetTexture2DArray::new(
-                context,
-                1,
-                1,
-                2,
-                Interpolation::Nearest,
-                Interpolation::Nearest,
-                None,
-                Wrapping::ClampToEdge,
-                Wrapping::ClampToEdge,
-                Format::RGBA,
-            )?),
+            contact_shadows: None,
+            precision,
+            geometry_pass_texture: Some(GeometryPassTexture::new(context, 1, 1, precision)?),
             geometry_pass_depth_texture: Some(DepthTargetTexture2DArray::new(
                 context,
                 1,
@@ -85,17 +182,12 @@ impl PhongDeferredPipeline {
         camera: &Camera,
         geometries: &[&dyn PhongGeometry],
     ) -> Result<(), Error> {
-        self.geometry_pass_texture = Some(ColorTargetTexture2DArray::<u8>::new(
+        crate::profile_scope!("geometry pass");
+        self.geometry_pass_texture = Some(GeometryPassTexture::new(
             &self.context,
             width,
             height,
-            2,
-            Interpolation::Nearest,
-            Interpolation::Nearest,
-            None,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            Format::RGBA,
+            self.precision,
         )?);
         self.geometry_pass_depth_texture = Some(DepthTargetTexture2DArray::new(
             &self.context,
@@ -106,33 +198,45 @@ impl PhongDeferredPipeline {
             Wrapping::ClampToEdge,
             DepthFormat::Depth32F,
         )?);
-        RenderTargetArray::new(
-            &self.context,
-            self.geometry_pass_texture.as_ref().unwrap(),
-            self.geometry_pass_depth_texture.as_ref().unwrap(),
-        )?
-        .write(&[0, 1], 0, ClearState::default(), || {
+        let viewport = Viewport::new_at_origo(width, height);
+        let render = || {
             for geometry in geometries {
                 if geometry
                     .aabb()
                     .map(|aabb| camera.in_frustum(&aabb))
                     .unwrap_or(true)
+                    && geometry.layer_mask() & camera.layer_mask() != 0
                 {
-                    geometry.geometry_pass(
-                        RenderStates::default(),
-                        Viewport::new_at_origo(width, height),
-                        camera,
-                    )?;
+                    geometry.geometry_pass(RenderStates::new(viewport), camera)?;
                 }
             }
             Ok(())
-        })?;
+        };
+        let depth_texture = self.geometry_pass_depth_texture.as_ref().unwrap();
+        let clear_state = camera.clear_state(0.0, 0.0, 0.0, 1.0);
+        match self.geometry_pass_texture.as_ref().unwrap() {
+            GeometryPassTexture::Low(texture) => RenderTargetArray::new(
+                &self.context,
+                texture,
+                depth_texture,
+            )?
+            .write(&[0, 1], 0, clear_state, render)?,
+            GeometryPassTexture::High(texture) => RenderTargetArray::new(
+                &self.context,
+                texture,
+                depth_texture,
+            )?
+            .write(&[0, 1], 0, clear_state, render)?,
+        };
         Ok(())
     }
 
     ///
     /// Uses the geometry and surface material parameters written in the last [geometry_pass](Self::geometry_pass) call
     /// and all of the given lights to shade the [Phong geometries](crate::PhongGeometry).
+    /// Since the layers of the geometries are not stored in the geometry buffer, the lights which do not share any [layers](crate::DirectionalLight::set_layer_mask)
+    /// with the [camera](crate::Camera::set_layer_mask) are skipped, but the remaining lights affect all geometries.
+    /// If the camera has [reversed depth](crate::Camera::set_reverse_z), the render target must be written to with the [clear state](crate::Camera::clear_state) of the camera.
     /// Must be called in a render target render function,
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     ///
@@ -145,9 +249,10 @@ impl PhongDeferredPipeline {
         spot_lights: &[&SpotLight],
         point_lights: &[&PointLight],
     ) -> Result<(), Error> {
+        crate::profile_scope!("light pass");
         let render_states = RenderStates {
             depth_test: DepthTestType::LessOrEqual,
-            ..Default::default()
+            ..RenderStates::new(viewport)
         };
 
         if self.debug_type != DebugType::NONE {
@@ -156,35 +261,49 @@ impl PhongDeferredPipeline {
                     ImageEffect::new(&self.context, include_str!("shaders/debug.frag")).unwrap(),
                 );
             }
-            self.debug_effect.as_ref().unwrap().use_uniform_mat4(
-                "viewProjectionInverse",
-                &(camera.projection() * camera.view()).invert().unwrap(),
-            )?;
             self.debug_effect
                 .as_ref()
                 .unwrap()
-                .use_texture_array(self.geometry_pass_texture(), "gbuffer")?;
+                .use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
             self.debug_effect
                 .as_ref()
                 .unwrap()
-                .use_texture_array(self.geometry_pass_depth_texture_array(), "depthMap")?;
+                .use_texture_array(self.geometry_pass_texture(), "gbuffer")?;
             self.debug_effect
                 .as_ref()
                 .unwrap()
-                .use_uniform_int("type", &(self.debug_type as i32))?;
+                .use_texture_array(self.geometry_pass_depth_texture_array(), "depthMap")?;
             self.debug_effect
                 .as_ref()
                 .unwrap()
-                .apply(render_states, viewport)?;
+                .use_uniform_int("type", &(self.debug_type as i32))?;
+            self.debug_effect.as_ref().unwrap().apply(render_states)?;
             return Ok(());
         }
 
+        let layer_mask = camera.layer_mask();
+        let directional_lights: Vec<_> = directional_lights
+            .iter()
+            .filter(|light| light.layer_mask() & layer_mask != 0)
+            .cloned()
+            .collect();
+        let spot_lights: Vec<_> = spot_lights
+            .iter()
+            .filter(|light| light.layer_mask() & layer_mask != 0)
+            .cloned()
+            .collect();
+        let point_lights: Vec<_> = point_lights
+            .iter()
+            .filter(|light| light.layer_mask() & layer_mask != 0)
+            .cloned()
+            .collect();
         let key = format!(
-            "{},{},{},{}",
+            "{},{},{},{},{}",
             ambient_light.is_some(),
             directional_lights.len(),
             spot_lights.len(),
-            point_lights.len()
+            point_lights.len(),
+            self.contact_shadows.is_some()
         );
         if !self.program_map.contains_key(&key) {
             self.program_map.insert(
@@ -192,7 +311,15 @@ impl PhongDeferredPipeline {
                 ImageEffect::new(
                     &self.context,
                     &crate::phong::phong_fragment_shader(
-                        &include_str!("shaders/deferred_surface.frag"),
+                        &if self.contact_shadows.is_some() {
+                            format!(
+                                "{}{}",
+                                include_str!("shaders/deferred_surface.frag"),
+                                include_str!("shaders/contact_shadow.frag")
+                            )
+                        } else {
+                            include_str!("shaders/deferred_surface.frag").to_string()
+                        },
                         directional_lights.len(),
                         spot_lights.len(),
                         point_lights.len(),
@@ -205,26 +332,41 @@ impl PhongDeferredPipeline {
         crate::phong::bind_lights(
             effect,
             ambient_light,
-            directional_lights,
-            spot_lights,
-            point_lights,
+            &directional_lights,
+            &spot_lights,
+            &point_lights,
         )?;
 
         effect.use_texture_array(self.geometry_pass_texture(), "gbuffer")?;
         effect.use_texture_array(self.geometry_pass_depth_texture_array(), "depthMap")?;
+        effect.use_uniform_float("farDepth", &camera.far_depth())?;
         if !directional_lights.is_empty() || !spot_lights.is_empty() || !point_lights.is_empty() {
-            effect.use_uniform_vec3("eyePosition", &camera.position())?;
-            effect.use_uniform_mat4(
-                "viewProjectionInverse",
-                &(camera.projection() * camera.view()).invert().unwrap(),
-            )?;
+            effect.use_uniform_block(camera.uniform_buffer_for_viewport(viewport)?, "Camera")?;
+        }
+        if let Some(contact_shadows) = self.contact_shadows {
+            if !directional_lights.is_empty() || !spot_lights.is_empty() {
+                effect.use_uniform_float("contactShadowLength", &contact_shadows.length)?;
+                effect.use_uniform_int(
+                    "contactShadowSteps",
+                    &(contact_shadows.steps.max(1) as i32),
+                )?;
+                effect.use_uniform_float("contactShadowThickness", &contact_shadows.thickness)?;
+                effect.use_uniform_float("contactShadowIntensity", &contact_shadows.intensity)?;
+            }
         }
-        effect.apply(render_states, viewport)?;
+        effect.apply(render_states)?;
         Ok(())
     }
 
-    pub fn geometry_pass_texture(&self) -> &ColorTargetTexture2DArray<u8> {
-        self.geometry_pass_texture.as_ref().unwrap()
+    ///
+    /// Returns the precision of the geometry buffer.
+    ///
+    pub fn precision(&self) -> GBufferPrecision {
+        self.precision
+    }
+
+    pub fn geometry_pass_texture(&self) -> &dyn TextureArray {
+        self.geometry_pass_texture.as_ref().unwrap().texture_array()
     }
     pub fn geometry_pass_depth_texture_array(&self) -> &DepthTargetTexture2DArray {
         self.geometry_pass_depth_texture.as_ref().unwrap()
@@ -0,0 +1,199 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::light::*;
+use crate::math::*;
+use crate::phong::*;
+
+///
+/// Statistics of the last [render](PhongForwardPipeline::render) call of a [PhongForwardPipeline],
+/// for example used to verify the effect of the [depth pre-pass](PhongForwardPipeline::depth_pre_pass).
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ForwardPipelineStats {
+    /// The number of geometries rendered in the depth pre-pass.
+    pub depth_pre_pass_draw_calls: usize,
+    /// The number of geometries rendered with lighting.
+    pub color_pass_draw_calls: usize,
+    /// The number of geometries which were not rendered because they were outside the camera frustum.
+    pub culled_geometries: usize,
+}
+
+///
+/// Forward pipeline based on the Phong reflection model supporting a performance-limited
+/// amount of directional, point and spot lights with shadows.
+/// Each geometry is shaded with all of the lights when it is rendered.
+///
+pub struct PhongForwardPipeline {
+    ///
+    /// Whether or not to render the depth of all geometries before shading them.
+    /// The geometries are then only shaded where they are visible which avoids shading fragments that are later overwritten (overdraw),
+    /// at the cost of rendering all geometries twice. This is beneficial when the fragment shading is expensive, for example with many lights.
+    ///
+    pub depth_pre_pass: bool,
+    stats: ForwardPipelineStats,
+    context: Context,
+    depth_texture: Option<DepthTargetTexture2D>,
+}
+
+impl PhongForwardPipeline {
+    ///
+    /// Constructor.
+    ///
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            depth_pre_pass: false,
+            stats: ForwardPipelineStats::default(),
+            context: context.clone(),
+            depth_texture: None,
+        })
+    }
+
+    ///
+    /// Render the given opaque [Phong geometries](crate::PhongForwardGeometry) shaded with the given lights.
+    /// Geometries outside the camera frustum or not in the [layers](crate::Camera::set_layer_mask) of the camera are skipped
+    /// and each geometry is only shaded with the lights which affect its [layers](crate::Geometry::layer_mask).
+    /// Transparent geometries should be rendered afterwards, since they should not be part of the depth pre-pass.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(
+        &mut self,
+        viewport: Viewport,
+        camera: &Camera,
+        ambient_light: Option<&AmbientLight>,
+        directional_lights: &[&DirectionalLight],
+        spot_lights: &[&SpotLight],
+        point_lights: &[&PointLight],
+        geometries: &[&dyn PhongForwardGeometry],
+    ) -> Result<(), Error> {
+        let mut stats = ForwardPipelineStats::default();
+        let geometries: Vec<_> = geometries
+            .iter()
+            .filter(|geometry| geometry.layer_mask() & camera.layer_mask() != 0)
+            .collect();
+        let visible_geometries: Vec<_> = geometries
+            .iter()
+            .filter(|geometry| {
+                geometry
+                    .aabb()
+                    .map(|aabb| camera.in_frustum(&aabb))
+                    .unwrap_or(true)
+            })
+            .collect();
+        stats.culled_geometries = geometries.len() - visible_geometries.len();
+
+        let render_states = if self.depth_pre_pass {
+            let depth_render_states = RenderStates {
+                write_mask: WriteMask::DEPTH,
+                ..RenderStates::new(viewport)
+            };
+            for geometry in visible_geometries.iter() {
+                geometry.render_depth(depth_render_states, camera)?;
+                stats.depth_pre_pass_draw_calls += 1;
+            }
+            // The depth is already written, so only the closest fragments pass the depth test
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTestType::LessOrEqual,
+                ..RenderStates::new(viewport)
+            }
+        } else {
+            RenderStates::new(viewport)
+        };
+
+        for geometry in visible_geometries.iter() {
+            let layer_mask = geometry.layer_mask();
+            let directional_lights: Vec<_> = directional_lights
+                .iter()
+                .filter(|light| light.layer_mask() & layer_mask != 0)
+                .cloned()
+                .collect();
+            let spot_lights: Vec<_> = spot_lights
+                .iter()
+                .filter(|light| light.layer_mask() & layer_mask != 0)
+                .cloned()
+                .collect();
+            let point_lights: Vec<_> = point_lights
+                .iter()
+                .filter(|light| light.layer_mask() & layer_mask != 0)
+                .cloned()
+                .collect();
+            geometry.render_with_lighting(
+                render_states,
+                camera,
+                ambient_light,
+                &directional_lights,
+                &spot_lights,
+                &point_lights,
+            )?;
+            stats.color_pass_draw_calls += 1;
+        }
+        self.stats = stats;
+        Ok(())
+    }
+
+    ///
+    /// Renders like [render](Self::render), but into the given color texture, which is first cleared to the given color,
+    /// together with a float depth buffer owned by this pipeline, which is cleared to the [far depth](crate::Camera::far_depth) of the camera.
+    /// Use this to get the full depth precision of a camera with [reversed depth](crate::Camera::set_reverse_z),
+    /// and then for example [copy](crate::ColorTargetTexture2D::copy_to) the texture to the screen.
+    /// Must not be called in a render target render function.
+    ///
+    pub fn render_to_texture<T: TextureDataType>(
+        &mut self,
+        color_texture: &ColorTargetTexture2D<T>,
+        clear_color: Vec4,
+        camera: &Camera,
+        ambient_light: Option<&AmbientLight>,
+        directional_lights: &[&DirectionalLight],
+        spot_lights: &[&SpotLight],
+        point_lights: &[&PointLight],
+        geometries: &[&dyn PhongForwardGeometry],
+    ) -> Result<(), Error> {
+        let (width, height) = (color_texture.width(), color_texture.height());
+        if self
+            .depth_texture
+            .as_ref()
+            .map(|texture| texture.width() != width || texture.height() != height)
+            .unwrap_or(true)
+        {
+            self.depth_texture = Some(DepthTargetTexture2D::new(
+                &self.context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )?);
+        }
+        let depth_texture = self.depth_texture.take().unwrap();
+        let result = RenderTarget::new(&self.context, color_texture, &depth_texture).and_then(
+            |render_target| {
+                render_target.write(
+                    camera.clear_state(clear_color.x, clear_color.y, clear_color.z, clear_color.w),
+                    || {
+                        self.render(
+                            Viewport::new_at_origo(width, height),
+                            camera,
+                            ambient_light,
+                            directional_lights,
+                            spot_lights,
+                            point_lights,
+                            geometries,
+                        )
+                    },
+                )
+            },
+        );
+        self.depth_texture = Some(depth_texture);
+        result
+    }
+
+    ///
+    /// Returns the statistics of the last [render](Self::render) call.
+    ///
+    pub fn stats(&self) -> ForwardPipelineStats {
+        self.stats
+    }
+}
@@ -1,6 +1,6 @@
 use crate::camera::*;
 use crate::core::*;
-use crate::math::*;
+use crate::light::*;
 use crate::Geometry;
 
 ///
@@ -11,10 +11,26 @@ pub trait PhongGeometry: Geometry {
     ///
     /// Render the geometry and surface material parameters of the mesh, ie. the first part of a [deferred render pass](crate::PhongDeferredPipeline::geometry_pass).
     ///
-    fn geometry_pass(
+    fn geometry_pass(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error>;
+}
+
+///
+/// Used for [forward Phong rendering](crate::PhongForwardPipeline).
+/// Implemented by [PhongMesh](crate::PhongMesh) and [PhongInstancedMesh](crate::PhongInstancedMesh).
+///
+pub trait PhongForwardGeometry: Geometry {
+    ///
+    /// Render the geometry shaded with the given lights based on the Phong shading model.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    fn render_with_lighting(
         &self,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
+        ambient_light: Option<&AmbientLight>,
+        directional_lights: &[&DirectionalLight],
+        spot_lights: &[&SpotLight],
+        point_lights: &[&PointLight],
     ) -> Result<(), Error>;
 }
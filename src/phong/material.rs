@@ -35,6 +35,14 @@ pub struct PhongMaterial {
     pub diffuse_intensity: f32,
     pub specular_intensity: f32,
     pub specular_power: f32,
+    /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped when rendering an object with this material,
+    /// for example `Some(CullType::None)` for double sided foliage cards.
+    /// Overrides the cull setting of the render states if set, and is overridden by the cull setting of the object itself.
+    pub cull: Option<CullType>,
+    /// Defines how the alpha value of the color is used.
+    /// Note that [AlphaMode::Blend] is only supported when rendering with [PhongMesh::render_with_lighting](crate::PhongMesh::render_with_lighting)
+    /// and [PhongForwardPipeline](crate::PhongForwardPipeline), the [deferred pipeline](crate::PhongDeferredPipeline) renders the object as opaque.
+    pub alpha_mode: AlphaMode,
 }
 
 impl PhongMaterial {
@@ -42,8 +50,32 @@ impl PhongMaterial {
     /// Constructor.
     ///
     pub fn new(context: &Context, cpu_material: &CPUMaterial) -> Result<Self, Error> {
-        let color_source = if let Some(ref cpu_texture) = cpu_material.color_texture {
-            ColorSource::Texture(Rc::new(Texture2D::new(&context, cpu_texture)?))
+        let texture = if let Some(ref cpu_texture) = cpu_material.color_texture {
+            Some(Rc::new(Texture2D::new(&context, cpu_texture)?))
+        } else {
+            None
+        };
+        Ok(Self::new_with_texture(cpu_material, texture))
+    }
+
+    ///
+    /// Constructor which gets the texture from the given texture cache, so that materials with identical textures share the same texture on the GPU.
+    ///
+    pub fn new_with_cache(
+        cpu_material: &CPUMaterial,
+        texture_cache: &mut TextureCache,
+    ) -> Result<Self, Error> {
+        let texture = if let Some(ref cpu_texture) = cpu_material.color_texture {
+            Some(texture_cache.texture(cpu_texture)?)
+        } else {
+            None
+        };
+        Ok(Self::new_with_texture(cpu_material, texture))
+    }
+
+    fn new_with_texture(cpu_material: &CPUMaterial, texture: Option<Rc<Texture2D>>) -> Self {
+        let color_source = if let Some(texture) = texture {
+            ColorSource::Texture(texture)
         } else {
             ColorSource::Color(
                 cpu_material
@@ -52,20 +84,31 @@ impl PhongMaterial {
                     .unwrap_or(vec4(1.0, 1.0, 1.0, 1.0)),
             )
         };
-        Ok(Self {
+        Self {
             name: cpu_material.name.clone(),
             color_source,
             diffuse_intensity: cpu_material.diffuse_intensity.unwrap_or(0.5),
             specular_intensity: cpu_material.specular_intensity.unwrap_or(0.2),
             specular_power: cpu_material.specular_power.unwrap_or(6.0),
-        })
+            cull: cpu_material.double_sided.map(|double_sided| {
+                if double_sided {
+                    CullType::None
+                } else {
+                    CullType::Back
+                }
+            }),
+            alpha_mode: cpu_material.alpha_mode.unwrap_or_default(),
+        }
     }
 
     pub(crate) fn bind(&self, program: &Program) -> Result<(), Error> {
         program.use_uniform_float("diffuse_intensity", &self.diffuse_intensity)?;
         program.use_uniform_float("specular_intensity", &self.specular_intensity)?;
         program.use_uniform_float("specular_power", &self.specular_power)?;
+        self.bind_color(program)
+    }
 
+    pub(crate) fn bind_color(&self, program: &Program) -> Result<(), Error> {
         match self.color_source {
             ColorSource::Color(ref color) => {
                 program.use_uniform_vec4("surfaceColor", color)?;
@@ -74,8 +117,45 @@ impl PhongMaterial {
                 program.use_texture(texture.as_ref(), "tex")?;
             }
         }
+        if let AlphaMode::Mask { cutoff } = self.alpha_mode {
+            program.use_uniform_float("alpha_cutoff", &cutoff)?;
+        }
         Ok(())
     }
+
+    ///
+    /// Returns the shader source which must be added to the fragment shaders to discard the fragments
+    /// below the alpha cutoff when the alpha mode is [AlphaMode::Mask].
+    ///
+    pub(crate) fn alpha_cutout_source(&self) -> &'static str {
+        match self.alpha_mode {
+            AlphaMode::Mask { .. } => "#define AlphaCutout;\nuniform float alpha_cutoff;",
+            _ => "",
+        }
+    }
+
+    pub(crate) fn is_alpha_cutout(&self) -> bool {
+        match self.alpha_mode {
+            AlphaMode::Mask { .. } => true,
+            _ => false,
+        }
+    }
+
+    ///
+    /// Returns the render states used when rendering an object with this material with lighting, ie. with the
+    /// cull setting of this material and with transparency blending if the alpha mode is [AlphaMode::Blend].
+    ///
+    pub(crate) fn forward_render_states(&self, render_states: RenderStates) -> RenderStates {
+        let render_states = render_states.override_cull(self.cull);
+        if self.alpha_mode == AlphaMode::Blend && render_states.blend.is_none() {
+            RenderStates {
+                blend: Some(BlendParameters::TRANSPARENCY),
+                ..render_states
+            }
+        } else {
+            render_states
+        }
+    }
 }
 
 impl Default for PhongMaterial {
@@ -86,6 +166,8 @@ impl Default for PhongMaterial {
             diffuse_intensity: 0.5,
             specular_intensity: 0.2,
             specular_power: 6.0,
+            cull: None,
+            alpha_mode: AlphaMode::Opaque,
         }
     }
 }
@@ -43,7 +43,6 @@ impl PhongInstancedMesh {
     pub fn render_with_lighting(
         &self,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
         ambient_light: Option<&AmbientLight>,
         directional_lights: &[&DirectionalLight],
@@ -51,8 +50,9 @@ impl PhongInstancedMesh {
         point_lights: &[&PointLight],
     ) -> Result<(), Error> {
         let key = format!(
-            "{},{},{},{}",
+            "{},{},{},{},{}",
             self.material.color_source,
+            self.material.is_alpha_cutout(),
             directional_lights.len(),
             spot_lights.len(),
             point_lights.len()
@@ -63,11 +63,12 @@ impl PhongInstancedMesh {
             }
             if !PROGRAMS.as_ref().unwrap().contains_key(&key) {
                 let surface_functionality = format!(
-                    "{}\n{}",
+                    "{}\n{}\n{}",
                     match self.material.color_source {
                         ColorSource::Color(_) => "",
                         ColorSource::Texture(_) => "#define UseColorTexture;\nin vec2 uvs;",
                     },
+                    self.material.alpha_cutout_source(),
                     include_str!("shaders/forward_surface.frag")
                 );
                 let fragment_shader_source = phong_fragment_shader(
@@ -93,73 +94,133 @@ impl PhongInstancedMesh {
         )?;
 
         if !directional_lights.is_empty() || !spot_lights.is_empty() || !point_lights.is_empty() {
-            program.use_uniform_vec3("eyePosition", &camera.position())?;
             self.material.bind(program)?;
         } else {
-            match self.material.color_source {
-                ColorSource::Color(ref color) => {
-                    program.use_uniform_vec4("surfaceColor", color)?;
-                }
-                ColorSource::Texture(ref texture) => {
-                    program.use_texture(texture.as_ref(), "tex")?;
-                }
-            }
+            self.material.bind_color(program)?;
         }
-        self.mesh.render(program, render_states, viewport, camera)?;
+        self.mesh.render(
+            program,
+            self.material.forward_render_states(render_states),
+            camera,
+        )?;
         Ok(())
     }
+
+    ///
+    /// Returns the program used for rendering the depth of this mesh, if the material has an alpha cutout.
+    ///
+    fn alpha_cutout_program(&self, pick: bool) -> Result<Option<&InstancedMeshProgram>, Error> {
+        if !self.material.is_alpha_cutout() {
+            return Ok(None);
+        }
+        let key = format!("{}DepthCutout,{}", self.material.color_source, pick);
+        unsafe {
+            if PROGRAMS.is_none() {
+                PROGRAMS = Some(std::collections::HashMap::new());
+            }
+            if !PROGRAMS.as_ref().unwrap().contains_key(&key) {
+                let fragment_shader_source = format!(
+                    "{}\n{}\n{}",
+                    match self.material.color_source {
+                        ColorSource::Color(_) => "",
+                        ColorSource::Texture(_) => "#define UseColorTexture;\nin vec2 uvs;",
+                    },
+                    if pick {
+                        "#define Pick;\nin vec3 pos;"
+                    } else {
+                        ""
+                    },
+                    include_str!("shaders/depth_cutout.frag")
+                );
+                PROGRAMS.as_mut().unwrap().insert(
+                    key.clone(),
+                    InstancedMeshProgram::new(&self.context, &fragment_shader_source)?,
+                );
+            }
+            Ok(PROGRAMS.as_ref().unwrap().get(&key))
+        }
+    }
 }
 
 impl Geometry for PhongInstancedMesh {
     fn render_depth_to_red(
         &self,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
         max_depth: f32,
     ) -> Result<(), Error> {
-        self.mesh
-            .render_depth_to_red(render_states, viewport, camera, max_depth)
+        let render_states = render_states.override_cull(self.material.cull);
+        if let Some(program) = self.alpha_cutout_program(true)? {
+            self.material.bind_color(program)?;
+            program.use_uniform_float("maxDistance", &max_depth)?;
+            self.mesh.render(program, render_states, camera)
+        } else {
+            self.mesh
+                .render_depth_to_red(render_states, camera, max_depth)
+        }
     }
 
-    fn render_depth(
-        &self,
-        render_states: RenderStates,
-        viewport: Viewport,
-        camera: &Camera,
-    ) -> Result<(), Error> {
-        self.mesh.render_depth(render_states, viewport, camera)
+    fn render_depth(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
+        let render_states = render_states.override_cull(self.material.cull);
+        if let Some(program) = self.alpha_cutout_program(false)? {
+            self.material.bind_color(program)?;
+            self.mesh.render(program, render_states, camera)
+        } else {
+            self.mesh.render_depth(render_states, camera)
+        }
     }
 
     fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
         self.mesh.aabb()
     }
+
+    fn layer_mask(&self) -> u32 {
+        self.mesh.layer_mask
+    }
 }
 
-impl PhongGeometry for PhongInstancedMesh {
-    fn geometry_pass(
+impl PhongForwardGeometry for PhongInstancedMesh {
+    fn render_with_lighting(
         &self,
         render_states: RenderStates,
-        viewport: Viewport,
         camera: &Camera,
+        ambient_light: Option<&AmbientLight>,
+        directional_lights: &[&DirectionalLight],
+        spot_lights: &[&SpotLight],
+        point_lights: &[&PointLight],
     ) -> Result<(), Error> {
+        PhongInstancedMesh::render_with_lighting(
+            self,
+            render_states,
+            camera,
+            ambient_light,
+            directional_lights,
+            spot_lights,
+            point_lights,
+        )
+    }
+}
+
+impl PhongGeometry for PhongInstancedMesh {
+    fn geometry_pass(&self, render_states: RenderStates, camera: &Camera) -> Result<(), Error> {
         let program = unsafe {
             if PROGRAMS.is_none() {
                 PROGRAMS = Some(std::collections::HashMap::new());
             }
-            let key = match self.material.color_source {
-                ColorSource::Color(_) => "ColorDeferred",
-                ColorSource::Texture(_) => "TextureDeferred",
-            };
-            if !PROGRAMS.as_ref().unwrap().contains_key(key) {
+            let key = format!(
+                "{}Deferred,{}",
+                self.material.color_source,
+                self.material.is_alpha_cutout()
+            );
+            if !PROGRAMS.as_ref().unwrap().contains_key(&key) {
                 PROGRAMS.as_mut().unwrap().insert(
-                    key.to_string(),
+                    key.clone(),
                     match self.material.color_source {
                         ColorSource::Color(_) => InstancedMeshProgram::new(
                             &self.context,
                             &format!(
                                 "{}\n{}",
-                                include_str!("shaders/deferred_objects_shared.frag"),
+                                self.material.alpha_cutout_source(),
                                 include_str!("shaders/deferred_color.frag")
                             ),
                         )?,
@@ -167,17 +228,21 @@ impl PhongGeometry for PhongInstancedMesh {
                             &self.context,
                             &format!(
                                 "{}\n{}",
-                                include_str!("shaders/deferred_objects_shared.frag"),
+                                self.material.alpha_cutout_source(),
                                 include_str!("shaders/deferred_texture.frag")
                             ),
                         )?,
                     },
                 );
             };
-            PROGRAMS.as_ref().unwrap().get(key).unwrap()
+            PROGRAMS.as_ref().unwrap().get(&key).unwrap()
         };
         self.material.bind(program)?;
-        self.mesh.render(program, render_states, viewport, camera)
+        self.mesh.render(
+            program,
+            render_states.override_cull(self.material.cull),
+            camera,
+        )
     }
 }
 
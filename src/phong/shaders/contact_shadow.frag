@@ -0,0 +1,40 @@
+
+uniform float contactShadowLength;
+uniform int contactShadowSteps;
+uniform float contactShadowThickness;
+uniform float contactShadowIntensity;
+
+float view_depth(vec2 screen_uv, float depth)
+{
+    vec4 p = camera.projectionInverse * vec4(screen_uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
+    return -p.z / p.w;
+}
+
+// Marches a short ray from the surface towards the light in screen space and returns how much light is not blocked
+// by the geometry in the depth buffer, which adds fine shadows where objects touch that the shadow maps cannot resolve.
+float contact_shadow(vec3 position, vec3 light_direction)
+{
+    vec3 ray_step = -light_direction * contactShadowLength / float(contactShadowSteps);
+    vec3 p = position;
+    for (int i = 0; i < contactShadowSteps; i++)
+    {
+        p += ray_step;
+        vec4 clip = camera.viewProjection * vec4(p, 1.0);
+        vec3 ndc = clip.xyz / clip.w;
+        vec2 screen_uv = ndc.xy * 0.5 + 0.5;
+        if(screen_uv.x < 0.0 || screen_uv.x > 1.0 || screen_uv.y < 0.0 || screen_uv.y > 1.0)
+        {
+            break;
+        }
+        float ray_depth = -(camera.view * vec4(p, 1.0)).z;
+        float scene_depth = view_depth(screen_uv, texture(depthMap, vec3(screen_uv, 0)).r);
+        float delta = ray_depth - scene_depth;
+        if(delta > 0.0 && delta < contactShadowThickness)
+        {
+            // Fade out the shadow towards the end of the ray to avoid a hard cut
+            float fade = 1.0 - float(i) / float(contactShadowSteps);
+            return 1.0 - contactShadowIntensity * fade;
+        }
+    }
+    return 1.0;
+}
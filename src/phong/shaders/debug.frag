@@ -4,7 +4,7 @@ uniform sampler2DArray depthMap;
 
 uniform int type;
 
-uniform mat4 viewProjectionInverse;
+#include "camera"
 
 in vec2 uv;
 
@@ -22,7 +22,7 @@ float linear_depth(float z)
 
 vec3 WorldPosFromDepth(float depth, vec2 uv) {
     vec4 clipSpacePosition = vec4(uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
-    vec4 position = viewProjectionInverse * clipSpacePosition;
+    vec4 position = camera.viewProjectionInverse * clipSpacePosition;
     return position.xyz / position.w;
 }
 
@@ -55,14 +55,14 @@ void main()
     else if(type == 5) // Specular
     {
         float nw = texture(gbuffer, vec3(uv, 1)).w;
-        int t = int(floor(nw*255.0));
+        int t = int(round(nw*255.0));
         float val = float(t & 15) / 15.0;
         color = vec4(val, val, val, 1.);
     }
     else if(type == 6) // Specular power
     {
         float nw = texture(gbuffer, vec3(uv, 1)).w;
-        int t = int(floor(nw*255.0));
+        int t = int(round(nw*255.0));
         float val = 2.0 * float((t & 240) >> 4);
         val /= 30.0;
         color = vec4(val, val, val, 1.);
@@ -1,3 +1,4 @@
+#include "deferred_objects"
 
 uniform vec4 surfaceColor;
 uniform float diffuse_intensity;
@@ -9,6 +10,9 @@ in vec3 pos;
 
 void main()
 {
+#ifdef AlphaCutout
+    if (surfaceColor.a < alpha_cutoff) discard;
+#endif
 	vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
 	write(normal, surfaceColor.rgb, diffuse_intensity, specular_intensity, specular_power);
 }
\ No newline at end of file
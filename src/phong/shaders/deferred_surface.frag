@@ -1,20 +1,21 @@
 
 uniform sampler2DArray gbuffer;
 uniform sampler2DArray depthMap;
-uniform mat4 viewProjectionInverse;
+uniform float farDepth;
 
 in vec2 uv;
 
 vec3 WorldPosFromDepth(float depth, vec2 uv) {
     vec4 clipSpacePosition = vec4(uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
-    vec4 position = viewProjectionInverse * clipSpacePosition;
+    vec4 position = camera.viewProjectionInverse * clipSpacePosition;
     return position.xyz / position.w;
 }
 
 float get_surface_depth()
 {
     float depth = texture(depthMap, vec3(uv,0)).r;
-    if(depth > 0.99999)
+    // Nothing is rendered where the depth is still the clear value, ie. the depth of the far plane
+    if(abs(depth - farDepth) < 0.00001)
     {
         discard;
     }
@@ -37,7 +38,7 @@ Surface get_surface()
     vec4 n = texture(gbuffer, vec3(uv, 1));
     vec3 normal = normalize(n.xyz*2.0 - 1.0);
     float diffuse_intensity = c.w;
-    int t = int(floor(n.w*255.0));
+    int t = int(round(n.w*255.0));
     float specular_intensity = float(t & 15) / 15.0;
     float specular_power = 2.0 * float((t & 240) >> 4);
 
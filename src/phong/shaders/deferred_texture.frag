@@ -1,3 +1,4 @@
+#include "deferred_objects"
 
 uniform sampler2D tex;
 uniform float diffuse_intensity;
@@ -11,6 +12,9 @@ in vec2 uvs;
 void main()
 {
 	vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
-    vec3 color = texture(tex, vec2(uvs.x, 1.0 - uvs.y)).rgb;
-	write(normal, color, diffuse_intensity, specular_intensity, specular_power);
+    vec4 color = texture(tex, vec2(uvs.x, 1.0 - uvs.y));
+#ifdef AlphaCutout
+    if (color.a < alpha_cutoff) discard;
+#endif
+	write(normal, color.rgb, diffuse_intensity, specular_intensity, specular_power);
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+uniform vec4 surfaceColor;
+uniform sampler2D tex;
+uniform float alpha_cutoff;
+
+#ifdef Pick
+#include "camera"
+
+uniform float maxDistance;
+
+layout (location = 0) out vec4 outColor;
+#endif
+
+void main()
+{
+#ifdef UseColorTexture
+    float alpha = texture(tex, vec2(uvs.x, 1.0 - uvs.y)).a;
+#else
+    float alpha = surfaceColor.a;
+#endif
+    if (alpha < alpha_cutoff) discard;
+#ifdef Pick
+    outColor = vec4(distance(pos, camera.position) / maxDistance, 0.0, 0.0, 0.0);
+#endif
+}
@@ -15,6 +15,9 @@ Surface get_surface()
     color = texture(tex, vec2(uvs.x, 1.0 - uvs.y));
 #else 
     color = surfaceColor;
+#endif
+#ifdef AlphaCutout
+    if (color.a < alpha_cutoff) discard;
 #endif
     vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
     return Surface(pos, normal, color, diffuse_intensity, specular_intensity, specular_power);
@@ -1,5 +1,5 @@
 
-uniform vec3 eyePosition;
+#include "camera"
 
 struct Surface
 {
@@ -31,6 +31,7 @@ struct DirectionalLight
     vec3 direction;
     float shadowEnabled;
     mat4 shadowMVP;
+    vec4 softShadow; // Light size, shadow map uv per unit, near and far plane of the shadow camera
 };
 
 struct PointLight
@@ -50,6 +51,7 @@ struct SpotLight
     vec3 direction;
     float shadowEnabled;
     mat4 shadowMVP;
+    vec4 softShadow; // Light size, shadow map uv per unit, near and far plane of the shadow camera
 };
 
 vec3 calculate_light(BaseLight light, vec3 lightDirection, vec3 position, vec3 normal,
@@ -64,7 +66,7 @@ vec3 calculate_light(BaseLight light, vec3 lightDirection, vec3 position, vec3 n
     {
         DiffuseColor = light.color * diffuse_intensity * light.intensity * DiffuseFactor;
 
-        vec3 VertexToEye = normalize(eyePosition - position);
+        vec3 VertexToEye = normalize(camera.position - position);
         vec3 lightReflect = normalize(reflect(lightDirection, normal));
         float SpecularFactor = dot(VertexToEye, lightReflect);
         if (SpecularFactor > 0.0)
@@ -102,23 +104,88 @@ float is_visible(sampler2D shadowMap, vec4 shadow_coord, vec2 offset)
     return uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0 || shadow_cast_distance > true_distance ? 1.0 : 0.0;
 }
 
-float calculate_shadow(sampler2D shadowMap, mat4 shadowMVP, vec3 position)
+const vec2 poissonDisk16[16] = vec2[](
+    vec2( -0.94201624, -0.39906216 ),
+    vec2( 0.94558609, -0.76890725 ),
+    vec2( -0.094184101, -0.92938870 ),
+    vec2( 0.34495938, 0.29387760 ),
+    vec2( -0.91588581, 0.45771432 ),
+    vec2( -0.81544232, -0.87912464 ),
+    vec2( -0.38277543, 0.27676845 ),
+    vec2( 0.97484398, 0.75648379 ),
+    vec2( 0.44323325, -0.97511554 ),
+    vec2( 0.53742981, -0.47373420 ),
+    vec2( -0.26496911, -0.41893023 ),
+    vec2( 0.79197514, 0.19090188 ),
+    vec2( -0.24188840, 0.99706507 ),
+    vec2( -0.81409955, 0.91437590 ),
+    vec2( 0.19984126, 0.78641367 ),
+    vec2( 0.14383161, -0.14100790 )
+);
+
+float linear_shadow_depth(float depth, vec4 softShadow, bool perspective)
+{
+    float near = softShadow.z;
+    float far = softShadow.w;
+    return perspective ? near * far / (far - depth * (far - near)) : depth * far;
+}
+
+// Percentage-closer soft shadows: The average depth of the blockers is used to estimate the size of the penumbra,
+// such that the shadow is sharp close to the caster and softens with the distance from the caster.
+float calculate_soft_shadow(sampler2D shadowMap, vec4 shadow_coord, vec4 softShadow, bool perspective)
+{
+    vec2 uv = shadow_coord.xy / shadow_coord.w;
+    float receiver_depth = (shadow_coord.z - 0.005) / shadow_coord.w;
+    float receiver = linear_shadow_depth(receiver_depth, softShadow, perspective);
+    float uv_per_unit = perspective ? softShadow.y / receiver : softShadow.y;
+
+    // Blocker search
+    float search_radius = min(softShadow.x * uv_per_unit * (perspective ? 1.0 : receiver), 0.05);
+    float blocker_sum = 0.0;
+    float blocker_count = 0.0;
+    for (int i=0;i<16;i++)
+    {
+        float depth = texture(shadowMap, uv + poissonDisk16[i] * search_radius).x;
+        if(depth < receiver_depth)
+        {
+            blocker_sum += linear_shadow_depth(depth, softShadow, perspective);
+            blocker_count += 1.0;
+        }
+    }
+    if(blocker_count < 0.5)
+    {
+        return 1.0;
+    }
+    float blocker = blocker_sum / blocker_count;
+
+    // Penumbra estimation
+    float penumbra = perspective ? (receiver - blocker) / max(blocker, 0.0001) : receiver - blocker;
+    float filter_radius = clamp(penumbra * softShadow.x * uv_per_unit, 0.0005, 0.05);
+
+    // Filtering
+    float visibility = 0.0;
+    for (int i=0;i<16;i++)
+    {
+        visibility += is_visible(shadowMap, shadow_coord, poissonDisk16[i] * filter_radius * shadow_coord.w);
+    }
+    return visibility / 16.0;
+}
+
+float calculate_shadow(sampler2D shadowMap, mat4 shadowMVP, vec4 softShadow, bool perspective, vec3 position)
 {
     if(shadowMVP[3][3] < 0.1) // Shadow disabled
     {
         return 1.0;
     }
     vec4 shadow_coord = shadowMVP * vec4(position, 1.);
+    if(softShadow.x > 0.0)
+    {
+        return calculate_soft_shadow(shadowMap, shadow_coord, softShadow, perspective);
+    }
     float visibility = 0.0;
-    vec2 poissonDisk[4] = vec2[](
-                                 vec2( -0.94201624, -0.39906216 ),
-                                 vec2( 0.94558609, -0.76890725 ),
-                                 vec2( -0.094184101, -0.92938870 ),
-                                 vec2( 0.34495938, 0.29387760 )
-                                 );
     for (int i=0;i<4;i++)
     {
-        visibility += is_visible(shadowMap, shadow_coord, poissonDisk[i] * 0.001f);
+        visibility += is_visible(shadowMap, shadow_coord, poissonDisk16[i] * 0.001f);
     }
     return visibility * 0.25;
 }
@@ -129,7 +196,7 @@ vec3 calculate_directional_light(DirectionalLight directionalLight, vec3 surface
     vec3 light = calculate_light(directionalLight.base, directionalLight.direction, position, normal,
         diffuse_intensity, specular_intensity, specular_power);
     if(directionalLight.shadowEnabled > 0.5) {
-        light *= calculate_shadow(shadowMap, directionalLight.shadowMVP, position);
+        light *= calculate_shadow(shadowMap, directionalLight.shadowMVP, directionalLight.softShadow, false, position);
     }
     return surface_color * light;
 }
@@ -153,7 +220,7 @@ vec3 calculate_spot_light(SpotLight spotLight, vec3 surface_color, vec3 position
         light = calculate_attenuated_light(spotLight.base, spotLight.attenuation, spotLight.position, position, normal,
             diffuse_intensity, specular_intensity, specular_power) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));
         if(spotLight.shadowEnabled > 0.5) {
-            light *= calculate_shadow(shadowMap, spotLight.shadowMVP, position);
+            light *= calculate_shadow(shadowMap, spotLight.shadowMVP, spotLight.softShadow, true, position);
         }
     }
     return surface_color * light;
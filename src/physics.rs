@@ -0,0 +1,11 @@
+//!
+//! Integration with the [rapier3d](https://rapier.rs) physics engine (only available when the `rapier-physics` feature is enabled).
+//! Keeps the transformations of rigid bodies and objects, for example a [Mesh](crate::Mesh), synchronized each frame
+//! and visualizes colliders using [DebugLines](crate::DebugLines).
+//!
+
+pub use rapier3d;
+
+mod rapier_bridge;
+#[doc(inline)]
+pub use rapier_bridge::*;
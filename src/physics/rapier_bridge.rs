@@ -0,0 +1,295 @@
+use crate::math::*;
+use crate::object::*;
+use rapier3d::na::{Quaternion, Translation3, UnitQuaternion};
+use rapier3d::prelude::{Collider, ColliderSet, Isometry, Real, RigidBodyHandle, RigidBodySet};
+
+///
+/// An object which has a transformation that can be synchronized with a rigid body, see [PhysicsBridge].
+///
+pub trait Transformable {
+    ///
+    /// Returns the transformation from the local space of the object to world space.
+    ///
+    fn transformation(&self) -> Mat4;
+
+    ///
+    /// Sets the transformation from the local space of the object to world space.
+    ///
+    fn set_transformation(&mut self, transformation: Mat4);
+}
+
+impl Transformable for Mesh {
+    fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+}
+
+impl Transformable for InstancedMesh {
+    fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+}
+
+impl Transformable for Particles {
+    fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+}
+
+///
+/// The direction in which a transformation is synchronized by a [PhysicsBridge].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SyncDirection {
+    /// The object follows the rigid body, for example used for dynamic rigid bodies which are moved by the simulation.
+    BodyToObject,
+    /// The rigid body follows the object, for example used for kinematic rigid bodies which are moved by the application.
+    ObjectToBody,
+}
+
+///
+/// Keeps track of which rigid bodies in a rapier3d [RigidBodySet] belong to which objects and synchronizes their transformations.
+/// The objects are identified by an index chosen by the application, typically the index of the object in a list of objects.
+/// Call [sync](PhysicsBridge::sync) once each frame after stepping the simulation.
+///
+/// The scale of an object is not part of the rigid body position, so the object keeps its scale when following a rigid body.
+///
+#[derive(Debug, Default)]
+pub struct PhysicsBridge {
+    bindings: Vec<(usize, RigidBodyHandle, SyncDirection)>,
+}
+
+impl PhysicsBridge {
+    ///
+    /// Constructs a new bridge without any objects.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Binds the object with the given index to the rigid body with the given handle.
+    ///
+    pub fn bind(&mut self, object_index: usize, body: RigidBodyHandle, direction: SyncDirection) {
+        self.unbind(object_index);
+        self.bindings.push((object_index, body, direction));
+    }
+
+    ///
+    /// Removes the binding of the object with the given index, for example when the object or rigid body is removed.
+    ///
+    pub fn unbind(&mut self, object_index: usize) {
+        self.bindings.retain(|(index, _, _)| *index != object_index);
+    }
+
+    ///
+    /// Returns the rigid body bound to the object with the given index.
+    ///
+    pub fn body(&self, object_index: usize) -> Option<RigidBodyHandle> {
+        self.bindings
+            .iter()
+            .find(|(index, _, _)| *index == object_index)
+            .map(|(_, body, _)| *body)
+    }
+
+    ///
+    /// Synchronizes the transformations of the bound objects and rigid bodies in the direction given when binding them.
+    /// The objects are looked up by their index in the given list of objects.
+    /// Kinematic rigid bodies are moved with [set_next_kinematic_position](rapier3d::dynamics::RigidBody::set_next_kinematic_position)
+    /// so that they push the dynamic rigid bodies correctly, other rigid bodies are teleported.
+    ///
+    pub fn sync(&self, bodies: &mut RigidBodySet, objects: &mut [&mut dyn Transformable]) {
+        for (index, handle, direction) in self.bindings.iter() {
+            if let (Some(body), Some(object)) = (bodies.get_mut(*handle), objects.get_mut(*index)) {
+                match direction {
+                    SyncDirection::BodyToObject => {
+                        let (_, _, scale) = decompose(&object.transformation());
+                        object.set_transformation(
+                            transformation_from_isometry(body.position())
+                                * Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z),
+                        );
+                    }
+                    SyncDirection::ObjectToBody => {
+                        let isometry = isometry_from_transformation(&object.transformation());
+                        if body.is_kinematic() {
+                            body.set_next_kinematic_position(isometry);
+                        } else {
+                            body.set_position(isometry, true);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+///
+/// Converts a rapier3d position to a transformation matrix.
+///
+pub fn transformation_from_isometry(isometry: &Isometry<Real>) -> Mat4 {
+    let t = isometry.translation.vector;
+    let r = isometry.rotation;
+    Mat4::from_translation(vec3(t.x, t.y, t.z))
+        * Mat4::from(cgmath::Quaternion::new(r.w, r.i, r.j, r.k))
+}
+
+///
+/// Converts a transformation matrix to a rapier3d position, ie. a translation and a rotation. Any scale is removed.
+///
+pub fn isometry_from_transformation(transformation: &Mat4) -> Isometry<Real> {
+    let (translation, rotation, _) = decompose(transformation);
+    Isometry::from_parts(
+        Translation3::new(translation.x, translation.y, translation.z),
+        UnitQuaternion::from_quaternion(Quaternion::new(
+            rotation.s,
+            rotation.v.x,
+            rotation.v.y,
+            rotation.v.z,
+        )),
+    )
+}
+
+fn decompose(transformation: &Mat4) -> (Vec3, cgmath::Quaternion<f32>, Vec3) {
+    let translation = transformation.w.truncate();
+    let x = transformation.x.truncate();
+    let y = transformation.y.truncate();
+    let z = transformation.z.truncate();
+    let scale = vec3(x.magnitude(), y.magnitude(), z.magnitude());
+    let rotation = Mat3::from_cols(
+        x / scale.x.max(std::f32::EPSILON),
+        y / scale.y.max(std::f32::EPSILON),
+        z / scale.z.max(std::f32::EPSILON),
+    );
+    (translation, rotation.into(), scale)
+}
+
+impl DebugLines {
+    ///
+    /// Adds lines visualizing the shape of the given collider at its current position.
+    /// Balls, cuboids, capsules, triangle meshes and convex polyhedrons are visualized by their shape, other shapes by their bounding box.
+    ///
+    pub fn add_collider(&mut self, collider: &Collider, color: &Vec4) {
+        let transformation = transformation_from_isometry(collider.position());
+        let shape = collider.shape();
+        let mut add_line = |a: Vec3, b: Vec3| {
+            self.add_line(
+                &(transformation * a.extend(1.0)).truncate(),
+                &(transformation * b.extend(1.0)).truncate(),
+                color,
+            )
+        };
+        if let Some(ball) = shape.as_ball() {
+            add_circles(&mut add_line, vec3(0.0, 0.0, 0.0), ball.radius);
+        } else if let Some(cuboid) = shape.as_cuboid() {
+            let e = cuboid.half_extents;
+            add_box_lines(&mut add_line, vec3(-e.x, -e.y, -e.z), vec3(e.x, e.y, e.z));
+        } else if let Some(capsule) = shape.as_capsule() {
+            let a = vec3(
+                capsule.segment.a.x,
+                capsule.segment.a.y,
+                capsule.segment.a.z,
+            );
+            let b = vec3(
+                capsule.segment.b.x,
+                capsule.segment.b.y,
+                capsule.segment.b.z,
+            );
+            add_circles(&mut add_line, a, capsule.radius);
+            add_circles(&mut add_line, b, capsule.radius);
+            for offset in [
+                vec3(capsule.radius, 0.0, 0.0),
+                vec3(-capsule.radius, 0.0, 0.0),
+                vec3(0.0, 0.0, capsule.radius),
+                vec3(0.0, 0.0, -capsule.radius),
+            ]
+            .iter()
+            {
+                add_line(a + offset, b + offset);
+            }
+        } else if let Some(trimesh) = shape.as_trimesh() {
+            let vertices = trimesh.vertices();
+            let point = |i: u32| {
+                let p = vertices[i as usize];
+                vec3(p.x, p.y, p.z)
+            };
+            for triangle in trimesh.indices().iter() {
+                add_line(point(triangle[0]), point(triangle[1]));
+                add_line(point(triangle[1]), point(triangle[2]));
+                add_line(point(triangle[2]), point(triangle[0]));
+            }
+        } else if let Some(polyhedron) = shape.as_convex_polyhedron() {
+            let points = polyhedron.points();
+            let point = |i: u32| {
+                let p = points[i as usize];
+                vec3(p.x, p.y, p.z)
+            };
+            for edge in polyhedron.edges().iter() {
+                add_line(point(edge.vertices.x), point(edge.vertices.y));
+            }
+        } else {
+            let aabb = collider.compute_aabb();
+            self.add_aabb(
+                &AxisAlignedBoundingBox::new_with_positions(&[
+                    aabb.mins.x,
+                    aabb.mins.y,
+                    aabb.mins.z,
+                    aabb.maxs.x,
+                    aabb.maxs.y,
+                    aabb.maxs.z,
+                ]),
+                color,
+            );
+        }
+    }
+
+    ///
+    /// Adds lines visualizing the shapes of all of the given colliders, see [add_collider](DebugLines::add_collider).
+    ///
+    pub fn add_colliders(&mut self, colliders: &ColliderSet, color: &Vec4) {
+        for (_, collider) in colliders.iter() {
+            self.add_collider(collider, color);
+        }
+    }
+}
+
+fn add_box_lines(add_line: &mut impl FnMut(Vec3, Vec3), min: Vec3, max: Vec3) {
+    let corner = |i: usize| {
+        vec3(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        )
+    };
+    for i in 0..8 {
+        for bit in [1, 2, 4].iter() {
+            if i & bit == 0 {
+                add_line(corner(i), corner(i | bit));
+            }
+        }
+    }
+}
+
+fn add_circles(add_line: &mut impl FnMut(Vec3, Vec3), center: Vec3, radius: f32) {
+    const SEGMENTS: usize = 24;
+    for i in 0..SEGMENTS {
+        let a0 = i as f32 * 2.0 * std::f32::consts::PI / SEGMENTS as f32;
+        let a1 = (i + 1) as f32 * 2.0 * std::f32::consts::PI / SEGMENTS as f32;
+        let (s0, c0) = (radius * a0.sin(), radius * a0.cos());
+        let (s1, c1) = (radius * a1.sin(), radius * a1.cos());
+        add_line(center + vec3(c0, s0, 0.0), center + vec3(c1, s1, 0.0));
+        add_line(center + vec3(c0, 0.0, s0), center + vec3(c1, 0.0, s1));
+        add_line(center + vec3(0.0, c0, s0), center + vec3(0.0, c1, s1));
+    }
+}
@@ -0,0 +1,16 @@
+//!
+//! Procedural generation of CPU-side data, for example [noise](crate::Noise) textures,
+//! which can be transferred to the GPU instead of loading heavy assets.
+//!
+
+mod noise;
+#[doc(inline)]
+pub use noise::*;
+
+mod normal_map;
+#[doc(inline)]
+pub use normal_map::*;
+
+mod signed_distance_field;
+#[doc(inline)]
+pub use signed_distance_field::*;
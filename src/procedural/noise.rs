@@ -0,0 +1,264 @@
+use crate::definition::*;
+
+///
+/// The type of noise generated by a [Noise] generator.
+///
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum NoiseType {
+    /// Gradient noise, smooth and blobby, for example used for terrain heights.
+    Perlin,
+    /// Gradient noise with fewer directional artifacts than Perlin noise.
+    Simplex,
+    /// Cellular noise given by the distance to the nearest of a set of random points, for example used for water caustics and stones.
+    Worley,
+}
+
+///
+/// A generator of 2D and 3D noise with a number of octaves, ie. fractal noise which is the sum of layers of noise with increasing frequency and decreasing amplitude.
+/// The noise can be sampled directly or written into a [CPUTexture], for example for terrain, clouds and water or as a detail texture.
+///
+#[derive(Clone, Debug)]
+pub struct Noise {
+    permutation: Vec<usize>,
+    /// The type of noise.
+    pub noise_type: NoiseType,
+    /// The frequency of the first octave, ie. the number of noise features per unit.
+    pub frequency: f32,
+    /// The number of layers of noise that are added together.
+    pub octaves: u32,
+    /// The factor which the amplitude is multiplied with for each octave.
+    pub persistence: f32,
+    /// The factor which the frequency is multiplied with for each octave.
+    pub lacunarity: f32,
+}
+
+impl Noise {
+    ///
+    /// Creates a new noise generator of the given type with one octave and a frequency of one.
+    /// The same seed always results in the same noise.
+    ///
+    pub fn new(noise_type: NoiseType, seed: u32) -> Self {
+        let mut permutation: Vec<usize> = (0..256).collect();
+        let mut state = seed.max(1);
+        for i in (1..256).rev() {
+            // Xorshift
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            permutation.swap(i, state as usize % (i + 1));
+        }
+        let repeated = permutation.clone();
+        permutation.extend(repeated);
+        Self {
+            permutation,
+            noise_type,
+            frequency: 1.0,
+            octaves: 1,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        }
+    }
+
+    ///
+    /// Returns the noise value in the range [0..1] at the given 2D position.
+    ///
+    pub fn sample_2d(&self, x: f32, y: f32) -> f32 {
+        self.sample_3d(x, y, 0.0)
+    }
+
+    ///
+    /// Returns the noise value in the range [0..1] at the given 3D position.
+    ///
+    pub fn sample_3d(&self, x: f32, y: f32, z: f32) -> f32 {
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut total_amplitude = 0.0;
+        let mut frequency = self.frequency;
+        for _ in 0..self.octaves.max(1) {
+            let (x, y, z) = (x * frequency, y * frequency, z * frequency);
+            let value = match self.noise_type {
+                NoiseType::Perlin => 0.5 * self.perlin(x, y, z) + 0.5,
+                NoiseType::Simplex => 0.5 * self.simplex(x, y, z) + 0.5,
+                NoiseType::Worley => self.worley(x, y, z),
+            };
+            sum += amplitude * value;
+            total_amplitude += amplitude;
+            amplitude *= self.persistence;
+            frequency *= self.lacunarity;
+        }
+        (sum / total_amplitude).max(0.0).min(1.0)
+    }
+
+    ///
+    /// Generates a single channel 2D texture with the given size containing noise values in the range [0..1].
+    /// The texture covers the area from 0 to 1 in both directions.
+    ///
+    pub fn texture_2d(&self, width: u32, height: u32) -> CPUTexture<f32> {
+        let mut data = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                data.push(self.sample_2d(x as f32 / width as f32, y as f32 / height as f32));
+            }
+        }
+        CPUTexture {
+            data,
+            width,
+            height,
+            format: Format::R,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Generates a single channel 3D texture with the given size containing noise values in the range [0..1].
+    /// The texture covers the volume from 0 to 1 in all directions.
+    ///
+    pub fn texture_3d(&self, width: u32, height: u32, depth: u32) -> CPUTexture<f32> {
+        let mut data = Vec::with_capacity(width as usize * height as usize * depth as usize);
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    data.push(self.sample_3d(
+                        x as f32 / width as f32,
+                        y as f32 / height as f32,
+                        z as f32 / depth as f32,
+                    ));
+                }
+            }
+        }
+        CPUTexture {
+            data,
+            width,
+            height,
+            depth,
+            format: Format::R,
+            ..Default::default()
+        }
+    }
+
+    fn hash(&self, x: i32, y: i32, z: i32) -> usize {
+        let p = &self.permutation;
+        p[p[p[(x & 255) as usize] + (y & 255) as usize] + (z & 255) as usize]
+    }
+
+    // Improved Perlin noise, returns a value in the range [-1..1]
+    fn perlin(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, yi, zi) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+        let (x, y, z) = (x - x.floor(), y - y.floor(), z - z.floor());
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        let g = |dx: i32, dy: i32, dz: i32| {
+            gradient(
+                self.hash(xi + dx, yi + dy, zi + dz),
+                x - dx as f32,
+                y - dy as f32,
+                z - dz as f32,
+            )
+        };
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, g(0, 0, 0), g(1, 0, 0)),
+                lerp(u, g(0, 1, 0), g(1, 1, 0)),
+            ),
+            lerp(
+                v,
+                lerp(u, g(0, 0, 1), g(1, 0, 1)),
+                lerp(u, g(0, 1, 1), g(1, 1, 1)),
+            ),
+        )
+    }
+
+    // Simplex noise, returns a value in the range [-1..1]
+    fn simplex(&self, x: f32, y: f32, z: f32) -> f32 {
+        const F3: f32 = 1.0 / 3.0;
+        const G3: f32 = 1.0 / 6.0;
+        let s = (x + y + z) * F3;
+        let (i, j, k) = (
+            (x + s).floor() as i32,
+            (y + s).floor() as i32,
+            (z + s).floor() as i32,
+        );
+        let t = (i + j + k) as f32 * G3;
+        let x0 = x - (i as f32 - t);
+        let y0 = y - (j as f32 - t);
+        let z0 = z - (k as f32 - t);
+
+        // Find the simplex that contains the point
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let corners = [
+            (0, 0, 0, 0.0),
+            (i1, j1, k1, G3),
+            (i2, j2, k2, 2.0 * G3),
+            (1, 1, 1, 3.0 * G3),
+        ];
+        let mut sum = 0.0;
+        for (di, dj, dk, offset) in corners.iter() {
+            let dx = x0 - *di as f32 + offset;
+            let dy = y0 - *dj as f32 + offset;
+            let dz = z0 - *dk as f32 + offset;
+            let t = 0.6 - dx * dx - dy * dy - dz * dz;
+            if t > 0.0 {
+                let t2 = t * t;
+                sum += t2 * t2 * gradient(self.hash(i + di, j + dj, k + dk), dx, dy, dz);
+            }
+        }
+        (32.0 * sum).max(-1.0).min(1.0)
+    }
+
+    // Worley noise, returns the distance to the nearest feature point clamped to the range [0..1]
+    fn worley(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, yi, zi) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+        let mut min_distance2 = std::f32::MAX;
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (cx, cy, cz) = (xi + dx, yi + dy, zi + dz);
+                    let h = self.hash(cx, cy, cz);
+                    let px = cx as f32 + self.permutation[h] as f32 / 256.0;
+                    let py = cy as f32 + self.permutation[h + 1] as f32 / 256.0;
+                    let pz = cz as f32 + self.permutation[h + 2] as f32 / 256.0;
+                    let distance2 = (px - x) * (px - x) + (py - y) * (py - y) + (pz - z) * (pz - z);
+                    min_distance2 = min_distance2.min(distance2);
+                }
+            }
+        }
+        min_distance2.sqrt().min(1.0)
+    }
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f32, a: f32, b: f32) -> f32 {
+    a + t * (b - a)
+}
+
+fn gradient(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
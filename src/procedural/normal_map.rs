@@ -0,0 +1,64 @@
+use crate::definition::*;
+use crate::math::*;
+
+///
+/// Converts the given height map into a tangent-space normal map, for example to add normal detail to a terrain
+/// or to a [noise](crate::Noise) texture.
+/// The height is read from the first channel of the height map and the strength scales the slopes, ie. a higher strength gives more pronounced details.
+/// The returned texture has the normals encoded in the RGB channels in the range [0..255] and is in linear color space.
+/// Pixels outside the height map are wrapped or clamped according to the wrapping modes of the height map.
+///
+pub fn normal_map_from_height_map(height_map: &CPUTexture<f32>, strength: f32) -> CPUTexture<u8> {
+    let width = height_map.width as i32;
+    let height = height_map.height as i32;
+    let channels = height_map.format.color_channel_count() as usize;
+    let lookup = |x: i32, y: i32| {
+        let x = wrap(x, width, height_map.wrap_s);
+        let y = wrap(y, height, height_map.wrap_t);
+        height_map.data[(y * width + x) as usize * channels]
+    };
+
+    let mut data = Vec::with_capacity(width as usize * height as usize * 3);
+    for y in 0..height {
+        for x in 0..width {
+            // Sobel filter
+            let top_left = lookup(x - 1, y - 1);
+            let top = lookup(x, y - 1);
+            let top_right = lookup(x + 1, y - 1);
+            let left = lookup(x - 1, y);
+            let right = lookup(x + 1, y);
+            let bottom_left = lookup(x - 1, y + 1);
+            let bottom = lookup(x, y + 1);
+            let bottom_right = lookup(x + 1, y + 1);
+            let dx =
+                (top_right + 2.0 * right + bottom_right) - (top_left + 2.0 * left + bottom_left);
+            let dy =
+                (bottom_left + 2.0 * bottom + bottom_right) - (top_left + 2.0 * top + top_right);
+            let normal = vec3(-dx * strength, -dy * strength, 1.0).normalize();
+            data.push(((0.5 * normal.x + 0.5) * 255.0).round() as u8);
+            data.push(((0.5 * normal.y + 0.5) * 255.0).round() as u8);
+            data.push(((0.5 * normal.z + 0.5) * 255.0).round() as u8);
+        }
+    }
+
+    CPUTexture {
+        data,
+        width: height_map.width,
+        height: height_map.height,
+        format: Format::RGB,
+        is_srgb: false,
+        min_filter: height_map.min_filter,
+        mag_filter: height_map.mag_filter,
+        mip_map_filter: height_map.mip_map_filter,
+        wrap_s: height_map.wrap_s,
+        wrap_t: height_map.wrap_t,
+        ..Default::default()
+    }
+}
+
+fn wrap(value: i32, size: i32, wrapping: Wrapping) -> i32 {
+    match wrapping {
+        Wrapping::Repeat => value.rem_euclid(size),
+        Wrapping::MirroredRepeat | Wrapping::ClampToEdge => value.max(0).min(size - 1),
+    }
+}
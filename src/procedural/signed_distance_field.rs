@@ -0,0 +1,117 @@
+use crate::definition::*;
+
+///
+/// Converts the given mask, where the pixels with a value of at least 128 in the first channel are inside the shape, into a signed distance field,
+/// for example for rendering crisp text and vector shapes at any scale using [Mesh::render_with_sdf](crate::Mesh::render_with_sdf).
+/// The returned single channel texture contains the distance to the edge of the shape, where 128 is on the edge, higher values are inside the shape
+/// and lower values are outside. The distance is scaled such that the values reach 0 and 255 at the given spread in pixels from the edge.
+///
+pub fn signed_distance_field(mask: &CPUTexture<u8>, spread: u32) -> CPUTexture<u8> {
+    let width = mask.width as usize;
+    let height = mask.height as usize;
+    let channels = mask.format.color_channel_count() as usize;
+    let is_inside: Vec<bool> = (0..width * height)
+        .map(|i| mask.data[i * channels] >= 128)
+        .collect();
+
+    let distance_to = |inside: bool| {
+        let mut grid: Vec<f32> = is_inside
+            .iter()
+            .map(|i| if *i == inside { 0.0 } else { std::f32::MAX })
+            .collect();
+        squared_distance_transform(&mut grid, width, height);
+        grid
+    };
+    let to_inside = distance_to(true);
+    let to_outside = distance_to(false);
+
+    let spread = spread.max(1) as f32;
+    let data = (0..width * height)
+        .map(|i| {
+            let distance = if is_inside[i] {
+                to_outside[i].sqrt() - 0.5
+            } else {
+                0.5 - to_inside[i].sqrt()
+            };
+            (128.0 + 127.0 * distance / spread)
+                .max(0.0)
+                .min(255.0)
+                .round() as u8
+        })
+        .collect();
+    CPUTexture {
+        data,
+        width: mask.width,
+        height: mask.height,
+        format: Format::R,
+        is_srgb: false,
+        mip_map_filter: None,
+        wrap_s: Wrapping::ClampToEdge,
+        wrap_t: Wrapping::ClampToEdge,
+        ..Default::default()
+    }
+}
+
+// Exact euclidean distance transform by Felzenszwalb and Huttenlocher, applied to the rows and then the columns of the grid
+fn squared_distance_transform(grid: &mut [f32], width: usize, height: usize) {
+    let mut line = vec![0.0; width.max(height)];
+    for x in 0..width {
+        for y in 0..height {
+            line[y] = grid[y * width + x];
+        }
+        let result = squared_distance_transform_1d(&line[..height]);
+        for y in 0..height {
+            grid[y * width + x] = result[y];
+        }
+    }
+    for y in 0..height {
+        let result = squared_distance_transform_1d(&grid[y * width..(y + 1) * width]);
+        grid[y * width..(y + 1) * width].copy_from_slice(&result);
+    }
+}
+
+fn squared_distance_transform_1d(f: &[f32]) -> Vec<f32> {
+    let n = f.len();
+    let mut result = vec![std::f32::MAX; n];
+    // The parabolas of the lower envelope and the boundaries between them
+    let mut v = vec![0usize; n];
+    let mut z = vec![0.0f32; n + 1];
+    let mut k = 0;
+    let mut first = None;
+    for q in 0..n {
+        if f[q] == std::f32::MAX {
+            continue;
+        }
+        if first.is_none() {
+            first = Some(q);
+            v[0] = q;
+            z[0] = std::f32::MIN;
+            z[1] = std::f32::MAX;
+            continue;
+        }
+        let intersection = |p: usize| {
+            ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32)) / (2.0 * q as f32 - 2.0 * p as f32)
+        };
+        let mut s = intersection(v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersection(v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = std::f32::MAX;
+    }
+    if first.is_none() {
+        return result;
+    }
+    k = 0;
+    for (q, r) in result.iter_mut().enumerate() {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let d = q as f32 - v[k] as f32;
+        *r = d * d + f[v[k]];
+    }
+    result
+}
@@ -0,0 +1,355 @@
+//!
+//! Support for writing regression tests of the rendered output, by rendering a scene headlessly at a fixed size
+//! and comparing the result to a golden image with a perceptual tolerance.
+//!
+//! The golden images are PNG files. If the environment variable `THREE_D_UPDATE_GOLDEN` is set,
+//! the golden images are (re)written with the rendered output instead of compared.
+//!
+//! # Feature
+//! Only available when the `test-support` feature is enabled.
+//!
+
+use crate::core::*;
+use crate::definition::*;
+use crate::io::*;
+use crate::math::*;
+use crate::window::*;
+use std::path::{Path, PathBuf};
+
+///
+/// The tolerance when comparing a rendered image to a golden image.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GoldenTolerance {
+    /// The largest perceptual difference between two pixels, in the range [0..1], for which the pixels are considered equal.
+    /// The difference is measured in the YIQ color space, which approximates the perceived difference between colors.
+    pub color_threshold: f32,
+    /// The largest fraction of the pixels, in the range [0..1], which are allowed to differ.
+    pub max_mismatched_fraction: f32,
+}
+
+impl Default for GoldenTolerance {
+    fn default() -> Self {
+        Self {
+            color_threshold: 0.1,
+            max_mismatched_fraction: 0.001,
+        }
+    }
+}
+
+///
+/// The result of [comparing](compare_images) two images.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ImageComparison {
+    /// The number of pixels which differ more than the color threshold.
+    pub mismatched_pixels: usize,
+    /// The fraction of the pixels which differ more than the color threshold.
+    pub mismatched_fraction: f32,
+    /// The largest perceptual difference between two pixels in the range [0..1].
+    pub max_difference: f32,
+}
+
+impl ImageComparison {
+    ///
+    /// Returns whether the images are equal within the given tolerance.
+    ///
+    pub fn is_within(&self, tolerance: &GoldenTolerance) -> bool {
+        self.mismatched_fraction <= tolerance.max_mismatched_fraction
+    }
+}
+
+///
+/// Error from a [GoldenImageTest].
+///
+#[derive(Debug)]
+pub enum GoldenImageError {
+    /// The headless context could not be created.
+    Window(WindowError),
+    /// The scene could not be rendered.
+    Render(Error),
+    /// The golden image could not be loaded or the rendered image could not be saved.
+    IO(IOError),
+    /// There is no golden image at the given path. The rendered image is saved next to it with the extension `.actual.png`.
+    MissingGolden {
+        /// The path to the missing golden image.
+        path: PathBuf,
+    },
+    /// The rendered image differs from the golden image. The rendered image is saved next to the golden image with the extension `.actual.png`.
+    Mismatch {
+        /// The path to the golden image.
+        path: PathBuf,
+        /// The comparison of the images.
+        comparison: ImageComparison,
+    },
+}
+
+impl From<WindowError> for GoldenImageError {
+    fn from(other: WindowError) -> Self {
+        GoldenImageError::Window(other)
+    }
+}
+
+impl From<Error> for GoldenImageError {
+    fn from(other: Error) -> Self {
+        GoldenImageError::Render(other)
+    }
+}
+
+impl From<IOError> for GoldenImageError {
+    fn from(other: IOError) -> Self {
+        GoldenImageError::IO(other)
+    }
+}
+
+impl From<image::ImageError> for GoldenImageError {
+    fn from(other: image::ImageError) -> Self {
+        GoldenImageError::IO(IOError::Image(other))
+    }
+}
+
+///
+/// Renders scenes headlessly at a fixed size and compares the results to golden images in a directory.
+///
+/// ```no_run
+/// # use three_d::*;
+/// let test = GoldenImageTest::new("tests/golden", 256, 256).unwrap();
+/// let context = test.context();
+/// let mesh = Mesh::new(&context, &CPUMesh::sphere(1.0)).unwrap();
+/// let camera = Camera::new_perspective(&context, vec3(0.0, 0.0, 4.0), vec3(0.0, 0.0, 0.0),
+///     vec3(0.0, 1.0, 0.0), degrees(45.0), 1.0, 0.1, 10.0).unwrap();
+/// test.assert_golden("sphere", ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0), |viewport| {
+///     mesh.render_color(RenderStates::new(viewport), &camera)
+/// });
+/// ```
+///
+pub struct GoldenImageTest {
+    context: HeadlessContext,
+    directory: PathBuf,
+    /// The width of the rendered images.
+    pub width: u32,
+    /// The height of the rendered images.
+    pub height: u32,
+    /// The tolerance when comparing the rendered images to the golden images.
+    pub tolerance: GoldenTolerance,
+}
+
+impl GoldenImageTest {
+    ///
+    /// Creates a new headless context for rendering images with the given width and height,
+    /// which are compared to the golden images in the given directory.
+    ///
+    pub fn new<P: AsRef<Path>>(
+        directory: P,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, GoldenImageError> {
+        Ok(Self {
+            context: HeadlessContext::new()?,
+            directory: directory.as_ref().to_path_buf(),
+            width,
+            height,
+            tolerance: GoldenTolerance::default(),
+        })
+    }
+
+    ///
+    /// Returns the graphics context which must be used for constructing the objects in the scenes.
+    ///
+    pub fn context(&self) -> Context {
+        self.context.gl().unwrap()
+    }
+
+    ///
+    /// Renders the scene using the `render` closure, which is called with the viewport to render into,
+    /// and compares the result to the golden image `name.png` in the directory.
+    ///
+    /// # Errors
+    /// Will return an error if the rendering fails, the golden image is missing or the images differ more than the [tolerance](Self::tolerance).
+    ///
+    pub fn check<F: FnOnce(Viewport) -> Result<(), Error>>(
+        &self,
+        name: &str,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<ImageComparison, GoldenImageError> {
+        let actual = render_offscreen(
+            &self.context(),
+            self.width,
+            self.height,
+            clear_state,
+            render,
+        )?;
+        let path = self.directory.join(format!("{}.png", name));
+        let actual_path = self.directory.join(format!("{}.actual.png", name));
+        if std::env::var_os("THREE_D_UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(&self.directory).map_err(IOError::from)?;
+            Saver::save_pixels(&path, &actual, self.width, self.height)?;
+            return Ok(ImageComparison {
+                mismatched_pixels: 0,
+                mismatched_fraction: 0.0,
+                max_difference: 0.0,
+            });
+        }
+        if !path.exists() {
+            std::fs::create_dir_all(&self.directory).map_err(IOError::from)?;
+            Saver::save_pixels(&actual_path, &actual, self.width, self.height)?;
+            return Err(GoldenImageError::MissingGolden { path });
+        }
+
+        let golden = image::open(&path)?.to_rgba8();
+        let comparison = if golden.width() != self.width || golden.height() != self.height {
+            ImageComparison {
+                mismatched_pixels: self.width as usize * self.height as usize,
+                mismatched_fraction: 1.0,
+                max_difference: 1.0,
+            }
+        } else {
+            // The golden image is stored with the top row first while the rendered pixels have the bottom row first
+            let row_length = 4 * self.width as usize;
+            let expected: Vec<u8> = golden
+                .into_raw()
+                .chunks_exact(row_length)
+                .rev()
+                .flat_map(|row| row.iter().cloned())
+                .collect();
+            compare_images(
+                &expected,
+                &actual,
+                self.width,
+                self.height,
+                self.tolerance.color_threshold,
+            )
+        };
+        if comparison.is_within(&self.tolerance) {
+            if actual_path.exists() {
+                std::fs::remove_file(&actual_path).map_err(IOError::from)?;
+            }
+            Ok(comparison)
+        } else {
+            Saver::save_pixels(&actual_path, &actual, self.width, self.height)?;
+            Err(GoldenImageError::Mismatch { path, comparison })
+        }
+    }
+
+    ///
+    /// Same as [check](Self::check), but panics with a descriptive message if the check fails, for use in tests.
+    ///
+    pub fn assert_golden<F: FnOnce(Viewport) -> Result<(), Error>>(
+        &self,
+        name: &str,
+        clear_state: ClearState,
+        render: F,
+    ) {
+        match self.check(name, clear_state, render) {
+            Ok(_) => {}
+            Err(GoldenImageError::MissingGolden { path }) => panic!(
+                "The golden image {:?} is missing, set THREE_D_UPDATE_GOLDEN to create it",
+                path
+            ),
+            Err(GoldenImageError::Mismatch { path, comparison }) => panic!(
+                "The rendered image differs from the golden image {:?} in {} pixels ({:.3}%) with a maximum difference of {:.3}",
+                path,
+                comparison.mismatched_pixels,
+                100.0 * comparison.mismatched_fraction,
+                comparison.max_difference
+            ),
+            Err(e) => panic!("The golden image test {} failed: {:?}", name, e),
+        }
+    }
+}
+
+///
+/// Renders whatever is rendered in the `render` closure into a texture with the given width and height, which is cleared with the given clear state,
+/// and returns the RGBA color values with the bottom row first, in the same way as [Screen::read_color](crate::Screen::read_color).
+/// This works with any context, also a [HeadlessContext] which has no screen.
+///
+pub fn render_offscreen<F: FnOnce(Viewport) -> Result<(), Error>>(
+    context: &Context,
+    width: u32,
+    height: u32,
+    clear_state: ClearState,
+    render: F,
+) -> Result<Vec<u8>, Error> {
+    let texture = ColorTargetTexture2D::<u8>::new(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )?;
+    let depth_texture = DepthTargetTexture2D::new(
+        context,
+        width,
+        height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )?;
+    let viewport = Viewport::new_at_origo(width, height);
+    RenderTarget::new(context, &texture, &depth_texture)?
+        .write(clear_state, || render(viewport))?;
+    texture.read(viewport)
+}
+
+///
+/// Compares two RGBA images with the given width and height using a perceptual color difference in the YIQ color space,
+/// where the pixels with a difference larger than the given threshold, in the range [0..1], are counted as mismatched.
+/// Transparent pixels are blended with white before comparing.
+///
+pub fn compare_images(
+    expected: &[u8],
+    actual: &[u8],
+    width: u32,
+    height: u32,
+    threshold: f32,
+) -> ImageComparison {
+    // The largest possible squared YIQ difference between two colors
+    const MAX_DELTA: f32 = 35215.0;
+    let pixel_count = width as usize * height as usize;
+    let mut mismatched_pixels = 0;
+    let mut max_delta = 0.0f32;
+    for (a, b) in expected
+        .chunks_exact(4)
+        .zip(actual.chunks_exact(4))
+        .take(pixel_count)
+    {
+        let delta = color_delta(a, b);
+        max_delta = max_delta.max(delta);
+        if delta > MAX_DELTA * threshold * threshold {
+            mismatched_pixels += 1;
+        }
+    }
+    // Missing pixels are mismatched
+    mismatched_pixels += pixel_count - (expected.len() / 4).min(actual.len() / 4).min(pixel_count);
+    ImageComparison {
+        mismatched_pixels,
+        mismatched_fraction: if pixel_count > 0 {
+            mismatched_pixels as f32 / pixel_count as f32
+        } else {
+            0.0
+        },
+        max_difference: (max_delta / MAX_DELTA).sqrt(),
+    }
+}
+
+fn color_delta(a: &[u8], b: &[u8]) -> f32 {
+    let blend = |c: &[u8]| {
+        let alpha = c[3] as f32 / 255.0;
+        let channel = |v: u8| 255.0 + (v as f32 - 255.0) * alpha;
+        (channel(c[0]), channel(c[1]), channel(c[2]))
+    };
+    let (r1, g1, b1) = blend(a);
+    let (r2, g2, b2) = blend(b);
+    let y = |r: f32, g: f32, b: f32| r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_2;
+    let i = |r: f32, g: f32, b: f32| r * 0.595_978 - g * 0.274_176_1 - b * 0.321_801_9;
+    let q = |r: f32, g: f32, b: f32| r * 0.211_470_2 - g * 0.522_617_1 + b * 0.311_146_9;
+    let dy = y(r1, g1, b1) - y(r2, g2, b2);
+    let di = i(r1, g1, b1) - i(r2, g2, b2);
+    let dq = q(r1, g1, b1) - q(r2, g2, b2);
+    0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq
+}
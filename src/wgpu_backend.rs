@@ -0,0 +1,34 @@
+//!
+//! A graphics backend on [wgpu](https://wgpu.rs) (only available when the `wgpu-backend` feature is enabled),
+//! which renders using Vulkan, Metal, DX12 or WebGPU instead of OpenGL/WebGL.
+//! It provides buffers, textures, shader programs and a forward pipeline with the same functionality as their OpenGL counterparts,
+//! see the [GraphicsBackend](crate::GraphicsBackend) trait which is implemented by both [Context](crate::Context) and [WgpuContext].
+//! The shader programs are written in the same GLSL as for [Program](crate::Program) and translated to WGSL,
+//! see [WgpuProgram::from_source] for the restrictions.
+//!
+//! The rest of the crate, for example the objects, lights and effects, still renders using OpenGL/WebGL.
+//!
+
+pub use wgpu;
+
+mod glsl_translation;
+
+mod context;
+#[doc(inline)]
+pub use context::*;
+
+mod buffer;
+#[doc(inline)]
+pub use buffer::*;
+
+mod texture;
+#[doc(inline)]
+pub use texture::*;
+
+mod program;
+#[doc(inline)]
+pub use program::*;
+
+mod forward_pipeline;
+#[doc(inline)]
+pub use forward_pipeline::*;
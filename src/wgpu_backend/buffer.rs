@@ -0,0 +1,200 @@
+use super::*;
+use crate::core::*;
+use wgpu::util::DeviceExt;
+
+///
+/// A buffer containing per vertex data, for example positions, normals, uv coordinates or colors,
+/// the equivalent of the OpenGL [VertexBuffer](crate::VertexBuffer)
+/// (see also [use_attribute](WgpuProgram::use_attribute), [use_attribute_vec2](WgpuProgram::use_attribute_vec2), etc.).
+///
+pub struct WgpuVertexBuffer {
+    context: WgpuContext,
+    pub(super) buffer: wgpu::Buffer,
+    count: usize,
+}
+
+impl WgpuVertexBuffer {
+    ///
+    /// Creates a new vertex buffer and fills it with the given data.
+    ///
+    pub fn new_with_static(context: &WgpuContext, data: &[f32]) -> Result<Self, Error> {
+        Ok(Self {
+            context: context.clone(),
+            buffer: create_buffer(context, &f32_bytes(data), wgpu::BufferUsages::VERTEX),
+            count: data.len(),
+        })
+    }
+
+    ///
+    /// Fills the vertex buffer with the given data. A new buffer is allocated if the size changes.
+    ///
+    pub fn fill_with_static(&mut self, data: &[f32]) {
+        if data.len() == self.count && !data.is_empty() {
+            self.context
+                .queue()
+                .write_buffer(&self.buffer, 0, &f32_bytes(data));
+        } else {
+            self.buffer =
+                create_buffer(&self.context, &f32_bytes(data), wgpu::BufferUsages::VERTEX);
+        }
+        self.count = data.len();
+    }
+
+    ///
+    /// The number of values in the buffer.
+    ///
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+///
+/// A buffer containing indices for rendering, the equivalent of the OpenGL [ElementBuffer](crate::ElementBuffer),
+/// see [draw_elements](WgpuProgram::draw_elements). The indices are always stored as 32-bit integers.
+///
+pub struct WgpuElementBuffer {
+    pub(super) buffer: wgpu::Buffer,
+    count: usize,
+}
+
+impl WgpuElementBuffer {
+    ///
+    /// Creates a new element buffer and fills it with the given indices.
+    ///
+    pub fn new<T: ElementBufferDataType>(context: &WgpuContext, data: &[T]) -> Result<Self, Error> {
+        let bytes: Vec<u8> = data
+            .iter()
+            .flat_map(|index| index.into_u32().to_le_bytes())
+            .collect();
+        Ok(Self {
+            buffer: create_buffer(context, &bytes, wgpu::BufferUsages::INDEX),
+            count: data.len(),
+        })
+    }
+
+    ///
+    /// The number of elements in the buffer.
+    ///
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+///
+/// A buffer for transferring a set of uniform variables to the shader program, the equivalent of the OpenGL [UniformBuffer](crate::UniformBuffer)
+/// (see also [use_uniform_block](WgpuProgram::use_uniform_block)).
+/// The data is written to the queue when it is updated, so all draw calls submitted afterwards use the latest data.
+///
+pub struct WgpuUniformBuffer {
+    context: WgpuContext,
+    pub(super) buffer: wgpu::Buffer,
+    offsets: Vec<usize>,
+    data: Vec<f32>,
+}
+
+impl WgpuUniformBuffer {
+    pub fn new(context: &WgpuContext, sizes: &[u32]) -> Result<Self, Error> {
+        let mut offsets = Vec::new();
+        let mut length = 0;
+        for size in sizes {
+            offsets.push(length);
+            length += *size as usize;
+        }
+        let max_size = context.device().limits().max_uniform_buffer_binding_size as usize;
+        if length * 4 > max_size {
+            Err(Error::BufferError {
+                message: format!(
+                    "The uniform buffer has a size of {} bytes which exceeds the maximum uniform block size of {} bytes supported by this device",
+                    length * 4,
+                    max_size
+                ),
+            })?;
+        }
+        let data = vec![0.0; length];
+        Ok(Self {
+            context: context.clone(),
+            buffer: create_buffer(
+                context,
+                &f32_bytes(&data),
+                wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            ),
+            offsets,
+            data,
+        })
+    }
+
+    pub fn update(&mut self, index: u32, data: &[f32]) -> Result<(), Error> {
+        let (offset, length) = self.offset_length(index as usize)?;
+        if data.len() != length {
+            return Err(Error::BufferError {
+                message: format!(
+                    "The uniform buffer data for index {} has length {} but it must be {}.",
+                    index,
+                    data.len(),
+                    length
+                ),
+            });
+        }
+        self.data[offset..offset + length].copy_from_slice(data);
+        self.context.queue().write_buffer(
+            &self.buffer,
+            (offset * std::mem::size_of::<f32>()) as u64,
+            &f32_bytes(data),
+        );
+        Ok(())
+    }
+
+    ///
+    /// Returns the size of the buffer in bytes.
+    ///
+    pub fn size_in_bytes(&self) -> usize {
+        self.data.len() * std::mem::size_of::<f32>()
+    }
+
+    pub fn get(&self, index: u32) -> Result<&[f32], Error> {
+        let (offset, length) = self.offset_length(index as usize)?;
+        Ok(&self.data[offset..offset + length])
+    }
+
+    fn offset_length(&self, index: usize) -> Result<(usize, usize), Error> {
+        if index >= self.offsets.len() {
+            return Err(Error::BufferError {
+                message: format!(
+                    "The uniform buffer index {} is outside the range 0-{}",
+                    index,
+                    self.offsets.len() as i64 - 1
+                ),
+            });
+        }
+        let offset = self.offsets[index];
+        let length = if index + 1 == self.offsets.len() {
+            self.data.len()
+        } else {
+            self.offsets[index + 1]
+        } - offset;
+        Ok((offset, length))
+    }
+}
+
+pub(super) fn f32_bytes(data: &[f32]) -> Vec<u8> {
+    data.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+// Creates a buffer with the given contents, wgpu does not allow empty buffers to be bound so at least four bytes are allocated
+pub(super) fn create_buffer(
+    context: &WgpuContext,
+    contents: &[u8],
+    usage: wgpu::BufferUsages,
+) -> wgpu::Buffer {
+    context
+        .device()
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("three-d buffer"),
+            contents: if contents.is_empty() {
+                &[0; 4]
+            } else {
+                contents
+            },
+            usage,
+        })
+}
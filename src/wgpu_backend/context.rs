@@ -0,0 +1,342 @@
+use super::*;
+use crate::context::Backend;
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// The wgpu device and queue which all of the wgpu resources are created on, the equivalent of the OpenGL [Context](crate::Context).
+/// It is cheap to clone, all clones refer to the same device.
+///
+#[derive(Clone, Debug)]
+pub struct WgpuContext {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    backend: wgpu::Backend,
+}
+
+impl WgpuContext {
+    ///
+    /// Creates a context from a device and queue created by the application, for example when rendering to a window surface.
+    /// The backend is the graphics API of the adapter which the device was requested from, see [wgpu::AdapterInfo::backend].
+    ///
+    pub fn from_device(device: wgpu::Device, queue: wgpu::Queue, backend: wgpu::Backend) -> Self {
+        Self {
+            device,
+            queue,
+            backend,
+        }
+    }
+
+    ///
+    /// Creates a context on the default adapter without a window, which can be used for rendering to [textures](WgpuTexture2D).
+    ///
+    /// # Errors
+    /// Will return an error if there is no adapter or if the device could not be created.
+    ///
+    pub async fn new_headless() -> Result<Self, Error> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await
+            .map_err(|e| Error::WgpuError {
+                message: format!("No graphics adapter is available: {}", e),
+            })?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("three-d"),
+                required_limits: adapter.limits(),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| Error::WgpuError {
+                message: format!("Could not create a device: {}", e),
+            })?;
+        Ok(Self::from_device(device, queue, adapter.get_info().backend))
+    }
+
+    ///
+    /// Returns the wgpu device, for example for creating resources which are not provided by this backend.
+    ///
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    ///
+    /// Returns the wgpu queue.
+    ///
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    ///
+    /// Returns the graphics API which this context maps to.
+    ///
+    pub fn backend(&self) -> Backend {
+        Backend::Wgpu(self.backend)
+    }
+}
+
+impl GraphicsBackend for WgpuContext {
+    type VertexBuffer = WgpuVertexBuffer;
+    type ElementBuffer = WgpuElementBuffer;
+    type UniformBuffer = WgpuUniformBuffer;
+    type Texture2D = WgpuTexture2D;
+    type Program = WgpuProgram;
+
+    fn backend(&self) -> Backend {
+        self.backend()
+    }
+
+    fn new_vertex_buffer(&self, data: &[f32]) -> Result<WgpuVertexBuffer, Error> {
+        WgpuVertexBuffer::new_with_static(self, data)
+    }
+
+    fn new_element_buffer(&self, data: &[u32]) -> Result<WgpuElementBuffer, Error> {
+        WgpuElementBuffer::new(self, data)
+    }
+
+    fn new_uniform_buffer(&self, sizes: &[u32]) -> Result<WgpuUniformBuffer, Error> {
+        WgpuUniformBuffer::new(self, sizes)
+    }
+
+    fn new_texture(&self, cpu_texture: &CPUTexture<u8>) -> Result<WgpuTexture2D, Error> {
+        WgpuTexture2D::new(self, cpu_texture)
+    }
+
+    fn new_program(
+        &self,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> Result<WgpuProgram, Error> {
+        WgpuProgram::from_source(self, vertex_shader_source, fragment_shader_source)
+    }
+}
+
+///
+/// A color and/or depth texture which can be rendered into, the equivalent of the OpenGL [RenderTarget](crate::RenderTarget).
+/// Use [from_texture_view](Self::from_texture_view) to render into the texture of a window surface.
+///
+pub struct WgpuRenderTarget<'a> {
+    context: WgpuContext,
+    color: Option<(&'a wgpu::TextureView, wgpu::TextureFormat)>,
+    depth: Option<&'a WgpuDepthTexture2D>,
+    width: u32,
+    height: u32,
+}
+
+impl<'a> WgpuRenderTarget<'a> {
+    ///
+    /// Constructs a render target which writes to the given color and depth texture.
+    ///
+    /// # Errors
+    /// Will return an error if the textures do not have the same size.
+    ///
+    pub fn new(
+        context: &WgpuContext,
+        color_texture: &'a WgpuTexture2D,
+        depth_texture: &'a WgpuDepthTexture2D,
+    ) -> Result<Self, Error> {
+        Self::from_texture_view(
+            context,
+            color_texture.view(),
+            color_texture.gpu_format(),
+            color_texture.width(),
+            color_texture.height(),
+            Some(depth_texture),
+        )
+    }
+
+    ///
+    /// Constructs a render target which only writes to the given color texture.
+    ///
+    pub fn new_color(context: &WgpuContext, color_texture: &'a WgpuTexture2D) -> Self {
+        Self {
+            context: context.clone(),
+            color: Some((color_texture.view(), color_texture.gpu_format())),
+            depth: None,
+            width: color_texture.width(),
+            height: color_texture.height(),
+        }
+    }
+
+    ///
+    /// Constructs a render target which only writes to the given depth texture.
+    ///
+    pub fn new_depth(context: &WgpuContext, depth_texture: &'a WgpuDepthTexture2D) -> Self {
+        Self {
+            context: context.clone(),
+            color: None,
+            depth: Some(depth_texture),
+            width: depth_texture.width(),
+            height: depth_texture.height(),
+        }
+    }
+
+    ///
+    /// Constructs a render target which writes to the given texture view with the given format and size, for example the current texture of a window surface,
+    /// and optionally to the given depth texture.
+    ///
+    /// # Errors
+    /// Will return an error if the depth texture does not have the given size.
+    ///
+    pub fn from_texture_view(
+        context: &WgpuContext,
+        view: &'a wgpu::TextureView,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        depth_texture: Option<&'a WgpuDepthTexture2D>,
+    ) -> Result<Self, Error> {
+        if let Some(depth_texture) = depth_texture {
+            if depth_texture.width() != width || depth_texture.height() != height {
+                Err(Error::RenderTargetError {
+                    message: format!(
+                        "The depth texture has size {}x{} but the color texture has size {}x{}",
+                        depth_texture.width(),
+                        depth_texture.height(),
+                        width,
+                        height
+                    ),
+                })?;
+            }
+        }
+        Ok(Self {
+            context: context.clone(),
+            color: Some((view, format)),
+            depth: depth_texture,
+            width,
+            height,
+        })
+    }
+
+    ///
+    /// Renders whatever rendered in the `render` closure into this render target after clearing it as defined by the clear state.
+    /// The draw calls are recorded in the given [WgpuRenderPass] and submitted to the queue when the closure returns.
+    ///
+    /// # Errors
+    /// Will return an error if only some of the color channels are cleared, since a wgpu render pass clears all channels or none,
+    /// or if the `render` closure returns an error.
+    ///
+    pub fn write<F: FnOnce(&mut WgpuRenderPass) -> Result<(), Error>>(
+        &self,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        let color_load = match (
+            clear_state.red,
+            clear_state.green,
+            clear_state.blue,
+            clear_state.alpha,
+        ) {
+            (Some(r), Some(g), Some(b), Some(a)) => wgpu::LoadOp::Clear(wgpu::Color {
+                r: r as f64,
+                g: g as f64,
+                b: b as f64,
+                a: a as f64,
+            }),
+            (None, None, None, None) => wgpu::LoadOp::Load,
+            _ => Err(Error::RenderTargetError {
+                message:
+                    "The wgpu backend can only clear all of the color channels or none of them"
+                        .to_string(),
+            })?,
+        };
+        let depth_load = match clear_state.depth {
+            Some(depth) => wgpu::LoadOp::Clear(depth),
+            None => wgpu::LoadOp::Load,
+        };
+
+        let mut encoder =
+            self.context
+                .device()
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("three-d render target"),
+                });
+        let result = {
+            let pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("three-d render pass"),
+                color_attachments: &[self.color.map(|(view, _)| wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: color_load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: self.depth.map(|depth| {
+                    wgpu::RenderPassDepthStencilAttachment {
+                        view: depth.view(),
+                        depth_ops: Some(wgpu::Operations {
+                            load: depth_load,
+                            store: wgpu::StoreOp::Store,
+                        }),
+                        stencil_ops: None,
+                    }
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            let mut pass = WgpuRenderPass {
+                pass,
+                color_format: self.color.map(|(_, format)| format),
+                depth_format: self.depth.map(|_| WgpuDepthTexture2D::FORMAT),
+                width: self.width,
+                height: self.height,
+                reverse_depth: clear_state.reverse_depth,
+            };
+            render(&mut pass)
+        };
+        // The recorded commands are submitted even if rendering failed, like the draw calls that were already issued with OpenGL
+        self.context.queue().submit(Some(encoder.finish()));
+        result
+    }
+
+    ///
+    /// The width of this render target.
+    ///
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    ///
+    /// The height of this render target.
+    ///
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+///
+/// A render pass on a [WgpuRenderTarget] which the draw calls of a [WgpuProgram] are recorded in.
+///
+pub struct WgpuRenderPass<'p> {
+    pass: wgpu::RenderPass<'p>,
+    pub(super) color_format: Option<wgpu::TextureFormat>,
+    pub(super) depth_format: Option<wgpu::TextureFormat>,
+    pub(super) width: u32,
+    pub(super) height: u32,
+    pub(super) reverse_depth: bool,
+}
+
+impl<'p> WgpuRenderPass<'p> {
+    ///
+    /// Returns the underlying wgpu render pass, for example for recording draw calls which are not provided by this backend.
+    ///
+    pub fn inner(&mut self) -> &mut wgpu::RenderPass<'p> {
+        &mut self.pass
+    }
+
+    ///
+    /// The width of the render target of this pass.
+    ///
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    ///
+    /// The height of the render target of this pass.
+    ///
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
@@ -0,0 +1,258 @@
+use super::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+
+// The maximum number of directional lights in a render call, since the lights are given in fixed size uniform arrays
+const MAX_DIRECTIONAL_LIGHTS: usize = 4;
+
+///
+/// A triangle mesh rendered by the [WgpuForwardPipeline] with a single color shaded using the Phong reflection model.
+///
+pub struct WgpuMesh {
+    /// The transformation from the local space of the mesh to world space.
+    pub transformation: Mat4,
+    /// The color of the surface in linear color space, the alpha value is only used when the render states enable blending.
+    pub color: Vec4,
+    pub diffuse_intensity: f32,
+    pub specular_intensity: f32,
+    pub specular_power: f32,
+    /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped when rendering this mesh.
+    /// Overrides the cull setting of the render states if set.
+    pub cull: Option<CullType>,
+    positions: WgpuVertexBuffer,
+    normals: WgpuVertexBuffer,
+    indices: Option<WgpuElementBuffer>,
+}
+
+impl WgpuMesh {
+    ///
+    /// Creates a new mesh from the given [CPUMesh], the normals are computed if the mesh does not have any.
+    /// The uv coordinates and colors of the mesh are not used.
+    ///
+    pub fn new(context: &WgpuContext, cpu_mesh: &CPUMesh) -> Result<Self, Error> {
+        let positions = WgpuVertexBuffer::new_with_static(context, &cpu_mesh.positions)?;
+        let normals = match cpu_mesh.normals {
+            Some(ref normals) => WgpuVertexBuffer::new_with_static(context, normals)?,
+            None => {
+                let mut mesh = CPUMesh {
+                    positions: cpu_mesh.positions.clone(),
+                    indices: cpu_mesh
+                        .indices
+                        .as_ref()
+                        .map(|indices| Indices::U32(indices.into_u32())),
+                    ..Default::default()
+                };
+                mesh.compute_normals();
+                WgpuVertexBuffer::new_with_static(context, mesh.normals.as_ref().unwrap())?
+            }
+        };
+        let indices = match cpu_mesh.indices {
+            Some(ref indices) => Some(WgpuElementBuffer::new(context, &indices.into_u32())?),
+            None => None,
+        };
+        Ok(Self {
+            transformation: Mat4::identity(),
+            color: vec4(1.0, 1.0, 1.0, 1.0),
+            diffuse_intensity: 0.5,
+            specular_intensity: 0.2,
+            specular_power: 6.0,
+            cull: None,
+            positions,
+            normals,
+            indices,
+        })
+    }
+}
+
+///
+/// The camera which the [WgpuForwardPipeline] renders from, given by its view-projection matrix and its position in world space.
+/// Use the [view](crate::Camera::view) and [projection](crate::Camera::projection) of a [Camera](crate::Camera) or create the matrices directly.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct WgpuCamera {
+    pub view_projection: Mat4,
+    pub position: Vec3,
+}
+
+///
+/// A light which shines equally on all parts of any surface rendered by the [WgpuForwardPipeline].
+///
+#[derive(Debug, Copy, Clone)]
+pub struct WgpuAmbientLight {
+    pub color: Vec3,
+    pub intensity: f32,
+}
+
+impl Default for WgpuAmbientLight {
+    fn default() -> Self {
+        Self {
+            color: vec3(1.0, 1.0, 1.0),
+            intensity: 1.0,
+        }
+    }
+}
+
+///
+/// A light which shines in the given direction on all surfaces rendered by the [WgpuForwardPipeline], for example the sun.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct WgpuDirectionalLight {
+    pub color: Vec3,
+    pub intensity: f32,
+    pub direction: Vec3,
+}
+
+///
+/// Forward pipeline on the wgpu backend based on the Phong reflection model, which shades each [WgpuMesh] with an ambient light
+/// and up to four directional lights when it is rendered. It is the wgpu counterpart of the [PhongForwardPipeline](crate::PhongForwardPipeline)
+/// without shadows, textures and point and spot lights.
+///
+pub struct WgpuForwardPipeline {
+    context: WgpuContext,
+    program: WgpuProgram,
+    depth_texture: Option<WgpuDepthTexture2D>,
+}
+
+impl WgpuForwardPipeline {
+    pub fn new(context: &WgpuContext) -> Result<Self, Error> {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.define("MAX_DIRECTIONAL_LIGHTS", MAX_DIRECTIONAL_LIGHTS);
+        Ok(Self {
+            context: context.clone(),
+            program: WgpuProgram::from_source_with_preprocessor(
+                context,
+                include_str!("shaders/forward.vert"),
+                include_str!("shaders/forward.frag"),
+                &preprocessor,
+            )?,
+            depth_texture: None,
+        })
+    }
+
+    ///
+    /// Renders the given meshes into the given render pass with the given render states as seen from the given camera.
+    ///
+    /// # Errors
+    /// Will return an error if more than four directional lights are given.
+    ///
+    pub fn render(
+        &self,
+        pass: &mut WgpuRenderPass,
+        render_states: RenderStates,
+        camera: &WgpuCamera,
+        ambient_light: Option<&WgpuAmbientLight>,
+        directional_lights: &[&WgpuDirectionalLight],
+        meshes: &[&WgpuMesh],
+    ) -> Result<(), Error> {
+        if directional_lights.len() > MAX_DIRECTIONAL_LIGHTS {
+            Err(Error::WgpuError {
+                message: format!(
+                    "The forward pipeline supports at most {} directional lights, but {} were given",
+                    MAX_DIRECTIONAL_LIGHTS,
+                    directional_lights.len()
+                ),
+            })?;
+        }
+        let program = &self.program;
+        program.use_uniform_mat4("viewProjection", &camera.view_projection)?;
+        program.use_uniform_vec3("eyePosition", &camera.position)?;
+        program.use_uniform_vec3(
+            "ambientColor",
+            &ambient_light
+                .map(|light| light.color * light.intensity)
+                .unwrap_or(vec3(0.0, 0.0, 0.0)),
+        )?;
+        program.use_uniform_int("directionalLightCount", &(directional_lights.len() as i32))?;
+        for (i, light) in directional_lights.iter().enumerate() {
+            program.use_uniform_vec4(
+                &format!("directionalLightColors[{}]", i),
+                &(light.color * light.intensity).extend(0.0),
+            )?;
+            program.use_uniform_vec4(
+                &format!("directionalLightDirections[{}]", i),
+                &light.direction.extend(0.0),
+            )?;
+        }
+
+        for mesh in meshes {
+            program.use_uniform_mat4("modelMatrix", &mesh.transformation)?;
+            program.use_uniform_mat4(
+                "normalMatrix",
+                &mesh
+                    .transformation
+                    .invert()
+                    .unwrap_or(Mat4::identity())
+                    .transpose(),
+            )?;
+            program.use_uniform_vec4("surfaceColor", &mesh.color)?;
+            program.use_uniform_float("diffuse_intensity", &mesh.diffuse_intensity)?;
+            program.use_uniform_float("specular_intensity", &mesh.specular_intensity)?;
+            program.use_uniform_float("specular_power", &mesh.specular_power)?;
+            program.use_attribute_vec3(&mesh.positions, "position")?;
+            program.use_attribute_vec3(&mesh.normals, "normal")?;
+            let render_states = match mesh.cull {
+                Some(cull) => RenderStates {
+                    cull,
+                    ..render_states
+                },
+                None => render_states,
+            };
+            match mesh.indices {
+                Some(ref indices) => program.draw_elements(pass, render_states, indices)?,
+                None => {
+                    program.draw_arrays(pass, render_states, mesh.positions.count() as u32 / 3)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Renders the given meshes into the given color texture like [render](Self::render) after clearing it to the given color,
+    /// using a depth texture of the same size which is owned by this pipeline.
+    ///
+    pub fn render_to_texture(
+        &mut self,
+        color_texture: &WgpuTexture2D,
+        clear_color: &Vec4,
+        camera: &WgpuCamera,
+        ambient_light: Option<&WgpuAmbientLight>,
+        directional_lights: &[&WgpuDirectionalLight],
+        meshes: &[&WgpuMesh],
+    ) -> Result<(), Error> {
+        let (width, height) = (color_texture.width(), color_texture.height());
+        if self
+            .depth_texture
+            .as_ref()
+            .map(|depth| depth.width() != width || depth.height() != height)
+            .unwrap_or(true)
+        {
+            self.depth_texture = Some(WgpuDepthTexture2D::new(&self.context, width, height)?);
+        }
+        let render_target = WgpuRenderTarget::new(
+            &self.context,
+            color_texture,
+            self.depth_texture.as_ref().unwrap(),
+        )?;
+        render_target.write(
+            ClearState::color_and_depth(
+                clear_color.x,
+                clear_color.y,
+                clear_color.z,
+                clear_color.w,
+                1.0,
+            ),
+            |pass| {
+                self.render(
+                    pass,
+                    RenderStates::new(Viewport::new_at_origo(width, height)),
+                    camera,
+                    ambient_light,
+                    directional_lights,
+                    meshes,
+                )
+            },
+        )
+    }
+}
@@ -0,0 +1,775 @@
+use crate::core::*;
+use std::collections::{HashMap, HashSet};
+
+// The uniforms declared outside a block are collected in a uniform block for each stage with these bindings,
+// the bindings of the other resources are allocated after these
+pub(super) const VERTEX_UNIFORMS_BINDING: u32 = 0;
+pub(super) const FRAGMENT_UNIFORMS_BINDING: u32 = 1;
+
+// The name of the vertex shader uniform which maps the depth from the OpenGL range [-1, 1] to the wgpu range [0, 1],
+// except when the depth is reversed, since the depth is then already in the range [0, 1], see ClearState::reverse_depth
+pub(super) const DEPTH_TRANSFORM: &str = "three_d_depth_transform";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum ResourceKind {
+    VertexUniforms,
+    FragmentUniforms,
+    UniformBlock(String),
+    Texture(String),
+    Sampler(String),
+}
+
+pub(super) struct Resource {
+    pub kind: ResourceKind,
+    pub binding: u32,
+    pub visibility: wgpu::ShaderStages,
+    // The size in bytes of a uniform block
+    pub size: u32,
+}
+
+// The name, offset and type of each member of a uniform block
+pub(super) type UniformMembers = Vec<(String, u32, UniformType)>;
+
+// The type of a uniform in a uniform block as laid out in memory
+#[derive(Debug, Clone)]
+pub(super) enum UniformType {
+    Float,
+    Int,
+    Vector(u32),
+    Matrix {
+        columns: u32,
+        rows: u32,
+        column_stride: u32,
+    },
+    Array {
+        element: Box<UniformType>,
+        count: u32,
+        stride: u32,
+    },
+    Struct(UniformMembers),
+}
+
+pub(super) struct Translation {
+    pub vertex_wgsl: String,
+    pub fragment_wgsl: String,
+    // The location and number of components of each vertex attribute
+    pub attributes: HashMap<String, (u32, u32)>,
+    pub resources: Vec<Resource>,
+    // The offset and type of the uniforms declared outside a block in the vertex and fragment shader
+    pub vertex_uniforms: UniformMembers,
+    pub fragment_uniforms: UniformMembers,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Stage {
+    Vertex,
+    Fragment,
+}
+
+impl Stage {
+    fn name(&self) -> &'static str {
+        match self {
+            Stage::Vertex => "vertex",
+            Stage::Fragment => "fragment",
+        }
+    }
+}
+
+///
+/// Translates a vertex and fragment shader written in the GLSL accepted by [Program::from_source](crate::Program::from_source)
+/// into WGSL. Since wgpu only has uniform blocks and separate textures and samplers, the uniforms declared outside a block
+/// are collected in a uniform block for each stage and each `sampler2D` is split into a texture and a sampler.
+/// The vertex attributes, the varyings and the fragment shader outputs are given a location if they do not already have one.
+/// Finally, the depth of the vertex position is mapped from the OpenGL range [-1, 1] to the wgpu range [0, 1],
+/// so that the same projection matrices can be used with both backends.
+///
+pub(super) fn translate(vertex_source: &str, fragment_source: &str) -> Result<Translation, Error> {
+    let mut translator = Translator {
+        resources: Vec::new(),
+        attributes: HashMap::new(),
+        varyings: HashMap::new(),
+        next_varying_location: 0,
+        samplers: HashSet::new(),
+    };
+    let vertex_glsl = translator.translate_stage(vertex_source, Stage::Vertex)?;
+    translator.samplers.clear();
+    let fragment_glsl = translator.translate_stage(fragment_source, Stage::Fragment)?;
+
+    let vertex_module = parse_glsl(&vertex_glsl, Stage::Vertex)?;
+    let fragment_module = parse_glsl(&fragment_glsl, Stage::Fragment)?;
+    let vertex_wgsl = write_wgsl(&vertex_module, Stage::Vertex)?;
+    let fragment_wgsl = write_wgsl(&fragment_module, Stage::Fragment)?;
+
+    let mut resources = translator.resources;
+    for resource in resources.iter_mut() {
+        resource.size = uniform_block(&vertex_module, resource.binding)
+            .or_else(|| uniform_block(&fragment_module, resource.binding))
+            .map(|(size, _)| size)
+            .unwrap_or(0);
+    }
+    // Only the attributes which are actually used by the vertex shader need a buffer when drawing
+    let used_locations: HashSet<u32> = vertex_module
+        .entry_points
+        .iter()
+        .flat_map(|entry_point| entry_point.function.arguments.iter())
+        .filter_map(|argument| match argument.binding {
+            Some(naga::Binding::Location { location, .. }) => Some(location),
+            _ => None,
+        })
+        .collect();
+    let mut attributes = translator.attributes;
+    attributes.retain(|_, (location, _)| used_locations.contains(location));
+    Ok(Translation {
+        vertex_wgsl,
+        fragment_wgsl,
+        attributes,
+        resources,
+        vertex_uniforms: uniform_block(&vertex_module, VERTEX_UNIFORMS_BINDING)
+            .map(|(_, members)| members)
+            .unwrap_or_default(),
+        fragment_uniforms: uniform_block(&fragment_module, FRAGMENT_UNIFORMS_BINDING)
+            .map(|(_, members)| members)
+            .unwrap_or_default(),
+    })
+}
+
+fn parse_glsl(source: &str, stage: Stage) -> Result<naga::Module, Error> {
+    let options = naga::front::glsl::Options::from(match stage {
+        Stage::Vertex => naga::ShaderStage::Vertex,
+        Stage::Fragment => naga::ShaderStage::Fragment,
+    });
+    naga::front::glsl::Frontend::default()
+        .parse(&options, source)
+        .map_err(|e| Error::ProgramError {
+            message: format!(
+                "Failed to translate the {} shader to WGSL:\n{}",
+                stage.name(),
+                e.emit_to_string(source)
+            ),
+        })
+}
+
+fn write_wgsl(module: &naga::Module, stage: Stage) -> Result<String, Error> {
+    let failed = |message: String| Error::ProgramError {
+        message: format!(
+            "Failed to translate the {} shader to WGSL: {}",
+            stage.name(),
+            message
+        ),
+    };
+    let info = validator()
+        .validate(module)
+        .map_err(|e| failed(format!("{:?}", e.into_inner())))?;
+    let wgsl =
+        naga::back::wgsl::write_string(module, &info, naga::back::wgsl::WriterFlags::empty())
+            .map_err(|e| failed(e.to_string()))?;
+    // Not everything can be expressed in WGSL, for example a uniform array of floats, so the result is validated again
+    let translated =
+        naga::front::wgsl::parse_str(&wgsl).map_err(|e| failed(e.emit_to_string(&wgsl)))?;
+    validator()
+        .validate(&translated)
+        .map_err(|e| failed(e.emit_to_string(&wgsl)))?;
+    Ok(wgsl)
+}
+
+fn validator() -> naga::valid::Validator {
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    )
+}
+
+// Returns the size and the members of the uniform block with the given binding
+fn uniform_block(module: &naga::Module, binding: u32) -> Option<(u32, UniformMembers)> {
+    let variable = module.global_variables.iter().find(|(_, variable)| {
+        variable.space == naga::AddressSpace::Uniform
+            && variable.binding.as_ref().map(|b| (b.group, b.binding)) == Some((0, binding))
+    })?;
+    match uniform_type(module, variable.1.ty) {
+        Some(UniformType::Struct(members)) => Some((
+            module.types[variable.1.ty].inner.size(module.to_ctx()),
+            members,
+        )),
+        _ => None,
+    }
+}
+
+fn uniform_type(module: &naga::Module, ty: naga::Handle<naga::Type>) -> Option<UniformType> {
+    let is_32_bit = |scalar: naga::Scalar| scalar.width == 4;
+    Some(match module.types[ty].inner {
+        naga::TypeInner::Scalar(scalar) if is_32_bit(scalar) => match scalar.kind {
+            naga::ScalarKind::Float => UniformType::Float,
+            naga::ScalarKind::Sint | naga::ScalarKind::Uint => UniformType::Int,
+            _ => None?,
+        },
+        naga::TypeInner::Vector { size, scalar }
+            if is_32_bit(scalar) && scalar.kind == naga::ScalarKind::Float =>
+        {
+            UniformType::Vector(size as u32)
+        }
+        naga::TypeInner::Matrix { columns, rows, .. } => UniformType::Matrix {
+            columns: columns as u32,
+            rows: rows as u32,
+            column_stride: module.types[ty].inner.size(module.to_ctx()) / columns as u32,
+        },
+        naga::TypeInner::Array {
+            base,
+            size: naga::ArraySize::Constant(count),
+            stride,
+        } => UniformType::Array {
+            element: Box::new(uniform_type(module, base)?),
+            count: count.get(),
+            stride,
+        },
+        naga::TypeInner::Struct { ref members, .. } => UniformType::Struct(
+            members
+                .iter()
+                .filter_map(|member| {
+                    Some((
+                        member.name.clone()?,
+                        member.offset,
+                        uniform_type(module, member.ty)?,
+                    ))
+                })
+                .collect(),
+        ),
+        _ => None?,
+    })
+}
+
+struct Translator {
+    resources: Vec<Resource>,
+    attributes: HashMap<String, (u32, u32)>,
+    varyings: HashMap<String, u32>,
+    next_varying_location: u32,
+    // The names of the sampler2D uniforms in the current stage, which are replaced by a combination of a texture and a sampler
+    samplers: HashSet<String>,
+}
+
+impl Translator {
+    fn translate_stage(&mut self, source: &str, stage: Stage) -> Result<String, Error> {
+        let tokens = tokenize(source);
+        let mut output = Vec::new();
+        let mut uniforms = Vec::new();
+        let mut uniforms_position = None;
+        let mut next_output_location = 0;
+        let mut depth = 0;
+        let mut is_statement_start = true;
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            if token.is_directive() {
+                if !token.text.starts_with("#version") {
+                    output.push(token.clone());
+                }
+                i += 1;
+                is_statement_start = true;
+                continue;
+            }
+            if token.is_newline() {
+                output.push(token.clone());
+                i += 1;
+                continue;
+            }
+            if depth == 0 && is_statement_start && is_declaration_start(&token.text) {
+                let end = statement_end(&tokens, i).ok_or_else(|| {
+                    translation_error(stage, "Unterminated declaration", &token.text)
+                })?;
+                let statement: Vec<Token> = tokens[i..end]
+                    .iter()
+                    .filter(|token| !token.is_newline())
+                    .cloned()
+                    .collect();
+                let declaration =
+                    self.translate_declaration(&statement, stage, &mut next_output_location)?;
+                match declaration {
+                    Declaration::Unchanged => output.extend(statement),
+                    Declaration::Replaced(text) => output.push(Token::new(&text)),
+                    Declaration::Uniform(members) => {
+                        uniforms_position.get_or_insert(output.len());
+                        uniforms.push(members);
+                    }
+                    Declaration::Removed => {}
+                }
+                i = end;
+                is_statement_start = true;
+                continue;
+            }
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" => depth -= 1,
+                _ => {}
+            }
+            is_statement_start = depth == 0 && (token.text == ";" || token.text == "}");
+            if stage == Stage::Vertex
+                && depth == 0
+                && token.text == "main"
+                && next_token(&tokens, i).map(|t| t.text == "(") == Some(true)
+            {
+                output.push(Token::new("three_d_main"));
+            } else if self.samplers.contains(&token.text) {
+                output.push(Token::new(&format!(
+                    "sampler2D({}, {})",
+                    token.text,
+                    sampler_name(&token.text)
+                )));
+            } else {
+                output.push(token.clone());
+            }
+            i += 1;
+        }
+
+        if stage == Stage::Vertex {
+            uniforms.push(format!("    vec2 {};", DEPTH_TRANSFORM));
+        }
+        if !uniforms.is_empty() {
+            let position = uniforms_position.unwrap_or(0);
+            let (name, binding) = match stage {
+                Stage::Vertex => ("VertexUniforms", VERTEX_UNIFORMS_BINDING),
+                Stage::Fragment => ("FragmentUniforms", FRAGMENT_UNIFORMS_BINDING),
+            };
+            let kind = match stage {
+                Stage::Vertex => ResourceKind::VertexUniforms,
+                Stage::Fragment => ResourceKind::FragmentUniforms,
+            };
+            self.resources.push(Resource {
+                kind,
+                binding,
+                visibility: shader_stage(stage),
+                size: 0,
+            });
+            output.insert(
+                position,
+                Token::new(&format!(
+                    "layout(std140, set = 0, binding = {}) uniform {} {{\n{}\n}};\n",
+                    binding,
+                    name,
+                    uniforms.join("\n")
+                )),
+            );
+        }
+
+        let mut glsl = String::from("#version 450\n");
+        for token in output.iter() {
+            if token.is_directive() && !glsl.ends_with('\n') {
+                glsl.push('\n');
+            }
+            if token.space_before {
+                glsl.push(' ');
+            }
+            glsl.push_str(&token.text);
+        }
+        if stage == Stage::Vertex {
+            glsl.push_str(
+                &format!("\nvoid main()\n{{\n    three_d_main();\n    gl_Position.z = dot(vec2(gl_Position.z, gl_Position.w), {});\n}}\n", DEPTH_TRANSFORM),
+            );
+        }
+        Ok(glsl)
+    }
+
+    fn translate_declaration(
+        &mut self,
+        statement: &[Token],
+        stage: Stage,
+        next_output_location: &mut u32,
+    ) -> Result<Declaration, Error> {
+        let text = statement
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let error = |message: &str| translation_error(stage, message, &text);
+
+        // Qualifiers
+        let mut location = None;
+        let mut interpolation = Vec::new();
+        let mut storage = None;
+        let mut i = 0;
+        while i < statement.len() {
+            match statement[i].text.as_str() {
+                "precision" => return Ok(Declaration::Removed),
+                "layout" => {
+                    let end = statement[i..]
+                        .iter()
+                        .position(|token| token.text == ")")
+                        .ok_or_else(|| error("Invalid layout qualifier"))?;
+                    let qualifiers: Vec<&str> = statement[i + 2..i + end]
+                        .iter()
+                        .map(|token| token.text.as_str())
+                        .collect();
+                    for qualifier in qualifiers.split(|token| *token == ",") {
+                        if let ["location", "=", value] = qualifier {
+                            location = Some(
+                                value
+                                    .parse::<u32>()
+                                    .map_err(|_| error("Invalid location"))?,
+                            );
+                        }
+                    }
+                    i += end + 1;
+                    continue;
+                }
+                "in" | "out" | "uniform" => storage = Some(statement[i].text.clone()),
+                "flat" | "smooth" | "noperspective" | "centroid" | "invariant" => {
+                    interpolation.push(statement[i].text.clone())
+                }
+                "highp" | "mediump" | "lowp" => {}
+                _ => break,
+            }
+            i += 1;
+        }
+        let storage = match storage {
+            Some(storage) => storage,
+            None => return Ok(Declaration::Unchanged),
+        };
+        let rest = &statement[i..];
+        if rest.len() < 3 {
+            return Err(error("Invalid declaration"));
+        }
+        let type_name = rest[0].text.as_str();
+
+        if rest[1].text == "{" {
+            if storage != "uniform" {
+                return Err(error("Interface blocks are not supported"));
+            }
+            let binding = self.binding(ResourceKind::UniformBlock(type_name.to_owned()), stage);
+            let block: Vec<&str> = rest.iter().map(|token| token.text.as_str()).collect();
+            return Ok(Declaration::Replaced(format!(
+                "layout(std140, set = 0, binding = {}) uniform {}",
+                binding,
+                block.join(" ")
+            )));
+        }
+
+        // The names of the declared variables, which can be several separated by commas
+        let declarators: Vec<&[Token]> = rest[1..rest.len() - 1]
+            .split(|token| token.text == ",")
+            .collect();
+        let simple_names = || -> Result<Vec<String>, Error> {
+            declarators
+                .iter()
+                .map(|declarator| match declarator {
+                    [name] if name.is_word() => Ok(name.text.clone()),
+                    _ => Err(error("Only declarations of single variables are supported")),
+                })
+                .collect()
+        };
+
+        match (storage.as_str(), stage) {
+            ("uniform", _) if type_name == "sampler2D" => {
+                let mut declarations = String::new();
+                for name in simple_names()? {
+                    let texture_binding = self.binding(ResourceKind::Texture(name.clone()), stage);
+                    let sampler_binding = self.binding(ResourceKind::Sampler(name.clone()), stage);
+                    declarations.push_str(&format!(
+                        "layout(set = 0, binding = {}) uniform texture2D {};\nlayout(set = 0, binding = {}) uniform sampler {};\n",
+                        texture_binding,
+                        name,
+                        sampler_binding,
+                        sampler_name(&name)
+                    ));
+                    self.samplers.insert(name);
+                }
+                Ok(Declaration::Replaced(declarations))
+            }
+            ("uniform", _) if type_name.contains("sampler") || type_name.contains("image") => {
+                Err(error("Only sampler2D is supported by the wgpu backend"))
+            }
+            ("uniform", _) => {
+                let member: Vec<&str> = rest.iter().map(|token| token.text.as_str()).collect();
+                Ok(Declaration::Uniform(format!("    {}", member.join(" "))))
+            }
+            ("in", Stage::Vertex) => {
+                let components = match type_name {
+                    "float" => 1,
+                    "vec2" => 2,
+                    "vec3" => 3,
+                    "vec4" => 4,
+                    _ => {
+                        return Err(error(
+                            "Only float, vec2, vec3 and vec4 attributes are supported",
+                        ))
+                    }
+                };
+                let mut declarations = String::new();
+                for name in simple_names()? {
+                    let location = location.unwrap_or_else(|| {
+                        self.attributes
+                            .values()
+                            .map(|(location, _)| location + 1)
+                            .max()
+                            .unwrap_or(0)
+                    });
+                    self.attributes.insert(name.clone(), (location, components));
+                    declarations.push_str(&format!(
+                        "layout(location = {}) in {} {};\n",
+                        location, type_name, name
+                    ));
+                }
+                Ok(Declaration::Replaced(declarations))
+            }
+            ("out", Stage::Vertex) | ("in", Stage::Fragment) => {
+                let location_count = match type_name {
+                    "mat2" => 2,
+                    "mat3" => 3,
+                    "mat4" => 4,
+                    _ => 1,
+                };
+                let mut declarations = String::new();
+                for name in simple_names()? {
+                    let location = match stage {
+                        Stage::Vertex => {
+                            let location = self.next_varying_location;
+                            self.next_varying_location += location_count;
+                            self.varyings.insert(name.clone(), location);
+                            location
+                        }
+                        Stage::Fragment => *self.varyings.get(&name).ok_or_else(|| {
+                            error("The fragment shader input is not an output of the vertex shader")
+                        })?,
+                    };
+                    declarations.push_str(&format!(
+                        "layout(location = {}) {} {} {} {};\n",
+                        location,
+                        interpolation.join(" "),
+                        storage,
+                        type_name,
+                        name
+                    ));
+                }
+                Ok(Declaration::Replaced(declarations))
+            }
+            ("out", Stage::Fragment) => {
+                let mut declarations = String::new();
+                for name in simple_names()? {
+                    let location = location.unwrap_or(*next_output_location);
+                    *next_output_location = location + 1;
+                    declarations.push_str(&format!(
+                        "layout(location = {}) out {} {};\n",
+                        location, type_name, name
+                    ));
+                }
+                Ok(Declaration::Replaced(declarations))
+            }
+            _ => Ok(Declaration::Unchanged),
+        }
+    }
+
+    // Returns the binding of the given resource, which is shared between the stages
+    fn binding(&mut self, kind: ResourceKind, stage: Stage) -> u32 {
+        if let Some(resource) = self.resources.iter_mut().find(|r| r.kind == kind) {
+            resource.visibility |= shader_stage(stage);
+            return resource.binding;
+        }
+        let binding = self
+            .resources
+            .iter()
+            .map(|resource| resource.binding + 1)
+            .max()
+            .unwrap_or(0)
+            .max(FRAGMENT_UNIFORMS_BINDING + 1);
+        self.resources.push(Resource {
+            kind,
+            binding,
+            visibility: shader_stage(stage),
+            size: 0,
+        });
+        binding
+    }
+}
+
+enum Declaration {
+    Unchanged,
+    Replaced(String),
+    // A uniform declared outside a block, which is moved into the uniform block of the stage
+    Uniform(String),
+    Removed,
+}
+
+fn shader_stage(stage: Stage) -> wgpu::ShaderStages {
+    match stage {
+        Stage::Vertex => wgpu::ShaderStages::VERTEX,
+        Stage::Fragment => wgpu::ShaderStages::FRAGMENT,
+    }
+}
+
+fn sampler_name(texture_name: &str) -> String {
+    format!("three_d_{}_sampler", texture_name)
+}
+
+fn translation_error(stage: Stage, message: &str, declaration: &str) -> Error {
+    Error::ProgramError {
+        message: format!(
+            "Failed to translate the {} shader to WGSL: {}: {}",
+            stage.name(),
+            message,
+            declaration
+        ),
+    }
+}
+
+fn is_declaration_start(word: &str) -> bool {
+    matches!(
+        word,
+        "precision"
+            | "layout"
+            | "in"
+            | "out"
+            | "uniform"
+            | "flat"
+            | "smooth"
+            | "noperspective"
+            | "centroid"
+            | "invariant"
+            | "highp"
+            | "mediump"
+            | "lowp"
+    )
+}
+
+// Returns the index after the semicolon which ends the statement starting at the given index
+fn statement_end(tokens: &[Token], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token.text.as_str() {
+            "{" | "(" | "[" => depth += 1,
+            "}" | ")" | "]" => depth -= 1,
+            ";" if depth == 0 => return Some(i + 1),
+            _ if token.is_directive() => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn next_token(tokens: &[Token], index: usize) -> Option<&Token> {
+    tokens[index + 1..].iter().find(|token| !token.is_newline())
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    space_before: bool,
+}
+
+impl Token {
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            space_before: true,
+        }
+    }
+
+    fn is_directive(&self) -> bool {
+        self.text.starts_with('#')
+    }
+
+    fn is_newline(&self) -> bool {
+        self.text == "\n"
+    }
+
+    fn is_word(&self) -> bool {
+        self.text
+            .chars()
+            .next()
+            .map(|c| c.is_ascii_alphabetic() || c == '_')
+            .unwrap_or(false)
+    }
+}
+
+// Splits the source into words, numbers, punctuation, preprocessor directives and line breaks, the comments are removed
+fn tokenize(source: &str) -> Vec<Token> {
+    let source = remove_comments(source);
+    let mut tokens = Vec::new();
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        if line.trim_start().starts_with('#') {
+            let mut directive = line.trim().to_owned();
+            while directive.ends_with('\\') {
+                directive.pop();
+                directive.push_str(lines.next().unwrap_or("").trim());
+            }
+            tokens.push(Token {
+                text: directive,
+                space_before: false,
+            });
+        } else {
+            let chars: Vec<char> = line.chars().collect();
+            let mut space_before = false;
+            let mut i = 0;
+            while i < chars.len() {
+                let c = chars[i];
+                let start = i;
+                if c.is_whitespace() {
+                    space_before = true;
+                    i += 1;
+                    continue;
+                } else if c.is_ascii_alphabetic() || c == '_' {
+                    while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                        i += 1;
+                    }
+                } else if c.is_ascii_digit()
+                    || (c == '.' && chars.get(i + 1).map(|c| c.is_ascii_digit()) == Some(true))
+                {
+                    while i < chars.len()
+                        && (chars[i].is_ascii_alphanumeric() || chars[i] == '.' || chars[i] == '_')
+                    {
+                        // The sign of an exponent is part of the number
+                        if (chars[i] == 'e' || chars[i] == 'E')
+                            && matches!(chars.get(i + 1), Some('+') | Some('-'))
+                            && !chars[start..i].iter().any(|c| *c == 'x' || *c == 'X')
+                        {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                } else {
+                    i += 1;
+                }
+                tokens.push(Token {
+                    text: chars[start..i].iter().collect(),
+                    space_before,
+                });
+                space_before = false;
+            }
+        }
+        tokens.push(Token {
+            text: "\n".to_owned(),
+            space_before: false,
+        });
+    }
+    tokens
+}
+
+fn remove_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                while let Some(c) = chars.peek() {
+                    if *c == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        result.push('\n');
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                result.push(' ');
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
@@ -0,0 +1,978 @@
+use super::glsl_translation::*;
+use super::*;
+use crate::core::*;
+use crate::math::*;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wgpu::util::DeviceExt;
+
+///
+/// A shader program consisting of a vertex shader followed by a fragment shader, the equivalent of the OpenGL [Program](crate::Program).
+/// The data is transferred to the shaders using the use_attribute, use_uniform and use_texture functionality like for the OpenGL program
+/// and the draw calls are recorded in a [WgpuRenderPass].
+///
+pub struct WgpuProgram {
+    context: WgpuContext,
+    vertex_wgsl: String,
+    fragment_wgsl: String,
+    vertex_module: wgpu::ShaderModule,
+    fragment_module: wgpu::ShaderModule,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    attributes: HashMap<String, (u32, u32)>,
+    resources: Vec<Resource>,
+    vertex_uniforms: UniformMembers,
+    fragment_uniforms: UniformMembers,
+    vertex_uniform_data: RefCell<Vec<u8>>,
+    fragment_uniform_data: RefCell<Vec<u8>>,
+    bindings: RefCell<HashMap<u32, Binding>>,
+    vertex_buffers: RefCell<HashMap<u32, (wgpu::Buffer, wgpu::VertexFormat)>>,
+    pipelines: RefCell<HashMap<PipelineKey, wgpu::RenderPipeline>>,
+}
+
+impl WgpuProgram {
+    ///
+    /// Creates a new shader program from the given vertex and fragment glsl shader source after processing both with the given [ShaderPreprocessor],
+    /// ie. after resolving includes and adding defines.
+    ///
+    pub fn from_source_with_preprocessor(
+        context: &WgpuContext,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+        preprocessor: &ShaderPreprocessor,
+    ) -> Result<Self, Error> {
+        Self::from_source(
+            context,
+            &preprocessor.process(vertex_shader_source)?,
+            &preprocessor.process(fragment_shader_source)?,
+        )
+    }
+
+    ///
+    /// Creates a new shader program from the given vertex and fragment glsl shader source, which is the same source as for [Program::from_source](crate::Program::from_source).
+    /// The source is translated to WGSL, which has some restrictions:
+    /// - Only `sampler2D` textures are supported.
+    /// - The vertex attributes must be of type `float`, `vec2`, `vec3` or `vec4`.
+    /// - Interface blocks and arrays are not supported as vertex shader outputs and fragment shader inputs.
+    /// - Uniform arrays of `float`, `int` and `vec2` are not supported, since their std140 layout cannot be expressed in WGSL, use `vec4` arrays instead.
+    ///
+    /// # Errors
+    /// Will return an error if the source is not valid GLSL or cannot be translated to WGSL.
+    ///
+    pub fn from_source(
+        context: &WgpuContext,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> Result<Self, Error> {
+        let translation = translate(vertex_shader_source, fragment_shader_source)?;
+        let device = context.device();
+        let create_module = |source: &str| {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("three-d shader"),
+                source: wgpu::ShaderSource::Wgsl(Cow::Owned(source.to_owned())),
+            })
+        };
+        let vertex_module = create_module(&translation.vertex_wgsl);
+        let fragment_module = create_module(&translation.fragment_wgsl);
+
+        let entries: Vec<wgpu::BindGroupLayoutEntry> = translation
+            .resources
+            .iter()
+            .map(|resource| wgpu::BindGroupLayoutEntry {
+                binding: resource.binding,
+                visibility: resource.visibility,
+                ty: match resource.kind {
+                    ResourceKind::Texture(_) => wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    ResourceKind::Sampler(_) => {
+                        wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)
+                    }
+                    _ => wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(resource.size as u64),
+                    },
+                },
+                count: None,
+            })
+            .collect();
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("three-d bind group layout"),
+            entries: &entries,
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("three-d pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let block_size = |kind: ResourceKind| {
+            translation
+                .resources
+                .iter()
+                .find(|resource| resource.kind == kind)
+                .map(|resource| resource.size as usize)
+                .unwrap_or(0)
+        };
+        Ok(Self {
+            context: context.clone(),
+            vertex_module,
+            fragment_module,
+            bind_group_layout,
+            pipeline_layout,
+            vertex_uniform_data: RefCell::new(vec![0; block_size(ResourceKind::VertexUniforms)]),
+            fragment_uniform_data: RefCell::new(vec![
+                0;
+                block_size(ResourceKind::FragmentUniforms)
+            ]),
+            vertex_wgsl: translation.vertex_wgsl,
+            fragment_wgsl: translation.fragment_wgsl,
+            attributes: translation.attributes,
+            resources: translation.resources,
+            vertex_uniforms: translation.vertex_uniforms,
+            fragment_uniforms: translation.fragment_uniforms,
+            bindings: RefCell::new(HashMap::new()),
+            vertex_buffers: RefCell::new(HashMap::new()),
+            pipelines: RefCell::new(HashMap::new()),
+        })
+    }
+
+    ///
+    /// Returns the vertex shader translated to WGSL.
+    ///
+    pub fn vertex_shader_wgsl(&self) -> &str {
+        &self.vertex_wgsl
+    }
+
+    ///
+    /// Returns the fragment shader translated to WGSL.
+    ///
+    pub fn fragment_shader_wgsl(&self) -> &str {
+        &self.fragment_wgsl
+    }
+
+    ///
+    /// Send the given integer value to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform int`. Like for the other uniforms, the name can refer to a member of a struct
+    /// or an element of an array, for example `lights[1].color`.
+    ///
+    pub fn use_uniform_int(&self, name: &str, data: &i32) -> Result<(), Error> {
+        self.use_uniform(name, Shape::Int, &[*data as u32], false)
+    }
+
+    ///
+    /// Send the given float value to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform float`.
+    ///
+    pub fn use_uniform_float(&self, name: &str, data: &f32) -> Result<(), Error> {
+        self.use_uniform_f32(name, Shape::Float, &[*data], false)
+    }
+
+    ///
+    /// Send the given [Vec2](crate::Vec2) value to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform vec2`.
+    ///
+    pub fn use_uniform_vec2(&self, name: &str, data: &Vec2) -> Result<(), Error> {
+        self.use_uniform_f32(name, Shape::Vector(2), &data.to_slice(), false)
+    }
+
+    ///
+    /// Send the given [Vec3](crate::Vec3) value to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform vec3`.
+    ///
+    pub fn use_uniform_vec3(&self, name: &str, data: &Vec3) -> Result<(), Error> {
+        self.use_uniform_f32(name, Shape::Vector(3), &data.to_slice(), false)
+    }
+
+    ///
+    /// Send the given [Vec4](crate::Vec4) value to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform vec4`.
+    ///
+    pub fn use_uniform_vec4(&self, name: &str, data: &Vec4) -> Result<(), Error> {
+        self.use_uniform_f32(name, Shape::Vector(4), &data.to_slice(), false)
+    }
+
+    ///
+    /// Send the given [Mat2](crate::Mat2) value to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform mat2`.
+    ///
+    pub fn use_uniform_mat2(&self, name: &str, data: &Mat2) -> Result<(), Error> {
+        self.use_uniform_f32(name, Shape::Matrix(2, 2), &data.to_slice(), false)
+    }
+
+    ///
+    /// Send the given [Mat3](crate::Mat3) value to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform mat3`.
+    ///
+    pub fn use_uniform_mat3(&self, name: &str, data: &Mat3) -> Result<(), Error> {
+        self.use_uniform_f32(name, Shape::Matrix(3, 3), &data.to_slice(), false)
+    }
+
+    ///
+    /// Send the given [Mat4](crate::Mat4) value to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform mat4`.
+    ///
+    pub fn use_uniform_mat4(&self, name: &str, data: &Mat4) -> Result<(), Error> {
+        self.use_uniform_f32(name, Shape::Matrix(4, 4), &data.to_slice(), false)
+    }
+
+    ///
+    /// Send the given array of [Mat4](crate::Mat4) values to this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform mat4 name[N]` where N is at least the number of given values.
+    ///
+    pub fn use_uniform_mat4_array(&self, name: &str, data: &[Mat4]) -> Result<(), Error> {
+        let data: Vec<f32> = data.iter().flat_map(|m| m.to_slice()).collect();
+        self.use_uniform_f32(name, Shape::Matrix(4, 4), &data, true)
+    }
+
+    fn use_uniform_f32(
+        &self,
+        name: &str,
+        shape: Shape,
+        data: &[f32],
+        is_array: bool,
+    ) -> Result<(), Error> {
+        let words: Vec<u32> = data.iter().map(|value| value.to_bits()).collect();
+        self.use_uniform(name, shape, &words, is_array)
+    }
+
+    // Writes the given data to the uniform block of each stage which uses the uniform with the given name
+    fn use_uniform(
+        &self,
+        name: &str,
+        shape: Shape,
+        words: &[u32],
+        is_array: bool,
+    ) -> Result<(), Error> {
+        let mut is_used = false;
+        for (members, data) in [
+            (&self.vertex_uniforms, &self.vertex_uniform_data),
+            (&self.fragment_uniforms, &self.fragment_uniform_data),
+        ]
+        .iter()
+        {
+            if let Some((offset, uniform_type)) = find_uniform(members, name) {
+                let data = &mut data.borrow_mut();
+                let is_written = match uniform_type {
+                    UniformType::Array {
+                        ref element,
+                        count,
+                        stride,
+                    } if is_array => {
+                        let element_size = shape.word_count();
+                        words.len() / element_size <= count as usize
+                            && words.chunks(element_size).enumerate().all(|(i, words)| {
+                                write_uniform(
+                                    data,
+                                    offset + i as u32 * stride,
+                                    element,
+                                    shape,
+                                    words,
+                                )
+                            })
+                    }
+                    _ if is_array => false,
+                    _ => write_uniform(data, offset, &uniform_type, shape, words),
+                };
+                if !is_written {
+                    Err(Error::ProgramError {
+                        message: format!(
+                            "The uniform {} is sent to the shader with a different type than it is declared with ({:?}).",
+                            name, uniform_type
+                        ),
+                    })?;
+                }
+                is_used = true;
+            }
+        }
+        if !is_used {
+            Err(Error::ProgramError {
+                message: format!(
+                    "The uniform {} is sent to the shader but it is never used.",
+                    name
+                ),
+            })?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Use the given [WgpuTexture2D] in this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform sampler2D`.
+    ///
+    pub fn use_texture(&self, texture: &WgpuTexture2D, texture_name: &str) -> Result<(), Error> {
+        let texture_binding = self.binding(ResourceKind::Texture(texture_name.to_owned()));
+        let sampler_binding = self.binding(ResourceKind::Sampler(texture_name.to_owned()));
+        match (texture_binding, sampler_binding) {
+            (Some(texture_binding), Some(sampler_binding)) => {
+                let mut bindings = self.bindings.borrow_mut();
+                bindings.insert(texture_binding, Binding::Texture(texture.view().clone()));
+                bindings.insert(sampler_binding, Binding::Sampler(texture.sampler.clone()));
+                Ok(())
+            }
+            _ => Err(Error::ProgramError {
+                message: format!(
+                    "The texture {} is sent to the shader but it is never used.",
+                    texture_name
+                ),
+            }),
+        }
+    }
+
+    ///
+    /// Use the given [WgpuUniformBuffer] in this shader program and associate it with the uniform block with the given name.
+    ///
+    /// # Errors
+    /// Will return an error if the uniform block is not used in the shader code
+    /// or if the buffer is smaller than the uniform block layout (std140) requires.
+    ///
+    pub fn use_uniform_block(
+        &self,
+        buffer: &WgpuUniformBuffer,
+        block_name: &str,
+    ) -> Result<(), Error> {
+        let resource = self
+            .resources
+            .iter()
+            .find(|resource| resource.kind == ResourceKind::UniformBlock(block_name.to_owned()))
+            .ok_or_else(|| Error::ProgramError {
+                message: format!(
+                    "The uniform block {} is not used in the shader program",
+                    block_name
+                ),
+            })?;
+        if (buffer.size_in_bytes() as u32) < resource.size {
+            Err(Error::ProgramError {
+                message: format!(
+                    "The uniform buffer used for the uniform block {} has size {} bytes, but the block requires {} bytes",
+                    block_name,
+                    buffer.size_in_bytes(),
+                    resource.size
+                ),
+            })?;
+        }
+        self.bindings
+            .borrow_mut()
+            .insert(resource.binding, Binding::Buffer(buffer.buffer.clone()));
+        Ok(())
+    }
+
+    ///
+    /// Uses the given [WgpuVertexBuffer] data in this shader program and associates it with the given named variable.
+    /// Each value in the buffer is used when rendering one vertex using the [draw_arrays](Self::draw_arrays) or [draw_elements](Self::draw_elements) methods.
+    /// The glsl shader variable must be of type `in float`.
+    ///
+    pub fn use_attribute(
+        &self,
+        buffer: &WgpuVertexBuffer,
+        attribute_name: &str,
+    ) -> Result<(), Error> {
+        self.use_attribute_format(buffer, attribute_name, wgpu::VertexFormat::Float32)
+    }
+
+    ///
+    /// Uses the given [WgpuVertexBuffer] data in this shader program and associates it with the given named variable.
+    /// Each consecutive pair of values in the buffer is used when rendering one vertex.
+    /// The glsl shader variable must be of type `in vec2`.
+    ///
+    pub fn use_attribute_vec2(
+        &self,
+        buffer: &WgpuVertexBuffer,
+        attribute_name: &str,
+    ) -> Result<(), Error> {
+        self.use_attribute_format(buffer, attribute_name, wgpu::VertexFormat::Float32x2)
+    }
+
+    ///
+    /// Uses the given [WgpuVertexBuffer] data in this shader program and associates it with the given named variable.
+    /// Each consecutive triple of values in the buffer is used when rendering one vertex.
+    /// The glsl shader variable must be of type `in vec3`.
+    ///
+    pub fn use_attribute_vec3(
+        &self,
+        buffer: &WgpuVertexBuffer,
+        attribute_name: &str,
+    ) -> Result<(), Error> {
+        self.use_attribute_format(buffer, attribute_name, wgpu::VertexFormat::Float32x3)
+    }
+
+    ///
+    /// Uses the given [WgpuVertexBuffer] data in this shader program and associates it with the given named variable.
+    /// Each consecutive four values in the buffer is used when rendering one vertex.
+    /// The glsl shader variable must be of type `in vec4`.
+    ///
+    pub fn use_attribute_vec4(
+        &self,
+        buffer: &WgpuVertexBuffer,
+        attribute_name: &str,
+    ) -> Result<(), Error> {
+        self.use_attribute_format(buffer, attribute_name, wgpu::VertexFormat::Float32x4)
+    }
+
+    fn use_attribute_format(
+        &self,
+        buffer: &WgpuVertexBuffer,
+        attribute_name: &str,
+        format: wgpu::VertexFormat,
+    ) -> Result<(), Error> {
+        let (location, _) =
+            self.attributes
+                .get(attribute_name)
+                .ok_or_else(|| Error::ProgramError {
+                    message: format!(
+                        "The attribute {} is sent to the shader but it is never used.",
+                        attribute_name
+                    ),
+                })?;
+        self.vertex_buffers
+            .borrow_mut()
+            .insert(*location, (buffer.buffer.clone(), format));
+        Ok(())
+    }
+
+    ///
+    /// Draws `count` number of triangles with the given render states and the attributes, uniforms and textures which are used in this program.
+    /// Unlike for the OpenGL program, the render states are given in the pass instead of applied directly.
+    /// The [line width](RenderStates::line_width) is ignored, since wgpu always draws lines with a width of one pixel.
+    ///
+    /// # Errors
+    /// Will return an error if an attribute, texture or uniform block used in the shader is not set,
+    /// or if the render states contain a stencil test, which is not supported by this backend.
+    ///
+    pub fn draw_arrays(
+        &self,
+        pass: &mut WgpuRenderPass,
+        render_states: RenderStates,
+        count: u32,
+    ) -> Result<(), Error> {
+        self.draw_arrays_with_primitive_type(pass, render_states, PrimitiveType::Triangles, count)
+    }
+
+    ///
+    /// Draws the given number of vertices given by the vertex attributes as the given type of primitives.
+    /// Triangle fans and line loops are not supported by wgpu.
+    ///
+    pub fn draw_arrays_with_primitive_type(
+        &self,
+        pass: &mut WgpuRenderPass,
+        render_states: RenderStates,
+        primitive_type: PrimitiveType,
+        count: u32,
+    ) -> Result<(), Error> {
+        if self.prepare(pass, render_states, primitive_type)? {
+            pass.inner().draw(0..count, 0..1);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Draws the triangles defined by the given [WgpuElementBuffer] with the given render states
+    /// and the attributes, uniforms and textures which are used in this program.
+    ///
+    pub fn draw_elements(
+        &self,
+        pass: &mut WgpuRenderPass,
+        render_states: RenderStates,
+        element_buffer: &WgpuElementBuffer,
+    ) -> Result<(), Error> {
+        if element_buffer.count() > 0
+            && self.prepare(pass, render_states, PrimitiveType::Triangles)?
+        {
+            pass.inner()
+                .set_index_buffer(element_buffer.buffer.slice(..), wgpu::IndexFormat::Uint32);
+            pass.inner()
+                .draw_indexed(0..element_buffer.count() as u32, 0, 0..1);
+        }
+        Ok(())
+    }
+
+    // Sets the pipeline, bind group, vertex buffers, viewport and scissor rectangle of the draw call,
+    // returns false if nothing is drawn, for example since both sides of the triangles are culled
+    fn prepare(
+        &self,
+        pass: &mut WgpuRenderPass,
+        render_states: RenderStates,
+        primitive_type: PrimitiveType,
+    ) -> Result<bool, Error> {
+        if render_states.stencil.is_some() {
+            Err(Error::WgpuError {
+                message: "The stencil test is not supported by the wgpu backend".to_string(),
+            })?;
+        }
+        let topology = match primitive_type {
+            PrimitiveType::Triangles => wgpu::PrimitiveTopology::TriangleList,
+            PrimitiveType::TriangleStrip => wgpu::PrimitiveTopology::TriangleStrip,
+            PrimitiveType::Lines => wgpu::PrimitiveTopology::LineList,
+            PrimitiveType::LineStrip => wgpu::PrimitiveTopology::LineStrip,
+            PrimitiveType::Points => wgpu::PrimitiveTopology::PointList,
+            PrimitiveType::TriangleFan | PrimitiveType::LineLoop => Err(Error::WgpuError {
+                message: format!(
+                    "The primitive type {:?} is not supported by wgpu",
+                    primitive_type
+                ),
+            })?,
+        };
+        let viewport = match clamp_to_target(render_states.viewport, pass) {
+            Some(viewport) => viewport,
+            None => return Ok(false),
+        };
+        let scissor = match render_states.scissor {
+            Some(scissor) => match clamp_to_target(scissor, pass) {
+                Some(scissor) => scissor,
+                None => return Ok(false),
+            },
+            None => viewport,
+        };
+        if render_states.cull == CullType::FrontAndBack {
+            return Ok(false);
+        }
+
+        // The vertex buffers are bound in the order of the attribute locations
+        let vertex_buffers = self.vertex_buffers.borrow();
+        let mut locations: Vec<u32> = self
+            .attributes
+            .values()
+            .map(|(location, _)| *location)
+            .collect();
+        locations.sort_unstable();
+        let mut buffers = Vec::new();
+        for location in locations.iter() {
+            match vertex_buffers.get(location) {
+                Some(buffer) => buffers.push(buffer),
+                None => Err(Error::ProgramError {
+                    message: format!(
+                        "The attribute {} is used in the shader but it is not set.",
+                        self.attributes
+                            .iter()
+                            .find(|(_, (l, _))| l == location)
+                            .map(|(name, _)| name.as_str())
+                            .unwrap_or("")
+                    ),
+                })?,
+            }
+        }
+
+        let key = PipelineKey {
+            attributes: locations
+                .iter()
+                .zip(buffers.iter())
+                .map(|(location, (_, format))| (*location, *format))
+                .collect(),
+            primitive: wgpu::PrimitiveState {
+                topology,
+                cull_mode: match render_states.cull {
+                    CullType::Back => Some(wgpu::Face::Back),
+                    CullType::Front => Some(wgpu::Face::Front),
+                    _ => None,
+                },
+                ..Default::default()
+            },
+            color_target: pass.color_format.map(|format| wgpu::ColorTargetState {
+                format,
+                blend: render_states.blend.map(blend_state),
+                write_mask: color_writes(render_states.write_mask),
+            }),
+            depth_stencil: pass
+                .depth_format
+                .map(|format| depth_stencil_state(format, &render_states, pass.reverse_depth)),
+        };
+        let pipeline = self
+            .pipelines
+            .borrow_mut()
+            .entry(key.clone())
+            .or_insert_with(|| self.create_pipeline(&key))
+            .clone();
+        let bind_group = self.bind_group(pass.reverse_depth)?;
+
+        let pass_inner = pass.inner();
+        pass_inner.set_pipeline(&pipeline);
+        pass_inner.set_bind_group(0, &bind_group, &[]);
+        for (slot, (buffer, _)) in buffers.iter().enumerate() {
+            pass_inner.set_vertex_buffer(slot as u32, buffer.slice(..));
+        }
+        pass_inner.set_viewport(
+            viewport.0 as f32,
+            viewport.1 as f32,
+            viewport.2 as f32,
+            viewport.3 as f32,
+            0.0,
+            1.0,
+        );
+        pass_inner.set_scissor_rect(scissor.0, scissor.1, scissor.2, scissor.3);
+        Ok(true)
+    }
+
+    fn create_pipeline(&self, key: &PipelineKey) -> wgpu::RenderPipeline {
+        let attributes: Vec<[wgpu::VertexAttribute; 1]> = key
+            .attributes
+            .iter()
+            .map(|(location, format)| {
+                [wgpu::VertexAttribute {
+                    format: *format,
+                    offset: 0,
+                    shader_location: *location,
+                }]
+            })
+            .collect();
+        let buffers: Vec<wgpu::VertexBufferLayout> = attributes
+            .iter()
+            .map(|attribute| wgpu::VertexBufferLayout {
+                array_stride: attribute[0].format.size(),
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: attribute,
+            })
+            .collect();
+        self.context
+            .device()
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("three-d pipeline"),
+                layout: Some(&self.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &self.vertex_module,
+                    entry_point: Some("main"),
+                    compilation_options: Default::default(),
+                    buffers: &buffers,
+                },
+                primitive: key.primitive,
+                depth_stencil: key.depth_stencil.clone(),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.fragment_module,
+                    entry_point: Some("main"),
+                    compilation_options: Default::default(),
+                    targets: std::slice::from_ref(&key.color_target),
+                }),
+                multiview: None,
+                cache: None,
+            })
+    }
+
+    // The uniforms declared outside a block are copied to new buffers for each draw call,
+    // since writing to the same buffer would change the uniforms of all the draw calls in the submission
+    fn bind_group(&self, reverse_depth: bool) -> Result<wgpu::BindGroup, Error> {
+        let bindings = self.bindings.borrow();
+        let mut buffers = HashMap::new();
+        for resource in self.resources.iter() {
+            let data = match resource.kind {
+                ResourceKind::VertexUniforms => {
+                    let depth_transform: [f32; 2] = if reverse_depth {
+                        [1.0, 0.0]
+                    } else {
+                        [0.5, 0.5]
+                    };
+                    let words: Vec<u32> = depth_transform.iter().map(|v| v.to_bits()).collect();
+                    if let Some((offset, uniform_type)) =
+                        find_uniform(&self.vertex_uniforms, DEPTH_TRANSFORM)
+                    {
+                        write_uniform(
+                            &mut self.vertex_uniform_data.borrow_mut(),
+                            offset,
+                            &uniform_type,
+                            Shape::Vector(2),
+                            &words,
+                        );
+                    }
+                    self.vertex_uniform_data.borrow().clone()
+                }
+                ResourceKind::FragmentUniforms => self.fragment_uniform_data.borrow().clone(),
+                _ => continue,
+            };
+            buffers.insert(
+                resource.binding,
+                self.context
+                    .device()
+                    .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: Some("three-d uniforms"),
+                        contents: &data,
+                        usage: wgpu::BufferUsages::UNIFORM,
+                    }),
+            );
+        }
+
+        let mut entries = Vec::new();
+        for resource in self.resources.iter() {
+            let resource_binding = match resource.kind {
+                ResourceKind::VertexUniforms | ResourceKind::FragmentUniforms => {
+                    buffers[&resource.binding].as_entire_binding()
+                }
+                _ => match bindings.get(&resource.binding) {
+                    Some(Binding::Buffer(buffer)) => buffer.as_entire_binding(),
+                    Some(Binding::Texture(view)) => wgpu::BindingResource::TextureView(view),
+                    Some(Binding::Sampler(sampler)) => wgpu::BindingResource::Sampler(sampler),
+                    None => Err(Error::ProgramError {
+                        message: match resource.kind {
+                            ResourceKind::UniformBlock(ref name) => format!(
+                                "The uniform block {} is used in the shader but no uniform buffer is set.",
+                                name
+                            ),
+                            ResourceKind::Texture(ref name) | ResourceKind::Sampler(ref name) => {
+                                format!(
+                                    "The texture {} is used in the shader but it is not set.",
+                                    name
+                                )
+                            }
+                            _ => unreachable!(),
+                        },
+                    })?,
+                },
+            };
+            entries.push(wgpu::BindGroupEntry {
+                binding: resource.binding,
+                resource: resource_binding,
+            });
+        }
+        Ok(self
+            .context
+            .device()
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("three-d bind group"),
+                layout: &self.bind_group_layout,
+                entries: &entries,
+            }))
+    }
+
+    fn binding(&self, kind: ResourceKind) -> Option<u32> {
+        self.resources
+            .iter()
+            .find(|resource| resource.kind == kind)
+            .map(|resource| resource.binding)
+    }
+}
+
+enum Binding {
+    Buffer(wgpu::Buffer),
+    Texture(wgpu::TextureView),
+    Sampler(wgpu::Sampler),
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    attributes: Vec<(u32, wgpu::VertexFormat)>,
+    primitive: wgpu::PrimitiveState,
+    color_target: Option<wgpu::ColorTargetState>,
+    depth_stencil: Option<wgpu::DepthStencilState>,
+}
+
+// The shape of the data given to one of the use_uniform functions
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Shape {
+    Float,
+    Int,
+    Vector(u32),
+    Matrix(u32, u32),
+}
+
+impl Shape {
+    fn word_count(&self) -> usize {
+        match self {
+            Shape::Float | Shape::Int => 1,
+            Shape::Vector(size) => *size as usize,
+            Shape::Matrix(columns, rows) => (columns * rows) as usize,
+        }
+    }
+}
+
+// Returns the offset and type of the uniform with the given name, which can contain struct members and array indices, for example `lights[1].color`
+fn find_uniform(members: &[(String, u32, UniformType)], name: &str) -> Option<(u32, UniformType)> {
+    let end = name.find(['.', '[']).unwrap_or(name.len());
+    let (_, mut offset, mut uniform_type) = members
+        .iter()
+        .find(|(member_name, _, _)| member_name == &name[..end])?
+        .clone();
+    let mut rest = &name[end..];
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let close = index.find(']')?;
+            let index: u32 = index[..close].trim().parse().ok()?;
+            match uniform_type {
+                UniformType::Array {
+                    element,
+                    count,
+                    stride,
+                } if index < count => {
+                    offset += index * stride;
+                    uniform_type = *element;
+                }
+                _ => None?,
+            }
+            rest = &rest[close + 2..];
+        } else {
+            let member = rest.strip_prefix('.')?;
+            let end = member.find(['.', '[']).unwrap_or(member.len());
+            match uniform_type {
+                UniformType::Struct(members) => {
+                    let (_, member_offset, member_type) = members
+                        .into_iter()
+                        .find(|(member_name, _, _)| member_name == &member[..end])?;
+                    offset += member_offset;
+                    uniform_type = member_type;
+                }
+                _ => None?,
+            }
+            rest = &member[end..];
+        }
+    }
+    Some((offset, uniform_type))
+}
+
+// Writes the given 32-bit words to the uniform at the given offset if the data has the shape of the uniform type
+fn write_uniform(
+    data: &mut [u8],
+    offset: u32,
+    uniform_type: &UniformType,
+    shape: Shape,
+    words: &[u32],
+) -> bool {
+    let write = |data: &mut [u8], offset: u32, words: &[u32]| {
+        for (i, word) in words.iter().enumerate() {
+            let start = offset as usize + i * 4;
+            data[start..start + 4].copy_from_slice(&word.to_le_bytes());
+        }
+    };
+    if words.len() != shape.word_count() {
+        return false;
+    }
+    match (uniform_type, shape) {
+        (UniformType::Float, Shape::Float) | (UniformType::Int, Shape::Int) => {
+            write(data, offset, words)
+        }
+        (UniformType::Vector(size), Shape::Vector(shape_size)) if *size == shape_size => {
+            write(data, offset, words)
+        }
+        (
+            UniformType::Matrix {
+                columns,
+                rows,
+                column_stride,
+            },
+            Shape::Matrix(shape_columns, shape_rows),
+        ) if *columns == shape_columns && *rows == shape_rows => {
+            for (column, words) in words.chunks(*rows as usize).enumerate() {
+                write(data, offset + column as u32 * column_stride, words);
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+// Returns the part of the viewport inside the render target as x, y, width and height with the origin in the top left corner like in wgpu,
+// or None if the viewport is entirely outside
+fn clamp_to_target(viewport: Viewport, pass: &WgpuRenderPass) -> Option<(u32, u32, u32, u32)> {
+    let x0 = viewport.x.max(0) as u32;
+    let y0 = viewport.y.max(0) as u32;
+    let x1 = ((viewport.x as i64 + viewport.width as i64).max(0) as u32).min(pass.width);
+    let y1 = ((viewport.y as i64 + viewport.height as i64).max(0) as u32).min(pass.height);
+    if x0 >= x1 || y0 >= y1 {
+        return None;
+    }
+    Some((x0, pass.height - y1, x1 - x0, y1 - y0))
+}
+
+fn color_writes(write_mask: WriteMask) -> wgpu::ColorWrites {
+    let mut color_writes = wgpu::ColorWrites::empty();
+    for (is_written, color_write) in [
+        (write_mask.red, wgpu::ColorWrites::RED),
+        (write_mask.green, wgpu::ColorWrites::GREEN),
+        (write_mask.blue, wgpu::ColorWrites::BLUE),
+        (write_mask.alpha, wgpu::ColorWrites::ALPHA),
+    ]
+    .iter()
+    {
+        if *is_written {
+            color_writes |= *color_write;
+        }
+    }
+    color_writes
+}
+
+fn depth_stencil_state(
+    format: wgpu::TextureFormat,
+    render_states: &RenderStates,
+    reverse_depth: bool,
+) -> wgpu::DepthStencilState {
+    // The depth test is mirrored in a pass with reversed depth like for OpenGL, see ClearState::reverse_depth
+    let depth_compare = match render_states.depth_test {
+        DepthTestType::Never => wgpu::CompareFunction::Never,
+        DepthTestType::Less if reverse_depth => wgpu::CompareFunction::Greater,
+        DepthTestType::Less => wgpu::CompareFunction::Less,
+        DepthTestType::Equal => wgpu::CompareFunction::Equal,
+        DepthTestType::LessOrEqual if reverse_depth => wgpu::CompareFunction::GreaterEqual,
+        DepthTestType::LessOrEqual => wgpu::CompareFunction::LessEqual,
+        DepthTestType::Greater if reverse_depth => wgpu::CompareFunction::Less,
+        DepthTestType::Greater => wgpu::CompareFunction::Greater,
+        DepthTestType::NotEqual => wgpu::CompareFunction::NotEqual,
+        DepthTestType::GreaterOrEqual if reverse_depth => wgpu::CompareFunction::LessEqual,
+        DepthTestType::GreaterOrEqual => wgpu::CompareFunction::GreaterEqual,
+        DepthTestType::Always => wgpu::CompareFunction::Always,
+    };
+    let bias = render_states
+        .polygon_offset
+        .map(|offset| wgpu::DepthBiasState {
+            constant: offset.units as i32,
+            slope_scale: offset.factor,
+            clamp: 0.0,
+        })
+        .unwrap_or_default();
+    wgpu::DepthStencilState {
+        format,
+        depth_write_enabled: render_states.write_mask.depth,
+        depth_compare,
+        stencil: wgpu::StencilState::default(),
+        bias,
+    }
+}
+
+fn blend_state(blend: BlendParameters) -> wgpu::BlendState {
+    let factor = |multiplier: BlendMultiplierType| match multiplier {
+        BlendMultiplierType::Zero => wgpu::BlendFactor::Zero,
+        BlendMultiplierType::One => wgpu::BlendFactor::One,
+        BlendMultiplierType::SrcColor => wgpu::BlendFactor::Src,
+        BlendMultiplierType::OneMinusSrcColor => wgpu::BlendFactor::OneMinusSrc,
+        BlendMultiplierType::DstColor => wgpu::BlendFactor::Dst,
+        BlendMultiplierType::OneMinusDstColor => wgpu::BlendFactor::OneMinusDst,
+        BlendMultiplierType::SrcAlpha => wgpu::BlendFactor::SrcAlpha,
+        BlendMultiplierType::OneMinusSrcAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
+        BlendMultiplierType::DstAlpha => wgpu::BlendFactor::DstAlpha,
+        BlendMultiplierType::OneMinusDstAlpha => wgpu::BlendFactor::OneMinusDstAlpha,
+        BlendMultiplierType::SrcAlphaSaturate => wgpu::BlendFactor::SrcAlphaSaturated,
+    };
+    let component = |source, destination, equation| {
+        let operation = match equation {
+            BlendEquationType::Add => wgpu::BlendOperation::Add,
+            BlendEquationType::Subtract => wgpu::BlendOperation::Subtract,
+            BlendEquationType::ReverseSubtract => wgpu::BlendOperation::ReverseSubtract,
+            BlendEquationType::Min => wgpu::BlendOperation::Min,
+            BlendEquationType::Max => wgpu::BlendOperation::Max,
+        };
+        // The multipliers are ignored by OpenGL for the min and max equations, but wgpu requires them to be one
+        if operation == wgpu::BlendOperation::Min || operation == wgpu::BlendOperation::Max {
+            wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::One,
+                operation,
+            }
+        } else {
+            wgpu::BlendComponent {
+                src_factor: factor(source),
+                dst_factor: factor(destination),
+                operation,
+            }
+        }
+    };
+    wgpu::BlendState {
+        color: component(
+            blend.source_rgb_multiplier,
+            blend.destination_rgb_multiplier,
+            blend.rgb_equation,
+        ),
+        alpha: component(
+            blend.source_alpha_multiplier,
+            blend.destination_alpha_multiplier,
+            blend.alpha_equation,
+        ),
+    }
+}
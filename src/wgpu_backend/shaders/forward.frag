@@ -0,0 +1,39 @@
+#include "shared"
+
+uniform vec4 surfaceColor;
+uniform float diffuse_intensity;
+uniform float specular_intensity;
+uniform float specular_power;
+uniform vec3 eyePosition;
+uniform vec3 ambientColor;
+uniform int directionalLightCount;
+uniform vec4 directionalLightColors[MAX_DIRECTIONAL_LIGHTS];
+uniform vec4 directionalLightDirections[MAX_DIRECTIONAL_LIGHTS];
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    vec3 viewDirection = normalize(eyePosition - pos);
+    vec3 color = ambientColor * surfaceColor.rgb;
+    for (int i = 0; i < MAX_DIRECTIONAL_LIGHTS; i++)
+    {
+        if (i < directionalLightCount)
+        {
+            vec3 lightDirection = -normalize(directionalLightDirections[i].xyz);
+            float diffuse = max(dot(normal, lightDirection), 0.0);
+            float specular = 0.0;
+            if (diffuse > 0.0)
+            {
+                vec3 halfwayDirection = normalize(lightDirection + viewDirection);
+                specular = pow(max(dot(normal, halfwayDirection), 0.0), specular_power);
+            }
+            color += directionalLightColors[i].rgb * (diffuse_intensity * diffuse * surfaceColor.rgb + specular_intensity * specular);
+        }
+    }
+    outColor = vec4(srgb_from_rgb(color), surfaceColor.a);
+}
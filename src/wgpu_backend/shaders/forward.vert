@@ -0,0 +1,17 @@
+uniform mat4 modelMatrix;
+uniform mat4 normalMatrix;
+uniform mat4 viewProjection;
+
+in vec3 position;
+in vec3 normal;
+
+out vec3 pos;
+out vec3 nor;
+
+void main()
+{
+    vec4 worldPosition = modelMatrix * vec4(position, 1.0);
+    pos = worldPosition.xyz;
+    nor = (normalMatrix * vec4(normal, 0.0)).xyz;
+    gl_Position = viewProjection * worldPosition;
+}
@@ -0,0 +1,326 @@
+use super::*;
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// A 2D texture which can be sampled in a [WgpuProgram] and rendered into using a [WgpuRenderTarget],
+/// the equivalent of the OpenGL [Texture2D](crate::Texture2D) and [ColorTargetTexture2D](crate::ColorTargetTexture2D).
+/// Textures with three channels are stored with four channels, since wgpu has no texture formats with three channels.
+/// Mip maps are not generated.
+///
+pub struct WgpuTexture2D {
+    context: WgpuContext,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    pub(super) sampler: wgpu::Sampler,
+    width: u32,
+    height: u32,
+    format: Format,
+}
+
+impl WgpuTexture2D {
+    ///
+    /// Constructs a new texture with the given data.
+    ///
+    pub fn new(context: &WgpuContext, cpu_texture: &CPUTexture<u8>) -> Result<Self, Error> {
+        let mut texture = Self::new_with_sampler(
+            context,
+            cpu_texture.width,
+            cpu_texture.height,
+            cpu_texture.gpu_format(),
+            &wgpu::SamplerDescriptor {
+                label: Some("three-d sampler"),
+                address_mode_u: address_mode(cpu_texture.wrap_s),
+                address_mode_v: address_mode(cpu_texture.wrap_t),
+                address_mode_w: address_mode(cpu_texture.wrap_r),
+                mag_filter: filter_mode(cpu_texture.mag_filter),
+                min_filter: filter_mode(cpu_texture.min_filter),
+                ..Default::default()
+            },
+        )?;
+        texture.fill(&cpu_texture.data)?;
+        Ok(texture)
+    }
+
+    ///
+    /// Constructs a new texture with the given size and format, which for example can be rendered into.
+    ///
+    pub fn new_empty(
+        context: &WgpuContext,
+        width: u32,
+        height: u32,
+        format: Format,
+    ) -> Result<Self, Error> {
+        Self::new_with_sampler(
+            context,
+            width,
+            height,
+            format,
+            &wgpu::SamplerDescriptor {
+                label: Some("three-d sampler"),
+                mag_filter: wgpu::FilterMode::Linear,
+                min_filter: wgpu::FilterMode::Linear,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn new_with_sampler(
+        context: &WgpuContext,
+        width: u32,
+        height: u32,
+        format: Format,
+        sampler: &wgpu::SamplerDescriptor,
+    ) -> Result<Self, Error> {
+        check_texture_size(
+            width,
+            height,
+            context.device().limits().max_texture_dimension_2d,
+        )?;
+        let texture = context.device().create_texture(&wgpu::TextureDescriptor {
+            label: Some("three-d texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: texture_format(format),
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        Ok(Self {
+            context: context.clone(),
+            view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            sampler: context.device().create_sampler(sampler),
+            texture,
+            width,
+            height,
+            format,
+        })
+    }
+
+    ///
+    /// Fills this texture with the given data, which is in the format the texture was created with.
+    ///
+    /// # Errors
+    /// Return an error if the length of the data array is smaller or bigger than the necessary number of bytes to fill the entire texture.
+    ///
+    pub fn fill(&mut self, data: &[u8]) -> Result<(), Error> {
+        check_data_length(self.width, self.height, 1, self.format, data.len())?;
+        let channels = self.format.color_channel_count() as usize;
+        let data: Vec<u8> = if channels == 3 {
+            data.chunks(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect()
+        } else {
+            data.to_vec()
+        };
+        self.context.queue().write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &data,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(self.width * bytes_per_pixel(self.format)),
+                rows_per_image: Some(self.height),
+            },
+            self.size(),
+        );
+        Ok(())
+    }
+
+    ///
+    /// Returns the data of this texture after waiting for all of the submitted rendering to finish.
+    /// Like [Screen::read_color](crate::Screen::read_color), the first row is the bottom row,
+    /// and like the data given to [fill](Self::fill), each pixel has one byte for each channel, except that textures with three channels are returned with four.
+    /// Only available on desktop, since it is not possible to wait for the GPU on web.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read(&self) -> Result<Vec<u8>, Error> {
+        let row_size = (self.width * bytes_per_pixel(self.format)) as usize;
+        // Each row in the buffer must be aligned
+        let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+        let padded_row_size = row_size.div_ceil(alignment) * alignment;
+        let device = self.context.device();
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("three-d read buffer"),
+            size: (padded_row_size * self.height as usize) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("three-d read"),
+        });
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_size as u32),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            self.size(),
+        );
+        self.context.queue().submit(Some(encoder.finish()));
+
+        let read_error = |message: String| Error::TextureError {
+            message: format!("Could not read the texture: {}", message),
+        };
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        device
+            .poll(wgpu::PollType::Wait)
+            .map_err(|e| read_error(e.to_string()))?;
+        receiver
+            .recv()
+            .map_err(|e| read_error(e.to_string()))?
+            .map_err(|e| read_error(e.to_string()))?;
+        let mapped = slice.get_mapped_range();
+        let mut data = Vec::with_capacity(row_size * self.height as usize);
+        for row in mapped.chunks(padded_row_size).rev() {
+            data.extend_from_slice(&row[..row_size]);
+        }
+        Ok(data)
+    }
+
+    ///
+    /// The width of this texture.
+    ///
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    ///
+    /// The height of this texture.
+    ///
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    ///
+    /// Returns the view of the whole texture, for example for using it in a render pass which is not provided by this backend.
+    ///
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub(super) fn gpu_format(&self) -> wgpu::TextureFormat {
+        texture_format(self.format)
+    }
+
+    fn size(&self) -> wgpu::Extent3d {
+        wgpu::Extent3d {
+            width: self.width,
+            height: self.height,
+            depth_or_array_layers: 1,
+        }
+    }
+}
+
+///
+/// A depth texture which can be rendered into using a [WgpuRenderTarget],
+/// the equivalent of the OpenGL [DepthTargetTexture2D](crate::DepthTargetTexture2D).
+///
+pub struct WgpuDepthTexture2D {
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+}
+
+impl WgpuDepthTexture2D {
+    pub(super) const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+    ///
+    /// Constructs a new depth texture with the given size.
+    ///
+    pub fn new(context: &WgpuContext, width: u32, height: u32) -> Result<Self, Error> {
+        check_texture_size(
+            width,
+            height,
+            context.device().limits().max_texture_dimension_2d,
+        )?;
+        let texture = context.device().create_texture(&wgpu::TextureDescriptor {
+            label: Some("three-d depth texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        Ok(Self {
+            view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            width,
+            height,
+        })
+    }
+
+    ///
+    /// The width of this texture.
+    ///
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    ///
+    /// The height of this texture.
+    ///
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub(super) fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+}
+
+fn texture_format(format: Format) -> wgpu::TextureFormat {
+    match format {
+        Format::R => wgpu::TextureFormat::R8Unorm,
+        Format::RG => wgpu::TextureFormat::Rg8Unorm,
+        Format::RGB | Format::RGBA => wgpu::TextureFormat::Rgba8Unorm,
+        Format::SRGB | Format::SRGBA => wgpu::TextureFormat::Rgba8UnormSrgb,
+    }
+}
+
+fn bytes_per_pixel(format: Format) -> u32 {
+    match format.color_channel_count() {
+        3 => 4,
+        channels => channels,
+    }
+}
+
+fn address_mode(wrapping: Wrapping) -> wgpu::AddressMode {
+    match wrapping {
+        Wrapping::Repeat => wgpu::AddressMode::Repeat,
+        Wrapping::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+        Wrapping::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+    }
+}
+
+fn filter_mode(interpolation: Interpolation) -> wgpu::FilterMode {
+    match interpolation {
+        Interpolation::Nearest => wgpu::FilterMode::Nearest,
+        Interpolation::Linear => wgpu::FilterMode::Linear,
+    }
+}
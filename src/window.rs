@@ -9,12 +9,42 @@ mod settings;
 #[doc(inline)]
 pub use settings::*;
 
+#[cfg(any(
+    all(feature = "glutin-window", not(target_arch = "wasm32")),
+    all(feature = "canvas", target_arch = "wasm32")
+))]
+mod clipboard;
+#[doc(inline)]
+#[cfg(any(
+    all(feature = "glutin-window", not(target_arch = "wasm32")),
+    all(feature = "canvas", target_arch = "wasm32")
+))]
+pub use clipboard::*;
+
 #[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
 mod glutin_window;
 #[doc(inline)]
 #[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
 pub use glutin_window::*;
 
+#[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
+mod multi_window;
+#[doc(inline)]
+#[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
+pub use multi_window::*;
+
+#[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
+mod raw_window;
+#[doc(inline)]
+#[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
+pub use raw_window::*;
+
+#[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
+mod headless_context;
+#[doc(inline)]
+#[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
+pub use headless_context::*;
+
 #[cfg(all(feature = "canvas", target_arch = "wasm32"))]
 mod canvas;
 #[doc(inline)]
@@ -1,3 +1,4 @@
+use crate::definition::*;
 use crate::frame::*;
 use crate::window::WindowSettings;
 use crate::Context;
@@ -15,18 +16,24 @@ pub enum WindowError {
     PerformanceError { message: String },
     EventListenerError { message: String },
     CanvasError { message: String },
+    ClipboardError { message: String },
 }
 
 pub struct Window {
     canvas: Option<web_sys::HtmlCanvasElement>,
     window: Rc<web_sys::Window>,
     settings: WindowSettings,
+    context: RefCell<Option<Context>>,
+    fill_window: bool,
+    resize_observer: Option<web_sys::ResizeObserver>,
+    frame_input_generator: FrameInputGenerator,
     closures: Vec<Closure<dyn FnMut()>>,
     closures_with_event: Vec<Closure<dyn FnMut(web_sys::Event)>>,
     closures_with_mouseevent: Vec<Closure<dyn FnMut(web_sys::MouseEvent)>>,
     closures_with_wheelevent: Vec<Closure<dyn FnMut(web_sys::WheelEvent)>>,
     closures_with_touchevent: Vec<Closure<dyn FnMut(web_sys::TouchEvent)>>,
     closures_with_keyboardevent: Vec<Closure<dyn FnMut(web_sys::KeyboardEvent)>>,
+    closures_with_dragevent: Vec<Closure<dyn FnMut(web_sys::DragEvent)>>,
 }
 
 impl Window {
@@ -44,15 +51,23 @@ impl Window {
             canvas: None,
             window: Rc::new(websys_window),
             settings,
+            context: RefCell::new(None),
+            fill_window: true,
+            resize_observer: None,
+            frame_input_generator: FrameInputGenerator::new(),
             closures: Vec::new(),
             closures_with_event: Vec::new(),
             closures_with_mouseevent: Vec::new(),
             closures_with_wheelevent: Vec::new(),
             closures_with_touchevent: Vec::new(),
             closures_with_keyboardevent: Vec::new(),
+            closures_with_dragevent: Vec::new(),
         };
-        if let Some(canvas) = document.get_elements_by_tag_name("canvas").item(0) {
-            window.set_canvas(
+        if let Some(id) = window.settings.canvas_id.clone() {
+            window.set_canvas_by_id(&id)?;
+        } else if let Some(canvas) = document.get_elements_by_tag_name("canvas").item(0) {
+            window.use_canvas(
+                true,
                 canvas
                     .dyn_into::<web_sys::HtmlCanvasElement>()
                     .map_err(|e| WindowError::WindowCreationError {
@@ -78,9 +93,38 @@ impl Window {
 
     ///
     /// Specifies the canvas to write to when using [Screen](crate::Screen). Will overwrite the default canvas if any has been found.
+    /// The canvas keeps the size given by the page layout and the framebuffer is sized to match it in physical pixels.
     ///
     pub fn set_canvas(&mut self, canvas: web_sys::HtmlCanvasElement) -> Result<(), WindowError> {
+        self.use_canvas(false, canvas)
+    }
+
+    ///
+    /// Specifies the canvas with the given element id to write to when using [Screen](crate::Screen), see [set_canvas](Self::set_canvas).
+    ///
+    pub fn set_canvas_by_id(&mut self, id: &str) -> Result<(), WindowError> {
+        let canvas = self
+            .window
+            .document()
+            .and_then(|document| document.get_element_by_id(id))
+            .ok_or(WindowError::CanvasError {
+                message: format!("Could not find a canvas with the id {}.", id),
+            })?
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .map_err(|e| WindowError::CanvasError {
+                message: format!("The element with the id {} is not a canvas: {:?}", id, e),
+            })?;
+        self.set_canvas(canvas)
+    }
+
+    fn use_canvas(
+        &mut self,
+        fill_window: bool,
+        canvas: web_sys::HtmlCanvasElement,
+    ) -> Result<(), WindowError> {
         self.canvas = Some(canvas);
+        self.fill_window = fill_window;
+        *self.context.get_mut() = None;
         self.set_canvas_size()?;
         Ok(())
     }
@@ -97,9 +141,16 @@ impl Window {
         Ok(crate::Viewport::new_at_origo(w, h))
     }
 
+    ///
+    /// Returns the graphics context for the canvas. The context is created the first time this is called and then shared.
+    ///
     pub fn gl(&self) -> Result<crate::Context, WindowError> {
+        if let Some(context) = self.context.borrow().as_ref() {
+            return Ok(context.clone());
+        }
         let context_options = ContextOptions {
             antialias: self.settings.multisamples > 0,
+            stencil: true,
         };
         let context = self.canvas.as_ref().ok_or(WindowError::CanvasError {message: "Could not find a canvas.".to_string()})?
             .get_context_with_context_options("webgl2", &JsValue::from_serde(&context_options).unwrap())
@@ -108,7 +159,71 @@ impl Window {
             .dyn_into::<WebGl2RenderingContext>().map_err(|e| WindowError::ContextError {message: format!("Unable to get webgl2 context for the given canvas. Maybe your browser doesn't support WebGL2? Error code: {:?}", e)})?;
         context.get_extension("EXT_color_buffer_float").map_err(|e| WindowError::ContextError {message: format!("Unable to get EXT_color_buffer_float extension for the given context. Maybe your browser doesn't support the get color_buffer_float extension? Error code: {:?}", e)})?;
         context.get_extension("OES_texture_float").map_err(|e| WindowError::ContextError {message: format!("Unable to get OES_texture_float extension for the given context. Maybe your browser doesn't support the get OES_texture_float extension? Error code: {:?}", e)})?;
-        Ok(Context::new(context))
+        let context = Context::new(context);
+        *self.context.borrow_mut() = Some(context.clone());
+        Ok(context)
+    }
+
+    ///
+    /// Returns a sender which can be used to send external data to the [payload](FrameInput::payload) of the frame input.
+    ///
+    pub fn payload_sender(&self) -> PayloadSender {
+        self.frame_input_generator.payload_sender()
+    }
+
+    ///
+    /// Sets the icon of the web page (the favicon) from the given texture with the top row first.
+    ///
+    pub fn set_icon(&self, icon: &CPUTexture<u8>) -> Result<(), WindowError> {
+        let url = data_url(&rgba_from_texture(icon), icon.width, icon.height)?;
+        let error = |e: JsValue| WindowError::WindowCreationError {
+            message: format!("Unable to set the icon. Error code: {:?}", e),
+        };
+        let document = self.window.document().ok_or(WindowError::WindowCreationError {
+            message: "Unable to get document".to_string(),
+        })?;
+        let link = if let Some(link) = document.query_selector("link[rel~='icon']").map_err(error)? {
+            link
+        } else {
+            let link = document.create_element("link").map_err(error)?;
+            link.set_attribute("rel", "icon").map_err(error)?;
+            document
+                .query_selector("head")
+                .map_err(error)?
+                .ok_or(WindowError::WindowCreationError {
+                    message: "Unable to find the head element of the document".to_string(),
+                })?
+                .append_child(&link)
+                .map_err(error)?;
+            link
+        };
+        link.set_attribute("href", &url).map_err(error)
+    }
+
+    ///
+    /// Sets the mouse cursor shown when the mouse is over the canvas using the CSS cursor property.
+    /// Use [FrameOutput::cursor] to change the cursor in the render loop.
+    ///
+    pub fn set_cursor(&self, cursor: &Cursor) -> Result<(), WindowError> {
+        set_cursor(self.canvas()?, cursor)
+    }
+
+    ///
+    /// Shows the canvas in fullscreen using the fullscreen API or leaves fullscreen.
+    /// The browser only allows entering fullscreen shortly after user input, for example a mouse click.
+    /// Use [FrameOutput::fullscreen] to change it in the render loop.
+    ///
+    pub fn set_fullscreen(&self, fullscreen: bool) -> Result<(), WindowError> {
+        set_fullscreen(&self.window, self.canvas()?, fullscreen)
+    }
+
+    ///
+    /// Locks the mouse pointer to the canvas using the pointer lock API or releases it.
+    /// The browser only allows locking the pointer shortly after user input, for example a mouse click.
+    /// Use [FrameOutput::cursor_grab] to change it in the render loop.
+    ///
+    pub fn set_cursor_grab(&self, grab: bool) -> Result<(), WindowError> {
+        set_cursor_grab(&self.window, self.canvas()?, grab)
     }
 
     pub fn render_loop<F: 'static>(mut self, mut callback: F) -> Result<(), WindowError>
@@ -121,9 +236,12 @@ impl Window {
             .ok_or(WindowError::PerformanceError {
                 message: "Performance (for timing) is not found on the window.".to_string(),
             })?;
-        let mut last_time = performance.now();
-        let mut accumulated_time = 0.0;
-        let mut first_frame = true;
+        let mut frame_input_generator = std::mem::take(&mut self.frame_input_generator);
+        frame_input_generator.set_fixed_time_step(self.settings.fixed_time_step());
+        frame_input_generator.set_log_stats(self.settings.log_frame_stats);
+        let min_frame_time = self.settings.min_frame_time();
+        let mut last_frame_time: Option<f64> = None;
+        let mut frame_globals = crate::FrameGlobals::new(&self.gl()?).unwrap();
 
         let input = Input::new(self.window.clone());
         self.add_context_menu_event_listener()?;
@@ -139,39 +257,59 @@ impl Window {
         self.add_touchmove_event_listener(input.clone())?;
         self.add_key_down_event_listener(input.clone())?;
         self.add_key_up_event_listener(input.clone())?;
+        self.add_drag_event_listeners(input.clone())?;
+        self.add_drop_event_listener(input.clone())?;
+        self.add_clipboard_event_listeners(input.clone())?;
+        self.add_composition_event_listeners(input.clone())?;
+        self.add_fullscreen_and_pointer_lock_event_listeners(input.clone())?;
+        self.add_focus_event_listeners(input.clone())?;
+        self.add_visibility_event_listener(input.clone())?;
 
         let input_clone = input.clone();
         input.borrow_mut().render_loop_closure = Some(Closure::wrap(Box::new(move || {
+            if let Some(min_frame_time) = min_frame_time {
+                // Skip this animation frame if it is too soon to render a new frame,
+                // allowing for a small tolerance since animation frames are synchronized with the display
+                let now = performance.now();
+                if let Some(last) = last_frame_time {
+                    if now - last < min_frame_time - 1.0 {
+                        input_clone.borrow_mut().request_animation_frame();
+                        return;
+                    }
+                }
+                last_frame_time = Some(now);
+            }
             let events = input_clone.borrow_mut().start_frame();
-            let now = performance.now();
-            let elapsed_time = now - last_time;
-            last_time = now;
-            accumulated_time += elapsed_time;
             self.set_canvas_size().unwrap();
             let device_pixel_ratio = self.pixels_per_point();
             let canvas = self.canvas.as_ref().unwrap();
-            let (width, height) = (
-                (canvas.width() as f64 / device_pixel_ratio) as u32,
-                (canvas.height() as f64 / device_pixel_ratio) as u32,
-            );
-            let frame_input = crate::FrameInput {
+            let frame_input = frame_input_generator.generate(
                 events,
-                elapsed_time,
-                accumulated_time,
-                viewport: crate::Viewport::new_at_origo(
-                    (device_pixel_ratio * width as f64) as u32,
-                    (device_pixel_ratio * height as f64) as u32,
-                ),
-                window_width: width,
-                window_height: height,
+                canvas.width(),
+                canvas.height(),
                 device_pixel_ratio,
-                first_frame: first_frame,
-            };
-            first_frame = false;
+            );
+            frame_globals.update(&frame_input).unwrap();
             let frame_output = callback(frame_input);
+            if let Some(ref cursor) = frame_output.cursor {
+                if let Err(e) = set_cursor(canvas, cursor) {
+                    log::warn!("{:?}", e);
+                }
+            }
+            if let Some(fullscreen) = frame_output.fullscreen {
+                if let Err(e) = set_fullscreen(&self.window, canvas, fullscreen) {
+                    log::warn!("{:?}", e);
+                }
+            }
+            if let Some(grab) = frame_output.cursor_grab {
+                if let Err(e) = set_cursor_grab(&self.window, canvas, grab) {
+                    log::warn!("{:?}", e);
+                }
+            }
 
-            if !frame_output.wait_next_event {
-                input_clone.borrow_mut().request_animation_frame();
+            let mut input = input_clone.borrow_mut();
+            if !frame_output.wait_next_event && !input.paused {
+                input.request_animation_frame();
             }
         })
             as Box<dyn FnMut()>));
@@ -192,6 +330,14 @@ impl Window {
         let canvas = self.canvas.as_ref().ok_or(WindowError::CanvasError {
             message: "Could not find a canvas.".to_string(),
         })?;
+        let device_pixel_ratio = self.pixels_per_point();
+        if !self.fill_window {
+            // Keep the size given by the page layout, unless the canvas is not laid out (for example hidden)
+            let width = u32::max(canvas.client_width().max(0) as u32, self.settings.min_size.0);
+            let height = u32::max(canvas.client_height().max(0) as u32, self.settings.min_size.1);
+            set_framebuffer_size(canvas, device_pixel_ratio, width, height);
+            return Ok(());
+        }
         let (window_width, window_height) = (
             self.window.inner_width().unwrap().as_f64().unwrap() as u32,
             self.window.inner_height().unwrap().as_f64().unwrap() as u32,
@@ -232,12 +378,37 @@ impl Window {
         }
 
         canvas.style().set_css_text(&style);
-        let device_pixel_ratio = self.pixels_per_point();
-        canvas.set_width((device_pixel_ratio * width as f64) as u32);
-        canvas.set_height((device_pixel_ratio * height as f64) as u32);
+        set_framebuffer_size(canvas, device_pixel_ratio, width, height);
         Ok(())
     }
 
+    ///
+    /// Returns the target for keyboard events, which is the document when filling the browser window,
+    /// otherwise the canvas is made focusable and keyboard events are only received when it has focus,
+    /// so several canvases in one page do not receive the same keyboard events.
+    ///
+    fn keyboard_event_target(&self) -> Result<web_sys::EventTarget, WindowError> {
+        if self.fill_window {
+            Ok(self
+                .window
+                .document()
+                .ok_or(WindowError::EventListenerError {
+                    message: "Unable to get document".to_string(),
+                })?
+                .into())
+        } else {
+            let canvas = self.canvas()?;
+            if !canvas.has_attribute("tabindex") {
+                canvas
+                    .set_attribute("tabindex", "0")
+                    .map_err(|e| WindowError::EventListenerError {
+                        message: format!("Unable to make the canvas focusable. Error code: {:?}", e),
+                    })?;
+            }
+            Ok(canvas.clone().into())
+        }
+    }
+
     fn add_context_menu_event_listener(&mut self) -> Result<(), WindowError> {
         let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
             event.prevent_default();
@@ -250,15 +421,30 @@ impl Window {
     }
 
     fn add_resize_event_listener(&mut self, input: Rc<RefCell<Input>>) -> Result<(), WindowError> {
+        // The browser window is resized, which also happens when the device pixel ratio changes, for example when zooming
+        let input_clone = input.clone();
         let closure = Closure::wrap(Box::new(move || {
-            input.borrow_mut().request_animation_frame();
+            input_clone.borrow_mut().request_animation_frame();
         }) as Box<dyn FnMut()>);
-        self.canvas()?
+        self.window
             .add_event_listener_with_callback("resize", closure.as_ref().unchecked_ref())
             .map_err(|e| WindowError::EventListenerError {
                 message: format!("Unable to add resize event listener. Error code: {:?}", e),
             })?;
         self.closures.push(closure);
+
+        // The canvas element is resized by the page layout
+        let closure = Closure::wrap(Box::new(move || {
+            input.borrow_mut().request_animation_frame();
+        }) as Box<dyn FnMut()>);
+        let observer = web_sys::ResizeObserver::new(closure.as_ref().unchecked_ref()).map_err(
+            |e| WindowError::EventListenerError {
+                message: format!("Unable to create resize observer. Error code: {:?}", e),
+            },
+        )?;
+        observer.observe(self.canvas()?);
+        self.resize_observer = Some(observer);
+        self.closures.push(closure);
         Ok(())
     }
 
@@ -397,19 +583,30 @@ impl Window {
         let closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
             if !event.default_prevented() {
                 let mut input = input.borrow_mut();
-                let delta = if let Some((x, y)) = input.last_position {
-                    ((event.offset_x() - x) as f64, (event.offset_y() - y) as f64)
-                } else {
-                    (0.0, 0.0)
-                };
                 let modifiers = input.modifiers;
-                input.events.push(Event::MouseMotion {
-                    delta,
-                    position: (event.offset_x() as f64, event.offset_y() as f64),
-                    modifiers,
-                    handled: false,
-                });
-                input.last_position = Some((event.offset_x(), event.offset_y()));
+                if input.pointer_locked {
+                    // The pointer does not move while it is locked, so only the movement is reported
+                    let position = input.last_position.map_or((0.0, 0.0), |(x, y)| (x as f64, y as f64));
+                    input.events.push(Event::MouseMotion {
+                        delta: (event.movement_x() as f64, event.movement_y() as f64),
+                        position,
+                        modifiers,
+                        handled: false,
+                    });
+                } else {
+                    let delta = if let Some((x, y)) = input.last_position {
+                        ((event.offset_x() - x) as f64, (event.offset_y() - y) as f64)
+                    } else {
+                        (0.0, 0.0)
+                    };
+                    input.events.push(Event::MouseMotion {
+                        delta,
+                        position: (event.offset_x() as f64, event.offset_y() as f64),
+                        modifiers,
+                        handled: false,
+                    });
+                    input.last_position = Some((event.offset_x(), event.offset_y()));
+                }
                 event.stop_propagation();
                 event.prevent_default();
 
@@ -456,6 +653,226 @@ impl Window {
         Ok(())
     }
 
+    fn add_drag_event_listeners(&mut self, input: Rc<RefCell<Input>>) -> Result<(), WindowError> {
+        for (name, event_type) in [
+            ("dragenter", Some(Event::FileHovered { path: None })),
+            ("dragover", None),
+            ("dragleave", Some(Event::FileHoverCancelled)),
+        ]
+        .iter()
+        .cloned()
+        {
+            let input = input.clone();
+            let closure = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+                // Preventing the default behaviour is needed for the canvas to accept the drop
+                event.stop_propagation();
+                event.prevent_default();
+                if let Some(ref event_type) = event_type {
+                    let mut input = input.borrow_mut();
+                    input.events.push(event_type.clone());
+                    input.request_animation_frame();
+                }
+            }) as Box<dyn FnMut(_)>);
+            self.canvas()?
+                .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                .map_err(|e| WindowError::EventListenerError {
+                    message: format!("Unable to add {} event listener. Error code: {:?}", name, e),
+                })?;
+            self.closures_with_dragevent.push(closure);
+        }
+        Ok(())
+    }
+
+    fn add_drop_event_listener(&mut self, input: Rc<RefCell<Input>>) -> Result<(), WindowError> {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+            event.stop_propagation();
+            event.prevent_default();
+            if let Some(files) = event.data_transfer().and_then(|d| d.files()) {
+                for i in 0..files.length() {
+                    if let Some(file) = files.get(i) {
+                        let input = input.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            let name = file.name();
+                            if let Ok(buffer) =
+                                wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await
+                            {
+                                let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+                                let mut input = input.borrow_mut();
+                                input.events.push(Event::DroppedFileData { name, bytes });
+                                input.request_animation_frame();
+                            }
+                        });
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+        self.canvas()?
+            .add_event_listener_with_callback("drop", closure.as_ref().unchecked_ref())
+            .map_err(|e| WindowError::EventListenerError {
+                message: format!("Unable to add drop event listener. Error code: {:?}", e),
+            })?;
+        self.closures_with_dragevent.push(closure);
+        Ok(())
+    }
+
+    fn add_clipboard_event_listeners(
+        &mut self,
+        input: Rc<RefCell<Input>>,
+    ) -> Result<(), WindowError> {
+        for name in ["copy", "cut", "paste"].iter() {
+            let input = input.clone();
+            let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+                if let Some(event) = event.dyn_ref::<web_sys::ClipboardEvent>() {
+                    let clipboard_event = match event.type_().as_str() {
+                        "copy" => Some(Event::Copy),
+                        "cut" => Some(Event::Cut),
+                        _ => event
+                            .clipboard_data()
+                            .and_then(|data| data.get_data("text/plain").ok())
+                            .map(Event::Paste),
+                    };
+                    if let Some(clipboard_event) = clipboard_event {
+                        let mut input = input.borrow_mut();
+                        input.events.push(clipboard_event);
+                        event.stop_propagation();
+                        event.prevent_default();
+                        input.request_animation_frame();
+                    }
+                }
+            }) as Box<dyn FnMut(_)>);
+            self.window
+                .document()
+                .unwrap()
+                .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                .map_err(|e| WindowError::EventListenerError {
+                    message: format!("Unable to add {} event listener. Error code: {:?}", name, e),
+                })?;
+            self.closures_with_event.push(closure);
+        }
+        Ok(())
+    }
+
+    fn add_composition_event_listeners(
+        &mut self,
+        input: Rc<RefCell<Input>>,
+    ) -> Result<(), WindowError> {
+        for name in ["compositionstart", "compositionupdate", "compositionend"].iter() {
+            let input = input.clone();
+            let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+                if let Some(event) = event.dyn_ref::<web_sys::CompositionEvent>() {
+                    let text = event.data().unwrap_or_default();
+                    let composition_event = match event.type_().as_str() {
+                        "compositionstart" => Event::CompositionStart,
+                        "compositionupdate" => Event::CompositionUpdate(text),
+                        _ => Event::CompositionEnd(text),
+                    };
+                    let mut input = input.borrow_mut();
+                    input.events.push(composition_event);
+                    input.request_animation_frame();
+                }
+            }) as Box<dyn FnMut(_)>);
+            self.window
+                .document()
+                .unwrap()
+                .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                .map_err(|e| WindowError::EventListenerError {
+                    message: format!("Unable to add {} event listener. Error code: {:?}", name, e),
+                })?;
+            self.closures_with_event.push(closure);
+        }
+        Ok(())
+    }
+
+    fn add_fullscreen_and_pointer_lock_event_listeners(
+        &mut self,
+        input: Rc<RefCell<Input>>,
+    ) -> Result<(), WindowError> {
+        let document = self.window.document().ok_or(WindowError::EventListenerError {
+            message: "Unable to get document".to_string(),
+        })?;
+        for name in ["fullscreenchange", "pointerlockchange"].iter() {
+            let input = input.clone();
+            let canvas: web_sys::Element = self.canvas()?.clone().into();
+            let document_clone = document.clone();
+            let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+                let mut input = input.borrow_mut();
+                if event.type_() == "fullscreenchange" {
+                    let fullscreen = document_clone.fullscreen_element().is_some();
+                    input.events.push(Event::FullscreenChange(fullscreen));
+                } else {
+                    let locked = document_clone.pointer_lock_element().map_or(false, |element| element == canvas);
+                    if locked != input.pointer_locked {
+                        input.pointer_locked = locked;
+                        input.events.push(Event::CursorGrabChange(locked));
+                    }
+                }
+                input.request_animation_frame();
+            }) as Box<dyn FnMut(_)>);
+            document
+                .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                .map_err(|e| WindowError::EventListenerError {
+                    message: format!("Unable to add {} event listener. Error code: {:?}", name, e),
+                })?;
+            self.closures_with_event.push(closure);
+        }
+        Ok(())
+    }
+
+    fn add_focus_event_listeners(&mut self, input: Rc<RefCell<Input>>) -> Result<(), WindowError> {
+        // The browser window has focus when filling the window, otherwise the focusable canvas
+        let target: web_sys::EventTarget = if self.fill_window {
+            (*self.window).clone().into()
+        } else {
+            self.keyboard_event_target()?
+        };
+        for name in ["focus", "blur"].iter() {
+            let input = input.clone();
+            let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+                let mut input = input.borrow_mut();
+                input.events.push(if event.type_() == "focus" {
+                    Event::Focused
+                } else {
+                    Event::Unfocused
+                });
+                input.request_animation_frame();
+            }) as Box<dyn FnMut(_)>);
+            target
+                .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                .map_err(|e| WindowError::EventListenerError {
+                    message: format!("Unable to add {} event listener. Error code: {:?}", name, e),
+                })?;
+            self.closures_with_event.push(closure);
+        }
+        Ok(())
+    }
+
+    fn add_visibility_event_listener(&mut self, input: Rc<RefCell<Input>>) -> Result<(), WindowError> {
+        let document = self.window.document().ok_or(WindowError::EventListenerError {
+            message: "Unable to get document".to_string(),
+        })?;
+        let pause_when_hidden = self.settings.pause_when_hidden;
+        let document_clone = document.clone();
+        let closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
+            let mut input = input.borrow_mut();
+            let hidden = document_clone.hidden();
+            input.paused = hidden && pause_when_hidden;
+            input.events.push(if hidden {
+                Event::Suspended
+            } else {
+                Event::Resumed
+            });
+            // Render one more frame when hidden so the suspended event is received before pausing
+            input.request_animation_frame();
+        }) as Box<dyn FnMut(_)>);
+        document
+            .add_event_listener_with_callback("visibilitychange", closure.as_ref().unchecked_ref())
+            .map_err(|e| WindowError::EventListenerError {
+                message: format!("Unable to add visibility change event listener. Error code: {:?}", e),
+            })?;
+        self.closures_with_event.push(closure);
+        Ok(())
+    }
+
     fn add_touchstart_event_listener(
         &mut self,
         input: Rc<RefCell<Input>>,
@@ -628,8 +1045,13 @@ impl Window {
                         modifiers,
                         handled: false,
                     });
-                    event.stop_propagation();
-                    event.prevent_default();
+                    // The default behaviour of the copy, cut and paste shortcuts is needed to receive the clipboard events
+                    let is_clipboard_shortcut = modifiers.command == State::Pressed
+                        && (kind == Key::C || kind == Key::X || kind == Key::V);
+                    if !is_clipboard_shortcut {
+                        event.stop_propagation();
+                        event.prevent_default();
+                    }
                 }
                 if modifiers.ctrl == State::Released
                     && modifiers.command == State::Released
@@ -643,10 +1065,7 @@ impl Window {
                 }
             }
         }) as Box<dyn FnMut(_)>);
-        web_sys::window()
-            .unwrap()
-            .document()
-            .unwrap()
+        self.keyboard_event_target()?
             .add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())
             .map_err(|e| WindowError::EventListenerError {
                 message: format!("Unable to add key down event listener. Error code: {:?}", e),
@@ -680,10 +1099,7 @@ impl Window {
                 }
             }
         }) as Box<dyn FnMut(_)>);
-        web_sys::window()
-            .unwrap()
-            .document()
-            .unwrap()
+        self.keyboard_event_target()?
             .add_event_listener_with_callback("keyup", closure.as_ref().unchecked_ref())
             .map_err(|e| WindowError::EventListenerError {
                 message: format!("Unable to add key up event listener. Error code: {:?}", e),
@@ -696,6 +1112,136 @@ impl Window {
 #[derive(Serialize)]
 struct ContextOptions {
     antialias: bool,
+    stencil: bool,
+}
+
+///
+/// Sets the size of the framebuffer of the canvas in physical pixels given the size in logical (CSS) pixels.
+/// The size is only set when it changes, since setting it clears the canvas.
+///
+fn set_framebuffer_size(
+    canvas: &web_sys::HtmlCanvasElement,
+    device_pixel_ratio: f64,
+    width: u32,
+    height: u32,
+) {
+    let physical_width = u32::max(1, (device_pixel_ratio * width as f64).round() as u32);
+    let physical_height = u32::max(1, (device_pixel_ratio * height as f64).round() as u32);
+    if canvas.width() != physical_width {
+        canvas.set_width(physical_width);
+    }
+    if canvas.height() != physical_height {
+        canvas.set_height(physical_height);
+    }
+}
+
+fn document(window: &web_sys::Window) -> Result<web_sys::Document, WindowError> {
+    window.document().ok_or(WindowError::CanvasError {
+        message: "Unable to get document".to_string(),
+    })
+}
+
+fn set_fullscreen(
+    window: &web_sys::Window,
+    canvas: &web_sys::HtmlCanvasElement,
+    fullscreen: bool,
+) -> Result<(), WindowError> {
+    let document = document(window)?;
+    if fullscreen && document.fullscreen_element().is_none() {
+        canvas.request_fullscreen().map_err(|e| WindowError::CanvasError {
+            message: format!("Unable to enter fullscreen. Error code: {:?}", e),
+        })?;
+    } else if !fullscreen && document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+    }
+    Ok(())
+}
+
+fn set_cursor_grab(
+    window: &web_sys::Window,
+    canvas: &web_sys::HtmlCanvasElement,
+    grab: bool,
+) -> Result<(), WindowError> {
+    let document = document(window)?;
+    if grab && document.pointer_lock_element().is_none() {
+        canvas.request_pointer_lock();
+    } else if !grab && document.pointer_lock_element().is_some() {
+        document.exit_pointer_lock();
+    }
+    Ok(())
+}
+
+fn set_cursor(canvas: &web_sys::HtmlCanvasElement, cursor: &Cursor) -> Result<(), WindowError> {
+    let value = match cursor {
+        Cursor::Default => "default".to_string(),
+        Cursor::Pointer => "pointer".to_string(),
+        Cursor::Crosshair => "crosshair".to_string(),
+        Cursor::Text => "text".to_string(),
+        Cursor::Move => "move".to_string(),
+        Cursor::Wait => "wait".to_string(),
+        Cursor::NotAllowed => "not-allowed".to_string(),
+        Cursor::Grab => "grab".to_string(),
+        Cursor::Grabbing => "grabbing".to_string(),
+        Cursor::ResizeHorizontal => "ew-resize".to_string(),
+        Cursor::ResizeVertical => "ns-resize".to_string(),
+        Cursor::ResizeDiagonal => "nwse-resize".to_string(),
+        Cursor::ResizeAntiDiagonal => "nesw-resize".to_string(),
+        Cursor::Hidden => "none".to_string(),
+        Cursor::Custom {
+            rgba,
+            width,
+            height,
+            hotspot,
+        } => format!(
+            "url({}) {} {}, auto",
+            data_url(rgba, *width, *height)?,
+            hotspot.0,
+            hotspot.1
+        ),
+    };
+    canvas
+        .style()
+        .set_property("cursor", &value)
+        .map_err(|e| WindowError::CanvasError {
+            message: format!("Unable to set the cursor. Error code: {:?}", e),
+        })
+}
+
+///
+/// Encodes the given RGBA pixels with the top row first as a PNG data URL by drawing them to a temporary canvas.
+///
+fn data_url(rgba: &[u8], width: u32, height: u32) -> Result<String, WindowError> {
+    let error = |e: JsValue| WindowError::CanvasError {
+        message: format!("Unable to encode the image. Error code: {:?}", e),
+    };
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or(WindowError::CanvasError {
+            message: "Unable to get document".to_string(),
+        })?;
+    let canvas = document
+        .create_element("canvas")
+        .map_err(error)?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .map_err(|e| error(e.into()))?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas
+        .get_context("2d")
+        .map_err(error)?
+        .ok_or(WindowError::CanvasError {
+            message: "Unable to get a 2D context for encoding the image".to_string(),
+        })?
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .map_err(|e| error(e.into()))?;
+    let image = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+        wasm_bindgen::Clamped(rgba),
+        width,
+        height,
+    )
+    .map_err(error)?;
+    context.put_image_data(&image, 0.0, 0.0).map_err(error)?;
+    canvas.to_data_url().map_err(error)
 }
 
 struct Input {
@@ -706,6 +1252,8 @@ struct Input {
     modifiers: Modifiers,
     last_position: Option<(i32, i32)>,
     last_zoom: Option<f64>,
+    pointer_locked: bool,
+    paused: bool,
 }
 
 impl Input {
@@ -718,6 +1266,8 @@ impl Input {
             modifiers: Modifiers::default(),
             last_position: None,
             last_zoom: None,
+            pointer_locked: false,
+            paused: false,
         }))
     }
 
@@ -0,0 +1,75 @@
+use crate::window::WindowError;
+
+///
+/// Access to the system clipboard.
+/// Text pasted by the user is received as [Paste](crate::Event::Paste) events,
+/// while text that should be copied in response to [Copy](crate::Event::Copy) and [Cut](crate::Event::Cut) events is written using this clipboard.
+///
+pub struct Clipboard {
+    #[cfg(not(target_arch = "wasm32"))]
+    context: copypasta::ClipboardContext,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Clipboard {
+    pub fn new() -> Result<Self, WindowError> {
+        Ok(Self {
+            context: copypasta::ClipboardContext::new()
+                .map_err(|e| WindowError::ClipboardError(e.to_string()))?,
+        })
+    }
+
+    ///
+    /// Returns the text currently in the clipboard or None if the clipboard does not contain text.
+    /// Only available on desktop, on web the clipboard can only be read when the user pastes.
+    ///
+    pub fn text(&mut self) -> Option<String> {
+        use copypasta::ClipboardProvider;
+        self.context.get_contents().ok()
+    }
+
+    ///
+    /// Writes the given text to the clipboard.
+    ///
+    pub fn set_text(&mut self, text: &str) -> Result<(), WindowError> {
+        use copypasta::ClipboardProvider;
+        self.context
+            .set_contents(text.to_owned())
+            .map_err(|e| WindowError::ClipboardError(e.to_string()))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Clipboard {
+    pub fn new() -> Result<Self, WindowError> {
+        Ok(Self {})
+    }
+
+    ///
+    /// Writes the given text to the clipboard.
+    /// The text is written asynchronously and only if the browser allows it,
+    /// which is usually the case when called in response to a user interaction.
+    ///
+    pub fn set_text(&mut self, text: &str) -> Result<(), WindowError> {
+        use wasm_bindgen::{JsCast, JsValue};
+        let error = |e: JsValue| WindowError::ClipboardError {
+            message: format!("Unable to write to the clipboard. Error code: {:?}", e),
+        };
+        let navigator = web_sys::window()
+            .ok_or(WindowError::ClipboardError {
+                message: "Unable to get the web window.".to_string(),
+            })?
+            .navigator();
+        // The clipboard API is accessed through reflection since it is still unstable in web-sys
+        let clipboard =
+            js_sys::Reflect::get(&navigator, &JsValue::from_str("clipboard")).map_err(error)?;
+        let write_text = js_sys::Reflect::get(&clipboard, &JsValue::from_str("writeText"))
+            .map_err(error)?
+            .dyn_into::<js_sys::Function>()
+            .map_err(|e| error(e.into()))?;
+        write_text
+            .call1(&clipboard, &JsValue::from_str(text))
+            .map_err(error)?;
+        Ok(())
+    }
+}
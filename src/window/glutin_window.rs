@@ -1,8 +1,9 @@
+use crate::definition::*;
 use crate::frame::*;
 use crate::math::*;
 use crate::window::WindowSettings;
 use crate::Context;
-use glutin::event::{Event, WindowEvent};
+use glutin::event::{DeviceEvent, Event, WindowEvent};
 use glutin::event_loop::{ControlFlow, EventLoop};
 use glutin::window::WindowBuilder;
 use glutin::ContextBuilder;
@@ -19,6 +20,16 @@ pub enum WindowError {
     ContextError(glutin::ContextError),
     /// The number of samples must be a power of two.
     InvalidNumberOfSamples,
+    /// It is not possible to create a context for the given raw window handle on this platform.
+    UnsupportedWindowHandle,
+    /// Unable to access the system clipboard.
+    ClipboardError(String),
+    /// The given window icon is invalid.
+    InvalidIcon(String),
+    /// Unable to grab or release the mouse cursor.
+    CursorGrabError(String),
+    /// There is no window with the given index in the [MultiWindow](crate::MultiWindow).
+    InvalidWindowIndex(usize),
 }
 
 impl From<glutin::CreationError> for WindowError {
@@ -40,6 +51,8 @@ pub struct Window {
     windowed_context: ContextWrapper<PossiblyCurrent, window::Window>,
     event_loop: EventLoop<()>,
     gl: crate::Context,
+    settings: WindowSettings,
+    frame_input_generator: FrameInputGenerator,
 }
 
 impl Window {
@@ -62,6 +75,8 @@ impl Window {
             windowed_context,
             event_loop,
             gl,
+            settings,
+            frame_input_generator: FrameInputGenerator::new(),
         })
     }
 
@@ -72,91 +87,94 @@ impl Window {
         if settings.multisamples > 0 && !settings.multisamples.is_power_of_two() {
             return Err(WindowError::InvalidNumberOfSamples);
         }
-        let window_builder = if let Some((width, height)) = settings.max_size {
-            WindowBuilder::new()
-                .with_title(&settings.title)
-                .with_min_inner_size(dpi::LogicalSize::new(
-                    settings.min_size.0,
-                    settings.min_size.1,
-                ))
-                .with_inner_size(dpi::LogicalSize::new(width as f64, height as f64))
-                .with_max_inner_size(dpi::LogicalSize::new(width as f64, height as f64))
-        } else {
-            WindowBuilder::new()
-                .with_min_inner_size(dpi::LogicalSize::new(
-                    settings.min_size.0,
-                    settings.min_size.1,
-                ))
-                .with_title(&settings.title)
-                .with_maximized(true)
-        };
+        let window_builder = window_builder(settings);
 
         Ok(ContextBuilder::new()
             .with_multisampling(settings.multisamples as u16)
+            .with_stencil_buffer(8)
             .with_vsync(settings.vsync)
             .build_windowed(window_builder, event_loop)?)
     }
 
     ///
     /// Start the main render loop which calls the `callback` closure each frame.
+    /// When the render loop ends, the `callback` closure is dropped and in debug builds,
+    /// a warning is logged if any GPU resources are still alive, see [Context::check_for_leaks](crate::context::Context::check_for_leaks).
     ///
-    pub fn render_loop<F: 'static>(self, mut callback: F) -> Result<(), WindowError>
+    pub fn render_loop<F: 'static>(self, callback: F) -> Result<(), WindowError>
     where
         F: FnMut(FrameInput) -> FrameOutput,
     {
         let windowed_context = self.windowed_context;
-        let mut last_time = std::time::Instant::now();
-        let mut accumulated_time = 0.0;
-        let mut events = Vec::new();
-        let mut cursor_pos = None;
-        let mut modifiers = Modifiers::default();
-        let mut first_frame = true;
+        let mut translator = EventTranslator::default();
+        let mut frame_input_generator = self.frame_input_generator;
+        frame_input_generator.set_fixed_time_step(self.settings.fixed_time_step());
+        frame_input_generator.set_log_stats(self.settings.log_frame_stats);
+        let mut frame_limiter = FrameLimiter::new(self.settings.min_frame_time());
+        let mut wait_next_event = false;
+        let pause_when_hidden = self.settings.pause_when_hidden;
         let context = self.gl.clone();
-        self.event_loop.run(move |event, _, control_flow| {
-            match event {
+        // Kept in options, so that they can be dropped before checking for leaks when the loop ends
+        let mut callback = Some(callback);
+        let mut frame_globals = Some(crate::FrameGlobals::new(&context).unwrap());
+        self.event_loop
+            .run(move |event, _, control_flow| match event {
                 Event::LoopDestroyed => {
-                    return;
+                    callback = None;
+                    frame_globals = None;
+                    context.check_for_leaks();
                 }
+                Event::Suspended => translator.set_suspended(true),
+                Event::Resumed => translator.set_suspended(false),
                 Event::MainEventsCleared => {
-                    windowed_context.window().request_redraw();
+                    // When waiting for the next event, only redraw if an event actually arrived
+                    if !wait_next_event || translator.has_events() {
+                        if let Some(time) = frame_limiter.wait_until() {
+                            *control_flow = ControlFlow::WaitUntil(time);
+                        } else {
+                            windowed_context.window().request_redraw();
+                        }
+                    }
                 }
                 Event::RedrawRequested(_) => {
-                    let now = std::time::Instant::now();
-                    let duration = now.duration_since(last_time);
-                    last_time = now;
-                    let elapsed_time =
-                        duration.as_secs() as f64 * 1000.0 + duration.subsec_nanos() as f64 * 1e-6;
-                    accumulated_time += elapsed_time;
-
+                    frame_limiter.start_frame();
                     let (physical_width, physical_height): (u32, u32) =
                         windowed_context.window().inner_size().into();
-                    let device_pixel_ratio = windowed_context.window().scale_factor();
-                    let (width, height): (u32, u32) = windowed_context
-                        .window()
-                        .inner_size()
-                        .to_logical::<f64>(device_pixel_ratio)
-                        .into();
-                    let frame_input = FrameInput {
-                        events: events.clone(),
-                        elapsed_time,
-                        accumulated_time,
-                        viewport: crate::Viewport::new_at_origo(physical_width, physical_height),
-                        window_width: width,
-                        window_height: height,
-                        device_pixel_ratio: device_pixel_ratio,
-                        first_frame: first_frame,
-                    };
-                    first_frame = false;
-                    events.clear();
-                    let frame_output = callback(frame_input);
+                    let frame_input = frame_input_generator.generate(
+                        translator.take_events(),
+                        physical_width,
+                        physical_height,
+                        windowed_context.window().scale_factor(),
+                    );
+                    frame_globals.as_mut().unwrap().update(&frame_input).unwrap();
+                    let frame_output = callback.as_mut().unwrap()(frame_input);
                     if frame_output.exit {
                         *control_flow = ControlFlow::Exit;
                     } else {
                         if frame_output.swap_buffers {
                             windowed_context.swap_buffers().unwrap();
                         }
-                        if frame_output.wait_next_event {
+                        if let Some((x, y)) = frame_output.ime_position {
+                            windowed_context
+                                .window()
+                                .set_ime_position(dpi::LogicalPosition::new(x, y));
+                        }
+                        if let Some(ref cursor) = frame_output.cursor {
+                            set_cursor(windowed_context.window(), cursor);
+                        }
+                        if let Some(fullscreen) = frame_output.fullscreen {
+                            set_fullscreen(windowed_context.window(), fullscreen);
+                            translator.update_fullscreen(windowed_context.window());
+                        }
+                        if let Some(grab) = frame_output.cursor_grab {
+                            translator.set_cursor_grab(windowed_context.window(), grab);
+                        }
+                        wait_next_event = frame_output.wait_next_event
+                            || (pause_when_hidden && translator.is_suspended());
+                        if wait_next_event {
                             *control_flow = ControlFlow::Wait;
+                        } else if let Some(time) = frame_limiter.wait_until() {
+                            *control_flow = ControlFlow::WaitUntil(time);
                         } else {
                             *control_flow = ControlFlow::Poll;
                             windowed_context.window().request_redraw();
@@ -168,146 +186,35 @@ impl Window {
                             crate::Viewport::new_at_origo(physical_width, physical_height),
                         )
                         .unwrap();
+                        #[cfg(feature = "image-io")]
                         crate::Saver::save_pixels(path, &pixels, physical_width, physical_height)
                             .unwrap();
+                        #[cfg(not(feature = "image-io"))]
+                        log::warn!(
+                            "Cannot save the screenshot {:?} with {} bytes since the image-io feature is disabled",
+                            path,
+                            pixels.len()
+                        );
                     }
                 }
                 Event::WindowEvent { ref event, .. } => match event {
                     WindowEvent::Resized(physical_size) => {
+                        translator
+                            .set_minimized(physical_size.width == 0 || physical_size.height == 0);
                         windowed_context.resize(*physical_size);
+                        translator.update_fullscreen(windowed_context.window());
+                        windowed_context.window().request_redraw();
                     }
-                    WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                    WindowEvent::KeyboardInput { input, .. } => {
-                        if let Some(keycode) = input.virtual_keycode {
-                            use event::VirtualKeyCode;
-                            let state = if input.state == event::ElementState::Pressed {
-                                crate::State::Pressed
-                            } else {
-                                crate::State::Released
-                            };
-                            if let Some(kind) = translate_virtual_key_code(keycode) {
-                                events.push(crate::Event::Key {
-                                    state,
-                                    kind,
-                                    modifiers,
-                                    handled: false,
-                                });
-                            } else {
-                                if keycode == VirtualKeyCode::LControl
-                                    || keycode == VirtualKeyCode::RControl
-                                {
-                                    modifiers.ctrl = state;
-                                    if !cfg!(target_os = "macos") {
-                                        modifiers.command = state;
-                                    }
-                                    events.push(crate::Event::ModifiersChange { modifiers });
-                                } else if keycode == VirtualKeyCode::LAlt
-                                    || keycode == VirtualKeyCode::RAlt
-                                {
-                                    modifiers.alt = state;
-                                    events.push(crate::Event::ModifiersChange { modifiers });
-                                } else if keycode == VirtualKeyCode::LShift
-                                    || keycode == VirtualKeyCode::RShift
-                                {
-                                    modifiers.shift = state;
-                                    events.push(crate::Event::ModifiersChange { modifiers });
-                                } else if keycode == VirtualKeyCode::LWin
-                                    || keycode == VirtualKeyCode::RWin
-                                {
-                                    if cfg!(target_os = "macos") {
-                                        modifiers.command = state;
-                                        events.push(crate::Event::ModifiersChange { modifiers });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    WindowEvent::MouseWheel { delta, .. } => {
-                        if let Some(position) = cursor_pos {
-                            match delta {
-                                glutin::event::MouseScrollDelta::LineDelta(x, y) => {
-                                    let line_height = 24.0; // TODO
-                                    events.push(crate::Event::MouseWheel {
-                                        delta: (
-                                            (*x * line_height) as f64,
-                                            (*y * line_height) as f64,
-                                        ),
-                                        position,
-                                        modifiers,
-                                        handled: false,
-                                    });
-                                }
-                                glutin::event::MouseScrollDelta::PixelDelta(delta) => {
-                                    let d =
-                                        delta.to_logical(windowed_context.window().scale_factor());
-                                    events.push(crate::Event::MouseWheel {
-                                        delta: (d.x, d.y),
-                                        position,
-                                        modifiers,
-                                        handled: false,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                    WindowEvent::MouseInput { state, button, .. } => {
-                        if let Some(position) = cursor_pos {
-                            let state = if *state == event::ElementState::Pressed {
-                                crate::State::Pressed
-                            } else {
-                                crate::State::Released
-                            };
-                            let button = match button {
-                                event::MouseButton::Left => Some(crate::MouseButton::Left),
-                                event::MouseButton::Middle => Some(crate::MouseButton::Middle),
-                                event::MouseButton::Right => Some(crate::MouseButton::Right),
-                                _ => None,
-                            };
-                            if let Some(b) = button {
-                                events.push(crate::Event::MouseClick {
-                                    state,
-                                    button: b,
-                                    position,
-                                    modifiers,
-                                    handled: false,
-                                });
-                            }
-                        }
-                    }
-                    WindowEvent::CursorMoved { position, .. } => {
-                        let p = position.to_logical(windowed_context.window().scale_factor());
-                        let delta = if let Some(last_pos) = cursor_pos {
-                            (p.x - last_pos.0, p.y - last_pos.1)
-                        } else {
-                            (0.0, 0.0)
-                        };
-                        events.push(crate::Event::MouseMotion {
-                            delta,
-                            position: (p.x, p.y),
-                            modifiers,
-                            handled: false,
-                        });
-                        cursor_pos = Some((p.x, p.y));
-                    }
-                    WindowEvent::ReceivedCharacter(ch) => {
-                        if is_printable_char(*ch)
-                            && modifiers.ctrl != State::Pressed
-                            && modifiers.command != State::Pressed
-                        {
-                            events.push(crate::Event::Text(ch.to_string()));
-                        }
+                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                        windowed_context.resize(**new_inner_size);
+                        windowed_context.window().request_redraw();
                     }
-                    WindowEvent::CursorEntered { .. } => {
-                        events.push(crate::Event::MouseEnter);
-                    }
-                    WindowEvent::CursorLeft { .. } => {
-                        events.push(crate::Event::MouseLeave);
-                    }
-                    _ => (),
+                    WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                    _ => translator.translate(event, windowed_context.window().scale_factor()),
                 },
+                Event::DeviceEvent { ref event, .. } => translator.translate_device_event(event),
                 _ => (),
-            }
-        });
+            });
     }
 
     ///
@@ -336,6 +243,442 @@ impl Window {
     pub fn gl(&self) -> Result<crate::Context, WindowError> {
         Ok(self.gl.clone())
     }
+
+    ///
+    /// Returns a sender which can be used to send external data, for example from another thread, to the [payload](FrameInput::payload) of the frame input.
+    ///
+    pub fn payload_sender(&self) -> PayloadSender {
+        self.frame_input_generator.payload_sender()
+    }
+
+    ///
+    /// Sets the icon of the window, for example shown in the title bar or task bar, from the given texture with the top row first.
+    ///
+    pub fn set_icon(&self, icon: &CPUTexture<u8>) -> Result<(), WindowError> {
+        set_icon(self.windowed_context.window(), icon)
+    }
+
+    ///
+    /// Sets the mouse cursor shown when the mouse is over the window.
+    /// Use [FrameOutput::cursor] to change the cursor in the render loop.
+    ///
+    pub fn set_cursor(&self, cursor: &Cursor) {
+        set_cursor(self.windowed_context.window(), cursor);
+    }
+
+    ///
+    /// Enters or leaves borderless fullscreen on the current monitor.
+    /// Use [FrameOutput::fullscreen] to change it in the render loop.
+    ///
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        set_fullscreen(self.windowed_context.window(), fullscreen);
+    }
+
+    ///
+    /// Grabs and hides the mouse cursor or releases it.
+    /// Use [FrameOutput::cursor_grab] to change it in the render loop, which also makes the mouse motion events report the raw mouse movement.
+    ///
+    pub fn set_cursor_grab(&self, grab: bool) -> Result<(), WindowError> {
+        set_cursor_grab(self.windowed_context.window(), grab)
+    }
+}
+
+pub(super) fn set_fullscreen(window: &window::Window, fullscreen: bool) {
+    window.set_fullscreen(if fullscreen {
+        Some(window::Fullscreen::Borderless(window.current_monitor()))
+    } else {
+        None
+    });
+}
+
+pub(super) fn set_cursor_grab(window: &window::Window, grab: bool) -> Result<(), WindowError> {
+    window
+        .set_cursor_grab(grab)
+        .map_err(|e| WindowError::CursorGrabError(e.to_string()))?;
+    window.set_cursor_visible(!grab);
+    Ok(())
+}
+
+pub(super) fn set_icon(window: &window::Window, icon: &CPUTexture<u8>) -> Result<(), WindowError> {
+    let icon = window::Icon::from_rgba(rgba_from_texture(icon), icon.width, icon.height)
+        .map_err(|e| WindowError::InvalidIcon(e.to_string()))?;
+    window.set_window_icon(Some(icon));
+    Ok(())
+}
+
+pub(super) fn set_cursor(window: &window::Window, cursor: &Cursor) {
+    use window::CursorIcon;
+    window.set_cursor_visible(*cursor != Cursor::Hidden);
+    window.set_cursor_icon(match cursor {
+        Cursor::Default | Cursor::Hidden => CursorIcon::Default,
+        Cursor::Pointer => CursorIcon::Hand,
+        Cursor::Crosshair => CursorIcon::Crosshair,
+        Cursor::Text => CursorIcon::Text,
+        Cursor::Move => CursorIcon::Move,
+        Cursor::Wait => CursorIcon::Wait,
+        Cursor::NotAllowed => CursorIcon::NotAllowed,
+        Cursor::Grab => CursorIcon::Grab,
+        Cursor::Grabbing => CursorIcon::Grabbing,
+        Cursor::ResizeHorizontal => CursorIcon::EwResize,
+        Cursor::ResizeVertical => CursorIcon::NsResize,
+        Cursor::ResizeDiagonal => CursorIcon::NwseResize,
+        Cursor::ResizeAntiDiagonal => CursorIcon::NeswResize,
+        Cursor::Custom { .. } => {
+            log::warn!(
+                "Custom cursor images are not supported on desktop, using the default cursor"
+            );
+            CursorIcon::Default
+        }
+    });
+}
+
+pub(super) fn window_builder(settings: &WindowSettings) -> WindowBuilder {
+    if let Some((width, height)) = settings.max_size {
+        WindowBuilder::new()
+            .with_title(&settings.title)
+            .with_min_inner_size(dpi::LogicalSize::new(
+                settings.min_size.0,
+                settings.min_size.1,
+            ))
+            .with_inner_size(dpi::LogicalSize::new(width as f64, height as f64))
+            .with_max_inner_size(dpi::LogicalSize::new(width as f64, height as f64))
+    } else {
+        WindowBuilder::new()
+            .with_min_inner_size(dpi::LogicalSize::new(
+                settings.min_size.0,
+                settings.min_size.1,
+            ))
+            .with_title(&settings.title)
+            .with_maximized(true)
+    }
+}
+
+///
+/// Limits the frame rate by keeping track of when the next frame is allowed to start.
+///
+pub(super) struct FrameLimiter {
+    min_frame_time: Option<std::time::Duration>,
+    next_frame_time: std::time::Instant,
+}
+
+impl FrameLimiter {
+    pub fn new(min_frame_time: Option<f64>) -> Self {
+        Self {
+            min_frame_time: min_frame_time
+                .map(|t| std::time::Duration::from_micros((t * 1000.0) as u64)),
+            next_frame_time: std::time::Instant::now(),
+        }
+    }
+
+    ///
+    /// Returns the time to wait until before starting the next frame, or None if the next frame can start now.
+    ///
+    pub fn wait_until(&self) -> Option<std::time::Instant> {
+        if self.min_frame_time.is_some() && std::time::Instant::now() < self.next_frame_time {
+            Some(self.next_frame_time)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Call this when a frame starts.
+    ///
+    pub fn start_frame(&mut self) {
+        if let Some(min_frame_time) = self.min_frame_time {
+            let now = std::time::Instant::now();
+            self.next_frame_time += min_frame_time;
+            // Do not try to catch up with frames that were missed
+            if self.next_frame_time < now {
+                self.next_frame_time = now;
+            }
+        }
+    }
+}
+
+///
+/// Translates glutin window events into [events](crate::Event) and keeps track of the state needed to do so.
+///
+#[derive(Default)]
+pub(super) struct EventTranslator {
+    events: Vec<crate::Event>,
+    cursor_pos: Option<(f64, f64)>,
+    modifiers: Modifiers,
+    clipboard: Option<crate::window::Clipboard>,
+    fullscreen: bool,
+    cursor_grabbed: bool,
+    suspended: bool,
+    minimized: bool,
+}
+
+impl EventTranslator {
+    ///
+    /// Returns the events translated since the last call to this function.
+    ///
+    pub(super) fn take_events(&mut self) -> Vec<crate::Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    ///
+    /// Returns whether or not any events have been translated since the last call to [take_events](EventTranslator::take_events).
+    ///
+    pub(super) fn has_events(&self) -> bool {
+        !self.events.is_empty()
+    }
+
+    ///
+    /// Sets whether the application is suspended by the system, which is only reported on mobile.
+    ///
+    pub(super) fn set_suspended(&mut self, suspended: bool) {
+        let was_suspended = self.is_suspended();
+        self.suspended = suspended;
+        self.send_suspended(was_suspended);
+    }
+
+    ///
+    /// Sets whether the window is minimized, which is detected from a resize to zero size since that is what minimizing
+    /// does on most desktop platforms.
+    ///
+    pub(super) fn set_minimized(&mut self, minimized: bool) {
+        let was_suspended = self.is_suspended();
+        self.minimized = minimized;
+        self.send_suspended(was_suspended);
+    }
+
+    ///
+    /// Sends a [Suspended](crate::Event::Suspended) or [Resumed](crate::Event::Resumed) event if the application has been suspended or resumed.
+    ///
+    fn send_suspended(&mut self, was_suspended: bool) {
+        let suspended = self.is_suspended();
+        if suspended != was_suspended {
+            self.events.push(if suspended {
+                crate::Event::Suspended
+            } else {
+                crate::Event::Resumed
+            });
+        }
+    }
+
+    ///
+    /// Returns whether the application is suspended by the system or the window is minimized.
+    ///
+    pub(super) fn is_suspended(&self) -> bool {
+        self.suspended || self.minimized
+    }
+
+    ///
+    /// Sends a [FullscreenChange](crate::Event::FullscreenChange) event if the window has entered or left fullscreen since the last call.
+    ///
+    pub(super) fn update_fullscreen(&mut self, window: &window::Window) {
+        let fullscreen = window.fullscreen().is_some();
+        if fullscreen != self.fullscreen {
+            self.fullscreen = fullscreen;
+            self.events.push(crate::Event::FullscreenChange(fullscreen));
+        }
+    }
+
+    ///
+    /// Grabs or releases the cursor of the window and sends a [CursorGrabChange](crate::Event::CursorGrabChange) event if it changed.
+    ///
+    pub(super) fn set_cursor_grab(&mut self, window: &window::Window, grab: bool) {
+        if grab == self.cursor_grabbed {
+            return;
+        }
+        match set_cursor_grab(window, grab) {
+            Ok(()) => {
+                self.cursor_grabbed = grab;
+                self.events.push(crate::Event::CursorGrabChange(grab));
+            }
+            Err(e) => log::warn!("{:?}", e),
+        }
+    }
+
+    ///
+    /// Translates the raw mouse movement into [MouseMotion](crate::Event::MouseMotion) events while the cursor is grabbed.
+    ///
+    pub(super) fn translate_device_event(&mut self, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta } = event {
+            if self.cursor_grabbed {
+                self.events.push(crate::Event::MouseMotion {
+                    delta: *delta,
+                    position: self.cursor_pos.unwrap_or((0.0, 0.0)),
+                    modifiers: self.modifiers,
+                    handled: false,
+                });
+            }
+        }
+    }
+
+    fn translate_clipboard_shortcut(&mut self, kind: crate::Key) {
+        match kind {
+            crate::Key::C => self.events.push(crate::Event::Copy),
+            crate::Key::X => self.events.push(crate::Event::Cut),
+            crate::Key::V => {
+                if self.clipboard.is_none() {
+                    self.clipboard = crate::window::Clipboard::new().ok();
+                }
+                if let Some(text) = self.clipboard.as_mut().and_then(|c| c.text()) {
+                    self.events.push(crate::Event::Paste(text));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    pub(super) fn translate(&mut self, event: &WindowEvent, scale_factor: f64) {
+        match event {
+            WindowEvent::HoveredFile(path) => {
+                self.events.push(crate::Event::FileHovered {
+                    path: Some(path.clone()),
+                });
+            }
+            WindowEvent::HoveredFileCancelled => {
+                self.events.push(crate::Event::FileHoverCancelled);
+            }
+            WindowEvent::DroppedFile(path) => {
+                self.events.push(crate::Event::DroppedFile(path.clone()));
+            }
+            WindowEvent::KeyboardInput { input, .. } => {
+                if let Some(keycode) = input.virtual_keycode {
+                    use event::VirtualKeyCode;
+                    let state = if input.state == event::ElementState::Pressed {
+                        crate::State::Pressed
+                    } else {
+                        crate::State::Released
+                    };
+                    if let Some(kind) = translate_virtual_key_code(keycode) {
+                        self.events.push(crate::Event::Key {
+                            state,
+                            kind,
+                            modifiers: self.modifiers,
+                            handled: false,
+                        });
+                        if state == State::Pressed && self.modifiers.command == State::Pressed {
+                            self.translate_clipboard_shortcut(kind);
+                        }
+                    } else {
+                        if keycode == VirtualKeyCode::LControl
+                            || keycode == VirtualKeyCode::RControl
+                        {
+                            self.modifiers.ctrl = state;
+                            if !cfg!(target_os = "macos") {
+                                self.modifiers.command = state;
+                            }
+                            self.events.push(crate::Event::ModifiersChange {
+                                modifiers: self.modifiers,
+                            });
+                        } else if keycode == VirtualKeyCode::LAlt || keycode == VirtualKeyCode::RAlt
+                        {
+                            self.modifiers.alt = state;
+                            self.events.push(crate::Event::ModifiersChange {
+                                modifiers: self.modifiers,
+                            });
+                        } else if keycode == VirtualKeyCode::LShift
+                            || keycode == VirtualKeyCode::RShift
+                        {
+                            self.modifiers.shift = state;
+                            self.events.push(crate::Event::ModifiersChange {
+                                modifiers: self.modifiers,
+                            });
+                        } else if keycode == VirtualKeyCode::LWin || keycode == VirtualKeyCode::RWin
+                        {
+                            if cfg!(target_os = "macos") {
+                                self.modifiers.command = state;
+                                self.events.push(crate::Event::ModifiersChange {
+                                    modifiers: self.modifiers,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                if let Some(position) = self.cursor_pos {
+                    match delta {
+                        glutin::event::MouseScrollDelta::LineDelta(x, y) => {
+                            let line_height = 24.0; // TODO
+                            self.events.push(crate::Event::MouseWheel {
+                                delta: ((*x * line_height) as f64, (*y * line_height) as f64),
+                                position,
+                                modifiers: self.modifiers,
+                                handled: false,
+                            });
+                        }
+                        glutin::event::MouseScrollDelta::PixelDelta(delta) => {
+                            let d = delta.to_logical(scale_factor);
+                            self.events.push(crate::Event::MouseWheel {
+                                delta: (d.x, d.y),
+                                position,
+                                modifiers: self.modifiers,
+                                handled: false,
+                            });
+                        }
+                    }
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                if let Some(position) = self.cursor_pos {
+                    let state = if *state == event::ElementState::Pressed {
+                        crate::State::Pressed
+                    } else {
+                        crate::State::Released
+                    };
+                    let button = match button {
+                        event::MouseButton::Left => Some(crate::MouseButton::Left),
+                        event::MouseButton::Middle => Some(crate::MouseButton::Middle),
+                        event::MouseButton::Right => Some(crate::MouseButton::Right),
+                        _ => None,
+                    };
+                    if let Some(b) = button {
+                        self.events.push(crate::Event::MouseClick {
+                            state,
+                            button: b,
+                            position,
+                            modifiers: self.modifiers,
+                            handled: false,
+                        });
+                    }
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } if !self.cursor_grabbed => {
+                let p = position.to_logical(scale_factor);
+                let delta = if let Some(last_pos) = self.cursor_pos {
+                    (p.x - last_pos.0, p.y - last_pos.1)
+                } else {
+                    (0.0, 0.0)
+                };
+                self.events.push(crate::Event::MouseMotion {
+                    delta,
+                    position: (p.x, p.y),
+                    modifiers: self.modifiers,
+                    handled: false,
+                });
+                self.cursor_pos = Some((p.x, p.y));
+            }
+            WindowEvent::ReceivedCharacter(ch) => {
+                if is_printable_char(*ch)
+                    && self.modifiers.ctrl != State::Pressed
+                    && self.modifiers.command != State::Pressed
+                {
+                    self.events.push(crate::Event::Text(ch.to_string()));
+                }
+            }
+            WindowEvent::Focused(focused) => {
+                self.events.push(if *focused {
+                    crate::Event::Focused
+                } else {
+                    crate::Event::Unfocused
+                });
+            }
+            WindowEvent::CursorEntered { .. } => {
+                self.events.push(crate::Event::MouseEnter);
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.events.push(crate::Event::MouseLeave);
+            }
+            _ => (),
+        }
+    }
 }
 
 fn is_printable_char(chr: char) -> bool {
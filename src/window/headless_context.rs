@@ -0,0 +1,71 @@
+use crate::window::*;
+use crate::Context;
+use glutin::event_loop::EventLoop;
+use glutin::*;
+
+///
+/// A graphics context without a window, for example for rendering images on a server or in tests.
+/// Since there is no default framebuffer, everything must be rendered into a [render target](crate::RenderTarget).
+///
+/// On Linux, an [OSMesa](https://docs.mesa3d.org/osmesa.html) context is used if available, so no display is needed.
+/// Otherwise the context is created through the window system, which for example on a Linux server requires a virtual display like Xvfb.
+///
+pub struct HeadlessContext {
+    // The context must be dropped before the event loop
+    _context: glutin::Context<PossiblyCurrent>,
+    _event_loop: Option<EventLoop<()>>,
+    gl: Context,
+}
+
+impl HeadlessContext {
+    ///
+    /// Creates a new headless context.
+    /// Can be called on any thread on Linux and Windows, for example in tests.
+    ///
+    pub fn new() -> Result<Self, WindowError> {
+        #[cfg(target_os = "linux")]
+        {
+            use glutin::platform::unix::HeadlessContextExt;
+            if let Ok(context) = ContextBuilder::new().build_osmesa(dpi::PhysicalSize::new(1, 1)) {
+                return Self::from_context(context, None);
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        let event_loop = {
+            use glutin::platform::unix::EventLoopExtUnix;
+            EventLoop::new_any_thread()
+        };
+        #[cfg(target_os = "windows")]
+        let event_loop = {
+            use glutin::platform::windows::EventLoopExtWindows;
+            EventLoop::new_any_thread()
+        };
+        #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+        let event_loop = EventLoop::new();
+
+        let context =
+            ContextBuilder::new().build_headless(&event_loop, dpi::PhysicalSize::new(1, 1))?;
+        Self::from_context(context, Some(event_loop))
+    }
+
+    fn from_context(
+        context: glutin::Context<NotCurrent>,
+        event_loop: Option<EventLoop<()>>,
+    ) -> Result<Self, WindowError> {
+        let context = unsafe { context.make_current() }.map_err(|(_, e)| e)?;
+        let gl = Context::load_with(|s| context.get_proc_address(s) as *const std::os::raw::c_void);
+        Ok(Self {
+            _context: context,
+            _event_loop: event_loop,
+            gl,
+        })
+    }
+
+    ///
+    /// Returns the graphics context.
+    ///
+    pub fn gl(&self) -> Result<Context, WindowError> {
+        Ok(self.gl.clone())
+    }
+}
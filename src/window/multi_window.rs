@@ -0,0 +1,394 @@
+use crate::definition::*;
+use crate::frame::*;
+use crate::math::*;
+use crate::window::glutin_window::{
+    set_cursor, set_cursor_grab, set_fullscreen, set_icon, window_builder, EventTranslator,
+    FrameLimiter,
+};
+use crate::window::*;
+use crate::Context;
+use glutin::event::{Event, WindowEvent};
+use glutin::event_loop::{ControlFlow, EventLoop};
+use glutin::window::WindowId;
+use glutin::*;
+
+struct WindowData {
+    windowed_context: Option<ContextWrapper<PossiblyCurrent, window::Window>>,
+    gl: Context,
+    index: usize,
+    shared: bool,
+    translator: EventTranslator,
+    frame_input_generator: FrameInputGenerator,
+    frame_globals: Option<crate::FrameGlobals>,
+    frame_limiter: FrameLimiter,
+    wait_next_event: bool,
+    pause_when_hidden: bool,
+}
+
+impl WindowData {
+    fn make_current(&mut self) -> Result<(), WindowError> {
+        let windowed_context = self.windowed_context.take().unwrap();
+        self.windowed_context = Some(unsafe {
+            windowed_context
+                .make_current()
+                .map_err(|(_, e)| WindowError::ContextError(e))?
+        });
+        // The graphics state cache is shared by all windows sharing resources, since the objects of one window are rendered in the others
+        if self.shared {
+            self.gl.make_current(self.index);
+        }
+        Ok(())
+    }
+
+    fn window(&self) -> &window::Window {
+        self.windowed_context.as_ref().unwrap().window()
+    }
+
+    fn id(&self) -> WindowId {
+        self.window().id()
+    }
+
+    fn update_fullscreen(&mut self) {
+        self.translator
+            .update_fullscreen(self.windowed_context.as_ref().unwrap().window());
+    }
+
+    fn set_cursor_grab(&mut self, grab: bool) {
+        self.translator
+            .set_cursor_grab(self.windowed_context.as_ref().unwrap().window(), grab);
+    }
+}
+
+///
+/// Several windows which are driven by one event loop, for example used for tools with detachable panels.
+/// By default, the windows share graphics resources (buffers, textures and shader programs) with the first window,
+/// so the same objects can be rendered in all of the windows, but each window has its own [graphics context](crate::Context)
+/// which must be used when rendering to that window. The graphics state cache is shared by the contexts of all windows and follows
+/// the window which is currently rendered, so objects created with the context of one window can be rendered in the other windows.
+/// A window can instead have a separate graphics context with its own resources, see [WindowSettings::share_context].
+/// When the render loop ends, the `callback` closure is dropped and in debug builds,
+/// a warning is logged if any GPU resources of a window are still alive, see [Context::check_for_leaks](crate::context::Context::check_for_leaks).
+///
+pub struct MultiWindow {
+    event_loop: EventLoop<()>,
+    windows: Vec<WindowData>,
+}
+
+impl MultiWindow {
+    ///
+    /// Constructs a new multi window without any windows, add windows with [add_window](MultiWindow::add_window).
+    ///
+    pub fn new() -> Self {
+        Self {
+            event_loop: EventLoop::new(),
+            windows: Vec::new(),
+        }
+    }
+
+    ///
+    /// Opens a new window with the given settings and returns the index of the window,
+    /// which is used for identifying the window in the [render loop](MultiWindow::render_loop).
+    ///
+    pub fn add_window(&mut self, settings: WindowSettings) -> Result<usize, WindowError> {
+        if settings.multisamples > 0 && !settings.multisamples.is_power_of_two() {
+            return Err(WindowError::InvalidNumberOfSamples);
+        }
+        let builder = ContextBuilder::new()
+            .with_multisampling(settings.multisamples as u16)
+            .with_stencil_buffer(8)
+            .with_vsync(settings.vsync);
+        // The first window owns the resources and the state cache which are shared with the other windows
+        let shared = settings.share_context || self.windows.is_empty();
+        let windowed_context =
+            if let Some(first) = self.windows.first().filter(|_| settings.share_context) {
+                builder
+                    .with_shared_lists(first.windowed_context.as_ref().unwrap().context())
+                    .build_windowed(window_builder(&settings), &self.event_loop)?
+            } else {
+                builder.build_windowed(window_builder(&settings), &self.event_loop)?
+            };
+        let windowed_context = unsafe {
+            windowed_context
+                .make_current()
+                .map_err(|(_, e)| WindowError::ContextError(e))?
+        };
+        let index = self.windows.len();
+        let load = |s: &str| windowed_context.get_proc_address(s) as *const std::os::raw::c_void;
+        let gl = if let Some(first) = self.windows.first().filter(|_| settings.share_context) {
+            Context::load_shared_with(load, &first.gl, index)
+        } else {
+            Context::load_with(load)
+        };
+        let mut frame_input_generator = FrameInputGenerator::new();
+        frame_input_generator.set_fixed_time_step(settings.fixed_time_step());
+        frame_input_generator.set_log_stats(settings.log_frame_stats);
+        let frame_globals = Some(crate::FrameGlobals::new(&gl).unwrap());
+        self.windows.push(WindowData {
+            windowed_context: Some(windowed_context),
+            gl,
+            index,
+            shared,
+            frame_globals,
+            translator: EventTranslator::default(),
+            frame_input_generator,
+            frame_limiter: FrameLimiter::new(settings.min_frame_time()),
+            wait_next_event: false,
+            pause_when_hidden: settings.pause_when_hidden,
+        });
+        Ok(index)
+    }
+
+    ///
+    /// Returns the number of windows.
+    ///
+    pub fn window_count(&self) -> usize {
+        self.windows.len()
+    }
+
+    ///
+    /// Returns the current viewport of the window with the given index in physical pixels (the size of the [screen](crate::Screen)).
+    ///
+    pub fn viewport(&self, index: usize) -> Result<Viewport, WindowError> {
+        let (w, h): (u32, u32) = self.window(index)?.window().inner_size().into();
+        Ok(Viewport::new_at_origo(w, h))
+    }
+
+    ///
+    /// Returns the graphics context for the window with the given index.
+    ///
+    pub fn gl(&self, index: usize) -> Result<crate::Context, WindowError> {
+        Ok(self.window(index)?.gl.clone())
+    }
+
+    ///
+    /// Returns a sender which can be used to send external data to the [payload](FrameInput::payload) of the frame input of the window with the given index.
+    ///
+    pub fn payload_sender(&self, index: usize) -> Result<PayloadSender, WindowError> {
+        Ok(self.window(index)?.frame_input_generator.payload_sender())
+    }
+
+    ///
+    /// Sets the icon of the window with the given index from the given texture with the top row first.
+    ///
+    pub fn set_icon(&self, index: usize, icon: &CPUTexture<u8>) -> Result<(), WindowError> {
+        set_icon(self.window(index)?.window(), icon)
+    }
+
+    ///
+    /// Sets the mouse cursor shown when the mouse is over the window with the given index.
+    /// Use [FrameOutput::cursor] to change the cursor in the render loop.
+    ///
+    pub fn set_cursor(&self, index: usize, cursor: &Cursor) -> Result<(), WindowError> {
+        set_cursor(self.window(index)?.window(), cursor);
+        Ok(())
+    }
+
+    ///
+    /// Enters or leaves borderless fullscreen for the window with the given index.
+    /// Use [FrameOutput::fullscreen] to change it in the render loop.
+    ///
+    pub fn set_fullscreen(&self, index: usize, fullscreen: bool) -> Result<(), WindowError> {
+        set_fullscreen(self.window(index)?.window(), fullscreen);
+        Ok(())
+    }
+
+    ///
+    /// Grabs and hides the mouse cursor of the window with the given index or releases it.
+    /// Use [FrameOutput::cursor_grab] to change it in the render loop.
+    ///
+    pub fn set_cursor_grab(&self, index: usize, grab: bool) -> Result<(), WindowError> {
+        set_cursor_grab(self.window(index)?.window(), grab)
+    }
+
+    fn window(&self, index: usize) -> Result<&WindowData, WindowError> {
+        self.windows
+            .get(index)
+            .ok_or(WindowError::InvalidWindowIndex(index))
+    }
+
+    ///
+    /// Start the main render loop which calls the `callback` closure each frame for each window
+    /// with the index of the window and the frame input containing the events for that window.
+    /// The graphics context of the window is current when the callback is called.
+    /// Closing any of the windows or setting [FrameOutput::exit] to true stops the render loop.
+    ///
+    pub fn render_loop<F: 'static>(self, callback: F) -> Result<(), WindowError>
+    where
+        F: FnMut(usize, FrameInput) -> FrameOutput,
+    {
+        let mut windows = self.windows;
+        // Kept in an option, so that it can be dropped before checking for leaks when the loop ends
+        let mut callback = Some(callback);
+        self.event_loop
+            .run(move |event, _, control_flow| match event {
+                Event::LoopDestroyed => {
+                    callback = None;
+                    for window in windows.iter_mut() {
+                        if window.make_current().is_ok() {
+                            window.frame_globals = None;
+                            window.gl.check_for_leaks();
+                        }
+                    }
+                }
+                Event::MainEventsCleared => {
+                    let mut wait_until = None;
+                    for window in windows.iter() {
+                        if !window.wait_next_event {
+                            if let Some(time) = window.frame_limiter.wait_until() {
+                                wait_until =
+                                    Some(wait_until.map_or(time, |t| std::cmp::min(t, time)));
+                            } else {
+                                window.window().request_redraw();
+                            }
+                        }
+                    }
+                    if let Some(time) = wait_until {
+                        *control_flow = ControlFlow::WaitUntil(time);
+                    }
+                }
+                Event::RedrawRequested(id) => {
+                    if let Some((index, window)) =
+                        windows.iter_mut().enumerate().find(|(_, w)| w.id() == id)
+                    {
+                        window.make_current().unwrap();
+                        window.frame_limiter.start_frame();
+                        let (physical_width, physical_height): (u32, u32) =
+                            window.window().inner_size().into();
+                        let scale_factor = window.window().scale_factor();
+                        let frame_input = window.frame_input_generator.generate(
+                            window.translator.take_events(),
+                            physical_width,
+                            physical_height,
+                            scale_factor,
+                        );
+                        window
+                            .frame_globals
+                            .as_mut()
+                            .unwrap()
+                            .update(&frame_input)
+                            .unwrap();
+                        let frame_output = callback.as_mut().unwrap()(index, frame_input);
+                        if frame_output.exit {
+                            *control_flow = ControlFlow::Exit;
+                            return;
+                        }
+                        if frame_output.swap_buffers {
+                            window
+                                .windowed_context
+                                .as_ref()
+                                .unwrap()
+                                .swap_buffers()
+                                .unwrap();
+                        }
+                        if let Some(ref path) = frame_output.screenshot {
+                            let pixels = crate::Screen::read_color(
+                                &window.gl,
+                                crate::Viewport::new_at_origo(physical_width, physical_height),
+                            )
+                            .unwrap();
+                            #[cfg(feature = "image-io")]
+                            crate::Saver::save_pixels(
+                                path,
+                                &pixels,
+                                physical_width,
+                                physical_height,
+                            )
+                            .unwrap();
+                            #[cfg(not(feature = "image-io"))]
+                            log::warn!(
+                                "Cannot save the screenshot {:?} with {} bytes since the image-io feature is disabled",
+                                path,
+                                pixels.len()
+                            );
+                        }
+                        if let Some((x, y)) = frame_output.ime_position {
+                            window
+                                .window()
+                                .set_ime_position(dpi::LogicalPosition::new(x, y));
+                        }
+                        if let Some(ref cursor) = frame_output.cursor {
+                            set_cursor(window.window(), cursor);
+                        }
+                        if let Some(fullscreen) = frame_output.fullscreen {
+                            set_fullscreen(window.window(), fullscreen);
+                            window.update_fullscreen();
+                        }
+                        if let Some(grab) = frame_output.cursor_grab {
+                            window.set_cursor_grab(grab);
+                        }
+                        window.wait_next_event = frame_output.wait_next_event
+                            || (window.pause_when_hidden && window.translator.is_suspended());
+                    }
+                    *control_flow = if windows.iter().all(|w| w.wait_next_event) {
+                        ControlFlow::Wait
+                    } else {
+                        ControlFlow::Poll
+                    };
+                }
+                Event::WindowEvent {
+                    ref event,
+                    window_id,
+                } => {
+                    if let Some(window) = windows.iter_mut().find(|w| w.id() == window_id) {
+                        match event {
+                            WindowEvent::Resized(physical_size) => {
+                                window.translator.set_minimized(
+                                    physical_size.width == 0 || physical_size.height == 0,
+                                );
+                                window.make_current().unwrap();
+                                window
+                                    .windowed_context
+                                    .as_ref()
+                                    .unwrap()
+                                    .resize(*physical_size);
+                                window.update_fullscreen();
+                                window.window().request_redraw();
+                            }
+                            WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                                window.make_current().unwrap();
+                                window
+                                    .windowed_context
+                                    .as_ref()
+                                    .unwrap()
+                                    .resize(**new_inner_size);
+                                window.window().request_redraw();
+                            }
+                            WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                            _ => {
+                                window
+                                    .translator
+                                    .translate(event, window.window().scale_factor());
+                                // Only redraw a waiting window if the event is relevant for the application
+                                if window.translator.has_events() {
+                                    window.window().request_redraw();
+                                }
+                            }
+                        }
+                    }
+                }
+                Event::Suspended | Event::Resumed => {
+                    for window in windows.iter_mut() {
+                        window
+                            .translator
+                            .set_suspended(matches!(event, Event::Suspended));
+                        window.window().request_redraw();
+                    }
+                }
+                Event::DeviceEvent { ref event, .. } => {
+                    for window in windows.iter_mut() {
+                        window.translator.translate_device_event(event);
+                        if window.translator.has_events() {
+                            window.window().request_redraw();
+                        }
+                    }
+                }
+                _ => (),
+            });
+    }
+}
+
+impl Default for MultiWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,132 @@
+use crate::window::*;
+use crate::Context;
+use glutin::*;
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+
+///
+/// A graphics context created on a window owned by another toolkit, for example Qt, GTK or iced,
+/// given by its [raw window handle](https://crates.io/crates/raw-window-handle).
+/// There is no event loop, so rendering is driven by the other toolkit and the [FrameInput](crate::FrameInput)
+/// can be created by a [FrameInputGenerator](crate::FrameInputGenerator).
+/// Use [swap_buffers](RawWindowContext::swap_buffers) after rendering a frame to display it.
+///
+/// If the other toolkit already has an OpenGL context, use [Context::load_with](crate::context::Context::load_with) instead.
+///
+pub struct RawWindowContext {
+    raw_context: RawContext<PossiblyCurrent>,
+    gl: Context,
+}
+
+impl RawWindowContext {
+    ///
+    /// Creates a new context on the window with the given raw window handle which has the given size in physical pixels.
+    /// Only the `vsync` and `multisamples` [settings](WindowSettings) are used.
+    ///
+    /// # Safety
+    /// The window must outlive the returned context.
+    ///
+    pub unsafe fn new(
+        window: &impl HasRawWindowHandle,
+        settings: &WindowSettings,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, WindowError> {
+        if settings.multisamples > 0 && !settings.multisamples.is_power_of_two() {
+            return Err(WindowError::InvalidNumberOfSamples);
+        }
+        let builder = ContextBuilder::new()
+            .with_multisampling(settings.multisamples as u16)
+            .with_stencil_buffer(8)
+            .with_vsync(settings.vsync);
+        let raw_context = build_raw_context(builder, window.raw_window_handle(), width, height)?
+            .make_current()
+            .map_err(|(_, e)| WindowError::ContextError(e))?;
+        let gl =
+            Context::load_with(|s| raw_context.get_proc_address(s) as *const std::os::raw::c_void);
+        Ok(Self { raw_context, gl })
+    }
+
+    ///
+    /// Returns the graphics context for this window.
+    ///
+    pub fn gl(&self) -> Result<Context, WindowError> {
+        Ok(self.gl.clone())
+    }
+
+    ///
+    /// Must be called when the window is resized, the size is in physical pixels.
+    ///
+    pub fn resize(&self, width: u32, height: u32) {
+        self.raw_context
+            .resize(dpi::PhysicalSize::new(width, height));
+    }
+
+    ///
+    /// Displays the frame that has been rendered since the last call to this function.
+    ///
+    pub fn swap_buffers(&self) -> Result<(), WindowError> {
+        self.raw_context.swap_buffers()?;
+        Ok(())
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+unsafe fn build_raw_context(
+    builder: ContextBuilder<NotCurrent>,
+    handle: RawWindowHandle,
+    width: u32,
+    height: u32,
+) -> Result<RawContext<NotCurrent>, WindowError> {
+    use glutin::platform::unix::RawContextExt;
+    match handle {
+        RawWindowHandle::Xlib(handle) => {
+            let connection = glutin::platform::unix::x11::XConnection::new(None)
+                .map_err(|_| WindowError::UnsupportedWindowHandle)?;
+            Ok(builder.build_raw_x11_context(std::sync::Arc::new(connection), handle.window)?)
+        }
+        RawWindowHandle::Wayland(handle) => Ok(builder.build_raw_wayland_context(
+            handle.display as *const _,
+            handle.surface,
+            width,
+            height,
+        )?),
+        _ => Err(WindowError::UnsupportedWindowHandle),
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn build_raw_context(
+    builder: ContextBuilder<NotCurrent>,
+    handle: RawWindowHandle,
+    _width: u32,
+    _height: u32,
+) -> Result<RawContext<NotCurrent>, WindowError> {
+    use glutin::platform::windows::RawContextExt;
+    match handle {
+        RawWindowHandle::Windows(handle) => Ok(builder.build_raw_context(handle.hwnd)?),
+        _ => Err(WindowError::UnsupportedWindowHandle),
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "windows"
+)))]
+unsafe fn build_raw_context(
+    _builder: ContextBuilder<NotCurrent>,
+    _handle: RawWindowHandle,
+    _width: u32,
+    _height: u32,
+) -> Result<RawContext<NotCurrent>, WindowError> {
+    Err(WindowError::UnsupportedWindowHandle)
+}
@@ -20,7 +20,52 @@ pub struct WindowSettings {
     /// On web, this can only be off (0) or on (>0).
     /// The actual number of samples depends on browser settings.
     pub multisamples: u8,
+    /// The maximum number of frames rendered each second. If None is specified, the frame rate is not limited
+    /// other than by VSync if that is enabled.
+    ///
+    /// When the frame rate is limited, the render loop sleeps between frames instead of rendering as fast as possible.
+    pub max_fps: Option<u32>,
+    /// The number of fixed time step updates each second. If specified, the [frame input](crate::FrameInput) contains
+    /// the number of [fixed time steps](crate::FrameInput::fixed_time_steps) to simulate each frame and the
+    /// [interpolation factor](crate::FrameInput::interpolation_factor) between the last two simulated states,
+    /// which makes it possible to have deterministic updates independent of the frame rate.
+    pub fixed_updates_per_second: Option<u32>,
+    /// The id of the canvas element to render to.
+    /// If None is specified, the first canvas in the document is used and resized to fill the browser window,
+    /// otherwise the canvas keeps the size given by the page layout, which makes it possible to embed several canvases in one page.
+    ///
+    /// On desktop this has no effect.
+    pub canvas_id: Option<String>,
+    /// Whether to stop rendering while the application is [suspended](crate::Event::Suspended),
+    /// for example while the page is hidden on web or the window is minimized on desktop, to save battery.
+    /// On desktop, a window which is covered by other windows is not detected, see [Event::Suspended](crate::Event::Suspended).
+    /// The render loop is continued when the application is [resumed](crate::Event::Resumed).
+    pub pause_when_hidden: bool,
+    /// Whether to log the [frame statistics](crate::FrameInput::stats), for example the frame rate, each second at the info level
+    /// using the [log](https://crates.io/crates/log) crate. The statistics are available in the frame input regardless of this setting.
+    pub log_frame_stats: bool,
+    /// Whether the window shares graphics resources (buffers, textures and shader programs) with the first window of a [MultiWindow](crate::MultiWindow),
+    /// so the same objects can be rendered in both windows, or has a separate graphics context with its own resources.
+    ///
+    /// Only used by [MultiWindow](crate::MultiWindow).
+    pub share_context: bool,
+}
+
+#[cfg(any(
+    all(feature = "glutin-window", not(target_arch = "wasm32")),
+    all(feature = "canvas", target_arch = "wasm32")
+))]
+impl WindowSettings {
+    pub(crate) fn fixed_time_step(&self) -> Option<f64> {
+        self.fixed_updates_per_second
+            .map(|updates| 1000.0 / updates.max(1) as f64)
+    }
+
+    pub(crate) fn min_frame_time(&self) -> Option<f64> {
+        self.max_fps.map(|fps| 1000.0 / fps.max(1) as f64)
+    }
 }
+
 impl Default for WindowSettings {
     fn default() -> Self {
         Self {
@@ -29,6 +74,12 @@ impl Default for WindowSettings {
             max_size: None,
             vsync: true,
             multisamples: 4,
+            max_fps: None,
+            fixed_updates_per_second: None,
+            canvas_id: None,
+            pause_when_hidden: false,
+            log_frame_stats: false,
+            share_context: true,
         }
     }
 }